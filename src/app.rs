@@ -2,12 +2,34 @@
 //!
 //! Contains the top-level App struct, Message enum, and update/view functions.
 
-use iced::widget::{button, column, container, horizontal_rule, row, text, vertical_rule};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use iced::widget::{button, column, container, horizontal_rule, row, text, text_input, vertical_rule};
 use iced::{Element, Length, Subscription, Task};
 
-use crate::model::layout::{AlignmentSpec, LengthSpec};
-use crate::model::{ComponentId, LayoutNode, Project, ProjectConfig};
-use crate::ui::{palette::WidgetKind, Canvas, Inspector, Palette, TreeView};
+use crate::codegen::state_check::StateStruct;
+use crate::io::crash_recovery::CrashHandle;
+use crate::io::{AppSettings, CanvasSize, RecentProject, RecentProjects, Snippet, ThemeChoice, TreeDock, WindowSize};
+use crate::logging::targets;
+use crate::model::layout::{AlignmentSpec, LayoutStats, LengthSpec, ValidationError, ValidationSeverity};
+use crate::model::project::Written;
+use crate::model::{ComponentId, ExportMode, I18nMode, LayoutNode, Project, ProjectConfig, Template};
+use crate::ui::canvas::{CanvasCache, CanvasPreset, RenderContext, Reuse};
+use crate::ui::context_menu::RightPressArea;
+use crate::ui::code_panel::{CodePreview, ExportReview, REGENERATE_DELAY};
+use crate::ui::delete_confirm::DeleteConfirm;
+use crate::ui::error_dialog::DialogState;
+use crate::ui::inspector::{DraftField, Drafts};
+use crate::ui::menu_bar::{Menu, MenuEntry, MENU_BAR_HEIGHT};
+use crate::ui::metrics;
+use crate::ui::minimap::{Minimap, MinimapCache, MinimapViewport};
+use crate::ui::palette::{InsertMode, PaletteContext, PaletteDrag};
+use crate::ui::problems_panel::{Problems, REVALIDATE_DELAY};
+use crate::ui::splitter::{splitter, Split, SplitterDrag};
+use crate::ui::tree_view::{DropPosition, TreeDrag, TreeState};
+use crate::ui::{palette::WidgetKind, Canvas, CodePanel, ContextMenu, DeleteConfirmDialog, ErrorDialog, HistoryPanel, Inspector, MenuBar, Palette, ProblemsPanel, TemplatePicker, TreeView, Welcome};
+use crate::util::{FormatBackend, Formatted};
 
 /// Editor mode.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -17,27 +39,341 @@ pub enum EditorMode {
     Preview,
 }
 
+/// Maximum delay between two clicks on the same node to count as a double-click.
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
+/// How often the open layout file is checked for changes made outside the builder.
+const LAYOUT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long info and success status messages stay in the status bar.
+const STATUS_FADE_AFTER: Duration = Duration::from_secs(5);
+
+/// How often a fading status message is checked for expiry.
+const STATUS_TICK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often unsaved changes are handed to the panic hook. Copying the layout
+/// on every edit would make each keystroke cost a full clone.
+const CRASH_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long to wait for the rest of a multi-file drop before opening one.
+const DROP_SETTLE_DELAY: Duration = Duration::from_millis(100);
+
+/// Status messages longer than this are cut short in the status bar.
+const STATUS_MAX_CHARS: usize = 120;
+
+/// How a status message reads and how long it stays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusSeverity {
+    /// Neutral feedback; fades out.
+    Info,
+    /// A completed action; fades out.
+    Success,
+    /// A completed action that left something to look at, such as a save
+    /// with validation problems; shown in amber until replaced.
+    Warning,
+    /// A failure; shown in red until replaced.
+    Error,
+}
+
+/// A message in the status bar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusMessage {
+    pub text: String,
+    pub severity: StatusSeverity,
+    pub set_at: Instant,
+}
+
+impl StatusMessage {
+    pub fn new(text: impl Into<String>, severity: StatusSeverity) -> Self {
+        Self {
+            text: text.into(),
+            severity,
+            set_at: Instant::now(),
+        }
+    }
+
+    pub fn info(text: impl Into<String>) -> Self {
+        Self::new(text, StatusSeverity::Info)
+    }
+
+    pub fn success(text: impl Into<String>) -> Self {
+        Self::new(text, StatusSeverity::Success)
+    }
+
+    pub fn warning(text: impl Into<String>) -> Self {
+        Self::new(text, StatusSeverity::Warning)
+    }
+
+    pub fn error(text: impl Into<String>) -> Self {
+        Self::new(text, StatusSeverity::Error)
+    }
+
+    /// Whether the message fades out; warnings and errors stay until replaced.
+    pub fn fades(&self) -> bool {
+        matches!(self.severity, StatusSeverity::Info | StatusSeverity::Success)
+    }
+
+    /// Whether the message has faded by `now`.
+    pub fn is_expired(&self, now: Instant) -> bool {
+        self.fades() && now.saturating_duration_since(self.set_at) >= STATUS_FADE_AFTER
+    }
+
+    /// The text shortened to fit the status bar, or `None` when it fits.
+    pub fn truncated(&self) -> Option<String> {
+        (self.text.chars().count() > STATUS_MAX_CHARS)
+            .then(|| format!("{}…", self.text.chars().take(STATUS_MAX_CHARS).collect::<String>()))
+    }
+}
+
+/// What to do with unsaved edits when the layout file changed on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalChangeChoice {
+    /// Load the file from disk, discarding the edits.
+    Reload,
+    /// Keep the edits; the next save overwrites the file.
+    KeepMine,
+    /// Save the edits to another file, then load the file from disk.
+    SaveAs,
+}
+
+/// What to do with unsaved edits when their tab is closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsavedChoice {
+    /// Save the project, then close it.
+    Save,
+    /// Close without saving.
+    Discard,
+    /// Keep the tab open.
+    Cancel,
+}
+
+/// What a path dropped onto the window can be opened as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DroppedPath {
+    /// A project directory, or a file inside one.
+    Project(std::path::PathBuf),
+    /// A layout file in a directory that isn't a project yet.
+    LoneLayout(std::path::PathBuf),
+}
+
+impl DroppedPath {
+    /// Classify a dropped path, or `None` if the builder can't open it.
+    pub fn classify(path: &std::path::Path) -> Option<Self> {
+        if path.is_dir() {
+            return crate::io::is_valid_project(path).then(|| Self::Project(path.to_path_buf()));
+        }
+        let parent = path.parent()?;
+        let is_config = path.file_name().is_some_and(|name| name == crate::io::CONFIG_FILENAME);
+        let is_layout = crate::io::LayoutFormat::from_path(path).is_some();
+        if (is_config || is_layout) && crate::io::is_valid_project(parent) {
+            Some(Self::Project(parent.to_path_buf()))
+        } else if is_layout && path.is_file() {
+            Some(Self::LoneLayout(path.to_path_buf()))
+        } else {
+            None
+        }
+    }
+}
+
+/// Identifier of the inline edit text input on the canvas.
+pub fn inline_edit_input_id() -> text_input::Id {
+    text_input::Id::new("canvas-inline-edit")
+}
+
+/// An in-progress inline edit of a Text or Button on the canvas.
+#[derive(Debug, Clone)]
+pub struct InlineEdit {
+    /// The node being edited.
+    pub id: ComponentId,
+    /// The uncommitted text.
+    pub draft: String,
+}
+
 /// Application state.
 #[derive(Debug)]
 pub struct App {
-    /// The currently open project.
-    project: Option<Project>,
+    /// Open projects, one per tab.
+    projects: Vec<Project>,
+    /// Index of the active tab in `projects`.
+    active: usize,
     /// Current editor mode.
     mode: EditorMode,
+    /// Mode to restore when leaving focus preview; `Some` while side panels are hidden.
+    focus_preview: Option<EditorMode>,
+    /// Whether to outline invisible elements on the design canvas.
+    show_invisible: bool,
+    /// Whether to overlay padding and spacing on the selected container.
+    show_measurements: bool,
+    /// Whether the layout statistics popover is open.
+    show_stats: bool,
+    /// Layout backups listed in the restore popover, while it is open.
+    backup_list: Option<Vec<std::path::PathBuf>>,
+    /// Menu open in the menu bar, if any.
+    open_menu: Option<Menu>,
+    /// Node whose context menu is open, and where it opened.
+    context_menu: Option<(ComponentId, iced::Point)>,
+    /// Where the last right press landed, in window coordinates.
+    last_right_press: iced::Point,
+    /// Whether the recent projects popover is open.
+    show_recent: bool,
+    /// Whether the popover listing unknown fields skipped on load is open.
+    show_load_warnings: bool,
+    /// Whether the user agreed to drop the unknown fields on the next save.
+    drop_unknown_fields: bool,
+    /// Recently opened projects, newest first.
+    recent_projects: RecentProjects,
+    /// Whether the undo history panel is shown under the inspector.
+    show_history: bool,
+    /// Whether the problems panel is shown under the inspector.
+    show_problems: bool,
+    /// Validation problems in the active project's layout.
+    problems: Problems,
+    /// Generated code preview, while its panel is open.
+    code_preview: Option<CodePreview>,
+    /// Whether an autosave timer is running.
+    autosave_scheduled: bool,
+    /// Layout revision last written to each project's autosave file.
+    autosaved_revisions: HashMap<std::path::PathBuf, u64>,
+    /// Layout the panic hook saves if the editor crashes.
+    crash_handle: CrashHandle,
+    /// Project and revision last given to `crash_handle`.
+    crash_snapshot: Option<(std::path::PathBuf, u64)>,
+    /// Whether the user is being asked about an external change to the layout file.
+    external_change_prompt: bool,
+    /// Inline edit in progress on the canvas, if any.
+    inline_edit: Option<InlineEdit>,
+    /// Last canvas click, used to detect double-clicks.
+    last_canvas_click: Option<(ComponentId, Instant)>,
+    /// Text typed into numeric inspector fields.
+    inspector_drafts: Drafts,
+    /// Transient PickList selections made in preview mode, keyed by node.
+    preview_selections: HashMap<ComponentId, String>,
+    /// Palette item waiting to be dropped on a container.
+    pending_insert: Option<WidgetKind>,
+    /// Snippets saved in the open project.
+    snippets: Vec<Snippet>,
+    /// Snippet being renamed and the draft name.
+    snippet_rename: Option<(usize, String)>,
+    /// Node being saved as a snippet and the name typed for it.
+    snippet_name: Option<(ComponentId, String)>,
+    /// Palette item under the cursor, described in the empty inspector.
+    palette_hover: Option<WidgetKind>,
+    /// Canvas nodes rendered in earlier frames.
+    canvas_cache: CanvasCache,
+    /// Projects being opened in the background.
+    loading: Vec<std::path::PathBuf>,
+    /// Projects being saved in the background.
+    saving: Vec<std::path::PathBuf>,
+    /// Palette search text.
+    palette_query: String,
+    /// Palette item pressed and possibly being dragged.
+    palette_drag: Option<PaletteDrag>,
+    /// Where palette items go relative to the selection.
+    insert_mode: InsertMode,
+    /// Keyboard modifiers currently held, used for Alt-click insertion.
+    modifiers: iced::keyboard::Modifiers,
+    /// Tree view expansion and drag state.
+    tree: TreeState,
+    /// Panel splitter being dragged, if any.
+    splitter_drag: Option<SplitterDrag>,
+    /// Cached minimap geometry.
+    minimap: MinimapCache,
+    /// Last known scroll viewport of the canvas.
+    canvas_viewport: Option<iced::widget::scrollable::Viewport>,
+    /// Editor settings persisted between sessions.
+    settings: AppSettings,
+    /// Text of the custom canvas width input.
+    canvas_width_input: String,
+    /// Text of the custom canvas height input.
+    canvas_height_input: String,
+    /// Text of the history limit input.
+    history_limit_input: String,
     /// Status message to display.
-    status_message: Option<String>,
+    status_message: Option<StatusMessage>,
+    /// Whether the full text of a truncated status message is shown.
+    show_full_status: bool,
+    /// Error dialog shown over the editor until dismissed.
+    pending_dialog: Option<DialogState>,
+    /// Folder chosen for a new project while its template is picked.
+    template_picker: Option<std::path::PathBuf>,
+    /// Delete of a node with children waiting for confirmation.
+    delete_confirm: Option<DeleteConfirm>,
+    /// Paths dropped onto the window that haven't been handled yet.
+    dropped_paths: Vec<std::path::PathBuf>,
 }
 
 /// Messages for the application.
 #[derive(Debug, Clone)]
 pub enum Message {
+    // Menu bar
+    ToggleMenu(Menu),
+    CloseMenu,
+    MenuCommand(Box<Message>),
+    RightPressed(iced::Point),
+    OpenContextMenu(ComponentId),
+    CloseContextMenu,
+    Quit,
+
+    // Tabs
+    SelectTab(usize),
+    CloseTab(usize),
+    CloseTabChosen(usize, UnsavedChoice),
+    CloseActiveTab,
+
     // File operations
     NewProject,
-    CreateProjectAt(std::path::PathBuf),
+    /// Pick a folder for a new project seeded with a template.
+    NewProjectFromTemplate(Template),
+    /// Ask which template to create a new project in the folder from.
+    ChooseTemplate(std::path::PathBuf),
+    CancelTemplatePicker,
+    CreateProjectAt(std::path::PathBuf, Template),
+    /// Whether to replace an open tab with unsaved changes by the new project.
+    ReplaceTabChosen(std::path::PathBuf, Template, bool),
     OpenProject,
+    OpenProjectAt(std::path::PathBuf),
+    /// Open the project at the path in the background.
+    LoadProject(std::path::PathBuf),
+    ToggleRecentProjects,
     SaveProject,
+    ProjectSaved(std::path::PathBuf, Result<Written, DialogState>),
+    SaveLayoutAs,
+    SaveLayoutAsTo(Option<std::path::PathBuf>),
+    SaveProjectAs,
+    SaveProjectAsChosen(Option<std::path::PathBuf>),
+    SaveProjectAsInto(std::path::PathBuf),
+    ExportBundle,
+    ExportBundleTo(Option<std::path::PathBuf>),
+    ExportOutline,
+    ExportOutlineTo(Option<std::path::PathBuf>),
+    CopyOutline,
+    ImportBundle,
+    ImportBundleChosen(Option<(std::path::PathBuf, std::path::PathBuf)>),
+    ImportBundleInto(std::path::PathBuf, std::path::PathBuf),
     ExportCode,
-    ProjectOpened(Result<Project, String>),
+    /// Code generated for the project at the path, with the differing
+    /// contents of the file it would overwrite.
+    ExportPrepared(std::path::PathBuf, Result<(Formatted, Option<String>), DialogState>),
+    /// Code written, described for the status bar.
+    CodeExported(Result<String, DialogState>),
+    ConfirmExport,
+    CancelExport,
+    AcknowledgeOverwrite(bool),
+    ExportExample,
+    ExampleExported(Result<std::path::PathBuf, DialogState>),
+    ToggleCodePanel,
+    ToggleExportMode,
+    CodePreviewDue(u64),
+    CodePreviewReady(u64, Result<String, String>),
+    ToggleCodeFormat(bool),
+    CopyGeneratedCode,
+    ProjectOpened(Result<Box<Project>, DialogState>),
+    AutosaveDue,
+    RecoverAutosave(bool),
+    RecoverCrash(bool),
+    LayoutFilePoll,
+    ExternalChangeChosen(ExternalChangeChoice),
+    SaveLayoutCopyTo(Option<std::path::PathBuf>),
 
     // Selection
     SelectComponent(ComponentId),
@@ -46,21 +382,144 @@ pub enum Message {
     SelectPrevious,
     SelectParent,
     SelectFirstChild,
+    SelectFirstRow,
+    SelectLastRow,
+
+    // Tree drag and drop
+    FocusTree,
+    TreeDragStart(ComponentId),
+    TreeDragOver(ComponentId, DropPosition),
+    TreeDragEnd,
+    MoveNode(ComponentId, ComponentId, usize),
+    ToggleTreeNode(ComponentId),
+    TreeScrolled(iced::widget::scrollable::Viewport),
+    ExpandAllTree,
+    CollapseAllTree,
+
+    // Panel splitters
+    SplitterPressed(Split),
+    SplitterDragged(iced::Point),
+    SplitterReleased,
+
+    // Canvas interaction
+    CanvasClicked(ComponentId),
+    InlineEditChanged(String),
+    CommitInlineEdit,
+    CancelInlineEdit,
 
     // Palette
     PaletteItemClicked(WidgetKind),
+    SetInsertMode(InsertMode),
+    ModifiersChanged(iced::keyboard::Modifiers),
+    PaletteDragStart(WidgetKind),
+    PaletteDragMoved(iced::Point),
+    PaletteDragEnd,
+    PaletteDropOn(ComponentId),
+    TogglePaletteSection(String),
+    PaletteHovered(WidgetKind),
+    PaletteUnhovered(WidgetKind),
+    PaletteSearchChanged(String),
+    PaletteSearchSubmitted,
+
+    // Snippets
+    SaveSelectionAsSnippet,
+    SnippetNameInput(String),
+    CommitSnippetName,
+    CancelSnippetName,
+    ExtractComponent,
+    ExtractComponentAs(ComponentId, std::path::PathBuf),
+    InsertSnippet(usize),
+    StartRenameSnippet(usize),
+    SnippetRenameInput(String),
+    CommitSnippetRename,
+    DeleteSnippet(usize),
 
     // Component operations
     DeleteSelected,
+    ConfirmDelete,
+    CancelDelete,
+    ToggleDontAskDelete(bool),
+    SetConfirmSubtreeDelete(bool),
     DuplicateSelected,
+    ConvertWidget(ComponentId, WidgetKind),
+    ChangeRootType(WidgetKind),
+    UpdateNodeName(ComponentId, String),
+    ToggleNodeHidden(ComponentId),
+    SetExcludeFromExport(ComponentId, bool),
+    FindComponent,
+    TreeSearchChanged(String),
+    FindNext,
+    FindPrevious,
+    CopySelected,
+    CopySelectionAsCode,
+    CutSelected,
+    Paste,
+    ClipboardRead(Option<String>),
 
     // Undo/Redo
     Undo,
     Redo,
+    JumpToHistory(usize),
 
     // Mode
     SetMode(EditorMode),
     TogglePreviewMode,
+    ToggleFocusPreview,
+
+    // Preview interaction
+    PreviewPickListSelected(ComponentId, String),
+
+    // Window
+    WindowResized(iced::Size),
+    WindowCloseRequested(iced::window::Id),
+    FileDropped(std::path::PathBuf),
+    /// Open the first usable path of the finished drop.
+    DropSettled,
+    CreateProjectForLayout(std::path::PathBuf),
+
+    // View
+    ToggleTheme,
+    SetUiScale(f32),
+    /// Make the UI one step larger (positive) or smaller (negative).
+    StepUiScale(i32),
+    ToggleShowInvisible,
+    ToggleMeasurements,
+    ToggleStats,
+    ToggleLoadWarnings,
+    ToggleFullStatus,
+    TickStatus,
+    CrashSnapshotDue,
+    DismissDialog,
+    CopyDialogDetails,
+    ConfirmDropUnknownFields(Box<Message>),
+    ToggleBackupList,
+    RestoreBackup(std::path::PathBuf),
+    ToggleHistoryPanel,
+    ToggleProblemsPanel,
+    /// Open the problems panel, or when it is open select the first problem.
+    ShowProblems,
+    /// Find the state struct in the target project and check bindings
+    /// against it.
+    CheckStateBindings,
+    /// Stop checking bindings against the target project.
+    ClearStateCheck,
+    ProblemsDue(u64),
+    SelectProblem(ComponentId),
+    SelectCanvasPreset(CanvasPreset),
+    ToggleCanvasAutoScroll(bool),
+    ToggleTreeDock,
+    CanvasScrolled(iced::widget::scrollable::Viewport),
+    MinimapClicked(f32),
+    CanvasWidthInput(String),
+    CanvasHeightInput(String),
+    CommitCanvasSize,
+    HistoryLimitInput(String),
+    CommitHistoryLimit,
+
+    // Preview sample data
+    UpdateSampleValue(String, Option<crate::model::SampleValue>),
+    /// Text typed into a numeric inspector field, applied once it parses.
+    DraftInput(ComponentId, DraftField, String),
 
     // Property updates
     UpdateTextContent(ComponentId, String),
@@ -98,18 +557,157 @@ impl Default for App {
 }
 
 impl App {
-    /// Create a new application instance.
+    /// Create a new application instance with the saved settings.
     pub fn new() -> Self {
+        Self::with_settings(AppSettings::load_or_default())
+    }
+
+    /// Keep `handle` up to date with the active project's unsaved layout,
+    /// for a panic hook to save.
+    pub fn with_crash_handle(mut self, handle: CrashHandle) -> Self {
+        self.crash_handle = handle;
+        self
+    }
+
+    /// Create a new application instance with the given settings.
+    pub fn with_settings(mut settings: AppSettings) -> Self {
+        settings.ui_scale = metrics::clamp_scale(settings.ui_scale);
+        metrics::set_scale(settings.ui_scale);
+        let (canvas_width_input, canvas_height_input) = Self::canvas_size_inputs(settings.canvas_size);
+        let history_limit_input = settings.history_limit.to_string();
+
         Self {
-            project: None,
+            projects: Vec::new(),
+            active: 0,
             mode: EditorMode::Design,
+            focus_preview: None,
+            show_invisible: false,
+            show_measurements: false,
+            show_stats: false,
+            backup_list: None,
+            open_menu: None,
+            context_menu: None,
+            last_right_press: iced::Point::ORIGIN,
+            show_recent: false,
+            show_load_warnings: false,
+            drop_unknown_fields: false,
+            recent_projects: RecentProjects::load_or_default(),
+            show_history: false,
+            show_problems: false,
+            problems: Problems::default(),
+            code_preview: None,
+            autosave_scheduled: false,
+            autosaved_revisions: HashMap::new(),
+            crash_handle: CrashHandle::default(),
+            crash_snapshot: None,
+            external_change_prompt: false,
+            inline_edit: None,
+            last_canvas_click: None,
+            inspector_drafts: Drafts::default(),
+            preview_selections: HashMap::new(),
+            pending_insert: None,
+            palette_drag: None,
+            insert_mode: InsertMode::default(),
+            modifiers: iced::keyboard::Modifiers::default(),
+            palette_query: String::new(),
+            palette_hover: None,
+            canvas_cache: CanvasCache::default(),
+            loading: Vec::new(),
+            saving: Vec::new(),
+            snippets: Vec::new(),
+            snippet_rename: None,
+            snippet_name: None,
+            tree: TreeState::default(),
+            splitter_drag: None,
+            minimap: MinimapCache::default(),
+            canvas_viewport: None,
+            settings,
+            canvas_width_input,
+            history_limit_input,
+            canvas_height_input,
             status_message: None,
+            show_full_status: false,
+            pending_dialog: None,
+            dropped_paths: Vec::new(),
+            template_picker: None,
+            delete_confirm: None,
+        }
+    }
+
+    /// Delete a node from the active project as one undo step.
+    fn delete_node(&mut self, id: ComponentId) {
+        let Some(project) = self.active_project_mut() else {
+            return;
+        };
+        tracing::info!(target: targets::TREE, %id, "Delete requested");
+
+        // Push history before modification
+        let label = Self::action_label("Delete", project, id);
+        project.history.push(project.layout.clone(), label);
+
+        // Remove the selected node
+        if project.remove_node(id) {
+            project.selected_id = None;
+            project.mark_dirty();
+            tracing::info!(target: targets::TREE, %id, "Component deleted");
+            self.status_message = Some(StatusMessage::success("Component deleted".to_string()));
+        } else {
+            // Undo the history push if removal failed
+            let _ = project.history.undo(project.layout.clone());
+            tracing::warn!(target: targets::TREE, %id, "Failed to delete component");
+            self.status_message = Some(StatusMessage::error("Cannot delete this component".to_string()));
+        }
+    }
+
+    /// The project in the active tab.
+    fn active_project(&self) -> Option<&Project> {
+        self.projects.get(self.active)
+    }
+
+    /// The project in the active tab, for editing.
+    fn active_project_mut(&mut self) -> Option<&mut Project> {
+        self.projects.get_mut(self.active)
+    }
+
+    /// Show a project in a new tab and make it active. A tab already holding
+    /// the same directory is replaced, so callers ask first when it has
+    /// unsaved changes.
+    fn open_tab(&mut self, project: Project) {
+        let index = match self.projects.iter().position(|p| p.path == project.path) {
+            Some(index) => {
+                self.projects[index] = project;
+                index
+            }
+            None => {
+                self.projects.push(project);
+                self.projects.len() - 1
+            }
+        };
+        self.select_tab(index);
+    }
+
+    /// Make a tab active, dropping view state tied to the previous project.
+    fn select_tab(&mut self, index: usize) {
+        self.active = index.min(self.projects.len().saturating_sub(1));
+        if let Some(preview) = &mut self.code_preview {
+            preview.revision = None;
         }
+        self.inline_edit = None;
+        self.pending_insert = None;
+        self.backup_list = None;
+        self.external_change_prompt = false;
+        self.preview_selections.clear();
+        // Revisions are per project, so cached views keyed on them are stale
+        self.minimap = MinimapCache::default();
+        self.tree = TreeState::default();
+        self.problems = Problems::default();
+        self.delete_confirm = None;
+        self.refresh_snippets();
     }
 
     /// Get the window title.
     pub fn title(&self) -> String {
-        match &self.project {
+        match self.active_project() {
             Some(p) => {
                 let dirty = if p.dirty { " •" } else { "" };
                 format!("Iced Builder - {}{}", p.layout.name, dirty)
@@ -118,13 +716,176 @@ impl App {
         }
     }
 
+    /// Get the window theme.
+    pub fn theme(&self) -> iced::Theme {
+        match self.settings.theme {
+            ThemeChoice::Light => iced::Theme::Light,
+            ThemeChoice::Dark => iced::Theme::Dark,
+        }
+    }
+
     /// Update application state based on a message.
     pub fn update(&mut self, message: Message) -> Task<Message> {
-        tracing::debug!(target: "iced_builder::app::message", ?message, "Processing message");
+        let task = self.handle_message(message);
+        self.update_crash_snapshot();
+        Task::batch([
+            task,
+            self.schedule_code_preview(),
+            self.schedule_problems(),
+            self.schedule_autosave(),
+        ])
+    }
+
+    fn handle_message(&mut self, message: Message) -> Task<Message> {
+        tracing::debug!(target: targets::MESSAGE, ?message, "Processing message");
         
         match message {
+            Message::ToggleMenu(menu) => {
+                self.open_menu = (self.open_menu != Some(menu)).then_some(menu);
+                Task::none()
+            }
+
+            Message::CloseMenu => {
+                self.open_menu = None;
+                Task::none()
+            }
+
+            Message::MenuCommand(command) => {
+                self.open_menu = None;
+                self.context_menu = None;
+                self.handle_message(*command)
+            }
+
+            Message::RightPressed(position) => {
+                self.last_right_press = position;
+                Task::none()
+            }
+
+            Message::OpenContextMenu(id) => {
+                if self.mode != EditorMode::Design {
+                    return Task::none();
+                }
+                self.open_menu = None;
+                self.tree.focused = false;
+                self.select_component(id);
+                self.context_menu = Some((id, self.last_right_press));
+                Task::none()
+            }
+
+            Message::CloseContextMenu => {
+                self.context_menu = None;
+                Task::none()
+            }
+
+            Message::Quit => {
+                tracing::info!(target: targets::APP, "Quitting");
+                self.save_settings();
+                iced::window::get_latest().and_then(iced::window::close)
+            }
+
+            Message::SelectTab(index) => {
+                if index != self.active && index < self.projects.len() {
+                    self.select_tab(index);
+                }
+                Task::none()
+            }
+
+            Message::CloseTab(index) => {
+                let Some(project) = self.projects.get(index) else {
+                    return Task::none();
+                };
+                if !project.dirty {
+                    return self.update(Message::CloseTabChosen(index, UnsavedChoice::Discard));
+                }
+                let description = format!("Save changes to \"{}\" before closing it?", project.layout.name);
+                Task::perform(
+                    async move {
+                        rfd::AsyncMessageDialog::new()
+                            .set_title("Unsaved changes")
+                            .set_description(description)
+                            .set_level(rfd::MessageLevel::Warning)
+                            .set_buttons(rfd::MessageButtons::YesNoCancelCustom(
+                                "Save".to_string(),
+                                "Don't Save".to_string(),
+                                "Cancel".to_string(),
+                            ))
+                            .show()
+                            .await
+                    },
+                    move |result| {
+                        let choice = match result {
+                            rfd::MessageDialogResult::Custom(label) if label == "Save" => UnsavedChoice::Save,
+                            rfd::MessageDialogResult::Custom(label) if label.starts_with("Don") => {
+                                UnsavedChoice::Discard
+                            }
+                            rfd::MessageDialogResult::Yes => UnsavedChoice::Save,
+                            rfd::MessageDialogResult::No => UnsavedChoice::Discard,
+                            _ => UnsavedChoice::Cancel,
+                        };
+                        Message::CloseTabChosen(index, choice)
+                    },
+                )
+            }
+
+            Message::CloseActiveTab => self.update(Message::CloseTab(self.active)),
+
+            Message::CloseTabChosen(index, choice) => {
+                let Some(project) = self.projects.get_mut(index) else {
+                    return Task::none();
+                };
+                match choice {
+                    UnsavedChoice::Cancel => return Task::none(),
+                    UnsavedChoice::Save => {
+                        if let Err(e) = project.save() {
+                            tracing::error!(target: targets::APP, error = %e, "Failed to save before closing");
+                            let path = project.path.clone();
+                            self.show_error("Failed to save project", Some(&path), &e);
+                            return Task::none();
+                        }
+                    }
+                    // Closing without saving means the edits aren't wanted back
+                    UnsavedChoice::Discard if project.dirty => project.discard_autosave(),
+                    UnsavedChoice::Discard => {}
+                }
+                let closed = self.projects.remove(index);
+                tracing::info!(target: targets::APP, name = %closed.layout.name, "Tab closed");
+                if index < self.active {
+                    self.active -= 1;
+                } else if index == self.active {
+                    // The next tab takes its place, or the previous one at the end
+                    self.select_tab(self.active);
+                }
+                self.status_message = Some(StatusMessage::info(format!("Closed {}", closed.layout.name)));
+                Task::none()
+            }
+
             Message::NewProject => {
-                tracing::info!(target: "iced_builder::app", "Creating new project");
+                tracing::info!(target: targets::APP, "Creating new project");
+                // The template is picked once the folder is known
+                Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Select folder for new project")
+                            .pick_folder()
+                            .await
+                            .map(|f| f.path().to_path_buf())
+                    },
+                    |path| path.map_or(Message::Noop, Message::ChooseTemplate),
+                )
+            }
+
+            Message::ChooseTemplate(path) => {
+                self.template_picker = Some(path);
+                Task::none()
+            }
+
+            Message::CancelTemplatePicker => {
+                self.template_picker = None;
+                Task::none()
+            }
+
+            Message::NewProjectFromTemplate(template) => {
+                tracing::info!(target: targets::APP, ?template, "Creating new project");
                 // Open folder picker for new project location
                 Task::perform(
                     async {
@@ -134,30 +895,67 @@ impl App {
                             .await;
                         folder.map(|f| f.path().to_path_buf())
                     },
-                    |path| match path {
-                        Some(path) => Message::CreateProjectAt(path),
+                    move |path| match path {
+                        Some(path) => Message::CreateProjectAt(path, template),
                         None => Message::Noop,
                     },
                 )
             }
 
-            Message::CreateProjectAt(path) => {
-                tracing::info!(target: "iced_builder::app", path = %path.display(), "Creating project at path");
-                match Project::create(&path, None) {
-                    Ok(project) => {
-                        self.project = Some(project);
-                        self.status_message = Some("New project created".to_string());
+            Message::CreateProjectAt(path, template) => {
+                self.template_picker = None;
+                // The new project takes over the tab already showing this folder
+                let Some(open) = self.projects.iter().find(|p| p.path == path && p.dirty) else {
+                    return self.update(Message::ReplaceTabChosen(path, template, true));
+                };
+                let description = format!(
+                    "\"{}\" is open with unsaved changes. Create a new project in its folder and discard them?",
+                    open.layout.name
+                );
+                Task::perform(
+                    async move {
+                        rfd::AsyncMessageDialog::new()
+                            .set_title("Replace open project?")
+                            .set_description(description)
+                            .set_level(rfd::MessageLevel::Warning)
+                            .set_buttons(rfd::MessageButtons::OkCancelCustom(
+                                "Replace".to_string(),
+                                "Cancel".to_string(),
+                            ))
+                            .show()
+                            .await
+                    },
+                    move |result| {
+                        let replace = match result {
+                            rfd::MessageDialogResult::Custom(label) => label == "Replace",
+                            result => result == rfd::MessageDialogResult::Ok,
+                        };
+                        Message::ReplaceTabChosen(path.clone(), template, replace)
+                    },
+                )
+            }
+
+            Message::ReplaceTabChosen(_, _, false) => Task::none(),
+
+            Message::ReplaceTabChosen(path, template, true) => {
+                tracing::info!(target: targets::APP, path = %path.display(), ?template, "Creating project at path");
+                match Project::create(&path, Some(template)) {
+                    Ok(mut project) => {
+                        project.history.set_limit(self.settings.history_limit);
+                        self.open_tab(project);
+                        self.remember_project();
+                        self.status_message = Some(StatusMessage::success("New project created".to_string()));
                     }
                     Err(e) => {
-                        tracing::error!(target: "iced_builder::app", error = %e, "Failed to create project");
-                        self.status_message = Some(format!("Failed to create project: {}", e));
+                        tracing::error!(target: targets::APP, error = %e, "Failed to create project");
+                        self.show_error("Failed to create project", Some(&path), &e);
                     }
                 }
                 Task::none()
             }
 
             Message::OpenProject => {
-                tracing::info!(target: "iced_builder::app", "Open project requested");
+                tracing::info!(target: targets::APP, "Open project requested");
                 // Open folder picker dialog
                 Task::perform(
                     async {
@@ -166,677 +964,4194 @@ impl App {
                             .pick_folder()
                             .await;
                         
-                        match folder {
-                            Some(f) => {
-                                let path = f.path().to_path_buf();
-                                Project::open(&path)
-                                    .map_err(|e| e.to_string())
-                            }
-                            None => Err("No folder selected".to_string()),
-                        }
+                        folder.map(|f| f.path().to_path_buf())
                     },
-                    Message::ProjectOpened,
+                    |path| path.map_or(Message::Noop, Message::LoadProject),
                 )
             }
 
-            Message::SaveProject => {
-                tracing::info!(target: "iced_builder::app", "Saving project");
-                if let Some(project) = &mut self.project {
-                    match project.save() {
-                        Ok(()) => {
-                            self.status_message = Some("Project saved".to_string());
-                        }
-                        Err(e) => {
-                            tracing::error!(target: "iced_builder::app", error = %e, "Failed to save project");
-                            self.status_message = Some(format!("Failed to save: {}", e));
-                        }
+            Message::OpenProjectAt(path) => {
+                self.show_recent = false;
+                // Projects moved or deleted since they were opened are forgotten
+                if !crate::io::is_valid_project(&path) {
+                    if self.recent_projects.remove(&path) {
+                        self.recent_projects.save_or_log();
                     }
-                } else {
-                    self.status_message = Some("No project open".to_string());
+                    self.status_message = Some(StatusMessage::error(format!("{} is no longer an Iced Builder project", path.display())));
+                    return Task::none();
+                }
+                tracing::info!(target: targets::APP, path = %path.display(), "Opening recent project");
+                self.update(Message::LoadProject(path))
+            }
+
+            Message::LoadProject(path) => {
+                if self.loading.contains(&path) {
+                    return Task::none();
+                }
+                self.loading.push(path.clone());
+                let title = "Failed to open project";
+                Task::perform(
+                    in_background(title, path.clone(), move || open_project(&path)),
+                    Message::ProjectOpened,
+                )
+            }
+
+            Message::ToggleRecentProjects => {
+                self.show_recent = !self.show_recent;
+                if self.show_recent && self.recent_projects.prune_missing() {
+                    self.recent_projects.save_or_log();
                 }
                 Task::none()
             }
 
-            Message::ExportCode => {
-                tracing::info!(target: "iced_builder::codegen", "Exporting code");
-                if let Some(project) = &self.project {
-                    match project.export() {
-                        Ok(code) => {
-                            tracing::debug!(target: "iced_builder::codegen", code_length = code.len(), "Code generated");
-                            self.status_message = Some(format!(
-                                "Code exported to {}",
-                                project.config.output_file.display()
-                            ));
-                        }
-                        Err(e) => {
-                            tracing::error!(target: "iced_builder::codegen", error = %e, "Export failed");
-                            self.status_message = Some(format!("Export failed: {}", e));
-                        }
+            Message::SaveProject => {
+                tracing::info!(target: targets::APP, "Saving project");
+                // Never overwrite changes made outside the builder unasked
+                if self.active_project().is_some_and(Project::layout_changed_on_disk) {
+                    return self.handle_external_change();
+                }
+                if let Some(task) = self.confirm_drop_unknown_fields(Message::SaveProject) {
+                    return task;
+                }
+                let Some(project) = self.projects.get(self.active) else {
+                    self.status_message = Some(StatusMessage::info("No project open".to_string()));
+                    return Task::none();
+                };
+                if self.saving.contains(&project.path) {
+                    self.status_message = Some(StatusMessage::info("Still saving the last changes".to_string()));
+                    return Task::none();
+                }
+                // Write a copy, so editing can go on while the files are written
+                let path = project.path.clone();
+                let snapshot = project.snapshot();
+                self.saving.push(path.clone());
+                self.status_message = Some(StatusMessage::info("Saving…".to_string()));
+                let title = "Failed to save project";
+                Task::perform(
+                    in_background(title, path.clone(), move || {
+                        snapshot
+                            .write()
+                            .map_err(|e| DialogState::error(title, Some(&snapshot.path), &e))
+                    }),
+                    move |result| Message::ProjectSaved(path.clone(), result),
+                )
+            }
+
+            Message::ProjectSaved(path, result) => {
+                self.saving.retain(|saving| *saving != path);
+                let written = match result {
+                    Ok(written) => written,
+                    Err(dialog) => {
+                        tracing::error!(target: targets::APP, error = %dialog.chain[0], "Failed to save project");
+                        self.show_dialog(dialog);
+                        return Task::none();
                     }
-                } else {
-                    self.status_message = Some("No project open".to_string());
+                };
+                // The tab may have been closed while saving
+                let Some(index) = self.projects.iter().position(|p| p.path == path) else {
+                    return Task::none();
+                };
+                let project = &mut self.projects[index];
+                let problems = project.finish_write(written);
+                if problems.is_empty() {
+                    self.status_message = Some(StatusMessage::success("Project saved".to_string()));
+                    return Task::none();
+                }
+                if index != self.active {
+                    // The panel shows the active tab; switching back revalidates
+                    self.status_message = Some(StatusMessage::warning(format!(
+                        "{} saved with problems",
+                        path.display()
+                    )));
+                    return Task::none();
                 }
+                // The save validated the layout, so the panel can show it as is
+                let revision = project.revision;
+                let state_issues = self.problems.state.as_ref().map(|state| state.check(&project.layout));
+                self.problems.list = problems;
+                self.problems.list.extend(state_issues.into_iter().flatten());
+                self.problems.revision = Some(revision);
+                self.status_message = Some(StatusMessage::warning(format!(
+                    "Project saved with {}",
+                    self.problems.summary()
+                )));
                 Task::none()
             }
 
-            Message::ProjectOpened(result) => {
-                match result {
-                    Ok(project) => {
-                        tracing::info!(target: "iced_builder::app", name = %project.layout.name, "Project opened");
-                        self.project = Some(project);
-                        self.status_message = Some("Project opened".to_string());
+            Message::SaveLayoutAs => {
+                let Some(project) = self.active_project() else {
+                    self.status_message = Some(StatusMessage::info("No project open".to_string()));
+                    return Task::none();
+                };
+                let directory = project.path.clone();
+                let file_name = project
+                    .config
+                    .layout_file()
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                Task::perform(
+                    async move {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Save layout as")
+                            .set_directory(directory)
+                            .set_file_name(file_name)
+                            .add_filter("RON layout", &["ron"])
+                            .add_filter("JSON layout", &["json"])
+                            .save_file()
+                            .await
+                            .map(|file| file.path().to_path_buf())
+                    },
+                    Message::SaveLayoutAsTo,
+                )
+            }
+
+            Message::SaveLayoutAsTo(path) => {
+                let Some(path) = path else {
+                    return Task::none();
+                };
+                if crate::io::LayoutFormat::from_path(&path).is_none() {
+                    self.status_message = Some(StatusMessage::error("Layouts must be saved as .ron or .json".to_string()));
+                    return Task::none();
+                }
+                if let Some(task) = self.confirm_drop_unknown_fields(Message::SaveLayoutAsTo(Some(path.clone()))) {
+                    return task;
+                }
+                let Some(project) = self.active_project_mut() else {
+                    return Task::none();
+                };
+                match project.save_as(&path) {
+                    Ok(()) => {
+                        self.status_message = Some(StatusMessage::success(format!("Layout saved as {}", path.display())));
                     }
                     Err(e) => {
-                        tracing::error!(target: "iced_builder::app", error = %e, "Failed to open project");
-                        // Show a shorter message in status bar
-                        let short_msg = if e.to_string().contains("Not an Iced Builder project") {
-                            "Not an Iced Builder project. Use 'New Project' to create one.".to_string()
-                        } else {
-                            format!("Failed to open: {}", e)
-                        };
-                        self.status_message = Some(short_msg);
+                        tracing::error!(target: targets::APP, error = %e, "Failed to save layout as");
+                        self.show_error("Failed to save layout", Some(&path), &e);
                     }
                 }
                 Task::none()
             }
 
-            Message::SelectComponent(id) => {
-                tracing::debug!(target: "iced_builder::app::selection", %id, "Component selected");
-                if let Some(project) = &mut self.project {
-                    project.selected_id = Some(id);
-                    
-                    // Log details about the selected node
-                    if let Some(node) = project.find_node(id) {
-                        tracing::debug!(
-                            target: "iced_builder::app::selection",
-                            widget_type = ?std::mem::discriminant(&node.widget),
-                            "Selected node details"
-                        );
-                    }
-                }
-                Task::none()
+            Message::SaveProjectAs => {
+                let Some(project) = self.active_project() else {
+                    self.status_message = Some(StatusMessage::info("No project open"));
+                    return Task::none();
+                };
+                let directory = project.path.parent().unwrap_or(&project.path).to_path_buf();
+                Task::perform(
+                    async move {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Save project as")
+                            .set_directory(directory)
+                            .pick_folder()
+                            .await
+                            .map(|folder| folder.path().to_path_buf())
+                    },
+                    Message::SaveProjectAsChosen,
+                )
             }
 
-            Message::DeselectComponent => {
-                tracing::debug!(target: "iced_builder::app::selection", "Component deselected");
-                if let Some(project) = &mut self.project {
-                    project.selected_id = None;
+            Message::SaveProjectAsChosen(target) => {
+                let Some(target) = target else {
+                    return Task::none();
+                };
+                if crate::io::bundle::is_empty_dir(&target) {
+                    return self.update(Message::SaveProjectAsInto(target));
                 }
-                Task::none()
+                let description = format!(
+                    "{} is not empty. Project files with the same names will be overwritten. Save here anyway?",
+                    target.display()
+                );
+                Task::perform(
+                    async move {
+                        rfd::AsyncMessageDialog::new()
+                            .set_title("Save into a non-empty folder?")
+                            .set_description(description)
+                            .set_level(rfd::MessageLevel::Warning)
+                            .set_buttons(rfd::MessageButtons::YesNo)
+                            .show()
+                            .await
+                    },
+                    move |result| match result {
+                        rfd::MessageDialogResult::Yes => Message::SaveProjectAsInto(target.clone()),
+                        _ => Message::Noop,
+                    },
+                )
             }
 
-            Message::SelectNext => {
-                if let Some(project) = &mut self.project {
-                    if let Some(next_id) = project.get_next_sibling() {
-                        project.selected_id = Some(next_id);
-                        self.status_message = Some("Selected next sibling".to_string());
+            Message::SaveProjectAsInto(target) => {
+                if let Some(task) = self.confirm_drop_unknown_fields(Message::SaveProjectAsInto(target.clone())) {
+                    return task;
+                }
+                let Some(project) = self.active_project_mut() else {
+                    return Task::none();
+                };
+                match project.save_project_as(&target) {
+                    Ok(count) => {
+                        self.status_message = Some(StatusMessage::success(format!(
+                            "Project saved to {} ({} files)",
+                            target.display(),
+                            count
+                        )));
+                        self.refresh_snippets();
+                        self.remember_project();
+                    }
+                    Err(e) => {
+                        tracing::error!(target: targets::APP, error = %e, "Failed to save project as");
+                        self.show_error("Failed to save project", Some(&target), &e);
                     }
                 }
                 Task::none()
             }
 
-            Message::SelectPrevious => {
-                if let Some(project) = &mut self.project {
-                    if let Some(prev_id) = project.get_previous_sibling() {
-                        project.selected_id = Some(prev_id);
-                        self.status_message = Some("Selected previous sibling".to_string());
-                    }
+            Message::ExportBundle => {
+                let Some(project) = self.active_project() else {
+                    self.status_message = Some(StatusMessage::info("No project open".to_string()));
+                    return Task::none();
+                };
+                // Bundles are written from disk, so unsaved edits would be missed
+                if project.dirty {
+                    self.status_message = Some(StatusMessage::error("Save the project before exporting a bundle".to_string()));
+                    return Task::none();
                 }
-                Task::none()
+                let directory = project.path.parent().unwrap_or(&project.path).to_path_buf();
+                let file_name = format!(
+                    "{}.{}",
+                    project.path.file_name().map_or("project".into(), |n| n.to_string_lossy()),
+                    crate::io::BUNDLE_EXTENSION
+                );
+                Task::perform(
+                    async move {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Export project bundle")
+                            .set_directory(directory)
+                            .set_file_name(file_name)
+                            .add_filter("Iced Builder bundle", &[crate::io::BUNDLE_EXTENSION])
+                            .save_file()
+                            .await
+                            .map(|file| file.path().to_path_buf())
+                    },
+                    Message::ExportBundleTo,
+                )
             }
 
-            Message::SelectParent => {
-                if let Some(project) = &mut self.project {
-                    if let Some(parent_id) = project.get_parent() {
-                        project.selected_id = Some(parent_id);
-                        self.status_message = Some("Selected parent".to_string());
+            Message::ExportBundleTo(path) => {
+                let (Some(path), Some(project)) = (path, self.active_project()) else {
+                    return Task::none();
+                };
+                match project.export_bundle(&path) {
+                    Ok(count) => {
+                        tracing::info!(target: targets::APP, path = %path.display(), files = count, "Bundle exported");
+                        self.status_message = Some(StatusMessage::success(format!("Exported {} files to {}", count, path.display())));
+                    }
+                    Err(e) => {
+                        tracing::error!(target: targets::APP, error = %e, "Failed to export bundle");
+                        self.show_error("Failed to export bundle", Some(&path), &e);
                     }
                 }
                 Task::none()
             }
 
-            Message::SelectFirstChild => {
-                if let Some(project) = &mut self.project {
-                    if let Some(child_id) = project.get_first_child() {
-                        project.selected_id = Some(child_id);
-                        self.status_message = Some("Selected first child".to_string());
+            Message::ExportOutline => {
+                let Some(project) = self.active_project() else {
+                    self.status_message = Some(StatusMessage::info("No project open".to_string()));
+                    return Task::none();
+                };
+                let directory = project.path.clone();
+                let file_name = format!("{}.txt", project.layout.name);
+                Task::perform(
+                    async move {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Export outline")
+                            .set_directory(directory)
+                            .set_file_name(file_name)
+                            .add_filter("Text", &["txt"])
+                            .save_file()
+                            .await
+                            .map(|file| file.path().to_path_buf())
+                    },
+                    Message::ExportOutlineTo,
+                )
+            }
+
+            Message::ExportOutlineTo(path) => {
+                let (Some(path), Some(project)) = (path, self.active_project()) else {
+                    return Task::none();
+                };
+                match std::fs::write(&path, project.layout.root.to_outline(0)) {
+                    Ok(()) => {
+                        tracing::info!(target: targets::APP, path = %path.display(), "Outline exported");
+                        self.status_message = Some(StatusMessage::success(format!("Exported outline to {}", path.display())));
+                    }
+                    Err(e) => {
+                        tracing::error!(target: targets::APP, error = %e, "Failed to export outline");
+                        self.show_error("Failed to export outline", Some(&path), &e);
                     }
                 }
                 Task::none()
             }
 
-            Message::PaletteItemClicked(kind) => {
-                tracing::info!(target: "iced_builder::app::tree", ?kind, "Adding widget from palette");
-                if let Some(project) = &mut self.project {
-                    // Push history before modification
-                    project.history.push(project.layout.clone());
-
-                    // Create the new node
-                    let new_node = create_node_for_kind(kind);
-                    let new_node_id = new_node.id;
-                    tracing::debug!(
-                        target: "iced_builder::app::tree", 
-                        node_id = %new_node.id, 
-                        "Created new node"
-                    );
+            Message::CopyOutline => {
+                let Some(project) = self.active_project() else {
+                    return Task::none();
+                };
+                let outline = project.layout.root.to_outline(0);
+                self.status_message = Some(StatusMessage::success("Copied layout outline".to_string()));
+                iced::clipboard::write(outline)
+            }
 
-                    // Try to add to selected container, otherwise add to root
-                    let added = if let Some(selected_id) = project.selected_id {
-                        if project.is_container(selected_id) {
-                            tracing::debug!(
-                                target: "iced_builder::app::tree",
-                                parent_id = %selected_id,
-                                "Adding to selected container"
-                            );
-                            project.add_child_to_node(selected_id, new_node)
-                        } else {
-                            tracing::debug!(
-                                target: "iced_builder::app::tree",
-                                "Selected node is not a container, adding to root"
-                            );
-                            project.add_child_to_root(new_node)
-                        }
-                    } else {
-                        tracing::debug!(
-                            target: "iced_builder::app::tree",
-                            "No selection, adding to root"
-                        );
-                        project.add_child_to_root(new_node)
-                    };
+            Message::ImportBundle => Task::perform(
+                async {
+                    let bundle = rfd::AsyncFileDialog::new()
+                        .set_title("Import project bundle")
+                        .add_filter("Iced Builder bundle", &[crate::io::BUNDLE_EXTENSION])
+                        .pick_file()
+                        .await?;
+                    let target = rfd::AsyncFileDialog::new()
+                        .set_title("Choose a folder for the imported project")
+                        .pick_folder()
+                        .await?;
+                    Some((bundle.path().to_path_buf(), target.path().to_path_buf()))
+                },
+                Message::ImportBundleChosen,
+            ),
 
-                    if added {
-                        project.mark_dirty();
-                        // Select the newly added node
-                        project.selected_id = Some(new_node_id);
-                        self.status_message = Some(format!("Added {}", kind.name()));
-                    } else {
-                        // Undo the history push if add failed
-                        let _ = project.history.undo(project.layout.clone());
-                        self.status_message = Some("Cannot add widget here".to_string());
-                    }
+            Message::ImportBundleChosen(chosen) => {
+                let Some((bundle, target)) = chosen else {
+                    return Task::none();
+                };
+                // Refuse a broken or unsafe bundle before touching the target
+                if let Err(e) = crate::io::bundle::read_manifest(&bundle) {
+                    tracing::error!(target: targets::APP, error = %e, "Invalid bundle");
+                    self.show_error("Failed to import bundle", Some(&bundle), &e);
+                    return Task::none();
                 }
-                Task::none()
+                if crate::io::bundle::is_empty_dir(&target) {
+                    return self.update(Message::ImportBundleInto(bundle, target));
+                }
+                let description = format!(
+                    "{} is not empty. Files from the bundle will overwrite files with the same name. Import anyway?",
+                    target.display()
+                );
+                Task::perform(
+                    async move {
+                        rfd::AsyncMessageDialog::new()
+                            .set_title("Import into a non-empty folder?")
+                            .set_description(description)
+                            .set_level(rfd::MessageLevel::Warning)
+                            .set_buttons(rfd::MessageButtons::YesNo)
+                            .show()
+                            .await
+                    },
+                    move |result| match result {
+                        rfd::MessageDialogResult::Yes => Message::ImportBundleInto(bundle.clone(), target.clone()),
+                        _ => Message::Noop,
+                    },
+                )
             }
 
-            Message::DeleteSelected => {
-                if let Some(project) = &mut self.project {
-                    if let Some(id) = project.selected_id {
-                        tracing::info!(target: "iced_builder::app::tree", %id, "Delete requested");
-                        
-                        // Push history before modification
-                        project.history.push(project.layout.clone());
-                        
-                        // Remove the selected node
-                        if project.remove_node(id) {
-                            project.selected_id = None;
-                            project.mark_dirty();
-                            tracing::info!(target: "iced_builder::app::tree", %id, "Component deleted");
-                            self.status_message = Some("Component deleted".to_string());
-                        } else {
-                            // Undo the history push if removal failed
-                            let _ = project.history.undo(project.layout.clone());
-                            tracing::warn!(target: "iced_builder::app::tree", %id, "Failed to delete component");
-                            self.status_message = Some("Cannot delete this component".to_string());
-                        }
+            Message::ImportBundleInto(bundle, target) => {
+                // Reaching here means the target was empty or overwriting was confirmed
+                match crate::io::bundle::extract_bundle(&bundle, &target, true) {
+                    Ok(manifest) => {
+                        tracing::info!(target: targets::APP, name = %manifest.name, target = %target.display(), "Bundle imported");
+                        self.update(Message::LoadProject(target))
+                    }
+                    Err(e) => {
+                        tracing::error!(target: targets::APP, error = %e, "Failed to import bundle");
+                        self.show_error("Failed to import bundle", Some(&bundle), &e);
+                        Task::none()
                     }
                 }
-                Task::none()
             }
 
-            Message::DuplicateSelected => {
-                if let Some(project) = &mut self.project {
-                    if let Some(id) = project.selected_id {
-                        tracing::info!(target: "iced_builder::app::tree", %id, "Duplicate requested");
-                        
-                        // Push history before modification
-                        project.history.push(project.layout.clone());
-                        
-                        // Duplicate the selected node
-                        if let Some(new_id) = project.duplicate_node(id) {
-                            project.selected_id = Some(new_id);
-                            project.mark_dirty();
-                            tracing::info!(target: "iced_builder::app::tree", old_id = %id, new_id = %new_id, "Component duplicated");
-                            self.status_message = Some("Component duplicated".to_string());
-                        } else {
-                            // Undo the history push if duplication failed
-                            let _ = project.history.undo(project.layout.clone());
-                            tracing::warn!(target: "iced_builder::app::tree", %id, "Failed to duplicate component");
-                            self.status_message = Some("Cannot duplicate this component".to_string());
-                        }
-                    }
+            Message::ExportCode => {
+                let Some(project) = self.projects.get(self.active) else {
+                    self.status_message = Some(StatusMessage::info("No project open".to_string()));
+                    return Task::none();
+                };
+                // A layout that would not compile is refused before any review
+                let blockers = project.export_blockers();
+                if !blockers.is_empty() {
+                    return self.report_validation_failure("Export", &blockers);
                 }
+                // Generating and formatting can take a while; review changes
+                // to an existing file before overwriting it
+                let path = project.path.clone();
+                let output = project.output_path();
+                let snapshot = project.snapshot();
+                let title = "Export failed";
+                Task::perform(
+                    in_background(title, output.clone(), move || {
+                        let code = snapshot
+                            .generate_output()
+                            .map_err(|e| DialogState::error(title, Some(&output), &e))?;
+                        let existing = std::fs::read_to_string(&output).ok().filter(|existing| *existing != code.code);
+                        Ok((code, existing))
+                    }),
+                    move |result| Message::ExportPrepared(path.clone(), result),
+                )
+            }
+
+            Message::ExportPrepared(path, result) => {
+                let (code, existing) = match result {
+                    Ok(prepared) => prepared,
+                    Err(dialog) => {
+                        tracing::error!(target: targets::CODEGEN, error = %dialog.chain[0], "Export failed");
+                        self.show_dialog(dialog);
+                        return Task::none();
+                    }
+                };
+                let Some(project) = self.projects.get(self.active).filter(|p| p.path == path) else {
+                    tracing::debug!(target: targets::CODEGEN, "Tab changed before the export was ready");
+                    return Task::none();
+                };
+                let Some(existing) = existing else {
+                    return self.export_code();
+                };
+
+                let close_after = self.code_preview.is_none();
+                let review = ExportReview::new(project.config.output_file.clone(), &existing, &code.code, close_after);
+                tracing::info!(
+                    target: targets::CODEGEN,
+                    added = review.added,
+                    removed = review.removed,
+                    foreign = review.foreign,
+                    edited = review.edited,
+                    "Reviewing export changes"
+                );
+                self.status_message = Some(StatusMessage::info(format!(
+                    "Export will add {} and remove {} lines",
+                    review.added, review.removed
+                )));
+                let preview = self.code_preview.get_or_insert_with(|| CodePreview {
+                    code: code.code,
+                    revision: Some(project.revision),
+                    ..CodePreview::new(project.config.format_output)
+                });
+                preview.review = Some(review);
                 Task::none()
             }
 
-            Message::Undo => {
-                tracing::debug!(target: "iced_builder::app", "Undo requested");
-                if let Some(project) = &mut self.project {
-                    if let Some(previous) = project.history.undo(project.layout.clone()) {
-                        project.layout = previous;
-                        project.rebuild_index();
-                        tracing::info!(target: "iced_builder::app", "Undo applied");
-                        self.status_message = Some("Undo".to_string());
+            Message::CodeExported(result) => {
+                match result {
+                    Ok(summary) => self.status_message = Some(StatusMessage::success(summary)),
+                    Err(dialog) => {
+                        tracing::error!(target: targets::CODEGEN, error = %dialog.chain[0], "Export failed");
+                        self.show_dialog(dialog);
                     }
                 }
                 Task::none()
             }
 
-            Message::Redo => {
-                tracing::debug!(target: "iced_builder::app", "Redo requested");
-                if let Some(project) = &mut self.project {
-                    if let Some(next) = project.history.redo(project.layout.clone()) {
-                        project.layout = next;
-                        project.rebuild_index();
-                        tracing::info!(target: "iced_builder::app", "Redo applied");
-                        self.status_message = Some("Redo".to_string());
+            Message::ConfirmExport => {
+                let Some(preview) = &mut self.code_preview else {
+                    return Task::none();
+                };
+                match preview.review.take_if(|review| review.can_confirm()) {
+                    Some(review) if review.close_after => self.code_preview = None,
+                    Some(_) => {}
+                    None => return Task::none(),
+                }
+                self.export_code()
+            }
+
+            Message::CancelExport => {
+                if let Some(preview) = &mut self.code_preview {
+                    if preview.review.take().is_some_and(|review| review.close_after) {
+                        self.code_preview = None;
                     }
                 }
+                self.status_message = Some(StatusMessage::info("Export cancelled".to_string()));
                 Task::none()
             }
 
-            Message::SetMode(mode) => {
-                tracing::debug!(target: "iced_builder::app", ?mode, "Mode changed");
-                self.mode = mode;
-                self.status_message = Some(format!("Mode: {:?}", mode));
+            Message::AcknowledgeOverwrite(acknowledged) => {
+                if let Some(review) = self.code_preview.as_mut().and_then(|p| p.review.as_mut()) {
+                    review.acknowledged = acknowledged;
+                }
                 Task::none()
             }
 
-            Message::TogglePreviewMode => {
-                self.mode = match self.mode {
-                    EditorMode::Design => {
-                        tracing::info!(target: "iced_builder::app", "Switching to Preview mode");
-                        self.status_message = Some("Preview mode - widgets are interactive".to_string());
-                        EditorMode::Preview
-                    }
-                    EditorMode::Preview => {
-                        tracing::info!(target: "iced_builder::app", "Switching to Design mode");
-                        self.status_message = Some("Design mode - click to select widgets".to_string());
-                        EditorMode::Design
-                    }
+            Message::ToggleCodePanel => {
+                if self.code_preview.take().is_some() {
+                    return Task::none();
+                }
+                let Some(project) = self.active_project() else {
+                    self.status_message = Some(StatusMessage::info("No project open".to_string()));
+                    return Task::none();
                 };
-                Task::none()
+                self.code_preview = Some(CodePreview::new(project.config.format_output));
+                self.regenerate_code_preview()
             }
 
-            Message::UpdateTextContent(id, content) => {
-                tracing::debug!(target: "iced_builder::ui::inspector", %id, "Updating text content");
-                self.update_node_property(id, |node| {
-                    if let crate::model::layout::WidgetType::Text { content: c, .. } = &mut node.widget {
-                        *c = content;
-                    }
-                });
-                Task::none()
+            Message::ExportExample => {
+                let Some(project) = self.active_project() else {
+                    self.status_message = Some(StatusMessage::info("No project open".to_string()));
+                    return Task::none();
+                };
+                let blockers = project.export_blockers();
+                if !blockers.is_empty() {
+                    return self.report_validation_failure("Example export", &blockers);
+                }
+                let snapshot = project.snapshot();
+                let title = "Example export failed";
+                Task::perform(
+                    in_background(title, project.path.clone(), move || {
+                        snapshot
+                            .export_example()
+                            .map_err(|e| DialogState::error(title, Some(&snapshot.path), &e))
+                    }),
+                    Message::ExampleExported,
+                )
             }
 
-            Message::UpdateButtonLabel(id, label) => {
-                tracing::debug!(target: "iced_builder::ui::inspector", %id, "Updating button label");
-                self.update_node_property(id, |node| {
-                    if let crate::model::layout::WidgetType::Button { label: l, .. } = &mut node.widget {
-                        *l = label;
+            Message::ExampleExported(result) => {
+                match result {
+                    Ok(path) => {
+                        let name = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+                        self.status_message = Some(StatusMessage::success(format!(
+                            "Example written to {} (cargo run --example {})",
+                            path.display(),
+                            name
+                        )));
                     }
-                });
+                    Err(dialog) => {
+                        tracing::error!(target: targets::CODEGEN, error = %dialog.chain[0], "Example export failed");
+                        self.show_dialog(dialog);
+                    }
+                }
                 Task::none()
             }
 
-            Message::UpdateMessageStub(id, stub) => {
-                tracing::debug!(target: "iced_builder::ui::inspector", %id, "Updating message stub");
-                self.update_node_property(id, |node| {
-                    match &mut node.widget {
-                        crate::model::layout::WidgetType::Button { message_stub, .. } => *message_stub = stub,
-                        crate::model::layout::WidgetType::TextInput { message_stub, .. } => *message_stub = stub,
-                        crate::model::layout::WidgetType::Checkbox { message_stub, .. } => *message_stub = stub,
-                        crate::model::layout::WidgetType::Slider { message_stub, .. } => *message_stub = stub,
-                        crate::model::layout::WidgetType::PickList { message_stub, .. } => *message_stub = stub,
-                        _ => {}
-                    }
-                });
+            Message::ToggleExportMode => {
+                let Some(project) = self.projects.get_mut(self.active) else {
+                    return Task::none();
+                };
+                project.config.export_mode = match project.config.export_mode {
+                    ExportMode::ViewOnly => ExportMode::Component,
+                    ExportMode::Component => ExportMode::ViewOnly,
+                };
+                project.mark_config_dirty();
+                // The preview follows the new mode
+                if let Some(preview) = &mut self.code_preview {
+                    preview.revision = None;
+                }
+                self.status_message = Some(StatusMessage::info(match project.config.export_mode {
+                    ExportMode::ViewOnly => "Export mode: view function".to_string(),
+                    ExportMode::Component => "Export mode: component module".to_string(),
+                }));
                 Task::none()
             }
 
-            Message::UpdatePlaceholder(id, placeholder) => {
-                self.update_node_property(id, |node| {
-                    if let crate::model::layout::WidgetType::TextInput { placeholder: p, .. } = &mut node.widget {
-                        *p = placeholder;
-                    }
-                });
-                Task::none()
+            Message::CodePreviewDue(revision) => {
+                let Some(preview) = &mut self.code_preview else {
+                    return Task::none();
+                };
+                preview.scheduled = None;
+                // Edited again while waiting; the next schedule waits for that edit
+                if self.active_project().map(|p| p.revision) != Some(revision) {
+                    return Task::none();
+                }
+                self.regenerate_code_preview()
             }
 
-            Message::UpdateBinding(id, binding) => {
-                self.update_node_property(id, |node| {
-                    match &mut node.widget {
-                        crate::model::layout::WidgetType::TextInput { value_binding, .. } => *value_binding = binding,
-                        crate::model::layout::WidgetType::Checkbox { checked_binding, .. } => *checked_binding = binding,
-                        crate::model::layout::WidgetType::Slider { value_binding, .. } => *value_binding = binding,
-                        crate::model::layout::WidgetType::PickList { selected_binding, .. } => *selected_binding = binding,
-                        _ => {}
+            Message::CodePreviewReady(revision, result) => {
+                if let Some(preview) = self.code_preview.as_mut().filter(|p| p.revision == Some(revision)) {
+                    // A broken template keeps the last good code on screen
+                    match result {
+                        Ok(code) => preview.code = code,
+                        Err(e) => self.status_message = Some(StatusMessage::error(format!("Template error: {}", e))),
                     }
-                });
+                }
                 Task::none()
             }
 
-            Message::UpdatePadding(id, padding) => {
-                self.update_node_property(id, |node| {
-                    match &mut node.widget {
-                        crate::model::layout::WidgetType::Column { attrs, .. }
-                        | crate::model::layout::WidgetType::Row { attrs, .. }
-                        | crate::model::layout::WidgetType::Container { attrs, .. }
-                        | crate::model::layout::WidgetType::Scrollable { attrs, .. }
-                        | crate::model::layout::WidgetType::Stack { attrs, .. } => {
-                            attrs.padding = crate::model::layout::PaddingSpec {
-                                top: padding,
-                                right: padding,
-                                bottom: padding,
-                                left: padding,
-                            };
-                        }
-                        _ => {}
-                    }
-                });
-                Task::none()
+            Message::ToggleCodeFormat(format) => {
+                if let Some(preview) = &mut self.code_preview {
+                    preview.format = format;
+                }
+                self.regenerate_code_preview()
             }
 
-            Message::UpdateSpacing(id, spacing) => {
-                self.update_node_property(id, |node| {
-                    match &mut node.widget {
-                        crate::model::layout::WidgetType::Column { attrs, .. }
-                        | crate::model::layout::WidgetType::Row { attrs, .. }
-                        | crate::model::layout::WidgetType::Container { attrs, .. }
-                        | crate::model::layout::WidgetType::Scrollable { attrs, .. }
-                        | crate::model::layout::WidgetType::Stack { attrs, .. } => {
-                            attrs.spacing = spacing;
+            Message::CopyGeneratedCode => match &self.code_preview {
+                Some(preview) if !preview.code.is_empty() => {
+                    self.status_message = Some(StatusMessage::success("Generated code copied".to_string()));
+                    iced::clipboard::write(preview.code.clone())
+                }
+                _ => Task::none(),
+            },
+
+            Message::ProjectOpened(result) => {
+                let path = match &result {
+                    Ok(project) => Some(&project.path),
+                    Err(dialog) => dialog.path.as_ref(),
+                };
+                self.loading.retain(|loading| Some(loading) != path);
+                match result {
+                    Ok(project) => {
+                        // Opening a project that already has a tab just shows it
+                        if let Some(index) = self.projects.iter().position(|p| p.path == project.path) {
+                            self.select_tab(index);
+                            self.status_message = Some(StatusMessage::info(format!(
+                                "{} is already open",
+                                project.path.display()
+                            )));
+                            return Task::none();
+                        }
+                        tracing::info!(target: targets::APP, name = %project.layout.name, "Project opened");
+                        let mut project = *project;
+                        project.history.set_limit(self.settings.history_limit);
+                        let crashed = project.has_crash_recovery();
+                        let recoverable = project.has_recoverable_autosave();
+                        self.open_tab(project);
+                        self.remember_project();
+                        self.status_message = Some(StatusMessage::success("Project opened".to_string()));
+                        self.report_load_warnings();
+                        self.report_config_issues();
+                        // The crash recovery file is at least as new as any autosave
+                        if crashed {
+                            return Task::perform(
+                                async {
+                                    rfd::AsyncMessageDialog::new()
+                                        .set_title("Restore after crash?")
+                                        .set_description(
+                                            "Iced Builder crashed while this project had unsaved changes. \
+                                             Restore them?",
+                                        )
+                                        .set_level(rfd::MessageLevel::Warning)
+                                        .set_buttons(rfd::MessageButtons::YesNo)
+                                        .show()
+                                        .await
+                                },
+                                |result| Message::RecoverCrash(result == rfd::MessageDialogResult::Yes),
+                            );
+                        }
+                        if recoverable {
+                            return Task::perform(
+                                async {
+                                    rfd::AsyncMessageDialog::new()
+                                        .set_title("Recover unsaved changes?")
+                                        .set_description(
+                                            "This project has autosaved changes that were never saved, \
+                                             probably because Iced Builder closed unexpectedly. \
+                                             Recover them?",
+                                        )
+                                        .set_level(rfd::MessageLevel::Warning)
+                                        .set_buttons(rfd::MessageButtons::YesNo)
+                                        .show()
+                                        .await
+                                },
+                                |result| Message::RecoverAutosave(result == rfd::MessageDialogResult::Yes),
+                            );
                         }
-                        _ => {}
                     }
-                });
+                    Err(dialog) => {
+                        tracing::error!(target: targets::APP, error = %dialog.chain[0], "Failed to open project");
+                        self.show_dialog(dialog);
+                    }
+                }
                 Task::none()
             }
 
-            Message::UpdateWidth(id, width) => {
-                self.update_node_property(id, |node| {
-                    match &mut node.widget {
-                        crate::model::layout::WidgetType::Column { attrs, .. }
-                        | crate::model::layout::WidgetType::Row { attrs, .. }
-                        | crate::model::layout::WidgetType::Container { attrs, .. }
-                        | crate::model::layout::WidgetType::Scrollable { attrs, .. }
-                        | crate::model::layout::WidgetType::Stack { attrs, .. } => {
-                            attrs.width = width;
+            Message::AutosaveDue => {
+                self.autosave_scheduled = false;
+                for project in self.projects.iter().filter(|p| p.dirty) {
+                    if self.autosaved_revisions.get(&project.path) == Some(&project.revision) {
+                        continue;
+                    }
+                    match project.autosave() {
+                        Ok(()) => {
+                            tracing::debug!(target: targets::IO, path = %project.path.display(), revision = project.revision, "Autosaved layout");
+                            self.autosaved_revisions.insert(project.path.clone(), project.revision);
+                        }
+                        Err(e) => {
+                            tracing::warn!(target: targets::IO, path = %project.path.display(), error = %e, "Autosave failed");
+                            self.status_message = Some(StatusMessage::error(format!("Autosave of \"{}\" failed: {}", project.layout.name, e)));
                         }
-                        _ => {}
                     }
-                });
+                }
                 Task::none()
             }
 
-            Message::UpdateHeight(id, height) => {
-                self.update_node_property(id, |node| {
-                    match &mut node.widget {
-                        crate::model::layout::WidgetType::Column { attrs, .. }
-                        | crate::model::layout::WidgetType::Row { attrs, .. }
-                        | crate::model::layout::WidgetType::Container { attrs, .. }
-                        | crate::model::layout::WidgetType::Scrollable { attrs, .. }
-                        | crate::model::layout::WidgetType::Stack { attrs, .. } => {
-                            attrs.height = height;
-                        }
-                        _ => {}
+            Message::RecoverAutosave(accepted) => {
+                let Some(project) = self.active_project_mut() else {
+                    return Task::none();
+                };
+                if !accepted {
+                    project.discard_autosave();
+                    self.status_message = Some(StatusMessage::info("Discarded autosaved changes".to_string()));
+                    return Task::none();
+                }
+                match project.recover_autosave() {
+                    Ok(()) => {
+                        self.status_message = Some(StatusMessage::success("Recovered unsaved changes; save to keep them".to_string()));
                     }
-                });
+                    Err(e) => {
+                        tracing::error!(target: targets::APP, error = %e, "Failed to recover autosave");
+                        self.status_message = Some(StatusMessage::error(format!("Failed to recover autosave: {}", e)));
+                    }
+                }
                 Task::none()
             }
 
-            Message::UpdateAlignX(id, align_x) => {
-                self.update_node_property(id, |node| {
-                    match &mut node.widget {
-                        crate::model::layout::WidgetType::Column { attrs, .. }
-                        | crate::model::layout::WidgetType::Row { attrs, .. }
-                        | crate::model::layout::WidgetType::Container { attrs, .. }
-                        | crate::model::layout::WidgetType::Scrollable { attrs, .. }
-                        | crate::model::layout::WidgetType::Stack { attrs, .. } => {
-                            attrs.align_x = align_x;
-                        }
-                        _ => {}
+            Message::RecoverCrash(accepted) => {
+                let Some(project) = self.active_project_mut() else {
+                    return Task::none();
+                };
+                if !accepted {
+                    project.discard_crash_recovery();
+                    project.discard_autosave();
+                    self.status_message = Some(StatusMessage::info("Discarded unsaved changes".to_string()));
+                    return Task::none();
+                }
+                match project.recover_crash() {
+                    Ok(()) => {
+                        self.status_message = Some(StatusMessage::success("Restored unsaved changes; save to keep them".to_string()));
                     }
-                });
+                    Err(e) => {
+                        tracing::error!(target: targets::APP, error = %e, "Failed to restore after crash");
+                        self.status_message = Some(StatusMessage::error(format!("Failed to restore after crash: {}", e)));
+                    }
+                }
                 Task::none()
             }
 
-            Message::UpdateAlignY(id, align_y) => {
-                self.update_node_property(id, |node| {
-                    match &mut node.widget {
-                        crate::model::layout::WidgetType::Column { attrs, .. }
-                        | crate::model::layout::WidgetType::Row { attrs, .. }
-                        | crate::model::layout::WidgetType::Container { attrs, .. }
-                        | crate::model::layout::WidgetType::Scrollable { attrs, .. }
-                        | crate::model::layout::WidgetType::Stack { attrs, .. } => {
-                            attrs.align_y = align_y;
+            Message::LayoutFilePoll => {
+                // Clean background tabs reload quietly; dirty ones ask once shown
+                for (index, project) in self.projects.iter_mut().enumerate() {
+                    if index == self.active || project.dirty || !project.layout_changed_on_disk() {
+                        continue;
+                    }
+                    let file = project.config.layout_file().display().to_string();
+                    match project.reload_layout() {
+                        Ok(()) => {
+                            tracing::info!(target: targets::IO, path = %project.path.display(), "Reloaded background tab after an external change");
+                            self.status_message = Some(StatusMessage::info(format!("Reloaded {} in \"{}\" after it changed on disk", file, project.layout.name)));
+                        }
+                        Err(e) => {
+                            project.keep_local_layout();
+                            tracing::warn!(target: targets::IO, path = %project.path.display(), error = %e, "Failed to reload layout");
                         }
-                        _ => {}
                     }
-                });
+                }
+                if self.active_project().is_some_and(Project::layout_changed_on_disk) {
+                    return self.handle_external_change();
+                }
                 Task::none()
             }
 
-            Message::UpdateFontSize(id, font_size) => {
-                self.update_node_property(id, |node| {
-                    if let crate::model::layout::WidgetType::Text { attrs, .. } = &mut node.widget {
-                        attrs.font_size = font_size;
+            Message::ExternalChangeChosen(choice) => {
+                self.external_change_prompt = false;
+                let Some(project) = self.active_project_mut() else {
+                    return Task::none();
+                };
+                tracing::info!(target: targets::APP, ?choice, "Resolving external layout change");
+                match choice {
+                    ExternalChangeChoice::Reload => {
+                        self.reload_layout();
                     }
-                });
-                Task::none()
-            }
-
-            Message::UpdateTextColor(id, color) => {
-                self.update_node_property(id, |node| {
-                    if let crate::model::layout::WidgetType::Text { attrs, .. } = &mut node.widget {
-                        attrs.color = color;
+                    ExternalChangeChoice::KeepMine => {
+                        project.keep_local_layout();
+                        self.status_message = Some(StatusMessage::info("Kept your changes; saving will overwrite the file on disk".to_string()));
                     }
-                });
+                    ExternalChangeChoice::SaveAs => {
+                        let directory = project.path.clone();
+                        let file_name = project
+                            .config
+                            .layout_file()
+                            .file_name()
+                            .map(|name| name.to_string_lossy().into_owned())
+                            .unwrap_or_default();
+                        return Task::perform(
+                            async move {
+                                rfd::AsyncFileDialog::new()
+                                    .set_title("Save your layout as")
+                                    .set_directory(directory)
+                                    .set_file_name(file_name)
+                                    .add_filter("Layout", &["ron", "json"])
+                                    .save_file()
+                                    .await
+                                    .map(|file| file.path().to_path_buf())
+                            },
+                            Message::SaveLayoutCopyTo,
+                        );
+                    }
+                }
                 Task::none()
             }
 
-            Message::UpdateCheckboxLabel(id, label) => {
-                self.update_node_property(id, |node| {
-                    if let crate::model::layout::WidgetType::Checkbox { label: l, .. } = &mut node.widget {
-                        *l = label;
+            Message::SaveLayoutCopyTo(path) => {
+                let Some(project) = self.active_project_mut() else {
+                    return Task::none();
+                };
+                let Some(path) = path else {
+                    // Cancelling the file dialog keeps the edits in memory
+                    project.keep_local_layout();
+                    self.status_message = Some(StatusMessage::info("Kept your changes; saving will overwrite the file on disk".to_string()));
+                    return Task::none();
+                };
+                if path == project.layout_path() {
+                    self.status_message = Some(StatusMessage::info("Choose a different file to keep both versions".to_string()));
+                    return self.update(Message::ExternalChangeChosen(ExternalChangeChoice::SaveAs));
+                }
+                match crate::io::save_layout(&path, &project.layout) {
+                    Ok(()) => {
+                        if self.reload_layout() {
+                            self.status_message = Some(StatusMessage::success(format!("Saved your changes to {} and reloaded the layout", path.display())));
+                        }
                     }
-                });
+                    Err(e) => {
+                        tracing::error!(target: targets::IO, error = %e, "Failed to save layout copy");
+                        self.show_error("Failed to save layout", Some(&path), &e);
+                    }
+                }
                 Task::none()
             }
 
-            Message::UpdateSliderRange(id, min, max) => {
-                self.update_node_property(id, |node| {
-                    if let crate::model::layout::WidgetType::Slider { min: m, max: mx, .. } = &mut node.widget {
-                        *m = min;
-                        *mx = max;
-                    }
-                });
-                Task::none()
+            Message::SelectComponent(id) => {
+                self.select_component(id);
+                self.scroll_canvas_to_selection()
             }
 
-            Message::Noop => Task::none(),
+            Message::DeselectComponent => {
+                if self.pending_dialog.take().is_some() {
+                    return Task::none();
+                }
+                if self.template_picker.take().is_some() {
+                    return Task::none();
+                }
+                if self.delete_confirm.take().is_some() {
+                    return Task::none();
+                }
+                if self.open_menu.take().is_some() {
+                    return Task::none();
+                }
+                if self.context_menu.take().is_some() {
+                    return Task::none();
+                }
+                // Escape cancels an inline edit before it clears the selection
+                if self.inline_edit.is_some() {
+                    return self.update(Message::CancelInlineEdit);
+                }
+                if self.pending_insert.take().is_some() {
+                    self.status_message = Some(StatusMessage::info("Insert cancelled".to_string()));
+                    return Task::none();
+                }
+                if self.palette_drag.take().is_some() {
+                    self.status_message = Some(StatusMessage::info("Drag cancelled".to_string()));
+                    return Task::none();
+                }
+                if self.tree.drag.take().is_some() {
+                    self.status_message = Some(StatusMessage::info("Move cancelled".to_string()));
+                    return Task::none();
+                }
+                if self.code_preview.as_ref().is_some_and(|p| p.review.is_some()) {
+                    return self.update(Message::CancelExport);
+                }
+                if self.code_preview.take().is_some() {
+                    return Task::none();
+                }
+                if self.show_stats {
+                    self.show_stats = false;
+                    return Task::none();
+                }
+                if self.backup_list.take().is_some() {
+                    return Task::none();
+                }
+                if self.show_recent {
+                    self.show_recent = false;
+                    return Task::none();
+                }
+                if self.show_load_warnings {
+                    self.show_load_warnings = false;
+                    return Task::none();
+                }
+                if self.show_full_status {
+                    self.show_full_status = false;
+                    return Task::none();
+                }
+                if self.focus_preview.is_some() {
+                    return self.update(Message::ToggleFocusPreview);
+                }
+                tracing::debug!(target: targets::SELECTION, "Component deselected");
+                if let Some(project) = self.active_project_mut() {
+                    project.selected_id = None;
+                }
+                Task::none()
+            }
+
+            Message::TreeDragStart(id) => {
+                self.tree.drag = Some(TreeDrag { source: id, target: None });
+                self.tree.focused = true;
+                self.update(Message::SelectComponent(id))
+            }
+
+            Message::TreeDragOver(id, position) => {
+                if let Some(drag) = &mut self.tree.drag {
+                    drag.target = (id != drag.source).then_some((id, position));
+                }
+                Task::none()
+            }
+
+            Message::TreeDragEnd => {
+                let Some(TreeDrag { source, target: Some((target, position)) }) = self.tree.drag.take()
+                else {
+                    // Released without moving onto another row: a plain click
+                    return Task::none();
+                };
+                let Some(project) = self.active_project() else {
+                    return Task::none();
+                };
+
+                let destination = match position {
+                    DropPosition::Inside => Some((target, usize::MAX)),
+                    DropPosition::Before => project.parent_of(target),
+                    DropPosition::After => project.parent_of(target).map(|(parent, index)| (parent, index + 1)),
+                };
+
+                match destination {
+                    Some((parent, index)) => self.update(Message::MoveNode(source, parent, index)),
+                    None => {
+                        tracing::debug!(target: targets::TREE, id = %source, %target, ?position, "Move rejected");
+                        self.status_message = Some(StatusMessage::error("Cannot move component here".to_string()));
+                        Task::none()
+                    }
+                }
+            }
+
+            Message::MoveNode(id, parent, index) => {
+                let Some(project) = self.active_project_mut() else {
+                    return Task::none();
+                };
+
+                // move_node pushes history itself and leaves the layout untouched on failure
+                if project.move_node(id, parent, index) {
+                    project.mark_dirty();
+                    tracing::info!(target: targets::TREE, %id, %parent, index, "Component moved");
+                    self.status_message = Some(StatusMessage::success("Component moved".to_string()));
+                    self.reveal_selection()
+                } else {
+                    tracing::debug!(target: targets::TREE, %id, %parent, index, "Move rejected");
+                    self.status_message = Some(StatusMessage::error("Cannot move component here".to_string()));
+                    Task::none()
+                }
+            }
+
+            Message::FocusTree => {
+                self.tree.focused = true;
+                Task::none()
+            }
+
+            Message::ToggleTreeNode(id) => {
+                self.tree.toggle(id);
+                Task::none()
+            }
+
+            Message::TreeScrolled(viewport) => {
+                self.tree.viewport = Some((viewport.absolute_offset().y, viewport.bounds().height));
+                Task::none()
+            }
+
+            Message::ExpandAllTree => {
+                self.tree.expand_all();
+                self.scroll_tree_to_selection()
+            }
+
+            Message::CollapseAllTree => {
+                if let Some(project) = self.projects.get(self.active) {
+                    self.tree.collapse_all(&project.layout.root);
+                }
+                Task::none()
+            }
+
+            Message::SplitterPressed(split) => {
+                self.splitter_drag = Some(SplitterDrag { split, anchor: None });
+                Task::none()
+            }
+
+            Message::SplitterDragged(position) => {
+                let Some(drag) = &mut self.splitter_drag else {
+                    return Task::none();
+                };
+                let current = match drag.split {
+                    Split::TreeHeight => self.settings.tree_height,
+                };
+                // The press carries no position, so the first move anchors the drag
+                let (anchor, start) = *drag.anchor.get_or_insert((position, current));
+                let size = drag.split.resize(start, anchor, position);
+                match drag.split {
+                    Split::TreeHeight => self.settings.tree_height = size,
+                }
+                Task::none()
+            }
+
+            Message::SplitterReleased => {
+                if let Some(drag) = self.splitter_drag.take() {
+                    tracing::debug!(target: targets::APP, split = ?drag.split, "Panel resized");
+                    self.save_settings();
+                }
+                Task::none()
+            }
+
+            // Arrow keys move the text cursor while editing inline
+            Message::SelectNext
+            | Message::SelectPrevious
+            | Message::SelectParent
+            | Message::SelectFirstChild
+            | Message::SelectFirstRow
+            | Message::SelectLastRow
+                if self.inline_edit.is_some() =>
+            {
+                Task::none()
+            }
+
+            // With tree focus, arrows move through the visible rows instead of siblings
+            Message::SelectNext if self.tree.focused => {
+                self.select_tree_row(|rows, current| current.map_or(0, |i| (i + 1).min(rows - 1)))
+            }
+
+            Message::SelectPrevious if self.tree.focused => {
+                self.select_tree_row(|_, current| current.map_or(0, |i| i.saturating_sub(1)))
+            }
+
+            Message::SelectParent if self.tree.focused => {
+                let Some(project) = self.active_project() else {
+                    return Task::none();
+                };
+                let Some(id) = project.selected_id else {
+                    return Task::none();
+                };
+                let has_children = project
+                    .find_node(id)
+                    .is_some_and(|node| !node.child_nodes().is_empty());
+                if has_children && !self.tree.is_collapsed(id) {
+                    self.tree.toggle(id);
+                    return Task::none();
+                }
+                match project.parent_of(id) {
+                    Some((parent, _)) => Task::batch([
+                        self.update(Message::SelectComponent(parent)),
+                        self.scroll_tree_to_selection(),
+                    ]),
+                    None => Task::none(),
+                }
+            }
+
+            Message::SelectFirstChild if self.tree.focused => {
+                let Some(project) = self.active_project() else {
+                    return Task::none();
+                };
+                let Some(id) = project.selected_id else {
+                    return Task::none();
+                };
+                let first_child = project
+                    .find_node(id)
+                    .and_then(|node| node.child_nodes().first().map(|child| child.id));
+                match first_child {
+                    Some(_) if self.tree.is_collapsed(id) => {
+                        self.tree.toggle(id);
+                        Task::none()
+                    }
+                    Some(child) => Task::batch([
+                        self.update(Message::SelectComponent(child)),
+                        self.scroll_tree_to_selection(),
+                    ]),
+                    None => Task::none(),
+                }
+            }
+
+            Message::SelectFirstRow if self.tree.focused => self.select_tree_row(|_, _| 0),
+
+            Message::SelectLastRow if self.tree.focused => self.select_tree_row(|rows, _| rows - 1),
+
+            Message::SelectFirstRow | Message::SelectLastRow => Task::none(),
+
+            Message::SelectNext => {
+                if let Some(project) = self.active_project_mut() {
+                    if let Some(next_id) = project.get_next_sibling() {
+                        project.selected_id = Some(next_id);
+                        self.status_message = Some(StatusMessage::info("Selected next sibling".to_string()));
+                    }
+                }
+                self.reveal_selection()
+            }
+
+            Message::SelectPrevious => {
+                if let Some(project) = self.active_project_mut() {
+                    if let Some(prev_id) = project.get_previous_sibling() {
+                        project.selected_id = Some(prev_id);
+                        self.status_message = Some(StatusMessage::info("Selected previous sibling".to_string()));
+                    }
+                }
+                self.reveal_selection()
+            }
+
+            Message::SelectParent => {
+                if let Some(project) = self.active_project_mut() {
+                    if let Some(parent_id) = project.get_parent() {
+                        project.selected_id = Some(parent_id);
+                        self.status_message = Some(StatusMessage::info("Selected parent".to_string()));
+                    }
+                }
+                self.reveal_selection()
+            }
+
+            Message::SelectFirstChild => {
+                if let Some(project) = self.active_project_mut() {
+                    if let Some(child_id) = project.get_first_child() {
+                        project.selected_id = Some(child_id);
+                        self.status_message = Some(StatusMessage::info("Selected first child".to_string()));
+                    }
+                }
+                self.reveal_selection()
+            }
+
+            Message::CanvasClicked(id) if self.pending_insert.is_some() => self.drop_pending_insert(id),
+
+            Message::CanvasClicked(id) => {
+                let now = Instant::now();
+                let is_double_click = matches!(
+                    self.last_canvas_click,
+                    Some((last_id, at)) if last_id == id && now.duration_since(at) <= DOUBLE_CLICK_INTERVAL
+                );
+                self.last_canvas_click = Some((id, now));
+
+                self.tree.focused = false;
+
+                // Clicking elsewhere commits any edit in progress
+                let commit = match &self.inline_edit {
+                    Some(edit) if edit.id != id => self.update(Message::CommitInlineEdit),
+                    _ => Task::none(),
+                };
+                self.select_component(id);
+                let select = self.scroll_tree_to_selection();
+
+                if !is_double_click || self.mode != EditorMode::Design {
+                    return Task::batch([commit, select]);
+                }
+
+                let draft = self
+                    .active_project()
+                    .and_then(|project| project.find_node(id))
+                    .and_then(|node| match &node.widget {
+                        crate::model::layout::WidgetType::Text { content, .. } => Some(content.clone()),
+                        crate::model::layout::WidgetType::Button { label, .. } => Some(label.clone()),
+                        _ => None,
+                    });
+
+                match draft {
+                    Some(draft) => {
+                        tracing::debug!(target: targets::APP, %id, "Starting inline edit");
+                        self.inline_edit = Some(InlineEdit { id, draft });
+                        self.last_canvas_click = None;
+                        let focus = text_input::focus(inline_edit_input_id());
+                        let select_all = text_input::select_all(inline_edit_input_id());
+                        Task::batch([commit, select, focus, select_all])
+                    }
+                    None => Task::batch([commit, select]),
+                }
+            }
+
+            Message::InlineEditChanged(value) => {
+                if let Some(edit) = &mut self.inline_edit {
+                    edit.draft = value;
+                }
+                Task::none()
+            }
+
+            Message::CommitInlineEdit => {
+                let Some(InlineEdit { id, draft }) = self.inline_edit.take() else {
+                    return Task::none();
+                };
+                tracing::debug!(target: targets::APP, %id, "Committing inline edit");
+
+                let node = self.active_project().and_then(|project| project.find_node(id));
+                match node.map(|node| &node.widget) {
+                    Some(crate::model::layout::WidgetType::Text { content, .. }) if *content != draft => {
+                        self.update(Message::UpdateTextContent(id, draft))
+                    }
+                    Some(crate::model::layout::WidgetType::Button { label, .. }) if *label != draft => {
+                        self.update(Message::UpdateButtonLabel(id, draft))
+                    }
+                    _ => Task::none(),
+                }
+            }
+
+            Message::CancelInlineEdit => {
+                if self.inline_edit.take().is_some() {
+                    tracing::debug!(target: targets::APP, "Inline edit cancelled");
+                    self.status_message = Some(StatusMessage::info("Edit cancelled".to_string()));
+                }
+                Task::none()
+            }
+
+            Message::PaletteItemClicked(kind) => {
+                self.tree.focused = false;
+
+                // A second click on the pending item adds it to the selection or root;
+                // sibling insertion does not need a target, so it adds right away
+                let beside_selection = self.effective_insert_mode() != InsertMode::Inside
+                    && self.active_project().is_some_and(|p| p.selected_id.is_some());
+                if self.pending_insert == Some(kind) || beside_selection {
+                    self.pending_insert = None;
+                    self.add_widget(kind, None);
+                } else {
+                    tracing::debug!(target: targets::TREE, ?kind, "Insert pending");
+                    self.pending_insert = Some(kind);
+                    self.status_message = Some(StatusMessage::info(format!(
+                        "Click a highlighted container to add {} (click again to add to selection, Esc to cancel)",
+                        kind.name()
+                    )));
+                }
+                Task::none()
+            }
+
+            Message::PaletteDragStart(kind) => {
+                self.palette_drag = Some(PaletteDrag::new(kind));
+                Task::none()
+            }
+
+            Message::PaletteDragMoved(position) => {
+                if let Some(drag) = &mut self.palette_drag {
+                    drag.move_to(position);
+                }
+                Task::none()
+            }
+
+            Message::PaletteDropOn(id) => {
+                // Drop targets report before the global release ends the drag
+                match self.palette_drag.take() {
+                    Some(drag) if drag.is_dragging() => {
+                        tracing::debug!(target: targets::TREE, kind = ?drag.kind, %id, "Palette item dropped");
+                        self.pending_insert = Some(drag.kind);
+                        self.drop_pending_insert(id)
+                    }
+                    other => {
+                        self.palette_drag = other;
+                        Task::none()
+                    }
+                }
+            }
+
+            Message::PaletteDragEnd => match self.palette_drag.take() {
+                Some(drag) if drag.is_dragging() => {
+                    self.status_message = Some(StatusMessage::info("Drop onto the canvas or a tree row to add a widget".to_string()));
+                    Task::none()
+                }
+                Some(drag) => self.update(Message::PaletteItemClicked(drag.kind)),
+                None => Task::none(),
+            },
+
+            Message::SetInsertMode(mode) => {
+                tracing::debug!(target: targets::APP, ?mode, "Insert mode changed");
+                self.insert_mode = mode;
+                Task::none()
+            }
+
+            Message::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers;
+                Task::none()
+            }
+
+            Message::TogglePaletteSection(title) => {
+                let collapsed = &mut self.settings.collapsed_palette_sections;
+                if !collapsed.remove(&title) {
+                    collapsed.insert(title);
+                }
+                self.save_settings();
+                Task::none()
+            }
+
+            Message::PaletteHovered(kind) => {
+                self.palette_hover = Some(kind);
+                Task::none()
+            }
+
+            Message::PaletteUnhovered(kind) => {
+                // Moving between items may report the new hover first
+                if self.palette_hover == Some(kind) {
+                    self.palette_hover = None;
+                }
+                Task::none()
+            }
+
+            Message::PaletteSearchChanged(query) => {
+                self.palette_query = query;
+                Task::none()
+            }
+
+            Message::PaletteSearchSubmitted => {
+                // Enter adds the first match to the selection or root
+                match self.palette_context().first_match() {
+                    Some(kind) => {
+                        self.pending_insert = None;
+                        self.add_widget(kind, None);
+                        self.palette_query.clear();
+                        self.reveal_selection()
+                    }
+                    None => Task::none(),
+                }
+            }
+
+            Message::SaveSelectionAsSnippet => {
+                let Some(project) = self.active_project() else {
+                    return Task::none();
+                };
+                let Some(node) = project.selected_id.and_then(|id| project.find_node(id)) else {
+                    self.status_message = Some(StatusMessage::info("Select a component to save as a snippet".to_string()));
+                    return Task::none();
+                };
+
+                // Suggest the widget type, numbered to stay unique
+                let base = node.widget.display_name();
+                let name = (1..)
+                    .map(|n| if n == 1 { base.to_string() } else { format!("{base} {n}") })
+                    .find(|name| !self.snippets.iter().any(|s| &s.name == name))
+                    .unwrap_or_default();
+                self.snippet_name = Some((node.id, name));
+                Task::batch([
+                    text_input::focus(Palette::snippet_name_id()),
+                    text_input::select_all(Palette::snippet_name_id()),
+                ])
+            }
+
+            Message::SnippetNameInput(draft) => {
+                if let Some((_, name)) = &mut self.snippet_name {
+                    *name = draft;
+                }
+                Task::none()
+            }
+
+            Message::CommitSnippetName => {
+                let Some((id, name)) = self.snippet_name.take() else {
+                    return Task::none();
+                };
+                let Some(project) = self.active_project() else {
+                    return Task::none();
+                };
+                let Some(node) = project.find_node(id) else {
+                    self.status_message = Some(StatusMessage::info("The component to save as a snippet was removed".to_string()));
+                    return Task::none();
+                };
+                match crate::io::save_snippet(project.project_path(), name.trim(), node) {
+                    Ok(snippet) => {
+                        self.status_message = Some(StatusMessage::success(format!("Saved snippet \"{}\"", snippet.name)));
+                        self.refresh_snippets();
+                    }
+                    Err(e) => {
+                        // Keep the name so it can be corrected
+                        tracing::warn!(target: targets::IO, error = %e, "Failed to save snippet");
+                        self.status_message = Some(StatusMessage::error(format!("Failed to save snippet: {}", e)));
+                        self.snippet_name = Some((id, name));
+                    }
+                }
+                Task::none()
+            }
+
+            Message::CancelSnippetName => {
+                self.snippet_name = None;
+                Task::none()
+            }
+
+            Message::ExtractComponent => {
+                let Some(project) = self.active_project() else {
+                    return Task::none();
+                };
+                let Some(id) = project.selected_id else {
+                    self.status_message = Some(StatusMessage::info("Select a component to extract".to_string()));
+                    return Task::none();
+                };
+                let layouts_dir = project.project_path().join("layouts");
+                Task::perform(
+                    async move {
+                        let file = rfd::AsyncFileDialog::new()
+                            .set_title("Extract component")
+                            .set_directory(&layouts_dir)
+                            .set_file_name("component.ron")
+                            .add_filter("RON layout", &["ron"])
+                            .save_file()
+                            .await;
+                        file.map(|f| f.path().to_path_buf())
+                    },
+                    move |path| match path {
+                        Some(path) => Message::ExtractComponentAs(id, path),
+                        None => Message::Noop,
+                    },
+                )
+            }
+
+            Message::ExtractComponentAs(id, path) => {
+                let Some(project) = self.active_project_mut() else {
+                    return Task::none();
+                };
+                // Components always live in layouts/, so only the chosen name is used
+                let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+                match project.extract_component(id, name) {
+                    Ok(reference_id) => {
+                        project.selected_id = Some(reference_id);
+                        project.mark_dirty();
+                        self.status_message = Some(StatusMessage::success(format!("Extracted component \"{}\"", name)));
+                        self.reveal_selection()
+                    }
+                    Err(e) => {
+                        tracing::warn!(target: targets::IO, error = %e, "Failed to extract component");
+                        self.status_message = Some(StatusMessage::error(e.to_string()));
+                        Task::none()
+                    }
+                }
+            }
+
+            Message::InsertSnippet(index) => {
+                let Some(snippet) = self.snippets.get(index) else {
+                    return Task::none();
+                };
+                match crate::io::load_snippet(&snippet.path) {
+                    Ok(node) => {
+                        let label = format!("snippet \"{}\"", snippet.name);
+                        self.insert_node(node, None, &label);
+                    }
+                    Err(e) => {
+                        tracing::warn!(target: targets::IO, error = %e, "Failed to load snippet");
+                        self.status_message = Some(StatusMessage::error(format!("Failed to load snippet: {}", e)));
+                    }
+                }
+                self.reveal_selection()
+            }
+
+            Message::StartRenameSnippet(index) => {
+                if let Some(snippet) = self.snippets.get(index) {
+                    self.snippet_rename = Some((index, snippet.name.clone()));
+                }
+                Task::none()
+            }
+
+            Message::SnippetRenameInput(draft) => {
+                if let Some((_, name)) = &mut self.snippet_rename {
+                    *name = draft;
+                }
+                Task::none()
+            }
+
+            Message::CommitSnippetRename => {
+                let Some((index, name)) = self.snippet_rename.take() else {
+                    return Task::none();
+                };
+                let Some(snippet) = self.snippets.get(index) else {
+                    return Task::none();
+                };
+                match crate::io::rename_snippet(snippet, name.trim()) {
+                    Ok(renamed) => {
+                        self.status_message = Some(StatusMessage::success(format!("Renamed snippet to \"{}\"", renamed.name)));
+                        self.refresh_snippets();
+                    }
+                    Err(e) => self.status_message = Some(StatusMessage::error(format!("Failed to rename snippet: {}", e))),
+                }
+                Task::none()
+            }
+
+            Message::DeleteSnippet(index) => {
+                let Some(snippet) = self.snippets.get(index) else {
+                    return Task::none();
+                };
+                match crate::io::delete_snippet(snippet) {
+                    Ok(()) => {
+                        self.status_message = Some(StatusMessage::success(format!("Deleted snippet \"{}\"", snippet.name)));
+                        self.snippet_rename = None;
+                        self.refresh_snippets();
+                    }
+                    Err(e) => self.status_message = Some(StatusMessage::error(format!("Failed to delete snippet: {}", e))),
+                }
+                Task::none()
+            }
+
+            Message::DeleteSelected => {
+                if self.delete_confirm.is_some() {
+                    return Task::none();
+                }
+                let Some(project) = self.active_project() else {
+                    return Task::none();
+                };
+                let Some(id) = project.selected_id else {
+                    return Task::none();
+                };
+                // The root can't be deleted, so there is nothing to confirm
+                let subtree = project
+                    .find_node(id)
+                    .filter(|node| node.id != project.layout.root.id)
+                    .map(|node| (node.widget.type_name(), node.descendant_count()));
+                match subtree {
+                    Some((type_name, descendants)) if descendants > 0 && self.settings.confirm_subtree_delete => {
+                        tracing::debug!(target: targets::TREE, %id, descendants, "Delete needs confirmation");
+                        self.delete_confirm = Some(DeleteConfirm {
+                            id,
+                            type_name,
+                            descendants,
+                            dont_ask: false,
+                        });
+                    }
+                    _ => self.delete_node(id),
+                }
+                Task::none()
+            }
+
+            Message::ConfirmDelete => {
+                if let Some(confirm) = self.delete_confirm.take() {
+                    if confirm.dont_ask {
+                        self.settings.confirm_subtree_delete = false;
+                        self.save_settings();
+                    }
+                    self.delete_node(confirm.id);
+                }
+                Task::none()
+            }
+
+            Message::CancelDelete => {
+                self.delete_confirm = None;
+                Task::none()
+            }
+
+            Message::ToggleDontAskDelete(dont_ask) => {
+                if let Some(confirm) = self.delete_confirm.as_mut() {
+                    confirm.dont_ask = dont_ask;
+                }
+                Task::none()
+            }
+
+            Message::SetConfirmSubtreeDelete(enabled) => {
+                self.settings.confirm_subtree_delete = enabled;
+                self.save_settings();
+                Task::none()
+            }
+
+            Message::DuplicateSelected => {
+                if let Some(project) = self.active_project_mut() {
+                    if project.selected_id.is_none() {
+                        self.status_message = Some(StatusMessage::info("Select a component to duplicate"));
+                    } else if project.selected_id == Some(project.layout.root.id) {
+                        self.status_message = Some(StatusMessage::error("The root cannot be duplicated"));
+                    } else if let Some(id) = project.selected_id {
+                        tracing::info!(target: targets::TREE, %id, "Duplicate requested");
+                        
+                        // Push history before modification
+                        let label = Self::action_label("Duplicate", project, id);
+                        project.history.push(project.layout.clone(), label);
+                        
+                        // Duplicate the selected node
+                        if let Some(new_id) = project.duplicate_node(id) {
+                            project.selected_id = Some(new_id);
+                            project.mark_dirty();
+                            tracing::info!(target: targets::TREE, old_id = %id, new_id = %new_id, "Component duplicated");
+                            self.status_message = Some(StatusMessage::success("Component duplicated".to_string()));
+                        } else {
+                            // Undo the history push if duplication failed
+                            let _ = project.history.undo(project.layout.clone());
+                            tracing::warn!(target: targets::TREE, %id, "Failed to duplicate component");
+                            self.status_message = Some(StatusMessage::error("Cannot duplicate this component".to_string()));
+                        }
+                    }
+                }
+                Task::none()
+            }
+
+            Message::ConvertWidget(id, kind) => {
+                let Some(project) = self.active_project_mut() else {
+                    return Task::none();
+                };
+
+                if project.convert_widget(id, kind) {
+                    project.mark_dirty();
+                    tracing::info!(target: targets::TREE, %id, ?kind, "Component converted");
+                    self.status_message = Some(StatusMessage::success(format!("Changed to {}", kind.name())));
+                } else {
+                    tracing::debug!(target: targets::TREE, %id, ?kind, "Conversion rejected");
+                    self.status_message = Some(StatusMessage::error(format!("Cannot change this component to {}", kind.name())));
+                }
+                Task::none()
+            }
+
+            Message::ChangeRootType(kind) => {
+                let Some(project) = self.active_project_mut() else {
+                    return Task::none();
+                };
+
+                if project.change_root_type(kind) {
+                    project.mark_dirty();
+                    tracing::info!(target: targets::TREE, ?kind, "Root type changed");
+                    self.status_message = Some(StatusMessage::success(format!("Root changed to {}", kind.name())));
+                } else {
+                    self.status_message = Some(StatusMessage::error(format!("Cannot change the root to {}", kind.name())));
+                }
+                Task::none()
+            }
+
+            Message::UpdateNodeName(id, name) => {
+                tracing::debug!(target: targets::INSPECTOR, %id, "Updating node name");
+                self.update_node_property(id, "Rename", |node| {
+                    node.name = (!name.trim().is_empty()).then_some(name);
+                });
+                Task::none()
+            }
+
+            Message::ToggleNodeHidden(id) => {
+                if self.active_project().is_some_and(|p| p.layout.root.id == id) {
+                    self.status_message = Some(StatusMessage::error("The root cannot be hidden".to_string()));
+                    return Task::none();
+                }
+                tracing::debug!(target: targets::INSPECTOR, %id, "Toggling node visibility");
+                self.update_node_property(id, "Toggle visibility", |node| node.hidden = !node.hidden);
+                Task::none()
+            }
+
+            Message::SetExcludeFromExport(id, exclude) => {
+                if self.active_project().is_some_and(|p| p.layout.root.id == id) {
+                    self.status_message = Some(StatusMessage::error("The root cannot be excluded from export".to_string()));
+                    return Task::none();
+                }
+                tracing::debug!(target: targets::INSPECTOR, %id, exclude, "Updating export exclusion");
+                self.update_node_property(id, "Change export exclusion", |node| node.exclude_from_export = exclude);
+                Task::none()
+            }
+
+            Message::FindComponent => {
+                self.tree.focused = false;
+                iced::widget::text_input::focus(TreeView::search_id())
+            }
+
+            Message::TreeSearchChanged(query) => {
+                self.tree.search = query;
+                Task::none()
+            }
+
+            Message::FindNext | Message::FindPrevious => {
+                let Some(project) = self.projects.get_mut(self.active) else {
+                    return Task::none();
+                };
+                let matches = project.find(&self.tree.search);
+                if matches.is_empty() {
+                    if !self.tree.search.trim().is_empty() {
+                        self.status_message = Some(StatusMessage::info(format!("No components match \"{}\"", self.tree.search.trim())));
+                    }
+                    return Task::none();
+                }
+
+                // Cycle from the selected match, wrapping at either end
+                let current = project
+                    .selected_id
+                    .and_then(|id| matches.iter().position(|m| *m == id));
+                let index = match (current, matches!(message, Message::FindPrevious)) {
+                    (Some(i), false) => (i + 1) % matches.len(),
+                    (Some(i), true) => (i + matches.len() - 1) % matches.len(),
+                    (None, false) => 0,
+                    (None, true) => matches.len() - 1,
+                };
+                project.selected_id = Some(matches[index]);
+                self.status_message = Some(StatusMessage::info(format!("Match {} of {}", index + 1, matches.len())));
+                self.reveal_selection()
+            }
+
+            Message::CopySelected => match self.encode_selection() {
+                Some(text) => {
+                    self.status_message = Some(StatusMessage::success("Copied to clipboard".to_string()));
+                    iced::clipboard::write(text)
+                }
+                None => Task::none(),
+            },
+
+            Message::CopySelectionAsCode => {
+                let Some(project) = self.active_project() else {
+                    return Task::none();
+                };
+                let Some(node) = project.selected_id.and_then(|id| project.find_node(id)) else {
+                    self.status_message = Some(StatusMessage::info("Select a component to copy".to_string()));
+                    return Task::none();
+                };
+                let code = crate::codegen::generate_node_code(&project.inlined_node(node), &project.config);
+                let code = crate::util::try_format_rust_expr(&code, project.format_backend(), &project.rustfmt_options());
+                tracing::debug!(target: targets::CODEGEN, node_id = %node.id, code_length = code.len(), "Copied selection as code");
+                self.status_message = Some(StatusMessage::success("Copied selection as code".to_string()));
+                iced::clipboard::write(code)
+            }
+
+            Message::CutSelected => {
+                let Some(text) = self.encode_selection() else {
+                    return Task::none();
+                };
+                let Some(project) = self.active_project_mut() else {
+                    return Task::none();
+                };
+                let Some(id) = project.selected_id else {
+                    return Task::none();
+                };
+
+                // Push history before modification
+                let label = Self::action_label("Cut", project, id);
+                project.history.push(project.layout.clone(), label);
+                if project.remove_node(id) {
+                    project.selected_id = None;
+                    project.mark_dirty();
+                    tracing::info!(target: targets::TREE, %id, "Component cut");
+                    self.status_message = Some(StatusMessage::success("Cut to clipboard".to_string()));
+                    iced::clipboard::write(text)
+                } else {
+                    let _ = project.history.undo(project.layout.clone());
+                    self.status_message = Some(StatusMessage::error("Cannot cut this component".to_string()));
+                    Task::none()
+                }
+            }
+
+            Message::Paste => {
+                if self.active_project().is_none() {
+                    return Task::none();
+                }
+                iced::clipboard::read().map(Message::ClipboardRead)
+            }
+
+            Message::ClipboardRead(contents) => {
+                let Some(contents) = contents else {
+                    self.status_message = Some(StatusMessage::info("Clipboard is empty".to_string()));
+                    return Task::none();
+                };
+                match crate::io::decode_subtree(&contents) {
+                    Ok(node) => {
+                        tracing::info!(target: targets::TREE, id = %node.id, "Pasting from clipboard");
+                        self.insert_node(node, None, "pasted component");
+                        self.reveal_selection()
+                    }
+                    Err(e) => {
+                        tracing::debug!(target: targets::TREE, error = %e, "Paste rejected");
+                        self.status_message = Some(StatusMessage::error(format!("Cannot paste: {}", e)));
+                        Task::none()
+                    }
+                }
+            }
+
+            Message::Undo => {
+                tracing::debug!(target: targets::APP, "Undo requested");
+                if let Some(project) = self.active_project_mut() {
+                    if let Some(label) = project.undo() {
+                        tracing::info!(target: targets::APP, %label, "Undo applied");
+                        self.status_message = Some(StatusMessage::info(format!("Undid: {}", label)));
+                    }
+                }
+                Task::none()
+            }
+
+            Message::JumpToHistory(index) => {
+                tracing::debug!(target: targets::APP, index, "History jump requested");
+                if let Some(project) = self.active_project_mut() {
+                    if project.jump_to_history(index) {
+                        tracing::info!(target: targets::APP, index, "History jump applied");
+                        self.status_message = Some(StatusMessage::info(match project.history.undo_label() {
+                            Some(label) => format!("Jumped to: {}", label),
+                            None => "Jumped to initial state".to_string(),
+                        }));
+                    }
+                }
+                Task::none()
+            }
+
+            Message::Redo => {
+                tracing::debug!(target: targets::APP, "Redo requested");
+                if let Some(project) = self.active_project_mut() {
+                    if let Some(label) = project.redo() {
+                        tracing::info!(target: targets::APP, %label, "Redo applied");
+                        self.status_message = Some(StatusMessage::info(format!("Redid: {}", label)));
+                    }
+                }
+                Task::none()
+            }
+
+            Message::SetMode(mode) => {
+                if mode == self.mode {
+                    return Task::none();
+                }
+                tracing::info!(target: targets::APP, ?mode, "Mode changed");
+                match mode {
+                    EditorMode::Preview => {
+                        // Inspector edits are disabled, so finish any inline edit first
+                        self.inline_edit = None;
+                        self.status_message = Some(StatusMessage::info("Preview mode - widgets are interactive".to_string()));
+                    }
+                    EditorMode::Design => {
+                        self.status_message = Some(StatusMessage::info("Design mode - click to select widgets".to_string()));
+                        // Preview interactions are transient
+                        self.preview_selections.clear();
+                    }
+                }
+                self.mode = mode;
+                Task::none()
+            }
+
+            Message::TogglePreviewMode => {
+                let mode = match self.mode {
+                    EditorMode::Design => EditorMode::Preview,
+                    EditorMode::Preview => EditorMode::Design,
+                };
+                self.update(Message::SetMode(mode))
+            }
+
+            Message::ToggleMeasurements => {
+                self.show_measurements = !self.show_measurements;
+                tracing::debug!(target: targets::APP, show_measurements = self.show_measurements, "Toggled measurement overlay");
+                self.status_message = Some(StatusMessage::info(if self.show_measurements {
+                    "Showing padding and spacing".to_string()
+                } else {
+                    "Hiding padding and spacing".to_string()
+                }));
+                Task::none()
+            }
+
+            Message::WindowResized(size) => {
+                // Saved when the window closes rather than on every step of a resize
+                self.settings.window_size = WindowSize::new(size.width, size.height);
+                Task::none()
+            }
+
+            Message::FileDropped(path) => {
+                tracing::debug!(target: targets::APP, path = %path.display(), "File dropped");
+                self.dropped_paths.push(path);
+                // Dropping several files sends one event each; wait for them all
+                if self.dropped_paths.len() > 1 {
+                    return Task::none();
+                }
+                Task::perform(delay(DROP_SETTLE_DELAY), |()| Message::DropSettled)
+            }
+
+            Message::DropSettled => {
+                let dropped = std::mem::take(&mut self.dropped_paths);
+                match dropped.iter().find_map(|path| DroppedPath::classify(path)) {
+                    Some(DroppedPath::Project(path)) => {
+                        tracing::info!(target: targets::APP, path = %path.display(), "Opening dropped project");
+                        self.update(Message::LoadProject(path))
+                    }
+                    Some(DroppedPath::LoneLayout(path)) => {
+                        let description = format!(
+                            "{} is not part of an Iced Builder project. Create a project in its folder that uses it as the main layout?",
+                            path.display()
+                        );
+                        Task::perform(
+                            async move {
+                                rfd::AsyncMessageDialog::new()
+                                    .set_title("Create project?")
+                                    .set_description(description)
+                                    .set_level(rfd::MessageLevel::Info)
+                                    .set_buttons(rfd::MessageButtons::YesNo)
+                                    .show()
+                                    .await
+                            },
+                            move |result| match result {
+                                rfd::MessageDialogResult::Yes => Message::CreateProjectForLayout(path.clone()),
+                                _ => Message::Noop,
+                            },
+                        )
+                    }
+                    None => {
+                        if let Some(first) = dropped.first() {
+                            self.status_message = Some(StatusMessage::error(format!(
+                                "{} is not an Iced Builder project or layout file",
+                                first.display()
+                            )));
+                        }
+                        Task::none()
+                    }
+                }
+            }
+
+            Message::CreateProjectForLayout(path) => {
+                match Project::create_for_layout(&path) {
+                    Ok(project) => self.update(Message::ProjectOpened(Ok(Box::new(project)))),
+                    Err(e) => {
+                        tracing::error!(target: targets::APP, error = %e, "Failed to create project for layout");
+                        self.show_error("Failed to create project", Some(&path), &e);
+                        Task::none()
+                    }
+                }
+            }
+
+            Message::WindowCloseRequested(id) => {
+                tracing::info!(target: targets::APP, "Window closing");
+                self.save_settings();
+                iced::window::close(id)
+            }
+
+            Message::ToggleTheme => {
+                self.settings.theme = match self.settings.theme {
+                    ThemeChoice::Light => ThemeChoice::Dark,
+                    ThemeChoice::Dark => ThemeChoice::Light,
+                };
+                self.save_settings();
+                Task::none()
+            }
+
+            Message::SetUiScale(scale) => {
+                let scale = metrics::clamp_scale(scale);
+                metrics::set_scale(scale);
+                self.status_message = Some(StatusMessage::info(format!("UI scale {}%", (scale * 100.0).round())));
+                if self.settings.ui_scale != scale {
+                    tracing::debug!(target: targets::APP, scale, "UI scale changed");
+                    self.settings.ui_scale = scale;
+                    self.save_settings();
+                }
+                Task::none()
+            }
+
+            Message::StepUiScale(steps) => {
+                self.update(Message::SetUiScale(self.settings.ui_scale + steps as f32 * metrics::SCALE_STEP))
+            }
+
+            Message::ToggleStats => {
+                self.show_stats = !self.show_stats;
+                Task::none()
+            }
+
+            Message::ToggleLoadWarnings => {
+                self.show_load_warnings = !self.show_load_warnings;
+                Task::none()
+            }
+
+            Message::ToggleFullStatus => {
+                self.show_full_status = !self.show_full_status;
+                Task::none()
+            }
+
+            Message::TickStatus => {
+                if self.status_message.as_ref().is_some_and(|status| status.is_expired(Instant::now())) {
+                    self.status_message = None;
+                    self.show_full_status = false;
+                }
+                Task::none()
+            }
+
+            Message::CrashSnapshotDue => {
+                self.refresh_crash_snapshot();
+                Task::none()
+            }
+
+            Message::DismissDialog => {
+                self.pending_dialog = None;
+                Task::none()
+            }
+
+            Message::CopyDialogDetails => match &self.pending_dialog {
+                Some(dialog) => {
+                    self.status_message = Some(StatusMessage::success("Error details copied".to_string()));
+                    iced::clipboard::write(dialog.details())
+                }
+                None => Task::none(),
+            },
+
+            Message::ConfirmDropUnknownFields(then) => {
+                self.drop_unknown_fields = true;
+                self.update(*then)
+            }
+
+            Message::ToggleBackupList => {
+                self.backup_list = match (&self.backup_list, self.active_project()) {
+                    (None, Some(project)) => Some(project.layout_backups()),
+                    _ => None,
+                };
+                Task::none()
+            }
+
+            Message::RestoreBackup(backup) => {
+                self.backup_list = None;
+                let Some(project) = self.active_project_mut() else {
+                    return Task::none();
+                };
+                match project.restore_backup(&backup) {
+                    Ok(()) => {
+                        self.inline_edit = None;
+                        self.status_message = Some(StatusMessage::success(format!(
+                            "Restored backup from {}; undo to go back",
+                            crate::io::backups::backup_label(&backup)
+                        )));
+                    }
+                    Err(e) => {
+                        tracing::error!(target: targets::APP, error = %e, "Failed to restore backup");
+                        self.status_message = Some(StatusMessage::info(format!("Failed to restore backup: {}", e)));
+                    }
+                }
+                Task::none()
+            }
+
+            Message::ToggleHistoryPanel => {
+                self.show_history = !self.show_history;
+                Task::none()
+            }
+
+            Message::ToggleProblemsPanel => {
+                self.show_problems = !self.show_problems;
+                Task::none()
+            }
+
+            Message::ShowProblems => {
+                if !self.show_problems {
+                    self.show_problems = true;
+                    return Task::none();
+                }
+                let first = self
+                    .problems
+                    .list
+                    .iter()
+                    .find(|problem| problem.severity == ValidationSeverity::Error)
+                    .or(self.problems.list.first());
+                match first.map(|problem| problem.node_id) {
+                    Some(id) => self.update(Message::SelectProblem(id)),
+                    None => Task::none(),
+                }
+            }
+
+            Message::CheckStateBindings => {
+                let Some(project) = self.active_project() else {
+                    return Task::none();
+                };
+                match StateStruct::find(&project.target_root(), &project.config.state_type) {
+                    Ok(state) => {
+                        let issues = state.check(&project.layout).len();
+                        tracing::info!(target: targets::APP, file = %state.file.display(), issues, "Checked bindings against state struct");
+                        self.status_message = Some(match issues {
+                            0 => StatusMessage::success(format!("Bindings match {}", state.name)),
+                            _ => StatusMessage::warning(format!(
+                                "{} problem{} against {}",
+                                issues,
+                                if issues == 1 { "" } else { "s" },
+                                state.name
+                            )),
+                        });
+                        self.problems.state = Some(state);
+                        self.show_problems = true;
+                    }
+                    Err(e) => {
+                        tracing::info!(target: targets::APP, error = %e, "State struct not found");
+                        self.status_message = Some(StatusMessage::info(format!("Can't check bindings: {}", e)));
+                        self.problems.state = None;
+                    }
+                }
+                self.refresh_problems();
+                Task::none()
+            }
+
+            Message::ClearStateCheck => {
+                self.problems.state = None;
+                self.refresh_problems();
+                Task::none()
+            }
+
+            Message::ProblemsDue(revision) => {
+                self.problems.scheduled = None;
+                // Edited again while waiting; the next schedule waits for that edit
+                if self.active_project().map(|p| p.revision) == Some(revision) {
+                    self.refresh_problems();
+                }
+                Task::none()
+            }
+
+            Message::SelectProblem(id) => {
+                self.select_component(id);
+                self.reveal_selection()
+            }
+
+            Message::PreviewPickListSelected(id, choice) => {
+                tracing::debug!(target: targets::APP, %id, %choice, "Preview PickList selection");
+                self.preview_selections.insert(id, choice);
+                Task::none()
+            }
+
+            Message::ToggleFocusPreview => {
+                match self.focus_preview.take() {
+                    Some(previous) => {
+                        tracing::info!(target: targets::APP, "Leaving focus preview");
+                        self.mode = previous;
+                        if previous != EditorMode::Preview {
+                            self.preview_selections.clear();
+                        }
+                        self.status_message = Some(StatusMessage::info("Focus preview closed".to_string()));
+                    }
+                    None if self.active_project().is_some() => {
+                        tracing::info!(target: targets::APP, "Entering focus preview");
+                        self.focus_preview = Some(self.mode);
+                        self.mode = EditorMode::Preview;
+                        self.status_message = Some(StatusMessage::info("Focus preview - press F11 or Esc to exit".to_string()));
+                    }
+                    None => {}
+                }
+                Task::none()
+            }
+
+            Message::ToggleShowInvisible => {
+                self.show_invisible = !self.show_invisible;
+                tracing::debug!(target: targets::APP, show_invisible = self.show_invisible, "Toggled invisible element outlines");
+                self.status_message = Some(StatusMessage::info(if self.show_invisible {
+                    "Showing invisible elements".to_string()
+                } else {
+                    "Hiding invisible elements".to_string()
+                }));
+                Task::none()
+            }
+
+            Message::SelectCanvasPreset(preset) => {
+                tracing::debug!(target: targets::APP, preset = preset.name, "Canvas preset selected");
+                (self.canvas_width_input, self.canvas_height_input) =
+                    Self::canvas_size_inputs(preset.size);
+                self.set_canvas_size(preset.size);
+                Task::none()
+            }
+
+            Message::ToggleCanvasAutoScroll(enabled) => {
+                self.settings.auto_scroll_canvas = enabled;
+                self.save_settings();
+                Task::none()
+            }
+
+            Message::ToggleTreeDock => {
+                self.settings.tree_dock = match self.settings.tree_dock {
+                    TreeDock::Bottom => TreeDock::Left,
+                    TreeDock::Left => TreeDock::Bottom,
+                };
+                tracing::debug!(target: targets::APP, dock = ?self.settings.tree_dock, "Tree view docked");
+                self.save_settings();
+                Task::none()
+            }
+
+            Message::CanvasScrolled(viewport) => {
+                self.canvas_viewport = Some(viewport);
+                Task::none()
+            }
+
+            Message::MinimapClicked(fraction) => {
+                let (x, y) = match &self.canvas_viewport {
+                    Some(viewport) => (
+                        viewport.relative_offset().x,
+                        MinimapViewport::from_scroll(viewport).centered_on(fraction),
+                    ),
+                    None => (0.0, fraction),
+                };
+                iced::widget::scrollable::snap_to(
+                    Canvas::scrollable_id(),
+                    iced::widget::scrollable::RelativeOffset { x, y },
+                )
+            }
+
+            Message::CanvasWidthInput(value) => {
+                self.canvas_width_input = value;
+                self.apply_custom_canvas_size();
+                Task::none()
+            }
+
+            Message::CanvasHeightInput(value) => {
+                self.canvas_height_input = value;
+                self.apply_custom_canvas_size();
+                Task::none()
+            }
+
+            Message::CommitCanvasSize => {
+                self.apply_custom_canvas_size();
+                self.save_settings();
+                Task::none()
+            }
+
+            Message::HistoryLimitInput(value) => {
+                // Applied on submit, so typing "100" never trims history to 1
+                self.history_limit_input = value;
+                Task::none()
+            }
+
+            Message::CommitHistoryLimit => {
+                let Some(limit) = self.history_limit_input.trim().parse::<usize>().ok().filter(|l| *l >= 1) else {
+                    self.history_limit_input = self.settings.history_limit.to_string();
+                    self.status_message = Some(StatusMessage::error("History limit must be a whole number of at least 1".to_string()));
+                    return Task::none();
+                };
+                self.history_limit_input = limit.to_string();
+                for project in &mut self.projects {
+                    project.history.set_limit(limit);
+                }
+                if self.settings.history_limit != limit {
+                    tracing::debug!(target: targets::APP, limit, "History limit changed");
+                    self.settings.history_limit = limit;
+                    self.save_settings();
+                }
+                Task::none()
+            }
+
+            Message::UpdateSampleValue(binding, value) => {
+                if let Some(project) = self.active_project_mut() {
+                    tracing::debug!(target: targets::INSPECTOR, %binding, ?value, "Updating sample value");
+                    match value {
+                        Some(value) => {
+                            project.config.sample_data.insert(binding, value);
+                        }
+                        None => {
+                            project.config.sample_data.remove(&binding);
+                        }
+                    }
+                    project.mark_config_dirty();
+                }
+                Task::none()
+            }
+
+            Message::UpdateTextContent(id, content) => {
+                tracing::debug!(target: targets::INSPECTOR, %id, "Updating text content");
+                self.update_node_property(id, "Change text", |node| {
+                    if let crate::model::layout::WidgetType::Text { content: c, .. } = &mut node.widget {
+                        *c = content;
+                    }
+                });
+                Task::none()
+            }
+
+            Message::UpdateButtonLabel(id, label) => {
+                tracing::debug!(target: targets::INSPECTOR, %id, "Updating button label");
+                self.update_node_property(id, "Change label", |node| {
+                    if let crate::model::layout::WidgetType::Button { label: l, .. } = &mut node.widget {
+                        *l = label;
+                    }
+                });
+                Task::none()
+            }
+
+            Message::UpdateMessageStub(id, stub) => {
+                tracing::debug!(target: targets::INSPECTOR, %id, "Updating message stub");
+                self.update_node_property(id, "Change message", |node| {
+                    match &mut node.widget {
+                        crate::model::layout::WidgetType::Button { message_stub, .. } => *message_stub = stub,
+                        crate::model::layout::WidgetType::TextInput { message_stub, .. } => *message_stub = stub,
+                        crate::model::layout::WidgetType::Checkbox { message_stub, .. } => *message_stub = stub,
+                        crate::model::layout::WidgetType::Slider { message_stub, .. } => *message_stub = stub,
+                        crate::model::layout::WidgetType::PickList { message_stub, .. } => *message_stub = stub,
+                        _ => {}
+                    }
+                });
+                Task::none()
+            }
+
+            Message::UpdatePlaceholder(id, placeholder) => {
+                self.update_node_property(id, "Change placeholder", |node| {
+                    if let crate::model::layout::WidgetType::TextInput { placeholder: p, .. } = &mut node.widget {
+                        *p = placeholder;
+                    }
+                });
+                Task::none()
+            }
+
+            Message::UpdateBinding(id, binding) => {
+                self.update_node_property(id, "Change binding", |node| {
+                    match &mut node.widget {
+                        crate::model::layout::WidgetType::TextInput { value_binding, .. } => *value_binding = binding,
+                        crate::model::layout::WidgetType::Checkbox { checked_binding, .. } => *checked_binding = binding,
+                        crate::model::layout::WidgetType::Slider { value_binding, .. } => *value_binding = binding,
+                        crate::model::layout::WidgetType::PickList { selected_binding, .. } => *selected_binding = binding,
+                        _ => {}
+                    }
+                });
+                Task::none()
+            }
+
+            Message::UpdatePadding(id, padding) => {
+                self.update_node_property(id, "Change padding", |node| {
+                    match &mut node.widget {
+                        crate::model::layout::WidgetType::Column { attrs, .. }
+                        | crate::model::layout::WidgetType::Row { attrs, .. }
+                        | crate::model::layout::WidgetType::Container { attrs, .. }
+                        | crate::model::layout::WidgetType::Scrollable { attrs, .. }
+                        | crate::model::layout::WidgetType::Stack { attrs, .. } => {
+                            attrs.padding = crate::model::layout::PaddingSpec {
+                                top: padding,
+                                right: padding,
+                                bottom: padding,
+                                left: padding,
+                            };
+                        }
+                        _ => {}
+                    }
+                });
+                Task::none()
+            }
+
+            Message::UpdateSpacing(id, spacing) => {
+                self.update_node_property(id, "Change spacing", |node| {
+                    match &mut node.widget {
+                        crate::model::layout::WidgetType::Column { attrs, .. }
+                        | crate::model::layout::WidgetType::Row { attrs, .. }
+                        | crate::model::layout::WidgetType::Container { attrs, .. }
+                        | crate::model::layout::WidgetType::Scrollable { attrs, .. }
+                        | crate::model::layout::WidgetType::Stack { attrs, .. } => {
+                            attrs.spacing = spacing;
+                        }
+                        _ => {}
+                    }
+                });
+                Task::none()
+            }
+
+            Message::UpdateWidth(id, width) => {
+                self.update_node_property(id, "Change width", |node| {
+                    match &mut node.widget {
+                        crate::model::layout::WidgetType::Column { attrs, .. }
+                        | crate::model::layout::WidgetType::Row { attrs, .. }
+                        | crate::model::layout::WidgetType::Container { attrs, .. }
+                        | crate::model::layout::WidgetType::Scrollable { attrs, .. }
+                        | crate::model::layout::WidgetType::Stack { attrs, .. } => {
+                            attrs.width = width;
+                        }
+                        _ => {}
+                    }
+                });
+                Task::none()
+            }
+
+            Message::UpdateHeight(id, height) => {
+                self.update_node_property(id, "Change height", |node| {
+                    match &mut node.widget {
+                        crate::model::layout::WidgetType::Column { attrs, .. }
+                        | crate::model::layout::WidgetType::Row { attrs, .. }
+                        | crate::model::layout::WidgetType::Container { attrs, .. }
+                        | crate::model::layout::WidgetType::Scrollable { attrs, .. }
+                        | crate::model::layout::WidgetType::Stack { attrs, .. } => {
+                            attrs.height = height;
+                        }
+                        _ => {}
+                    }
+                });
+                Task::none()
+            }
+
+            Message::UpdateAlignX(id, align_x) => {
+                self.update_node_property(id, "Change horizontal alignment", |node| {
+                    match &mut node.widget {
+                        crate::model::layout::WidgetType::Column { attrs, .. }
+                        | crate::model::layout::WidgetType::Row { attrs, .. }
+                        | crate::model::layout::WidgetType::Container { attrs, .. }
+                        | crate::model::layout::WidgetType::Scrollable { attrs, .. }
+                        | crate::model::layout::WidgetType::Stack { attrs, .. } => {
+                            attrs.align_x = align_x;
+                        }
+                        _ => {}
+                    }
+                });
+                Task::none()
+            }
+
+            Message::UpdateAlignY(id, align_y) => {
+                self.update_node_property(id, "Change vertical alignment", |node| {
+                    match &mut node.widget {
+                        crate::model::layout::WidgetType::Column { attrs, .. }
+                        | crate::model::layout::WidgetType::Row { attrs, .. }
+                        | crate::model::layout::WidgetType::Container { attrs, .. }
+                        | crate::model::layout::WidgetType::Scrollable { attrs, .. }
+                        | crate::model::layout::WidgetType::Stack { attrs, .. } => {
+                            attrs.align_y = align_y;
+                        }
+                        _ => {}
+                    }
+                });
+                Task::none()
+            }
+
+            Message::UpdateFontSize(id, font_size) => {
+                self.update_node_property(id, "Change font size", |node| {
+                    if let crate::model::layout::WidgetType::Text { attrs, .. } = &mut node.widget {
+                        attrs.font_size = font_size;
+                    }
+                });
+                Task::none()
+            }
+
+            Message::UpdateTextColor(id, color) => {
+                self.update_node_property(id, "Change text color", |node| {
+                    if let crate::model::layout::WidgetType::Text { attrs, .. } = &mut node.widget {
+                        attrs.color = color;
+                    }
+                });
+                Task::none()
+            }
+
+            Message::UpdateCheckboxLabel(id, label) => {
+                self.update_node_property(id, "Change label", |node| {
+                    if let crate::model::layout::WidgetType::Checkbox { label: l, .. } = &mut node.widget {
+                        *l = label;
+                    }
+                });
+                Task::none()
+            }
+
+            Message::DraftInput(id, field, text) => {
+                let Some(project) = self.active_project() else {
+                    return Task::none();
+                };
+                let before = project.revision;
+                let (min, max, binding) = match project.find_node(id).map(|node| &node.widget) {
+                    Some(crate::model::layout::WidgetType::Slider { min, max, value_binding, .. }) => {
+                        (*min, *max, value_binding.clone())
+                    }
+                    _ => return Task::none(),
+                };
+                // An inverted or empty range is never applied; the typed
+                // text is kept until the entry makes sense again
+                let number = text.trim().parse::<f32>().ok().filter(|v| v.is_finite());
+                let task = match field {
+                    DraftField::Sample => {
+                        let value = (!text.trim().is_empty()).then(|| crate::model::SampleValue::parse(&text));
+                        self.update(Message::UpdateSampleValue(binding, value))
+                    }
+                    DraftField::Min => match number.filter(|v| *v < max) {
+                        Some(v) => self.update(Message::UpdateSliderRange(id, v, max)),
+                        None => Task::none(),
+                    },
+                    DraftField::Max => match number.filter(|v| *v > min) {
+                        Some(v) => self.update(Message::UpdateSliderRange(id, min, v)),
+                        None => Task::none(),
+                    },
+                };
+                if let Some(after) = self.active_project().map(|p| p.revision) {
+                    self.inspector_drafts.set(before, after, id, field, text);
+                }
+                task
+            }
+
+            Message::UpdateSliderRange(id, min, max) => {
+                if min.is_nan() || max.is_nan() || min >= max {
+                    return Task::none();
+                }
+                self.update_node_property(id, "Change slider range", |node| {
+                    if let crate::model::layout::WidgetType::Slider { min: m, max: mx, .. } = &mut node.widget {
+                        *m = min;
+                        *mx = max;
+                    }
+                });
+                Task::none()
+            }
+
+            Message::Noop => Task::none(),
+        }
+    }
+
+    /// Helper to update a node property with history tracking.
+    ///
+    /// `label` names the edit in the undo history, e.g. "Change padding".
+    fn update_node_property<F>(&mut self, id: ComponentId, label: &str, update_fn: F)
+    where
+        F: FnOnce(&mut LayoutNode),
+    {
+        let Some(project) = self.active_project_mut() else {
+            return;
+        };
+        if project.update_node(id, label, update_fn) {
+            tracing::debug!(target: targets::PROPERTY, %id, "Property updated");
+        } else {
+            tracing::warn!(target: targets::PROPERTY, %id, "Node not found for property update");
+        }
+    }
+
+    /// History label for an action on a node, e.g. "Delete Button".
+    fn action_label(verb: &str, project: &Project, id: ComponentId) -> String {
+        match project.find_node(id) {
+            Some(node) => format!("{} {}", verb, node.widget.type_name()),
+            None => verb.to_string(),
+        }
+    }
+
+    /// Add the pending palette widget to the nearest container at or above `id`.
+    fn drop_pending_insert(&mut self, id: ComponentId) -> Task<Message> {
+        let target = self
+            .active_project()
+            .and_then(|project| project.nearest_container(id));
+        match (self.pending_insert.take(), target) {
+            (Some(kind), Some(parent_id)) => self.add_widget(kind, Some(parent_id)),
+            _ => self.status_message = Some(StatusMessage::error("Cannot add widget here".to_string())),
+        }
+        self.scroll_tree_to_selection()
+    }
+
+    /// Add a new widget from the palette.
+    ///
+    /// Adds to `parent_id` when given, otherwise to the selected container or root.
+    fn add_widget(&mut self, kind: WidgetKind, parent_id: Option<ComponentId>) {
+        tracing::info!(target: targets::TREE, ?kind, "Adding widget from palette");
+        if self.insert_node(create_node_for_kind(kind), parent_id, kind.name()) {
+            self.settings.remember_widget(kind);
+            self.save_settings();
+        }
+    }
+
+    /// Insert a new node and select it.
+    ///
+    /// Adds to `parent_id` when given. Otherwise Before/After modes insert next
+    /// to the selection, and Inside adds to the selected container or root.
+    /// `label` names the node in the status message. Returns true if it was added.
+    fn insert_node(&mut self, new_node: LayoutNode, parent_id: Option<ComponentId>, label: &str) -> bool {
+        let position = self.effective_insert_mode().position();
+        let Some(project) = self.active_project_mut() else {
+            return false;
+        };
+
+        // Before/After modes insert next to a selected non-root node
+        let anchor_id = project
+            .selected_id
+            .filter(|id| *id != project.layout.root.id);
+        if let (None, Some(position), Some(anchor_id)) = (parent_id, position, anchor_id) {
+            let new_node_id = new_node.id;
+            tracing::debug!(
+                target: targets::TREE,
+                node_id = %new_node_id,
+                %anchor_id,
+                ?position,
+                "Inserting node beside selection"
+            );
+            if project.insert_sibling(anchor_id, new_node, position) {
+                project.mark_dirty();
+                project.selected_id = Some(new_node_id);
+                self.status_message = Some(StatusMessage::success(format!("Added {}", label)));
+                return true;
+            }
+            self.status_message = Some(StatusMessage::error("Cannot add a sibling here".to_string()));
+            return false;
+        }
+
+        // Push history before modification
+        project
+            .history
+            .push(project.layout.clone(), format!("Add {}", new_node.widget.type_name()));
+
+        let new_node_id = new_node.id;
+        tracing::debug!(
+            target: targets::TREE, 
+            node_id = %new_node.id, 
+            "Inserting new node"
+        );
+
+        // Add to the explicit parent, else the selected container, else root
+        let added = if let Some(parent_id) = parent_id {
+            tracing::debug!(
+                target: targets::TREE,
+                %parent_id,
+                "Adding to drop target"
+            );
+            project.add_child_to_node(parent_id, new_node)
+        } else if let Some(selected_id) = project.selected_id {
+            if project.is_container(selected_id) {
+                tracing::debug!(
+                    target: targets::TREE,
+                    parent_id = %selected_id,
+                    "Adding to selected container"
+                );
+                project.add_child_to_node(selected_id, new_node)
+            } else {
+                tracing::debug!(
+                    target: targets::TREE,
+                    "Selected node is not a container, adding to root"
+                );
+                project.add_child_to_root(new_node)
+            }
+        } else {
+            tracing::debug!(
+                target: targets::TREE,
+                "No selection, adding to root"
+            );
+            project.add_child_to_root(new_node)
+        };
+
+        if added {
+            project.mark_dirty();
+            // Select the newly added node
+            project.selected_id = Some(new_node_id);
+            self.status_message = Some(StatusMessage::success(format!("Added {}", label)));
+        } else {
+            // Undo the history push if add failed
+            let _ = project.history.undo(project.layout.clone());
+            self.status_message = Some(StatusMessage::error("Cannot add widget here".to_string()));
+        }
+        added
+    }
+
+    /// Insertion mode for the next palette click.
+    ///
+    /// Alt inserts after the selection and Shift+Alt before it, overriding
+    /// the palette's mode selector.
+    fn effective_insert_mode(&self) -> InsertMode {
+        match (self.modifiers.alt(), self.modifiers.shift()) {
+            (true, true) => InsertMode::Before,
+            (true, false) => InsertMode::After,
+            _ => self.insert_mode,
+        }
+    }
+
+    /// Encode the selected subtree as clipboard text.
+    ///
+    /// Sets a status message and returns `None` when nothing is selected or
+    /// encoding fails.
+    fn encode_selection(&mut self) -> Option<String> {
+        let project = self.active_project()?;
+        let Some(node) = project.selected_id.and_then(|id| project.find_node(id)) else {
+            self.status_message = Some(StatusMessage::info("Select a component to copy".to_string()));
+            return None;
+        };
+        match crate::io::encode_subtree(node) {
+            Ok(text) => Some(text),
+            Err(e) => {
+                tracing::warn!(target: targets::TREE, error = %e, "Failed to encode selection");
+                self.status_message = Some(StatusMessage::error(format!("Failed to copy: {}", e)));
+                None
+            }
+        }
+    }
+
+    /// Collect the state the palette renders from.
+    fn palette_context(&self) -> PaletteContext<'_> {
+        PaletteContext {
+            pending: self.pending_insert,
+            recent: &self.settings.recent_widgets,
+            snippets: &self.snippets,
+            renaming: self.snippet_rename.as_ref(),
+            naming: self.snippet_name.as_ref().map(|(_, name)| name.as_str()),
+            collapsed: &self.settings.collapsed_palette_sections,
+            query: &self.palette_query,
+            insert_mode: self.insert_mode,
+            ineligible: self.palette_ineligibility(),
+            disable_ineligible: self.settings.disable_ineligible_palette_items,
+        }
+    }
+
+    /// Why a palette click cannot go where the insertion mode asks, if it cannot.
+    ///
+    /// Eligibility depends only on the selection, so it is computed once per
+    /// view and shared by every palette item.
+    fn palette_ineligibility(&self) -> Option<&'static str> {
+        let project = self.active_project()?;
+        let selected_id = project.selected_id?;
+
+        if self.effective_insert_mode().position().is_some() && selected_id != project.layout.root.id {
+            let (parent_id, _) = project.parent_of(selected_id)?;
+            let multi_child = project
+                .find_node(parent_id)
+                .is_some_and(LayoutNode::is_multi_child);
+            return (!multi_child).then_some("Cannot be added next to the selection");
+        }
+
+        (!project.is_container(selected_id)).then_some("Will be added to root")
+    }
+
+    /// Reload the snippet list from the open project.
+    fn refresh_snippets(&mut self) {
+        self.snippets = self
+            .active_project()
+            .map(|project| crate::io::list_snippets(project.project_path()))
+            .unwrap_or_default();
+    }
+
+    /// Show a failure that needs the user's attention in the error dialog,
+    /// with a short summary in the status bar.
+    fn show_error(&mut self, title: &str, path: Option<&std::path::Path>, error: &dyn std::error::Error) {
+        self.show_dialog(DialogState::error(title, path, error));
+    }
+
+    /// Show an error described away from the UI thread.
+    fn show_dialog(&mut self, dialog: DialogState) {
+        self.status_message = Some(StatusMessage::error(dialog.title.clone()));
+        self.pending_dialog = Some(dialog);
+    }
+
+    /// Point out unknown fields skipped while loading the layout.
+    fn report_load_warnings(&mut self) {
+        self.drop_unknown_fields = false;
+        let count = self.active_project().map_or(0, |p| p.load_warnings.len());
+        if count > 0 {
+            self.status_message = Some(StatusMessage::info(format!(
+                "Loaded with {} warning{} (newer format?)",
+                count,
+                if count == 1 { "" } else { "s" }
+            )));
+        }
+    }
+
+    /// Ask before a save that would drop unknown fields from the layout,
+    /// sending `then` once the user agrees. `None` when there is nothing to ask.
+    fn confirm_drop_unknown_fields(&self, then: Message) -> Option<Task<Message>> {
+        let count = self.active_project().map_or(0, |p| p.load_warnings.len());
+        if count == 0 || self.drop_unknown_fields {
+            return None;
+        }
+        let description = format!(
+            "This layout has {} field{} this version of Iced Builder doesn't know, \
+             probably written by a newer version. Saving will drop {}. Save anyway?",
+            count,
+            if count == 1 { "" } else { "s" },
+            if count == 1 { "it" } else { "them" },
+        );
+        Some(Task::perform(
+            async move {
+                rfd::AsyncMessageDialog::new()
+                    .set_title("Drop unknown data?")
+                    .set_description(description)
+                    .set_level(rfd::MessageLevel::Warning)
+                    .set_buttons(rfd::MessageButtons::YesNo)
+                    .show()
+                    .await
+            },
+            move |result| match result {
+                rfd::MessageDialogResult::Yes => Message::ConfirmDropUnknownFields(Box::new(then.clone())),
+                _ => Message::Noop,
+            },
+        ))
+    }
+
+    /// Put the open project at the top of the recent projects list.
+    fn remember_project(&mut self) {
+        if let Some(project) = self.projects.get(self.active) {
+            self.recent_projects.record(project.project_path(), &project.layout.name);
+            self.recent_projects.save_or_log();
+        }
+    }
+
+    /// Select a component by ID.
+    fn select_component(&mut self, id: ComponentId) {
+        tracing::debug!(target: targets::SELECTION, %id, "Component selected");
+        if let Some(project) = self.active_project_mut() {
+            project.selected_id = Some(id);
+
+            // Log details about the selected node
+            if let Some(node) = project.find_node(id) {
+                tracing::debug!(
+                    target: targets::SELECTION,
+                    widget_type = ?std::mem::discriminant(&node.widget),
+                    "Selected node details"
+                );
+            }
+        }
+    }
+
+    /// Scroll both the tree view and the canvas to the selected node.
+    fn reveal_selection(&mut self) -> Task<Message> {
+        Task::batch([self.scroll_tree_to_selection(), self.scroll_canvas_to_selection()])
+    }
+
+    /// Select a visible tree row picked from the row count and the selected row's index.
+    fn select_tree_row(&mut self, pick: impl FnOnce(usize, Option<usize>) -> usize) -> Task<Message> {
+        let Some(project) = self.active_project() else {
+            return Task::none();
+        };
+        let rows = TreeView::visible_rows(&project.layout.root, &self.tree);
+        let current = project
+            .selected_id
+            .and_then(|id| rows.iter().position(|&row| row == id));
+        let Some(&id) = rows.get(pick(rows.len(), current)) else {
+            return Task::none();
+        };
+        Task::batch([
+            self.update(Message::SelectComponent(id)),
+            self.scroll_tree_to_selection(),
+        ])
+    }
+
+    /// Scroll the canvas so the selected node is visible, if auto-scroll is on.
+    fn scroll_canvas_to_selection(&self) -> Task<Message> {
+        if self.settings.auto_scroll_canvas && self.mode == EditorMode::Design {
+            Canvas::scroll_to_selection()
+        } else {
+            Task::none()
+        }
+    }
+
+    /// Scroll the tree view so the selected node is visible, expanding its ancestors.
+    fn scroll_tree_to_selection(&mut self) -> Task<Message> {
+        let Some(project) = self.projects.get(self.active) else {
+            return Task::none();
+        };
+        let mut ancestor = project.selected_id.and_then(|id| project.parent_of(id));
+        while let Some((parent, _)) = ancestor {
+            self.tree.collapsed.remove(&parent);
+            ancestor = project.parent_of(parent);
+        }
+
+        let offset = project
+            .selected_id
+            .and_then(|id| TreeView::scroll_offset_for(&project.layout.root, id, &self.tree));
+        match offset {
+            Some(offset) => iced::widget::scrollable::snap_to(TreeView::scrollable_id(), offset),
+            None => Task::none(),
+        }
+    }
+
+    /// Format a canvas size as the text of the custom width and height inputs.
+    fn canvas_size_inputs(size: Option<CanvasSize>) -> (String, String) {
+        match size {
+            Some(size) => (size.width.to_string(), size.height.to_string()),
+            None => (String::new(), String::new()),
+        }
+    }
+
+    /// Resize the canvas to the custom width and height inputs once both
+    /// are valid sizes. The size is saved when the input is submitted, so
+    /// typing doesn't write the settings file on every keystroke.
+    fn apply_custom_canvas_size(&mut self) {
+        let parse = |s: &str| s.trim().parse::<f32>().ok().filter(|v| *v >= 1.0);
+        if let (Some(width), Some(height)) = (
+            parse(&self.canvas_width_input),
+            parse(&self.canvas_height_input),
+        ) {
+            self.settings.canvas_size = Some(CanvasSize::new(width, height));
+        }
+    }
+
+    /// Set the canvas size and persist it in the app settings.
+    fn set_canvas_size(&mut self, size: Option<CanvasSize>) {
+        if self.settings.canvas_size == size {
+            return;
+        }
+        self.settings.canvas_size = size;
+        self.save_settings();
+    }
+
+    /// Persist the app settings, logging any failure.
+    fn save_settings(&self) {
+        if let Err(e) = self.settings.save() {
+            tracing::warn!(target: targets::APP, error = %e, "Failed to save settings");
+        }
+    }
+
+    /// Render the application view.
+    pub fn view(&self) -> Element<'_, Message> {
+        let dropping = self.palette_drag.is_some_and(|drag| drag.is_dragging());
+        let palette = Palette::view(self.palette_context());
+
+        let canvas: Element<Message> = match self.active_project() {
+            _ if !self.loading.is_empty() => Welcome::loading(&self.loading),
+            Some(project) => Canvas::view(
+                &project.layout.root,
+                RenderContext {
+                    selected_id: project.selected_id,
+                    mode: self.mode,
+                    show_invisible: self.show_invisible,
+                    show_measurements: self.show_measurements,
+                    inline_edit: self.inline_edit.as_ref(),
+                    sample_data: Some(&project.config.sample_data),
+                    inserting: self.pending_insert.is_some() || dropping,
+                    dropping,
+                    preview_selections: Some(&self.preview_selections),
+                    components: Some(&project.components),
+                    component_depth: 0,
+                    reuse: Some(Reuse::new(&self.canvas_cache, project)),
+                },
+                self.settings.canvas_size,
+            ),
+            None => Welcome::view(&self.recent_projects.projects),
+        };
+
+        // Focus preview shows only the canvas
+        if self.focus_preview.is_some() {
+            return canvas;
         }
+
+        // Minimap overlaid in the bottom-right corner of the canvas
+        let canvas: Element<Message> = match self.active_project() {
+            Some(project) => {
+                let minimap = Minimap::view(
+                    &project.layout.root,
+                    project.revision,
+                    &self.minimap,
+                    self.canvas_viewport.as_ref().map(MinimapViewport::from_scroll),
+                );
+                iced::widget::stack![
+                    canvas,
+                    container(minimap)
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                        .align_x(iced::alignment::Horizontal::Right)
+                        .align_y(iced::alignment::Vertical::Bottom)
+                        .padding(metrics::scaled(12.0)),
+                ]
+                .into()
+            }
+            None => canvas,
+        };
+
+        let palette_hint = self.palette_hover.or(self.pending_insert);
+        let inspector: Element<Message> = match self.active_project() {
+            Some(project) => {
+                let selected_node = project
+                    .selected_id
+                    .and_then(|id| project.find_node(id));
+                Inspector::view(
+                    selected_node,
+                    project.selected_id,
+                    Some(&project.config.sample_data),
+                    palette_hint,
+                    project.selected_id == Some(project.layout.root.id),
+                    self.mode == EditorMode::Design,
+                    self.inspector_drafts.current(project.revision),
+                )
+            }
+            None => Inspector::view(None, None, None, palette_hint, false, true, None),
+        };
+        let inspector: Element<Message> = match self.active_project().filter(|_| self.show_history) {
+            Some(project) => column![inspector, horizontal_rule(1), HistoryPanel::view(&project.history, &self.history_limit_input)].into(),
+            None => inspector,
+        };
+        let inspector: Element<Message> = match self.active_project().filter(|_| self.show_problems) {
+            Some(project) => column![inspector, horizontal_rule(1), ProblemsPanel::view(&self.problems, project.selected_id)].into(),
+            None => inspector,
+        };
+
+        let tree_view: Element<Message> = match self.active_project() {
+            Some(project) => TreeView::view(
+                &project.layout.root,
+                project.revision,
+                project.selected_id,
+                &self.tree,
+                dropping,
+            ),
+            None => container(text("No project")).into(),
+        };
+
+        // Build status bar content
+        let status_text: Element<Message> = match &self.status_message {
+            Some(status) => {
+                let color = match status.severity {
+                    StatusSeverity::Error => iced::Color::from_rgb(0.9, 0.3, 0.3),
+                    StatusSeverity::Success => iced::Color::from_rgb(0.4, 0.75, 0.4),
+                    StatusSeverity::Warning => iced::Color::from_rgb(0.9, 0.7, 0.2),
+                    StatusSeverity::Info => iced::Color::from_rgb(0.6, 0.6, 0.6),
+                };
+                match status.truncated() {
+                    // Long messages expand into a popover on click
+                    Some(short) => button(text(short).size(metrics::text_md()).color(color))
+                        .on_press(Message::ToggleFullStatus)
+                        .style(button::text)
+                        .padding(0)
+                        .into(),
+                    None => text(&status.text).size(metrics::text_md()).color(color).into(),
+                }
+            }
+            None => text("Ready").size(metrics::text_md()).color(iced::Color::from_rgb(0.6, 0.6, 0.6)).into(),
+        };
+        let history_status = match self.active_project() {
+            Some(project) => format!(
+                " | Undo: {} | Redo: {}",
+                project
+                    .history
+                    .undo_label()
+                    .map_or("-".to_string(), |label| format!("{} (Ctrl+Z)", label)),
+                project
+                    .history
+                    .redo_label()
+                    .map_or("-".to_string(), |label| format!("{} (Ctrl+Y)", label)),
+            ),
+            None => String::new(),
+        };
+        
+        let dirty_indicator = match self.active_project() {
+            Some(project) if project.dirty => " [unsaved]",
+            _ => "",
+        };
+        
+        // Active canvas size
+        let canvas_size_status = match self.settings.canvas_size {
+            Some(size) => format!(" | Canvas: {}×{}", size.width, size.height),
+            None => " | Canvas: Fit".to_string(),
+        };
+
+        // Mode indicator
+        let mode_text = match self.mode {
+            EditorMode::Design => "Design",
+            EditorMode::Preview => "Preview",
+        };
+
+        // Design/Preview segmented toggle, the active side highlighted
+        let mode_segment = |label: &'static str, mode: EditorMode| {
+            button(text(label).size(metrics::text_md()))
+                .on_press(Message::SetMode(mode))
+                .style(if self.mode == mode { button::primary } else { button::secondary })
+                .padding(metrics::pad())
+        };
+
+        // Invisible element outline toggle
+        let invisible_button_label = if self.show_invisible {
+            "Hide Invisible"
+        } else {
+            "Show Invisible"
+        };
+
+        // Padding/spacing overlay toggle
+        let measurements_button_label = if self.show_measurements {
+            "Hide Spacing"
+        } else {
+            "Show Spacing"
+        };
+
+        let history_button_label = if self.show_history {
+            "Hide History"
+        } else {
+            "Show History"
+        };
+
+        // Tree view placement toggle
+        let dock_button_label = match self.settings.tree_dock {
+            TreeDock::Bottom => "Dock Tree Left",
+            TreeDock::Left => "Dock Tree Bottom",
+        };
+
+        let export_mode_label = match self.active_project().map(|p| p.config.export_mode) {
+            Some(ExportMode::Component) => "Export: Component",
+            _ => "Export: View",
+        };
+
+        // Toolbar with file operations
+        let toolbar = container(
+            row![
+                button(text("New Project").size(metrics::text_md()))
+                    .on_press(Message::NewProject)
+                    .padding(metrics::pad()),
+                button(text("Open Project").size(metrics::text_md()))
+                    .on_press(Message::OpenProject)
+                    .padding(metrics::pad()),
+                button(text("Open Recent…").size(metrics::text_md()))
+                    .on_press(Message::ToggleRecentProjects)
+                    .padding(metrics::pad()),
+                button(text("Save").size(metrics::text_md()))
+                    .on_press(Message::SaveProject)
+                    .padding(metrics::pad()),
+                button(text("Save As…").size(metrics::text_md()))
+                    .on_press_maybe(self.active_project().is_some().then_some(Message::SaveLayoutAs))
+                    .padding(metrics::pad()),
+                button(text("Restore Backup…").size(metrics::text_md()))
+                    .on_press_maybe(self.active_project().is_some().then_some(Message::ToggleBackupList))
+                    .padding(metrics::pad()),
+                button(text("Export Code").size(metrics::text_md()))
+                    .on_press(Message::ExportCode)
+                    .padding(metrics::pad()),
+                button(text("View Code").size(metrics::text_md()))
+                    .on_press_maybe(self.active_project().is_some().then_some(Message::ToggleCodePanel))
+                    .padding(metrics::pad()),
+                button(text("Export Example").size(metrics::text_md()))
+                    .on_press_maybe(self.active_project().is_some().then_some(Message::ExportExample))
+                    .padding(metrics::pad()),
+                button(text(export_mode_label).size(metrics::text_md()))
+                    .on_press_maybe(self.active_project().is_some().then_some(Message::ToggleExportMode))
+                    .padding(metrics::pad()),
+                button(text("Save as Snippet").size(metrics::text_md()))
+                    .on_press_maybe(
+                        self.active_project()
+                            .and_then(|p| p.selected_id)
+                            .map(|_| Message::SaveSelectionAsSnippet),
+                    )
+                    .padding(metrics::pad()),
+                button(text("Copy as Code").size(metrics::text_md()))
+                    .on_press_maybe(
+                        self.active_project()
+                            .and_then(|p| p.selected_id)
+                            .map(|_| Message::CopySelectionAsCode),
+                    )
+                    .padding(metrics::pad()),
+                button(text("Extract Component…").size(metrics::text_md()))
+                    .on_press_maybe(
+                        self.active_project()
+                            .and_then(|p| p.selected_id.filter(|id| *id != p.layout.root.id))
+                            .map(|_| Message::ExtractComponent),
+                    )
+                    .padding(metrics::pad()),
+                // Spacer
+                iced::widget::horizontal_space(),
+                // View toggles
+                button(text(invisible_button_label).size(metrics::text_md()))
+                    .on_press(Message::ToggleShowInvisible)
+                    .padding(metrics::pad()),
+                button(text(measurements_button_label).size(metrics::text_md()))
+                    .on_press(Message::ToggleMeasurements)
+                    .padding(metrics::pad()),
+                button(text(dock_button_label).size(metrics::text_md()))
+                    .on_press(Message::ToggleTreeDock)
+                    .padding(metrics::pad()),
+                button(text(history_button_label).size(metrics::text_md()))
+                    .on_press(Message::ToggleHistoryPanel)
+                    .padding(metrics::pad()),
+                // Mode toggle (Ctrl+P / F5)
+                row![
+                    mode_segment("Design", EditorMode::Design),
+                    mode_segment("Preview", EditorMode::Preview),
+                ],
+                button(text("Focus Preview (F11)").size(metrics::text_md()))
+                    .on_press_maybe(self.active_project().is_some().then_some(Message::ToggleFocusPreview))
+                    .padding(metrics::pad()),
+            ]
+            .spacing(5),
+        )
+        .padding(metrics::scaled(5.0))
+        .style(|_theme| container::Style {
+            background: Some(iced::Background::Color(iced::Color::from_rgb(0.2, 0.2, 0.2))),
+            ..Default::default()
+        });
+
+        // Status bar with mode indicator and keyboard hints
+        let load_warning_count = self.active_project().map(|p| p.load_warnings.len());
+        let shortcuts_hint = " | ↑↓←→: Navigate | Del: Delete | Ctrl+D: Duplicate | Ctrl+C/X/V: Clipboard";
+        let status = container(
+            row![
+                text(format!("[{}]", mode_text))
+                    .size(metrics::text_md())
+                    .color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+                status_text,
+                text(format!("{}{}{}", dirty_indicator, history_status, canvas_size_status))
+                    .size(metrics::text_md())
+                    .color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+                iced::widget::horizontal_space(),
+                text(shortcuts_hint)
+                    .size(metrics::text_sm())
+                    .color(iced::Color::from_rgb(0.4, 0.4, 0.4)),
+            ]
+            .push_maybe(load_warning_count.filter(|count| *count > 0).map(|count| {
+                button(text(format!("⚠ {} load warning{}", count, if count == 1 { "" } else { "s" })).size(metrics::text_sm()))
+                    .on_press(Message::ToggleLoadWarnings)
+                    .style(if self.show_load_warnings { button::primary } else { button::text })
+                    .padding(metrics::pad_xy(0.0, 6.0))
+            }))
+            .push(
+                button(text("Stats").size(metrics::text_sm()))
+                    .on_press_maybe(self.active_project().is_some().then_some(Message::ToggleStats))
+                    .style(if self.show_stats { button::primary } else { button::text })
+                    .padding(metrics::pad_xy(0.0, 6.0)),
+            )
+            .push_maybe(
+                self.active_project()
+                    .map(|_| ProblemsPanel::indicator(&self.problems, self.show_problems)),
+            )
+            .spacing(8)
+            .align_y(iced::Alignment::Center)
+        )
+        .padding(metrics::scaled(5.0));
+
+        // Main layout: palette | canvas | inspector, with the tree docked
+        // under the canvas or under the palette
+        let mut center = column![
+            Canvas::size_bar(
+                &self.settings,
+                &self.canvas_width_input,
+                &self.canvas_height_input,
+            ),
+            horizontal_rule(1),
+            canvas,
+        ]
+        .width(Length::Fill);
+
+        let sidebar: Element<Message> = match self.settings.tree_dock {
+            TreeDock::Bottom => {
+                center = center
+                    .push(splitter(
+                        Split::TreeHeight,
+                        self.splitter_drag.is_some_and(|d| d.split == Split::TreeHeight),
+                    ))
+                    .push(
+                        container(tree_view)
+                            .height(Length::Fixed(Split::TreeHeight.clamp(self.settings.tree_height))),
+                    );
+                palette
+            }
+            TreeDock::Left => column![palette, horizontal_rule(1), tree_view]
+                .width(Length::Fixed(metrics::scaled(180.0)))
+                .into(),
+        };
+
+        let main_row = row![
+            sidebar,
+            vertical_rule(1),
+            center,
+            vertical_rule(1),
+            inspector,
+        ]
+        .height(Length::Fill);
+
+        // Full layout with toolbar, main content, and status bar
+        let layout = column![
+            MenuBar::view(self.open_menu),
+            toolbar,
+            self.tab_strip(),
+            horizontal_rule(1),
+            main_row,
+            horizontal_rule(1),
+            status
+        ];
+
+        // Statistics popover anchored above the status bar
+        let layout: Element<Message> = match self.active_project().filter(|_| self.show_stats) {
+            Some(project) => iced::widget::stack![layout, Self::stats_popover(&project.layout.stats())].into(),
+            None => layout.into(),
+        };
+
+        // Full text of a long status message, anchored above it
+        let layout: Element<Message> = match self.status_message.as_ref().filter(|_| self.show_full_status) {
+            Some(status) if status.truncated().is_some() => {
+                iced::widget::stack![layout, Self::full_status_popover(status)].into()
+            }
+            _ => layout,
+        };
+
+        // Unknown fields skipped on load, anchored above the status bar
+        let layout: Element<Message> = match self.active_project().filter(|_| self.show_load_warnings) {
+            Some(project) if !project.load_warnings.is_empty() => {
+                iced::widget::stack![layout, Self::load_warnings_popover(&project.load_warnings)].into()
+            }
+            _ => layout,
+        };
+
+        // Backup list drops down from the toolbar
+        let layout: Element<Message> = match &self.backup_list {
+            Some(backups) => iced::widget::stack![layout, Self::backup_popover(backups)].into(),
+            None => layout,
+        };
+
+        // Recent projects drop down from the toolbar
+        let layout: Element<Message> = if self.show_recent {
+            iced::widget::stack![layout, Self::recent_popover(&self.recent_projects.projects)].into()
+        } else {
+            layout
+        };
+
+        // Code preview covers the editor while open
+        let layout: Element<Message> = match &self.code_preview {
+            Some(preview) => iced::widget::stack![layout, CodePanel::view(preview)].into(),
+            None => layout,
+        };
+
+        // Open menu drops down over everything but a dragged palette item
+        let layout: Element<Message> = match self.open_menu {
+            Some(menu) => iced::widget::stack![layout, MenuBar::dropdown(menu, self.menu_entries(menu))].into(),
+            None => layout,
+        };
+
+        // Right-clicked nodes get their menu at the cursor
+        let layout: Element<Message> = match self.context_menu {
+            Some((id, position)) => {
+                let window = iced::Size::new(self.settings.window_size.width, self.settings.window_size.height);
+                let menu = ContextMenu::view(position, window, self.context_menu_entries(id));
+                iced::widget::stack![layout, menu].into()
+            }
+            None => layout,
+        };
+
+        // Dragged palette items float above everything else
+        let layout: Element<Message> = match self.palette_drag.as_ref().and_then(Palette::ghost) {
+            Some(ghost) => iced::widget::stack![layout, ghost].into(),
+            None => layout,
+        };
+
+        // Picking a template for a new project blocks the editor below
+        let layout: Element<Message> = match &self.template_picker {
+            Some(folder) => iced::widget::stack![layout, TemplatePicker::view(folder)].into(),
+            None => layout,
+        };
+
+        // Deleting a node with children waits for an answer
+        let layout: Element<Message> = match &self.delete_confirm {
+            Some(confirm) => iced::widget::stack![layout, DeleteConfirmDialog::view(confirm)].into(),
+            None => layout,
+        };
+
+        // An error dialog blocks the whole window until dismissed
+        let layout: Element<Message> = match &self.pending_dialog {
+            Some(dialog) => iced::widget::stack![layout, ErrorDialog::view(dialog)].into(),
+            None => layout,
+        };
+
+        RightPressArea::new(layout, Message::RightPressed).into()
     }
 
-    /// Helper to update a node property with history tracking.
-    fn update_node_property<F>(&mut self, id: ComponentId, update_fn: F)
-    where
-        F: FnOnce(&mut LayoutNode),
-    {
-        if let Some(project) = &mut self.project {
-            // Push history before modification
-            project.history.push(project.layout.clone());
-            
-            // Find and update the node
-            if let Some(node) = project.find_node_mut(id) {
-                update_fn(node);
-                tracing::debug!(target: "iced_builder::app::property", %id, "Property updated");
-                project.mark_dirty();
+    /// The entries of a menu, enabled according to the current state.
+    fn menu_entries(&self, menu: Menu) -> Vec<MenuEntry> {
+        let open = self.active_project().is_some();
+        let selected = self.active_project().is_some_and(|p| p.selected_id.is_some());
+        let child_selected = self
+            .active_project()
+            .is_some_and(|p| p.selected_id.is_some_and(|id| id != p.layout.root.id));
+        let when = |enabled: bool, message: Message| enabled.then_some(message);
+
+        match menu {
+            Menu::File => vec![
+                MenuEntry::item("New Project…", Some("Ctrl+N"), Some(Message::NewProject)),
+                MenuEntry::item("Open Project…", Some("Ctrl+O"), Some(Message::OpenProject)),
+                MenuEntry::item(
+                    "Open Recent…",
+                    None,
+                    when(!self.recent_projects.projects.is_empty(), Message::ToggleRecentProjects),
+                ),
+                MenuEntry::Separator,
+                MenuEntry::item("Save", Some("Ctrl+S"), when(open, Message::SaveProject)),
+                MenuEntry::item("Save Layout As…", Some("Ctrl+Shift+S"), when(open, Message::SaveLayoutAs)),
+                MenuEntry::item("Save Project As…", None, when(open, Message::SaveProjectAs)),
+                MenuEntry::item("Restore Backup…", None, when(open, Message::ToggleBackupList)),
+                MenuEntry::item("Close Tab", Some("Ctrl+W"), when(open, Message::CloseTab(self.active))),
+                MenuEntry::Separator,
+                MenuEntry::item("Export Code", Some("Ctrl+E"), when(open, Message::ExportCode)),
+                MenuEntry::item("Export Example", None, when(open, Message::ExportExample)),
+                MenuEntry::item("Export Bundle…", None, when(open, Message::ExportBundle)),
+                MenuEntry::item("Export Outline…", None, when(open, Message::ExportOutline)),
+                MenuEntry::item("Import Bundle…", None, Some(Message::ImportBundle)),
+                MenuEntry::Separator,
+                MenuEntry::item("Quit", Some("Ctrl+Q"), Some(Message::Quit)),
+            ],
+            Menu::Edit => {
+                let history = self.active_project().map(|p| &p.history);
+                vec![
+                    MenuEntry::item("Undo", Some("Ctrl+Z"), when(history.is_some_and(|h| h.can_undo()), Message::Undo)),
+                    MenuEntry::item("Redo", Some("Ctrl+Y"), when(history.is_some_and(|h| h.can_redo()), Message::Redo)),
+                    MenuEntry::Separator,
+                    MenuEntry::item("Cut", Some("Ctrl+X"), when(selected, Message::CutSelected)),
+                    MenuEntry::item("Copy", Some("Ctrl+C"), when(selected, Message::CopySelected)),
+                    MenuEntry::item(
+                        "Copy Selection as Code",
+                        Some("Ctrl+Shift+C"),
+                        when(selected, Message::CopySelectionAsCode),
+                    ),
+                    MenuEntry::item("Copy Layout Outline", None, when(open, Message::CopyOutline)),
+                    MenuEntry::item("Paste", Some("Ctrl+V"), when(open, Message::Paste)),
+                    MenuEntry::Separator,
+                    MenuEntry::item("Duplicate", Some("Ctrl+D"), when(child_selected, Message::DuplicateSelected)),
+                    MenuEntry::item("Delete", Some("Del"), when(child_selected, Message::DeleteSelected)),
+                    MenuEntry::toggle(
+                        "Confirm Deleting Containers",
+                        self.settings.confirm_subtree_delete,
+                        None,
+                        Some(Message::SetConfirmSubtreeDelete(!self.settings.confirm_subtree_delete)),
+                    ),
+                    MenuEntry::Separator,
+                    MenuEntry::item("Find Component", Some("Ctrl+F"), when(open, Message::FindComponent)),
+                ]
+            }
+            Menu::View => vec![
+                MenuEntry::toggle(
+                    "Tree Docked Left",
+                    self.settings.tree_dock == TreeDock::Left,
+                    None,
+                    Some(Message::ToggleTreeDock),
+                ),
+                MenuEntry::toggle(
+                    "Code Panel",
+                    self.code_preview.is_some(),
+                    Some("Ctrl+Shift+E"),
+                    when(open, Message::ToggleCodePanel),
+                ),
+                MenuEntry::toggle("History Panel", self.show_history, None, Some(Message::ToggleHistoryPanel)),
+                MenuEntry::toggle("Problems Panel", self.show_problems, None, Some(Message::ToggleProblemsPanel)),
+                MenuEntry::toggle("Layout Statistics", self.show_stats, None, when(open, Message::ToggleStats)),
+                MenuEntry::Separator,
+                MenuEntry::toggle(
+                    "Preview Mode",
+                    self.mode == EditorMode::Preview,
+                    Some("Ctrl+P"),
+                    Some(Message::TogglePreviewMode),
+                ),
+                MenuEntry::item("Focus Preview", Some("F11"), when(open, Message::ToggleFocusPreview)),
+                MenuEntry::toggle("Show Invisible", self.show_invisible, None, Some(Message::ToggleShowInvisible)),
+                MenuEntry::toggle("Show Spacing", self.show_measurements, None, Some(Message::ToggleMeasurements)),
+                MenuEntry::Separator,
+                MenuEntry::toggle(
+                    "Dark Theme",
+                    self.settings.theme == ThemeChoice::Dark,
+                    None,
+                    Some(Message::ToggleTheme),
+                ),
+                MenuEntry::item(
+                    "Larger UI",
+                    Some("Ctrl+Shift+="),
+                    when(self.settings.ui_scale < metrics::MAX_SCALE, Message::StepUiScale(1)),
+                ),
+                MenuEntry::item(
+                    "Smaller UI",
+                    Some("Ctrl+Shift+-"),
+                    when(self.settings.ui_scale > metrics::MIN_SCALE, Message::StepUiScale(-1)),
+                ),
+                MenuEntry::item(
+                    format!("Reset UI Scale ({}%)", (self.settings.ui_scale * 100.0).round()),
+                    None,
+                    when(self.settings.ui_scale != 1.0, Message::SetUiScale(1.0)),
+                ),
+            ],
+        }
+    }
+
+    /// The entries of the context menu for a node, which is also selected.
+    fn context_menu_entries(&self, id: ComponentId) -> Vec<MenuEntry> {
+        let is_root = self.active_project().is_some_and(|p| p.layout.root.id == id);
+        let when = |enabled: bool, message: Message| enabled.then_some(message);
+
+        let mut entries = vec![
+            MenuEntry::item("Cut", Some("Ctrl+X"), Some(Message::CutSelected)),
+            MenuEntry::item("Copy", Some("Ctrl+C"), Some(Message::CopySelected)),
+            MenuEntry::item("Copy as Code", Some("Ctrl+Shift+C"), Some(Message::CopySelectionAsCode)),
+            MenuEntry::item("Paste", Some("Ctrl+V"), Some(Message::Paste)),
+            MenuEntry::Separator,
+            MenuEntry::item("Duplicate", Some("Ctrl+D"), when(!is_root, Message::DuplicateSelected)),
+            MenuEntry::item("Delete", Some("Del"), when(!is_root, Message::DeleteSelected)),
+        ];
+
+        // Conversions, as in the inspector's type picker
+        let targets = self
+            .active_project()
+            .and_then(|p| p.find_node(id))
+            .map(|node| Project::conversion_targets(&node.widget, is_root))
+            .unwrap_or_default();
+        if !targets.is_empty() {
+            entries.push(MenuEntry::Separator);
+        }
+        entries.extend(targets.into_iter().map(|kind| {
+            let message = if is_root {
+                Message::ChangeRootType(kind)
             } else {
-                // Undo the history push if node not found
-                let _ = project.history.undo(project.layout.clone());
-                tracing::warn!(target: "iced_builder::app::property", %id, "Node not found for property update");
+                Message::ConvertWidget(id, kind)
+            };
+            MenuEntry::item(format!("Change to {}", kind.name()), None, Some(message))
+        }));
+        entries
+    }
+
+    /// Write the generated code to disk in the background.
+    fn export_code(&mut self) -> Task<Message> {
+        tracing::info!(target: targets::CODEGEN, "Exporting code");
+        let Some(project) = self.active_project() else {
+            self.status_message = Some(StatusMessage::info("No project open".to_string()));
+            return Task::none();
+        };
+        let blockers = project.export_blockers();
+        if !blockers.is_empty() {
+            return self.report_validation_failure("Export", &blockers);
+        }
+        let output = project.output_path();
+        let snapshot = project.snapshot();
+        self.status_message = Some(StatusMessage::info("Exporting…".to_string()));
+        let title = "Export failed";
+        Task::perform(
+            in_background(title, output.clone(), move || {
+                let formatted = snapshot
+                    .export()
+                    .map_err(|e| DialogState::error(title, Some(&output), &e))?;
+                tracing::debug!(target: targets::CODEGEN, code_length = formatted.code.len(), "Code generated");
+                Ok(export_summary(&snapshot.config, &formatted))
+            }),
+            Message::CodeExported,
+        )
+    }
+
+    /// Report the first few errors that blocked `action` and select the node
+    /// with the first one.
+    fn report_validation_failure(&mut self, action: &str, errors: &[ValidationError]) -> Task<Message> {
+        const SHOWN: usize = 3;
+        tracing::warn!(target: targets::CODEGEN, count = errors.len(), "{} blocked by validation errors", action);
+        let mut shown: Vec<_> = errors
+            .iter()
+            .take(SHOWN)
+            .map(|e| format!("{}: {}", e.path, e.message))
+            .collect();
+        if errors.len() > SHOWN {
+            shown.push(format!("and {} more", errors.len() - SHOWN));
+        }
+        self.status_message = Some(StatusMessage::error(format!(
+            "{} blocked by {} validation error(s): {}",
+            action,
+            errors.len(),
+            shown.join("; ")
+        )));
+        self.refresh_problems();
+        self.show_problems = true;
+
+        let (Some(project), Some(first)) = (self.active_project_mut(), errors.first()) else {
+            return Task::none();
+        };
+        project.selected_id = Some(first.node_id);
+        self.reveal_selection()
+    }
+
+    /// Regenerate the open code preview from the current layout, off the UI thread.
+    fn regenerate_code_preview(&mut self) -> Task<Message> {
+        let (Some(preview), Some(project)) = (&mut self.code_preview, self.projects.get(self.active)) else {
+            return Task::none();
+        };
+        let revision = project.revision;
+        preview.revision = Some(revision);
+        let layout = project.inlined_layout();
+        let config = project.config.clone();
+        let template = project.template_path();
+        let rustfmt = project.rustfmt_options();
+        let backend = if preview.format {
+            config.format_backend
+        } else {
+            FormatBackend::None
+        };
+        Task::perform(
+            async move {
+                let code = crate::codegen::template::generate_code_with_template(&layout, &config, template.as_deref())
+                    .map_err(|e| e.to_string())?;
+                Ok(crate::codegen::stamp(&crate::util::try_format_rust_code(&code, backend, &rustfmt).code))
+            },
+            move |code| Message::CodePreviewReady(revision, code),
+        )
+    }
+
+    /// React to the layout file changing on disk: reload a clean project,
+    /// ask what to do with unsaved edits otherwise.
+    fn handle_external_change(&mut self) -> Task<Message> {
+        let Some(project) = self.projects.get(self.active) else {
+            return Task::none();
+        };
+        if self.external_change_prompt {
+            return Task::none();
+        }
+        if !project.dirty {
+            self.reload_layout();
+            return Task::none();
+        }
+
+        tracing::info!(target: targets::APP, "Layout file changed on disk with unsaved edits");
+        self.external_change_prompt = true;
+        let file = project.config.layout_file().display().to_string();
+        Task::perform(
+            async move {
+                rfd::AsyncMessageDialog::new()
+                    .set_title("Layout changed on disk")
+                    .set_description(format!(
+                        "{} was changed outside Iced Builder, and you have unsaved changes. \
+                         Reload it and discard your changes, keep your changes, \
+                         or save your changes to another file first?",
+                        file
+                    ))
+                    .set_level(rfd::MessageLevel::Warning)
+                    .set_buttons(rfd::MessageButtons::YesNoCancelCustom(
+                        "Reload".to_string(),
+                        "Keep Mine".to_string(),
+                        "Save As…".to_string(),
+                    ))
+                    .show()
+                    .await
+            },
+            |result| {
+                Message::ExternalChangeChosen(match result {
+                    rfd::MessageDialogResult::Custom(label) if label == "Reload" => ExternalChangeChoice::Reload,
+                    rfd::MessageDialogResult::Custom(label) if label.starts_with("Save As") => {
+                        ExternalChangeChoice::SaveAs
+                    }
+                    rfd::MessageDialogResult::Yes => ExternalChangeChoice::Reload,
+                    _ => ExternalChangeChoice::KeepMine,
+                })
+            },
+        )
+    }
+
+    /// Load the layout file from disk into the open project, returning
+    /// whether it loaded.
+    fn reload_layout(&mut self) -> bool {
+        let Some(project) = self.active_project_mut() else {
+            return false;
+        };
+        let file = project.config.layout_file().display().to_string();
+        match project.reload_layout() {
+            Ok(()) => {
+                self.inline_edit = None;
+                self.status_message = Some(StatusMessage::success(format!("Reloaded {} after it changed on disk", file)));
+                self.report_load_warnings();
+                true
             }
+            Err(e) => {
+                // Don't report the same broken file on every poll
+                project.keep_local_layout();
+                tracing::warn!(target: targets::IO, error = %e, "Failed to reload layout");
+                self.status_message = Some(StatusMessage::error(format!("{} changed on disk but could not be reloaded: {}", file, e)));
+                false
+            }
+        }
+    }
+
+    /// Project and revision the panic hook should hold: the active project,
+    /// while it has unsaved changes.
+    fn crash_snapshot_target(&self) -> Option<(std::path::PathBuf, u64)> {
+        self.active_project().filter(|p| p.dirty).map(|p| (p.path.clone(), p.revision))
+    }
+
+    /// Clear the panic hook's layout as soon as nothing is left unsaved.
+    ///
+    /// New unsaved changes are copied by [`Message::CrashSnapshotDue`] on a
+    /// timer instead, so edits never clone the whole layout.
+    fn update_crash_snapshot(&mut self) {
+        if self.crash_snapshot.is_some() && self.crash_snapshot_target().is_none() {
+            self.crash_handle.clear();
+            self.crash_snapshot = None;
+        }
+    }
+
+    /// Give the panic hook the active project's layout if it has unsaved
+    /// changes the hook hasn't seen.
+    fn refresh_crash_snapshot(&mut self) {
+        let current = self.crash_snapshot_target();
+        if current == self.crash_snapshot {
+            return;
+        }
+        match self.projects.get(self.active).filter(|p| p.dirty) {
+            Some(project) => self.crash_handle.set(Some(&project.path), &project.layout),
+            None => self.crash_handle.clear(),
+        }
+        self.crash_snapshot = current;
+    }
+
+    /// Start the autosave timer while any open project has unsaved changes.
+    fn schedule_autosave(&mut self) -> Task<Message> {
+        let interval = self.settings.autosave_interval_secs;
+        let dirty = self.projects.iter().any(|p| p.dirty);
+        if interval == 0 || !dirty || self.autosave_scheduled {
+            return Task::none();
+        }
+        self.autosave_scheduled = true;
+        Task::perform(delay(Duration::from_secs(interval)), |()| Message::AutosaveDue)
+    }
+
+    /// Refresh the problem list once edits settle, or straight away when
+    /// there is no list for this project yet.
+    fn schedule_problems(&mut self) -> Task<Message> {
+        let Some(revision) = self.active_project().map(|p| p.revision) else {
+            self.problems = Problems::default();
+            return Task::none();
+        };
+        if self.problems.revision.is_none() {
+            self.refresh_problems();
+            return Task::none();
+        }
+        if self.problems.revision == Some(revision) || self.problems.scheduled.is_some() {
+            return Task::none();
+        }
+        self.problems.scheduled = Some(revision);
+        Task::perform(
+            async move {
+                delay(REVALIDATE_DELAY).await;
+                revision
+            },
+            Message::ProblemsDue,
+        )
+    }
+
+    /// Point out config values export can't work with, listing them in the
+    /// problems panel.
+    fn report_config_issues(&mut self) {
+        let Some(project) = self.active_project() else {
+            return;
+        };
+        let issues = project.config.validate(&project.path);
+        if issues.is_empty() {
+            return;
+        }
+        tracing::warn!(target: targets::APP, count = issues.len(), "Project config has problems");
+        self.status_message = Some(StatusMessage::error(match issues.as_slice() {
+            [issue] => format!("{}: {}", crate::io::CONFIG_FILENAME, issue),
+            _ => format!("{} has {} problems", crate::io::CONFIG_FILENAME, issues.len()),
+        }));
+        self.show_problems = true;
+        self.refresh_problems();
+    }
+
+    /// Validate the active project's layout now.
+    fn refresh_problems(&mut self) {
+        let Some(project) = self.projects.get(self.active) else {
+            return;
+        };
+        self.problems.list = project.validate();
+        self.problems.revision = Some(project.revision);
+        if let Some(state) = &self.problems.state {
+            self.problems.list.extend(state.check(&project.layout));
+        }
+    }
+
+    /// Schedule a delayed regeneration of the open code preview once the
+    /// layout has changed since it was generated.
+    fn schedule_code_preview(&mut self) -> Task<Message> {
+        let (Some(preview), Some(project)) = (&mut self.code_preview, self.projects.get(self.active)) else {
+            return Task::none();
+        };
+        if preview.revision == Some(project.revision) || preview.scheduled.is_some() {
+            return Task::none();
+        }
+        let revision = project.revision;
+        preview.scheduled = Some(revision);
+        Task::perform(
+            async move {
+                delay(REGENERATE_DELAY).await;
+                revision
+            },
+            Message::CodePreviewDue,
+        )
+    }
+
+    /// Render the recently opened projects, newest first.
+    fn recent_popover(recent: &[RecentProject]) -> Element<'_, Message> {
+        let mut content = column![text("Open recent").size(metrics::text_lg())]
+            .spacing(4)
+            .width(Length::Fixed(metrics::scaled(320.0)));
+        if recent.is_empty() {
+            content = content.push(
+                text("No recent projects.")
+                    .size(metrics::text_md())
+                    .color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+            );
+        }
+        for project in recent {
+            content = content.push(
+                button(
+                    column![
+                        text(&project.name).size(metrics::text_md()),
+                        text(project.path.display().to_string())
+                            .size(metrics::text_xs())
+                            .color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+                    ]
+                    .spacing(1),
+                )
+                .on_press(Message::OpenProjectAt(project.path.clone()))
+                .style(button::text)
+                .width(Length::Fill)
+                .padding(metrics::pad_sm()),
+            );
+        }
+
+        let card = container(content)
+            .padding(metrics::pad_panel())
+            .style(|theme: &iced::Theme| container::Style {
+                background: Some(theme.extended_palette().background.weak.color.into()),
+                border: iced::Border {
+                    color: theme.extended_palette().background.strong.color,
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                ..Default::default()
+            });
+
+        container(card)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(iced::Padding { top: metrics::scaled(40.0 + MENU_BAR_HEIGHT), right: 0.0, bottom: 0.0, left: 100.0 })
+            .into()
+    }
+
+    /// Render the list of layout backups to restore, newest first.
+    fn backup_popover(backups: &[std::path::PathBuf]) -> Element<'_, Message> {
+        let mut content = column![text("Restore from backup").size(metrics::text_lg())]
+            .spacing(4)
+            .width(Length::Fixed(metrics::scaled(260.0)));
+        if backups.is_empty() {
+            content = content.push(
+                text("No backups yet. One is kept each time the project is saved.")
+                    .size(metrics::text_md())
+                    .color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+            );
+        }
+        for backup in backups {
+            content = content.push(
+                button(text(crate::io::backups::backup_label(backup)).size(metrics::text_md()))
+                    .on_press(Message::RestoreBackup(backup.clone()))
+                    .style(button::text)
+                    .width(Length::Fill)
+                    .padding(metrics::pad_sm()),
+            );
+        }
+
+        let card = container(content)
+            .padding(metrics::pad_panel())
+            .style(|theme: &iced::Theme| container::Style {
+                background: Some(theme.extended_palette().background.weak.color.into()),
+                border: iced::Border {
+                    color: theme.extended_palette().background.strong.color,
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                ..Default::default()
+            });
+
+        container(card)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(iced::Padding { top: metrics::scaled(40.0 + MENU_BAR_HEIGHT), right: 0.0, bottom: 0.0, left: 160.0 })
+            .into()
+    }
+
+    /// Render the unknown fields skipped when the layout was loaded.
+    fn load_warnings_popover(warnings: &[crate::io::LoadWarning]) -> Element<'_, Message> {
+        let mut content = column![
+            text("Loaded with warnings").size(metrics::text_lg()),
+            text("This layout has fields this version of Iced Builder doesn't know, probably from a newer version. They are kept on disk until you save.")
+                .size(metrics::text_sm())
+                .color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+            horizontal_rule(1),
+        ]
+        .spacing(4)
+        .width(Length::Fixed(metrics::scaled(360.0)));
+        for warning in warnings {
+            content = content.push(text(warning.to_string()).size(metrics::text_md()));
+        }
+
+        let card = container(iced::widget::scrollable(content).height(Length::Shrink))
+            .padding(metrics::pad_panel())
+            .max_height(320.0)
+            .style(|theme: &iced::Theme| container::Style {
+                background: Some(theme.extended_palette().background.weak.color.into()),
+                border: iced::Border {
+                    color: theme.extended_palette().background.strong.color,
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                ..Default::default()
+            });
+
+        container(card)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Right)
+            .align_y(iced::alignment::Vertical::Bottom)
+            .padding(iced::Padding { top: 0.0, right: 8.0, bottom: metrics::scaled(32.0), left: 0.0 })
+            .into()
+    }
+
+    /// Render a tab per open project, the active one highlighted, each with a close button.
+    fn tab_strip(&self) -> Element<'_, Message> {
+        let tabs = self.projects.iter().enumerate().map(|(index, project)| {
+            let active = index == self.active;
+            let label = format!("{}{}", project.layout.name, if project.dirty { " •" } else { "" });
+            row![
+                button(text(label).size(metrics::text_md()))
+                    .on_press(Message::SelectTab(index))
+                    .style(if active { button::primary } else { button::secondary })
+                    .padding(metrics::pad_xy(4.0, 10.0)),
+                button(text("×").size(metrics::text_md()))
+                    .on_press(Message::CloseTab(index))
+                    .style(if active { button::primary } else { button::secondary })
+                    .padding(metrics::pad_xy(4.0, 6.0)),
+            ]
+            .into()
+        });
+
+        container(row(tabs).spacing(4))
+            .width(Length::Fill)
+            .padding(iced::Padding { top: 4.0, right: 5.0, bottom: 0.0, left: 5.0 })
+            .into()
+    }
+
+    /// Render the full text of a truncated status message.
+    fn full_status_popover(status: &StatusMessage) -> Element<'_, Message> {
+        let content = column![
+            text(&status.text).size(metrics::text_md()),
+            button(text("Close").size(metrics::text_sm()))
+                .on_press(Message::ToggleFullStatus)
+                .padding(metrics::pad_xy(2.0, 8.0)),
+        ]
+        .spacing(8)
+        .width(Length::Fixed(metrics::scaled(480.0)));
+
+        let card = container(iced::widget::scrollable(content).height(Length::Shrink))
+            .padding(metrics::pad_panel())
+            .max_height(320.0)
+            .style(|theme: &iced::Theme| container::Style {
+                background: Some(theme.extended_palette().background.weak.color.into()),
+                border: iced::Border {
+                    color: theme.extended_palette().background.strong.color,
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                ..Default::default()
+            });
+
+        container(card)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Left)
+            .align_y(iced::alignment::Vertical::Bottom)
+            .padding(iced::Padding { top: 0.0, right: 0.0, bottom: metrics::scaled(32.0), left: 8.0 })
+            .into()
+    }
+
+    /// Render the layout statistics popover shown from the status bar.
+    fn stats_popover(stats: &LayoutStats) -> Element<'static, Message> {
+        let line = |label: &str, value: usize| {
+            row![
+                text(label.to_string()).size(metrics::text_md()).width(Length::Fill),
+                text(value.to_string()).size(metrics::text_md()),
+            ]
+            .spacing(12)
+        };
+
+        let mut content = column![
+            text("Layout Statistics").size(metrics::text_lg()),
+            line("Nodes", stats.node_count),
+            line("Max depth", stats.max_depth),
+            line(
+                &format!("Containers with >{} children", LayoutStats::WIDE_CONTAINER_CHILDREN),
+                stats.wide_containers,
+            ),
+            line("Empty message stubs", stats.empty_message_stubs),
+            horizontal_rule(1),
+        ]
+        .spacing(4)
+        .width(Length::Fixed(metrics::scaled(240.0)));
+        for (name, count) in &stats.type_counts {
+            content = content.push(line(name, *count));
+        }
+
+        let card = container(content)
+            .padding(metrics::pad_panel())
+            .style(|theme: &iced::Theme| container::Style {
+                background: Some(theme.extended_palette().background.weak.color.into()),
+                border: iced::Border {
+                    color: theme.extended_palette().background.strong.color,
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                ..Default::default()
+            });
+
+        container(card)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Right)
+            .align_y(iced::alignment::Vertical::Bottom)
+            .padding(iced::Padding { top: 0.0, right: 8.0, bottom: metrics::scaled(32.0), left: 0.0 })
+            .into()
+    }
+
+    /// Handle subscriptions (keyboard shortcuts).
+    /// Map a key press during focus preview: saving and quitting still
+    /// work, and F11 or Escape leave the preview.
+    fn focus_preview_key(key: iced::keyboard::Key, modifiers: iced::keyboard::Modifiers) -> Option<Message> {
+        use iced::keyboard::{key::Named, Key};
+
+        match (key.as_ref(), modifiers.command(), modifiers.shift()) {
+            (Key::Character("s"), true, false) => Some(Message::SaveProject),
+            (Key::Character("q"), true, false) => Some(Message::Quit),
+            (Key::Named(Named::F11), false, false) => Some(Message::ToggleFocusPreview),
+            // Escape goes through deselection, which leaves the preview first
+            (Key::Named(Named::Escape), false, false) => Some(Message::DeselectComponent),
+            _ => None,
         }
     }
 
-    /// Render the application view.
-    pub fn view(&self) -> Element<'_, Message> {
-        let palette = Palette::view();
+    pub fn subscription(&self) -> Subscription<Message> {
+        use iced::{event, keyboard, mouse};
+
+        // Focus preview only shows the layout, so it gets a reduced key map
+        // without mode switches or edits
+        let keys = if self.focus_preview.is_some() {
+            keyboard::on_key_press(Self::focus_preview_key)
+        } else {
+            keyboard::on_key_press(|key, modifiers| {
+                match (key.as_ref(), modifiers.command(), modifiers.shift()) {
+                    // File operations
+                    (keyboard::Key::Character("z"), true, false) => Some(Message::Undo),
+                    (keyboard::Key::Character("z"), true, true) => Some(Message::Redo),
+                    (keyboard::Key::Character("y"), true, false) => Some(Message::Redo),
+                    (keyboard::Key::Character("s"), true, false) => Some(Message::SaveProject),
+                    (keyboard::Key::Character("s" | "S"), true, true) => Some(Message::SaveLayoutAs),
+                    (keyboard::Key::Character("e"), true, false) => Some(Message::ExportCode),
+                    (keyboard::Key::Character("e" | "E"), true, true) => Some(Message::ToggleCodePanel),
+                    (keyboard::Key::Character("n"), true, false) => Some(Message::NewProject),
+                    (keyboard::Key::Character("o"), true, false) => Some(Message::OpenProject),
+                    (keyboard::Key::Character("q"), true, false) => Some(Message::Quit),
+                    (keyboard::Key::Character("w"), true, false) => Some(Message::CloseActiveTab),
+                    // Shift turns = into + and - into _ on most layouts
+                    (keyboard::Key::Character("=" | "+"), true, true) => Some(Message::StepUiScale(1)),
+                    (keyboard::Key::Character("-" | "_"), true, true) => Some(Message::StepUiScale(-1)),
+                    (keyboard::Key::Character("d"), true, false) => Some(Message::DuplicateSelected),
+                    (keyboard::Key::Character("c"), true, false) => Some(Message::CopySelected),
+                    (keyboard::Key::Character("c" | "C"), true, true) => Some(Message::CopySelectionAsCode),
+                    (keyboard::Key::Character("x"), true, false) => Some(Message::CutSelected),
+                    (keyboard::Key::Character("v"), true, false) => Some(Message::Paste),
+                    (keyboard::Key::Character("f"), true, false) => Some(Message::FindComponent),
+                    (keyboard::Key::Named(keyboard::key::Named::F3), false, false) => Some(Message::FindNext),
+                    (keyboard::Key::Named(keyboard::key::Named::F3), false, true) => Some(Message::FindPrevious),
+                    // Preview mode toggle
+                    (keyboard::Key::Character("p"), true, false) => Some(Message::TogglePreviewMode),
+                    (keyboard::Key::Named(keyboard::key::Named::F5), false, false) => Some(Message::TogglePreviewMode),
+                    (keyboard::Key::Named(keyboard::key::Named::F11), false, false) => {
+                        Some(Message::ToggleFocusPreview)
+                    }
+                    // Navigation
+                    (keyboard::Key::Named(keyboard::key::Named::ArrowDown), false, false) => {
+                        Some(Message::SelectNext)
+                    }
+                    (keyboard::Key::Named(keyboard::key::Named::ArrowUp), false, false) => {
+                        Some(Message::SelectPrevious)
+                    }
+                    (keyboard::Key::Named(keyboard::key::Named::ArrowLeft), false, false) => {
+                        Some(Message::SelectParent)
+                    }
+                    (keyboard::Key::Named(keyboard::key::Named::ArrowRight), false, false) => {
+                        Some(Message::SelectFirstChild)
+                    }
+                    (keyboard::Key::Named(keyboard::key::Named::Home), false, false) => {
+                        Some(Message::SelectFirstRow)
+                    }
+                    (keyboard::Key::Named(keyboard::key::Named::End), false, false) => {
+                        Some(Message::SelectLastRow)
+                    }
+                    // Delete and deselect
+                    (keyboard::Key::Named(keyboard::key::Named::Delete), false, false) => {
+                        Some(Message::DeleteSelected)
+                    }
+                    (keyboard::Key::Named(keyboard::key::Named::Backspace), false, false) => {
+                        Some(Message::DeleteSelected)
+                    }
+                    (keyboard::Key::Named(keyboard::key::Named::Escape), false, false) => {
+                        Some(Message::DeselectComponent)
+                    }
+                    _ => None,
+                }
+            })
+        };
+
+        // Tree drags end wherever the button is released, even outside the tree.
+        // Modifier changes are tracked for Alt-click insertion, and files
+        // dropped anywhere on the window are opened.
+        let releases = event::listen_with(|event, _status, _window| match event {
+            iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                Some(Message::TreeDragEnd)
+            }
+            iced::Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                Some(Message::ModifiersChanged(modifiers))
+            }
+            iced::Event::Window(iced::window::Event::FileDropped(path)) => Some(Message::FileDropped(path)),
+            _ => None,
+        });
 
-        let canvas: Element<Message> = match &self.project {
-            Some(project) => Canvas::view(&project.layout.root, project.selected_id, self.mode),
-            None => Canvas::view_empty(),
+        // Splitter drags follow the cursor anywhere in the window
+        let splitter_events = if self.splitter_drag.is_some() {
+            event::listen_with(|event, _status, _window| match event {
+                iced::Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                    Some(Message::SplitterDragged(position))
+                }
+                iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                    Some(Message::SplitterReleased)
+                }
+                _ => None,
+            })
+        } else {
+            Subscription::none()
         };
 
-        let inspector: Element<Message> = match &self.project {
-            Some(project) => {
-                let selected_node = project
-                    .selected_id
-                    .and_then(|id| project.find_node(id));
-                Inspector::view(selected_node, project.selected_id)
-            }
-            None => Inspector::view(None, None),
+        // Palette presses track the cursor to tell drags from clicks
+        let palette_events = if self.palette_drag.is_some() {
+            event::listen_with(|event, _status, _window| match event {
+                iced::Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                    Some(Message::PaletteDragMoved(position))
+                }
+                iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                    Some(Message::PaletteDragEnd)
+                }
+                _ => None,
+            })
+        } else {
+            Subscription::none()
         };
 
-        let tree_view: Element<Message> = match &self.project {
-            Some(project) => TreeView::view(&project.layout.root, project.selected_id),
-            None => container(text("No project")).into(),
+        // Poll the open layout file for outside edits; dropped with the project
+        let layout_watch = match self.active_project() {
+            Some(project) => Subscription::run_with_id(
+                ("layout-watch", project.layout_path()),
+                ticks(LAYOUT_POLL_INTERVAL, Message::LayoutFilePoll),
+            ),
+            None => Subscription::none(),
         };
 
-        // Build status bar content
-        let status_text = self.status_message.as_deref().unwrap_or("Ready");
-        let history_status = match &self.project {
-            Some(project) => {
-                let can_undo = project.history.can_undo();
-                let can_redo = project.history.can_redo();
-                format!(
-                    " | Undo: {} | Redo: {}",
-                    if can_undo { "Ctrl+Z" } else { "-" },
-                    if can_redo { "Ctrl+Y" } else { "-" }
-                )
+        let window_events = Subscription::batch([
+            iced::window::resize_events().map(|(_, size)| Message::WindowResized(size)),
+            iced::window::close_requests().map(Message::WindowCloseRequested),
+        ]);
+
+        // Tick while a status message is waiting to fade
+        let status_fade = match &self.status_message {
+            Some(status) if status.fades() => {
+                Subscription::run_with_id("status-fade", ticks(STATUS_TICK_INTERVAL, Message::TickStatus))
             }
-            None => String::new(),
-        };
-        
-        let dirty_indicator = match &self.project {
-            Some(project) if project.dirty => " [unsaved]",
-            _ => "",
-        };
-        
-        // Mode indicator
-        let mode_text = match self.mode {
-            EditorMode::Design => "Design",
-            EditorMode::Preview => "Preview",
+            _ => Subscription::none(),
         };
 
-        // Preview mode toggle button
-        let mode_button_label = match self.mode {
-            EditorMode::Design => "Preview (Ctrl+P)",
-            EditorMode::Preview => "Design (Ctrl+P)",
+        // Tick while the panic hook holds an outdated layout
+        let crash_snapshot = if self.crash_snapshot_target().is_some_and(|t| Some(t) != self.crash_snapshot) {
+            Subscription::run_with_id("crash-snapshot", ticks(CRASH_SNAPSHOT_INTERVAL, Message::CrashSnapshotDue))
+        } else {
+            Subscription::none()
         };
 
-        // Toolbar with file operations
-        let toolbar = container(
-            row![
-                button(text("New Project").size(12))
-                    .on_press(Message::NewProject)
-                    .padding([4, 8]),
-                button(text("Open Project").size(12))
-                    .on_press(Message::OpenProject)
-                    .padding([4, 8]),
-                button(text("Save").size(12))
-                    .on_press(Message::SaveProject)
-                    .padding([4, 8]),
-                button(text("Export Code").size(12))
-                    .on_press(Message::ExportCode)
-                    .padding([4, 8]),
-                // Spacer
-                iced::widget::horizontal_space(),
-                // Mode toggle
-                button(text(mode_button_label).size(12))
-                    .on_press(Message::TogglePreviewMode)
-                    .padding([4, 8]),
-            ]
-            .spacing(5),
-        )
-        .padding(5)
-        .style(|_theme| container::Style {
-            background: Some(iced::Background::Color(iced::Color::from_rgb(0.2, 0.2, 0.2))),
-            ..Default::default()
-        });
-
-        // Status bar with mode indicator and keyboard hints
-        let shortcuts_hint = " | ↑↓←→: Navigate | Del: Delete | Ctrl+D: Duplicate";
-        let status = container(
-            row![
-                text(format!("[{}] {}{}{}", mode_text, status_text, dirty_indicator, history_status))
-                    .size(12)
-                    .color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
-                iced::widget::horizontal_space(),
-                text(shortcuts_hint)
-                    .size(11)
-                    .color(iced::Color::from_rgb(0.4, 0.4, 0.4)),
-            ]
-        )
-        .padding(5);
+        Subscription::batch([
+            keys,
+            releases,
+            splitter_events,
+            palette_events,
+            layout_watch,
+            window_events,
+            status_fade,
+            crash_snapshot,
+        ])
+    }
+}
 
-        // Main layout: palette | canvas | inspector
-        let main_row = row![
-            palette,
-            vertical_rule(1),
-            column![canvas, horizontal_rule(1), tree_view].width(Length::Fill),
-            vertical_rule(1),
-            inspector,
-        ]
-        .height(Length::Fill);
+/// Open the project at `path`, describing a failure for the error dialog.
+fn open_project(path: &std::path::Path) -> Result<Box<Project>, DialogState> {
+    Project::open(path)
+        .map(Box::new)
+        .map_err(|e| DialogState::error("Failed to open project", Some(path), &e))
+}
 
-        // Full layout with toolbar, main content, and status bar
-        column![toolbar, horizontal_rule(1), main_row, horizontal_rule(1), status].into()
+/// Describe an export for the status bar, e.g. "Code exported to
+/// src/ui.rs (formatted with rustfmt)".
+fn export_summary(config: &ProjectConfig, formatted: &Formatted) -> String {
+    let mut notes = Vec::new();
+    if formatted.backend != FormatBackend::None {
+        notes.push(format!("formatted with {}", formatted.backend));
     }
+    let writes_stubs = config.generate_message_stub || config.generate_update_skeleton;
+    if writes_stubs && config.export_mode == ExportMode::ViewOnly {
+        notes.push(format!("stubs in {}", config.message_stub_file.display()));
+    }
+    if config.i18n_mode == I18nMode::Keys {
+        notes.push(format!("strings in {}", config.strings_file.display()));
+    }
+    if notes.is_empty() {
+        format!("Code exported to {}", config.output_file.display())
+    } else {
+        format!("Code exported to {} ({})", config.output_file.display(), notes.join("; "))
+    }
+}
 
-    /// Handle subscriptions (keyboard shortcuts).
-    pub fn subscription(&self) -> Subscription<Message> {
-        use iced::keyboard;
-
-        keyboard::on_key_press(|key, modifiers| {
-            match (key.as_ref(), modifiers.command(), modifiers.shift()) {
-                // File operations
-                (keyboard::Key::Character("z"), true, false) => Some(Message::Undo),
-                (keyboard::Key::Character("z"), true, true) => Some(Message::Redo),
-                (keyboard::Key::Character("y"), true, false) => Some(Message::Redo),
-                (keyboard::Key::Character("s"), true, false) => Some(Message::SaveProject),
-                (keyboard::Key::Character("e"), true, false) => Some(Message::ExportCode),
-                (keyboard::Key::Character("n"), true, false) => Some(Message::NewProject),
-                (keyboard::Key::Character("o"), true, false) => Some(Message::OpenProject),
-                (keyboard::Key::Character("d"), true, false) => Some(Message::DuplicateSelected),
-                // Preview mode toggle
-                (keyboard::Key::Character("p"), true, false) => Some(Message::TogglePreviewMode),
-                // Navigation
-                (keyboard::Key::Named(keyboard::key::Named::ArrowDown), false, false) => {
-                    Some(Message::SelectNext)
-                }
-                (keyboard::Key::Named(keyboard::key::Named::ArrowUp), false, false) => {
-                    Some(Message::SelectPrevious)
-                }
-                (keyboard::Key::Named(keyboard::key::Named::ArrowLeft), false, false) => {
-                    Some(Message::SelectParent)
-                }
-                (keyboard::Key::Named(keyboard::key::Named::ArrowRight), false, false) => {
-                    Some(Message::SelectFirstChild)
-                }
-                // Delete and deselect
-                (keyboard::Key::Named(keyboard::key::Named::Delete), false, false) => {
-                    Some(Message::DeleteSelected)
-                }
-                (keyboard::Key::Named(keyboard::key::Named::Backspace), false, false) => {
-                    Some(Message::DeleteSelected)
-                }
-                (keyboard::Key::Named(keyboard::key::Named::Escape), false, false) => {
-                    Some(Message::DeselectComponent)
-                }
-                _ => None,
+/// Run `work` on a thread of its own once the future is polled, so reading
+/// and writing large projects neither freezes the window nor ties up the
+/// executor. A panic in `work` is reported as a failure titled `title`.
+async fn in_background<T: Send + 'static>(
+    title: &'static str,
+    path: std::path::PathBuf,
+    work: impl FnOnce() -> Result<T, DialogState> + Send + 'static,
+) -> Result<T, DialogState> {
+    let (sender, receiver) = iced::futures::channel::oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(work());
+    });
+    receiver.await.unwrap_or_else(|_| {
+        let error = std::io::Error::other("the background task stopped unexpectedly");
+        Err(DialogState::error(title, Some(&path), &error))
+    })
+}
+
+/// Wait for `duration` on a thread of its own, leaving the executor free
+/// for other tasks in the meantime.
+async fn delay(duration: Duration) {
+    let (sender, receiver) = iced::futures::channel::oneshot::channel();
+    std::thread::spawn(move || {
+        std::thread::sleep(duration);
+        let _ = sender.send(());
+    });
+    let _ = receiver.await;
+}
+
+/// Emit `message` every `interval` from a thread that stops once the
+/// subscription is dropped.
+fn ticks(interval: Duration, message: Message) -> impl iced::futures::Stream<Item = Message> {
+    iced::stream::channel(1, move |mut output| async move {
+        std::thread::spawn(move || loop {
+            std::thread::sleep(interval);
+            if output.is_closed() {
+                break;
             }
-        })
-    }
+            // A full channel means a tick is still pending; skip this one
+            let _ = output.try_send(message.clone());
+        });
+    })
 }
 
 /// Create a new LayoutNode for the given widget kind.
@@ -906,3 +5221,877 @@ fn create_node_for_kind(kind: WidgetKind) -> LayoutNode {
 
     LayoutNode::new(widget)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_messages_fade_unless_errors() {
+        let info = StatusMessage::info("Project opened");
+        let error = StatusMessage::error("Failed to save: disk full");
+        let later = info.set_at + STATUS_FADE_AFTER;
+
+        assert!(!info.is_expired(info.set_at));
+        assert!(info.is_expired(later));
+        assert!(StatusMessage::success("Project saved").is_expired(later + Duration::from_secs(1)));
+        assert!(!error.is_expired(later + STATUS_FADE_AFTER));
+    }
+
+    #[test]
+    fn test_set_mode_switches_and_view_builds_in_both_modes() {
+        let temp = tempfile::tempdir().unwrap();
+        // Keep the recent projects list away from the user's config
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings::default());
+        let project = Project::create(&temp.path().join("project"), None).unwrap();
+        let root = project.layout.root.id;
+        app.open_tab(project);
+        let _ = app.update(Message::SelectComponent(root));
+        let _ = app.view();
+
+        let _ = app.update(Message::SetMode(EditorMode::Preview));
+        assert_eq!(app.mode, EditorMode::Preview);
+        let _ = app.view();
+
+        let _ = app.update(Message::TogglePreviewMode);
+        assert_eq!(app.mode, EditorMode::Design);
+        let _ = app.update(Message::SetMode(EditorMode::Design));
+        assert_eq!(app.mode, EditorMode::Design);
+    }
+
+    #[test]
+    fn test_focus_preview_keys_do_not_edit() {
+        use iced::keyboard::{key::Named, Key, Modifiers};
+
+        let key = |key: Key, modifiers: Modifiers| App::focus_preview_key(key, modifiers);
+        for (pressed, modifiers) in [
+            (Key::Character("p".into()), Modifiers::COMMAND),
+            (Key::Character("d".into()), Modifiers::COMMAND),
+            (Key::Character("v".into()), Modifiers::COMMAND),
+            (Key::Character("x".into()), Modifiers::COMMAND),
+            (Key::Character("z".into()), Modifiers::COMMAND),
+            (Key::Named(Named::F5), Modifiers::empty()),
+            (Key::Named(Named::Delete), Modifiers::empty()),
+            (Key::Named(Named::Backspace), Modifiers::empty()),
+        ] {
+            assert!(key(pressed.clone(), modifiers).is_none(), "{pressed:?} is mapped");
+        }
+        assert!(matches!(key(Key::Named(Named::F11), Modifiers::empty()), Some(Message::ToggleFocusPreview)));
+        assert!(matches!(key(Key::Named(Named::Escape), Modifiers::empty()), Some(Message::DeselectComponent)));
+        assert!(matches!(key(Key::Character("s".into()), Modifiers::COMMAND), Some(Message::SaveProject)));
+    }
+
+    #[test]
+    fn test_menu_items_follow_state_and_close_after_a_command() {
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings::default());
+        let enabled = |app: &App, menu: Menu, label: &str| {
+            app.menu_entries(menu).into_iter().find_map(|entry| match entry {
+                MenuEntry::Item { label: l, message, .. } if l.ends_with(label) => Some(message.is_some()),
+                _ => None,
+            })
+        };
+        assert_eq!(enabled(&app, Menu::File, "Save"), Some(false));
+        assert_eq!(enabled(&app, Menu::Edit, "Undo"), Some(false));
+
+        let project = Project::create(&temp.path().join("project"), None).unwrap();
+        let root = project.layout.root.id;
+        app.open_tab(project);
+        assert_eq!(enabled(&app, Menu::File, "Save"), Some(true));
+        assert_eq!(enabled(&app, Menu::Edit, "Delete"), Some(false));
+        let _ = app.update(Message::SelectComponent(root));
+        assert_eq!(enabled(&app, Menu::Edit, "Delete"), Some(false));
+        assert_eq!(enabled(&app, Menu::Edit, "Copy"), Some(true));
+
+        let _ = app.update(Message::ToggleMenu(Menu::View));
+        assert_eq!(app.open_menu, Some(Menu::View));
+        let _ = app.view();
+        let _ = app.update(Message::MenuCommand(Box::new(Message::ToggleShowInvisible)));
+        assert_eq!(app.open_menu, None);
+        assert!(app.show_invisible);
+    }
+
+    #[test]
+    fn test_duplicate_selected_selects_the_copy_and_can_repeat() {
+        use crate::model::layout::{TextAttrs, WidgetType};
+
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings::default());
+        let mut project = Project::create(&temp.path().join("project"), None).unwrap();
+        let root = project.layout.root.id;
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Hello".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        let original = text.id;
+        assert!(project.add_child_to_root(text));
+        project.mark_saved();
+        app.open_tab(project);
+
+        let _ = app.update(Message::SelectComponent(root));
+        let _ = app.update(Message::DuplicateSelected);
+        let project = app.active_project().unwrap();
+        assert!(!project.dirty);
+        assert!(!project.history.can_undo());
+        assert_eq!(app.status_message.as_ref().map(|s| s.severity), Some(StatusSeverity::Error));
+
+        let _ = app.update(Message::SelectComponent(original));
+        let _ = app.update(Message::DuplicateSelected);
+        let copy = app.active_project().unwrap().selected_id.unwrap();
+        // Duplicating the copy puts the new copy right after it
+        let _ = app.update(Message::DuplicateSelected);
+        let project = app.active_project().unwrap();
+        let copy_of_copy = project.selected_id.unwrap();
+
+        let ids = [original, copy, copy_of_copy];
+        assert_eq!(ids.iter().collect::<std::collections::HashSet<_>>().len(), 3);
+        for (index, id) in ids.iter().enumerate() {
+            assert_eq!(project.parent_of(*id), Some((root, index)));
+        }
+        assert!(project.dirty);
+        assert_eq!(project.history.undo_count(), 2);
+        assert_eq!(app.status_message.as_ref().map(|s| s.severity), Some(StatusSeverity::Success));
+    }
+
+    #[test]
+    fn test_context_menu_opens_where_pressed_and_duplicates() {
+        use crate::model::layout::{TextAttrs, WidgetType};
+
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings::default());
+        let mut project = Project::create(&temp.path().join("project"), None).unwrap();
+        let root = project.layout.root.id;
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Hello".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        let original = text.id;
+        assert!(project.add_child_to_root(text));
+        app.open_tab(project);
+        let enabled = |app: &App, id: ComponentId, label: &str| {
+            app.context_menu_entries(id).into_iter().find_map(|entry| match entry {
+                MenuEntry::Item { label: l, message, .. } if l == label => Some(message.is_some()),
+                _ => None,
+            })
+        };
+        assert_eq!(enabled(&app, root, "Duplicate"), Some(false));
+        assert_eq!(enabled(&app, original, "Duplicate"), Some(true));
+        assert_eq!(enabled(&app, root, "Copy as Code"), Some(true));
+
+        // The window reports the press before the node asks for its menu
+        let position = iced::Point::new(120.0, 80.0);
+        let _ = app.update(Message::RightPressed(position));
+        let _ = app.update(Message::OpenContextMenu(original));
+        assert_eq!(app.context_menu, Some((original, position)));
+        assert_eq!(app.active_project().unwrap().selected_id, Some(original));
+        let _ = app.view();
+
+        let _ = app.update(Message::MenuCommand(Box::new(Message::DuplicateSelected)));
+        assert_eq!(app.context_menu, None);
+        let project = app.active_project().unwrap();
+        let copy = project.selected_id.unwrap();
+        assert_ne!(copy, original);
+        assert_eq!(project.parent_of(copy), Some((root, 1)));
+
+        // Conversions are offered as in the inspector
+        assert_eq!(enabled(&app, root, "Change to Row"), Some(true));
+        assert_eq!(enabled(&app, copy, "Change to Row"), None);
+        let _ = app.update(Message::OpenContextMenu(copy));
+        let _ = app.update(Message::MenuCommand(Box::new(Message::ConvertWidget(copy, WidgetKind::Button))));
+        let project = app.active_project().unwrap();
+        assert!(matches!(project.find_node(copy).unwrap().widget, WidgetType::Button { .. }));
+
+        // Preview mode has no context menu
+        let _ = app.update(Message::TogglePreviewMode);
+        let _ = app.update(Message::OpenContextMenu(original));
+        assert_eq!(app.context_menu, None);
+    }
+
+    #[test]
+    fn test_tabs_switch_close_and_edit_only_the_active_project() {
+        use crate::model::layout::{TextAttrs, WidgetType};
+
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings::default());
+        for name in ["First", "Second", "Third"] {
+            let mut project = Project::create(&temp.path().join(name), None).unwrap();
+            project.layout.name = name.to_string();
+            app.open_tab(project);
+        }
+        assert_eq!(app.active, 2);
+        assert!(app.title().contains("Third"));
+        let _ = app.view();
+
+        let _ = app.update(Message::SelectTab(0));
+        assert!(app.title().contains("First"));
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Only here".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        let text_id = text.id;
+        let project = app.active_project_mut().unwrap();
+        project.history.push(project.layout.clone(), "Add Text");
+        assert!(project.add_child_to_root(text));
+        project.mark_dirty();
+        assert!(app.projects[1].find_node(text_id).is_none());
+
+        let _ = app.update(Message::SelectTab(1));
+        let _ = app.update(Message::Undo);
+        assert!(app.projects[0].find_node(text_id).is_some());
+
+        // Closing a tab before the active one keeps the same project active
+        let _ = app.update(Message::CloseTabChosen(0, UnsavedChoice::Cancel));
+        assert_eq!(app.projects.len(), 3);
+        let _ = app.update(Message::CloseTabChosen(0, UnsavedChoice::Discard));
+        assert_eq!(app.projects.len(), 2);
+        assert!(app.title().contains("Second"));
+
+        // Closing the last, active tab falls back to the one before it
+        let _ = app.update(Message::SelectTab(1));
+        let _ = app.update(Message::CloseTab(1));
+        assert_eq!(app.active, 0);
+        assert!(app.title().contains("Second"));
+        let _ = app.update(Message::CloseActiveTab);
+        assert!(app.active_project().is_none());
+        assert_eq!(app.title(), "Iced Builder");
+    }
+
+    #[test]
+    fn test_history_limit_applies_on_submit_to_every_tab() {
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings::default());
+        for name in ["First", "Second"] {
+            app.open_tab(Project::create(&temp.path().join(name), None).unwrap());
+        }
+        let before = app.settings.history_limit;
+
+        // Typing "100" passes through "1" without applying it
+        let _ = app.update(Message::HistoryLimitInput("1".to_string()));
+        let _ = app.update(Message::HistoryLimitInput("100".to_string()));
+        assert_eq!(app.settings.history_limit, before);
+        assert!(app.projects.iter().all(|p| p.history.limit() == before));
+
+        let _ = app.update(Message::CommitHistoryLimit);
+        assert_eq!(app.settings.history_limit, 100);
+        assert!(app.projects.iter().all(|p| p.history.limit() == 100));
+
+        // Rejected input falls back to the current limit
+        let _ = app.update(Message::HistoryLimitInput("0".to_string()));
+        let _ = app.update(Message::CommitHistoryLimit);
+        assert_eq!(app.history_limit_input, "100");
+        assert_eq!(app.settings.history_limit, 100);
+    }
+
+    #[test]
+    fn test_slider_fields_keep_typed_text() {
+        use crate::model::layout::{SliderAttrs, WidgetType};
+        use crate::model::SampleValue;
+
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings::default());
+        app.open_tab(Project::create(&temp.path().join("Sliders"), None).unwrap());
+        let slider = LayoutNode::new(WidgetType::Slider {
+            min: 0.0,
+            max: 10.0,
+            value_binding: "volume".to_string(),
+            message_stub: "VolumeChanged".to_string(),
+            attrs: SliderAttrs::default(),
+        });
+        let id = slider.id;
+        assert!(app.projects[0].add_child_to_root(slider));
+        let range = |app: &App| match &app.projects[0].find_node(id).unwrap().widget {
+            WidgetType::Slider { min, max, .. } => (*min, *max),
+            _ => unreachable!(),
+        };
+        let draft = |app: &App, field| {
+            let revision = app.projects[0].revision;
+            app.inspector_drafts.current(revision).and_then(|d| d.get(id, field)).map(str::to_owned)
+        };
+
+        // "1." applies 1 but keeps the dot for the next digit
+        let _ = app.update(Message::DraftInput(id, DraftField::Sample, "1.".to_string()));
+        assert_eq!(app.projects[0].config.sample_data.get("volume"), Some(&SampleValue::Number(1.0)));
+        assert_eq!(draft(&app, DraftField::Sample).as_deref(), Some("1."));
+
+        // A half-typed negative minimum is kept without being applied
+        let _ = app.update(Message::DraftInput(id, DraftField::Min, "-".to_string()));
+        assert_eq!(range(&app), (0.0, 10.0));
+        assert_eq!(draft(&app, DraftField::Min).as_deref(), Some("-"));
+        let _ = app.update(Message::DraftInput(id, DraftField::Min, "-0.5".to_string()));
+        assert_eq!(range(&app), (-0.5, 10.0));
+        assert_eq!(draft(&app, DraftField::Min).as_deref(), Some("-0.5"));
+
+        // An inverted range is rejected, but the text stays
+        let _ = app.update(Message::DraftInput(id, DraftField::Max, "-1".to_string()));
+        assert_eq!(range(&app), (-0.5, 10.0));
+        assert_eq!(draft(&app, DraftField::Max).as_deref(), Some("-1"));
+        let _ = app.update(Message::SelectComponent(id));
+        let _ = app.view();
+
+        // Undo shows the applied values again
+        let _ = app.update(Message::Undo);
+        assert_eq!(range(&app), (0.0, 10.0));
+        assert_eq!(draft(&app, DraftField::Min), None);
+    }
+
+    #[test]
+    fn test_saving_a_snippet_asks_for_its_name() {
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings::default());
+        app.open_tab(Project::create(&temp.path().join("Snippets"), None).unwrap());
+        let root = app.projects[0].layout.root.id;
+        let _ = app.update(Message::SelectComponent(root));
+
+        // Nothing is written until the suggested name is confirmed or changed
+        let _ = app.update(Message::SaveSelectionAsSnippet);
+        assert!(app.snippets.is_empty());
+        assert_eq!(app.snippet_name, Some((root, "Column".to_string())));
+        let _ = app.update(Message::CancelSnippetName);
+        assert!(app.snippet_name.is_none());
+
+        let _ = app.update(Message::SaveSelectionAsSnippet);
+        let _ = app.update(Message::SnippetNameInput("Sidebar".to_string()));
+        let _ = app.update(Message::CommitSnippetName);
+        assert_eq!(app.snippets.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), ["Sidebar"]);
+        assert!(app.snippet_name.is_none());
+
+        // A taken name keeps the prompt open for another try
+        let _ = app.update(Message::SaveSelectionAsSnippet);
+        let _ = app.update(Message::SnippetNameInput("Sidebar".to_string()));
+        let _ = app.update(Message::CommitSnippetName);
+        assert_eq!(app.snippets.len(), 1);
+        assert_eq!(app.snippet_name, Some((root, "Sidebar".to_string())));
+    }
+
+    #[test]
+    fn test_background_tabs_autosave_reload_and_are_not_replaced_unasked() {
+        use crate::model::layout::{TextAttrs, WidgetType};
+
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings::default());
+        for name in ["Edited", "Clean"] {
+            app.open_tab(Project::create(&temp.path().join(name), None).unwrap());
+        }
+        let edited_path = app.projects[0].path.clone();
+        let edited = &mut app.projects[0];
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Unsaved".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        let text_id = text.id;
+        assert!(edited.add_child_to_root(text));
+        edited.mark_dirty();
+        assert_eq!(app.active, 1);
+
+        // Autosave covers tabs in the background too
+        let _ = app.update(Message::AutosaveDue);
+        assert!(crate::io::autosave::autosave_path(&edited_path).exists());
+
+        // A clean background tab picks up changes made on disk
+        let _ = app.update(Message::SelectTab(0));
+        let clean = &app.projects[1];
+        let mut changed = clean.layout.clone();
+        changed.name = "Changed on disk".to_string();
+        crate::io::save_layout(&clean.layout_path(), &changed).unwrap();
+        let _ = app.update(Message::LayoutFilePoll);
+        assert_eq!(app.projects[1].layout.name, "Changed on disk");
+        assert!(!app.projects[1].dirty);
+
+        // Creating a project over a tab with unsaved changes asks first
+        let _ = app.update(Message::CreateProjectAt(edited_path.clone(), Template::Blank));
+        assert!(app.projects[0].find_node(text_id).is_some());
+        let _ = app.update(Message::ReplaceTabChosen(edited_path.clone(), Template::Blank, false));
+        assert!(app.projects[0].find_node(text_id).is_some());
+        let _ = app.update(Message::ReplaceTabChosen(edited_path, Template::Blank, true));
+        assert_eq!(app.projects.len(), 2);
+        assert!(app.projects[0].find_node(text_id).is_none());
+    }
+
+    #[test]
+    fn test_failures_open_the_error_dialog_until_escape() {
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings::default());
+        // A file where the project directory should go can't be created
+        let path = temp.path().join("taken");
+        std::fs::write(&path, "").unwrap();
+
+        let _ = app.update(Message::CreateProjectAt(path.clone(), Template::Blank));
+        let dialog = app.pending_dialog.clone().unwrap();
+        assert_eq!(dialog.title, "Failed to create project");
+        assert_eq!(dialog.path.as_deref(), Some(path.as_path()));
+        assert!(!dialog.chain.is_empty());
+        assert_eq!(app.status_message.as_ref().unwrap().severity, StatusSeverity::Error);
+        let _ = app.view();
+
+        let _ = app.update(Message::DeselectComponent);
+        assert!(app.pending_dialog.is_none());
+        let _ = app.update(Message::CreateProjectAt(path, Template::Blank));
+        let _ = app.update(Message::DismissDialog);
+        assert!(app.pending_dialog.is_none());
+    }
+
+    #[test]
+    fn test_problems_refresh_after_edits_and_open_when_export_is_blocked() {
+        use crate::model::layout::{ButtonAttrs, WidgetType};
+
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings::default());
+        let mut project = Project::create(&temp.path().join("project"), None).unwrap();
+        let button = LayoutNode::new(WidgetType::Button {
+            label: "Go".to_string(),
+            message_stub: "not valid".to_string(),
+            attrs: ButtonAttrs::default(),
+        });
+        let button_id = button.id;
+        assert!(project.add_child_to_root(button));
+        app.open_tab(project);
+
+        // The first update validates straight away
+        let _ = app.update(Message::Noop);
+        assert!(app.problems.errors() > 0);
+        assert!(app.problems.list.iter().any(|p| p.node_id == button_id));
+        assert!(!app.show_problems);
+
+        let _ = app.update(Message::ExportCode);
+        assert!(app.show_problems);
+        assert_eq!(app.active_project().unwrap().selected_id, Some(button_id));
+        let _ = app.view();
+
+        // Fixing the stub waits for the delayed refresh
+        let _ = app.update(Message::SelectProblem(button_id));
+        let project = app.active_project_mut().unwrap();
+        if let Some(node) = project.find_node_mut(button_id) {
+            if let WidgetType::Button { message_stub, .. } = &mut node.widget {
+                *message_stub = "Go".to_string();
+            }
+        }
+        project.mark_dirty();
+        let revision = project.revision;
+        let _ = app.update(Message::Noop);
+        assert_eq!(app.problems.scheduled, Some(revision));
+        assert!(app.problems.errors() > 0);
+        let _ = app.update(Message::ProblemsDue(revision));
+        assert_eq!(app.problems.errors(), 0);
+        let _ = app.view();
+    }
+
+    #[test]
+    fn test_status_bar_problem_count_opens_panel_then_selects() {
+        use crate::model::layout::{ButtonAttrs, WidgetType};
+
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings::default());
+        let mut project = Project::create(&temp.path().join("project"), None).unwrap();
+        let button = LayoutNode::new(WidgetType::Button {
+            label: "Go".to_string(),
+            message_stub: "not valid".to_string(),
+            attrs: ButtonAttrs::default(),
+        });
+        let button_id = button.id;
+        assert!(project.add_child_to_root(button));
+        app.open_tab(project);
+        let _ = app.update(Message::Noop);
+        let _ = app.view();
+
+        let _ = app.update(Message::ShowProblems);
+        assert!(app.show_problems);
+        assert_eq!(app.active_project().unwrap().selected_id, None);
+        let _ = app.update(Message::ShowProblems);
+        assert!(app.show_problems);
+        assert_eq!(app.active_project().unwrap().selected_id, Some(button_id));
+        let _ = app.view();
+    }
+
+    #[test]
+    fn test_check_bindings_against_target_state() {
+        use crate::model::layout::{CheckboxAttrs, WidgetType};
+
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings::default());
+        let mut project = Project::create(&temp.path().join("project"), None).unwrap();
+        project.config.project_root = Some(temp.path().join("target"));
+        let checkbox = LayoutNode::new(WidgetType::Checkbox {
+            label: "Agree".to_string(),
+            checked_binding: "agreed".to_string(),
+            message_stub: "Toggled".to_string(),
+            attrs: CheckboxAttrs::default(),
+        });
+        let checkbox_id = checkbox.id;
+        assert!(project.add_child_to_root(checkbox));
+        app.open_tab(project);
+        let _ = app.update(Message::Noop);
+
+        // Without the target's sources there is nothing to check against
+        let _ = app.update(Message::CheckStateBindings);
+        assert!(app.problems.state.is_none());
+        assert_eq!(app.status_message.as_ref().unwrap().severity, StatusSeverity::Info);
+
+        std::fs::create_dir_all(temp.path().join("target/src")).unwrap();
+        std::fs::write(temp.path().join("target/src/main.rs"), "struct AppState { accepted: bool }\n").unwrap();
+        let _ = app.update(Message::CheckStateBindings);
+        assert!(app.show_problems);
+        assert_eq!(app.problems.state.as_ref().unwrap().fields, ["accepted"]);
+        assert!(app
+            .problems
+            .list
+            .iter()
+            .any(|p| p.node_id == checkbox_id && p.message.contains("not a field of AppState")));
+        let _ = app.view();
+
+        let _ = app.update(Message::ClearStateCheck);
+        assert!(!app.problems.list.iter().any(|p| p.message.contains("AppState")));
+    }
+
+    #[test]
+    fn test_ui_scale_steps_within_range() {
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings {
+            ui_scale: 5.0,
+            ..AppSettings::default()
+        });
+        assert_eq!(app.settings.ui_scale, metrics::MAX_SCALE);
+
+        let _ = app.update(Message::StepUiScale(1));
+        assert_eq!(app.settings.ui_scale, metrics::MAX_SCALE);
+        let _ = app.update(Message::StepUiScale(-2));
+        assert_eq!(app.settings.ui_scale, metrics::MAX_SCALE - 2.0 * metrics::SCALE_STEP);
+        let _ = app.view();
+
+        let _ = app.update(Message::SetUiScale(0.1));
+        assert_eq!(app.settings.ui_scale, metrics::MIN_SCALE);
+        let _ = app.update(Message::SetUiScale(1.0));
+        assert_eq!(app.settings.ui_scale, 1.0);
+    }
+
+    #[test]
+    fn test_dropped_paths_open_projects_or_lone_layouts() {
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let project = temp.path().join("project");
+        Project::create(&project, None).unwrap();
+        let loose = temp.path().join("loose");
+        std::fs::create_dir_all(&loose).unwrap();
+        let layout = loose.join("screen.ron");
+        crate::io::save_layout(&layout, &crate::model::LayoutDocument::default()).unwrap();
+        let notes = loose.join("notes.txt");
+        std::fs::write(&notes, "").unwrap();
+
+        assert_eq!(DroppedPath::classify(&project), Some(DroppedPath::Project(project.clone())));
+        assert_eq!(
+            DroppedPath::classify(&project.join("layout.ron")),
+            Some(DroppedPath::Project(project.clone()))
+        );
+        assert_eq!(DroppedPath::classify(&layout), Some(DroppedPath::LoneLayout(layout.clone())));
+        assert_eq!(DroppedPath::classify(&notes), None);
+        assert_eq!(DroppedPath::classify(&loose), None);
+
+        let mut app = App::with_settings(AppSettings::default());
+        let _ = app.update(Message::FileDropped(notes.clone()));
+        let _ = app.update(Message::FileDropped(loose.clone()));
+        let _ = app.update(Message::DropSettled);
+        assert!(app.dropped_paths.is_empty());
+        let status = app.status_message.as_ref().unwrap();
+        assert_eq!(status.severity, StatusSeverity::Error);
+        assert!(status.text.contains("notes.txt"));
+
+        let _ = app.update(Message::CreateProjectForLayout(layout));
+        assert_eq!(app.active_project().map(|p| p.path.as_path()), Some(loose.as_path()));
+    }
+
+    #[test]
+    fn test_template_picker_creates_seeded_project() {
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings::default());
+        // The welcome screen replaces the canvas until a project is open
+        let _ = app.view();
+
+        // A folder picked from New Project waits for a template
+        let folder = temp.path().join("form");
+        let _ = app.update(Message::ChooseTemplate(folder.clone()));
+        let _ = app.view();
+        let _ = app.update(Message::DeselectComponent);
+        assert!(app.template_picker.is_none());
+
+        let _ = app.update(Message::ChooseTemplate(folder.clone()));
+        let _ = app.update(Message::CreateProjectAt(folder, Template::Form));
+        assert!(app.template_picker.is_none());
+        let project = app.active_project().unwrap();
+        assert_eq!(project.layout.name, "Form");
+        assert!(!project.dirty);
+        let _ = app.view();
+    }
+
+    #[test]
+    fn test_deleting_a_container_with_children_asks_first() {
+        use crate::model::layout::{ContainerAttrs, TextAttrs, WidgetType};
+
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings::default());
+        let mut project = Project::create(&temp.path().join("project"), None).unwrap();
+        let text = || {
+            LayoutNode::new(WidgetType::Text {
+                content: "Hello".to_string(),
+                attrs: TextAttrs::default(),
+            })
+        };
+        let leaf = text();
+        let leaf_id = leaf.id;
+        let column = LayoutNode::new(WidgetType::Column {
+            children: vec![text(), text()],
+            attrs: ContainerAttrs::default(),
+        });
+        let column_id = column.id;
+        assert!(project.add_child_to_root(leaf));
+        assert!(project.add_child_to_root(column));
+        app.open_tab(project);
+
+        // Leaf widgets go straight away
+        let _ = app.update(Message::SelectComponent(leaf_id));
+        let _ = app.update(Message::DeleteSelected);
+        assert!(app.delete_confirm.is_none());
+        assert!(app.active_project().unwrap().find_node(leaf_id).is_none());
+
+        let _ = app.update(Message::SelectComponent(column_id));
+        let _ = app.update(Message::DeleteSelected);
+        let confirm = app.delete_confirm.clone().unwrap();
+        assert_eq!(confirm.question(), "Delete Column and its 2 children?");
+        let _ = app.view();
+        let _ = app.update(Message::DeselectComponent);
+        assert!(app.delete_confirm.is_none());
+        assert!(app.active_project().unwrap().find_node(column_id).is_some());
+
+        let _ = app.update(Message::DeleteSelected);
+        let _ = app.update(Message::ToggleDontAskDelete(true));
+        let _ = app.update(Message::ConfirmDelete);
+        assert!(app.delete_confirm.is_none());
+        assert!(!app.settings.confirm_subtree_delete);
+        let project = app.active_project().unwrap();
+        assert!(project.find_node(column_id).is_none());
+        assert_eq!(project.history.undo_count(), 2);
+    }
+
+    #[test]
+    fn test_crash_handle_follows_unsaved_changes() {
+        use crate::io::crash_recovery::crash_recovery_path;
+        use crate::model::layout::{TextAttrs, WidgetType};
+
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let handle = CrashHandle::default();
+        let mut app = App::with_settings(AppSettings::default()).with_crash_handle(handle.clone());
+        let mut project = Project::create(&temp.path().join("project"), None).unwrap();
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Hello".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        let id = text.id;
+        assert!(project.add_child_to_root(text));
+        project.mark_saved();
+        let path = project.path.clone();
+        app.open_tab(project);
+
+        // Nothing to lose while everything is saved
+        let _ = app.update(Message::SelectComponent(id));
+        assert!(handle.write().is_none());
+
+        // Edits reach the panic hook on the next snapshot tick
+        let _ = app.update(Message::DuplicateSelected);
+        assert!(handle.write().is_none());
+        let _ = app.update(Message::CrashSnapshotDue);
+        let written = handle.write().unwrap().unwrap();
+        assert_eq!(written, crash_recovery_path(Some(&path)));
+        let saved = crate::io::load_layout(&written).unwrap();
+        assert_eq!(&saved, &app.active_project().unwrap().layout);
+
+        let _ = app.update(Message::RecoverCrash(false));
+        assert!(!written.exists());
+    }
+
+    #[test]
+    fn test_typing_into_the_inspector_does_not_copy_the_layout() {
+        use crate::model::layout::{TextAttrs, WidgetType};
+
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let handle = CrashHandle::default();
+        let mut app = App::with_settings(AppSettings::default()).with_crash_handle(handle.clone());
+        let mut project = Project::create(&temp.path().join("project"), None).unwrap();
+        for i in 0..500 {
+            assert!(project.add_child_to_root(LayoutNode::new(WidgetType::Text {
+                content: format!("Item {}", i),
+                attrs: TextAttrs::default(),
+            })));
+        }
+        let id = project.layout.root.child_nodes()[250].id;
+        project.mark_saved();
+        app.open_tab(project);
+
+        // Neither the history nor the panic hook copies the layout per keystroke
+        for typed in ["H", "He", "Hel", "Hell", "Hello"] {
+            let _ = app.update(Message::UpdateTextContent(id, typed.to_string()));
+        }
+        let project = app.active_project().unwrap();
+        assert_eq!(project.history.undo_count(), 5);
+        assert!(project.history.memory_estimate() < 50 * std::mem::size_of::<LayoutNode>());
+        assert!(handle.write().is_none());
+
+        let _ = app.update(Message::CrashSnapshotDue);
+        let saved = crate::io::load_layout(&handle.write().unwrap().unwrap()).unwrap();
+        assert_eq!(&saved, &app.active_project().unwrap().layout);
+    }
+
+    #[test]
+    fn test_opening_a_project_reports_config_issues() {
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings::default());
+        let mut project = Project::create(&temp.path().join("project"), None).unwrap();
+        project.config.message_type = "crate::Message-Type".to_string();
+        project.save().unwrap();
+
+        let _ = app.update(Message::ProjectOpened(open_project(&project.path)));
+        assert!(app.show_problems);
+        assert_eq!(app.problems.list[0].path, "iced_builder.toml: message_type");
+        let status = app.status_message.as_ref().unwrap();
+        assert_eq!(status.severity, StatusSeverity::Error);
+        assert!(status.text.contains("message_type"));
+    }
+
+    #[test]
+    fn test_saving_with_problems_is_a_warning() {
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings::default());
+        app.open_tab(Project::create(&temp.path().join("project"), Some(Template::Form)).unwrap());
+
+        let _ = app.update(Message::SaveProject);
+        let snapshot = app.active_project().unwrap().snapshot();
+        finish_save(&mut app, snapshot);
+        let status = app.status_message.clone().unwrap();
+        assert_eq!((status.severity, status.text.as_str()), (StatusSeverity::Success, "Project saved"));
+
+        app.active_project_mut().unwrap().config.message_type = "not a path".to_string();
+        let _ = app.update(Message::SaveProject);
+        let snapshot = app.active_project().unwrap().snapshot();
+        finish_save(&mut app, snapshot);
+        let status = app.status_message.clone().unwrap();
+        assert_eq!(status.severity, StatusSeverity::Warning);
+        assert_eq!(status.text, "Project saved with 1 error");
+        assert!(!status.fades());
+        assert!(!app.active_project().unwrap().dirty);
+    }
+
+    /// Write `snapshot` and report it saved, as the task `SaveProject`
+    /// returns would.
+    fn finish_save(app: &mut App, snapshot: Project) {
+        let written = snapshot
+            .write()
+            .map_err(|e| DialogState::error("Failed to save project", Some(&snapshot.path), &e));
+        let _ = app.update(Message::ProjectSaved(snapshot.path.clone(), written));
+    }
+
+    #[test]
+    fn test_edits_made_while_saving_stay_unsaved() {
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings::default());
+        app.open_tab(Project::create(&temp.path().join("project"), None).unwrap());
+        let path = app.active_project().unwrap().path.clone();
+        app.active_project_mut().unwrap().layout.name = "Saved".to_string();
+        app.active_project_mut().unwrap().mark_dirty();
+
+        // Saving writes a copy, so the project stays dirty until it is written
+        let _ = app.update(Message::SaveProject);
+        let snapshot = app.active_project().unwrap().snapshot();
+        assert_eq!(app.saving, vec![path.clone()]);
+        assert!(app.active_project().unwrap().dirty);
+        let _ = app.update(Message::SaveProject);
+        assert_eq!(app.status_message.as_ref().unwrap().text, "Still saving the last changes");
+
+        app.active_project_mut().unwrap().layout.name = "Edited".to_string();
+        app.active_project_mut().unwrap().mark_dirty();
+        finish_save(&mut app, snapshot);
+        assert!(app.saving.is_empty());
+        let project = app.active_project().unwrap();
+        assert!(project.dirty);
+        assert!(!project.layout_changed_on_disk());
+        assert_eq!(crate::io::load_layout(&project.layout_path()).unwrap().name, "Saved");
+
+        let _ = app.update(Message::SaveProject);
+        let snapshot = app.active_project().unwrap().snapshot();
+        finish_save(&mut app, snapshot);
+        assert!(!app.active_project().unwrap().dirty);
+        assert!(app.status_message.as_ref().unwrap().text.starts_with("Project saved"));
+    }
+
+    #[test]
+    fn test_projects_load_in_the_background() {
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var(crate::io::app_settings::CONFIG_DIR_ENV, temp.path().join("config"));
+        let mut app = App::with_settings(AppSettings::default());
+        let path = Project::create(&temp.path().join("project"), None).unwrap().path;
+
+        let _ = app.update(Message::LoadProject(path.clone()));
+        let _ = app.update(Message::LoadProject(path.clone()));
+        assert_eq!(app.loading, vec![path.clone()]);
+        let _ = app.view();
+        let _ = app.update(Message::ProjectOpened(open_project(&path)));
+        assert!(app.loading.is_empty());
+        assert_eq!(app.active_project().map(|p| p.path.as_path()), Some(path.as_path()));
+
+        // Failures clear the loading state too
+        let missing = temp.path().join("missing");
+        let _ = app.update(Message::LoadProject(missing.clone()));
+        let _ = app.update(Message::ProjectOpened(open_project(&missing)));
+        assert!(app.loading.is_empty());
+        assert_eq!(app.pending_dialog.as_ref().unwrap().title, "Failed to open project");
+    }
+
+    #[test]
+    fn test_background_work_reports_panics() {
+        use iced::futures::executor::block_on;
+
+        let path = std::path::PathBuf::from("project");
+        assert_eq!(block_on(in_background("Failed", path.clone(), || Ok(7))).unwrap(), 7);
+        let dialog = block_on(in_background("Failed", path.clone(), || -> Result<(), _> { panic!("lost") })).unwrap_err();
+        assert_eq!(dialog.title, "Failed");
+        assert_eq!(dialog.path, Some(path));
+    }
+
+    #[test]
+    fn test_delays_do_not_hold_the_executor() {
+        use iced::futures::executor::block_on;
+        use iced::futures::future::join_all;
+
+        // On one executor thread, blocking delays would run one after another
+        let started = std::time::Instant::now();
+        block_on(join_all((0..4).map(|_| delay(Duration::from_millis(200)))));
+        assert!(started.elapsed() < Duration::from_millis(700));
+    }
+
+    #[test]
+    fn test_long_status_messages_are_truncated() {
+        assert_eq!(StatusMessage::info("Short").truncated(), None);
+
+        let long = StatusMessage::error("é".repeat(STATUS_MAX_CHARS + 1));
+        let short = long.truncated().unwrap();
+        assert_eq!(short.chars().count(), STATUS_MAX_CHARS + 1);
+        assert!(short.ends_with('…'));
+    }
+}
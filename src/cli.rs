@@ -0,0 +1,288 @@
+//! Command-line subcommands that run without the GUI.
+//!
+//! `iced-builder` with no arguments opens the editor. Subcommands work on a
+//! project directory headlessly, for scripts and CI:
+//!
+//! ```bash
+//! # Regenerate the view code
+//! iced-builder export path/to/project
+//!
+//! # Fail when the committed code is out of date
+//! iced-builder export path/to/project --check
+//!
+//! # List layout problems, failing on errors
+//! iced-builder validate path/to/project
+//! ```
+//!
+//! Results go to stdout, errors to stderr. Exit status is 0 on success,
+//! [`EXIT_CHECK_FAILED`] when a check fails and [`EXIT_FAILURE`] on errors.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use thiserror::Error;
+
+use crate::model::layout::{ValidationError, ValidationSeverity};
+use crate::model::{Project, ProjectError};
+
+/// Exit status when a check finds something to fix.
+pub const EXIT_CHECK_FAILED: u8 = 1;
+
+/// Exit status when a command could not run.
+pub const EXIT_FAILURE: u8 = 2;
+
+/// Command-line arguments.
+#[derive(Debug, Parser)]
+#[command(name = "iced-builder", version, about = "A visual GUI builder for Iced applications")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Headless subcommands.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Generate code for a project without opening the editor
+    Export {
+        /// The project directory
+        project_dir: PathBuf,
+        /// Only compare the generated code with the file on disk, failing if they differ
+        #[arg(long)]
+        check: bool,
+        /// Write the code here instead of the project's configured output file
+        #[arg(long, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+    /// Check a project's layout, printing one `severity path: message` line per issue
+    Validate {
+        /// The project directory
+        project_dir: PathBuf,
+        /// Fail on warnings as well as errors
+        #[arg(long)]
+        deny_warnings: bool,
+    },
+}
+
+/// Errors from command-line subcommands.
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error("{0}")]
+    Project(#[from] ProjectError),
+
+    #[error("Failed to resolve path: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{} is out of date; run `iced-builder export` to regenerate it", .0.display())]
+    Outdated(PathBuf),
+
+    #[error("Validation failed with {errors} error(s) and {warnings} warning(s)")]
+    Invalid { errors: usize, warnings: usize },
+}
+
+impl CliError {
+    /// The process exit status for this error.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::Outdated(_) | Self::Invalid { .. } => EXIT_CHECK_FAILED,
+            _ => EXIT_FAILURE,
+        }
+    }
+}
+
+/// What an export did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportOutcome {
+    /// The code was written to this file.
+    Written(PathBuf),
+    /// `--check` found this file up to date.
+    UpToDate(PathBuf),
+}
+
+/// Run a subcommand, printing its result, and return the exit status.
+pub fn run(command: Command) -> ExitCode {
+    let result = match command {
+        Command::Export { project_dir, check, output } => {
+            export(&project_dir, check, output.as_deref()).map(|outcome| match outcome {
+                ExportOutcome::Written(path) => println!("Wrote {}", path.display()),
+                ExportOutcome::UpToDate(path) => println!("{} is up to date", path.display()),
+            })
+        }
+        Command::Validate { project_dir, deny_warnings } => validate(&project_dir).and_then(|issues| {
+            for issue in &issues {
+                println!("{}", format_issue(issue));
+            }
+            check_issues(&issues, deny_warnings)
+        }),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            if let CliError::Project(ProjectError::ValidationFailed(errors)) = &e {
+                for error in errors {
+                    eprintln!("{}", error);
+                }
+            }
+            eprintln!("error: {}", e);
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
+
+/// Export a project's code, or with `check` compare it with the file on disk
+/// without writing anything.
+pub fn export(project_dir: &Path, check: bool, output: Option<&Path>) -> Result<ExportOutcome, CliError> {
+    let mut project = Project::open(project_dir)?;
+    if let Some(output) = output {
+        // Relative to where the command runs, not to the project
+        project.config.output_file = std::path::absolute(output)?;
+    }
+    let path = project.output_path();
+
+    if !check {
+        project.export()?;
+        return Ok(ExportOutcome::Written(path));
+    }
+
+    let blockers = project.export_blockers();
+    if !blockers.is_empty() {
+        return Err(ProjectError::ValidationFailed(blockers).into());
+    }
+    let generated = project.generate_output()?;
+    // A checkout may have converted line endings
+    let existing = std::fs::read_to_string(&path).unwrap_or_default().replace("\r\n", "\n");
+    if existing == generated.code {
+        Ok(ExportOutcome::UpToDate(path))
+    } else {
+        Err(CliError::Outdated(path))
+    }
+}
+
+/// Load a project and validate its layout.
+pub fn validate(project_dir: &Path) -> Result<Vec<ValidationError>, CliError> {
+    let project = Project::open(project_dir)?;
+    Ok(project.validate())
+}
+
+/// Fail when any issue is an error, or with `deny_warnings` a warning.
+pub fn check_issues(issues: &[ValidationError], deny_warnings: bool) -> Result<(), CliError> {
+    let errors = issues.iter().filter(|i| i.severity == ValidationSeverity::Error).count();
+    let warnings = issues.len() - errors;
+    if errors > 0 || (deny_warnings && warnings > 0) {
+        Err(CliError::Invalid { errors, warnings })
+    } else {
+        Ok(())
+    }
+}
+
+/// Format an issue as `severity path: message`.
+pub fn format_issue(issue: &ValidationError) -> String {
+    let severity = match issue.severity {
+        ValidationSeverity::Error => "error",
+        ValidationSeverity::Warning => "warning",
+    };
+    format!("{} {}: {}", severity, issue.path, issue.message)
+}
+
+/// Reattach to the console of the terminal that started us.
+///
+/// The binary uses the Windows GUI subsystem so no console window opens with
+/// the editor, which also leaves subcommands without stdout and stderr.
+#[cfg(windows)]
+pub fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    // Fails harmlessly when there is no parent console, e.g. from Explorer
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+/// Reattach to the console of the terminal that started us; only needed on Windows.
+#[cfg(not(windows))]
+pub fn attach_console() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_cli_arguments() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from(["iced-builder", "export", "ui", "--check"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Export { check: true, output: None, .. })));
+        assert!(Cli::try_parse_from(["iced-builder"]).unwrap().command.is_none());
+        assert!(Cli::try_parse_from(["iced-builder", "export"]).is_err());
+        let cli = Cli::try_parse_from(["iced-builder", "validate", "ui", "--deny-warnings"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Validate { deny_warnings: true, .. })));
+    }
+
+    #[test]
+    fn test_export_and_check() {
+        let temp = tempdir().unwrap();
+        let project = Project::create(temp.path(), None).unwrap();
+        let path = project.output_path();
+
+        assert!(matches!(export(temp.path(), true, None), Err(CliError::Outdated(_))));
+        assert_eq!(export(temp.path(), false, None).unwrap(), ExportOutcome::Written(path.clone()));
+        assert_eq!(export(temp.path(), true, None).unwrap(), ExportOutcome::UpToDate(path.clone()));
+
+        let code = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, code.replace('\n', "\r\n")).unwrap();
+        assert!(export(temp.path(), true, None).is_ok());
+
+        std::fs::write(&path, format!("// edited\n{}", code)).unwrap();
+        let error = export(temp.path(), true, None).unwrap_err();
+        assert_eq!(error.exit_code(), EXIT_CHECK_FAILED);
+
+        let elsewhere = temp.path().join("out").join("view.rs");
+        assert_eq!(
+            export(temp.path(), false, Some(&elsewhere)).unwrap(),
+            ExportOutcome::Written(elsewhere.clone())
+        );
+        assert_eq!(std::fs::read_to_string(&elsewhere).unwrap(), code);
+    }
+
+    #[test]
+    fn test_validate_reports_issues_and_fails_on_errors() {
+        use crate::model::layout::{ButtonAttrs, LayoutNode, WidgetType};
+
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        let issues = validate(temp.path()).unwrap();
+        let lines: Vec<_> = issues.iter().map(format_issue).collect();
+        assert_eq!(lines, ["warning root: Container has no children"]);
+        assert!(check_issues(&issues, false).is_ok());
+        let error = check_issues(&issues, true).unwrap_err();
+        assert_eq!(error.exit_code(), EXIT_CHECK_FAILED);
+
+        let button = LayoutNode::new(WidgetType::Button {
+            label: "Go".to_string(),
+            message_stub: "go now".to_string(),
+            attrs: ButtonAttrs::default(),
+        });
+        assert!(project.add_child_to_root(button));
+        project.save().unwrap();
+
+        let issues = validate(temp.path()).unwrap();
+        let lines: Vec<_> = issues.iter().map(format_issue).collect();
+        assert_eq!(
+            lines,
+            ["error root.children[0]: message_stub 'go now' is not a valid Rust identifier"]
+        );
+        let error = check_issues(&issues, false).unwrap_err();
+        assert!(matches!(error, CliError::Invalid { errors: 1, warnings: 0 }));
+    }
+
+    #[test]
+    fn test_export_of_missing_project_fails() {
+        let temp = tempdir().unwrap();
+        let error = export(&temp.path().join("missing"), false, None).unwrap_err();
+        assert_eq!(error.exit_code(), EXIT_FAILURE);
+    }
+}
@@ -0,0 +1,244 @@
+//! Named color constants for generated code.
+//!
+//! Collects the distinct colors a layout uses so the view can refer to
+//! `palette::RED` instead of repeating literals. Names come from the closest
+//! inspector preset, or `COLOR_1`, `COLOR_2`, ... in order of first use, so
+//! tweaking one color only changes its constant.
+
+use crate::model::{layout::WidgetType, LayoutDocument};
+use std::fmt::Write;
+
+/// Colors closer than this in every channel share a constant.
+pub const COLOR_EPSILON: f32 = 0.005;
+
+/// Preset colors offered by the inspector for text, which also name
+/// the constants of matching colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// No color set; the theme's text color.
+    Default,
+    /// White.
+    White,
+    /// Black.
+    Black,
+    /// Red.
+    Red,
+    /// Green.
+    Green,
+    /// Blue.
+    Blue,
+    /// Yellow.
+    Yellow,
+    /// Orange.
+    Orange,
+    /// Purple.
+    Purple,
+    /// Gray.
+    Gray,
+}
+
+impl ColorChoice {
+    /// All available color choices.
+    pub const ALL: [ColorChoice; 10] = [
+        ColorChoice::Default,
+        ColorChoice::White,
+        ColorChoice::Black,
+        ColorChoice::Red,
+        ColorChoice::Green,
+        ColorChoice::Blue,
+        ColorChoice::Yellow,
+        ColorChoice::Orange,
+        ColorChoice::Purple,
+        ColorChoice::Gray,
+    ];
+
+    /// Convert to RGBA array (None for default).
+    pub fn to_rgba(self) -> Option<[f32; 4]> {
+        match self {
+            ColorChoice::Default => None,
+            ColorChoice::White => Some([1.0, 1.0, 1.0, 1.0]),
+            ColorChoice::Black => Some([0.0, 0.0, 0.0, 1.0]),
+            ColorChoice::Red => Some([1.0, 0.2, 0.2, 1.0]),
+            ColorChoice::Green => Some([0.2, 0.8, 0.2, 1.0]),
+            ColorChoice::Blue => Some([0.2, 0.5, 1.0, 1.0]),
+            ColorChoice::Yellow => Some([1.0, 0.9, 0.2, 1.0]),
+            ColorChoice::Orange => Some([1.0, 0.6, 0.2, 1.0]),
+            ColorChoice::Purple => Some([0.7, 0.3, 0.9, 1.0]),
+            ColorChoice::Gray => Some([0.5, 0.5, 0.5, 1.0]),
+        }
+    }
+
+    /// Create from RGBA array.
+    pub fn from_rgba(color: Option<[f32; 4]>) -> Self {
+        match color {
+            None => ColorChoice::Default,
+            Some([r, g, b, _]) => {
+                // Find closest match
+                if (r - 1.0).abs() < 0.1 && (g - 1.0).abs() < 0.1 && (b - 1.0).abs() < 0.1 {
+                    ColorChoice::White
+                } else if r < 0.1 && g < 0.1 && b < 0.1 {
+                    ColorChoice::Black
+                } else if r > 0.8 && g < 0.4 && b < 0.4 {
+                    ColorChoice::Red
+                } else if r < 0.4 && g > 0.6 && b < 0.4 {
+                    ColorChoice::Green
+                } else if r < 0.4 && g < 0.6 && b > 0.8 {
+                    ColorChoice::Blue
+                } else if r > 0.8 && g > 0.8 && b < 0.4 {
+                    ColorChoice::Yellow
+                } else if r > 0.8 && g > 0.4 && g < 0.8 && b < 0.4 {
+                    ColorChoice::Orange
+                } else if r > 0.5 && g < 0.5 && b > 0.8 {
+                    ColorChoice::Purple
+                } else if (r - 0.5).abs() < 0.1 && (g - 0.5).abs() < 0.1 && (b - 0.5).abs() < 0.1 {
+                    ColorChoice::Gray
+                } else {
+                    ColorChoice::Default
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ColorChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColorChoice::Default => write!(f, "Default"),
+            ColorChoice::White => write!(f, "White"),
+            ColorChoice::Black => write!(f, "Black"),
+            ColorChoice::Red => write!(f, "Red"),
+            ColorChoice::Green => write!(f, "Green"),
+            ColorChoice::Blue => write!(f, "Blue"),
+            ColorChoice::Yellow => write!(f, "Yellow"),
+            ColorChoice::Orange => write!(f, "Orange"),
+            ColorChoice::Purple => write!(f, "Purple"),
+            ColorChoice::Gray => write!(f, "Gray"),
+        }
+    }
+}
+
+/// The distinct colors of a layout and their constant names.
+#[derive(Debug, Default)]
+pub struct ColorPalette {
+    entries: Vec<([f32; 4], String)>,
+}
+
+impl ColorPalette {
+    /// Collect the colors used by exported nodes, in document order.
+    pub fn collect(layout: &LayoutDocument) -> Self {
+        let mut palette = Self::default();
+        for node in layout.exported_nodes() {
+            if let WidgetType::Text { attrs, .. } = &node.widget {
+                if let Some(color) = attrs.color {
+                    palette.insert(color);
+                }
+            }
+        }
+        palette
+    }
+
+    fn insert(&mut self, color: [f32; 4]) {
+        if self.name_of(color).is_some() {
+            return;
+        }
+        let base = match ColorChoice::from_rgba(Some(color)) {
+            ColorChoice::Default => None,
+            preset => Some(format!("{:?}", preset).to_uppercase()),
+        };
+        let name = match base {
+            Some(base) => {
+                let taken = self.entries.iter().filter(|(_, name)| name == &base || name.starts_with(&format!("{}_", base))).count();
+                if taken == 0 {
+                    base
+                } else {
+                    format!("{}_{}", base, taken + 1)
+                }
+            }
+            None => {
+                let unnamed = self.entries.iter().filter(|(_, name)| name.starts_with("COLOR_")).count();
+                format!("COLOR_{}", unnamed + 1)
+            }
+        };
+        self.entries.push((color, name));
+    }
+
+    /// Get the constant name for a color, if it is in the palette.
+    pub fn name_of(&self, color: [f32; 4]) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(c, _)| c.iter().zip(&color).all(|(a, b)| (a - b).abs() < COLOR_EPSILON))
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// Whether the layout uses no colors.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Write the `palette` module holding the constants.
+    pub fn write_module(&self, output: &mut String) {
+        writeln!(output, "pub mod palette {{").unwrap();
+        writeln!(output, "    use iced::Color;").unwrap();
+        writeln!(output).unwrap();
+        for (color, name) in &self.entries {
+            writeln!(output, "    pub const {}: Color = {};", name, color_literal(*color)).unwrap();
+        }
+        writeln!(output, "}}").unwrap();
+    }
+}
+
+/// Rust expression for a color literal.
+pub fn color_literal(color: [f32; 4]) -> String {
+    format!(
+        "Color::from_rgba({:.2}, {:.2}, {:.2}, {:.2})",
+        color[0], color[1], color[2], color[3]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::layout::TextAttrs;
+    use crate::model::LayoutNode;
+    use crate::test_support::doc_with;
+
+    fn colored_text(color: [f32; 4]) -> LayoutNode {
+        LayoutNode::new(WidgetType::Text {
+            content: "Hi".to_string(),
+            attrs: TextAttrs {
+                color: Some(color),
+                ..TextAttrs::default()
+            },
+        })
+    }
+
+    #[test]
+    fn test_palette_names_presets_and_numbers_the_rest() {
+        let doc = doc_with(vec![
+            colored_text([0.13, 0.27, 0.41, 1.0]),
+            colored_text([1.0, 0.2, 0.2, 1.0]),
+            colored_text([0.9, 0.1, 0.1, 1.0]),
+            colored_text([0.6, 0.7, 0.1, 1.0]),
+        ]);
+        let palette = ColorPalette::collect(&doc);
+        assert_eq!(palette.name_of([0.13, 0.27, 0.41, 1.0]), Some("COLOR_1"));
+        assert_eq!(palette.name_of([1.0, 0.2, 0.2, 1.0]), Some("RED"));
+        assert_eq!(palette.name_of([0.9, 0.1, 0.1, 1.0]), Some("RED_2"));
+        assert_eq!(palette.name_of([0.6, 0.7, 0.1, 1.0]), Some("COLOR_2"));
+    }
+
+    #[test]
+    fn test_palette_dedupes_near_equal_colors() {
+        let doc = doc_with(vec![
+            colored_text([0.2, 0.5, 1.0, 1.0]),
+            colored_text([0.201, 0.5, 0.999, 1.0]),
+        ]);
+        let palette = ColorPalette::collect(&doc);
+        assert_eq!(palette.entries.len(), 1);
+        assert_eq!(palette.name_of([0.201, 0.5, 0.999, 1.0]), Some("BLUE"));
+
+        let mut module = String::new();
+        palette.write_module(&mut module);
+        assert!(module.contains("pub const BLUE: Color = Color::from_rgba(0.20, 0.50, 1.00, 1.00);"));
+    }
+}
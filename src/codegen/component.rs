@@ -0,0 +1,131 @@
+//! Component module export.
+//!
+//! Generates a self-contained Elm-style module from a layout: a state struct
+//! holding the bound fields, a message enum, and `update` and `view`
+//! methods. Type names are derived from the layout name, so a layout named
+//! "Login Form" produces `LoginFormState` and `LoginFormMessage`.
+//!
+//! A runnable example is the same module with a `main` that opens it in a
+//! window.
+
+use crate::model::{I18nMode, LayoutDocument, ProjectConfig};
+use crate::util::to_ident;
+use std::fmt::Write;
+
+use super::generator::{generate_node, with_header, write_imports, write_node_doc, Context};
+use super::stubs::{collect_variants, write_message_enum, write_update_match};
+
+/// Second header line of component modules and examples.
+const NOTE: &str = "Regenerate by opening this project in Iced Builder.";
+
+/// Generate a component module for a layout.
+///
+/// Message stubs used with several payloads keep the first one; export
+/// refuses such layouts before getting here.
+pub fn generate_component(layout: &LayoutDocument, config: &ProjectConfig) -> String {
+    with_header(layout, config, NOTE, &component_body(layout, config))
+}
+
+/// The component module without its header.
+fn component_body(layout: &LayoutDocument, config: &ProjectConfig) -> String {
+    let name = crate::util::to_pascal_case(&layout.name, "Layout");
+    let state_name = format!("{}State", name);
+    let message_name = format!("{}Message", name);
+    tracing::debug!(target: "iced_builder::codegen", component = %name, "Generating component module");
+
+    let mut output = String::new();
+    write_imports(&mut output, config.codegen_style);
+
+    let ctx = Context {
+        colors: Context::palette(layout, config),
+        state: "self",
+        message: &message_name,
+        style: config.codegen_style,
+        strings: Context::strings(layout, config),
+        translate: &config.i18n_function,
+    };
+    if let Some(palette) = &ctx.colors {
+        palette.write_module(&mut output);
+        writeln!(output).unwrap();
+    }
+
+    // State struct with one field per binding
+    writeln!(output, "/// State of the {} component.", layout.name).unwrap();
+    writeln!(output, "#[derive(Debug, Clone, Default)]").unwrap();
+    writeln!(output, "pub struct {} {{", state_name).unwrap();
+    for (field, ty) in state_fields(layout) {
+        writeln!(output, "    pub {}: {},", to_ident(field), ty).unwrap();
+    }
+    writeln!(output, "}}").unwrap();
+    writeln!(output).unwrap();
+
+    let variants = collect_variants(layout);
+    write_message_enum(&mut output, &message_name, &variants);
+    writeln!(output).unwrap();
+
+    writeln!(output, "impl {} {{", state_name).unwrap();
+    writeln!(output, "    /// Apply a message to the state.").unwrap();
+    writeln!(output, "    pub fn update(&mut self, message: {}) {{", message_name).unwrap();
+    write_update_match(&mut output, &message_name, "self", 2, &variants);
+    writeln!(output, "    }}").unwrap();
+    writeln!(output).unwrap();
+    writeln!(output, "    /// Render the component.").unwrap();
+    if layout.root.name.is_some() {
+        writeln!(output, "    ///").unwrap();
+        write_node_doc(&mut output, &layout.root, 1);
+    }
+    writeln!(output, "    pub fn view(&self) -> Element<'_, {}> {{", message_name).unwrap();
+    writeln!(output, "{}.into()", generate_node(&layout.root, 2, &ctx)).unwrap();
+    writeln!(output, "    }}").unwrap();
+    writeln!(output, "}}").unwrap();
+
+    if config.generate_tests {
+        let setup = format!("{}::default()", state_name);
+        super::snapshot::write_test_module(&mut output, layout, &setup, "state.view()");
+    }
+
+    output
+}
+
+/// Generate a standalone example program showing the layout.
+///
+/// Meant for `examples/<name>.rs`, so `cargo run --example <name>` opens it.
+pub fn generate_example(layout: &LayoutDocument, config: &ProjectConfig) -> String {
+    let name = crate::util::to_pascal_case(&layout.name, "Layout");
+    // An example has no translation function to call, so it keeps literals
+    let config = ProjectConfig {
+        i18n_mode: I18nMode::Literals,
+        ..config.clone()
+    };
+    let mut output = component_body(layout, &config);
+    writeln!(output).unwrap();
+    writeln!(output, "fn main() -> iced::Result {{").unwrap();
+    writeln!(
+        output,
+        "    iced::run(\"{}\", {}State::update, {}State::view)",
+        layout.name.escape_default(),
+        name,
+        name
+    )
+    .unwrap();
+    writeln!(output, "}}").unwrap();
+    with_header(layout, &config, NOTE, &output)
+}
+
+/// Bound state fields and their Rust types, sorted by name.
+pub(super) fn state_fields(layout: &LayoutDocument) -> Vec<(&str, &'static str)> {
+    let mut fields: Vec<(&str, &'static str)> = Vec::new();
+    for node in layout.exported_nodes() {
+        let Some(ty) = node.widget.binding_type() else {
+            continue;
+        };
+        if let Some(field) = node.widget.value_binding() {
+            if !fields.iter().any(|(f, _)| *f == field) {
+                fields.push((field, ty));
+            }
+        }
+    }
+    fields.sort_by_key(|(field, _)| *field);
+    fields
+}
+
@@ -4,28 +4,111 @@
 
 use crate::model::{
     layout::{AlignmentSpec, LengthSpec, PaddingSpec, WidgetType},
-    LayoutDocument, LayoutNode, ProjectConfig,
+    CodegenStyle, ComponentId, ExportMode, I18nMode, LayoutDocument, LayoutNode, ProjectConfig,
 };
+use crate::util::to_ident;
 use std::fmt::Write;
 
+use super::colors::{color_literal, ColorPalette};
+use super::strings::StringTable;
+
+/// First line of every file written by the code generator.
+pub const GENERATED_HEADER: &str = "// Auto-generated by Iced Builder – do not edit manually.";
+
+/// Start of the trailer line holding the hash of the file as written.
+pub const STAMP_PREFIX: &str = "// iced-builder:hash=";
+
+/// Options shared by every node during generation.
+#[derive(Debug)]
+pub(super) struct Context<'a> {
+    /// Named colors, when colors are generated as constants.
+    pub colors: Option<ColorPalette>,
+    /// Expression the view reads bindings from.
+    pub state: &'a str,
+    /// Name of the message type variants are taken from.
+    pub message: &'a str,
+    /// Whether multi-child containers use macros or builders.
+    pub style: CodegenStyle,
+    /// Translation keys, when text is generated as lookups.
+    pub strings: Option<StringTable>,
+    /// Function that looks up a translation key.
+    pub translate: &'a str,
+}
+
+impl Default for Context<'_> {
+    fn default() -> Self {
+        Self {
+            colors: None,
+            state: "state",
+            message: "Message",
+            style: CodegenStyle::default(),
+            strings: None,
+            translate: "t",
+        }
+    }
+}
+
+impl Context<'_> {
+    /// Collect the palette when colors are generated as constants.
+    pub fn palette(layout: &LayoutDocument, config: &ProjectConfig) -> Option<ColorPalette> {
+        config
+            .color_constants
+            .then(|| ColorPalette::collect(layout))
+            .filter(|palette| !palette.is_empty())
+    }
+
+    /// Collect translation keys when text is generated as lookups.
+    pub fn strings(layout: &LayoutDocument, config: &ProjectConfig) -> Option<StringTable> {
+        (config.i18n_mode == I18nMode::Keys).then(|| StringTable::collect(layout))
+    }
+
+    /// Rust expression for a node's text, looking it up by key when it has one.
+    fn text_code(&self, id: ComponentId, content: &str) -> String {
+        match self.strings.as_ref().and_then(|strings| strings.key_of(id)) {
+            Some(key) => format!("{}(\"{}\")", self.translate, key),
+            None => format!("\"{}\"", escape_string(content)),
+        }
+    }
+
+    /// Rust expression for a color, using its constant when there is one.
+    fn color_code(&self, color: [f32; 4]) -> String {
+        match self.colors.as_ref().and_then(|palette| palette.name_of(color)) {
+            Some(name) => format!("palette::{}", name),
+            None => color_literal(color),
+        }
+    }
+}
+
 /// Generate Rust code from a layout document.
+///
+/// The code is unformatted; [`crate::util::try_format_rust_code`] tidies
+/// it up, and [`crate::model::Project::export`] does both and writes it.
+///
+/// ```
+/// use iced_builder::codegen::generate_code;
+/// use iced_builder::model::layout::{ButtonAttrs, WidgetType};
+/// use iced_builder::model::{LayoutDocument, LayoutNode, ProjectConfig};
+///
+/// let mut layout = LayoutDocument::default();
+/// let button = LayoutNode::new(WidgetType::Button {
+///     label: "Save".to_string(),
+///     message_stub: "Save".to_string(),
+///     attrs: ButtonAttrs::default(),
+/// });
+/// layout.root.children_mut().unwrap().push(button);
+///
+/// let code = generate_code(&layout, &ProjectConfig::default());
+/// assert!(code.contains("on_press(Message::Save)"));
+/// ```
 pub fn generate_code(layout: &LayoutDocument, config: &ProjectConfig) -> String {
     tracing::info!(target: "iced_builder::codegen", layout_name = %layout.name, "Starting code generation");
+
+    if config.export_mode == ExportMode::Component {
+        return super::component::generate_component(layout, config);
+    }
     
     let mut output = String::new();
-
-    // Header comment
-    writeln!(output, "// Auto-generated by Iced Builder – do not edit manually.").unwrap();
-    writeln!(output, "// Regenerate by opening this project in Iced Builder.").unwrap();
-    writeln!(output).unwrap();
-
-    // Imports
-    writeln!(output, "use iced::widget::{{").unwrap();
-    writeln!(output, "    button, checkbox, column, container, pick_list, row,").unwrap();
-    writeln!(output, "    scrollable, slider, stack, text, text_input, Space,").unwrap();
-    writeln!(output, "}};").unwrap();
-    writeln!(output, "use iced::{{Alignment, Color, Element, Length}};").unwrap();
-    writeln!(output).unwrap();
+    write_imports(&mut output, config.codegen_style);
 
     // Import user types
     writeln!(output, "use {};", config.message_type).unwrap();
@@ -40,7 +123,21 @@ pub fn generate_code(layout: &LayoutDocument, config: &ProjectConfig) -> String
         .unwrap_or("Message");
     let state_name = config.state_type.split("::").last().unwrap_or("AppState");
 
+    let ctx = Context {
+        colors: Context::palette(layout, config),
+        state: "state",
+        message: message_name,
+        style: config.codegen_style,
+        strings: Context::strings(layout, config),
+        translate: &config.i18n_function,
+    };
+    if let Some(palette) = &ctx.colors {
+        palette.write_module(&mut output);
+        writeln!(output).unwrap();
+    }
+
     // View function
+    write_node_doc(&mut output, &layout.root, 0);
     writeln!(
         output,
         "pub fn view(state: &{}) -> Element<{}> {{",
@@ -50,30 +147,138 @@ pub fn generate_code(layout: &LayoutDocument, config: &ProjectConfig) -> String
 
     // Generate the widget tree
     tracing::debug!(target: "iced_builder::codegen", "Generating widget tree");
-    let widget_code = generate_node(&layout.root, 1);
-    writeln!(output, "{}", widget_code).unwrap();
+    let widget_code = generate_node(&layout.root, 1, &ctx);
+    writeln!(output, "{}.into()", widget_code).unwrap();
 
     writeln!(output, "}}").unwrap();
 
-    output
+    if config.generate_tests {
+        let setup = format!("{}::default()", state_name);
+        super::snapshot::write_test_module(&mut output, layout, &setup, "view(&state)");
+    }
+
+    with_header(layout, config, "Regenerate by opening this project in Iced Builder.", &output)
+}
+
+/// Generate the widget expression for a single node and its subtree.
+///
+/// Used for copying part of a layout as code. Colors are written as
+/// literals and text as plain strings, since a snippet has no palette
+/// module or strings file to refer to.
+pub fn generate_node_code(node: &LayoutNode, config: &ProjectConfig) -> String {
+    let message = config.message_type.split("::").last().unwrap_or("Message");
+    let ctx = Context {
+        message,
+        style: config.codegen_style,
+        ..Context::default()
+    };
+    generate_node(node, 0, &ctx)
+}
+
+/// Prefix generated code with the generated-file header.
+///
+/// The header names the layout, the file it was saved to and the builder
+/// version, and hashes the code rather than recording when it was
+/// generated, so an unchanged layout exports identical bytes.
+pub(super) fn with_header(layout: &LayoutDocument, config: &ProjectConfig, note: &str, body: &str) -> String {
+    format!(
+        "{}\n// {}\n// Layout: {} ({}), content hash {:016x}\n// Iced Builder {}\n\n{}",
+        GENERATED_HEADER,
+        note,
+        layout.name.escape_debug(),
+        config.layout_file().display(),
+        content_hash(body),
+        env!("CARGO_PKG_VERSION"),
+        body
+    )
+}
+
+/// Append the trailer line hashing `code` as it will be written.
+///
+/// Runs after formatting, so [`stamp_matches`] can later tell whether the
+/// file was edited since export.
+pub fn stamp(code: &str) -> String {
+    let mut code = code.to_string();
+    if !code.ends_with('\n') {
+        code.push('\n');
+    }
+    let hash = content_hash(&code);
+    writeln!(code, "{}{:016x}", STAMP_PREFIX, hash).unwrap();
+    code
+}
+
+/// Whether a stamped file is unchanged since it was written, or `None` if it
+/// has no trailer.
+pub fn stamp_matches(contents: &str) -> Option<bool> {
+    let body = contents.strip_suffix('\n').unwrap_or(contents);
+    let (code, trailer) = match body.rfind('\n') {
+        Some(index) => body.split_at(index + 1),
+        None => ("", body),
+    };
+    let hash = trailer.strip_prefix(STAMP_PREFIX)?;
+    Some(u64::from_str_radix(hash, 16).ok() == Some(content_hash(code)))
+}
+
+/// Write a doc comment describing `node` if it has a friendly name.
+pub(super) fn write_node_doc(output: &mut String, node: &LayoutNode, indent: usize) {
+    let Some(name) = &node.name else {
+        return;
+    };
+    let children = node
+        .children()
+        .map(|children| children.iter().filter(|c| !c.exclude_from_export).count());
+    let description = match children {
+        Some(1) => format!("{}, 1 child", node.widget.type_name()),
+        Some(count) => format!("{}, {} children", node.widget.type_name(), count),
+        None => node.widget.type_name().to_string(),
+    };
+    writeln!(
+        output,
+        "{}/// Generated from node \"{}\" ({}).",
+        "    ".repeat(indent),
+        name.escape_debug(),
+        description
+    )
+    .unwrap();
+}
+
+/// FNV-1a hash of generated code, stable across runs and toolchains unlike
+/// the std hasher.
+fn content_hash(code: &str) -> u64 {
+    code.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Write the iced imports.
+pub(super) fn write_imports(output: &mut String, style: CodegenStyle) {
+    writeln!(output, "use iced::widget::{{").unwrap();
+    writeln!(output, "    button, checkbox, column, container, pick_list, row,").unwrap();
+    writeln!(output, "    scrollable, slider, stack, text, text_input, Space,").unwrap();
+    if style == CodegenStyle::Builders {
+        writeln!(output, "    Column, Row, Stack,").unwrap();
+    }
+    writeln!(output, "}};").unwrap();
+    writeln!(output, "use iced::{{Alignment, Color, Element, Length}};").unwrap();
+    writeln!(output).unwrap();
 }
 
 /// Generate code for a single node.
-fn generate_node(node: &LayoutNode, indent: usize) -> String {
+pub(super) fn generate_node(node: &LayoutNode, indent: usize, ctx: &Context) -> String {
     let indent_str = "    ".repeat(indent);
 
     match &node.widget {
         WidgetType::Column { children, attrs } => {
-            generate_column(children, attrs, indent)
+            generate_column(children, attrs, indent, ctx)
         }
 
         WidgetType::Row { children, attrs } => {
-            generate_row(children, attrs, indent)
+            generate_row(children, attrs, indent, ctx)
         }
 
         WidgetType::Container { child, attrs } => {
-            let child_code = match child {
-                Some(c) => generate_node(c, indent + 1),
+            let child_code = match child.as_deref().filter(|c| !c.exclude_from_export) {
+                Some(c) => generate_node(c, indent + 1, ctx),
                 None => format!("{}text(\"\")", "    ".repeat(indent + 1)),
             };
 
@@ -86,36 +291,33 @@ fn generate_node(node: &LayoutNode, indent: usize) -> String {
             if attrs.align_y != AlignmentSpec::Start {
                 code = format!("{}.align_y({})", code, alignment_to_code(attrs.align_y));
             }
-            format!("{}.into()", code)
+            code
         }
 
         WidgetType::Scrollable { child, attrs } => {
-            let child_code = match child {
-                Some(c) => generate_node(c, indent + 1),
+            let child_code = match child.as_deref().filter(|c| !c.exclude_from_export) {
+                Some(c) => generate_node(c, indent + 1, ctx),
                 None => format!("{}text(\"\")", "    ".repeat(indent + 1)),
             };
 
             let mut code = format!("{}scrollable(\n{}\n{})", indent_str, child_code, indent_str);
             code = append_length_attrs(&code, attrs.width, attrs.height);
-            format!("{}.into()", code)
+            code
         }
 
         WidgetType::Stack { children, attrs } => {
-            generate_stack(children, attrs, indent)
+            generate_stack(children, attrs, indent, ctx)
         }
 
         WidgetType::Text { content, attrs } => {
-            let mut code = format!("{}text(\"{}\")", indent_str, escape_string(content));
+            let mut code = format!("{}text({})", indent_str, ctx.text_code(node.id, content));
             if attrs.font_size != 16.0 {
                 code = format!("{}.size({:.0})", code, attrs.font_size);
             }
             if let Some(color) = attrs.color {
-                code = format!(
-                    "{}.color(Color::from_rgba({:.2}, {:.2}, {:.2}, {:.2}))",
-                    code, color[0], color[1], color[2], color[3]
-                );
+                code = format!("{}.color({})", code, ctx.color_code(color));
             }
-            format!("{}.into()", code)
+            code
         }
 
         WidgetType::Button {
@@ -124,13 +326,14 @@ fn generate_node(node: &LayoutNode, indent: usize) -> String {
             attrs,
         } => {
             let mut code = format!(
-                "{}button(text(\"{}\")).on_press(Message::{})",
+                "{}button(text({})).on_press({}::{})",
                 indent_str,
-                escape_string(label),
+                ctx.text_code(node.id, label),
+                ctx.message,
                 message_stub
             );
             code = append_length_attrs(&code, attrs.width, attrs.height);
-            format!("{}.into()", code)
+            code
         }
 
         WidgetType::TextInput {
@@ -140,16 +343,18 @@ fn generate_node(node: &LayoutNode, indent: usize) -> String {
             attrs,
         } => {
             let mut code = format!(
-                "{}text_input(\"{}\", &state.{}).on_input(Message::{})",
+                "{}text_input(\"{}\", &{}.{}).on_input({}::{})",
                 indent_str,
                 escape_string(placeholder),
-                value_binding,
+                ctx.state,
+                to_ident(value_binding),
+                ctx.message,
                 message_stub
             );
             if attrs.width != LengthSpec::Shrink {
                 code = format!("{}.width({})", code, length_to_code(attrs.width));
             }
-            format!("{}.into()", code)
+            code
         }
 
         WidgetType::Checkbox {
@@ -159,10 +364,12 @@ fn generate_node(node: &LayoutNode, indent: usize) -> String {
             ..
         } => {
             format!(
-                "{}checkbox(\"{}\", state.{}).on_toggle(Message::{}).into()",
+                "{}checkbox(\"{}\", {}.{}).on_toggle({}::{})",
                 indent_str,
                 escape_string(label),
-                checked_binding,
+                ctx.state,
+                to_ident(checked_binding),
+                ctx.message,
                 message_stub
             )
         }
@@ -175,13 +382,13 @@ fn generate_node(node: &LayoutNode, indent: usize) -> String {
             attrs,
         } => {
             let mut code = format!(
-                "{}slider({:.1}..={:.1}, state.{}, Message::{})",
-                indent_str, min, max, value_binding, message_stub
+                "{}slider({:.1}..={:.1}, {}.{}, {}::{})",
+                indent_str, min, max, ctx.state, to_ident(value_binding), ctx.message, message_stub
             );
             if attrs.width != LengthSpec::Fill {
                 code = format!("{}.width({})", code, length_to_code(attrs.width));
             }
-            format!("{}.into()", code)
+            code
         }
 
         WidgetType::PickList {
@@ -192,22 +399,66 @@ fn generate_node(node: &LayoutNode, indent: usize) -> String {
         } => {
             let options_code: Vec<String> = options.iter().map(|o| format!("\"{}\"", o)).collect();
             format!(
-                "{}pick_list(&[{}][..], state.{}.as_ref(), Message::{}).into()",
+                "{}pick_list(&[{}][..], {}.{}.as_ref(), {}::{})",
                 indent_str,
                 options_code.join(", "),
-                selected_binding,
+                ctx.state,
+                to_ident(selected_binding),
+                ctx.message,
                 message_stub
             )
         }
 
         WidgetType::Space { width, height } => {
             format!(
-                "{}Space::new({}, {}).into()",
+                "{}Space::new({}, {})",
                 indent_str,
                 length_to_code(*width),
                 length_to_code(*height)
             )
         }
+
+        WidgetType::ComponentRef { .. } => {
+            // Projects inline component trees before generating code, so
+            // only a bare layout gets here; it calls the component's module
+            let module = node.widget.component_name().unwrap_or("component");
+            format!("{}{}::view({})", indent_str, module, ctx.state)
+        }
+    }
+}
+
+/// Generate a multi-child container and its children in the configured style.
+///
+/// `kind` is the macro name; the builder type is its capitalized form.
+fn generate_children(kind: &str, children: &[LayoutNode], indent: usize, ctx: &Context) -> String {
+    let indent_str = "    ".repeat(indent);
+    let children: Vec<&LayoutNode> = children.iter().filter(|c| !c.exclude_from_export).collect();
+
+    match ctx.style {
+        CodegenStyle::Macros if children.is_empty() => format!("{}{}![]", indent_str, kind),
+        CodegenStyle::Macros => {
+            let mut c = format!("{}{}![\n", indent_str, kind);
+            for (i, child) in children.iter().enumerate() {
+                let child_code = generate_node(child, indent + 1, ctx);
+                c.push_str(&child_code);
+                if i < children.len() - 1 {
+                    c.push_str(",\n");
+                } else {
+                    c.push('\n');
+                }
+            }
+            c.push_str(&format!("{}]", indent_str));
+            c
+        }
+        CodegenStyle::Builders => {
+            let type_name = format!("{}{}", kind[..1].to_uppercase(), &kind[1..]);
+            let mut c = format!("{}{}::new()", indent_str, type_name);
+            for child in children {
+                let child_code = generate_node(child, indent + 1, ctx);
+                c.push_str(&format!("\n{}    .push({})", indent_str, child_code.trim_start()));
+            }
+            c
+        }
     }
 }
 
@@ -216,25 +467,9 @@ fn generate_column(
     children: &[LayoutNode],
     attrs: &crate::model::layout::ContainerAttrs,
     indent: usize,
+    ctx: &Context,
 ) -> String {
-    let indent_str = "    ".repeat(indent);
-
-    let mut code = if children.is_empty() {
-        format!("{}column![]", indent_str)
-    } else {
-        let mut c = format!("{}column![\n", indent_str);
-        for (i, child) in children.iter().enumerate() {
-            let child_code = generate_node(child, indent + 1);
-            c.push_str(&child_code);
-            if i < children.len() - 1 {
-                c.push_str(",\n");
-            } else {
-                c.push('\n');
-            }
-        }
-        c.push_str(&format!("{}]", indent_str));
-        c
-    };
+    let mut code = generate_children("column", children, indent, ctx);
 
     code = append_container_attrs(&code, attrs, indent);
     
@@ -243,7 +478,7 @@ fn generate_column(
         code = format!("{}.align_x({})", code, alignment_to_code(attrs.align_x));
     }
     
-    format!("{}.into()", code)
+    code
 }
 
 /// Generate code for row containers with align_y support.
@@ -251,25 +486,9 @@ fn generate_row(
     children: &[LayoutNode],
     attrs: &crate::model::layout::ContainerAttrs,
     indent: usize,
+    ctx: &Context,
 ) -> String {
-    let indent_str = "    ".repeat(indent);
-
-    let mut code = if children.is_empty() {
-        format!("{}row![]", indent_str)
-    } else {
-        let mut c = format!("{}row![\n", indent_str);
-        for (i, child) in children.iter().enumerate() {
-            let child_code = generate_node(child, indent + 1);
-            c.push_str(&child_code);
-            if i < children.len() - 1 {
-                c.push_str(",\n");
-            } else {
-                c.push('\n');
-            }
-        }
-        c.push_str(&format!("{}]", indent_str));
-        c
-    };
+    let mut code = generate_children("row", children, indent, ctx);
 
     code = append_container_attrs(&code, attrs, indent);
     
@@ -278,7 +497,7 @@ fn generate_row(
         code = format!("{}.align_y({})", code, alignment_to_code(attrs.align_y));
     }
     
-    format!("{}.into()", code)
+    code
 }
 
 /// Generate code for stack containers.
@@ -286,29 +505,13 @@ fn generate_stack(
     children: &[LayoutNode],
     attrs: &crate::model::layout::ContainerAttrs,
     indent: usize,
+    ctx: &Context,
 ) -> String {
-    let indent_str = "    ".repeat(indent);
-
-    let mut code = if children.is_empty() {
-        format!("{}stack![]", indent_str)
-    } else {
-        let mut c = format!("{}stack![\n", indent_str);
-        for (i, child) in children.iter().enumerate() {
-            let child_code = generate_node(child, indent + 1);
-            c.push_str(&child_code);
-            if i < children.len() - 1 {
-                c.push_str(",\n");
-            } else {
-                c.push('\n');
-            }
-        }
-        c.push_str(&format!("{}]", indent_str));
-        c
-    };
+    let mut code = generate_children("stack", children, indent, ctx);
 
     code = append_length_attrs(&code, attrs.width, attrs.height);
     
-    format!("{}.into()", code)
+    code
 }
 
 /// Append container attributes to code.
@@ -414,24 +617,29 @@ mod tests {
 
     #[test]
     fn test_generate_column_with_alignment() {
-        let mut attrs = ContainerAttrs::default();
-        attrs.align_x = AlignmentSpec::Center;
+        let attrs = ContainerAttrs {
+            align_x: AlignmentSpec::Center,
+            ..Default::default()
+        };
         
-        let code = generate_column(&[], &attrs, 1);
+        let code = generate_column(&[], &attrs, 1, &Context::default());
         assert!(code.contains("column![]"));
         assert!(code.contains(".align_x(Alignment::Center)"));
-        assert!(code.contains(".into()"));
+        // Children are converted by the macros, so only the root calls into()
+        assert!(!code.contains(".into()"));
     }
 
     #[test]
     fn test_generate_row_with_alignment() {
-        let mut attrs = ContainerAttrs::default();
-        attrs.align_y = AlignmentSpec::End;
+        let attrs = ContainerAttrs {
+            align_y: AlignmentSpec::End,
+            ..Default::default()
+        };
         
-        let code = generate_row(&[], &attrs, 1);
+        let code = generate_row(&[], &attrs, 1, &Context::default());
         assert!(code.contains("row![]"));
         assert!(code.contains(".align_y(Alignment::End)"));
-        assert!(code.contains(".into()"));
+        assert!(!code.contains(".into()"));
     }
 
     #[test]
@@ -448,12 +656,12 @@ mod tests {
         ];
         
         let attrs = ContainerAttrs::default();
-        let code = generate_stack(&children, &attrs, 1);
+        let code = generate_stack(&children, &attrs, 1, &Context::default());
         
         assert!(code.contains("stack!["));
         assert!(code.contains("Layer 1"));
         assert!(code.contains("Layer 2"));
-        assert!(code.contains(".into()"));
+        assert!(!code.contains(".into()"));
     }
 
     #[test]
@@ -474,16 +682,45 @@ mod tests {
             attrs: TextAttrs {
                 font_size: 20.0,
                 color: Some([1.0, 0.0, 0.0, 1.0]),
-                horizontal_alignment: AlignmentSpec::Start,
+                align_x: AlignmentSpec::Start,
             },
         });
         
-        let code = generate_node(&node, 1);
+        let code = generate_node(&node, 1, &Context::default());
         assert!(code.contains("text(\"Colored\")"));
         assert!(code.contains(".size(20)"));
         assert!(code.contains(".color(Color::from_rgba"));
     }
 
+    #[test]
+    fn test_generate_color_constants() {
+        let text = |color| {
+            LayoutNode::new(WidgetType::Text {
+                content: "Colored".to_string(),
+                attrs: TextAttrs {
+                    color: Some(color),
+                    ..TextAttrs::default()
+                },
+            })
+        };
+        let mut layout = LayoutDocument::default();
+        *layout.root.children_mut().unwrap() = vec![text([1.0, 0.2, 0.2, 1.0]), text([1.0, 0.2, 0.2, 1.0])];
+        let config = ProjectConfig {
+            color_constants: true,
+            ..ProjectConfig::default()
+        };
+
+        let code = generate_code(&layout, &config);
+        assert!(code.contains("pub mod palette {"));
+        assert_eq!(code.matches("Color::from_rgba").count(), 1);
+        assert_eq!(code.matches(".color(palette::RED)").count(), 2);
+
+        // Literals stay inline when the option is off
+        let code = generate_code(&layout, &ProjectConfig::default());
+        assert!(!code.contains("mod palette"));
+        assert_eq!(code.matches(".color(Color::from_rgba(1.00, 0.20, 0.20, 1.00))").count(), 2);
+    }
+
     #[test]
     fn test_generate_button() {
         let node = LayoutNode::new(WidgetType::Button {
@@ -492,7 +729,7 @@ mod tests {
             attrs: ButtonAttrs::default(),
         });
         
-        let code = generate_node(&node, 1);
+        let code = generate_node(&node, 1, &Context::default());
         assert!(code.contains("button(text(\"Click Me\"))"));
         assert!(code.contains(".on_press(Message::OnClick)"));
     }
@@ -506,11 +743,24 @@ mod tests {
             attrs: InputAttrs::default(),
         });
         
-        let code = generate_node(&node, 1);
+        let code = generate_node(&node, 1, &Context::default());
         assert!(code.contains("text_input(\"Enter name\", &state.username)"));
         assert!(code.contains(".on_input(Message::UsernameChanged)"));
     }
 
+    #[test]
+    fn test_generate_keyword_binding_as_raw_identifier() {
+        let node = LayoutNode::new(WidgetType::TextInput {
+            placeholder: String::new(),
+            value_binding: "type".to_string(),
+            message_stub: "TypeChanged".to_string(),
+            attrs: InputAttrs::default(),
+        });
+
+        let code = generate_node(&node, 1, &Context::default());
+        assert!(code.contains("&state.r#type)"));
+    }
+
     #[test]
     fn test_generate_checkbox() {
         let node = LayoutNode::new(WidgetType::Checkbox {
@@ -520,7 +770,7 @@ mod tests {
             attrs: CheckboxAttrs { spacing: 10.0 },
         });
         
-        let code = generate_node(&node, 1);
+        let code = generate_node(&node, 1, &Context::default());
         assert!(code.contains("checkbox(\"Accept terms\", state.accepted)"));
         assert!(code.contains(".on_toggle(Message::ToggleAccept)"));
     }
@@ -535,36 +785,81 @@ mod tests {
             attrs: SliderAttrs { width: LengthSpec::Fill },
         });
         
-        let code = generate_node(&node, 1);
+        let code = generate_node(&node, 1, &Context::default());
         assert!(code.contains("slider(0.0..=100.0, state.volume, Message::VolumeChanged)"));
     }
 
+    #[test]
+    fn test_generate_skips_excluded_subtrees() {
+        let kept = LayoutNode::new(WidgetType::Text {
+            content: "Kept".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        let mut excluded = LayoutNode::new(WidgetType::Text {
+            content: "Draft".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        excluded.exclude_from_export = true;
+        let mut hidden = LayoutNode::new(WidgetType::Text {
+            content: "Hidden".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        hidden.hidden = true;
+
+        let code = generate_column(&[kept, excluded.clone(), hidden], &ContainerAttrs::default(), 1, &Context::default());
+        assert!(code.contains("Kept"));
+        assert!(!code.contains("Draft"));
+        // Hidden only affects the canvas
+        assert!(code.contains("Hidden"));
+
+        let container = LayoutNode::new(WidgetType::Container {
+            child: Some(Box::new(excluded)),
+            attrs: ContainerAttrs::default(),
+        });
+        let code = generate_node(&container, 1, &Context::default());
+        assert!(!code.contains("Draft"));
+        assert!(code.contains("text(\"\")"));
+    }
+
+    #[test]
+    fn test_generate_component_ref_calls_view() {
+        let node = LayoutNode::new(WidgetType::ComponentRef {
+            file: std::path::PathBuf::from("layouts/card.ron"),
+        });
+        let code = generate_node(&node, 1, &Context::default());
+        assert_eq!(code, "    card::view(state)");
+    }
+
     #[test]
     fn test_generate_container_with_padding() {
-        let mut attrs = ContainerAttrs::default();
-        attrs.padding = PaddingSpec { top: 10.0, right: 10.0, bottom: 10.0, left: 10.0 };
+        let attrs = ContainerAttrs {
+            padding: PaddingSpec { top: 10.0, right: 10.0, bottom: 10.0, left: 10.0 },
+            ..Default::default()
+        };
         
         let node = LayoutNode::new(WidgetType::Container {
             child: None,
             attrs,
         });
         
-        let code = generate_node(&node, 1);
+        let code = generate_node(&node, 1, &Context::default());
         assert!(code.contains("container("));
         assert!(code.contains(".padding(10)"));
     }
 
     #[test]
     fn test_generate_container_with_different_padding() {
-        let mut attrs = ContainerAttrs::default();
-        attrs.padding = PaddingSpec { top: 10.0, right: 20.0, bottom: 30.0, left: 40.0 };
+        let attrs = ContainerAttrs {
+            padding: PaddingSpec { top: 10.0, right: 20.0, bottom: 30.0, left: 40.0 },
+            ..Default::default()
+        };
         
         let node = LayoutNode::new(WidgetType::Container {
             child: None,
             attrs,
         });
         
-        let code = generate_node(&node, 1);
+        let code = generate_node(&node, 1, &Context::default());
         assert!(code.contains(".padding([10, 20, 30, 40])"));
     }
 
@@ -575,7 +870,7 @@ mod tests {
             height: LengthSpec::Fixed(30.0),
         });
         
-        let code = generate_node(&node, 1);
+        let code = generate_node(&node, 1, &Context::default());
         assert!(code.contains("Space::new(Length::Fixed(20.0), Length::Fixed(30.0))"));
     }
 
@@ -595,11 +890,75 @@ mod tests {
             attrs: crate::model::layout::PickListAttrs::default(),
         });
         
-        let code = generate_node(&node, 1);
+        let code = generate_node(&node, 1, &Context::default());
         assert!(code.contains("pick_list"));
         assert!(code.contains("\"Red\", \"Green\", \"Blue\""));
         assert!(code.contains("state.color"));
         assert!(code.contains("Message::ColorSelected"));
     }
-}
 
+    #[test]
+    fn test_generate_node_code_for_subtree() {
+        let row = LayoutNode::new(WidgetType::Row {
+            children: vec![
+                LayoutNode::new(WidgetType::Text {
+                    content: "Name".to_string(),
+                    attrs: TextAttrs {
+                        color: Some([1.0, 0.0, 0.0, 1.0]),
+                        ..TextAttrs::default()
+                    },
+                }),
+                LayoutNode::new(WidgetType::Button {
+                    label: "Save".to_string(),
+                    message_stub: "Save".to_string(),
+                    attrs: ButtonAttrs::default(),
+                }),
+            ],
+            attrs: ContainerAttrs::default(),
+        });
+        let config = ProjectConfig {
+            message_type: "crate::ui::Msg".to_string(),
+            color_constants: true,
+            i18n_mode: I18nMode::Keys,
+            ..ProjectConfig::default()
+        };
+
+        let code = generate_node_code(&row, &config);
+        assert!(code.starts_with("row!["));
+        assert!(code.contains("text(\"Name\").color(Color::from_rgba(1.00, 0.00, 0.00, 1.00))"));
+        assert!(code.contains("button(text(\"Save\")).on_press(Msg::Save)"));
+        assert!(!code.contains(".into()"));
+
+        let config = ProjectConfig {
+            codegen_style: CodegenStyle::Builders,
+            ..config
+        };
+        assert!(generate_node_code(&row, &config).starts_with("Row::new()\n    .push(text(\"Name\")"));
+    }
+
+    #[test]
+    fn test_stamp_detects_edits() {
+        let stamped = stamp("fn main() {}");
+        assert!(stamped.starts_with("fn main() {}\n// iced-builder:hash="));
+        assert_eq!(stamp_matches(&stamped), Some(true));
+        assert_eq!(stamp_matches(&stamped.replace("main", "start")), Some(false));
+        assert_eq!(stamp_matches("fn main() {}\n"), None);
+    }
+
+    #[test]
+    fn test_named_root_documents_view() {
+        let mut doc = LayoutDocument::default();
+        doc.root.name = Some("Sidebar".to_string());
+        doc.root.children_mut().unwrap().push(LayoutNode::new(WidgetType::Text {
+            content: "Home".to_string(),
+            attrs: TextAttrs::default(),
+        }));
+
+        let code = generate_code(&doc, &ProjectConfig::default());
+        assert!(code.contains("/// Generated from node \"Sidebar\" (Column, 1 child).\npub fn view("));
+        assert!(code.contains(&format!("// Iced Builder {}\n", env!("CARGO_PKG_VERSION"))));
+
+        doc.root.name = None;
+        assert!(!generate_code(&doc, &ProjectConfig::default()).contains("/// Generated from"));
+    }
+}
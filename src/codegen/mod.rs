@@ -2,6 +2,15 @@
 //!
 //! Converts the Layout AST to Rust/Iced source code.
 
+pub mod colors;
+pub mod component;
 pub mod generator;
+pub mod snapshot;
+pub mod state_check;
+pub mod strings;
+pub mod stubs;
+pub mod template;
 
-pub use generator::generate_code;
+pub use component::generate_example;
+pub use generator::{generate_code, generate_node_code, stamp, stamp_matches, GENERATED_HEADER};
+pub use stubs::generate_stubs;
@@ -0,0 +1,122 @@
+//! Regression tests emitted alongside generated code.
+//!
+//! With `generate_tests` set, export appends a `#[cfg(test)]` module that
+//! builds the view from a default state and compares the shape of its widget
+//! tree, the number of children at each node, against a snapshot taken from
+//! the layout. A downstream project that regenerates after editing the
+//! layout sees the change as a test failure to review.
+//!
+//! The shape is read through `iced::advanced`, so the downstream crate needs
+//! iced's `advanced` feature.
+
+use crate::model::{layout::WidgetType, LayoutDocument, LayoutNode};
+use std::fmt::Write;
+
+/// Append the test module for a view built by `build`.
+///
+/// `setup` declares `state`, and `build` is an expression producing the
+/// element from it, e.g. `view(&state)`.
+pub(super) fn write_test_module(output: &mut String, layout: &LayoutDocument, setup: &str, build: &str) {
+    writeln!(output).unwrap();
+    writeln!(output, "#[cfg(test)]").unwrap();
+    writeln!(output, "mod tests {{").unwrap();
+    writeln!(output, "    use super::*;").unwrap();
+    writeln!(output, "    use iced::advanced::widget::Tree;").unwrap();
+    writeln!(output).unwrap();
+    writeln!(output, "    /// Child counts of a widget tree, e.g. `2[0, 1[0]]`.").unwrap();
+    writeln!(output, "    fn shape(tree: &Tree) -> String {{").unwrap();
+    writeln!(output, "        if tree.children.is_empty() {{").unwrap();
+    writeln!(output, "            return \"0\".to_string();").unwrap();
+    writeln!(output, "        }}").unwrap();
+    writeln!(output, "        let children: Vec<_> = tree.children.iter().map(shape).collect();").unwrap();
+    writeln!(output, "        format!(\"{{}}[{{}}]\", children.len(), children.join(\", \"))").unwrap();
+    writeln!(output, "    }}").unwrap();
+    writeln!(output).unwrap();
+    writeln!(output, "    #[test]").unwrap();
+    writeln!(output, "    fn view_builds() {{").unwrap();
+    writeln!(output, "        let state = {};", setup).unwrap();
+    writeln!(output, "        let _ = {};", build).unwrap();
+    writeln!(output, "    }}").unwrap();
+    writeln!(output).unwrap();
+    writeln!(output, "    #[test]").unwrap();
+    writeln!(output, "    fn view_matches_layout_shape() {{").unwrap();
+    writeln!(output, "        let state = {};", setup).unwrap();
+    writeln!(output, "        let element = {};", build).unwrap();
+    writeln!(
+        output,
+        "        assert_eq!(shape(&Tree::new(&element)), \"{}\");",
+        layout_shape(&layout.root)
+    )
+    .unwrap();
+    writeln!(output, "    }}").unwrap();
+    writeln!(output, "}}").unwrap();
+}
+
+/// The widget tree shape the generated code for `node` builds.
+///
+/// Mirrors iced's trees: containers are transparent and share their child's
+/// tree, buttons and scrollables hold one child, and empty slots are filled
+/// with `text("")`.
+pub(super) fn layout_shape(node: &LayoutNode) -> String {
+    let exported = |child: &Option<Box<LayoutNode>>| {
+        child
+            .as_deref()
+            .filter(|c| !c.exclude_from_export)
+            .map_or_else(|| "0".to_string(), layout_shape)
+    };
+
+    match &node.widget {
+        WidgetType::Column { children, .. }
+        | WidgetType::Row { children, .. }
+        | WidgetType::Stack { children, .. } => {
+            let shapes: Vec<_> = children
+                .iter()
+                .filter(|c| !c.exclude_from_export)
+                .map(layout_shape)
+                .collect();
+            if shapes.is_empty() {
+                "0".to_string()
+            } else {
+                format!("{}[{}]", shapes.len(), shapes.join(", "))
+            }
+        }
+        WidgetType::Container { child, .. } => exported(child),
+        WidgetType::Scrollable { child, .. } => format!("1[{}]", exported(child)),
+        WidgetType::Button { .. } => "1[0]".to_string(),
+        _ => "0".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::layout::{ButtonAttrs, ContainerAttrs};
+
+    #[test]
+    fn test_layout_shape_follows_iced_trees() {
+        let button = LayoutNode::new(WidgetType::Button {
+            label: "Go".to_string(),
+            message_stub: "Go".to_string(),
+            attrs: ButtonAttrs::default(),
+        });
+        let mut hidden = LayoutNode::new(WidgetType::Text {
+            content: String::new(),
+            attrs: Default::default(),
+        });
+        hidden.exclude_from_export = true;
+        let container = LayoutNode::new(WidgetType::Container {
+            child: Some(Box::new(button)),
+            attrs: ContainerAttrs::default(),
+        });
+        let scrollable = LayoutNode::new(WidgetType::Scrollable {
+            child: None,
+            attrs: ContainerAttrs::default(),
+        });
+        let row = LayoutNode::new(WidgetType::Row {
+            children: vec![container, hidden, scrollable],
+            attrs: ContainerAttrs::default(),
+        });
+
+        assert_eq!(layout_shape(&row), "2[1[0], 1[0]]");
+    }
+}
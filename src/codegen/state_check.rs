@@ -0,0 +1,258 @@
+//! Checking bindings against the target project's state struct.
+//!
+//! The generated view reads `state.<binding>` for every bound widget, so a
+//! binding without a matching field only shows up when the target project
+//! fails to compile. This finds the struct named by `state_type` in the
+//! target's `src/` tree and compares its fields with the layout's bindings.
+
+use crate::model::layout::ValidationError;
+use crate::model::LayoutDocument;
+use std::path::{Path, PathBuf};
+use syn::ext::IdentExt;
+use thiserror::Error;
+
+/// The state struct could not be found.
+#[derive(Debug, Error)]
+pub enum StateCheckError {
+    /// The target project has no `src` directory.
+    #[error("{} has no src directory", .0.display())]
+    NoSource(PathBuf),
+
+    /// No struct with the name and named fields was found.
+    #[error("No struct {name} with named fields in {}", dir.display())]
+    NotFound {
+        /// Name of the struct.
+        name: String,
+        /// The `src` directory searched.
+        dir: PathBuf,
+    },
+}
+
+/// A state struct found in the target project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateStruct {
+    /// Struct name, e.g. "AppState".
+    pub name: String,
+    /// File the struct is declared in.
+    pub file: PathBuf,
+    /// Field names in declaration order.
+    pub fields: Vec<String>,
+}
+
+impl StateStruct {
+    /// Find the struct named by `state_type` under `root/src`.
+    ///
+    /// Structs are matched by name; when several share it, the one whose
+    /// module matches the `crate::` path wins, since re-exports make the
+    /// configured path an unreliable guide on its own. Files that can't be
+    /// read or parsed are skipped.
+    pub fn find(root: &Path, state_type: &str) -> Result<Self, StateCheckError> {
+        let src = root.join("src");
+        if !src.is_dir() {
+            return Err(StateCheckError::NoSource(root.to_path_buf()));
+        }
+        let mut segments: Vec<&str> = state_type.split("::").map(str::trim).collect();
+        let name = segments.pop().unwrap_or_default();
+        if segments.first() == Some(&"crate") {
+            segments.remove(0);
+        }
+
+        let mut files = Vec::new();
+        collect_rust_files(&src, &mut files);
+        let mut found: Option<StateStruct> = None;
+        for file in files {
+            let Ok(source) = std::fs::read_to_string(&file) else {
+                tracing::debug!(target: "iced_builder::codegen", path = %file.display(), "Skipping unreadable file");
+                continue;
+            };
+            let Ok(parsed) = syn::parse_file(&source) else {
+                tracing::debug!(target: "iced_builder::codegen", path = %file.display(), "Skipping unparsable file");
+                continue;
+            };
+            let mut candidates = Vec::new();
+            find_structs(&parsed.items, module_of(&src, &file), name, &mut candidates);
+            for (module, fields) in candidates {
+                let candidate = StateStruct {
+                    name: name.to_string(),
+                    file: file.clone(),
+                    fields,
+                };
+                if module == segments {
+                    return Ok(candidate);
+                }
+                found.get_or_insert(candidate);
+            }
+        }
+        found.ok_or_else(|| StateCheckError::NotFound {
+            name: name.to_string(),
+            dir: src,
+        })
+    }
+
+    /// Compare the layout's bindings with the fields.
+    ///
+    /// Each binding without a field is a warning on its widget, and fields
+    /// no widget binds are listed in one warning on the root.
+    pub fn check(&self, layout: &LayoutDocument) -> Vec<ValidationError> {
+        let mut issues = Vec::new();
+        let mut bound = Vec::new();
+        for (path, node) in layout.exported_paths() {
+            let Some(binding) = node.widget.value_binding() else {
+                continue;
+            };
+            if self.fields.iter().any(|field| field == binding) {
+                bound.push(binding);
+            } else {
+                issues.push(ValidationError::warning(
+                    &path,
+                    format!("binding '{}' is not a field of {}", binding, self.name),
+                    node.id,
+                ));
+            }
+        }
+        let unused: Vec<&str> = self
+            .fields
+            .iter()
+            .map(String::as_str)
+            .filter(|field| !bound.contains(field))
+            .collect();
+        if !unused.is_empty() {
+            issues.push(ValidationError::warning(
+                "root",
+                format!("{} fields not bound in this layout: {}", self.name, unused.join(", ")),
+                layout.root.id,
+            ));
+        }
+        issues
+    }
+}
+
+/// Collect the `.rs` files under `dir`, sorted so results don't depend on
+/// directory order.
+fn collect_rust_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            collect_rust_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+}
+
+/// Module path of a file under `src`, e.g. `["ui", "state"]` for
+/// `src/ui/state.rs` or `src/ui/state/mod.rs`, and nothing for the crate root.
+fn module_of(src: &Path, file: &Path) -> Vec<String> {
+    let relative = file.strip_prefix(src).unwrap_or(file).with_extension("");
+    let mut module: Vec<String> = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    if matches!(module.as_slice(), [only] if only == "lib" || only == "main")
+        || module.last().is_some_and(|last| last == "mod")
+    {
+        module.pop();
+    }
+    module
+}
+
+/// Find structs called `name` with named fields, including those in inline
+/// modules, with the module each is in.
+fn find_structs(items: &[syn::Item], module: Vec<String>, name: &str, found: &mut Vec<(Vec<String>, Vec<String>)>) {
+    for item in items {
+        match item {
+            syn::Item::Struct(item) if item.ident == name => {
+                if let syn::Fields::Named(fields) = &item.fields {
+                    // Compare `r#type` as the binding `type`
+                    let names = fields.named.iter().filter_map(|f| f.ident.as_ref().map(|i| i.unraw().to_string())).collect();
+                    found.push((module.clone(), names));
+                }
+            }
+            syn::Item::Mod(item) => {
+                if let Some((_, items)) = &item.content {
+                    let mut inner = module.clone();
+                    inner.push(item.ident.to_string());
+                    find_structs(items, inner, name, found);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::layout::{CheckboxAttrs, InputAttrs, LayoutNode, ValidationSeverity, WidgetType};
+    use tempfile::tempdir;
+
+    fn write(root: &Path, file: &str, source: &str) {
+        let path = root.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    }
+
+    #[test]
+    fn test_find_prefers_matching_module() {
+        let temp = tempdir().unwrap();
+        write(temp.path(), "src/main.rs", "mod state;\nstruct AppState { old: u32 }\n");
+        write(temp.path(), "src/state/mod.rs", "pub struct AppState { pub name: String, pub volume: f32 }\n");
+        write(temp.path(), "src/broken.rs", "fn {");
+
+        let state = StateStruct::find(temp.path(), "crate::state::AppState").unwrap();
+        assert_eq!(state.fields, ["name", "volume"]);
+        assert!(state.file.ends_with("mod.rs"));
+
+        // A re-exported path falls back to the first struct with the name
+        let state = StateStruct::find(temp.path(), "crate::AppState").unwrap();
+        assert_eq!(state.fields, ["old"]);
+
+        write(temp.path(), "src/lib.rs", "pub mod inner { pub struct Settings { pub dark: bool } }\n");
+        let state = StateStruct::find(temp.path(), "crate::inner::Settings").unwrap();
+        assert_eq!(state.fields, ["dark"]);
+
+        assert!(matches!(
+            StateStruct::find(temp.path(), "crate::Missing"),
+            Err(StateCheckError::NotFound { .. })
+        ));
+        assert!(matches!(
+            StateStruct::find(&temp.path().join("src"), "crate::AppState"),
+            Err(StateCheckError::NoSource(_))
+        ));
+    }
+
+    #[test]
+    fn test_check_reports_missing_and_unused_fields() {
+        let state = StateStruct {
+            name: "AppState".to_string(),
+            file: PathBuf::from("src/state.rs"),
+            fields: vec!["name".to_string(), "count".to_string(), "agreed".to_string()],
+        };
+        let mut layout = LayoutDocument::default();
+        let input = LayoutNode::new(WidgetType::TextInput {
+            placeholder: String::new(),
+            value_binding: "name".to_string(),
+            message_stub: "NameChanged".to_string(),
+            attrs: InputAttrs::default(),
+        });
+        let checkbox = LayoutNode::new(WidgetType::Checkbox {
+            label: "Agree".to_string(),
+            checked_binding: "accepted".to_string(),
+            message_stub: "Toggled".to_string(),
+            attrs: CheckboxAttrs::default(),
+        });
+        let checkbox_id = checkbox.id;
+        layout.root.children_mut().unwrap().extend([input, checkbox]);
+
+        let issues = state.check(&layout);
+        assert_eq!(issues.len(), 2);
+        assert!(issues.iter().all(|issue| issue.severity == ValidationSeverity::Warning));
+        assert_eq!(issues[0].node_id, checkbox_id);
+        assert_eq!(issues[0].message, "binding 'accepted' is not a field of AppState");
+        assert_eq!(issues[1].message, "AppState fields not bound in this layout: count, agreed");
+    }
+}
@@ -0,0 +1,123 @@
+//! Translation keys for user-visible text.
+//!
+//! With `i18n_mode = "keys"`, text content and button labels are generated as
+//! calls like `t("form.title")` instead of literals, and export writes a TOML
+//! file mapping each key to the text currently in the layout.
+//!
+//! Keys are `<layout>.<friendly name>` for named nodes and
+//! `<layout>.<widget>_<id prefix>` otherwise. Both come from data saved with
+//! the layout, so re-exporting keeps the keys of unchanged nodes.
+
+use crate::model::{layout::WidgetType, ComponentId, LayoutDocument};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+/// First line of the generated strings file.
+pub const STRINGS_HEADER: &str = "# Auto-generated by Iced Builder – do not edit manually.";
+
+/// Translation keys of a layout's text and the text they stand for.
+#[derive(Debug, Default)]
+pub struct StringTable {
+    keys: HashMap<ComponentId, String>,
+    entries: BTreeMap<String, String>,
+}
+
+impl StringTable {
+    /// Assign keys to the non-empty text and button labels of exported nodes.
+    pub fn collect(layout: &LayoutDocument) -> Self {
+        let prefix = crate::util::to_snake_case(&layout.name, "layout");
+        let mut table = Self::default();
+        for node in layout.exported_nodes() {
+            let (kind, content) = match &node.widget {
+                WidgetType::Text { content, .. } => ("text", content),
+                WidgetType::Button { label, .. } => ("button", label),
+                _ => continue,
+            };
+            if content.is_empty() {
+                continue;
+            }
+
+            let fallback = || {
+                let id = node.id.to_string();
+                format!("{}.{}_{}", prefix, kind, &id[..8])
+            };
+            // A friendly name shared by nodes with different text can't be one key
+            let key = match &node.name {
+                Some(name) => {
+                    let key = format!("{}.{}", prefix, crate::util::to_snake_case(name, kind));
+                    match table.entries.get(&key) {
+                        Some(existing) if existing != content => fallback(),
+                        _ => key,
+                    }
+                }
+                None => fallback(),
+            };
+            table.entries.insert(key.clone(), content.clone());
+            table.keys.insert(node.id, key);
+        }
+        table
+    }
+
+    /// The key for a node's text, if it has one.
+    pub fn key_of(&self, id: ComponentId) -> Option<&str> {
+        self.keys.get(&id).map(String::as_str)
+    }
+
+    /// Render the table as a TOML file, sorted by key.
+    pub fn to_toml(&self, layout: &LayoutDocument) -> String {
+        let mut output = String::new();
+        writeln!(output, "{}", STRINGS_HEADER).unwrap();
+        writeln!(output, "# Strings of the {} layout, keyed as in the generated code.", layout.name).unwrap();
+        writeln!(output).unwrap();
+        output.push_str(&toml::to_string(&self.entries).expect("string map serializes"));
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::layout::{ButtonAttrs, TextAttrs};
+    use crate::model::LayoutNode;
+
+    fn text(content: &str, name: Option<&str>) -> LayoutNode {
+        let mut node = LayoutNode::new(WidgetType::Text {
+            content: content.to_string(),
+            attrs: TextAttrs::default(),
+        });
+        node.name = name.map(str::to_string);
+        node
+    }
+
+    #[test]
+    fn test_keys_use_friendly_names_or_ids() {
+        let mut doc = LayoutDocument {
+            name: "Login Form".to_string(),
+            ..Default::default()
+        };
+        let title = text("Welcome", Some("Title"));
+        let plain = text("Sign in below", None);
+        let clash = text("Something else", Some("Title"));
+        let empty = text("", None);
+        let button = LayoutNode::new(WidgetType::Button {
+            label: "Go".to_string(),
+            message_stub: "Go".to_string(),
+            attrs: ButtonAttrs::default(),
+        });
+        let ids = [title.id, plain.id, clash.id, empty.id, button.id];
+        *doc.root.children_mut().unwrap() = vec![title, plain, clash, empty, button];
+
+        let table = StringTable::collect(&doc);
+        assert_eq!(table.key_of(ids[0]), Some("login_form.title"));
+        let plain_key = format!("login_form.text_{}", &ids[1].to_string()[..8]);
+        assert_eq!(table.key_of(ids[1]), Some(plain_key.as_str()));
+        let clash_key = format!("login_form.text_{}", &ids[2].to_string()[..8]);
+        assert_eq!(table.key_of(ids[2]), Some(clash_key.as_str()));
+        assert_eq!(table.key_of(ids[3]), None);
+        assert!(table.key_of(ids[4]).unwrap().starts_with("login_form.button_"));
+
+        let toml = table.to_toml(&doc);
+        assert!(toml.starts_with(STRINGS_HEADER));
+        assert!(toml.contains("\"login_form.title\" = \"Welcome\"\n"));
+    }
+}
@@ -0,0 +1,291 @@
+//! Stub generation for the code the view depends on.
+//!
+//! Produces a `Message` enum with one variant per distinct message stub in
+//! the layout, carrying the payload its widgets send, and optionally an
+//! `update` skeleton that stores each payload in its bound state field.
+
+use crate::model::{
+    layout::{MessagePayload, ValidationError, WidgetType},
+    LayoutDocument, ProjectConfig,
+};
+use crate::util::to_ident;
+use std::fmt::Write;
+
+use super::generator::with_header;
+
+/// A message variant and the state fields its widgets are bound to.
+pub(super) struct Variant<'a> {
+    pub stub: &'a str,
+    pub payload: MessagePayload,
+    /// Bound field names, and whether the field holds an `Option`.
+    pub bindings: Vec<(&'a str, bool)>,
+}
+
+/// Collect message variants sorted by stub, so moving widgets around doesn't
+/// reorder the generated enum.
+///
+/// A stub used with several payloads keeps the first one; callers check
+/// `message_conflicts` first.
+pub(super) fn collect_variants(layout: &LayoutDocument) -> Vec<Variant<'_>> {
+    let mut variants: Vec<Variant<'_>> = Vec::new();
+    for message in layout.message_uses() {
+        let index = match variants.iter().position(|v| v.stub == message.stub) {
+            Some(index) => index,
+            None => {
+                variants.push(Variant {
+                    stub: message.stub,
+                    payload: message.payload,
+                    bindings: Vec::new(),
+                });
+                variants.len() - 1
+            }
+        };
+        if let Some(field) = message.widget.value_binding() {
+            let optional = matches!(message.widget, WidgetType::PickList { .. });
+            if !variants[index].bindings.iter().any(|(f, _)| *f == field) {
+                variants[index].bindings.push((field, optional));
+            }
+        }
+    }
+    variants.sort_by_key(|v| v.stub);
+    for variant in &mut variants {
+        variant.bindings.sort_by_key(|(field, _)| *field);
+    }
+    variants
+}
+
+/// Generate the stub file for a layout.
+///
+/// Contains the message enum when `generate_message_stub` is set and the
+/// `update` skeleton when `generate_update_skeleton` is set. Fails with the
+/// conflicts if a stub is used with different payload types.
+pub fn generate_stubs(layout: &LayoutDocument, config: &ProjectConfig) -> Result<String, Vec<ValidationError>> {
+    let conflicts = layout.message_conflicts();
+    if !conflicts.is_empty() {
+        return Err(conflicts);
+    }
+    let variants = collect_variants(layout);
+    tracing::debug!(target: "iced_builder::codegen", variants = variants.len(), "Generating stubs");
+
+    let enum_name = config.message_type.split("::").last().unwrap_or("Message");
+
+    let mut output = String::new();
+    if config.generate_message_stub {
+        write_message_enum(&mut output, enum_name, &variants);
+    }
+
+    if config.generate_update_skeleton {
+        // Use the enum from this file when we generate it, else the configured path
+        let message_type = if config.generate_message_stub {
+            enum_name
+        } else {
+            config.message_type.as_str()
+        };
+        if !output.is_empty() {
+            writeln!(output).unwrap();
+        }
+        write_update_fn(&mut output, message_type, &config.state_type, &variants);
+    }
+
+    Ok(with_header(layout, config, "Regenerate by exporting this project from Iced Builder.", &output))
+}
+
+pub(super) fn write_message_enum(output: &mut String, enum_name: &str, variants: &[Variant<'_>]) {
+    writeln!(output, "/// Messages produced by the generated view.").unwrap();
+    writeln!(output, "#[derive(Debug, Clone)]").unwrap();
+    writeln!(output, "pub enum {} {{", enum_name).unwrap();
+    for variant in variants {
+        match variant.payload.rust_type() {
+            Some(ty) => writeln!(output, "    {}({}),", variant.stub, ty).unwrap(),
+            None => writeln!(output, "    {},", variant.stub).unwrap(),
+        }
+    }
+    writeln!(output, "}}").unwrap();
+}
+
+fn write_update_fn(output: &mut String, message_type: &str, state_type: &str, variants: &[Variant<'_>]) {
+    writeln!(output, "/// Apply a message from the generated view to the state.").unwrap();
+    writeln!(output, "pub fn update(state: &mut {}, message: {}) {{", state_type, message_type).unwrap();
+    write_update_match(output, message_type, "state", 1, variants);
+    writeln!(output, "}}").unwrap();
+}
+
+/// Write a `match message` that stores payloads in `receiver`'s bound
+/// fields and leaves a todo comment for the rest.
+pub(super) fn write_update_match(
+    output: &mut String,
+    message_type: &str,
+    receiver: &str,
+    indent: usize,
+    variants: &[Variant<'_>],
+) {
+    let pad = "    ".repeat(indent);
+    writeln!(output, "{}match message {{", pad).unwrap();
+    for variant in variants {
+        let has_payload = variant.payload.rust_type().is_some();
+        if has_payload && !variant.bindings.is_empty() {
+            writeln!(output, "{}    {}::{}(value) => {{", pad, message_type, variant.stub).unwrap();
+            let last = variant.bindings.len() - 1;
+            for (i, (field, optional)) in variant.bindings.iter().enumerate() {
+                let value = if i == last { "value" } else { "value.clone()" };
+                let field = to_ident(field);
+                if *optional {
+                    writeln!(output, "{}        {}.{} = Some({});", pad, receiver, field, value).unwrap();
+                } else {
+                    writeln!(output, "{}        {}.{} = {};", pad, receiver, field, value).unwrap();
+                }
+            }
+        } else {
+            let pattern = if has_payload { "(_)" } else { "" };
+            writeln!(output, "{}    {}::{}{} => {{", pad, message_type, variant.stub, pattern).unwrap();
+            writeln!(output, "{}        // todo!(\"handle {}\")", pad, variant.stub).unwrap();
+        }
+        writeln!(output, "{}    }}", pad).unwrap();
+    }
+    writeln!(output, "{}}}", pad).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::layout::{ButtonAttrs, CheckboxAttrs, InputAttrs, PickListAttrs, SliderAttrs};
+    use crate::codegen::GENERATED_HEADER;
+    use crate::model::LayoutNode;
+    use crate::test_support::doc_with;
+
+    fn button(stub: &str) -> LayoutNode {
+        LayoutNode::new(WidgetType::Button {
+            label: "Go".to_string(),
+            message_stub: stub.to_string(),
+            attrs: ButtonAttrs::default(),
+        })
+    }
+
+    #[test]
+    fn test_message_enum_has_one_variant_per_stub() {
+        let doc = doc_with(vec![
+            LayoutNode::new(WidgetType::TextInput {
+                placeholder: String::new(),
+                value_binding: "name".to_string(),
+                message_stub: "NameChanged".to_string(),
+                attrs: InputAttrs::default(),
+            }),
+            button("Submit"),
+            LayoutNode::new(WidgetType::Slider {
+                min: 0.0,
+                max: 1.0,
+                value_binding: "volume".to_string(),
+                message_stub: "VolumeChanged".to_string(),
+                attrs: SliderAttrs::default(),
+            }),
+            LayoutNode::new(WidgetType::Checkbox {
+                label: String::new(),
+                checked_binding: "agreed".to_string(),
+                message_stub: "AgreedToggled".to_string(),
+                attrs: CheckboxAttrs::default(),
+            }),
+            button("Submit"),
+            button(""),
+        ]);
+
+        let code = generate_stubs(&doc, &ProjectConfig::default()).unwrap();
+        assert!(code.starts_with(GENERATED_HEADER));
+        assert!(code.contains(
+            "pub enum Message {\n    AgreedToggled(bool),\n    NameChanged(String),\n    Submit,\n    VolumeChanged(f32),\n}"
+        ));
+    }
+
+    #[test]
+    fn test_message_enum_uses_configured_name() {
+        let doc = doc_with(vec![button("Submit")]);
+        let config = ProjectConfig {
+            message_type: "crate::app::AppMessage".to_string(),
+            ..ProjectConfig::default()
+        };
+        let code = generate_stubs(&doc, &config).unwrap();
+        assert!(code.contains("pub enum AppMessage {"));
+    }
+
+    #[test]
+    fn test_update_skeleton_assigns_bound_fields() {
+        let doc = doc_with(vec![
+            LayoutNode::new(WidgetType::TextInput {
+                placeholder: String::new(),
+                value_binding: "name".to_string(),
+                message_stub: "NameChanged".to_string(),
+                attrs: InputAttrs::default(),
+            }),
+            LayoutNode::new(WidgetType::PickList {
+                options: vec!["A".to_string()],
+                selected_binding: "choice".to_string(),
+                message_stub: "ChoicePicked".to_string(),
+                attrs: PickListAttrs::default(),
+            }),
+            button("Submit"),
+        ]);
+        let config = ProjectConfig {
+            generate_update_skeleton: true,
+            ..ProjectConfig::default()
+        };
+
+        let code = generate_stubs(&doc, &config).unwrap();
+        assert!(code.contains("pub fn update(state: &mut crate::AppState, message: Message) {"));
+        assert!(code.contains("Message::NameChanged(value) => {\n            state.name = value;"));
+        assert!(code.contains("Message::ChoicePicked(value) => {\n            state.choice = Some(value);"));
+        assert!(code.contains("Message::Submit => {\n            // todo!(\"handle Submit\")"));
+
+        // Off by default
+        let code = generate_stubs(&doc, &ProjectConfig::default()).unwrap();
+        assert!(!code.contains("pub fn update"));
+    }
+
+    #[test]
+    fn test_update_skeleton_uses_raw_identifiers() {
+        let doc = doc_with(vec![LayoutNode::new(WidgetType::TextInput {
+            placeholder: String::new(),
+            value_binding: "type".to_string(),
+            message_stub: "TypeChanged".to_string(),
+            attrs: InputAttrs::default(),
+        })]);
+        let config = ProjectConfig {
+            generate_update_skeleton: true,
+            ..ProjectConfig::default()
+        };
+
+        let code = generate_stubs(&doc, &config).unwrap();
+        assert!(code.contains("state.r#type = value;"));
+    }
+
+    #[test]
+    fn test_update_skeleton_without_enum_uses_configured_type() {
+        let doc = doc_with(vec![button("Submit")]);
+        let config = ProjectConfig {
+            generate_message_stub: false,
+            generate_update_skeleton: true,
+            ..ProjectConfig::default()
+        };
+
+        let code = generate_stubs(&doc, &config).unwrap();
+        assert!(!code.contains("pub enum"));
+        assert!(code.contains("message: crate::Message) {"));
+        assert!(code.contains("crate::Message::Submit => {"));
+    }
+
+    #[test]
+    fn test_message_enum_rejects_conflicting_payloads() {
+        let mut input = LayoutNode::new(WidgetType::TextInput {
+            placeholder: String::new(),
+            value_binding: "name".to_string(),
+            message_stub: "Submit".to_string(),
+            attrs: InputAttrs::default(),
+        });
+        let doc = doc_with(vec![button("Submit"), input.clone()]);
+        let conflicts = generate_stubs(&doc, &ProjectConfig::default()).unwrap_err();
+        assert_eq!(conflicts.len(), 1);
+
+        // Excluded widgets are not generated, so they cannot conflict
+        input.exclude_from_export = true;
+        let doc = doc_with(vec![button("Submit"), input]);
+        assert!(generate_stubs(&doc, &ProjectConfig::default()).is_ok());
+    }
+}
@@ -0,0 +1,220 @@
+//! User-provided codegen templates.
+//!
+//! When `codegen_template` names a minijinja template in the project, export
+//! renders it instead of running the built-in generator. The template sees:
+//!
+//! - `layout`: the layout document as saved, with its node tree under `root`
+//! - `messages`: one entry per message variant, with `name`, `payload` (the
+//!   Rust type or none) and `bindings` (state fields it updates)
+//! - `bindings`: bound state fields, with `name` and `type`
+//!
+//! Field names are ready to paste into Rust, so keywords come as raw
+//! identifiers like `r#type`.
+//! - `config`: the project configuration
+//! - `message_name` and `state_name`: the last segments of the configured types
+//! - `view`: the built-in widget expression for the root, for templates that
+//!   only change what surrounds it, and `view_doc`, its doc comment
+//! - `palette`: the color constants module, empty unless `color_constants` is set
+//!
+//! Rendered output gets the usual generated-file header, so export keeps
+//! recognising the file as its own. `examples/templates/view.rs.jinja`
+//! reproduces the built-in output.
+
+use crate::model::{LayoutDocument, ProjectConfig};
+use crate::util::to_ident;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use super::generator::{generate_node, with_header, write_node_doc, Context};
+use super::stubs::collect_variants;
+
+/// A template that could not be read or rendered.
+#[derive(Debug, Error)]
+#[error("{}{}: {message}", path.display(), line.map(|l| format!(":{}", l)).unwrap_or_default())]
+pub struct TemplateError {
+    /// The template file.
+    pub path: PathBuf,
+    /// Line of the template the error points at, when known.
+    pub line: Option<usize>,
+    /// What went wrong.
+    pub message: String,
+}
+
+/// A message variant as seen by templates.
+#[derive(Serialize)]
+struct MessageInfo<'a> {
+    name: &'a str,
+    payload: Option<&'static str>,
+    bindings: Vec<Cow<'a, str>>,
+}
+
+/// A bound state field as seen by templates.
+#[derive(Serialize)]
+struct BindingInfo<'a> {
+    name: Cow<'a, str>,
+    #[serde(rename = "type")]
+    ty: &'static str,
+}
+
+/// Generate code for a layout, rendering `template` when it exists.
+///
+/// A template that doesn't exist falls back to the built-in generator, so a
+/// project checked out without its template still exports.
+pub fn generate_code_with_template(
+    layout: &LayoutDocument,
+    config: &ProjectConfig,
+    template: Option<&Path>,
+) -> Result<String, TemplateError> {
+    let Some(path) = template else {
+        return Ok(super::generate_code(layout, config));
+    };
+    let source = match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            tracing::warn!(target: "iced_builder::codegen", path = %path.display(), "Template not found, using the built-in generator");
+            return Ok(super::generate_code(layout, config));
+        }
+        Err(e) => {
+            return Err(TemplateError {
+                path: path.to_path_buf(),
+                line: None,
+                message: e.to_string(),
+            })
+        }
+    };
+    render_template(&source, path, layout, config)
+}
+
+/// Render template `source`, read from `path`, for a layout.
+pub fn render_template(
+    source: &str,
+    path: &Path,
+    layout: &LayoutDocument,
+    config: &ProjectConfig,
+) -> Result<String, TemplateError> {
+    tracing::info!(target: "iced_builder::codegen", template = %path.display(), "Rendering codegen template");
+    let message_name = config.message_type.split("::").last().unwrap_or("Message");
+    let state_name = config.state_type.split("::").last().unwrap_or("AppState");
+
+    let ctx = Context {
+        colors: Context::palette(layout, config),
+        message: message_name,
+        style: config.codegen_style,
+        strings: Context::strings(layout, config),
+        translate: &config.i18n_function,
+        ..Context::default()
+    };
+    let mut view_doc = String::new();
+    write_node_doc(&mut view_doc, &layout.root, 0);
+    let mut palette = String::new();
+    if let Some(colors) = &ctx.colors {
+        colors.write_module(&mut palette);
+    }
+
+    let variants = collect_variants(layout);
+    let messages: Vec<_> = variants
+        .iter()
+        .map(|v| MessageInfo {
+            name: v.stub,
+            payload: v.payload.rust_type(),
+            bindings: v.bindings.iter().map(|(field, _)| to_ident(field)).collect(),
+        })
+        .collect();
+    let bindings: Vec<_> = super::component::state_fields(layout)
+        .into_iter()
+        .map(|(name, ty)| BindingInfo { name: to_ident(name), ty })
+        .collect();
+
+    let mut env = minijinja::Environment::new();
+    env.set_keep_trailing_newline(true);
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+    let name = path.display().to_string();
+    let error = |e: minijinja::Error| TemplateError {
+        path: path.to_path_buf(),
+        line: e.line(),
+        message: e.detail().map_or_else(|| e.kind().to_string(), str::to_string),
+    };
+    env.add_template(&name, source).map_err(error)?;
+    let body = env
+        .get_template(&name)
+        .and_then(|template| {
+            template.render(minijinja::context! {
+                layout => layout,
+                messages => messages,
+                bindings => bindings,
+                config => config,
+                message_name => message_name,
+                state_name => state_name,
+                view => generate_node(&layout.root, 1, &ctx),
+                view_doc => view_doc,
+                palette => palette,
+            })
+        })
+        .map_err(error)?;
+
+    Ok(with_header(layout, config, "Regenerate by opening this project in Iced Builder.", &body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::layout::{InputAttrs, WidgetType};
+    use crate::model::{CodegenStyle, LayoutNode};
+
+    const EXAMPLE: &str = include_str!("../../examples/templates/view.rs.jinja");
+
+    fn form() -> LayoutDocument {
+        let mut doc = LayoutDocument {
+            name: "Form".to_string(),
+            ..Default::default()
+        };
+        *doc.root.children_mut().unwrap() = vec![LayoutNode::new(WidgetType::TextInput {
+            placeholder: "Name".to_string(),
+            value_binding: "name".to_string(),
+            message_stub: "NameChanged".to_string(),
+            attrs: InputAttrs::default(),
+        })];
+        doc
+    }
+
+    #[test]
+    fn test_example_template_matches_built_in_output() {
+        let doc = form();
+        let path = Path::new("view.rs.jinja");
+        for style in [CodegenStyle::Macros, CodegenStyle::Builders] {
+            let config = ProjectConfig {
+                codegen_style: style,
+                ..ProjectConfig::default()
+            };
+            let rendered = render_template(EXAMPLE, path, &doc, &config).unwrap();
+            assert_eq!(rendered, super::super::generate_code(&doc, &config));
+        }
+    }
+
+    #[test]
+    fn test_template_sees_messages_and_bindings() {
+        let source = "{% for m in messages %}{{ m.name }}({{ m.payload }}) -> {{ m.bindings | join(\",\") }}\n{% endfor %}\
+                      {% for b in bindings %}{{ b.name }}: {{ b.type }}\n{% endfor %}{{ layout.name }}";
+        let rendered = render_template(source, Path::new("t.jinja"), &form(), &ProjectConfig::default()).unwrap();
+        assert!(rendered.ends_with("\nNameChanged(String) -> name\nname: String\nForm"));
+    }
+
+    #[test]
+    fn test_template_errors_carry_line_numbers() {
+        let source = "line one\n{{ view }\n";
+        let error = render_template(source, Path::new("bad.jinja"), &form(), &ProjectConfig::default()).unwrap_err();
+        assert_eq!(error.line, Some(2));
+        assert!(error.to_string().starts_with("bad.jinja:2: "));
+    }
+
+    #[test]
+    fn test_missing_template_falls_back() {
+        let doc = form();
+        let config = ProjectConfig::default();
+        let code = generate_code_with_template(&doc, &config, Some(Path::new("/nonexistent/view.jinja"))).unwrap();
+        assert_eq!(code, super::super::generate_code(&doc, &config));
+    }
+}
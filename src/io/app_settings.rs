@@ -0,0 +1,366 @@
+//! Editor-wide settings.
+//!
+//! Handles loading and saving `settings.toml` in the platform config
+//! directory, or in `ICED_BUILDER_CONFIG_DIR` when set. These settings
+//! belong to the editor, not to any project.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use super::autosave::DEFAULT_AUTOSAVE_INTERVAL;
+use crate::model::history::DEFAULT_HISTORY_LIMIT;
+use crate::model::WidgetKind;
+
+/// The settings file name inside the config directory.
+pub const SETTINGS_FILENAME: &str = "settings.toml";
+
+/// Environment variable that overrides the config directory.
+pub const CONFIG_DIR_ENV: &str = "ICED_BUILDER_CONFIG_DIR";
+
+/// Most recently used widget kinds kept for the palette's Recent section.
+pub const MAX_RECENT_WIDGETS: usize = 5;
+
+/// Errors that can occur when loading or saving settings.
+#[derive(Debug, Error)]
+pub enum SettingsError {
+    /// Reading or writing the file failed.
+    #[error("Failed to access settings file: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The file is not valid settings TOML.
+    #[error("Failed to parse settings: {0}")]
+    Parse(#[from] toml::de::Error),
+
+    /// The settings could not be written as TOML.
+    #[error("Failed to serialize settings: {0}")]
+    Serialize(#[from] toml::ser::Error),
+
+    /// The platform has no config directory and none was set.
+    #[error("No config directory available on this platform")]
+    NoConfigDir,
+}
+
+/// Default height of the tree view panel.
+pub const DEFAULT_TREE_HEIGHT: f32 = 200.0;
+
+/// A fixed canvas size in logical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CanvasSize {
+    /// Width in logical pixels.
+    pub width: f32,
+    /// Height in logical pixels.
+    pub height: f32,
+}
+
+impl CanvasSize {
+    /// Create a new canvas size.
+    pub const fn new(width: f32, height: f32) -> Self {
+        Self { width, height }
+    }
+}
+
+/// Size of the editor window in logical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowSize {
+    /// Width in logical pixels.
+    pub width: f32,
+    /// Height in logical pixels.
+    pub height: f32,
+}
+
+impl WindowSize {
+    /// Create a new window size.
+    pub const fn new(width: f32, height: f32) -> Self {
+        Self { width, height }
+    }
+}
+
+/// Size of the editor window on first launch.
+pub const DEFAULT_WINDOW_SIZE: WindowSize = WindowSize::new(1280.0, 800.0);
+
+/// The editor's color theme.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeChoice {
+    /// Light theme.
+    #[default]
+    Light,
+    /// Dark theme.
+    Dark,
+}
+
+/// Where the tree view panel is placed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TreeDock {
+    /// Below the canvas.
+    #[default]
+    Bottom,
+    /// In the left sidebar, under the palette.
+    Left,
+}
+
+/// Editor settings persisted between sessions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppSettings {
+    /// Size of the editor window when it was last closed.
+    #[serde(default = "default_window_size")]
+    pub window_size: WindowSize,
+
+    /// Color theme of the editor.
+    #[serde(default)]
+    pub theme: ThemeChoice,
+
+    /// Fixed canvas size, or `None` to fill the canvas panel.
+    #[serde(default)]
+    pub canvas_size: Option<CanvasSize>,
+
+    /// Whether selecting a node scrolls the canvas to reveal it.
+    #[serde(default = "default_true")]
+    pub auto_scroll_canvas: bool,
+
+    /// Height of the tree view panel in logical pixels.
+    #[serde(default = "default_tree_height")]
+    pub tree_height: f32,
+
+    /// Placement of the tree view panel.
+    #[serde(default)]
+    pub tree_dock: TreeDock,
+
+    /// Widget kinds inserted most recently, newest first.
+    #[serde(default)]
+    pub recent_widgets: Vec<WidgetKind>,
+
+    /// Titles of collapsed palette sections.
+    #[serde(default)]
+    pub collapsed_palette_sections: BTreeSet<String>,
+
+    /// Whether palette items that cannot go into the selection are disabled
+    /// rather than dimmed.
+    #[serde(default)]
+    pub disable_ineligible_palette_items: bool,
+
+    /// Number of undo and redo steps kept per project; at least one.
+    #[serde(default = "default_history_limit")]
+    pub history_limit: usize,
+
+    /// Seconds between autosaves of unsaved changes; zero disables autosave.
+    #[serde(default = "default_autosave_interval")]
+    pub autosave_interval_secs: u64,
+
+    /// Factor applied to text sizes and paddings across the editor, from
+    /// 0.75 to 2.0.
+    #[serde(default = "default_ui_scale")]
+    pub ui_scale: f32,
+
+    /// Whether deleting a node with children asks for confirmation first.
+    #[serde(default = "default_true")]
+    pub confirm_subtree_delete: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_window_size() -> WindowSize {
+    DEFAULT_WINDOW_SIZE
+}
+
+fn default_tree_height() -> f32 {
+    DEFAULT_TREE_HEIGHT
+}
+
+fn default_history_limit() -> usize {
+    DEFAULT_HISTORY_LIMIT
+}
+
+fn default_autosave_interval() -> u64 {
+    DEFAULT_AUTOSAVE_INTERVAL
+}
+
+fn default_ui_scale() -> f32 {
+    1.0
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            window_size: DEFAULT_WINDOW_SIZE,
+            theme: ThemeChoice::Light,
+            canvas_size: None,
+            auto_scroll_canvas: true,
+            tree_height: DEFAULT_TREE_HEIGHT,
+            tree_dock: TreeDock::Bottom,
+            recent_widgets: Vec::new(),
+            collapsed_palette_sections: BTreeSet::new(),
+            disable_ineligible_palette_items: false,
+            history_limit: DEFAULT_HISTORY_LIMIT,
+            autosave_interval_secs: DEFAULT_AUTOSAVE_INTERVAL,
+            ui_scale: 1.0,
+            confirm_subtree_delete: true,
+        }
+    }
+}
+
+/// Get the editor's config directory: `ICED_BUILDER_CONFIG_DIR` if set,
+/// otherwise the platform config directory.
+pub fn config_dir() -> Option<PathBuf> {
+    config_dir_from(std::env::var_os(CONFIG_DIR_ENV))
+}
+
+fn config_dir_from(overridden: Option<OsString>) -> Option<PathBuf> {
+    match overridden {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => directories::ProjectDirs::from("", "", "iced-builder").map(|dirs| dirs.config_dir().to_path_buf()),
+    }
+}
+
+/// Get the settings file path in the config directory.
+pub fn settings_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(SETTINGS_FILENAME))
+}
+
+/// Load settings from a file, falling back to defaults if it does not exist.
+pub fn load_settings(path: &Path) -> Result<AppSettings, SettingsError> {
+    if !path.exists() {
+        tracing::debug!(target: "iced_builder::io", path = %path.display(), "No settings file, using defaults");
+        return Ok(AppSettings::default());
+    }
+
+    let content = std::fs::read_to_string(path)?;
+    let settings = toml::from_str(&content)?;
+    tracing::debug!(target: "iced_builder::io", path = %path.display(), "Settings loaded");
+    Ok(settings)
+}
+
+/// Save settings to a file, creating parent directories as needed.
+pub fn save_settings(path: &Path, settings: &AppSettings) -> Result<(), SettingsError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let content = toml::to_string_pretty(settings)?;
+    std::fs::write(path, content)?;
+    tracing::debug!(target: "iced_builder::io", path = %path.display(), "Settings saved");
+    Ok(())
+}
+
+impl AppSettings {
+    /// Load settings from the config directory, or defaults on any error.
+    pub fn load_or_default() -> Self {
+        let Some(path) = settings_path() else {
+            return Self::default();
+        };
+        load_settings(&path).unwrap_or_else(|e| {
+            tracing::warn!(target: "iced_builder::io", error = %e, "Failed to load settings, using defaults");
+            Self::default()
+        })
+    }
+
+    /// Move `kind` to the front of the recently used widgets.
+    pub fn remember_widget(&mut self, kind: WidgetKind) {
+        self.recent_widgets.retain(|k| *k != kind);
+        self.recent_widgets.insert(0, kind);
+        self.recent_widgets.truncate(MAX_RECENT_WIDGETS);
+    }
+
+    /// Save settings to the config directory.
+    pub fn save(&self) -> Result<(), SettingsError> {
+        let path = settings_path().ok_or(SettingsError::NoConfigDir)?;
+        save_settings(&path, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_missing_settings_use_defaults() {
+        let temp = tempdir().unwrap();
+        let settings = load_settings(&temp.path().join(SETTINGS_FILENAME)).unwrap();
+        assert_eq!(settings, AppSettings::default());
+    }
+
+    #[test]
+    fn test_partial_settings_fill_defaults() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join(SETTINGS_FILENAME);
+        std::fs::write(&path, "auto_scroll_canvas = false\n").unwrap();
+
+        let settings = load_settings(&path).unwrap();
+        assert!(!settings.auto_scroll_canvas);
+        assert_eq!(settings.tree_height, DEFAULT_TREE_HEIGHT);
+        assert_eq!(settings.history_limit, DEFAULT_HISTORY_LIMIT);
+        assert_eq!(settings.autosave_interval_secs, DEFAULT_AUTOSAVE_INTERVAL);
+        assert_eq!(settings.ui_scale, 1.0);
+        assert!(settings.confirm_subtree_delete);
+        assert_eq!(settings.window_size, DEFAULT_WINDOW_SIZE);
+        assert_eq!(settings.theme, ThemeChoice::Light);
+    }
+
+    #[test]
+    fn test_config_dir_override() {
+        let temp = tempdir().unwrap();
+        let dir = config_dir_from(Some(temp.path().as_os_str().to_owned())).unwrap();
+        assert_eq!(dir, temp.path());
+        // An empty override falls back to the platform directory
+        assert_eq!(config_dir_from(Some(OsString::new())), config_dir_from(None));
+    }
+
+    #[test]
+    fn test_remember_widget_keeps_distinct_recent_kinds() {
+        let mut settings = AppSettings::default();
+        for kind in [
+            WidgetKind::Text,
+            WidgetKind::Button,
+            WidgetKind::Slider,
+            WidgetKind::Checkbox,
+            WidgetKind::Space,
+            WidgetKind::PickList,
+            WidgetKind::Button,
+        ] {
+            settings.remember_widget(kind);
+        }
+
+        assert_eq!(
+            settings.recent_widgets,
+            vec![
+                WidgetKind::Button,
+                WidgetKind::PickList,
+                WidgetKind::Space,
+                WidgetKind::Checkbox,
+                WidgetKind::Slider,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_settings_round_trip() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join("nested").join(SETTINGS_FILENAME);
+
+        let settings = AppSettings {
+            window_size: WindowSize::new(1600.0, 1000.0),
+            theme: ThemeChoice::Dark,
+            canvas_size: Some(CanvasSize::new(800.0, 600.0)),
+            auto_scroll_canvas: false,
+            tree_height: 320.0,
+            tree_dock: TreeDock::Left,
+            recent_widgets: vec![WidgetKind::Button, WidgetKind::RowContainer],
+            collapsed_palette_sections: BTreeSet::from(["Widgets".to_string()]),
+            disable_ineligible_palette_items: true,
+            history_limit: 200,
+            autosave_interval_secs: 0,
+            ui_scale: 1.5,
+            confirm_subtree_delete: false,
+        };
+        save_settings(&path, &settings).unwrap();
+
+        assert_eq!(load_settings(&path).unwrap(), settings);
+    }
+}
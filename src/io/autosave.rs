@@ -0,0 +1,74 @@
+//! Crash recovery autosaves.
+//!
+//! While a project has unsaved changes the editor periodically writes its
+//! layout to `.iced_builder/autosave.ron`, leaving the real layout file and
+//! the dirty flag alone. A manual save deletes the autosave, so one that is
+//! newer than the layout file means a session ended with unsaved work, which
+//! is offered for recovery when the project is opened again.
+
+use super::history_file::STATE_DIR;
+use super::layout_file::{save_layout_with_backup, LayoutFileError};
+use crate::model::LayoutDocument;
+use std::path::{Path, PathBuf};
+
+/// The autosave file name inside the state directory.
+pub const AUTOSAVE_FILENAME: &str = "autosave.ron";
+
+/// Default seconds between autosaves.
+pub const DEFAULT_AUTOSAVE_INTERVAL: u64 = 60;
+
+/// Get the autosave file path for a project directory.
+pub fn autosave_path(project_dir: &Path) -> PathBuf {
+    project_dir.join(STATE_DIR).join(AUTOSAVE_FILENAME)
+}
+
+/// Write an autosave of `layout`.
+pub fn save_autosave(path: &Path, layout: &LayoutDocument) -> Result<(), LayoutFileError> {
+    save_layout_with_backup(path, layout, 0)
+}
+
+/// Whether the autosave at `path` holds changes newer than `layout_path`.
+pub fn is_newer(path: &Path, layout_path: &Path) -> bool {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (modified(path), modified(layout_path)) {
+        (Some(autosave), Some(layout)) => autosave > layout,
+        (Some(_), None) => true,
+        (None, _) => false,
+    }
+}
+
+/// Delete the autosave at `path`, if any. Failures are logged.
+pub fn discard_autosave(path: &Path) {
+    match std::fs::remove_file(path) {
+        Ok(()) => tracing::debug!(target: "iced_builder::io", path = %path.display(), "Autosave discarded"),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => tracing::warn!(target: "iced_builder::io", error = %e, "Failed to delete autosave"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_autosave_newer_than_layout() {
+        let temp = tempdir().unwrap();
+        let layout_path = temp.path().join("layout.ron");
+        let path = autosave_path(temp.path());
+        assert!(!is_newer(&path, &layout_path));
+
+        save_autosave(&path, &LayoutDocument::default()).unwrap();
+        assert!(is_newer(&path, &layout_path));
+
+        let past = SystemTime::now() - Duration::from_secs(60);
+        std::fs::File::options().write(true).open(&path).unwrap().set_modified(past).unwrap();
+        std::fs::write(&layout_path, "").unwrap();
+        assert!(!is_newer(&path, &layout_path));
+
+        discard_autosave(&path);
+        assert!(!path.exists());
+        discard_autosave(&path);
+    }
+}
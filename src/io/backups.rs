@@ -0,0 +1,173 @@
+//! Rotating timestamped backups.
+//!
+//! Before a file is overwritten it is copied next to itself as
+//! `<name>.bak-YYYYMMDD-HHMMSS` (UTC). Only the newest backups are kept, so
+//! a few saves in a row never destroy the state from before them.
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Default number of backups kept per file.
+pub const DEFAULT_BACKUP_COUNT: usize = 5;
+
+/// Marker between a file name and the timestamp of its backups.
+const BACKUP_MARKER: &str = ".bak-";
+
+/// Copy `path` to a new timestamped backup and prune all but the newest
+/// `keep` backups. Does nothing when `keep` is zero or `path` doesn't exist.
+pub fn create_backup(path: &Path, keep: usize) -> std::io::Result<Option<PathBuf>> {
+    if keep == 0 || !path.exists() {
+        return Ok(None);
+    }
+
+    let stamp = timestamp(SystemTime::now());
+    // Saves within the same second get a counter past any earlier one,
+    // even if that one was pruned
+    let last = backups_for(path)
+        .iter()
+        .map(|b| sort_key(b))
+        .filter(|(s, _)| *s == stamp)
+        .map(|(_, n)| n)
+        .max();
+    let backup = match last {
+        Some(n) => backup_path(path, &format!("{}-{}", stamp, n + 1)),
+        None => backup_path(path, &stamp),
+    };
+
+    tracing::debug!(target: "iced_builder::io",
+        original = %path.display(),
+        backup = %backup.display(),
+        "Creating backup file"
+    );
+    std::fs::copy(path, &backup)?;
+
+    for old in backups_for(path).into_iter().skip(keep) {
+        tracing::debug!(target: "iced_builder::io", backup = %old.display(), "Pruning old backup");
+        if let Err(e) = std::fs::remove_file(&old) {
+            tracing::warn!(target: "iced_builder::io", error = %e, "Failed to prune backup");
+        }
+    }
+    Ok(Some(backup))
+}
+
+/// List the backups of `path`, newest first.
+pub fn backups_for(path: &Path) -> Vec<PathBuf> {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name().and_then(|n| n.to_str())) else {
+        return Vec::new();
+    };
+    let prefix = format!("{}{}", name, BACKUP_MARKER);
+    let dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|p| {
+            p.is_file() && p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with(&prefix))
+        })
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(sort_key(b)));
+    backups
+}
+
+/// The time a backup was made, as `YYYY-MM-DD HH:MM:SS UTC`, taken from its name.
+pub fn backup_label(backup: &Path) -> String {
+    let stamp = stamp_of(backup);
+    match (stamp.get(0..4), stamp.get(4..6), stamp.get(6..8), stamp.get(9..11), stamp.get(11..13), stamp.get(13..15)) {
+        (Some(y), Some(mo), Some(d), Some(h), Some(mi), Some(s)) => {
+            format!("{}-{}-{} {}:{}:{} UTC", y, mo, d, h, mi, s)
+        }
+        _ => backup.display().to_string(),
+    }
+}
+
+fn backup_path(path: &Path, stamp: &str) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}{}{}", name, BACKUP_MARKER, stamp))
+}
+
+/// The `YYYYMMDD-HHMMSS` stamp of a backup name.
+fn stamp_of(backup: &Path) -> String {
+    suffix_of(backup).chars().take(15).collect()
+}
+
+/// The part of a backup name after the marker.
+fn suffix_of(backup: &Path) -> String {
+    let name = backup.file_name().unwrap_or_default().to_string_lossy();
+    name.rsplit_once(BACKUP_MARKER)
+        .map(|(_, suffix)| suffix.to_string())
+        .unwrap_or_default()
+}
+
+/// Order backups by stamp, then by same-second counter.
+fn sort_key(backup: &Path) -> (String, u32) {
+    let suffix = suffix_of(backup);
+    let counter = suffix.get(16..).and_then(|n| n.parse().ok()).unwrap_or(1);
+    (stamp_of(backup), counter)
+}
+
+/// Format a time as `YYYYMMDD-HHMMSS` in UTC.
+fn timestamp(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_timestamp_is_utc_civil_time() {
+        assert_eq!(timestamp(UNIX_EPOCH), "19700101-000000");
+        let june = UNIX_EPOCH + Duration::from_secs(1_717_243_381);
+        assert_eq!(timestamp(june), "20240601-120301");
+        let leap = UNIX_EPOCH + Duration::from_secs(951_782_400);
+        assert_eq!(timestamp(leap), "20000229-000000");
+    }
+
+    #[test]
+    fn test_backups_rotate_newest_first() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join("layout.ron");
+        assert_eq!(create_backup(&path, 3).unwrap(), None);
+
+        std::fs::write(&path, "0").unwrap();
+        for i in 1..=5 {
+            create_backup(&path, 3).unwrap().unwrap();
+            std::fs::write(&path, i.to_string()).unwrap();
+        }
+
+        let backups = backups_for(&path);
+        assert_eq!(backups.len(), 3);
+        let contents: Vec<_> = backups.iter().map(|b| std::fs::read_to_string(b).unwrap()).collect();
+        assert_eq!(contents, ["4", "3", "2"]);
+        assert!(backup_label(&backups[0]).ends_with(" UTC"));
+
+        assert_eq!(create_backup(&path, 0).unwrap(), None);
+        assert_eq!(backups_for(&temp.path().join("other.ron")), Vec::<PathBuf>::new());
+    }
+}
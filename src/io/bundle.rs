@@ -0,0 +1,262 @@
+//! Portable project bundles.
+//!
+//! A bundle is a zip archive with the `.icedproj` extension holding a
+//! project's files and an `icedproj.toml` manifest listing them. Only files
+//! named in the manifest are extracted, and only to paths inside the target
+//! directory.
+
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+
+use super::config::CONFIG_FILENAME;
+
+/// File extension of project bundles.
+pub const BUNDLE_EXTENSION: &str = "icedproj";
+
+/// Name of the manifest inside a bundle.
+pub const MANIFEST_FILENAME: &str = "icedproj.toml";
+
+/// Current bundle format version.
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Errors that can occur when writing or reading bundles.
+#[derive(Debug, Error)]
+pub enum BundleError {
+    /// Reading or writing a file failed.
+    #[error("Failed to access bundle: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The archive is not a valid zip file.
+    #[error("Invalid bundle archive: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
+    /// The manifest is missing or invalid.
+    #[error("Invalid bundle manifest: {0}")]
+    Manifest(String),
+
+    /// The bundle was made by a newer version.
+    #[error("Bundle version {0} is newer than this Iced Builder supports (up to {max})", max = BUNDLE_VERSION)]
+    UnsupportedVersion(u32),
+
+    /// An entry would extract outside the target folder.
+    #[error("Bundle contains an unsafe path: {0}")]
+    UnsafePath(String),
+
+    /// A file the manifest lists is not in the archive.
+    #[error("Bundle is missing {0}")]
+    MissingFile(String),
+
+    /// The target folder already has files in it.
+    #[error("{} is not empty", .0.display())]
+    NotEmpty(PathBuf),
+}
+
+/// Description of a bundle's contents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    /// Bundle format version.
+    pub version: u32,
+    /// Name of the bundled layout.
+    pub name: String,
+    /// Bundled files, relative to the project with `/` separators.
+    pub files: Vec<String>,
+}
+
+impl BundleManifest {
+    /// Check the manifest describes a project this version can extract.
+    fn validate(&self) -> Result<(), BundleError> {
+        if self.version > BUNDLE_VERSION {
+            return Err(BundleError::UnsupportedVersion(self.version));
+        }
+        if !self.files.iter().any(|f| f == CONFIG_FILENAME) {
+            return Err(BundleError::MissingFile(CONFIG_FILENAME.to_string()));
+        }
+        for file in &self.files {
+            safe_relative_path(file)?;
+        }
+        Ok(())
+    }
+}
+
+/// Write `files`, relative to `project_dir`, into a bundle at `path`.
+pub fn write_bundle(
+    path: &Path,
+    project_dir: &Path,
+    name: &str,
+    files: &[PathBuf],
+) -> Result<BundleManifest, BundleError> {
+    tracing::info!(target: "iced_builder::io", path = %path.display(), files = files.len(), "Writing bundle");
+
+    let manifest = BundleManifest {
+        version: BUNDLE_VERSION,
+        name: name.to_string(),
+        files: files.iter().map(|f| archive_name(f)).collect::<Result<_, _>>()?,
+    };
+    manifest.validate()?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path)?);
+    let options = zip::write::SimpleFileOptions::default();
+
+    let manifest_toml = toml::to_string_pretty(&manifest).map_err(|e| BundleError::Manifest(e.to_string()))?;
+    zip.start_file(MANIFEST_FILENAME, options)?;
+    zip.write_all(manifest_toml.as_bytes())?;
+    for (file, entry) in files.iter().zip(&manifest.files) {
+        zip.start_file(entry.as_str(), options)?;
+        zip.write_all(&std::fs::read(project_dir.join(file))?)?;
+    }
+    zip.finish()?;
+    Ok(manifest)
+}
+
+/// Read and validate the manifest of a bundle.
+pub fn read_manifest(path: &Path) -> Result<BundleManifest, BundleError> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?)?;
+    manifest_of(&mut archive)
+}
+
+/// Extract a bundle into `target`, which must be empty or missing unless
+/// `overwrite` is set.
+pub fn extract_bundle(path: &Path, target: &Path, overwrite: bool) -> Result<BundleManifest, BundleError> {
+    tracing::info!(target: "iced_builder::io", path = %path.display(), target = %target.display(), "Extracting bundle");
+
+    if !overwrite && !is_empty_dir(target) {
+        return Err(BundleError::NotEmpty(target.to_path_buf()));
+    }
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?)?;
+    let manifest = manifest_of(&mut archive)?;
+
+    for file in &manifest.files {
+        let destination = target.join(safe_relative_path(file)?);
+        let mut entry = match archive.by_name(file) {
+            Ok(entry) => entry,
+            Err(zip::result::ZipError::FileNotFound) => return Err(BundleError::MissingFile(file.clone())),
+            Err(e) => return Err(e.into()),
+        };
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents)?;
+        if let Some(parent) = destination.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&destination, contents)?;
+    }
+    Ok(manifest)
+}
+
+/// Whether `path` is missing or an empty directory.
+pub fn is_empty_dir(path: &Path) -> bool {
+    match std::fs::read_dir(path) {
+        Ok(mut entries) => entries.next().is_none(),
+        Err(_) => !path.exists(),
+    }
+}
+
+fn manifest_of(archive: &mut zip::ZipArchive<std::fs::File>) -> Result<BundleManifest, BundleError> {
+    let mut content = String::new();
+    match archive.by_name(MANIFEST_FILENAME) {
+        Ok(mut entry) => entry.read_to_string(&mut content)?,
+        Err(zip::result::ZipError::FileNotFound) => {
+            return Err(BundleError::MissingFile(MANIFEST_FILENAME.to_string()))
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let manifest: BundleManifest = toml::from_str(&content).map_err(|e| BundleError::Manifest(e.to_string()))?;
+    manifest.validate()?;
+    Ok(manifest)
+}
+
+/// The archive entry name of a project-relative path.
+fn archive_name(path: &Path) -> Result<String, BundleError> {
+    let parts: Option<Vec<&str>> = path
+        .components()
+        .map(|c| match c {
+            Component::Normal(part) => part.to_str(),
+            _ => None,
+        })
+        .collect();
+    match parts {
+        Some(parts) if !parts.is_empty() => Ok(parts.join("/")),
+        _ => Err(BundleError::UnsafePath(path.display().to_string())),
+    }
+}
+
+/// A bundle entry name as a relative path that can't leave the target directory.
+fn safe_relative_path(name: &str) -> Result<PathBuf, BundleError> {
+    let path: PathBuf = name.split('/').collect();
+    let safe = name.split('/').all(|part| !part.is_empty() && part != "." && !part.contains(['\\', ':']))
+        && path.components().all(|c| matches!(c, Component::Normal(_)));
+    if safe {
+        Ok(path)
+    } else {
+        Err(BundleError::UnsafePath(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let temp = tempdir().unwrap();
+        let project = temp.path().join("project");
+        std::fs::create_dir_all(project.join("snippets")).unwrap();
+        std::fs::write(project.join(CONFIG_FILENAME), "output_file = \"src/view.rs\"\n").unwrap();
+        std::fs::write(project.join("layout.ron"), "layout").unwrap();
+        std::fs::write(project.join("snippets").join("card.ron"), "card").unwrap();
+        let files = [
+            PathBuf::from(CONFIG_FILENAME),
+            PathBuf::from("layout.ron"),
+            Path::new("snippets").join("card.ron"),
+        ];
+
+        let bundle = temp.path().join("shared.icedproj");
+        let written = write_bundle(&bundle, &project, "Login", &files).unwrap();
+        assert_eq!(written.files[2], "snippets/card.ron");
+        assert_eq!(read_manifest(&bundle).unwrap(), written);
+
+        let target = temp.path().join("imported");
+        extract_bundle(&bundle, &target, false).unwrap();
+        assert_eq!(std::fs::read_to_string(target.join("snippets").join("card.ron")).unwrap(), "card");
+        assert_eq!(std::fs::read_to_string(target.join("layout.ron")).unwrap(), "layout");
+
+        assert!(matches!(extract_bundle(&bundle, &target, false), Err(BundleError::NotEmpty(_))));
+        extract_bundle(&bundle, &target, true).unwrap();
+    }
+
+    #[test]
+    fn test_bundle_rejects_unsafe_or_incomplete_manifests() {
+        let temp = tempdir().unwrap();
+        let bundle = temp.path().join("bad.icedproj");
+        let write = |manifest: &str| {
+            let mut zip = zip::ZipWriter::new(std::fs::File::create(&bundle).unwrap());
+            zip.start_file(MANIFEST_FILENAME, zip::write::SimpleFileOptions::default()).unwrap();
+            zip.write_all(manifest.as_bytes()).unwrap();
+            zip.finish().unwrap();
+        };
+
+        write("version = 1\nname = \"x\"\nfiles = [\"iced_builder.toml\", \"../escape.ron\"]\n");
+        assert!(matches!(read_manifest(&bundle), Err(BundleError::UnsafePath(_))));
+
+        write("version = 1\nname = \"x\"\nfiles = [\"layout.ron\"]\n");
+        assert!(matches!(read_manifest(&bundle), Err(BundleError::MissingFile(_))));
+
+        write("version = 9\nname = \"x\"\nfiles = [\"iced_builder.toml\"]\n");
+        assert!(matches!(read_manifest(&bundle), Err(BundleError::UnsupportedVersion(9))));
+
+        write("version = 1\nname = \"x\"\nfiles = [\"iced_builder.toml\"]\n");
+        let error = extract_bundle(&bundle, &temp.path().join("out"), false).unwrap_err();
+        assert!(matches!(error, BundleError::MissingFile(f) if f == CONFIG_FILENAME));
+
+        assert!(safe_relative_path("/etc/passwd").is_err());
+        assert!(safe_relative_path("a/./b").is_err());
+        assert!(safe_relative_path("C:/temp").is_err());
+        assert!(safe_relative_path("snippets/card.ron").is_ok());
+        assert!(safe_relative_path("a\\..\\b").is_err());
+    }
+}
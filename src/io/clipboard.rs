@@ -0,0 +1,75 @@
+//! Clipboard encoding for layout subtrees.
+//!
+//! Copied subtrees are written to the system clipboard as RON prefixed with
+//! a marker line, so pasting can tell our own data apart from other text.
+//! Decoding gives every node a fresh ComponentId, which lets the same data
+//! be pasted repeatedly or into another project.
+
+use crate::model::LayoutNode;
+use thiserror::Error;
+
+/// First line of clipboard text holding a layout subtree.
+pub const CLIPBOARD_MARKER: &str = "// iced-builder subtree v1";
+
+/// Errors that can occur when reading a subtree from the clipboard.
+#[derive(Debug, Error)]
+pub enum ClipboardError {
+    /// The clipboard text is not a copied subtree.
+    #[error("Clipboard does not contain a layout")]
+    NotALayout,
+
+    /// The copied subtree could not be parsed.
+    #[error("Failed to parse clipboard layout: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+
+    /// The subtree could not be written as RON.
+    #[error("Failed to serialize layout: {0}")]
+    Serialize(#[from] ron::Error),
+}
+
+/// Encode a subtree as clipboard text.
+pub fn encode_subtree(node: &LayoutNode) -> Result<String, ClipboardError> {
+    let pretty = ron::ser::PrettyConfig::default().struct_names(true);
+    let body = ron::ser::to_string_pretty(node, pretty)?;
+    Ok(format!("{CLIPBOARD_MARKER}\n{body}"))
+}
+
+/// Decode clipboard text into a subtree, giving every node a new ComponentId.
+pub fn decode_subtree(text: &str) -> Result<LayoutNode, ClipboardError> {
+    let body = text
+        .trim_start()
+        .strip_prefix(CLIPBOARD_MARKER)
+        .ok_or(ClipboardError::NotALayout)?;
+    let mut node = super::migrate::subtree_from_ron(body)?;
+    node.regenerate_ids();
+    Ok(node)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::layout::WidgetType;
+    use crate::test_support::sample_subtree;
+
+    #[test]
+    fn test_clipboard_round_trip_regenerates_ids() {
+        let original = sample_subtree();
+        let text = encode_subtree(&original).unwrap();
+        assert!(text.starts_with(CLIPBOARD_MARKER));
+
+        let pasted = decode_subtree(&text).unwrap();
+        assert_ne!(pasted.id, original.id);
+        let child = &pasted.children().unwrap()[0];
+        assert_ne!(child.id, original.children().unwrap()[0].id);
+        assert!(matches!(&child.widget, WidgetType::Text { content, .. } if content == "Hello"));
+    }
+
+    #[test]
+    fn test_clipboard_rejects_foreign_text() {
+        assert!(matches!(decode_subtree("hello world"), Err(ClipboardError::NotALayout)));
+        assert!(matches!(decode_subtree(""), Err(ClipboardError::NotALayout)));
+
+        let garbage = format!("{CLIPBOARD_MARKER}\nnot ron at all");
+        assert!(matches!(decode_subtree(&garbage), Err(ClipboardError::Parse(_))));
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Handles parsing `iced_builder.toml` files with backup support.
 
+use super::backups::{self, DEFAULT_BACKUP_COUNT};
 use crate::model::ProjectConfig;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
@@ -12,18 +13,23 @@ pub const CONFIG_FILENAME: &str = "iced_builder.toml";
 /// Errors that can occur when loading config.
 #[derive(Debug, Error)]
 pub enum ConfigError {
+    /// Reading or writing the file failed.
     #[error("Failed to read config file: {0}")]
     ReadError(#[from] std::io::Error),
 
+    /// The file is not valid config TOML.
     #[error("Failed to parse config: {0}")]
     ParseError(#[from] toml::de::Error),
 
+    /// The config could not be written as TOML.
     #[error("Failed to serialize config: {0}")]
     SerializeError(#[from] toml::ser::Error),
 
+    /// No config file was found.
     #[error("Config file not found: {0}")]
     NotFound(String),
 
+    /// Backing up the previous config failed.
     #[error("Failed to create backup: {0}")]
     BackupError(String),
 }
@@ -43,30 +49,22 @@ pub fn load_config(path: &Path) -> Result<ProjectConfig, ConfigError> {
     Ok(config)
 }
 
-/// Save project configuration to a TOML file.
+/// Save project configuration to a TOML file, keeping the default number of backups.
 pub fn save_config(path: &Path, config: &ProjectConfig) -> Result<(), ConfigError> {
-    save_config_with_backup(path, config, true)
+    save_config_with_backup(path, config, DEFAULT_BACKUP_COUNT)
 }
 
-/// Save project configuration with optional backup.
+/// Save project configuration, keeping the newest `keep_backups` timestamped
+/// backups of the previous file; zero disables backups.
 pub fn save_config_with_backup(
     path: &Path,
     config: &ProjectConfig,
-    create_backup: bool,
+    keep_backups: usize,
 ) -> Result<(), ConfigError> {
     tracing::info!(target: "iced_builder::io", path = %path.display(), "Saving config file");
 
-    // Create backup if file exists
-    if create_backup && path.exists() {
-        let backup_path = path.with_extension("toml.bak");
-        tracing::debug!(target: "iced_builder::io", 
-            backup = %backup_path.display(), 
-            "Creating config backup"
-        );
-        std::fs::copy(path, &backup_path).map_err(|e| {
-            ConfigError::BackupError(format!("Failed to create backup: {}", e))
-        })?;
-    }
+    backups::create_backup(path, keep_backups)
+        .map_err(|e| ConfigError::BackupError(format!("Failed to create backup: {}", e)))?;
 
     // Ensure parent directory exists
     if let Some(parent) = path.parent() {
@@ -85,7 +83,7 @@ pub fn save_config_with_backup(
 /// Create a default configuration file.
 pub fn create_default_config(path: &Path) -> Result<(), ConfigError> {
     let config = ProjectConfig::default();
-    save_config_with_backup(path, &config, false)
+    save_config_with_backup(path, &config, 0)
 }
 
 /// Find the config file in a project directory.
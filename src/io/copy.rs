@@ -0,0 +1,99 @@
+//! Copying project files to another directory.
+//!
+//! Used when a project is saved under a new location. Paths passed in are
+//! relative to the source directory and keep the same place under the
+//! destination.
+
+use std::path::{Path, PathBuf};
+
+/// Copy `files`, relative to `from`, to the same paths under `to`, creating
+/// directories as needed. Returns the number of files copied.
+pub fn copy_files(from: &Path, to: &Path, files: &[PathBuf]) -> std::io::Result<usize> {
+    for file in files {
+        let destination = to.join(file);
+        if let Some(parent) = destination.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(from.join(file), destination)?;
+    }
+    Ok(files.len())
+}
+
+/// Copy the directory `from` and everything in it to `to`, returning the
+/// number of files copied. Nothing is copied when `from` doesn't exist.
+pub fn copy_dir_recursive(from: &Path, to: &Path) -> std::io::Result<usize> {
+    if !from.is_dir() {
+        return Ok(0);
+    }
+    // Copying a directory into itself would never finish
+    if to.starts_with(from) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("cannot copy {} into itself", from.display()),
+        ));
+    }
+
+    tracing::debug!(target: "iced_builder::io", from = %from.display(), to = %to.display(), "Copying directory");
+    std::fs::create_dir_all(to)?;
+    let mut copied = 0;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let destination = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copied += copy_dir_recursive(&entry.path(), &destination)?;
+        } else {
+            std::fs::copy(entry.path(), destination)?;
+            copied += 1;
+        }
+    }
+    Ok(copied)
+}
+
+/// Whether a relative path stays inside the directory it is relative to.
+pub fn is_inside(relative: &Path) -> bool {
+    relative.is_relative()
+        && relative
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_) | std::path::Component::CurDir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_copy_dir_recursive_copies_nested_files() {
+        let temp = tempdir().unwrap();
+        let from = temp.path().join("from");
+        std::fs::create_dir_all(from.join("a").join("b")).unwrap();
+        std::fs::create_dir_all(from.join("empty")).unwrap();
+        std::fs::write(from.join("top.ron"), "top").unwrap();
+        std::fs::write(from.join("a").join("b").join("deep.ron"), "deep").unwrap();
+
+        let to = temp.path().join("to");
+        assert_eq!(copy_dir_recursive(&from, &to).unwrap(), 2);
+        assert_eq!(std::fs::read_to_string(to.join("a").join("b").join("deep.ron")).unwrap(), "deep");
+        assert!(to.join("empty").is_dir());
+
+        assert_eq!(copy_dir_recursive(&temp.path().join("missing"), &to).unwrap(), 0);
+        assert!(copy_dir_recursive(&from, &from.join("a")).is_err());
+    }
+
+    #[test]
+    fn test_copy_files_keeps_relative_paths() {
+        let temp = tempdir().unwrap();
+        let from = temp.path().join("from");
+        std::fs::create_dir_all(from.join("components")).unwrap();
+        std::fs::write(from.join("components").join("card.ron"), "card").unwrap();
+
+        let to = temp.path().join("to");
+        let files = [Path::new("components").join("card.ron")];
+        assert_eq!(copy_files(&from, &to, &files).unwrap(), 1);
+        assert_eq!(std::fs::read_to_string(to.join(&files[0])).unwrap(), "card");
+
+        assert!(is_inside(Path::new("./src/view.rs")));
+        assert!(!is_inside(Path::new("../shared/view.rs")));
+        assert!(!is_inside(Path::new("/abs/view.rs")));
+    }
+}
@@ -0,0 +1,137 @@
+//! Saving the layout when the editor panics.
+//!
+//! Autosaves only run every so often, so a panic can still lose the latest
+//! edits. Every second or so the editor updates a [`CrashHandle`] with the
+//! active project's unsaved layout, and the panic hook installed by
+//! [`install_panic_hook`] writes it to `.iced_builder/crash-recovery.ron`
+//! before the process goes down. Opening the project again offers to
+//! restore it.
+
+use super::history_file::STATE_DIR;
+use super::layout_file::{save_layout_with_backup, LayoutFileError};
+use crate::model::LayoutDocument;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// The crash recovery file name inside the state directory.
+pub const CRASH_RECOVERY_FILENAME: &str = "crash-recovery.ron";
+
+/// Get the crash recovery file path for a project directory, or a file in
+/// the temp directory for a layout without one.
+pub fn crash_recovery_path(project_dir: Option<&Path>) -> PathBuf {
+    match project_dir {
+        Some(dir) => dir.join(STATE_DIR).join(CRASH_RECOVERY_FILENAME),
+        None => std::env::temp_dir().join(format!("iced-builder-{}", CRASH_RECOVERY_FILENAME)),
+    }
+}
+
+/// The layout to save if the editor panics.
+#[derive(Debug, Clone)]
+struct Snapshot {
+    project_dir: Option<PathBuf>,
+    layout: LayoutDocument,
+}
+
+/// Shared handle to the layout the panic hook saves.
+#[derive(Debug, Clone, Default)]
+pub struct CrashHandle(Arc<Mutex<Option<Snapshot>>>);
+
+impl CrashHandle {
+    /// Save `layout` from `project_dir` if the editor panics.
+    pub fn set(&self, project_dir: Option<&Path>, layout: &LayoutDocument) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = Some(Snapshot {
+            project_dir: project_dir.map(Path::to_path_buf),
+            layout: layout.clone(),
+        });
+    }
+
+    /// Nothing needs saving, e.g. because all changes are saved.
+    pub fn clear(&self) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    /// Write the layout to its crash recovery file, returning where it went.
+    ///
+    /// Returns `None` when there is nothing to save, or when the handle is
+    /// locked by the thread that panicked.
+    pub fn write(&self) -> Option<Result<PathBuf, LayoutFileError>> {
+        let guard = match self.0.try_lock() {
+            Ok(guard) => guard,
+            Err(std::sync::TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+            Err(std::sync::TryLockError::WouldBlock) => return None,
+        };
+        let snapshot = guard.as_ref()?;
+        let path = crash_recovery_path(snapshot.project_dir.as_deref());
+        Some(save_layout_with_backup(&path, &snapshot.layout, 0).map(|()| path))
+    }
+}
+
+/// Install a panic hook that saves the layout in `handle` before running
+/// the default hook.
+pub fn install_panic_hook(handle: CrashHandle) {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        match handle.write() {
+            Some(Ok(path)) => {
+                tracing::error!(target: "iced_builder::io", path = %path.display(), "Panicked; saved layout for recovery");
+                eprintln!("Iced Builder crashed. Unsaved changes were saved to {}", path.display());
+            }
+            Some(Err(e)) => tracing::error!(target: "iced_builder::io", error = %e, "Panicked; failed to save layout"),
+            None => {}
+        }
+        default_hook(info);
+    }));
+}
+
+/// Whether a crash left a layout to restore in `project_dir`.
+pub fn has_crash_recovery(project_dir: &Path) -> bool {
+    crash_recovery_path(Some(project_dir)).is_file()
+}
+
+/// Delete the crash recovery file in `project_dir`, if any. Failures are
+/// logged.
+pub fn discard_crash_recovery(project_dir: &Path) {
+    let path = crash_recovery_path(Some(project_dir));
+    match std::fs::remove_file(&path) {
+        Ok(()) => tracing::debug!(target: "iced_builder::io", path = %path.display(), "Crash recovery discarded"),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => tracing::warn!(target: "iced_builder::io", error = %e, "Failed to delete crash recovery"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::layout_file::load_layout;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_handle_writes_current_layout() {
+        let temp = tempdir().unwrap();
+        let handle = CrashHandle::default();
+        assert!(handle.write().is_none());
+
+        let layout = LayoutDocument {
+            name: "Unsaved".to_string(),
+            ..Default::default()
+        };
+        handle.set(Some(temp.path()), &layout);
+        let path = handle.write().unwrap().unwrap();
+        assert_eq!(path, crash_recovery_path(Some(temp.path())));
+        assert!(has_crash_recovery(temp.path()));
+        assert_eq!(load_layout(&path).unwrap(), layout);
+
+        handle.clear();
+        assert!(handle.write().is_none());
+        discard_crash_recovery(temp.path());
+        assert!(!has_crash_recovery(temp.path()));
+        discard_crash_recovery(temp.path());
+    }
+
+    #[test]
+    fn test_layout_without_project_goes_to_temp_dir() {
+        let path = crash_recovery_path(None);
+        assert!(path.starts_with(std::env::temp_dir()));
+        assert!(path.to_string_lossy().ends_with(CRASH_RECOVERY_FILENAME));
+    }
+}
@@ -0,0 +1,151 @@
+//! Undo history persistence.
+//!
+//! When a project opts in with `persist_history`, the undo stack is written
+//! to `.iced_builder/history.ron` on save, tagged with a hash of the saved
+//! layout file. Snapshots repeat most of the layout, so the RON is
+//! zlib-compressed. On open the history is restored only if the layout file
+//! still hashes the same, so edits made outside the builder never get
+//! undone into. Missing, stale or unreadable history files are ignored.
+
+use crate::model::History;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Directory for builder state inside a project.
+pub const STATE_DIR: &str = ".iced_builder";
+
+/// The history file name inside the state directory.
+pub const HISTORY_FILENAME: &str = "history.ron";
+
+/// Errors that can occur when saving history.
+#[derive(Debug, Error)]
+pub enum HistoryFileError {
+    /// Writing the file failed.
+    #[error("Failed to write history file: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The history could not be written as RON.
+    #[error("Failed to serialize history: {0}")]
+    Serialize(#[from] ron::Error),
+}
+
+/// History as stored on disk.
+#[derive(Serialize, Deserialize)]
+struct HistoryFile<H> {
+    /// Hash of the layout file the history leads up to.
+    layout_hash: u64,
+    history: H,
+}
+
+/// Get the history file path for a project directory.
+pub fn history_path(project_dir: &Path) -> PathBuf {
+    project_dir.join(STATE_DIR).join(HISTORY_FILENAME)
+}
+
+/// Hash file contents with 64-bit FNV-1a, which is stable across builds.
+pub fn content_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Save the undo stack, tagged with the contents of the saved layout file.
+pub fn save_history(path: &Path, history: &History, layout_contents: &[u8]) -> Result<(), HistoryFileError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let file = HistoryFile {
+        layout_hash: content_hash(layout_contents),
+        history,
+    };
+    let content = ron::ser::to_string(&file)?;
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(content.as_bytes())?;
+    std::fs::write(path, encoder.finish()?)?;
+
+    tracing::debug!(
+        target: "iced_builder::io",
+        path = %path.display(),
+        steps = history.undo_count(),
+        "History saved"
+    );
+    Ok(())
+}
+
+/// Load history saved against the given layout file contents.
+///
+/// Returns None if the file is missing, unreadable, or was saved against
+/// a different layout.
+pub fn load_history(path: &Path, layout_contents: &[u8]) -> Option<History> {
+    let compressed = std::fs::read(path).ok()?;
+    let mut content = String::new();
+    if let Err(e) = ZlibDecoder::new(compressed.as_slice()).read_to_string(&mut content) {
+        tracing::debug!(target: "iced_builder::io", path = %path.display(), error = %e, "Ignoring unreadable history");
+        return None;
+    }
+    let file: HistoryFile<History> = match ron::from_str(&content) {
+        Ok(file) => file,
+        Err(e) => {
+            tracing::debug!(target: "iced_builder::io", path = %path.display(), error = %e, "Ignoring unreadable history");
+            return None;
+        }
+    };
+    if file.layout_hash != content_hash(layout_contents) {
+        tracing::debug!(target: "iced_builder::io", path = %path.display(), "Ignoring history saved against another layout");
+        return None;
+    }
+
+    tracing::debug!(
+        target: "iced_builder::io",
+        path = %path.display(),
+        steps = file.history.undo_count(),
+        "History restored"
+    );
+    Some(file.history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::doc_named;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_history_round_trip_requires_matching_layout() {
+        let temp = tempdir().unwrap();
+        let path = history_path(temp.path());
+
+        let mut history = History::default();
+        history.push(doc_named("A"), "Add Button");
+        history.push(doc_named("B"), "Change padding");
+        save_history(&path, &history, b"saved layout").unwrap();
+        let plain = ron::ser::to_string(&history).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() < plain.len() as u64);
+
+        let mut restored = load_history(&path, b"saved layout").unwrap();
+        assert_eq!(restored.labels().collect::<Vec<_>>(), ["Add Button", "Change padding"]);
+        let (b, _) = restored.undo(doc_named("C")).unwrap();
+        assert_eq!(b.name, "B");
+        let (a, _) = restored.undo(b).unwrap();
+        assert_eq!(a.name, "A");
+
+        assert!(load_history(&path, b"edited elsewhere").is_none());
+    }
+
+    #[test]
+    fn test_missing_or_corrupt_history_is_ignored() {
+        let temp = tempdir().unwrap();
+        let path = history_path(temp.path());
+        assert!(load_history(&path, b"layout").is_none());
+
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "not a history file").unwrap();
+        assert!(load_history(&path, b"layout").is_none());
+    }
+}
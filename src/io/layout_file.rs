@@ -1,40 +1,123 @@
 //! Layout file loading and saving.
 //!
-//! Supports both RON and JSON formats with backup creation.
-
-use crate::model::LayoutDocument;
+//! Supports both RON and JSON formats with rotating backups. Files saved by
+//! older versions are upgraded on load, see [`super::migrate`]. Fields this
+//! version doesn't know are skipped and reported as [`LoadWarning`]s.
+
+use super::backups::{self, DEFAULT_BACKUP_COUNT};
+use super::migrate;
+use crate::model::{layout::LAYOUT_VERSION, LayoutDocument};
+use serde::Deserialize;
+use std::fmt;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 /// Errors that can occur when loading/saving layouts.
 #[derive(Debug, Error)]
 pub enum LayoutFileError {
+    /// Reading or writing the file failed.
     #[error("Failed to read file: {0}")]
     ReadError(#[from] std::io::Error),
 
+    /// The file is not valid layout RON.
     #[error("Failed to parse RON: {0}")]
     RonParseError(#[from] ron::error::SpannedError),
 
+    /// The layout could not be written as RON.
     #[error("Failed to serialize RON: {0}")]
     RonSerializeError(#[from] ron::Error),
 
+    /// The file is not valid layout JSON, or could not be written as JSON.
     #[error("Failed to parse JSON: {0}")]
     JsonParseError(#[from] serde_json::Error),
 
+    /// The extension is neither `.ron` nor `.json`.
     #[error("Unknown file format: {0}")]
     UnknownFormat(String),
 
+    /// The file does not exist.
     #[error("File not found: {0}")]
     NotFound(String),
 
+    /// Backing up the previous layout failed.
     #[error("Failed to create backup: {0}")]
     BackupError(String),
+
+    /// The layout was saved by a newer version.
+    #[error("Layout version {0} is newer than this Iced Builder supports (up to {max}); update Iced Builder to open it", max = LAYOUT_VERSION)]
+    UnsupportedVersion(u32),
+
+    /// The layout could not be read for migration.
+    #[error("Failed to read layout for migration: {0}")]
+    MigrationError(String),
+}
+
+/// A field in a layout file that this version doesn't know, skipped on load.
+///
+/// Usually written by a newer Iced Builder; saving drops it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadWarning {
+    /// Where the field was, e.g. `root.widget.children.1`; empty for
+    /// the document itself.
+    pub path: String,
+    /// The unknown field name.
+    pub field: String,
+}
+
+impl LoadWarning {
+    fn from_ignored(path: &serde_ignored::Path) -> Self {
+        let mut segments = Vec::new();
+        Self::collect(path, &mut segments);
+        let field = segments.pop().unwrap_or_default();
+        Self {
+            path: segments.join("."),
+            field,
+        }
+    }
+
+    /// Collect the named segments of a path, skipping `Some` and newtype wrappers.
+    fn collect(path: &serde_ignored::Path, segments: &mut Vec<String>) {
+        use serde_ignored::Path;
+        match path {
+            Path::Root => {}
+            Path::Seq { parent, index } => {
+                Self::collect(parent, segments);
+                segments.push(index.to_string());
+            }
+            Path::Map { parent, key } => {
+                Self::collect(parent, segments);
+                segments.push(key.clone());
+            }
+            Path::Some { parent } | Path::NewtypeStruct { parent } | Path::NewtypeVariant { parent } => {
+                Self::collect(parent, segments);
+            }
+        }
+    }
+}
+
+impl fmt::Display for LoadWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "Unknown field `{}` in the document", self.field)
+        } else {
+            write!(f, "Unknown field `{}` in {}", self.field, self.path)
+        }
+    }
+}
+
+/// The part of a layout file needed to decide how to load it.
+#[derive(Deserialize)]
+#[serde(rename = "LayoutDocument")]
+struct VersionProbe {
+    version: u32,
 }
 
 /// Detected file format.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LayoutFormat {
+    /// Rusty Object Notation, the default.
     Ron,
+    /// JSON.
     Json,
 }
 
@@ -66,7 +149,31 @@ impl LayoutFormat {
 }
 
 /// Load a layout document from a file.
+///
+/// The format follows the extension, and layouts saved by older versions
+/// are migrated on the way in.
+///
+/// ```
+/// use iced_builder::io::{load_layout, save_layout};
+/// use iced_builder::model::LayoutDocument;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let dir = std::env::temp_dir().join("iced-builder-doc-load");
+/// # std::fs::create_dir_all(&dir)?;
+/// let layout = LayoutDocument::default();
+/// let path = dir.join("layout.json");
+/// save_layout(&path, &layout)?;
+/// assert_eq!(load_layout(&path)?, layout);
+/// # Ok(())
+/// # }
+/// ```
 pub fn load_layout(path: &Path) -> Result<LayoutDocument, LayoutFileError> {
+    load_layout_with_warnings(path).map(|(doc, _)| doc)
+}
+
+/// Load a layout document from a file, along with the unknown fields that
+/// were skipped.
+pub fn load_layout_with_warnings(path: &Path) -> Result<(LayoutDocument, Vec<LoadWarning>), LayoutFileError> {
     tracing::info!(target: "iced_builder::io", path = %path.display(), "Loading layout file");
 
     if !path.exists() {
@@ -78,43 +185,86 @@ pub fn load_layout(path: &Path) -> Result<LayoutDocument, LayoutFileError> {
     let format = LayoutFormat::from_path(path)
         .ok_or_else(|| LayoutFileError::UnknownFormat(path.display().to_string()))?;
 
+    let (doc, warnings) = parse_layout_with_warnings(&content, format)?;
+    tracing::info!(target: "iced_builder::io", warnings = warnings.len(), "Layout loaded successfully");
+    Ok((doc, warnings))
+}
+
+/// Parse layout file contents in the given format, migrating older versions.
+pub fn parse_layout(content: &str, format: LayoutFormat) -> Result<LayoutDocument, LayoutFileError> {
+    parse_layout_with_warnings(content, format).map(|(doc, _)| doc)
+}
+
+/// Parse layout file contents like [`parse_layout`], also returning the
+/// unknown fields that were skipped.
+pub fn parse_layout_with_warnings(
+    content: &str,
+    format: LayoutFormat,
+) -> Result<(LayoutDocument, Vec<LoadWarning>), LayoutFileError> {
+    let version = match format {
+        LayoutFormat::Ron => ron::from_str::<VersionProbe>(content)?.version,
+        LayoutFormat::Json => serde_json::from_str::<VersionProbe>(content)?.version,
+    };
+    if version > LAYOUT_VERSION {
+        return Err(LayoutFileError::UnsupportedVersion(version));
+    }
+
+    let mut warnings = Vec::new();
+    let mut skipped = |path: serde_ignored::Path| {
+        let warning = LoadWarning::from_ignored(&path);
+        tracing::warn!(target: "iced_builder::io", %warning, "Skipping unknown layout field");
+        warnings.push(warning);
+    };
     let doc = match format {
-        LayoutFormat::Ron => {
+        LayoutFormat::Ron if version == LAYOUT_VERSION => {
             tracing::debug!(target: "iced_builder::io", "Parsing RON format");
-            ron::from_str(&content)?
+            let mut de = ron::Deserializer::from_str(content)?;
+            let doc = serde_ignored::deserialize(&mut de, &mut skipped).map_err(|e| de.span_error(e))?;
+            de.end().map_err(|e| de.span_error(e))?;
+            doc
         }
-        LayoutFormat::Json => {
+        LayoutFormat::Json if version == LAYOUT_VERSION => {
             tracing::debug!(target: "iced_builder::io", "Parsing JSON format");
-            serde_json::from_str(&content)?
+            let mut de = serde_json::Deserializer::from_str(content);
+            let doc = serde_ignored::deserialize(&mut de, &mut skipped)?;
+            de.end()?;
+            doc
+        }
+        LayoutFormat::Ron => {
+            tracing::debug!(target: "iced_builder::io", version, "Parsing RON format for migration");
+            let value = migrate::ron_to_value(content).map_err(LayoutFileError::MigrationError)?;
+            serde_ignored::deserialize(migrate::migrate(value, version), &mut skipped)?
+        }
+        LayoutFormat::Json => {
+            tracing::debug!(target: "iced_builder::io", version, "Parsing JSON format for migration");
+            let value = serde_json::from_str(content)?;
+            serde_ignored::deserialize(migrate::migrate(value, version), &mut skipped)?
         }
     };
-
-    tracing::info!(target: "iced_builder::io", "Layout loaded successfully");
-    Ok(doc)
+    Ok((doc, warnings))
 }
 
-/// Save a layout document to a file with optional backup.
+/// Save a layout document to a file, keeping the default number of backups.
 pub fn save_layout(path: &Path, layout: &LayoutDocument) -> Result<(), LayoutFileError> {
-    save_layout_with_backup(path, layout, true)
+    save_layout_with_backup(path, layout, DEFAULT_BACKUP_COUNT)
 }
 
 /// Save a layout document to a file.
 /// 
-/// If `create_backup` is true and the file exists, creates a `.bak` backup first.
+/// If the file exists, it is first copied to a timestamped backup, keeping
+/// the newest `keep_backups` backups; zero disables backups.
 pub fn save_layout_with_backup(
     path: &Path,
     layout: &LayoutDocument,
-    create_backup: bool,
+    keep_backups: usize,
 ) -> Result<(), LayoutFileError> {
     tracing::info!(target: "iced_builder::io", path = %path.display(), "Saving layout file");
 
     let format = LayoutFormat::from_path(path)
         .ok_or_else(|| LayoutFileError::UnknownFormat(path.display().to_string()))?;
 
-    // Create backup if file exists and backup is requested
-    if create_backup && path.exists() {
-        create_backup_file(path)?;
-    }
+    backups::create_backup(path, keep_backups)
+        .map_err(|e| LayoutFileError::BackupError(format!("Failed to create backup: {}", e)))?;
 
     // Ensure parent directory exists
     if let Some(parent) = path.parent() {
@@ -142,30 +292,10 @@ pub fn save_layout_with_backup(
     Ok(())
 }
 
-/// Create a backup of an existing file.
-fn create_backup_file(path: &Path) -> Result<PathBuf, LayoutFileError> {
-    let backup_path = path.with_extension(format!(
-        "{}.bak",
-        path.extension().and_then(|e| e.to_str()).unwrap_or("bak")
-    ));
-
-    tracing::debug!(target: "iced_builder::io", 
-        original = %path.display(), 
-        backup = %backup_path.display(), 
-        "Creating backup file"
-    );
-
-    std::fs::copy(path, &backup_path).map_err(|e| {
-        LayoutFileError::BackupError(format!("Failed to create backup: {}", e))
-    })?;
-
-    Ok(backup_path)
-}
-
 /// Create a new layout file with default content.
 pub fn create_default_layout(path: &Path) -> Result<(), LayoutFileError> {
     let layout = LayoutDocument::default();
-    save_layout_with_backup(path, &layout, false)
+    save_layout_with_backup(path, &layout, 0)
 }
 
 /// Find layout files in a directory.
@@ -240,6 +370,98 @@ mod tests {
         assert_eq!(LayoutFormat::Json.name(), "JSON");
     }
 
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+    }
+
+    #[test]
+    fn test_v1_fixtures_are_migrated() {
+        use crate::model::layout::{AlignmentSpec, WidgetType};
+
+        // The v1 name only loads through the migration, not as an alias
+        let source = std::fs::read_to_string(fixture("layout_v1.ron")).unwrap();
+        let value = migrate::ron_to_value(&source).unwrap();
+        assert!(value.to_string().contains("\"horizontal_alignment\""));
+        assert!(serde_json::from_value::<LayoutDocument>(value.clone()).is_err());
+        let migrated = migrate::migrate(value, 1);
+        assert!(!migrated.to_string().contains("\"horizontal_alignment\""));
+        assert!(serde_json::from_value::<LayoutDocument>(migrated).is_ok());
+
+        let from_ron = load_layout(&fixture("layout_v1.ron")).unwrap();
+        let from_json = load_layout(&fixture("layout_v1.json")).unwrap();
+        assert_eq!(from_ron, from_json);
+        assert_eq!(from_ron.version, LAYOUT_VERSION);
+
+        let alignments: Vec<_> = from_ron
+            .exported_nodes()
+            .into_iter()
+            .filter_map(|node| match &node.widget {
+                WidgetType::Text { attrs, .. } => Some(attrs.align_x),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(alignments, [AlignmentSpec::Center, AlignmentSpec::End]);
+    }
+
+    #[test]
+    fn test_migrated_layout_saves_current_version() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("layout.ron");
+        let doc = load_layout(&fixture("layout_v1.ron")).unwrap();
+        save_layout_with_backup(&path, &doc, 0).unwrap();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(saved.contains(&format!("version: {},", LAYOUT_VERSION)));
+        assert!(!saved.contains("horizontal_alignment"));
+        assert_eq!(load_layout(&path).unwrap(), doc);
+    }
+
+    #[test]
+    fn test_future_version_is_unsupported() {
+        let error = load_layout(&fixture("layout_v3.ron")).unwrap_err();
+        assert!(matches!(error, LayoutFileError::UnsupportedVersion(3)));
+        assert!(error.to_string().contains("update Iced Builder"));
+    }
+
+    #[test]
+    fn test_ron_json_conversion_round_trips() {
+        let temp = tempfile::tempdir().unwrap();
+        let sample = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/sample_project/layout.ron");
+        let original = load_layout(&sample).unwrap();
+
+        let json = temp.path().join("layout.json");
+        save_layout_with_backup(&json, &original, 0).unwrap();
+        let from_json = load_layout(&json).unwrap();
+        assert_eq!(from_json, original);
+
+        let ron = temp.path().join("layout.ron");
+        save_layout_with_backup(&ron, &from_json, 0).unwrap();
+        assert_eq!(load_layout(&ron).unwrap(), original);
+    }
+
+    #[test]
+    fn test_unknown_fields_are_skipped_with_warnings() {
+        let (from_ron, warnings) = load_layout_with_warnings(&fixture("layout_unknown_fields.ron")).unwrap();
+        let (from_json, json_warnings) = load_layout_with_warnings(&fixture("layout_unknown_fields.json")).unwrap();
+        assert_eq!(from_ron, from_json);
+        assert_eq!(warnings, json_warnings);
+
+        let found: Vec<_> = warnings.iter().map(|w| (w.path.as_str(), w.field.as_str())).collect();
+        assert_eq!(
+            found,
+            [
+                ("", "theme"),
+                ("root.widget.children.0.widget.attrs", "line_height"),
+                ("root.widget.children.1", "tooltip"),
+                ("root.widget.children.1.widget.attrs", "style"),
+            ]
+        );
+        assert_eq!(warnings[0].to_string(), "Unknown field `theme` in the document");
+
+        let (_, clean) = load_layout_with_warnings(&fixture("layout_v1.ron")).unwrap();
+        assert!(clean.is_empty());
+    }
+
     #[test]
     fn test_default_layout_path() {
         let dir = PathBuf::from("/home/user/project");
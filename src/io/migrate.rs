@@ -0,0 +1,619 @@
+//! Layout schema migrations.
+//!
+//! Layout files record the schema `version` they were saved with. Files
+//! older than [`LAYOUT_VERSION`] are read into a generic value and passed
+//! through each registered migration step before being deserialized, so
+//! renamed or restructured fields keep loading.
+//!
+//! Values use the JSON shape serde gives the model: structs are objects,
+//! enum variants with data are `{"Variant": ...}` and unit variants are
+//! strings. RON files are read into the same shape by [`ron_to_value`], so a
+//! migration is written once for both formats.
+//!
+//! To change the schema, bump [`LAYOUT_VERSION`], append a step to
+//! [`MIGRATIONS`] and add a fixture saved with the previous version.
+
+use crate::model::layout::LAYOUT_VERSION;
+use crate::model::LayoutNode;
+use serde_json::{Map, Number, Value};
+
+/// A migration step, upgrading a document value by one version.
+pub type Migration = fn(Value) -> Value;
+
+/// Registered migrations; `MIGRATIONS[n]` upgrades version `n + 1` to `n + 2`.
+pub const MIGRATIONS: &[Migration] = &[migrate_v1_to_v2];
+
+/// Upgrade a document value saved with `version` to [`LAYOUT_VERSION`].
+///
+/// Versions newer than [`LAYOUT_VERSION`] must be rejected by the caller.
+pub fn migrate(mut value: Value, version: u32) -> Value {
+    let first = version.max(1) as usize - 1;
+    for (step, migration) in MIGRATIONS.iter().enumerate().skip(first) {
+        tracing::info!(target: "iced_builder::io", from = step + 1, to = step + 2, "Migrating layout");
+        value = migration(value);
+    }
+    value["version"] = LAYOUT_VERSION.into();
+    value
+}
+
+/// Read a RON subtree saved without a schema version, such as a snippet or
+/// clipboard text.
+///
+/// Subtrees in the current schema are read directly. Anything else is put
+/// through every migration step, which therefore must leave data already in
+/// a newer shape alone. The original parse error is returned if that fails
+/// too.
+pub fn subtree_from_ron(source: &str) -> Result<LayoutNode, ron::error::SpannedError> {
+    ron::from_str(source).or_else(|error| {
+        let value = ron_to_value(source).map_err(|_| error.clone())?;
+        let value = MIGRATIONS.iter().fold(value, |value, migration| migration(value));
+        serde_json::from_value(value).map_err(|_| error)
+    })
+}
+
+/// Version 2 renamed `TextAttrs.horizontal_alignment` to `align_x`, matching
+/// `ContainerAttrs` and iced's own naming.
+fn migrate_v1_to_v2(mut value: Value) -> Value {
+    for_each_widget(&mut value, &mut |kind, data| {
+        if kind != "Text" {
+            return;
+        }
+        if let Some(attrs) = data.get_mut("attrs").and_then(Value::as_object_mut) {
+            if let Some(alignment) = attrs.remove("horizontal_alignment") {
+                attrs.insert("align_x".to_string(), alignment);
+            }
+        }
+    });
+    value
+}
+
+/// Call `f` with the variant name and data of every widget under `value`.
+fn for_each_widget(value: &mut Value, f: &mut dyn FnMut(&str, &mut Value)) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::Object(widget)) = map.get_mut("widget") {
+                for (kind, data) in widget.iter_mut() {
+                    f(kind, data);
+                }
+            }
+            for child in map.values_mut() {
+                for_each_widget(child, f);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                for_each_widget(item, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Structs of the layout schema.
+///
+/// RON writes structs and enum variants alike as `Name(...)`, so the reader
+/// needs to know which names are structs. Every struct reachable from
+/// `LayoutDocument` must be listed here.
+const STRUCT_NAMES: &[&str] = &[
+    "LayoutDocument",
+    "LayoutNode",
+    "ComponentId",
+    "PaddingSpec",
+    "ContainerAttrs",
+    "TextAttrs",
+    "ButtonAttrs",
+    "InputAttrs",
+    "CheckboxAttrs",
+    "SliderAttrs",
+    "PickListAttrs",
+];
+
+/// Read a RON layout into the value shape migrations work on.
+pub fn ron_to_value(source: &str) -> Result<Value, String> {
+    let mut reader = Reader { source, pos: 0 };
+    reader.skip_attributes()?;
+    let value = reader.value()?;
+    reader.skip_whitespace()?;
+    if reader.pos < source.len() {
+        return Err(reader.error("unexpected trailing characters"));
+    }
+    Ok(value)
+}
+
+/// The contents of a parenthesised group.
+enum Group {
+    Fields(Map<String, Value>),
+    Tuple(Vec<Value>),
+}
+
+/// A minimal RON reader that keeps struct and variant names.
+struct Reader<'a> {
+    source: &'a str,
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn rest(&self) -> &'a str {
+        &self.source[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn error(&self, message: &str) -> String {
+        let before = &self.source[..self.pos];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+        format!("{}:{}: {}", line, column, message)
+    }
+
+    /// Skip whitespace and comments, including nested block comments.
+    fn skip_whitespace(&mut self) -> Result<(), String> {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                let mut depth = 0;
+                loop {
+                    let rest = self.rest();
+                    if rest.starts_with("/*") {
+                        depth += 1;
+                        self.pos += 2;
+                    } else if rest.starts_with("*/") {
+                        depth -= 1;
+                        self.pos += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    } else if self.bump().is_none() {
+                        return Err(self.error("unterminated comment"));
+                    }
+                }
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Skip `#![enable(...)]` extension attributes at the top of a file.
+    fn skip_attributes(&mut self) -> Result<(), String> {
+        self.skip_whitespace()?;
+        while self.rest().starts_with("#!") {
+            match self.rest().find(']') {
+                Some(end) => self.pos += end + 1,
+                None => return Err(self.error("unterminated attribute")),
+            }
+            self.skip_whitespace()?;
+        }
+        Ok(())
+    }
+
+    fn eat(&mut self, c: char) -> Result<bool, String> {
+        self.skip_whitespace()?;
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(c)? {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", c)))
+        }
+    }
+
+    fn identifier(&mut self) -> Option<&'a str> {
+        let rest = self.rest();
+        let start = if rest.starts_with("r#") { 2 } else { 0 };
+        let len = rest[start..]
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len() - start);
+        if len == 0 || rest[start..].starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        self.pos += start + len;
+        Some(&self.source[self.pos - len..self.pos])
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace()?;
+        let rest = self.rest();
+        match self.peek() {
+            Some('"') => self.string().map(Value::String),
+            Some('r') if rest[1..].trim_start_matches('#').starts_with('"') => {
+                self.raw_string().map(Value::String)
+            }
+            Some('\'') => self.character().map(Value::String),
+            Some('[') => {
+                self.bump();
+                let mut items = Vec::new();
+                while !self.eat(']')? {
+                    items.push(self.value()?);
+                    if !self.eat(',')? {
+                        self.expect(']')?;
+                        break;
+                    }
+                }
+                Ok(Value::Array(items))
+            }
+            Some('{') => {
+                self.bump();
+                let mut map = Map::new();
+                while !self.eat('}')? {
+                    let key = match self.value()? {
+                        Value::String(key) => key,
+                        other => other.to_string(),
+                    };
+                    self.expect(':')?;
+                    map.insert(key, self.value()?);
+                    if !self.eat(',')? {
+                        self.expect('}')?;
+                        break;
+                    }
+                }
+                Ok(Value::Object(map))
+            }
+            Some('(') => {
+                self.bump();
+                // Unnamed structs, and newtypes written without their name;
+                // the schema has no one-element tuples to confuse them with
+                Ok(match self.group()? {
+                    Group::Fields(map) if map.is_empty() => Value::Null,
+                    Group::Fields(map) => Value::Object(map),
+                    Group::Tuple(mut items) if items.len() == 1 => items.remove(0),
+                    Group::Tuple(items) => Value::Array(items),
+                })
+            }
+            Some(c) if c.is_ascii_digit() || matches!(c, '-' | '+' | '.') => self.number(),
+            Some(_) => self.named(),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    /// An identifier, optionally followed by a parenthesised group.
+    fn named(&mut self) -> Result<Value, String> {
+        let name = self
+            .identifier()
+            .ok_or_else(|| self.error("expected a value"))?
+            .to_string();
+        match name.as_str() {
+            "true" => return Ok(Value::Bool(true)),
+            "false" => return Ok(Value::Bool(false)),
+            "None" => return Ok(Value::Null),
+            "Some" => {
+                self.expect('(')?;
+                let value = self.value()?;
+                self.eat(',')?;
+                self.expect(')')?;
+                return Ok(value);
+            }
+            _ => {}
+        }
+        if !self.eat('(')? {
+            return Ok(Value::String(name));
+        }
+
+        // Newtypes and newtype variants are their inner value in serde's shape
+        let data = match self.group()? {
+            Group::Fields(map) => Value::Object(map),
+            Group::Tuple(mut items) if items.len() == 1 => items.remove(0),
+            Group::Tuple(items) => Value::Array(items),
+        };
+        if STRUCT_NAMES.contains(&name.as_str()) {
+            Ok(data)
+        } else {
+            Ok(Value::Object(Map::from_iter([(name, data)])))
+        }
+    }
+
+    /// The contents of a group whose `(` was consumed, up to its `)`.
+    fn group(&mut self) -> Result<Group, String> {
+        self.skip_whitespace()?;
+        let start = self.pos;
+        let is_fields = self.identifier().is_some() && {
+            self.skip_whitespace()?;
+            self.rest().starts_with(':') && !self.rest().starts_with("::")
+        };
+        self.pos = start;
+
+        if is_fields {
+            let mut map = Map::new();
+            while !self.eat(')')? {
+                let field = self
+                    .identifier()
+                    .ok_or_else(|| self.error("expected a field name"))?
+                    .to_string();
+                self.expect(':')?;
+                map.insert(field, self.value()?);
+                if !self.eat(',')? {
+                    self.expect(')')?;
+                    break;
+                }
+            }
+            Ok(Group::Fields(map))
+        } else {
+            let mut items = Vec::new();
+            while !self.eat(')')? {
+                items.push(self.value()?);
+                if !self.eat(',')? {
+                    self.expect(')')?;
+                    break;
+                }
+            }
+            if items.is_empty() {
+                Ok(Group::Fields(Map::new()))
+            } else {
+                Ok(Group::Tuple(items))
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, String> {
+        let rest = self.rest();
+        let len = rest
+            .char_indices()
+            .find(|&(i, c)| {
+                !(c.is_ascii_digit()
+                    || matches!(c, '.' | '_' | 'e' | 'E')
+                    || (matches!(c, '-' | '+') && (i == 0 || rest[..i].ends_with(['e', 'E']))))
+            })
+            .map_or(rest.len(), |(i, _)| i);
+        let text = rest[..len].replace('_', "");
+        self.pos += len;
+
+        let number = if text.contains(['.', 'e', 'E']) {
+            text.parse::<f64>().ok().and_then(Number::from_f64)
+        } else if let Ok(n) = text.parse::<i64>() {
+            Some(n.into())
+        } else {
+            text.parse::<u64>().ok().map(Number::from)
+        };
+        number
+            .map(Value::Number)
+            .ok_or_else(|| self.error(&format!("invalid number `{}`", text)))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.bump();
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(out),
+                Some('\\') => out.push(self.escape()?),
+                Some(c) => out.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn raw_string(&mut self) -> Result<String, String> {
+        self.bump();
+        let hashes = self.rest().len() - self.rest().trim_start_matches('#').len();
+        self.pos += hashes;
+        self.expect('"')?;
+        let terminator = format!("\"{}", "#".repeat(hashes));
+        let end = self
+            .rest()
+            .find(&terminator)
+            .ok_or_else(|| self.error("unterminated raw string"))?;
+        let out = self.rest()[..end].to_string();
+        self.pos += end + terminator.len();
+        Ok(out)
+    }
+
+    fn character(&mut self) -> Result<String, String> {
+        self.bump();
+        let c = match self.bump() {
+            Some('\\') => self.escape()?,
+            Some(c) => c,
+            None => return Err(self.error("unterminated character")),
+        };
+        if self.bump() != Some('\'') {
+            return Err(self.error("unterminated character"));
+        }
+        Ok(c.to_string())
+    }
+
+    /// The character of an escape sequence whose `\` was consumed.
+    fn escape(&mut self) -> Result<char, String> {
+        let c = self.bump().ok_or_else(|| self.error("unterminated escape"))?;
+        let hex = |reader: &mut Self, digits: &str| {
+            u32::from_str_radix(digits, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or_else(|| reader.error("invalid escape"))
+        };
+        match c {
+            'n' => Ok('\n'),
+            'r' => Ok('\r'),
+            't' => Ok('\t'),
+            '0' => Ok('\0'),
+            'b' => Ok('\u{8}'),
+            'f' => Ok('\u{c}'),
+            '\\' | '"' | '\'' | '/' => Ok(c),
+            'x' => {
+                let digits = self.rest().get(..2).unwrap_or_default().to_string();
+                self.pos += digits.len();
+                hex(self, &digits)
+            }
+            'u' if self.peek() == Some('{') => {
+                let end = self.rest().find('}').ok_or_else(|| self.error("invalid escape"))?;
+                let digits = self.rest()[1..end].to_string();
+                self.pos += end + 1;
+                hex(self, &digits)
+            }
+            'u' => {
+                let digits = self.rest().get(..4).unwrap_or_default().to_string();
+                self.pos += digits.len();
+                hex(self, &digits)
+            }
+            _ => Err(self.error("invalid escape")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::layout::*;
+    use crate::model::{LayoutDocument, LayoutNode};
+
+    /// A document using every widget type, with optional fields set.
+    fn every_widget() -> LayoutDocument {
+        let mut title = LayoutNode::new(WidgetType::Text {
+            content: "Say \"hi\"\n\tthen ✓ /* not a comment */".to_string(),
+            attrs: TextAttrs {
+                color: Some([1.0, 0.5, 0.0, 1.0]),
+                align_x: AlignmentSpec::Center,
+                ..Default::default()
+            },
+        });
+        title.name = Some("Title".to_string());
+        title.hidden = true;
+        let mut scratch = LayoutNode::new(WidgetType::Space {
+            width: LengthSpec::FillPortion(3),
+            height: LengthSpec::Fixed(12.5),
+        });
+        scratch.exclude_from_export = true;
+        let leaves = vec![
+            title,
+            scratch,
+            LayoutNode::new(WidgetType::Button {
+                label: "Go".to_string(),
+                message_stub: "Go".to_string(),
+                attrs: ButtonAttrs {
+                    width: LengthSpec::Fill,
+                    ..Default::default()
+                },
+            }),
+            LayoutNode::new(WidgetType::TextInput {
+                placeholder: "Name".to_string(),
+                value_binding: "name".to_string(),
+                message_stub: "NameChanged".to_string(),
+                attrs: InputAttrs::default(),
+            }),
+            LayoutNode::new(WidgetType::Checkbox {
+                label: "Agree".to_string(),
+                checked_binding: "agreed".to_string(),
+                message_stub: "AgreeToggled".to_string(),
+                attrs: CheckboxAttrs { spacing: 4.0 },
+            }),
+            LayoutNode::new(WidgetType::Slider {
+                min: -1.0,
+                max: 1e3,
+                value_binding: "volume".to_string(),
+                message_stub: "VolumeChanged".to_string(),
+                attrs: SliderAttrs::default(),
+            }),
+            LayoutNode::new(WidgetType::PickList {
+                options: vec!["One".to_string(), "Two, three".to_string()],
+                selected_binding: "choice".to_string(),
+                message_stub: "Chosen".to_string(),
+                attrs: PickListAttrs {
+                    placeholder: "Pick".to_string(),
+                    ..Default::default()
+                },
+            }),
+            LayoutNode::new(WidgetType::ComponentRef {
+                file: "components/header.ron".into(),
+            }),
+        ];
+        let stack = LayoutNode::new(WidgetType::Stack {
+            children: leaves,
+            attrs: ContainerAttrs::default(),
+        });
+        let container = LayoutNode::new(WidgetType::Container {
+            child: Some(Box::new(stack)),
+            attrs: ContainerAttrs {
+                padding: PaddingSpec::uniform(8.0),
+                ..Default::default()
+            },
+        });
+        let scrollable = LayoutNode::new(WidgetType::Scrollable {
+            child: None,
+            attrs: ContainerAttrs::default(),
+        });
+        let row = LayoutNode::new(WidgetType::Row {
+            children: vec![container, scrollable],
+            attrs: ContainerAttrs::default(),
+        });
+        let mut doc = LayoutDocument::default();
+        *doc.root.children_mut().unwrap() = vec![row];
+        doc
+    }
+
+    #[test]
+    fn test_migrations_reach_current_version() {
+        assert_eq!(MIGRATIONS.len() as u32, LAYOUT_VERSION - 1);
+    }
+
+    #[test]
+    fn test_ron_reader_matches_json_shape() {
+        let doc = every_widget();
+        let pretty = ron::ser::PrettyConfig::default()
+            .struct_names(true)
+            .enumerate_arrays(true);
+        let ron = ron::ser::to_string_pretty(&doc, pretty).unwrap();
+
+        let value = ron_to_value(&ron).unwrap();
+        assert_eq!(value, serde_json::to_value(&doc).unwrap());
+        assert_eq!(serde_json::from_value::<LayoutDocument>(value).unwrap(), doc);
+        // Compact output without struct names reads the same
+        assert_eq!(ron_to_value(&ron::to_string(&doc).unwrap()).unwrap(), serde_json::to_value(&doc).unwrap());
+    }
+
+    #[test]
+    fn test_ron_reader_literals() {
+        let value = ron_to_value("#![enable(implicit_some)]\n(a: r#\"raw \"text\"\"#, b: '\\u{41}', /* x /* y */ */ c: [1, -2, 3.5e1,], d: {\"k\": Some(true)}, e: ())").unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"a": "raw \"text\"", "b": "A", "c": [1, -2, 35.0], "d": {"k": true}, "e": null})
+        );
+        assert!(ron_to_value("(a: \"open)").unwrap_err().contains("unterminated string"));
+        assert!(ron_to_value("(a: 1,\n b 2)").unwrap_err().starts_with("2:4:"));
+    }
+
+    #[test]
+    fn test_v1_to_v2_renames_text_alignment_only() {
+        let v1 = serde_json::json!({
+            "version": 1,
+            "root": {"widget": {"Column": {"children": [
+                {"widget": {"Text": {"content": "", "attrs": {"horizontal_alignment": "End"}}}}
+            ], "attrs": {"align_x": "Center"}}}}
+        });
+        let v2 = migrate(v1, 1);
+        assert_eq!(v2["version"], LAYOUT_VERSION);
+        let column = &v2["root"]["widget"]["Column"];
+        assert_eq!(column["attrs"]["align_x"], "Center");
+        let text = &column["children"][0]["widget"]["Text"]["attrs"];
+        assert_eq!(text["align_x"], "End");
+        assert!(text.get("horizontal_alignment").is_none());
+    }
+
+    #[test]
+    fn test_unversioned_v1_subtree_is_migrated() {
+        let v1 = "LayoutNode(id: ComponentId(\"6f1c2a3e-0b7d-4f5a-9c1e-2d3b4a5c6d7e\"), \
+                  widget: Text(content: \"Hi\", attrs: TextAttrs(font_size: 16.0, color: None, horizontal_alignment: End)))";
+        assert!(ron::from_str::<LayoutNode>(v1).is_err());
+        let node = subtree_from_ron(v1).unwrap();
+        assert!(matches!(&node.widget, WidgetType::Text { attrs, .. } if attrs.align_x == AlignmentSpec::End));
+
+        let current = LayoutNode::new(WidgetType::Text { content: "Hi".to_string(), attrs: TextAttrs::default() });
+        assert_eq!(subtree_from_ron(&ron::to_string(&current).unwrap()).unwrap(), current);
+        assert!(subtree_from_ron("LayoutNode(").is_err());
+    }
+}
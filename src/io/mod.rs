@@ -2,17 +2,32 @@
 //!
 //! Handles loading and saving layout files and project configuration.
 
+pub mod app_settings;
+pub mod autosave;
+pub mod backups;
+pub mod bundle;
+pub mod clipboard;
 pub mod config;
+pub mod copy;
+pub mod crash_recovery;
+pub mod history_file;
 pub mod layout_file;
+pub mod migrate;
+pub mod recent;
+pub mod snippets;
 
 // Re-exports for convenience
-#[allow(unused_imports)]
+pub use app_settings::{AppSettings, CanvasSize, SettingsError, ThemeChoice, TreeDock, WindowSize};
+pub use backups::{backups_for, DEFAULT_BACKUP_COUNT};
+pub use bundle::{BundleError, BUNDLE_EXTENSION};
+pub use clipboard::{decode_subtree, encode_subtree};
 pub use config::{
     config_path, find_config, is_valid_project, load_config, save_config, ConfigError,
     CONFIG_FILENAME,
 };
-#[allow(unused_imports)]
 pub use layout_file::{
-    default_layout_path, find_layout_files, load_layout, save_layout, LayoutFileError,
-    LayoutFormat,
+    default_layout_path, find_layout_files, load_layout, load_layout_with_warnings, save_layout,
+    LayoutFileError, LayoutFormat, LoadWarning,
 };
+pub use recent::{RecentProject, RecentProjects, MAX_RECENT_PROJECTS};
+pub use snippets::{delete_snippet, list_snippets, load_snippet, rename_snippet, save_snippet, Snippet};
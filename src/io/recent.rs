@@ -0,0 +1,175 @@
+//! Recently opened projects.
+//!
+//! Kept in `recent.toml` next to the editor settings, newest first and
+//! capped at [`MAX_RECENT_PROJECTS`]. Projects that no longer exist are
+//! dropped when the list is loaded or when one fails to open.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::app_settings::{settings_path, SettingsError};
+use super::config::is_valid_project;
+
+/// The recent projects file name inside the config directory.
+pub const RECENT_FILENAME: &str = "recent.toml";
+
+/// Maximum number of projects remembered.
+pub const MAX_RECENT_PROJECTS: usize = 10;
+
+/// A project opened before.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentProject {
+    /// The project directory.
+    pub path: PathBuf,
+    /// Name of the project's layout when it was last opened.
+    pub name: String,
+    /// When the project was last opened, in seconds since the Unix epoch.
+    pub opened: u64,
+}
+
+impl RecentProject {
+    /// Describe how long ago the project was opened, e.g. "3 days ago".
+    pub fn opened_ago(&self) -> String {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        let secs = now.saturating_sub(self.opened);
+        let (count, unit) = match secs {
+            0..=59 => return "just now".to_string(),
+            60..=3_599 => (secs / 60, "minute"),
+            3_600..=86_399 => (secs / 3_600, "hour"),
+            _ => (secs / 86_400, "day"),
+        };
+        format!("{} {}{} ago", count, unit, if count == 1 { "" } else { "s" })
+    }
+}
+
+/// Recently opened projects, newest first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecentProjects {
+    /// The projects, newest first.
+    #[serde(default)]
+    pub projects: Vec<RecentProject>,
+}
+
+/// Get the recent projects file path in the config directory.
+pub fn recent_path() -> Option<PathBuf> {
+    settings_path().map(|path| path.with_file_name(RECENT_FILENAME))
+}
+
+impl RecentProjects {
+    /// Load the list from a file, empty if it does not exist.
+    pub fn load(path: &Path) -> Result<Self, SettingsError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    /// Save the list to a file, creating parent directories as needed.
+    pub fn save(&self, path: &Path) -> Result<(), SettingsError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Load the list from the config directory without projects
+    /// that are gone, or an empty list on any error.
+    pub fn load_or_default() -> Self {
+        let Some(path) = recent_path() else {
+            return Self::default();
+        };
+        let mut recent = Self::load(&path).unwrap_or_else(|e| {
+            tracing::warn!(target: "iced_builder::io", error = %e, "Failed to load recent projects");
+            Self::default()
+        });
+        if recent.prune_missing() {
+            recent.save_or_log();
+        }
+        recent
+    }
+
+    /// Save the list to the config directory, logging failures.
+    pub fn save_or_log(&self) {
+        let result = recent_path().ok_or(SettingsError::NoConfigDir).and_then(|path| self.save(&path));
+        if let Err(e) = result {
+            tracing::warn!(target: "iced_builder::io", error = %e, "Failed to save recent projects");
+        }
+    }
+
+    /// Move a project to the front of the list, as opened now.
+    pub fn record(&mut self, path: &Path, name: &str) {
+        let opened = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        self.projects.retain(|p| p.path != path);
+        self.projects.insert(
+            0,
+            RecentProject {
+                path: path.to_path_buf(),
+                name: name.to_string(),
+                opened,
+            },
+        );
+        self.projects.truncate(MAX_RECENT_PROJECTS);
+    }
+
+    /// Forget a project, returning whether it was listed.
+    pub fn remove(&mut self, path: &Path) -> bool {
+        let before = self.projects.len();
+        self.projects.retain(|p| p.path != path);
+        self.projects.len() != before
+    }
+
+    /// Drop projects whose directory no longer holds a project, returning
+    /// whether any were dropped.
+    pub fn prune_missing(&mut self) -> bool {
+        let before = self.projects.len();
+        self.projects.retain(|p| is_valid_project(&p.path));
+        self.projects.len() != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_record_moves_to_front_and_caps() {
+        let mut recent = RecentProjects::default();
+        for i in 0..12 {
+            recent.record(Path::new(&format!("/p{}", i)), "Layout");
+        }
+        recent.record(Path::new("/p5"), "Renamed");
+
+        assert_eq!(recent.projects.len(), MAX_RECENT_PROJECTS);
+        assert_eq!(recent.projects[0].path, Path::new("/p5"));
+        assert_eq!(recent.projects[0].name, "Renamed");
+        assert_eq!(recent.projects[1].path, Path::new("/p11"));
+        assert!(!recent.projects.iter().any(|p| p.path == Path::new("/p1")));
+        assert_eq!(recent.projects.iter().filter(|p| p.path == Path::new("/p5")).count(), 1);
+    }
+
+    #[test]
+    fn test_round_trip_and_prune() {
+        let temp = tempdir().unwrap();
+        let project = temp.path().join("project");
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(project.join(super::super::CONFIG_FILENAME), "").unwrap();
+
+        let mut recent = RecentProjects::default();
+        recent.record(&temp.path().join("gone"), "Gone");
+        recent.record(&project, "Kept");
+        let path = temp.path().join("config").join(RECENT_FILENAME);
+        recent.save(&path).unwrap();
+        let mut loaded = RecentProjects::load(&path).unwrap();
+        assert_eq!(loaded, recent);
+
+        assert!(loaded.prune_missing());
+        assert_eq!(loaded.projects.len(), 1);
+        assert_eq!(loaded.projects[0].name, "Kept");
+        assert!(loaded.remove(&project));
+        assert!(!loaded.remove(&project));
+    }
+}
@@ -0,0 +1,227 @@
+//! Reusable component snippets.
+//!
+//! A snippet is a saved subtree stored as a RON file in the project's
+//! `snippets/` directory. Loading a snippet gives every node a fresh
+//! ComponentId so it can be inserted any number of times.
+
+use crate::model::LayoutNode;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// The snippets directory inside a project.
+pub const SNIPPETS_DIR: &str = "snippets";
+
+/// Errors that can occur when managing snippets.
+#[derive(Debug, Error)]
+pub enum SnippetError {
+    /// Reading or writing the file failed.
+    #[error("Failed to access snippet: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The file is not a valid snippet.
+    #[error("Failed to parse snippet: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+
+    /// The snippet could not be written as RON.
+    #[error("Failed to serialize snippet: {0}")]
+    Serialize(#[from] ron::Error),
+
+    /// The name is empty or can't be used as a file name.
+    #[error("Invalid snippet name: {0:?}")]
+    InvalidName(String),
+
+    /// Another snippet already has the name.
+    #[error("A snippet named {0:?} already exists")]
+    AlreadyExists(String),
+}
+
+/// A snippet file in the project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    /// Display name, taken from the file stem.
+    pub name: String,
+    /// Path to the snippet file.
+    pub path: PathBuf,
+}
+
+/// Get the snippets directory for a project.
+pub fn snippets_dir(project_dir: &Path) -> PathBuf {
+    project_dir.join(SNIPPETS_DIR)
+}
+
+/// Check that a name can be used as a snippet file name.
+fn validate_name(name: &str) -> Result<(), SnippetError> {
+    let valid = !name.trim().is_empty()
+        && name.trim() == name
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '_' | '-'));
+    if valid {
+        Ok(())
+    } else {
+        Err(SnippetError::InvalidName(name.to_string()))
+    }
+}
+
+/// List the snippets in a project, sorted by name.
+pub fn list_snippets(project_dir: &Path) -> Vec<Snippet> {
+    let mut snippets: Vec<Snippet> = std::fs::read_dir(snippets_dir(project_dir))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|e| e == "ron"))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_str()?.to_string();
+            Some(Snippet { name, path })
+        })
+        .collect();
+    snippets.sort_by_key(|s| s.name.to_lowercase());
+    snippets
+}
+
+/// Save a subtree as a new snippet.
+pub fn save_snippet(project_dir: &Path, name: &str, node: &LayoutNode) -> Result<Snippet, SnippetError> {
+    validate_name(name)?;
+
+    let path = snippets_dir(project_dir).join(format!("{name}.ron"));
+    if path.exists() {
+        return Err(SnippetError::AlreadyExists(name.to_string()));
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let pretty = ron::ser::PrettyConfig::default().struct_names(true);
+    std::fs::write(&path, ron::ser::to_string_pretty(node, pretty)?)?;
+    tracing::info!(target: "iced_builder::io", path = %path.display(), "Snippet saved");
+
+    Ok(Snippet {
+        name: name.to_string(),
+        path,
+    })
+}
+
+/// Load a snippet, giving every node a new ComponentId.
+pub fn load_snippet(path: &Path) -> Result<LayoutNode, SnippetError> {
+    let content = std::fs::read_to_string(path)?;
+    let mut node = super::migrate::subtree_from_ron(&content)?;
+    node.regenerate_ids();
+    tracing::debug!(target: "iced_builder::io", path = %path.display(), "Snippet loaded");
+    Ok(node)
+}
+
+/// Rename a snippet, keeping it in the same directory.
+pub fn rename_snippet(snippet: &Snippet, new_name: &str) -> Result<Snippet, SnippetError> {
+    validate_name(new_name)?;
+
+    let path = snippet.path.with_file_name(format!("{new_name}.ron"));
+    let taken = if new_name.to_lowercase() == snippet.name.to_lowercase() {
+        // On case-insensitive filesystems the snippet itself answers to the
+        // new name, so only another file spelled exactly that way conflicts
+        let file_name = path.file_name().unwrap_or_default();
+        std::fs::read_dir(snippet.path.parent().unwrap_or(Path::new(".")))?
+            .flatten()
+            .any(|entry| entry.file_name() == file_name && entry.path() != snippet.path)
+    } else {
+        path.exists()
+    };
+    if taken {
+        return Err(SnippetError::AlreadyExists(new_name.to_string()));
+    }
+
+    std::fs::rename(&snippet.path, &path)?;
+    tracing::info!(target: "iced_builder::io", from = %snippet.path.display(), to = %path.display(), "Snippet renamed");
+
+    Ok(Snippet {
+        name: new_name.to_string(),
+        path,
+    })
+}
+
+/// Delete a snippet file.
+pub fn delete_snippet(snippet: &Snippet) -> Result<(), SnippetError> {
+    std::fs::remove_file(&snippet.path)?;
+    tracing::info!(target: "iced_builder::io", path = %snippet.path.display(), "Snippet deleted");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ComponentId;
+    use crate::test_support::sample_subtree;
+    use tempfile::tempdir;
+
+    fn collect_ids(node: &LayoutNode, ids: &mut Vec<ComponentId>) {
+        ids.push(node.id);
+        if let Some(children) = node.children() {
+            for child in children {
+                collect_ids(child, ids);
+            }
+        }
+    }
+
+    #[test]
+    fn test_snippet_load_regenerates_ids() {
+        let temp = tempdir().unwrap();
+        let original = sample_subtree();
+        let snippet = save_snippet(temp.path(), "Header Row", &original).unwrap();
+
+        let first = load_snippet(&snippet.path).unwrap();
+        let second = load_snippet(&snippet.path).unwrap();
+
+        let mut ids = Vec::new();
+        collect_ids(&original, &mut ids);
+        collect_ids(&first, &mut ids);
+        collect_ids(&second, &mut ids);
+        let unique: std::collections::HashSet<_> = ids.iter().collect();
+        assert_eq!(ids.len(), 6);
+        assert_eq!(unique.len(), ids.len());
+
+        // Only the IDs change
+        assert_eq!(first.children().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_snippet_list_rename_and_delete() {
+        let temp = tempdir().unwrap();
+        assert!(list_snippets(temp.path()).is_empty());
+
+        let snippet = save_snippet(temp.path(), "card", &sample_subtree()).unwrap();
+        save_snippet(temp.path(), "Banner", &sample_subtree()).unwrap();
+        let names: Vec<_> = list_snippets(temp.path()).into_iter().map(|s| s.name).collect();
+        assert_eq!(names, ["Banner", "card"]);
+
+        assert!(matches!(
+            rename_snippet(&snippet, "Banner"),
+            Err(SnippetError::AlreadyExists(_))
+        ));
+        let renamed = rename_snippet(&snippet, "Card").unwrap();
+        assert!(renamed.path.exists());
+        assert_eq!(rename_snippet(&renamed, "Card").unwrap(), renamed);
+
+        // A different file that differs only in case still conflicts
+        let lower = save_snippet(temp.path(), "banner", &sample_subtree());
+        if let Ok(lower) = lower {
+            assert!(matches!(rename_snippet(&lower, "Banner"), Err(SnippetError::AlreadyExists(_))));
+            delete_snippet(&lower).unwrap();
+        }
+
+        delete_snippet(&renamed).unwrap();
+        let names: Vec<_> = list_snippets(temp.path()).into_iter().map(|s| s.name).collect();
+        assert_eq!(names, ["Banner"]);
+    }
+
+    #[test]
+    fn test_snippet_rejects_invalid_names() {
+        let temp = tempdir().unwrap();
+        for name in ["", " padded", "../escape", "a/b"] {
+            assert!(matches!(
+                save_snippet(temp.path(), name, &sample_subtree()),
+                Err(SnippetError::InvalidName(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Iced Builder's layout model and code generator, for use without the
+//! editor.
+//!
+//! The editor binary is built on this library. Depending on it with
+//! `default-features = false` leaves out the `gui` feature and with it
+//! iced and the file dialogs, so a build script can regenerate a project's
+//! view code on every `cargo build`:
+//!
+//! ```no_run
+//! // build.rs
+//! use std::path::Path;
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     println!("cargo::rerun-if-changed=ui");
+//!     let project = iced_builder::model::Project::open(Path::new("ui"))?;
+//!     project.export()?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The pieces can also be used on their own: [`io`] reads and writes
+//! layout and config files, [`model`] holds the layout tree, and
+//! [`codegen`] turns a layout into Rust source.
+//!
+//! ```
+//! use iced_builder::codegen::generate_code;
+//! use iced_builder::io::{load_layout, save_layout};
+//! use iced_builder::model::{LayoutDocument, ProjectConfig};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # let dir = std::env::temp_dir().join("iced-builder-doc-lib");
+//! # std::fs::create_dir_all(&dir)?;
+//! let path = dir.join("layout.ron");
+//! save_layout(&path, &LayoutDocument::default())?;
+//!
+//! let layout = load_layout(&path)?;
+//! let code = generate_code(&layout, &ProjectConfig::default());
+//! assert!(code.contains("pub fn view"));
+//! # Ok(())
+//! # }
+//! ```
+
+#![warn(missing_docs)]
+
+pub mod codegen;
+pub mod io;
+pub mod model;
+pub mod util;
+
+#[cfg(test)]
+mod test_support;
@@ -53,6 +53,23 @@ pub fn init() {
     tracing::info!("Iced Builder logging initialized");
 }
 
+/// Initialize logging for command-line subcommands.
+///
+/// Logs go to stderr so stdout carries only command output, and default to
+/// `warn`; `ICED_BUILDER_LOG` still overrides the level.
+pub fn init_cli() {
+    let filter = EnvFilter::try_from_env("ICED_BUILDER_LOG")
+        .unwrap_or_else(|_| EnvFilter::new("warn"));
+
+    tracing_subscriber::registry()
+        .with(fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_target(true)
+            .compact())
+        .with(filter)
+        .init();
+}
+
 /// Log categories for different subsystems.
 /// 
 /// These are used as targets for filtering log output.
@@ -63,6 +80,9 @@ pub mod targets {
     /// Message handling and state updates
     pub const MESSAGE: &str = "iced_builder::app::message";
     
+    /// Property updates applied to layout nodes
+    pub const PROPERTY: &str = "iced_builder::app::property";
+    
     /// Selection and interaction events
     pub const SELECTION: &str = "iced_builder::app::selection";
     
@@ -80,9 +100,6 @@ pub mod targets {
     
     /// Inspector property changes
     pub const INSPECTOR: &str = "iced_builder::ui::inspector";
-    
-    /// Palette interactions
-    pub const PALETTE: &str = "iced_builder::ui::palette";
 }
 
 /// Convenience macros for logging with predefined targets.
@@ -1,29 +1,65 @@
 //! Iced Builder - A visual GUI builder for Iced applications.
 //!
 //! This application allows developers to visually design and modify
-//! Iced UI layouts and export them as Rust code.
+//! Iced UI layouts and export them as Rust code. Run without arguments to
+//! open the editor; see [`cli`] for the headless subcommands. Layouts and
+//! code generation come from the `iced_builder` library.
 
+// No console window with the editor; subcommands reattach to the terminal
 #![windows_subsystem = "windows"]
 
 mod app;
-mod codegen;
-mod io;
+mod cli;
 mod logging;
-mod model;
 mod ui;
-mod util;
+
+// The data and codegen layer lives in the library
+use iced_builder::{codegen, io, model, util};
+
+use std::process::ExitCode;
 
 use app::App;
-use iced::Size;
+use clap::Parser;
+use iced::{Size, Task};
+use io::crash_recovery::{self, CrashHandle};
+use io::AppSettings;
+
+fn main() -> ExitCode {
+    if std::env::args_os().len() > 1 {
+        // Before parsing, so usage errors reach the terminal too
+        cli::attach_console();
+    }
+    let args = cli::Cli::parse();
+    if let Some(command) = args.command {
+        logging::init_cli();
+        return cli::run(command);
+    }
 
-fn main() -> iced::Result {
     // Initialize logging system first
     logging::init();
 
     tracing::info!("Starting Iced Builder");
 
-    iced::application(App::title, App::update, App::view)
+    // Loaded up front so the window opens at its last size
+    let settings = AppSettings::load_or_default();
+    let window_size = Size::new(settings.window_size.width, settings.window_size.height);
+
+    // Installed before iced starts so a panic anywhere saves unsaved work
+    let crash_handle = CrashHandle::default();
+    crash_recovery::install_panic_hook(crash_handle.clone());
+
+    let result = iced::application(App::title, App::update, App::view)
         .subscription(App::subscription)
-        .window_size(Size::new(1280.0, 800.0))
-        .run()
+        .theme(App::theme)
+        .window_size(window_size)
+        // Settings are saved before the window closes
+        .exit_on_close_request(false)
+        .run_with(move || (App::with_settings(settings).with_crash_handle(crash_handle), Task::none()));
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            tracing::error!(error = %e, "Iced Builder failed to start");
+            ExitCode::FAILURE
+        }
+    }
 }
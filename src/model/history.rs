@@ -1,67 +1,372 @@
 //! Undo/redo history management using snapshot-based approach.
 //!
-//! Stores complete layout snapshots for simple and reliable undo/redo.
+//! Callers hand in complete layout snapshots, but only the top of each stack
+//! is kept whole. When a newer snapshot lands on top, the one below is
+//! reduced to a delta: the smallest subtree that differs from its newer
+//! neighbour, plus the path to it. Popping the top rebuilds the next entry
+//! from the popped state, so memory grows with the size of the edits rather
+//! than with layout size times history length.
+//!
+//! Edits to a single node skip the snapshot altogether: [`History::push_node`]
+//! stores just that node as a delta against the current layout, which the
+//! caller keeps. Such a delta only ever sits on top of the undo stack and
+//! becomes an ordinary one as soon as anything is pushed above it.
+//!
+//! Each snapshot carries a short label naming the action that replaced it,
+//! such as "Delete Button", so the UI can say what undo and redo will do.
+
+use crate::model::layout::WidgetType;
+use crate::model::{LayoutDocument, LayoutNode};
+use serde::{Deserialize, Serialize};
+
+/// Default number of steps kept in each direction.
+pub const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+/// A stored state: whole on top of a stack, a delta everywhere else.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum Stored {
+    /// A complete document.
+    Full(LayoutDocument),
+    /// Differences from the entry directly above in the same stack, or
+    /// from the current layout when on top of the undo stack.
+    Delta(Delta),
+}
+
+/// What it takes to rebuild a document from its newer neighbour.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Delta {
+    version: u32,
+    name: String,
+    /// The subtree to put back and its path, or None if the trees match.
+    subtree: Option<(Vec<usize>, LayoutNode)>,
+}
+
+impl Delta {
+    /// Describe `old` relative to `newer`.
+    fn between(old: &LayoutDocument, newer: &LayoutDocument) -> Self {
+        let subtree = changed_path(&old.root, &newer.root).map(|path| {
+            let node = old.root.descendant_at(&path).expect("path taken from this tree");
+            (path, node.clone())
+        });
+        Self {
+            version: old.version,
+            name: old.name.clone(),
+            subtree,
+        }
+    }
+
+    /// Rebuild the old document from its newer neighbour.
+    fn apply(&self, newer: &LayoutDocument) -> LayoutDocument {
+        let mut doc = newer.clone();
+        doc.version = self.version;
+        doc.name = self.name.clone();
+        if let Some((path, node)) = &self.subtree {
+            if let Some(slot) = doc.root.descendant_at_mut(path) {
+                *slot = node.clone();
+            }
+        }
+        doc
+    }
+}
+
+/// A stored state paired with the label of the action it undoes or redoes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    state: Stored,
+    label: String,
+}
+
+/// Push a complete snapshot, reducing the previous top to a delta against it.
+fn push_entry(stack: &mut Vec<Entry>, snapshot: LayoutDocument, label: String) {
+    if let Some(top) = stack.last_mut() {
+        if let Stored::Full(previous) = &top.state {
+            top.state = Stored::Delta(Delta::between(previous, &snapshot));
+        }
+    }
+    stack.push(Entry {
+        state: Stored::Full(snapshot),
+        label,
+    });
+}
 
-use crate::model::LayoutDocument;
+/// Pop the top snapshot, rebuilding the entry below so it becomes whole.
+/// A delta on top is relative to `current`.
+fn pop_entry(stack: &mut Vec<Entry>, current: &LayoutDocument) -> Option<(LayoutDocument, String)> {
+    let Entry { state, label } = stack.pop()?;
+    let snapshot = match state {
+        Stored::Full(snapshot) => snapshot,
+        Stored::Delta(delta) => delta.apply(current),
+    };
+    if let Some(next) = stack.last_mut() {
+        if let Stored::Delta(delta) = &next.state {
+            next.state = Stored::Full(delta.apply(&snapshot));
+        }
+    }
+    Some((snapshot, label))
+}
 
-/// Maximum number of states to keep in history.
-const MAX_HISTORY_SIZE: usize = 50;
+/// Whether two nodes match apart from the contents of their children.
+fn same_shell(a: &LayoutNode, b: &LayoutNode) -> bool {
+    if a.id != b.id || a.name != b.name || a.hidden != b.hidden || a.exclude_from_export != b.exclude_from_export {
+        return false;
+    }
+    match (&a.widget, &b.widget) {
+        (WidgetType::Column { children: x, attrs: p }, WidgetType::Column { children: y, attrs: q })
+        | (WidgetType::Row { children: x, attrs: p }, WidgetType::Row { children: y, attrs: q })
+        | (WidgetType::Stack { children: x, attrs: p }, WidgetType::Stack { children: y, attrs: q }) => {
+            p == q && x.len() == y.len()
+        }
+        (WidgetType::Container { child: x, attrs: p }, WidgetType::Container { child: y, attrs: q })
+        | (WidgetType::Scrollable { child: x, attrs: p }, WidgetType::Scrollable { child: y, attrs: q }) => {
+            p == q && x.is_some() == y.is_some()
+        }
+        (x, y) => x == y,
+    }
+}
+
+/// Path to the smallest subtree holding every difference between two
+/// trees, or None if they are equal.
+fn changed_path(old: &LayoutNode, newer: &LayoutNode) -> Option<Vec<usize>> {
+    if old == newer {
+        return None;
+    }
+
+    let mut path = Vec::new();
+    let (mut a, mut b) = (old, newer);
+    while same_shell(a, b) {
+        let (left, right) = (a.child_nodes(), b.child_nodes());
+        let mut differing = left
+            .into_iter()
+            .zip(right)
+            .enumerate()
+            .filter(|(_, (x, y))| x != y);
+        match (differing.next(), differing.next()) {
+            (Some((index, (x, y))), None) => {
+                path.push(index);
+                a = x;
+                b = y;
+            }
+            _ => break,
+        }
+    }
+    Some(path)
+}
 
 /// Manages undo/redo history for layout changes.
-#[derive(Debug, Clone)]
+///
+/// Serializes only the undo stack; the redo stack and limit are not kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct History {
     /// Stack of previous states (for undo).
-    undo_stack: Vec<LayoutDocument>,
+    undo_stack: Vec<Entry>,
     /// Stack of future states (for redo).
-    redo_stack: Vec<LayoutDocument>,
+    #[serde(skip)]
+    redo_stack: Vec<Entry>,
+    /// Maximum number of entries in each stack.
+    #[serde(skip, default = "default_limit")]
+    limit: usize,
+    /// Timeline index of the last saved state, if it is still reachable.
+    #[serde(skip)]
+    saved: Option<usize>,
+}
+
+fn default_limit() -> usize {
+    DEFAULT_HISTORY_LIMIT
 }
 
 impl Default for History {
     fn default() -> Self {
-        Self::new()
+        Self::new(DEFAULT_HISTORY_LIMIT)
     }
 }
 
 impl History {
-    /// Create a new empty history.
-    pub fn new() -> Self {
+    /// Create a new empty history keeping up to `limit` steps, at least one.
+    pub fn new(limit: usize) -> Self {
         Self {
-            undo_stack: Vec::with_capacity(MAX_HISTORY_SIZE),
-            redo_stack: Vec::with_capacity(MAX_HISTORY_SIZE),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            limit: limit.max(1),
+            saved: Some(0),
         }
     }
 
-    /// Push a snapshot before making a change.
-    /// This clears the redo stack.
-    pub fn push(&mut self, snapshot: LayoutDocument) {
-        // Clear redo stack when new changes are made
-        self.redo_stack.clear();
+    /// Maximum number of steps kept in each direction.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Change the limit, at least one, dropping the oldest undo steps and
+    /// the furthest redo steps that no longer fit.
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit.max(1);
+        // The bottom of each stack is furthest from the current state
+        let undo_excess = self.undo_stack.len().saturating_sub(self.limit);
+        self.undo_stack.drain(..undo_excess);
+        self.saved = self.saved.and_then(|saved| saved.checked_sub(undo_excess));
+
+        let redo_excess = self.redo_stack.len().saturating_sub(self.limit);
+        self.redo_stack.drain(..redo_excess);
+        let last = self.position() + self.redo_stack.len();
+        self.saved = self.saved.filter(|saved| *saved <= last);
+    }
+
+    /// Remember the current state as the one last saved.
+    pub fn mark_saved(&mut self) {
+        self.saved = Some(self.position());
+    }
+
+    /// Whether the current state is the one last saved.
+    pub fn is_at_saved(&self) -> bool {
+        self.saved == Some(self.position())
+    }
+
+    /// Approximate heap memory held by the history, in bytes.
+    pub fn memory_estimate(&self) -> usize {
+        fn node_bytes(node: &LayoutNode) -> usize {
+            std::mem::size_of::<LayoutNode>() + node.child_nodes().into_iter().map(node_bytes).sum::<usize>()
+        }
+
+        self.undo_stack
+            .iter()
+            .chain(&self.redo_stack)
+            .map(|entry| {
+                let state = match &entry.state {
+                    Stored::Full(doc) => doc.name.len() + node_bytes(&doc.root),
+                    Stored::Delta(delta) => {
+                        delta.name.len()
+                            + delta
+                                .subtree
+                                .as_ref()
+                                .map_or(0, |(path, node)| path.len() * std::mem::size_of::<usize>() + node_bytes(node))
+                    }
+                };
+                std::mem::size_of::<Entry>() + entry.label.len() + state
+            })
+            .sum()
+    }
+
+    /// Push a snapshot before making a change, labelled with the action
+    /// about to be applied. This clears the redo stack.
+    pub fn push(&mut self, snapshot: LayoutDocument, label: impl Into<String>) {
+        self.forget_redo();
 
         // Add to undo stack
-        self.undo_stack.push(snapshot);
+        push_entry(&mut self.undo_stack, snapshot, label.into());
+
+        self.trim_undo();
+    }
 
-        // Trim to max size
-        if self.undo_stack.len() > MAX_HISTORY_SIZE {
+    /// Record a change about to be made to the node at `path` of `current`,
+    /// storing only that node instead of a snapshot. Returns false, recording
+    /// nothing, if there is no node at `path`.
+    ///
+    /// Until the next push, undo or redo, the caller must change nothing
+    /// outside that node's subtree.
+    pub fn push_node(&mut self, current: &LayoutDocument, path: &[usize], label: impl Into<String>) -> bool {
+        let Some(node) = current.root.descendant_at(path) else {
+            return false;
+        };
+        self.forget_redo();
+
+        // The previous top is relative to `current` from now on
+        if let Some(top) = self.undo_stack.last_mut() {
+            if let Stored::Full(previous) = &top.state {
+                top.state = Stored::Delta(Delta::between(previous, current));
+            }
+        }
+        self.undo_stack.push(Entry {
+            state: Stored::Delta(Delta {
+                version: current.version,
+                name: current.name.clone(),
+                subtree: Some((path.to_vec(), node.clone())),
+            }),
+            label: label.into(),
+        });
+
+        self.trim_undo();
+        true
+    }
+
+    /// Clear the redo stack before new changes are made, forgetting a saved
+    /// state that was only reachable by redo.
+    fn forget_redo(&mut self) {
+        self.redo_stack.clear();
+        let position = self.position();
+        self.saved = self.saved.filter(|saved| *saved <= position);
+    }
+
+    /// Drop the oldest undo step if the stack is over the limit.
+    fn trim_undo(&mut self) {
+        if self.undo_stack.len() > self.limit {
             self.undo_stack.remove(0);
+            self.saved = self.saved.and_then(|saved| saved.checked_sub(1));
         }
     }
 
     /// Undo the last change.
-    /// Returns the previous state, or None if no undo available.
+    /// Returns the previous state and the label of the undone action,
+    /// or None if no undo available.
     /// The caller should pass in the current state to save for redo.
-    pub fn undo(&mut self, current: LayoutDocument) -> Option<LayoutDocument> {
-        let previous = self.undo_stack.pop()?;
-        self.redo_stack.push(current);
-        Some(previous)
+    pub fn undo(&mut self, current: LayoutDocument) -> Option<(LayoutDocument, String)> {
+        let (previous, label) = pop_entry(&mut self.undo_stack, &current)?;
+        push_entry(&mut self.redo_stack, current, label.clone());
+        Some((previous, label))
     }
 
     /// Redo a previously undone change.
-    /// Returns the next state, or None if no redo available.
+    /// Returns the next state and the label of the redone action,
+    /// or None if no redo available.
     /// The caller should pass in the current state to save for undo.
-    pub fn redo(&mut self, current: LayoutDocument) -> Option<LayoutDocument> {
-        let next = self.redo_stack.pop()?;
-        self.undo_stack.push(current);
-        Some(next)
+    pub fn redo(&mut self, current: LayoutDocument) -> Option<(LayoutDocument, String)> {
+        let (next, label) = pop_entry(&mut self.redo_stack, &current)?;
+        push_entry(&mut self.undo_stack, current, label.clone());
+        Some((next, label))
+    }
+
+    /// Label of the action the next undo would revert.
+    pub fn undo_label(&self) -> Option<&str> {
+        self.undo_stack.last().map(|entry| entry.label.as_str())
+    }
+
+    /// Label of the action the next redo would reapply.
+    pub fn redo_label(&self) -> Option<&str> {
+        self.redo_stack.last().map(|entry| entry.label.as_str())
+    }
+
+    /// Labels of all recorded actions, oldest first, including undone ones.
+    ///
+    /// State `i` of the timeline is the one reached after the first `i`
+    /// actions; [`History::position`] is the index of the current state.
+    pub fn labels(&self) -> impl DoubleEndedIterator<Item = &str> {
+        self.undo_stack
+            .iter()
+            .chain(self.redo_stack.iter().rev())
+            .map(|entry| entry.label.as_str())
+    }
+
+    /// Index of the current state in the timeline.
+    pub fn position(&self) -> usize {
+        self.undo_stack.len()
+    }
+
+    /// Move to timeline state `index` by undoing or redoing as many steps
+    /// as needed. Returns the state to show, or None if `index` is the
+    /// current state or out of range.
+    /// The caller should pass in the current state, as for `undo`.
+    pub fn jump_to(&mut self, index: usize, current: LayoutDocument) -> Option<LayoutDocument> {
+        if index == self.position() || index > self.undo_stack.len() + self.redo_stack.len() {
+            return None;
+        }
+
+        let mut state = current;
+        while self.position() > index {
+            state = self.undo(state)?.0;
+        }
+        while self.position() < index {
+            state = self.redo(state)?.0;
+        }
+        Some(state)
     }
 
     /// Check if undo is available.
@@ -76,6 +381,7 @@ impl History {
 
     /// Clear all history.
     pub fn clear(&mut self) {
+        self.saved = self.is_at_saved().then_some(0);
         self.undo_stack.clear();
         self.redo_stack.clear();
     }
@@ -94,70 +400,66 @@ impl History {
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    fn make_doc(name: &str) -> LayoutDocument {
-        let mut doc = LayoutDocument::default();
-        doc.name = name.to_string();
-        doc
-    }
+    use crate::model::layout::{ContainerAttrs, TextAttrs};
+    use crate::test_support::doc_named;
 
     #[test]
     fn test_push_and_undo() {
-        let mut history = History::new();
+        let mut history = History::default();
 
-        let state1 = make_doc("State 1");
-        let state2 = make_doc("State 2");
-        let current = make_doc("Current");
+        let state1 = doc_named("State 1");
+        let state2 = doc_named("State 2");
+        let current = doc_named("Current");
 
-        history.push(state1.clone());
-        history.push(state2.clone());
+        history.push(state1.clone(), "Edit");
+        history.push(state2.clone(), "Edit");
 
         assert!(history.can_undo());
         assert!(!history.can_redo());
 
-        let restored = history.undo(current.clone()).unwrap();
+        let restored = history.undo(current.clone()).unwrap().0;
         assert_eq!(restored.name, "State 2");
         assert!(history.can_redo());
     }
 
     #[test]
     fn test_redo() {
-        let mut history = History::new();
+        let mut history = History::default();
 
-        let state1 = make_doc("State 1");
-        let current = make_doc("Current");
+        let state1 = doc_named("State 1");
+        let current = doc_named("Current");
 
-        history.push(state1.clone());
+        history.push(state1.clone(), "Edit");
 
-        let restored = history.undo(current.clone()).unwrap();
+        let restored = history.undo(current.clone()).unwrap().0;
         assert_eq!(restored.name, "State 1");
 
-        let redone = history.redo(restored).unwrap();
+        let redone = history.redo(restored).unwrap().0;
         assert_eq!(redone.name, "Current");
     }
 
     #[test]
     fn test_push_clears_redo() {
-        let mut history = History::new();
+        let mut history = History::default();
 
-        let state1 = make_doc("State 1");
-        let state2 = make_doc("State 2");
-        let current = make_doc("Current");
+        let state1 = doc_named("State 1");
+        let state2 = doc_named("State 2");
+        let current = doc_named("Current");
 
-        history.push(state1);
+        history.push(state1, "Edit");
         history.undo(current);
 
         assert!(history.can_redo());
 
-        history.push(state2);
+        history.push(state2, "Edit");
         assert!(!history.can_redo());
     }
 
     #[test]
     fn test_clear() {
-        let mut history = History::new();
-        history.push(make_doc("State 1"));
-        history.push(make_doc("State 2"));
+        let mut history = History::default();
+        history.push(doc_named("State 1"), "Edit");
+        history.push(doc_named("State 2"), "Edit");
         
         assert!(history.can_undo());
         
@@ -171,18 +473,18 @@ mod tests {
 
     #[test]
     fn test_undo_count_redo_count() {
-        let mut history = History::new();
+        let mut history = History::default();
         
         assert_eq!(history.undo_count(), 0);
         assert_eq!(history.redo_count(), 0);
         
-        history.push(make_doc("State 1"));
-        history.push(make_doc("State 2"));
+        history.push(doc_named("State 1"), "Edit");
+        history.push(doc_named("State 2"), "Edit");
         
         assert_eq!(history.undo_count(), 2);
         assert_eq!(history.redo_count(), 0);
         
-        history.undo(make_doc("Current"));
+        history.undo(doc_named("Current"));
         
         assert_eq!(history.undo_count(), 1);
         assert_eq!(history.redo_count(), 1);
@@ -190,11 +492,11 @@ mod tests {
 
     #[test]
     fn test_max_undo_limit() {
-        let mut history = History::new();
+        let mut history = History::default();
         
-        // Push more than MAX_UNDO_STACK (50) states
+        // Push more than DEFAULT_HISTORY_LIMIT (50) states
         for i in 0..60 {
-            history.push(make_doc(&format!("State {}", i)));
+            history.push(doc_named(&format!("State {}", i)), "Edit");
         }
         
         // Should be capped at 50
@@ -203,40 +505,375 @@ mod tests {
 
     #[test]
     fn test_undo_empty_returns_none() {
-        let mut history = History::new();
-        let result = history.undo(make_doc("Current"));
+        let mut history = History::default();
+        let result = history.undo(doc_named("Current"));
         assert!(result.is_none());
     }
 
     #[test]
     fn test_redo_empty_returns_none() {
-        let mut history = History::new();
-        let result = history.redo(make_doc("Current"));
+        let mut history = History::default();
+        let result = history.redo(doc_named("Current"));
         assert!(result.is_none());
     }
 
     #[test]
     fn test_multiple_undo_redo_cycles() {
-        let mut history = History::new();
+        let mut history = History::default();
         
-        history.push(make_doc("A"));
-        history.push(make_doc("B"));
-        history.push(make_doc("C"));
+        history.push(doc_named("A"), "Edit");
+        history.push(doc_named("B"), "Edit");
+        history.push(doc_named("C"), "Edit");
         
         // Undo all
-        let c = history.undo(make_doc("D")).unwrap();
+        let c = history.undo(doc_named("D")).unwrap().0;
         assert_eq!(c.name, "C");
-        let b = history.undo(c).unwrap();
+        let b = history.undo(c).unwrap().0;
         assert_eq!(b.name, "B");
-        let a = history.undo(b).unwrap();
+        let a = history.undo(b).unwrap().0;
         assert_eq!(a.name, "A");
         
         // Redo all
-        let b2 = history.redo(a).unwrap();
+        let b2 = history.redo(a).unwrap().0;
         assert_eq!(b2.name, "B");
-        let c2 = history.redo(b2).unwrap();
+        let c2 = history.redo(b2).unwrap().0;
         assert_eq!(c2.name, "C");
-        let d = history.redo(c2).unwrap();
+        let d = history.redo(c2).unwrap().0;
         assert_eq!(d.name, "D");
     }
+
+    #[test]
+    fn test_labels_travel_with_snapshots() {
+        let mut history = History::default();
+        assert_eq!(history.undo_label(), None);
+
+        history.push(doc_named("A"), "Add Button");
+        history.push(doc_named("B"), "Change padding");
+        assert_eq!(history.undo_label(), Some("Change padding"));
+        assert_eq!(history.redo_label(), None);
+
+        let (b, label) = history.undo(doc_named("C")).unwrap();
+        assert_eq!((b.name.as_str(), label.as_str()), ("B", "Change padding"));
+        assert_eq!(history.undo_label(), Some("Add Button"));
+        assert_eq!(history.redo_label(), Some("Change padding"));
+
+        let (a, label) = history.undo(b).unwrap();
+        assert_eq!((a.name.as_str(), label.as_str()), ("A", "Add Button"));
+        assert_eq!(history.redo_label(), Some("Add Button"));
+
+        // Redo reapplies in order with the same labels
+        let (b, label) = history.redo(a).unwrap();
+        assert_eq!((b.name.as_str(), label.as_str()), ("B", "Add Button"));
+        let (c, label) = history.redo(b).unwrap();
+        assert_eq!((c.name.as_str(), label.as_str()), ("C", "Change padding"));
+        assert_eq!(history.undo_label(), Some("Change padding"));
+        assert_eq!(history.redo_label(), None);
+
+        // A new change replaces the redo labels
+        let (b, _) = history.undo(c).unwrap();
+        history.push(b, "Delete Text");
+        assert_eq!(history.undo_label(), Some("Delete Text"));
+        assert_eq!(history.redo_label(), None);
+    }
+
+    #[test]
+    fn test_jump_to_moves_between_stacks() {
+        let mut history = History::default();
+        history.push(doc_named("A"), "First");
+        history.push(doc_named("B"), "Second");
+        history.push(doc_named("C"), "Third");
+        assert_eq!(history.position(), 3);
+        assert_eq!(history.labels().collect::<Vec<_>>(), ["First", "Second", "Third"]);
+
+        // Back to the initial state in one step
+        let a = history.jump_to(0, doc_named("D")).unwrap();
+        assert_eq!(a.name, "A");
+        assert_eq!(history.position(), 0);
+        assert_eq!((history.undo_count(), history.redo_count()), (0, 3));
+        assert_eq!(history.labels().collect::<Vec<_>>(), ["First", "Second", "Third"]);
+
+        // Forward to the middle, then plain undo/redo continue from there
+        let c = history.jump_to(2, a).unwrap();
+        assert_eq!(c.name, "C");
+        assert_eq!(history.undo_label(), Some("Second"));
+        assert_eq!(history.redo_label(), Some("Third"));
+        let (d, label) = history.redo(c).unwrap();
+        assert_eq!((d.name.as_str(), label.as_str()), ("D", "Third"));
+
+        // Current position and out-of-range indices change nothing
+        assert!(history.jump_to(3, d.clone()).is_none());
+        assert!(history.jump_to(4, d).is_none());
+        assert_eq!((history.undo_count(), history.redo_count()), (3, 0));
+    }
+
+    /// A Column of `rows` Rows, each holding `per_row` Text nodes.
+    fn make_large_doc(rows: usize, per_row: usize) -> LayoutDocument {
+        let row = |r: usize| {
+            LayoutNode::new(WidgetType::Row {
+                children: (0..per_row)
+                    .map(|c| {
+                        LayoutNode::new(WidgetType::Text {
+                            content: format!("Cell {} {}", r, c),
+                            attrs: TextAttrs::default(),
+                        })
+                    })
+                    .collect(),
+                attrs: ContainerAttrs::default(),
+            })
+        };
+        LayoutDocument {
+            root: LayoutNode::new(WidgetType::Column {
+                children: (0..rows).map(row).collect(),
+                attrs: ContainerAttrs::default(),
+            }),
+            ..LayoutDocument::default()
+        }
+    }
+
+    /// Edit the text of one cell in place.
+    fn edit_cell(doc: &mut LayoutDocument, row: usize, cell: usize, content: &str) {
+        let node = doc.root.descendant_at_mut(&[row, cell]).unwrap();
+        if let WidgetType::Text { content: c, .. } = &mut node.widget {
+            *c = content.to_string();
+        }
+    }
+
+    fn count_nodes(node: &LayoutNode) -> usize {
+        1 + node.descendant_count()
+    }
+
+    /// Number of layout nodes held across both stacks.
+    fn stored_nodes(history: &History) -> usize {
+        history
+            .undo_stack
+            .iter()
+            .chain(&history.redo_stack)
+            .map(|entry| match &entry.state {
+                Stored::Full(doc) => count_nodes(&doc.root),
+                Stored::Delta(delta) => delta.subtree.as_ref().map_or(0, |(_, node)| count_nodes(node)),
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_history_memory_does_not_scale_with_layout_size() {
+        let mut current = make_large_doc(25, 20);
+        let layout_nodes = count_nodes(&current.root);
+        assert_eq!(layout_nodes, 526);
+
+        // Each edit changes the text of a single cell
+        let mut history = History::default();
+        let mut states = Vec::new();
+        for i in 0..DEFAULT_HISTORY_LIMIT {
+            states.push(current.clone());
+            history.push(current.clone(), format!("Edit {}", i));
+            edit_cell(&mut current, i % 25, i % 20, &format!("Edited {}", i));
+        }
+
+        // One whole snapshot plus one Text node per older entry, instead
+        // of a whole snapshot per entry
+        let stored = stored_nodes(&history);
+        assert_eq!(stored, layout_nodes + DEFAULT_HISTORY_LIMIT - 1);
+        assert!(stored * 10 < layout_nodes * DEFAULT_HISTORY_LIMIT);
+
+        // Every state comes back exactly, in both directions
+        for expected in states.iter().rev() {
+            let (previous, _) = history.undo(current).unwrap();
+            assert_eq!(&previous, expected);
+            current = previous;
+        }
+        assert!(stored_nodes(&history) * 10 < layout_nodes * DEFAULT_HISTORY_LIMIT);
+        for expected in states.iter().skip(1) {
+            let (next, _) = history.redo(current).unwrap();
+            assert_eq!(&next, expected);
+            current = next;
+        }
+    }
+
+    #[test]
+    fn test_node_edits_store_only_the_node() {
+        let mut current = make_large_doc(25, 20);
+        let mut history = History::default();
+        let mut states = Vec::new();
+
+        // A whole snapshot first, then typing into one cell
+        states.push(current.clone());
+        history.push(current.clone(), "Delete Text");
+        edit_cell(&mut current, 0, 0, "");
+        let after_snapshot = stored_nodes(&history);
+        for i in 1..20 {
+            states.push(current.clone());
+            assert!(history.push_node(&current, &[3, 4], format!("Edit {}", i)));
+            edit_cell(&mut current, 3, 4, &"x".repeat(i));
+        }
+
+        // The snapshot became a one-node delta and each keystroke adds a node
+        assert_eq!(after_snapshot, 526);
+        assert_eq!(stored_nodes(&history), 20);
+        assert!(!history.push_node(&current, &[99], "Missing"));
+        assert_eq!(history.undo_count(), 20);
+
+        // Every state comes back exactly, in both directions, and a full
+        // push on top of node edits keeps them intact
+        for expected in states.iter().rev() {
+            let (previous, _) = history.undo(current).unwrap();
+            assert_eq!(&previous, expected);
+            current = previous;
+        }
+        for expected in states.iter().skip(1).take(10) {
+            let (next, _) = history.redo(current).unwrap();
+            assert_eq!(&next, expected);
+            current = next;
+        }
+        let before_push = current.clone();
+        history.push(current.clone(), "Rename");
+        current.name = "Renamed".to_string();
+        assert!(history.push_node(&current, &[0, 0], "Edit"));
+        edit_cell(&mut current, 0, 0, "again");
+        current = history.undo(current).unwrap().0;
+        assert_eq!(current.name, "Renamed");
+        current = history.undo(current).unwrap().0;
+        assert_eq!(current, before_push);
+        assert_eq!(history.undo(current).unwrap().0, states[9]);
+    }
+
+    #[test]
+    fn test_history_restores_structural_changes() {
+        let original = make_large_doc(3, 3);
+
+        // Remove a row, then rename the document and change the root type
+        let mut removed = original.clone();
+        if let WidgetType::Column { children, .. } = &mut removed.root.widget {
+            children.remove(1);
+        }
+        let mut reshaped = removed.clone();
+        reshaped.name = "Reshaped".to_string();
+        let children = match &reshaped.root.widget {
+            WidgetType::Column { children, .. } => children.clone(),
+            _ => unreachable!(),
+        };
+        reshaped.root.widget = WidgetType::Row {
+            children,
+            attrs: ContainerAttrs::default(),
+        };
+
+        let mut history = History::default();
+        history.push(original.clone(), "Delete Row");
+        history.push(removed.clone(), "Change root to Row");
+        history.push(removed.clone(), "No-op");
+
+        let (state, _) = history.undo(reshaped.clone()).unwrap();
+        assert_eq!(state, removed);
+        let (state, _) = history.undo(state).unwrap();
+        assert_eq!(state, removed);
+        let (state, _) = history.undo(state).unwrap();
+        assert_eq!(state, original);
+
+        let restored = history.jump_to(3, state).unwrap();
+        assert_eq!(restored, reshaped);
+    }
+
+    #[test]
+    fn test_custom_limit_trims_both_stacks() {
+        let mut history = History::new(5);
+        for i in 0..8 {
+            history.push(doc_named(&format!("State {}", i)), format!("Edit {}", i));
+        }
+        assert_eq!(history.undo_count(), 5);
+        assert_eq!(history.labels().next(), Some("Edit 3"));
+
+        // Undo three steps, then shrink the limit below both stack sizes
+        let mut current = doc_named("Current");
+        for _ in 0..3 {
+            current = history.undo(current).unwrap().0;
+        }
+        assert_eq!((history.undo_count(), history.redo_count()), (2, 3));
+        history.set_limit(2);
+        assert_eq!((history.undo_count(), history.redo_count()), (2, 2));
+        assert_eq!(history.labels().collect::<Vec<_>>(), ["Edit 3", "Edit 4", "Edit 5", "Edit 6"]);
+
+        // The kept states still come back exactly
+        let (state, label) = history.redo(current).unwrap();
+        assert_eq!((state.name.as_str(), label.as_str()), ("State 6", "Edit 5"));
+
+        // A limit of zero still keeps one step
+        history.set_limit(0);
+        assert_eq!(history.limit(), 1);
+        assert_eq!((history.undo_count(), history.redo_count()), (1, 1));
+        assert_eq!(History::new(0).limit(), 1);
+    }
+
+    #[test]
+    fn test_memory_estimate_tracks_stored_states() {
+        let mut history = History::default();
+        assert_eq!(history.memory_estimate(), 0);
+
+        let mut current = make_large_doc(10, 10);
+        history.push(current.clone(), "Edit 0");
+        let one_snapshot = history.memory_estimate();
+        assert!(one_snapshot >= 111 * std::mem::size_of::<LayoutNode>());
+
+        // Small edits add far less than another whole snapshot
+        for i in 1..10 {
+            edit_cell(&mut current, i, i, "Edited");
+            history.push(current.clone(), format!("Edit {}", i));
+        }
+        assert!(history.memory_estimate() < one_snapshot * 2);
+
+        history.clear();
+        assert_eq!(history.memory_estimate(), 0);
+    }
+
+    #[test]
+    fn test_saved_marker_follows_position() {
+        let mut history = History::default();
+        assert!(history.is_at_saved());
+
+        history.push(doc_named("A"), "Edit 1");
+        assert!(!history.is_at_saved());
+        let (a, _) = history.undo(doc_named("B")).unwrap();
+        assert!(history.is_at_saved());
+
+        // Save after redoing, then undo away from and redo back to it
+        let (b, _) = history.redo(a).unwrap();
+        history.mark_saved();
+        let (a, _) = history.undo(b).unwrap();
+        assert!(!history.is_at_saved());
+        let (b, _) = history.redo(a).unwrap();
+        assert!(history.is_at_saved());
+
+        // A new branch from an earlier state can never reach the saved one
+        let (a, _) = history.undo(b).unwrap();
+        history.push(a, "Edit 2");
+        assert!(!history.is_at_saved());
+        history.undo(doc_named("C")).unwrap();
+        assert!(!history.is_at_saved());
+    }
+
+    #[test]
+    fn test_saved_marker_survives_trimming() {
+        let mut history = History::new(3);
+        history.push(doc_named("A"), "Edit 1");
+        history.mark_saved();
+        history.push(doc_named("B"), "Edit 2");
+        history.push(doc_named("C"), "Edit 3");
+        history.push(doc_named("D"), "Edit 4");
+        assert_eq!(history.undo_count(), 3);
+
+        // The saved state is now the oldest kept one
+        let mut current = doc_named("E");
+        for _ in 0..3 {
+            current = history.undo(current).unwrap().0;
+        }
+        assert_eq!(current.name, "B");
+        assert!(history.is_at_saved());
+
+        // Dropping it from the bottom forgets it
+        for _ in 0..2 {
+            current = history.redo(current).unwrap().0;
+        }
+        history.set_limit(1);
+        history.undo(current).unwrap();
+        assert!(!history.is_at_saved());
+    }
 }
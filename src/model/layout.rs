@@ -5,13 +5,20 @@
 //! - Serialized to/from RON or JSON files
 //! - Converted to Rust/Iced code
 
-use crate::util::{is_rust_keyword, is_valid_rust_identifier};
+use crate::util::{is_raw_identifier_keyword, is_rust_keyword, is_valid_rust_identifier, truncate_with_ellipsis};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::path::PathBuf;
 use thiserror::Error;
 use uuid::Uuid;
 
+/// Schema version written by this build; older files are migrated on load.
+pub const LAYOUT_VERSION: u32 = 2;
+
+/// Characters of content shown in a node's label before it is cut off.
+pub const LABEL_PREVIEW_CHARS: usize = 24;
+
 /// Unique identifier for a component in the layout tree.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ComponentId(Uuid);
@@ -36,11 +43,12 @@ impl fmt::Display for ComponentId {
 }
 
 /// Length specification for width/height properties.
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
 pub enum LengthSpec {
     /// Fill available space.
     Fill,
     /// Shrink to fit content.
+    #[default]
     Shrink,
     /// Fill a portion of available space.
     FillPortion(u16),
@@ -48,31 +56,33 @@ pub enum LengthSpec {
     Fixed(f32),
 }
 
-impl Default for LengthSpec {
-    fn default() -> Self {
-        Self::Shrink
-    }
-}
-
 /// Alignment specification.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum AlignmentSpec {
+    /// Aligned to the start: left or top.
     #[default]
     Start,
+    /// Centered.
     Center,
+    /// Aligned to the end: right or bottom.
     End,
 }
 
 /// Padding specification (uniform or per-side).
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
 pub struct PaddingSpec {
+    /// Top padding in pixels.
     pub top: f32,
+    /// Right padding in pixels.
     pub right: f32,
+    /// Bottom padding in pixels.
     pub bottom: f32,
+    /// Left padding in pixels.
     pub left: f32,
 }
 
 impl PaddingSpec {
+    /// No padding.
     pub const ZERO: Self = Self {
         top: 0.0,
         right: 0.0,
@@ -80,6 +90,7 @@ impl PaddingSpec {
         left: 0.0,
     };
 
+    /// The same padding on every side.
     pub fn uniform(value: f32) -> Self {
         Self {
             top: value,
@@ -93,11 +104,17 @@ impl PaddingSpec {
 /// Common attributes for container widgets (Column, Row, Container, Scrollable).
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ContainerAttrs {
+    /// Space between the border and the children.
     pub padding: PaddingSpec,
+    /// Space between children, for columns and rows.
     pub spacing: f32,
+    /// Horizontal alignment of the children.
     pub align_x: AlignmentSpec,
+    /// Vertical alignment of the children.
     pub align_y: AlignmentSpec,
+    /// Width of the container.
     pub width: LengthSpec,
+    /// Height of the container.
     pub height: LengthSpec,
 }
 
@@ -117,9 +134,12 @@ impl Default for ContainerAttrs {
 /// Attributes for Text widgets.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextAttrs {
+    /// Font size in pixels.
     pub font_size: f32,
-    pub color: Option<[f32; 4]>, // RGBA, None means default
-    pub horizontal_alignment: AlignmentSpec,
+    /// RGBA color, or `None` for the theme's text color.
+    pub color: Option<[f32; 4]>,
+    /// Horizontal alignment of the text.
+    pub align_x: AlignmentSpec,
 }
 
 impl Default for TextAttrs {
@@ -127,7 +147,7 @@ impl Default for TextAttrs {
         Self {
             font_size: 16.0,
             color: None,
-            horizontal_alignment: AlignmentSpec::Start,
+            align_x: AlignmentSpec::Start,
         }
     }
 }
@@ -135,25 +155,30 @@ impl Default for TextAttrs {
 /// Attributes for Button widgets.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct ButtonAttrs {
+    /// Width of the button.
     pub width: LengthSpec,
+    /// Height of the button.
     pub height: LengthSpec,
 }
 
 /// Attributes for TextInput widgets.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct InputAttrs {
+    /// Width of the input.
     pub width: LengthSpec,
 }
 
 /// Attributes for Checkbox widgets.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct CheckboxAttrs {
+    /// Space between the box and the label.
     pub spacing: f32,
 }
 
 /// Attributes for Slider widgets.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SliderAttrs {
+    /// Width of the slider.
     pub width: LengthSpec,
 }
 
@@ -168,7 +193,9 @@ impl Default for SliderAttrs {
 /// Attributes for PickList widgets.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct PickListAttrs {
+    /// Width of the pick list.
     pub width: LengthSpec,
+    /// Text shown while nothing is selected.
     pub placeholder: String,
 }
 
@@ -177,58 +204,283 @@ pub struct PickListAttrs {
 pub struct LayoutNode {
     /// Unique identifier for this node.
     pub id: ComponentId,
+    /// Optional friendly name shown in the tree view.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Whether the node is hidden on the design canvas.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub hidden: bool,
+    /// Whether code generation and validation skip this subtree.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub exclude_from_export: bool,
     /// The widget type and its specific data.
     pub widget: WidgetType,
 }
 
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
 impl LayoutNode {
     /// Create a new layout node with a random ID.
     pub fn new(widget: WidgetType) -> Self {
         Self {
             id: ComponentId::new(),
+            name: None,
+            hidden: false,
+            exclude_from_export: false,
             widget,
         }
     }
 
+    /// Check if the node's friendly name contains `query`, ignoring case.
+    pub fn matches_name(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.name
+            .as_ref()
+            .is_some_and(|name| name.to_lowercase().contains(&query))
+    }
+
+    /// Check if the node's text, labels, placeholder or message stub
+    /// contains `query`, ignoring case.
+    pub fn matches_content(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        let fields: &[&String] = match &self.widget {
+            WidgetType::Text { content, .. } => &[content],
+            WidgetType::Button { label, message_stub, .. }
+            | WidgetType::Checkbox { label, message_stub, .. } => &[label, message_stub],
+            WidgetType::TextInput { placeholder, message_stub, .. } => &[placeholder, message_stub],
+            WidgetType::Slider { message_stub, .. } => &[message_stub],
+            WidgetType::PickList { message_stub, attrs, .. } => &[&attrs.placeholder, message_stub],
+            _ => &[],
+        };
+        fields.iter().any(|field| field.to_lowercase().contains(&query))
+    }
+
     /// Regenerate the ID for this node and all its children.
     /// Used when duplicating a node to ensure unique IDs.
     pub fn regenerate_ids(&mut self) {
         self.id = ComponentId::new();
+        for child in self.child_nodes_mut() {
+            child.regenerate_ids();
+        }
+    }
+
+    /// Count the nodes below this one, at any depth.
+    pub fn descendant_count(&self) -> usize {
+        self.child_nodes()
+            .into_iter()
+            .map(|child| 1 + child.descendant_count())
+            .sum()
+    }
+
+    /// Get every child of this node, including the one in a single-child
+    /// container.
+    ///
+    /// This is the one place that knows where each container keeps its
+    /// children; walks over the tree should go through it rather than
+    /// matching on the container variants themselves.
+    pub fn child_nodes(&self) -> Vec<&LayoutNode> {
+        match &self.widget {
+            WidgetType::Column { children, .. }
+            | WidgetType::Row { children, .. }
+            | WidgetType::Stack { children, .. } => children.iter().collect(),
+            WidgetType::Container { child, .. } | WidgetType::Scrollable { child, .. } => {
+                child.as_deref().into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Get every child of this node mutably, including the one in a
+    /// single-child container.
+    pub fn child_nodes_mut(&mut self) -> Vec<&mut LayoutNode> {
         match &mut self.widget {
             WidgetType::Column { children, .. }
             | WidgetType::Row { children, .. }
-            | WidgetType::Stack { children, .. } => {
-                for child in children {
-                    child.regenerate_ids();
-                }
+            | WidgetType::Stack { children, .. } => children.iter_mut().collect(),
+            WidgetType::Container { child, .. } | WidgetType::Scrollable { child, .. } => {
+                child.as_deref_mut().into_iter().collect()
             }
-            WidgetType::Container { child, .. }
-            | WidgetType::Scrollable { child, .. } => {
-                if let Some(c) = child {
-                    c.regenerate_ids();
+            _ => Vec::new(),
+        }
+    }
+
+    /// Get this node and every node below it, in document order.
+    pub fn subtree(&self) -> Vec<&LayoutNode> {
+        let mut nodes = Vec::new();
+        let mut pending = vec![self];
+        while let Some(node) = pending.pop() {
+            nodes.push(node);
+            pending.extend(node.child_nodes().into_iter().rev());
+        }
+        nodes
+    }
+
+    /// Get the slot of a single-child container (Container or Scrollable).
+    pub fn child_slot_mut(&mut self) -> Option<&mut Option<Box<LayoutNode>>> {
+        match &mut self.widget {
+            WidgetType::Container { child, .. } | WidgetType::Scrollable { child, .. } => Some(child),
+            _ => None,
+        }
+    }
+
+    /// Get the child at `index`, counting a single-child container's child
+    /// as index 0.
+    pub fn child_at(&self, index: usize) -> Option<&LayoutNode> {
+        match &self.widget {
+            WidgetType::Column { children, .. }
+            | WidgetType::Row { children, .. }
+            | WidgetType::Stack { children, .. } => children.get(index),
+            WidgetType::Container { child, .. } | WidgetType::Scrollable { child, .. } => {
+                child.as_deref().filter(|_| index == 0)
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the child at `index` mutably, counting a single-child container's
+    /// child as index 0.
+    pub fn child_at_mut(&mut self, index: usize) -> Option<&mut LayoutNode> {
+        match &mut self.widget {
+            WidgetType::Column { children, .. }
+            | WidgetType::Row { children, .. }
+            | WidgetType::Stack { children, .. } => children.get_mut(index),
+            WidgetType::Container { child, .. } | WidgetType::Scrollable { child, .. } => {
+                child.as_deref_mut().filter(|_| index == 0)
+            }
+            _ => None,
+        }
+    }
+
+    /// Follow a path of child indices down from this node.
+    pub fn descendant_at(&self, path: &[usize]) -> Option<&LayoutNode> {
+        path.iter().try_fold(self, |node, &index| node.child_at(index))
+    }
+
+    /// Follow a path of child indices down from this node, mutably.
+    pub fn descendant_at_mut(&mut self, path: &[usize]) -> Option<&mut LayoutNode> {
+        path.iter().try_fold(self, |node, &index| node.child_at_mut(index))
+    }
+
+    /// Get the validation path of the child at `index`, given this node's
+    /// own path: `root.children[2]` in a list, `root.child` otherwise.
+    pub fn child_path(&self, path: &str, index: usize) -> String {
+        if self.is_multi_child() {
+            format!("{}.children[{}]", path, index)
+        } else {
+            format!("{}.child", path)
+        }
+    }
+
+    /// Get the label shown for this node: its name, or the type name plus a
+    /// preview of its content, if any.
+    pub fn label(&self) -> String {
+        let name = self.widget.display_name();
+        if let Some(friendly) = self.name.as_deref().filter(|n| !n.trim().is_empty()) {
+            let friendly = truncate_with_ellipsis(friendly, LABEL_PREVIEW_CHARS);
+            return format!("{friendly} ({name})");
+        }
+        let preview = match &self.widget {
+            WidgetType::Text { content, .. } => Some(content.as_str()),
+            WidgetType::Button { label, .. } | WidgetType::Checkbox { label, .. } => Some(label.as_str()),
+            WidgetType::TextInput { placeholder, .. } => Some(placeholder.as_str()),
+            WidgetType::ComponentRef { .. } => self.widget.component_name(),
+            _ => None,
+        };
+
+        match preview.map(|p| truncate_with_ellipsis(p, LABEL_PREVIEW_CHARS)) {
+            Some(p) if !p.trim().is_empty() => format!("{name} \"{p}\""),
+            _ => name.to_string(),
+        }
+    }
+
+    /// Render this node and everything below it as an indented text outline,
+    /// one node per line, starting `depth` levels in.
+    ///
+    /// ```text
+    /// Column (spacing 20, padding 20)
+    ///   Row (spacing 10)
+    ///     Text "Dashboard" size=28
+    /// ```
+    pub fn to_outline(&self, depth: usize) -> String {
+        let mut outline = String::new();
+        self.write_outline(depth, &mut outline);
+        outline
+    }
+
+    fn write_outline(&self, depth: usize, outline: &mut String) {
+        outline.push_str(&"  ".repeat(depth));
+        outline.push_str(&self.label());
+        match &self.widget {
+            WidgetType::Column { attrs, .. }
+            | WidgetType::Row { attrs, .. }
+            | WidgetType::Container { attrs, .. }
+            | WidgetType::Scrollable { attrs, .. }
+            | WidgetType::Stack { attrs, .. } => {
+                let mut details = Vec::new();
+                if attrs.spacing != 0.0 {
+                    details.push(format!("spacing {}", attrs.spacing));
+                }
+                let p = attrs.padding;
+                if p == PaddingSpec::uniform(p.top) && p.top != 0.0 {
+                    details.push(format!("padding {}", p.top));
+                } else if p != PaddingSpec::ZERO {
+                    details.push(format!("padding [{}, {}, {}, {}]", p.top, p.right, p.bottom, p.left));
+                }
+                if !details.is_empty() {
+                    outline.push_str(&format!(" ({})", details.join(", ")));
                 }
             }
-            _ => {} // Leaf nodes only need their own ID regenerated
+            WidgetType::Text { attrs, .. } if attrs.font_size != TextAttrs::default().font_size => {
+                outline.push_str(&format!(" size={}", attrs.font_size));
+            }
+            _ => {}
+        }
+        outline.push('\n');
+        for child in self.child_nodes() {
+            child.write_outline(depth + 1, outline);
         }
     }
 
-    /// Get children of this node (if it's a container).
+    /// Get the child list of this node, if it is a multi-child container
+    /// (Column, Row or Stack).
     pub fn children(&self) -> Option<&Vec<LayoutNode>> {
         match &self.widget {
-            WidgetType::Column { children, .. } => Some(children),
-            WidgetType::Row { children, .. } => Some(children),
+            WidgetType::Column { children, .. }
+            | WidgetType::Row { children, .. }
+            | WidgetType::Stack { children, .. } => Some(children),
             _ => None,
         }
     }
 
-    /// Get mutable children of this node (if it's a container).
+    /// Get the mutable child list of this node, if it is a multi-child
+    /// container (Column, Row or Stack).
     pub fn children_mut(&mut self) -> Option<&mut Vec<LayoutNode>> {
         match &mut self.widget {
-            WidgetType::Column { children, .. } => Some(children),
-            WidgetType::Row { children, .. } => Some(children),
+            WidgetType::Column { children, .. }
+            | WidgetType::Row { children, .. }
+            | WidgetType::Stack { children, .. } => Some(children),
             _ => None,
         }
     }
+
+    /// Check if this node holds a list of children (Column, Row or Stack).
+    pub fn is_multi_child(&self) -> bool {
+        self.children().is_some()
+    }
+
+    /// Check if this node can accept another child.
+    ///
+    /// Multi-child containers always can; single-child containers only when empty.
+    pub fn can_accept_child(&self) -> bool {
+        match &self.widget {
+            WidgetType::Container { child, .. } | WidgetType::Scrollable { child, .. } => {
+                child.is_none()
+            }
+            _ => self.is_multi_child(),
+        }
+    }
 }
 
 /// The type of widget and its associated data.
@@ -236,74 +488,249 @@ impl LayoutNode {
 pub enum WidgetType {
     /// A vertical container.
     Column {
+        /// Child nodes, top to bottom.
         children: Vec<LayoutNode>,
+        /// Layout attributes.
         attrs: ContainerAttrs,
     },
     /// A horizontal container.
     Row {
+        /// Child nodes, left to right.
         children: Vec<LayoutNode>,
+        /// Layout attributes.
         attrs: ContainerAttrs,
     },
     /// A single-child container for alignment/padding.
     Container {
+        /// The wrapped node, if any.
         child: Option<Box<LayoutNode>>,
+        /// Layout attributes.
         attrs: ContainerAttrs,
     },
     /// A scrollable container.
     Scrollable {
+        /// The scrolled node, if any.
         child: Option<Box<LayoutNode>>,
+        /// Layout attributes.
         attrs: ContainerAttrs,
     },
     /// A stack container for overlays.
     Stack {
+        /// Child nodes, bottom layer first.
         children: Vec<LayoutNode>,
+        /// Layout attributes.
         attrs: ContainerAttrs,
     },
     /// A text label.
     Text {
+        /// The text shown.
         content: String,
+        /// Text attributes.
         attrs: TextAttrs,
     },
     /// A clickable button.
     Button {
+        /// Text on the button.
         label: String,
+        /// Message variant sent when pressed.
         message_stub: String,
+        /// Size attributes.
         attrs: ButtonAttrs,
     },
     /// A text input field.
     TextInput {
+        /// Text shown while the input is empty.
         placeholder: String,
+        /// State field holding the text.
         value_binding: String,
+        /// Message variant sent with the new text.
         message_stub: String,
+        /// Input attributes.
         attrs: InputAttrs,
     },
     /// A checkbox.
     Checkbox {
+        /// Text next to the box.
         label: String,
+        /// State field holding whether it is checked.
         checked_binding: String,
+        /// Message variant sent with the new value.
         message_stub: String,
+        /// Checkbox attributes.
         attrs: CheckboxAttrs,
     },
     /// A slider.
     Slider {
+        /// Smallest value.
         min: f32,
+        /// Largest value.
         max: f32,
+        /// State field holding the value.
         value_binding: String,
+        /// Message variant sent with the new value.
         message_stub: String,
+        /// Slider attributes.
         attrs: SliderAttrs,
     },
     /// A pick list (dropdown).
     PickList {
+        /// Options to choose from.
         options: Vec<String>,
+        /// State field holding the selected option.
         selected_binding: String,
+        /// Message variant sent with the chosen option.
         message_stub: String,
+        /// Pick list attributes.
         attrs: PickListAttrs,
     },
     /// Empty space.
     Space {
+        /// Width of the space.
         width: LengthSpec,
+        /// Height of the space.
         height: LengthSpec,
     },
+    /// A component whose layout lives in its own file, relative to the project.
+    ComponentRef {
+        /// Path of the component's layout file.
+        file: PathBuf,
+    },
+}
+
+impl WidgetType {
+    /// Get the name shown for this widget type in the tree view and outlines.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            WidgetType::ComponentRef { .. } => "Component",
+            _ => self.type_name(),
+        }
+    }
+
+    /// Get the variant name of this widget type.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            WidgetType::Column { .. } => "Column",
+            WidgetType::Row { .. } => "Row",
+            WidgetType::Container { .. } => "Container",
+            WidgetType::Scrollable { .. } => "Scrollable",
+            WidgetType::Stack { .. } => "Stack",
+            WidgetType::Text { .. } => "Text",
+            WidgetType::Button { .. } => "Button",
+            WidgetType::TextInput { .. } => "TextInput",
+            WidgetType::Checkbox { .. } => "Checkbox",
+            WidgetType::Slider { .. } => "Slider",
+            WidgetType::PickList { .. } => "PickList",
+            WidgetType::Space { .. } => "Space",
+            WidgetType::ComponentRef { .. } => "ComponentRef",
+        }
+    }
+
+    /// Get the widget's message stub and the payload it carries, if it
+    /// produces messages and the stub is set.
+    pub fn message(&self) -> Option<(&str, MessagePayload)> {
+        let (stub, payload) = match self {
+            WidgetType::Button { message_stub, .. } => (message_stub, MessagePayload::None),
+            WidgetType::TextInput { message_stub, .. } => (message_stub, MessagePayload::String),
+            WidgetType::Checkbox { message_stub, .. } => (message_stub, MessagePayload::Bool),
+            WidgetType::Slider { message_stub, .. } => (message_stub, MessagePayload::F32),
+            WidgetType::PickList { message_stub, .. } => (message_stub, MessagePayload::String),
+            _ => return None,
+        };
+        (!stub.is_empty()).then_some((stub.as_str(), payload))
+    }
+
+    /// Get the state field this widget reads its value from, if any.
+    pub fn value_binding(&self) -> Option<&str> {
+        let binding = match self {
+            WidgetType::TextInput { value_binding, .. } | WidgetType::Slider { value_binding, .. } => value_binding,
+            WidgetType::Checkbox { checked_binding, .. } => checked_binding,
+            WidgetType::PickList { selected_binding, .. } => selected_binding,
+            _ => return None,
+        };
+        (!binding.is_empty()).then_some(binding.as_str())
+    }
+
+    /// Get the width and height settings of this widget, named by field.
+    pub fn lengths(&self) -> Vec<(&'static str, LengthSpec)> {
+        match self {
+            WidgetType::Column { attrs, .. }
+            | WidgetType::Row { attrs, .. }
+            | WidgetType::Container { attrs, .. }
+            | WidgetType::Scrollable { attrs, .. }
+            | WidgetType::Stack { attrs, .. } => vec![("width", attrs.width), ("height", attrs.height)],
+            WidgetType::Button { attrs, .. } => vec![("width", attrs.width), ("height", attrs.height)],
+            WidgetType::TextInput { attrs, .. } => vec![("width", attrs.width)],
+            WidgetType::Slider { attrs, .. } => vec![("width", attrs.width)],
+            WidgetType::PickList { attrs, .. } => vec![("width", attrs.width)],
+            WidgetType::Space { width, height } => vec![("width", *width), ("height", *height)],
+            WidgetType::Text { .. } | WidgetType::Checkbox { .. } | WidgetType::ComponentRef { .. } => Vec::new(),
+        }
+    }
+
+    /// Get the Rust type of the state field this widget binds to, if it has
+    /// a binding.
+    pub fn binding_type(&self) -> Option<&'static str> {
+        match self {
+            WidgetType::TextInput { .. } => Some("String"),
+            WidgetType::Checkbox { .. } => Some("bool"),
+            WidgetType::Slider { .. } => Some("f32"),
+            WidgetType::PickList { .. } => Some("Option<String>"),
+            _ => None,
+        }
+    }
+
+    /// Get the name of a referenced component, taken from its file stem.
+    pub fn component_name(&self) -> Option<&str> {
+        match self {
+            WidgetType::ComponentRef { file } => file.file_stem().and_then(|s| s.to_str()),
+            _ => None,
+        }
+    }
+}
+
+/// The value a widget's message carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessagePayload {
+    /// No payload, as for button presses.
+    None,
+    /// Text, as for text inputs and pick lists.
+    String,
+    /// A number, as for sliders.
+    F32,
+    /// A flag, as for checkboxes.
+    Bool,
+}
+
+impl MessagePayload {
+    /// Get the Rust type of the payload, or None for payload-free messages.
+    pub fn rust_type(&self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::String => Some("String"),
+            Self::F32 => Some("f32"),
+            Self::Bool => Some("bool"),
+        }
+    }
+
+    /// Get a readable description of the payload.
+    pub fn describe(&self) -> &'static str {
+        self.rust_type().unwrap_or("no payload")
+    }
+}
+
+/// A message stub used by a widget in a layout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageUse<'a> {
+    /// Path to the widget, as in validation errors.
+    pub path: String,
+    /// The widget's ComponentId.
+    pub node_id: ComponentId,
+    /// The message variant name.
+    pub stub: &'a str,
+    /// The payload the widget sends with it.
+    pub payload: MessagePayload,
+    /// The widget sending the message.
+    pub widget: &'a WidgetType,
 }
 
 /// A complete layout document that can be saved/loaded.
@@ -320,7 +747,7 @@ pub struct LayoutDocument {
 impl Default for LayoutDocument {
     fn default() -> Self {
         Self {
-            version: 1,
+            version: LAYOUT_VERSION,
             name: String::from("Untitled"),
             root: LayoutNode::new(WidgetType::Column {
                 children: Vec::new(),
@@ -343,11 +770,47 @@ pub enum ValidationSeverity {
     Warning,
 }
 
+/// Fixed sizes above this many pixels are reported as likely typos.
+pub const MAX_REASONABLE_FIXED: f32 = 10_000.0;
+
+/// Slider ranges wider than this are reported as likely typos.
+pub const MAX_REASONABLE_SLIDER_SPAN: f32 = 1_000_000.0;
+
+/// Default nesting depth past which validation warns.
+pub const DEFAULT_MAX_DEPTH: usize = 12;
+
+/// Project settings for layout validation, the `[validation]` table of the
+/// project config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationConfig {
+    /// Nesting depth past which a warning is shown, where the root alone is
+    /// depth 1.
+    #[serde(default = "default_max_depth")]
+    pub max_depth: usize,
+    /// Accept keyword bindings such as `type`, which export writes as raw
+    /// identifiers (`r#type`), instead of reporting them as errors.
+    #[serde(default)]
+    pub raw_identifiers: bool,
+}
+
+fn default_max_depth() -> usize {
+    DEFAULT_MAX_DEPTH
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_DEPTH,
+            raw_identifiers: false,
+        }
+    }
+}
+
 /// A validation error or warning found in the layout tree.
 #[derive(Debug, Clone, Error)]
 #[error("{severity:?} at {path}: {message}")]
 pub struct ValidationError {
-    /// The path to the node with the issue (e.g., "root.children[2].child").
+    /// The path to the node with the issue (e.g. `root.children[2].child`).
     pub path: String,
     /// The severity of the issue.
     pub severity: ValidationSeverity,
@@ -355,6 +818,9 @@ pub struct ValidationError {
     pub message: String,
     /// The ComponentId of the node with the issue.
     pub node_id: ComponentId,
+    /// Path and ComponentId of another node involved, such as the first
+    /// widget using a duplicated binding.
+    pub related: Option<(String, ComponentId)>,
 }
 
 impl ValidationError {
@@ -365,6 +831,7 @@ impl ValidationError {
             severity: ValidationSeverity::Error,
             message: message.into(),
             node_id,
+            related: None,
         }
     }
 
@@ -375,8 +842,15 @@ impl ValidationError {
             severity: ValidationSeverity::Warning,
             message: message.into(),
             node_id,
+            related: None,
         }
     }
+
+    /// Point at another node involved in the issue.
+    pub fn with_related(mut self, path: impl Into<String>, node_id: ComponentId) -> Self {
+        self.related = Some((path.into(), node_id));
+        self
+    }
 }
 
 impl LayoutNode {
@@ -384,12 +858,33 @@ impl LayoutNode {
     ///
     /// Returns a list of validation errors and warnings.
     pub fn validate(&self) -> Vec<ValidationError> {
+        self.validate_with(&ValidationConfig::default())
+    }
+
+    /// Validate this node and its children with the given settings.
+    pub fn validate_with(&self, config: &ValidationConfig) -> Vec<ValidationError> {
         let mut errors = Vec::new();
-        self.validate_recursive("root", &mut errors);
+        self.validate_recursive("root", 1, config, &mut errors);
         errors
     }
 
-    fn validate_recursive(&self, path: &str, errors: &mut Vec<ValidationError>) {
+    fn validate_recursive(&self, path: &str, depth: usize, config: &ValidationConfig, errors: &mut Vec<ValidationError>) {
+        // Excluded subtrees are never generated, so their problems don't matter
+        if self.exclude_from_export {
+            return;
+        }
+
+        // Only the first node past the limit is reported, not its whole subtree
+        if depth == config.max_depth + 1 {
+            errors.push(ValidationError::warning(
+                path,
+                format!("Nested {} levels deep, past the limit of {}", depth, config.max_depth),
+                self.id,
+            ));
+        }
+
+        self.validate_lengths(path, errors);
+
         // Check widget-specific constraints
         match &self.widget {
             // Multi-child containers
@@ -403,17 +898,22 @@ impl LayoutNode {
                         self.id,
                     ));
                 }
-                for (i, child) in children.iter().enumerate() {
-                    let child_path = format!("{}.children[{}]", path, i);
-                    child.validate_recursive(&child_path, errors);
-                }
             }
 
             // Single-child containers
-            WidgetType::Container { child, .. } | WidgetType::Scrollable { child, .. } => {
+            WidgetType::Container { child, attrs } | WidgetType::Scrollable { child, attrs } => {
                 if let Some(c) = child {
-                    let child_path = format!("{}.child", path);
-                    c.validate_recursive(&child_path, errors);
+                    if let (WidgetType::Container { .. }, WidgetType::Container { attrs: inner, .. }) =
+                        (&self.widget, &c.widget)
+                    {
+                        if inner == attrs {
+                            errors.push(ValidationError::warning(
+                                path,
+                                "Container only wraps another Container with the same settings; unwrap one",
+                                self.id,
+                            ));
+                        }
+                    }
                 } else {
                     errors.push(ValidationError::warning(
                         path,
@@ -431,7 +931,7 @@ impl LayoutNode {
             }
             WidgetType::TextInput { value_binding, message_stub, .. } => {
                 if !value_binding.is_empty() {
-                    self.validate_identifier(path, "value_binding", value_binding, errors);
+                    self.validate_binding(path, "value_binding", value_binding, config, errors);
                 }
                 if !message_stub.is_empty() {
                     self.validate_identifier(path, "message_stub", message_stub, errors);
@@ -439,32 +939,127 @@ impl LayoutNode {
             }
             WidgetType::Checkbox { checked_binding, message_stub, .. } => {
                 if !checked_binding.is_empty() {
-                    self.validate_identifier(path, "checked_binding", checked_binding, errors);
+                    self.validate_binding(path, "checked_binding", checked_binding, config, errors);
                 }
                 if !message_stub.is_empty() {
                     self.validate_identifier(path, "message_stub", message_stub, errors);
                 }
             }
-            WidgetType::Slider { value_binding, message_stub, .. } => {
+            WidgetType::Slider { min, max, value_binding, message_stub, .. } => {
+                if min.is_nan() || max.is_nan() || min >= max {
+                    errors.push(ValidationError::error(
+                        path,
+                        format!("Slider range {}..={} is empty; min must be less than max", min, max),
+                        self.id,
+                    ));
+                } else if max - min > MAX_REASONABLE_SLIDER_SPAN {
+                    errors.push(ValidationError::warning(
+                        path,
+                        format!("Slider range {}..={} spans more than {}", min, max, MAX_REASONABLE_SLIDER_SPAN),
+                        self.id,
+                    ));
+                }
                 if !value_binding.is_empty() {
-                    self.validate_identifier(path, "value_binding", value_binding, errors);
+                    self.validate_binding(path, "value_binding", value_binding, config, errors);
                 }
                 if !message_stub.is_empty() {
                     self.validate_identifier(path, "message_stub", message_stub, errors);
                 }
             }
-            WidgetType::PickList { selected_binding, message_stub, .. } => {
+            WidgetType::PickList { options, selected_binding, message_stub, .. } => {
+                self.validate_options(path, options, errors);
                 if !selected_binding.is_empty() {
-                    self.validate_identifier(path, "selected_binding", selected_binding, errors);
+                    self.validate_binding(path, "selected_binding", selected_binding, config, errors);
                 }
                 if !message_stub.is_empty() {
                     self.validate_identifier(path, "message_stub", message_stub, errors);
                 }
             }
 
+            // Component references become calls to the component's module
+            WidgetType::ComponentRef { .. } => {
+                let module = self.widget.component_name().unwrap_or_default();
+                self.validate_identifier(path, "component", module, errors);
+            }
+
             // Leaf widgets without special validation
             WidgetType::Text { .. } | WidgetType::Space { .. } => {}
         }
+
+        for (i, child) in self.child_nodes().into_iter().enumerate() {
+            child.validate_recursive(&self.child_path(path, i), depth + 1, config, errors);
+        }
+    }
+
+    fn validate_lengths(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        for (field, length) in self.widget.lengths() {
+            match length {
+                LengthSpec::Fixed(v) if v.is_nan() || v <= 0.0 => errors.push(ValidationError::error(
+                    path,
+                    format!("{} is Fixed({}), but sizes must be positive", field, v),
+                    self.id,
+                )),
+                LengthSpec::Fixed(v) if v > MAX_REASONABLE_FIXED => errors.push(ValidationError::warning(
+                    path,
+                    format!("{} is Fixed({}), more than {} pixels", field, v, MAX_REASONABLE_FIXED),
+                    self.id,
+                )),
+                LengthSpec::FillPortion(0) => errors.push(ValidationError::error(
+                    path,
+                    format!("{} is FillPortion(0), which takes no space; use 1 or more", field),
+                    self.id,
+                )),
+                _ => {}
+            }
+        }
+    }
+
+    /// Check the options of a choice widget: none at all renders an empty
+    /// dropdown, and blank or repeated options are ambiguous.
+    fn validate_options(&self, path: &str, options: &[String], errors: &mut Vec<ValidationError>) {
+        if options.is_empty() {
+            errors.push(ValidationError::error(path, "No options to choose from", self.id));
+            return;
+        }
+        for (i, option) in options.iter().enumerate() {
+            if option.trim().is_empty() {
+                errors.push(ValidationError::warning(
+                    path,
+                    format!("Option {} is blank", i + 1),
+                    self.id,
+                ));
+            } else if options[..i].contains(option) && !options[i + 1..].contains(option) {
+                errors.push(ValidationError::warning(
+                    path,
+                    format!("Option '{}' is listed more than once", option),
+                    self.id,
+                ));
+            }
+        }
+    }
+
+    /// Bindings become state fields, which can be raw identifiers when the
+    /// project allows it.
+    fn validate_binding(
+        &self,
+        path: &str,
+        field: &str,
+        value: &str,
+        config: &ValidationConfig,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        if !is_raw_identifier_keyword(value) {
+            self.validate_identifier(path, field, value, errors);
+        } else if !config.raw_identifiers {
+            errors.push(ValidationError::error(
+                path,
+                format!(
+                    "{} '{}' is a Rust keyword; rename it or set raw_identifiers under [validation] to export it as r#{}",
+                    field, value, value
+                ),
+                self.id,
+            ));
+        }
     }
 
     fn validate_identifier(&self, path: &str, field: &str, value: &str, errors: &mut Vec<ValidationError>) {
@@ -487,7 +1082,150 @@ impl LayoutNode {
 impl LayoutDocument {
     /// Validate the entire document.
     pub fn validate(&self) -> Vec<ValidationError> {
-        self.root.validate()
+        self.validate_with(&ValidationConfig::default())
+    }
+
+    /// Validate the entire document with the given settings.
+    pub fn validate_with(&self, config: &ValidationConfig) -> Vec<ValidationError> {
+        let mut errors = self.root.validate_with(config);
+        errors.extend(self.message_conflicts());
+        errors.extend(self.duplicate_uses());
+        errors
+    }
+
+    /// Collect the message stubs used in exported parts of the tree, in
+    /// document order.
+    pub fn message_uses(&self) -> Vec<MessageUse<'_>> {
+        self.exported_paths()
+            .into_iter()
+            .filter_map(|(path, node)| {
+                let (stub, payload) = node.widget.message()?;
+                Some(MessageUse {
+                    path,
+                    node_id: node.id,
+                    stub,
+                    payload,
+                    widget: &node.widget,
+                })
+            })
+            .collect()
+    }
+
+    /// Get the exported nodes with their paths, in document order.
+    pub fn exported_paths(&self) -> Vec<(String, &LayoutNode)> {
+        fn walk<'a>(node: &'a LayoutNode, path: String, nodes: &mut Vec<(String, &'a LayoutNode)>) {
+            if node.exclude_from_export {
+                return;
+            }
+            nodes.push((path.clone(), node));
+            for (i, child) in node.child_nodes().into_iter().enumerate() {
+                walk(child, node.child_path(&path, i), nodes);
+            }
+        }
+
+        let mut nodes = Vec::new();
+        walk(&self.root, "root".to_string(), &mut nodes);
+        nodes
+    }
+
+    /// Get the nodes that export generates code for, in document order.
+    pub fn exported_nodes(&self) -> Vec<&LayoutNode> {
+        fn walk<'a>(node: &'a LayoutNode, nodes: &mut Vec<&'a LayoutNode>) {
+            if node.exclude_from_export {
+                return;
+            }
+            nodes.push(node);
+            for child in node.child_nodes() {
+                walk(child, nodes);
+            }
+        }
+
+        let mut nodes = Vec::new();
+        walk(&self.root, &mut nodes);
+        nodes
+    }
+
+    /// Report message stubs used with different payload types, which
+    /// cannot become a single enum variant.
+    pub fn message_conflicts(&self) -> Vec<ValidationError> {
+        let mut first_use: HashMap<&str, MessageUse<'_>> = HashMap::new();
+        let mut errors = Vec::new();
+        for message in self.message_uses() {
+            match first_use.get(message.stub) {
+                Some(first) if first.payload != message.payload => {
+                    errors.push(ValidationError::error(
+                        &message.path,
+                        format!(
+                            "message_stub '{}' carries {} here but {} at {}",
+                            message.stub,
+                            message.payload.describe(),
+                            first.payload.describe(),
+                            first.path
+                        ),
+                        message.node_id,
+                    )
+                    .with_related(&first.path, first.node_id));
+                }
+                Some(_) => {}
+                None => {
+                    first_use.insert(message.stub, message);
+                }
+            }
+        }
+        errors
+    }
+
+    /// Report bindings and message stubs shared by several widgets.
+    ///
+    /// Sharing is sometimes intended, so it is only a warning, but a binding
+    /// shared by widgets that need different field types is an error.
+    pub fn duplicate_uses(&self) -> Vec<ValidationError> {
+        let mut bindings: HashMap<&str, (String, ComponentId, &'static str)> = HashMap::new();
+        let mut stubs: HashMap<&str, (String, ComponentId, MessagePayload)> = HashMap::new();
+        let mut errors = Vec::new();
+        for (path, node) in self.exported_paths() {
+            if let (Some(binding), Some(ty)) = (node.widget.value_binding(), node.widget.binding_type()) {
+                match bindings.get(binding) {
+                    Some((first_path, first_id, first_ty)) if *first_ty != ty => errors.push(
+                        ValidationError::error(
+                            &path,
+                            format!("binding '{}' needs {} here but {} at {}", binding, ty, first_ty, first_path),
+                            node.id,
+                        )
+                        .with_related(first_path, *first_id),
+                    ),
+                    Some((first_path, first_id, _)) => errors.push(
+                        ValidationError::warning(
+                            &path,
+                            format!("binding '{}' is also used at {}", binding, first_path),
+                            node.id,
+                        )
+                        .with_related(first_path, *first_id),
+                    ),
+                    None => {
+                        bindings.insert(binding, (path.clone(), node.id, ty));
+                    }
+                }
+            }
+            if let Some((stub, payload)) = node.widget.message() {
+                match stubs.get(stub) {
+                    // Different payloads are reported by message_conflicts
+                    Some((first_path, first_id, first_payload)) if *first_payload == payload => errors.push(
+                        ValidationError::warning(
+                            &path,
+                            format!("message_stub '{}' is also sent at {}", stub, first_path),
+                            node.id,
+                        )
+                        .with_related(first_path, *first_id),
+                    ),
+                    Some(_) => {}
+                    None => {
+                        stubs.insert(stub, (path.clone(), node.id, payload));
+                    }
+                }
+            }
+        }
+        errors
     }
 
     /// Check if the document has any validation errors (not just warnings).
@@ -498,6 +1236,79 @@ impl LayoutDocument {
     }
 }
 
+// ============================================================================
+// Statistics
+// ============================================================================
+
+/// Size and complexity figures for a layout document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LayoutStats {
+    /// Total number of nodes, including the root.
+    pub node_count: usize,
+    /// Number of nodes of each widget type, keyed by variant name.
+    pub type_counts: BTreeMap<&'static str, usize>,
+    /// Deepest nesting level, where the root alone is depth 1.
+    pub max_depth: usize,
+    /// Containers holding more than [`LayoutStats::WIDE_CONTAINER_CHILDREN`] children.
+    pub wide_containers: usize,
+    /// Interactive widgets whose message stub is empty.
+    pub empty_message_stubs: usize,
+}
+
+impl LayoutStats {
+    /// Child count above which a container is reported as wide.
+    pub const WIDE_CONTAINER_CHILDREN: usize = 10;
+
+    fn collect(&mut self, node: &LayoutNode, depth: usize) {
+        self.node_count += 1;
+        *self.type_counts.entry(node.widget.type_name()).or_default() += 1;
+        self.max_depth = self.max_depth.max(depth);
+
+        if node.children().is_some_and(|children| children.len() > Self::WIDE_CONTAINER_CHILDREN) {
+            self.wide_containers += 1;
+        }
+        for child in node.child_nodes() {
+            self.collect(child, depth + 1);
+        }
+
+        if let WidgetType::Button { message_stub, .. }
+        | WidgetType::TextInput { message_stub, .. }
+        | WidgetType::Checkbox { message_stub, .. }
+        | WidgetType::Slider { message_stub, .. }
+        | WidgetType::PickList { message_stub, .. } = &node.widget
+        {
+            if message_stub.is_empty() {
+                self.empty_message_stubs += 1;
+            }
+        }
+    }
+}
+
+impl fmt::Display for LayoutStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let types = self
+            .type_counts
+            .iter()
+            .map(|(name, count)| format!("{}: {}", name, count))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(
+            f,
+            "{} nodes ({}), max depth {}, {} wide containers, {} empty message stubs",
+            self.node_count, types, self.max_depth, self.wide_containers, self.empty_message_stubs
+        )
+    }
+}
+
+impl LayoutDocument {
+    /// Compute size and complexity statistics for the whole tree.
+    pub fn stats(&self) -> LayoutStats {
+        let mut stats = LayoutStats::default();
+        stats.collect(&self.root, 1);
+        stats
+    }
+}
+
 /// Index for O(1) node lookup by ComponentId.
 pub type NodeIndex = HashMap<ComponentId, Vec<usize>>;
 
@@ -508,26 +1319,70 @@ pub fn build_node_index(root: &LayoutNode) -> NodeIndex {
     index
 }
 
-fn build_index_recursive(node: &LayoutNode, path: &mut Vec<usize>, index: &mut NodeIndex) {
-    index.insert(node.id, path.clone());
+/// Update `index` after the children of `parent` from `from` on changed
+/// position, as after inserting or removing the child at `from`. Only those
+/// children's subtrees are visited.
+pub fn reindex_children_from(index: &mut NodeIndex, parent: &LayoutNode, parent_path: &[usize], from: usize) {
+    let mut path = parent_path.to_vec();
+    for (i, child) in parent.child_nodes().into_iter().enumerate().skip(from) {
+        path.push(i);
+        build_index_recursive(child, &mut path, index);
+        path.pop();
+    }
+}
 
-    if let Some(children) = node.children() {
-        for (i, child) in children.iter().enumerate() {
-            path.push(i);
-            build_index_recursive(child, path, index);
-            path.pop();
-        }
+/// Remove `node` and its descendants from `index`.
+pub fn unindex_subtree(index: &mut NodeIndex, node: &LayoutNode) {
+    index.remove(&node.id);
+    for child in node.child_nodes() {
+        unindex_subtree(index, child);
+    }
+}
+
+/// Revision of every subtree, for caches of anything derived from one.
+///
+/// A node's revision changes whenever the node or anything below it does.
+/// Nodes untouched since the last wholesale change, such as a load or an
+/// undo, share the base revision.
+#[derive(Debug, Clone, Default)]
+pub struct SubtreeRevisions {
+    base: u64,
+    changed: HashMap<ComponentId, u64>,
+}
+
+impl SubtreeRevisions {
+    /// Get the revision of the subtree rooted at `id`.
+    pub fn get(&self, id: ComponentId) -> u64 {
+        self.changed.get(&id).copied().unwrap_or(self.base)
     }
 
-    // Handle single-child containers
-    match &node.widget {
-        WidgetType::Container { child: Some(c), .. }
-        | WidgetType::Scrollable { child: Some(c), .. } => {
-            path.push(0);
-            build_index_recursive(c, path, index);
-            path.pop();
+    /// Every subtree changed; they now all have `revision`.
+    pub fn reset(&mut self, revision: u64) {
+        self.base = revision;
+        self.changed.clear();
+    }
+
+    /// The node at `path` changed, so it and every node above it now have
+    /// `revision`.
+    pub fn touch(&mut self, root: &LayoutNode, path: &[usize], revision: u64) {
+        let mut node = root;
+        self.changed.insert(node.id, revision);
+        for &i in path {
+            let Some(child) = node.child_nodes().get(i).copied() else {
+                return;
+            };
+            node = child;
+            self.changed.insert(node.id, revision);
         }
-        _ => {}
+    }
+}
+
+fn build_index_recursive(node: &LayoutNode, path: &mut Vec<usize>, index: &mut NodeIndex) {
+    index.insert(node.id, path.clone());
+    for (i, child) in node.child_nodes().into_iter().enumerate() {
+        path.push(i);
+        build_index_recursive(child, path, index);
+        path.pop();
     }
 }
 
@@ -551,10 +1406,52 @@ mod tests {
         assert!(display.contains('-'));
     }
 
+    #[test]
+    fn test_can_accept_child() {
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Hi".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        assert!(!text.can_accept_child());
+
+        let column = LayoutNode::new(WidgetType::Column {
+            children: vec![text.clone()],
+            attrs: ContainerAttrs::default(),
+        });
+        assert!(column.can_accept_child());
+
+        let empty = LayoutNode::new(WidgetType::Container {
+            child: None,
+            attrs: ContainerAttrs::default(),
+        });
+        assert!(empty.can_accept_child());
+
+        let full = LayoutNode::new(WidgetType::Container {
+            child: Some(Box::new(text)),
+            attrs: ContainerAttrs::default(),
+        });
+        assert!(!full.can_accept_child());
+    }
+
+    #[test]
+    fn test_node_name_is_optional_in_files() {
+        let mut node = LayoutNode::new(WidgetType::Text {
+            content: "Hi".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        let unnamed = ron::to_string(&node).unwrap();
+        assert!(!unnamed.contains("name"));
+        assert_eq!(ron::from_str::<LayoutNode>(&unnamed).unwrap(), node);
+
+        node.name = Some("Greeting".to_string());
+        let named = ron::to_string(&node).unwrap();
+        assert_eq!(ron::from_str::<LayoutNode>(&named).unwrap().name.as_deref(), Some("Greeting"));
+    }
+
     #[test]
     fn test_layout_document_default() {
         let doc = LayoutDocument::default();
-        assert_eq!(doc.version, 1);
+        assert_eq!(doc.version, LAYOUT_VERSION);
         assert_eq!(doc.name, "Untitled");
     }
 
@@ -586,12 +1483,14 @@ mod tests {
 
     #[test]
     fn test_validate_valid_identifier() {
-        let mut doc = LayoutDocument::default();
-        doc.root = LayoutNode::new(WidgetType::Button {
-            label: "Click me".to_string(),
-            message_stub: "handle_click".to_string(),
-            attrs: ButtonAttrs::default(),
-        });
+        let doc = LayoutDocument {
+            root: LayoutNode::new(WidgetType::Button {
+                label: "Click me".to_string(),
+                message_stub: "handle_click".to_string(),
+                attrs: ButtonAttrs::default(),
+            }),
+            ..Default::default()
+        };
         let errors = doc.validate();
         // Valid identifier, no errors
         assert!(errors.is_empty());
@@ -599,12 +1498,14 @@ mod tests {
 
     #[test]
     fn test_validate_invalid_identifier() {
-        let mut doc = LayoutDocument::default();
-        doc.root = LayoutNode::new(WidgetType::Button {
-            label: "Click me".to_string(),
-            message_stub: "123-invalid".to_string(),
-            attrs: ButtonAttrs::default(),
-        });
+        let doc = LayoutDocument {
+            root: LayoutNode::new(WidgetType::Button {
+                label: "Click me".to_string(),
+                message_stub: "123-invalid".to_string(),
+                attrs: ButtonAttrs::default(),
+            }),
+            ..Default::default()
+        };
         let errors = doc.validate();
         assert_eq!(errors.len(), 1);
         assert_eq!(errors[0].severity, ValidationSeverity::Error);
@@ -613,12 +1514,14 @@ mod tests {
 
     #[test]
     fn test_validate_rust_keyword() {
-        let mut doc = LayoutDocument::default();
-        doc.root = LayoutNode::new(WidgetType::Button {
-            label: "Click me".to_string(),
-            message_stub: "fn".to_string(),
-            attrs: ButtonAttrs::default(),
-        });
+        let doc = LayoutDocument {
+            root: LayoutNode::new(WidgetType::Button {
+                label: "Click me".to_string(),
+                message_stub: "fn".to_string(),
+                attrs: ButtonAttrs::default(),
+            }),
+            ..Default::default()
+        };
         let errors = doc.validate();
         assert_eq!(errors.len(), 1);
         assert_eq!(errors[0].severity, ValidationSeverity::Error);
@@ -626,17 +1529,202 @@ mod tests {
     }
 
     #[test]
-    fn test_validate_nested_containers() {
-        let mut doc = LayoutDocument::default();
-        doc.root = LayoutNode::new(WidgetType::Column {
-            children: vec![
-                LayoutNode::new(WidgetType::Row {
-                    children: vec![],
-                    attrs: ContainerAttrs::default(),
-                }),
-            ],
+    fn test_validate_keyword_bindings() {
+        let input = |binding: &str| {
+            LayoutNode::new(WidgetType::TextInput {
+                placeholder: String::new(),
+                value_binding: binding.to_string(),
+                message_stub: "Changed".to_string(),
+                attrs: InputAttrs::default(),
+            })
+        };
+        let raw = ValidationConfig {
+            raw_identifiers: true,
+            ..Default::default()
+        };
+
+        let errors = input("type").validate();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("Rust keyword"));
+        assert!(errors[0].message.contains("r#type"));
+        assert!(input("type").validate_with(&raw).is_empty());
+        assert!(input("yield").validate_with(&raw).is_empty());
+        assert!(input("union").validate().is_empty());
+        // Path keywords have no raw form
+        assert_eq!(input("self").validate_with(&raw).len(), 1);
+    }
+
+    #[test]
+    fn test_validate_skips_excluded_subtrees() {
+        let mut draft = LayoutNode::new(WidgetType::Button {
+            label: "Draft".to_string(),
+            message_stub: "not valid".to_string(),
+            attrs: ButtonAttrs::default(),
+        });
+        draft.exclude_from_export = true;
+        let column = LayoutNode::new(WidgetType::Column {
+            children: vec![draft.clone()],
             attrs: ContainerAttrs::default(),
         });
+        assert!(column.validate().is_empty());
+
+        draft.exclude_from_export = false;
+        assert!(!draft.validate().is_empty());
+    }
+
+    #[test]
+    fn test_validate_nesting_depth() {
+        // 14 nested containers around a text: the text is at depth 15
+        let mut node = LayoutNode::new(WidgetType::Text {
+            content: "Deep".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        for i in 0..14 {
+            node = LayoutNode::new(WidgetType::Container {
+                child: Some(Box::new(node)),
+                attrs: ContainerAttrs {
+                    // Distinct settings, so none is a redundant wrap
+                    spacing: i as f32,
+                    ..Default::default()
+                },
+            });
+        }
+        let depth_warnings = |config: &ValidationConfig| -> Vec<String> {
+            node.validate_with(config)
+                .into_iter()
+                .filter(|e| e.message.starts_with("Nested"))
+                .map(|e| e.path)
+                .collect()
+        };
+
+        let first_over = format!("root{}", ".child".repeat(12));
+        assert_eq!(depth_warnings(&ValidationConfig::default()), [first_over]);
+        assert_eq!(depth_warnings(&ValidationConfig { max_depth: 2, ..Default::default() }), ["root.child.child"]);
+        assert!(depth_warnings(&ValidationConfig { max_depth: 15, ..Default::default() }).is_empty());
+    }
+
+    #[test]
+    fn test_validate_redundant_container_wrap() {
+        let wrap = |child: LayoutNode, spacing: f32| {
+            LayoutNode::new(WidgetType::Container {
+                child: Some(Box::new(child)),
+                attrs: ContainerAttrs {
+                    spacing,
+                    ..Default::default()
+                },
+            })
+        };
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Hi".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        let redundant = |node: &LayoutNode| -> Vec<String> {
+            node.validate()
+                .into_iter()
+                .filter(|e| e.message.contains("unwrap"))
+                .map(|e| e.path)
+                .collect()
+        };
+
+        assert_eq!(redundant(&wrap(wrap(text.clone(), 4.0), 4.0)), ["root"]);
+        assert!(redundant(&wrap(wrap(text, 4.0), 8.0)).is_empty());
+    }
+
+    #[test]
+    fn test_validate_lengths() {
+        let space = |width: LengthSpec, height: LengthSpec| LayoutNode::new(WidgetType::Space { width, height });
+        let issues = |node: &LayoutNode| -> Vec<(ValidationSeverity, String)> {
+            node.validate().into_iter().map(|e| (e.severity, e.message)).collect()
+        };
+
+        assert!(issues(&space(LengthSpec::Fixed(20.0), LengthSpec::FillPortion(1))).is_empty());
+        let bad = issues(&space(LengthSpec::Fixed(-20.0), LengthSpec::FillPortion(0)));
+        assert_eq!(bad.len(), 2);
+        assert!(bad.iter().all(|(severity, _)| *severity == ValidationSeverity::Error));
+        assert!(bad[0].1.starts_with("width is Fixed(-20)"));
+        assert!(bad[1].1.starts_with("height is FillPortion(0)"));
+        assert_eq!(issues(&space(LengthSpec::Fixed(0.0), LengthSpec::Shrink))[0].0, ValidationSeverity::Error);
+        assert_eq!(issues(&space(LengthSpec::Fixed(f32::NAN), LengthSpec::Shrink))[0].0, ValidationSeverity::Error);
+
+        let huge = issues(&space(LengthSpec::Shrink, LengthSpec::Fixed(12_000.0)));
+        assert_eq!(huge.len(), 1);
+        assert_eq!(huge[0].0, ValidationSeverity::Warning);
+
+        let button = LayoutNode::new(WidgetType::Button {
+            label: "Go".to_string(),
+            message_stub: "Go".to_string(),
+            attrs: ButtonAttrs {
+                height: LengthSpec::FillPortion(0),
+                ..Default::default()
+            },
+        });
+        assert_eq!(issues(&button).len(), 1);
+    }
+
+    #[test]
+    fn test_validate_slider_range() {
+        let slider = |min: f32, max: f32| {
+            LayoutNode::new(WidgetType::Slider {
+                min,
+                max,
+                value_binding: "volume".to_string(),
+                message_stub: "VolumeChanged".to_string(),
+                attrs: SliderAttrs::default(),
+            })
+        };
+        let severities = |node: &LayoutNode| -> Vec<ValidationSeverity> {
+            node.validate().into_iter().map(|e| e.severity).collect()
+        };
+
+        assert!(severities(&slider(0.0, 100.0)).is_empty());
+        assert_eq!(severities(&slider(5.0, 5.0)), [ValidationSeverity::Error]);
+        assert_eq!(severities(&slider(10.0, -10.0)), [ValidationSeverity::Error]);
+        assert_eq!(severities(&slider(f32::NAN, 1.0)), [ValidationSeverity::Error]);
+        assert_eq!(severities(&slider(0.0, 2_000_000.0)), [ValidationSeverity::Warning]);
+    }
+
+    #[test]
+    fn test_validate_pick_list_options() {
+        let pick_list = |options: &[&str]| {
+            LayoutNode::new(WidgetType::PickList {
+                options: options.iter().map(|o| o.to_string()).collect(),
+                selected_binding: "choice".to_string(),
+                message_stub: "Picked".to_string(),
+                attrs: PickListAttrs::default(),
+            })
+        };
+        let issues = |node: &LayoutNode| -> Vec<(ValidationSeverity, String)> {
+            node.validate().into_iter().map(|e| (e.severity, e.message)).collect()
+        };
+
+        assert!(issues(&pick_list(&["A", "B"])).is_empty());
+        assert_eq!(
+            issues(&pick_list(&[])),
+            [(ValidationSeverity::Error, "No options to choose from".to_string())]
+        );
+        assert_eq!(
+            issues(&pick_list(&["A", " ", "B", "A", "A"])),
+            [
+                (ValidationSeverity::Warning, "Option 2 is blank".to_string()),
+                (ValidationSeverity::Warning, "Option 'A' is listed more than once".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_nested_containers() {
+        let doc = LayoutDocument {
+            root: LayoutNode::new(WidgetType::Column {
+                children: vec![
+                    LayoutNode::new(WidgetType::Row {
+                        children: vec![],
+                        attrs: ContainerAttrs::default(),
+                    }),
+                ],
+                attrs: ContainerAttrs::default(),
+            }),
+            ..Default::default()
+        };
         let errors = doc.validate();
         // The nested Row has no children, should produce a warning
         assert_eq!(errors.len(), 1);
@@ -646,34 +1734,160 @@ mod tests {
 
     #[test]
     fn test_validate_text_input_bindings() {
-        let mut doc = LayoutDocument::default();
-        doc.root = LayoutNode::new(WidgetType::TextInput {
-            placeholder: "Enter text".to_string(),
-            value_binding: "user_input".to_string(),
-            message_stub: "on-change".to_string(), // Invalid! Contains hyphen
-            attrs: InputAttrs::default(),
-        });
+        let doc = LayoutDocument {
+            root: LayoutNode::new(WidgetType::TextInput {
+                placeholder: "Enter text".to_string(),
+                value_binding: "user_input".to_string(),
+                message_stub: "on-change".to_string(), // Invalid! Contains hyphen
+                attrs: InputAttrs::default(),
+            }),
+            ..Default::default()
+        };
         let errors = doc.validate();
         assert_eq!(errors.len(), 1);
         assert_eq!(errors[0].severity, ValidationSeverity::Error);
         assert!(errors[0].message.contains("message_stub"));
     }
 
+    #[test]
+    fn test_validate_conflicting_message_payloads() {
+        let mut doc = LayoutDocument::default();
+        if let WidgetType::Column { children, .. } = &mut doc.root.widget {
+            children.push(LayoutNode::new(WidgetType::TextInput {
+                placeholder: String::new(),
+                value_binding: "name".to_string(),
+                message_stub: "Changed".to_string(),
+                attrs: InputAttrs::default(),
+            }));
+            children.push(LayoutNode::new(WidgetType::PickList {
+                options: Vec::new(),
+                selected_binding: "choice".to_string(),
+                message_stub: "Changed".to_string(),
+                attrs: PickListAttrs::default(),
+            }));
+        }
+        assert!(doc.message_conflicts().is_empty());
+
+        // A checkbox sends bool, which cannot share the String variant
+        let checkbox = LayoutNode::new(WidgetType::Checkbox {
+            label: String::new(),
+            checked_binding: "agreed".to_string(),
+            message_stub: "Changed".to_string(),
+            attrs: CheckboxAttrs::default(),
+        });
+        let checkbox_id = checkbox.id;
+        doc.root.children_mut().unwrap().push(checkbox);
+
+        let conflicts = doc.message_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].node_id, checkbox_id);
+        assert_eq!(conflicts[0].path, "root.children[2]");
+        assert!(conflicts[0].message.contains("root.children[0]"));
+        assert_eq!(conflicts[0].related.as_ref().map(|(path, _)| path.as_str()), Some("root.children[0]"));
+        assert!(doc.has_errors());
+    }
+
+    #[test]
+    fn test_validate_duplicate_bindings_and_stubs() {
+        let input = || {
+            LayoutNode::new(WidgetType::TextInput {
+                placeholder: String::new(),
+                value_binding: "input_value".to_string(),
+                message_stub: "InputChanged".to_string(),
+                attrs: InputAttrs::default(),
+            })
+        };
+        let first = input();
+        let first_id = first.id;
+        let mut doc = LayoutDocument::default();
+        doc.root.children_mut().unwrap().extend([first, input()]);
+
+        // Sharing both is allowed, but flagged
+        let duplicates = doc.duplicate_uses();
+        assert_eq!(duplicates.len(), 2);
+        assert!(duplicates.iter().all(|d| d.severity == ValidationSeverity::Warning));
+        assert!(duplicates.iter().all(|d| d.path == "root.children[1]"));
+        assert!(duplicates.iter().all(|d| d.related == Some(("root.children[0]".to_string(), first_id))));
+        assert!(!doc.has_errors());
+
+        // A slider needs an f32 field, which cannot share a String one
+        let slider = LayoutNode::new(WidgetType::Slider {
+            min: 0.0,
+            max: 1.0,
+            value_binding: "input_value".to_string(),
+            message_stub: "Slid".to_string(),
+            attrs: SliderAttrs::default(),
+        });
+        doc.root.children_mut().unwrap().push(slider);
+        let errors: Vec<_> = doc
+            .duplicate_uses()
+            .into_iter()
+            .filter(|d| d.severity == ValidationSeverity::Error)
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "root.children[2]");
+        assert!(errors[0].message.contains("f32") && errors[0].message.contains("root.children[0]"));
+        assert_eq!(errors[0].related.as_ref().map(|(_, id)| *id), Some(first_id));
+        assert!(doc.has_errors());
+    }
+
     #[test]
     fn test_has_errors() {
         let doc = LayoutDocument::default();
         // Empty container only produces warnings, not errors
         assert!(!doc.has_errors());
 
-        let mut doc_with_error = LayoutDocument::default();
-        doc_with_error.root = LayoutNode::new(WidgetType::Button {
-            label: "Click".to_string(),
-            message_stub: "123bad".to_string(),
-            attrs: ButtonAttrs::default(),
-        });
+        let doc_with_error = LayoutDocument {
+            root: LayoutNode::new(WidgetType::Button {
+                label: "Click".to_string(),
+                message_stub: "123bad".to_string(),
+                attrs: ButtonAttrs::default(),
+            }),
+            ..Default::default()
+        };
         assert!(doc_with_error.has_errors());
     }
 
+    #[test]
+    fn test_subtree_revisions_touch_ancestors_only() {
+        let leaf = LayoutNode::new(WidgetType::Text {
+            content: "Leaf".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        let sibling = LayoutNode::new(WidgetType::Space {
+            width: LengthSpec::Shrink,
+            height: LengthSpec::Shrink,
+        });
+        let (leaf_id, sibling_id) = (leaf.id, sibling.id);
+        let inner = LayoutNode::new(WidgetType::Container {
+            child: Some(Box::new(leaf)),
+            attrs: ContainerAttrs::default(),
+        });
+        let inner_id = inner.id;
+        let mut root = LayoutNode::new(WidgetType::Column {
+            children: vec![sibling, inner],
+            attrs: ContainerAttrs::default(),
+        });
+        let root_id = root.id;
+
+        let mut revisions = SubtreeRevisions::default();
+        revisions.reset(3);
+        revisions.touch(&root, &[1, 0], 4);
+        assert_eq!(revisions.get(root_id), 4);
+        assert_eq!(revisions.get(inner_id), 4);
+        assert_eq!(revisions.get(leaf_id), 4);
+        assert_eq!(revisions.get(sibling_id), 3);
+
+        // A path that no longer leads anywhere stops at the last node found
+        root.children_mut().unwrap().truncate(1);
+        revisions.touch(&root, &[1, 0], 5);
+        assert_eq!(revisions.get(root_id), 5);
+        assert_eq!(revisions.get(inner_id), 4);
+
+        revisions.reset(6);
+        assert_eq!(revisions.get(leaf_id), 6);
+    }
+
     #[test]
     fn test_build_node_index() {
         let child1 = LayoutNode::new(WidgetType::Text {
@@ -730,6 +1944,58 @@ mod tests {
         assert_ne!(new_child_id, original_child_id);
     }
 
+    #[test]
+    fn test_descendant_count() {
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Hi".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        assert_eq!(text.descendant_count(), 0);
+
+        let empty = LayoutNode::new(WidgetType::Container {
+            child: None,
+            attrs: ContainerAttrs::default(),
+        });
+        assert_eq!(empty.descendant_count(), 0);
+
+        let wrapped = LayoutNode::new(WidgetType::Container {
+            child: Some(Box::new(text.clone())),
+            attrs: ContainerAttrs::default(),
+        });
+        assert_eq!(wrapped.descendant_count(), 1);
+
+        let column = LayoutNode::new(WidgetType::Column {
+            children: vec![text.clone(), wrapped, text],
+            attrs: ContainerAttrs::default(),
+        });
+        assert_eq!(column.descendant_count(), 4);
+    }
+
+    #[test]
+    fn test_outline_labels_and_padding() {
+        let mut text = LayoutNode::new(WidgetType::Text {
+            content: "Hi".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        text.name = Some("greeting".to_string());
+        let container = LayoutNode::new(WidgetType::Container {
+            child: Some(Box::new(text)),
+            attrs: ContainerAttrs {
+                padding: PaddingSpec { top: 4.0, right: 8.0, bottom: 4.0, left: 8.0 },
+                ..Default::default()
+            },
+        });
+        assert_eq!(
+            container.to_outline(0),
+            "Container (padding [4, 8, 4, 8])\n  greeting (Text)\n"
+        );
+
+        let component = LayoutNode::new(WidgetType::ComponentRef {
+            file: PathBuf::from("components/header.ron"),
+        });
+        assert_eq!(component.to_outline(2), "    Component \"header\"\n");
+    }
+
     #[test]
     fn test_layout_node_children() {
         let node = LayoutNode::new(WidgetType::Column {
@@ -757,6 +2023,45 @@ mod tests {
         assert!(node.children().is_none());
     }
 
+    #[test]
+    fn test_child_helpers_cover_every_container() {
+        let text = |content: &str| {
+            LayoutNode::new(WidgetType::Text {
+                content: content.to_string(),
+                attrs: TextAttrs::default(),
+            })
+        };
+        let framed = LayoutNode::new(WidgetType::Container {
+            child: Some(Box::new(text("Framed"))),
+            attrs: ContainerAttrs::default(),
+        });
+        let stack = LayoutNode::new(WidgetType::Stack {
+            children: vec![text("Back"), framed],
+            attrs: ContainerAttrs::default(),
+        });
+        let mut root = LayoutNode::new(WidgetType::Column {
+            children: vec![stack],
+            attrs: ContainerAttrs::default(),
+        });
+
+        let leaf = root.descendant_at(&[0, 1, 0]).unwrap();
+        assert_eq!(leaf.label(), "Text \"Framed\"");
+        assert!(root.descendant_at(&[0, 1, 1]).is_none());
+        assert_eq!(root.subtree().len(), 5);
+        assert_eq!(root.descendant_count(), 4);
+
+        let stack = root.child_at(0).unwrap();
+        assert!(stack.is_multi_child());
+        assert_eq!(stack.child_path("root.children[0]", 1), "root.children[0].children[1]");
+        let framed = stack.child_at(1).unwrap();
+        assert!(!framed.is_multi_child());
+        assert_eq!(framed.child_path("root", 0), "root.child");
+
+        let ids: Vec<ComponentId> = root.subtree().iter().map(|node| node.id).collect();
+        root.regenerate_ids();
+        assert!(root.subtree().iter().all(|node| !ids.contains(&node.id)));
+    }
+
     #[test]
     fn test_padding_spec_zero() {
         let padding = PaddingSpec::ZERO;
@@ -794,13 +2099,15 @@ mod tests {
 
     #[test]
     fn test_validate_checkbox_bindings() {
-        let mut doc = LayoutDocument::default();
-        doc.root = LayoutNode::new(WidgetType::Checkbox {
-            label: "Check".to_string(),
-            checked_binding: "is-checked".to_string(), // Invalid
-            message_stub: "OnToggle".to_string(),
-            attrs: CheckboxAttrs { spacing: 10.0 },
-        });
+        let doc = LayoutDocument {
+            root: LayoutNode::new(WidgetType::Checkbox {
+                label: "Check".to_string(),
+                checked_binding: "is-checked".to_string(), // Invalid
+                message_stub: "OnToggle".to_string(),
+                attrs: CheckboxAttrs { spacing: 10.0 },
+            }),
+            ..Default::default()
+        };
         let errors = doc.validate();
         assert_eq!(errors.len(), 1);
         assert!(errors[0].message.contains("checked_binding"));
@@ -808,14 +2115,16 @@ mod tests {
 
     #[test]
     fn test_validate_slider_bindings() {
-        let mut doc = LayoutDocument::default();
-        doc.root = LayoutNode::new(WidgetType::Slider {
-            min: 0.0,
-            max: 100.0,
-            value_binding: "my value".to_string(), // Invalid (has space)
-            message_stub: "OnChange".to_string(),
-            attrs: SliderAttrs::default(),
-        });
+        let doc = LayoutDocument {
+            root: LayoutNode::new(WidgetType::Slider {
+                min: 0.0,
+                max: 100.0,
+                value_binding: "my value".to_string(), // Invalid (has space)
+                message_stub: "OnChange".to_string(),
+                attrs: SliderAttrs::default(),
+            }),
+            ..Default::default()
+        };
         let errors = doc.validate();
         assert_eq!(errors.len(), 1);
         assert!(errors[0].message.contains("value_binding"));
@@ -823,13 +2132,15 @@ mod tests {
 
     #[test]
     fn test_validate_pick_list_bindings() {
-        let mut doc = LayoutDocument::default();
-        doc.root = LayoutNode::new(WidgetType::PickList {
-            options: vec!["A".to_string()],
-            selected_binding: "selected".to_string(),
-            message_stub: "return".to_string(), // Invalid (keyword)
-            attrs: PickListAttrs::default(),
-        });
+        let doc = LayoutDocument {
+            root: LayoutNode::new(WidgetType::PickList {
+                options: vec!["A".to_string()],
+                selected_binding: "selected".to_string(),
+                message_stub: "return".to_string(), // Invalid (keyword)
+                attrs: PickListAttrs::default(),
+            }),
+            ..Default::default()
+        };
         let errors = doc.validate();
         assert_eq!(errors.len(), 1);
         assert!(errors[0].message.contains("Rust keyword"));
@@ -6,7 +6,9 @@
 pub mod history;
 pub mod layout;
 pub mod project;
+pub mod widget_kind;
 
 pub use history::History;
 pub use layout::{ComponentId, LayoutDocument, LayoutNode};
-pub use project::{Project, ProjectConfig};
+pub use project::{CodegenStyle, ExportMode, I18nMode, Position, Project, ProjectConfig, ProjectError, SampleData, SampleValue, Template};
+pub use widget_kind::{WidgetCategory, WidgetKind};
@@ -3,29 +3,70 @@
 //! Handles loading/saving project configuration from `iced_builder.toml`
 //! and managing the overall project state.
 
-use crate::io::{config, layout_file};
-use crate::model::{layout::NodeIndex, ComponentId, History, LayoutDocument, LayoutNode};
+use crate::io::{autosave, config, crash_recovery, history_file, layout_file, LoadWarning};
+use crate::model::{layout::{NodeIndex, SubtreeRevisions, ValidationConfig, ValidationError, ValidationSeverity}, ComponentId, History, LayoutDocument, LayoutNode, WidgetKind};
+use crate::codegen::strings::{StringTable, STRINGS_HEADER};
+use crate::codegen::template::{generate_code_with_template, TemplateError};
+use crate::util::{is_rust_keyword, is_valid_rust_identifier, FormatBackend, Formatted, RustfmtOptions};
 use serde::{Deserialize, Serialize};
-use std::path::{Path, PathBuf};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
 use thiserror::Error;
 
 /// Errors that can occur when working with projects.
 #[derive(Debug, Error)]
 pub enum ProjectError {
+    /// Reading or writing a project file failed.
     #[error("Failed to read config file: {0}")]
     ConfigRead(#[from] std::io::Error),
 
+    /// The config file is not valid TOML.
     #[error("Failed to parse config file: {0}")]
     ConfigParse(#[from] toml::de::Error),
 
+    /// The folder has no `iced_builder.toml`.
     #[error("Not an Iced Builder project: {0}\n\nThis folder doesn't contain an 'iced_builder.toml' config file.\nUse 'New Project' to create a new Iced Builder project, or select an existing Iced Builder project folder.")]
     ConfigNotFound(PathBuf),
 
+    /// The layout file named in the config does not exist.
     #[error("Layout file not found: {0}")]
     LayoutNotFound(PathBuf),
 
+    /// The layout could not be read or written.
     #[error("Failed to parse layout file: {0}")]
     LayoutParse(String),
+
+    /// The selection can't be made into a component.
+    #[error("Cannot extract component: {0}")]
+    InvalidComponent(String),
+
+    /// The layout has problems that make the action impossible.
+    #[error("Layout has errors: {0}")]
+    InvalidLayout(String),
+
+    /// The output file was not written by Iced Builder.
+    #[error("Refusing to overwrite {0}: it was not generated by Iced Builder")]
+    ForeignFile(PathBuf),
+
+    /// Validation errors block code generation.
+    #[error("Layout has {} validation error(s)", .0.len())]
+    ValidationFailed(Vec<ValidationError>),
+
+    /// A custom codegen template failed.
+    #[error("Template error: {0}")]
+    Template(#[from] TemplateError),
+
+    /// Reading or writing a bundle failed.
+    #[error("Bundle error: {0}")]
+    Bundle(#[from] crate::io::BundleError),
+
+    /// Saving the project somewhere it already is.
+    #[error("The project is already saved in {0}")]
+    SameLocation(PathBuf),
+
+    /// Layout files must live inside the project folder.
+    #[error("{0} is outside the project folder; save layouts inside it, or use Save Project As to move the project")]
+    OutsideProject(PathBuf),
 }
 
 /// Project configuration loaded from `iced_builder.toml`.
@@ -54,12 +95,197 @@ pub struct ProjectConfig {
     /// Whether to run rustfmt on generated code.
     #[serde(default = "default_true")]
     pub format_output: bool,
+
+    /// Formatter used when `format_output` is set.
+    #[serde(default)]
+    pub format_backend: FormatBackend,
+
+    /// Sample values for bindings, shown in preview mode.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sample_data: SampleData,
+    /// Whether undo history is saved with the project and restored on open.
+    #[serde(default)]
+    pub persist_history: bool,
+
+    /// Number of timestamped backups kept of the layout and config files;
+    /// zero disables backups.
+    #[serde(default = "default_backup_count")]
+    pub backup_count: usize,
+
+    /// Whether export also writes a `Message` enum stub for the layout.
+    #[serde(default = "default_true")]
+    pub generate_message_stub: bool,
+
+    /// Whether export writes a bare view function or a full component module.
+    #[serde(default)]
+    pub export_mode: ExportMode,
+
+    /// Whether multi-child containers are generated as macros or builders.
+    #[serde(default)]
+    pub codegen_style: CodegenStyle,
+
+    /// Directory runnable examples are written to.
+    #[serde(default = "default_example_dir")]
+    pub example_dir: PathBuf,
+
+    /// Whether text colors are generated as named constants in a `palette` module.
+    #[serde(default)]
+    pub color_constants: bool,
+
+    /// Whether export adds an `update` skeleton to the stub file.
+    #[serde(default)]
+    pub generate_update_skeleton: bool,
+
+    /// Relative path for the generated stub file.
+    #[serde(default = "default_message_stub_file")]
+    pub message_stub_file: PathBuf,
+
+    /// Whether text is generated as literals or as translation lookups.
+    #[serde(default)]
+    pub i18n_mode: I18nMode,
+
+    /// Function generated code calls to look up a translation key.
+    #[serde(default = "default_i18n_function")]
+    pub i18n_function: String,
+
+    /// Relative path for the generated translation strings file.
+    #[serde(default = "default_strings_file")]
+    pub strings_file: PathBuf,
+
+    /// Relative path of a minijinja template rendered instead of the
+    /// built-in generator.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codegen_template: Option<PathBuf>,
+
+    /// Whether generated code ends with a test module checking the view
+    /// still builds the layout's widget tree.
+    #[serde(default)]
+    pub generate_tests: bool,
+
+    /// Whether export goes ahead even when the layout has validation errors.
+    #[serde(default)]
+    pub allow_export_with_errors: bool,
+
+    /// Whether saving validates the project and reports the problems found.
+    /// Problems never stop the save.
+    #[serde(default = "default_true")]
+    pub validate_on_save: bool,
+
+    /// Layout validation settings.
+    #[serde(default)]
+    pub validation: ValidationConfig,
+}
+
+/// What export generates from a layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportMode {
+    /// A `view` function over the app's own state and message types.
+    #[default]
+    ViewOnly,
+    /// A module with its own state struct, message enum, `update` and `view`.
+    Component,
+}
+
+/// How multi-child containers are written in generated code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodegenStyle {
+    /// `column![a, b].spacing(10)`
+    #[default]
+    Macros,
+    /// `Column::new().push(a).push(b).spacing(10)`, easier to extend with
+    /// conditional children.
+    Builders,
+}
+
+/// How user-visible text is written in generated code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum I18nMode {
+    /// `text("Form Title")`
+    #[default]
+    Literals,
+    /// `text(t("form.title"))`, with the text written to the strings file.
+    Keys,
+}
+
+/// Map of binding name to the sample value shown in preview mode.
+pub type SampleData = BTreeMap<String, SampleValue>;
+
+/// A sample value for a binding.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SampleValue {
+    /// A boolean, for checkbox bindings.
+    Bool(bool),
+    /// A number, for slider bindings.
+    Number(f64),
+    /// Text, for text input and pick list bindings.
+    Text(String),
+}
+
+impl SampleValue {
+    /// Get the value as display text.
+    pub fn as_text(&self) -> String {
+        match self {
+            SampleValue::Bool(b) => b.to_string(),
+            SampleValue::Number(n) => n.to_string(),
+            SampleValue::Text(s) => s.clone(),
+        }
+    }
+
+    /// Get the value as a boolean, if it has a boolean meaning.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            SampleValue::Bool(b) => Some(*b),
+            SampleValue::Number(n) => Some(*n != 0.0),
+            SampleValue::Text(s) => s.trim().parse().ok(),
+        }
+    }
+
+    /// Get the value as a number, if it has a numeric meaning.
+    pub fn as_number(&self) -> Option<f32> {
+        match self {
+            SampleValue::Bool(_) => None,
+            SampleValue::Number(n) => Some(*n as f32),
+            SampleValue::Text(s) => s.trim().parse().ok(),
+        }
+    }
+
+    /// Parse user input, keeping numbers and booleans typed.
+    pub fn parse(input: &str) -> Self {
+        let trimmed = input.trim();
+        if let Ok(b) = trimmed.parse::<bool>() {
+            SampleValue::Bool(b)
+        } else if let Ok(n) = trimmed.parse::<f64>() {
+            SampleValue::Number(n)
+        } else {
+            SampleValue::Text(input.to_string())
+        }
+    }
 }
 
 fn default_output_file() -> PathBuf {
     PathBuf::from("src/ui/layout_generated.rs")
 }
 
+fn default_example_dir() -> PathBuf {
+    PathBuf::from("examples")
+}
+
+fn default_message_stub_file() -> PathBuf {
+    PathBuf::from("src/ui/message_stub.rs")
+}
+
+fn default_i18n_function() -> String {
+    String::from("t")
+}
+
+fn default_strings_file() -> PathBuf {
+    PathBuf::from("src/ui/strings.toml")
+}
+
 fn default_message_type() -> String {
     String::from("crate::Message")
 }
@@ -72,6 +298,34 @@ fn default_true() -> bool {
     true
 }
 
+fn default_backup_count() -> usize {
+    crate::io::DEFAULT_BACKUP_COUNT
+}
+
+/// Error for a layout that cannot be exported.
+fn invalid_layout(errors: &[ValidationError]) -> ProjectError {
+    let messages: Vec<_> = errors.iter().map(|e| e.message.as_str()).collect();
+    ProjectError::InvalidLayout(messages.join("; "))
+}
+
+/// Error for a config file that could not be written for a new project.
+fn config_create_error(error: config::ConfigError) -> ProjectError {
+    match error {
+        config::ConfigError::ReadError(io) => ProjectError::ConfigRead(io),
+        config::ConfigError::SerializeError(s) => ProjectError::LayoutParse(s.to_string()),
+        _ => ProjectError::LayoutParse("Config create error".to_string()),
+    }
+}
+
+/// Whether a file is missing or starts with the generator's `header`, and so
+/// may be overwritten by export.
+fn is_generated_file(path: &Path, header: &str) -> bool {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => contents.starts_with(header),
+        Err(_) => !path.exists(),
+    }
+}
+
 impl Default for ProjectConfig {
     fn default() -> Self {
         Self {
@@ -81,11 +335,122 @@ impl Default for ProjectConfig {
             state_type: default_state_type(),
             layout_files: Vec::new(),
             format_output: true,
+            format_backend: FormatBackend::default(),
+            sample_data: SampleData::new(),
+            persist_history: false,
+            backup_count: default_backup_count(),
+            generate_message_stub: true,
+            generate_update_skeleton: false,
+            color_constants: false,
+            export_mode: ExportMode::default(),
+            example_dir: default_example_dir(),
+            codegen_style: CodegenStyle::default(),
+            message_stub_file: default_message_stub_file(),
+            i18n_mode: I18nMode::default(),
+            i18n_function: default_i18n_function(),
+            strings_file: default_strings_file(),
+            codegen_template: None,
+            generate_tests: false,
+            allow_export_with_errors: false,
+            validate_on_save: true,
+            validation: ValidationConfig::default(),
+        }
+    }
+}
+
+/// A config value that export cannot work with.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{field}: {message}")]
+pub struct ConfigIssue {
+    /// The config key, e.g. `message_type`.
+    pub field: &'static str,
+    /// What is wrong with its value.
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn new(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            message: message.into(),
+        }
+    }
+}
+
+/// Whether `path` is a Rust type path such as `crate::ui::Message`.
+fn is_valid_rust_path(path: &str) -> bool {
+    let path = path.strip_prefix("::").unwrap_or(path);
+    path.split("::").enumerate().all(|(index, segment)| {
+        let leading_keyword = index == 0 && matches!(segment, "crate" | "self" | "super");
+        let super_chain = segment == "super";
+        leading_keyword || super_chain || (is_valid_rust_identifier(segment) && !is_rust_keyword(segment))
+    })
+}
+
+/// Whether `path` is relative and stays inside the directory it is relative to.
+fn stays_inside(path: &Path) -> bool {
+    let mut depth = 0usize;
+    for component in path.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir => match depth.checked_sub(1) {
+                Some(up) => depth = up,
+                None => return false,
+            },
+            Component::RootDir | Component::Prefix(_) => return false,
         }
     }
+    true
+}
+
+/// `path` relative to `dir`, if it lies inside `dir` once both are absolute.
+fn path_inside(dir: &Path, path: &Path) -> Option<PathBuf> {
+    let dir = std::path::absolute(dir).ok()?;
+    let path = std::path::absolute(path).ok()?;
+    let rest = path.strip_prefix(&dir).ok()?;
+    stays_inside(rest).then(|| rest.to_path_buf())
 }
 
 impl ProjectConfig {
+    /// The layout file the project saves to, relative to the project.
+    pub fn layout_file(&self) -> &Path {
+        self.layout_files.first().map_or(Path::new("layout.ron"), PathBuf::as_path)
+    }
+
+    /// Check the values export depends on.
+    ///
+    /// Layout files must live inside `project_dir`: relative entries may not
+    /// climb out of it, and absolute entries must point under it.
+    pub fn validate(&self, project_dir: &Path) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+        for (field, value) in [("message_type", &self.message_type), ("state_type", &self.state_type)] {
+            if !is_valid_rust_path(value) {
+                issues.push(ConfigIssue::new(field, format!("'{}' is not a Rust path like crate::Message", value)));
+            }
+        }
+        if self.output_file.extension().is_none_or(|ext| ext != "rs") {
+            issues.push(ConfigIssue::new(
+                "output_file",
+                format!("'{}' should be a .rs file", self.output_file.display()),
+            ));
+        }
+        for layout in &self.layout_files {
+            let inside = if layout.is_absolute() {
+                path_inside(project_dir, layout).is_some()
+            } else {
+                stays_inside(layout)
+            };
+            if !inside {
+                issues.push(ConfigIssue::new(
+                    "layout_files",
+                    format!("'{}' must be a path inside the project", layout.display()),
+                ));
+            }
+        }
+        issues
+    }
+
     /// Load project configuration from a TOML file.
     pub fn load(path: &std::path::Path) -> Result<Self, ProjectError> {
         if !path.exists() {
@@ -103,6 +468,37 @@ impl ProjectConfig {
         std::fs::write(path, content)?;
         Ok(())
     }
+
+    /// Make relative paths that lead outside `project_dir` absolute, so they
+    /// still point at the same files once the config moves elsewhere.
+    /// Paths inside the project stay relative and follow it.
+    /// Layout files must stay inside the project, so an outside layout that
+    /// is rebased here is still reported by `validate`.
+    pub fn rebase_outside_paths(&mut self, project_dir: &Path) {
+        let rebase = |path: &mut PathBuf| {
+            if path.is_relative() && !crate::io::copy::is_inside(path) {
+                *path = project_dir.join(&*path);
+            }
+        };
+        rebase(&mut self.output_file);
+        rebase(&mut self.example_dir);
+        rebase(&mut self.message_stub_file);
+        rebase(&mut self.strings_file);
+        self.project_root.iter_mut().for_each(rebase);
+        self.codegen_template.iter_mut().for_each(rebase);
+        self.layout_files.iter_mut().for_each(rebase);
+    }
+}
+
+/// What [`Project::write`] wrote, to mark the project saved afterwards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Written {
+    /// Layout revision that was written.
+    pub revision: u64,
+    /// Config revision that was written.
+    pub config_revision: u64,
+    /// Hash of the layout file as written.
+    pub layout_hash: Option<u64>,
 }
 
 /// The complete state of an open project.
@@ -128,6 +524,26 @@ pub struct Project {
 
     /// Whether there are unsaved changes.
     pub dirty: bool,
+    /// Whether the config changed since the last save, outside the history.
+    pub unsaved_config: bool,
+
+    /// Incremented whenever the layout changes, for caches keyed on it.
+    pub revision: u64,
+    /// Incremented whenever the config changes outside the history.
+    pub config_revision: u64,
+
+    /// Revision of each subtree, for caches of parts of the layout.
+    pub subtree_revisions: SubtreeRevisions,
+
+    /// Component layouts referenced from the layout, keyed by project-relative path.
+    pub components: BTreeMap<PathBuf, LayoutDocument>,
+
+    /// Hash of the layout file as last loaded or saved, to notice edits
+    /// made outside the builder.
+    pub layout_hash: Option<u64>,
+
+    /// Unknown fields skipped when the layout was loaded; saving drops them.
+    pub load_warnings: Vec<LoadWarning>,
 }
 
 impl Project {
@@ -142,8 +558,15 @@ impl Project {
             layout,
             node_index,
             selected_id: None,
-            history: History::new(),
+            history: History::default(),
             dirty: false,
+            unsaved_config: false,
+            revision: 0,
+            config_revision: 0,
+            subtree_revisions: SubtreeRevisions::default(),
+            components: BTreeMap::new(),
+            layout_hash: None,
+            load_warnings: Vec::new(),
         }
     }
 
@@ -174,7 +597,7 @@ impl Project {
         tracing::debug!(target: "iced_builder::io", ?config, "Config loaded");
 
         // Load layout file
-        let layout = Self::load_layout_for_project(project_dir, &config)?;
+        let (layout, load_warnings) = Self::load_layout_for_project(project_dir, &config)?;
         let node_index = crate::model::layout::build_node_index(&layout.root);
 
         tracing::info!(
@@ -184,26 +607,43 @@ impl Project {
             "Project opened successfully"
         );
 
-        Ok(Self {
+        let mut project = Self {
             path: project_dir.to_path_buf(),
             config,
             layout,
             node_index,
             selected_id: None,
-            history: History::new(),
+            history: History::default(),
             dirty: false,
-        })
+            unsaved_config: false,
+            revision: 0,
+            config_revision: 0,
+            subtree_revisions: SubtreeRevisions::default(),
+            components: BTreeMap::new(),
+            layout_hash: None,
+            load_warnings: Vec::new(),
+        };
+        project.layout_hash = project.disk_layout_hash();
+        project.load_warnings = load_warnings;
+        project.load_components();
+        if project.config.persist_history {
+            project.restore_history();
+        }
+        Ok(project)
     }
 
     /// Load the layout file for a project.
-    fn load_layout_for_project(project_dir: &Path, config: &ProjectConfig) -> Result<LayoutDocument, ProjectError> {
+    fn load_layout_for_project(
+        project_dir: &Path,
+        config: &ProjectConfig,
+    ) -> Result<(LayoutDocument, Vec<LoadWarning>), ProjectError> {
         // Try layout files from config first
         if !config.layout_files.is_empty() {
             for layout_path in &config.layout_files {
                 let full_path = project_dir.join(layout_path);
                 if full_path.exists() {
                     tracing::debug!(target: "iced_builder::io", path = %full_path.display(), "Loading layout from config");
-                    return layout_file::load_layout(&full_path)
+                    return layout_file::load_layout_with_warnings(&full_path)
                         .map_err(|e| ProjectError::LayoutParse(e.to_string()));
                 }
             }
@@ -213,7 +653,7 @@ impl Project {
         let default_path = project_dir.join("layout.ron");
         if default_path.exists() {
             tracing::debug!(target: "iced_builder::io", path = %default_path.display(), "Loading default layout.ron");
-            return layout_file::load_layout(&default_path)
+            return layout_file::load_layout_with_warnings(&default_path)
                 .map_err(|e| ProjectError::LayoutParse(e.to_string()));
         }
 
@@ -221,7 +661,7 @@ impl Project {
         let json_path = project_dir.join("layout.json");
         if json_path.exists() {
             tracing::debug!(target: "iced_builder::io", path = %json_path.display(), "Loading layout.json");
-            return layout_file::load_layout(&json_path)
+            return layout_file::load_layout_with_warnings(&json_path)
                 .map_err(|e| ProjectError::LayoutParse(e.to_string()));
         }
 
@@ -231,53 +671,417 @@ impl Project {
 
     /// Save the project to disk.
     ///
-    /// Saves both the configuration and the layout file.
-    pub fn save(&mut self) -> Result<(), ProjectError> {
+    /// Saves both the configuration and the layout file. With
+    /// `validate_on_save` set, returns the validation problems found so
+    /// they can be shown; otherwise the list is empty.
+    pub fn save(&mut self) -> Result<Vec<ValidationError>, ProjectError> {
+        let written = self.write()?;
+        Ok(self.finish_write(written))
+    }
+
+    /// Write the configuration and layout without marking the project
+    /// saved, e.g. on a [`Self::snapshot`] away from the UI thread. Pass
+    /// the result to [`Self::finish_write`] on the project itself.
+    pub fn write(&self) -> Result<Written, ProjectError> {
         tracing::info!(target: "iced_builder::io", path = %self.path.display(), "Saving project");
 
-        // Save config
+        self.save_config_file()?;
+
+        let layout_path = self.layout_path();
+
+        // Save layout
+        layout_file::save_layout_with_backup(&layout_path, &self.layout, self.config.backup_count)
+            .map_err(|e| ProjectError::LayoutParse(e.to_string()))?;
+        self.after_write(&layout_path);
+
+        tracing::info!(target: "iced_builder::io", "Project saved successfully");
+        Ok(Written {
+            revision: self.revision,
+            config_revision: self.config_revision,
+            layout_hash: self.disk_layout_hash(),
+        })
+    }
+
+    /// Record a finished [`Self::write`] and validate as [`Self::save`]
+    /// does. The project stays dirty if it changed while being written.
+    pub fn finish_write(&mut self, written: Written) -> Vec<ValidationError> {
+        self.layout_hash = written.layout_hash;
+        self.load_warnings.clear();
+        if written.revision == self.revision && written.config_revision == self.config_revision {
+            self.mark_saved();
+        } else {
+            tracing::debug!(target: "iced_builder::io", "Project changed while saving; still unsaved");
+        }
+
+        if !self.config.validate_on_save {
+            return Vec::new();
+        }
+        let problems = self.validate();
+        if !problems.is_empty() {
+            tracing::warn!(target: "iced_builder::io", count = problems.len(), "Project saved with validation problems");
+        }
+        problems
+    }
+
+    /// Copy the project for saving or exporting it away from the UI
+    /// thread. The undo history is only copied when it is saved too.
+    pub fn snapshot(&self) -> Project {
+        Project {
+            path: self.path.clone(),
+            config: self.config.clone(),
+            layout: self.layout.clone(),
+            node_index: self.node_index.clone(),
+            selected_id: self.selected_id,
+            history: if self.config.persist_history {
+                self.history.clone()
+            } else {
+                History::default()
+            },
+            dirty: self.dirty,
+            unsaved_config: self.unsaved_config,
+            revision: self.revision,
+            config_revision: self.config_revision,
+            subtree_revisions: SubtreeRevisions::default(),
+            components: self.components.clone(),
+            layout_hash: self.layout_hash,
+            load_warnings: Vec::new(),
+        }
+    }
+
+    /// Save the layout to a new file and make it the project's main layout.
+    ///
+    /// The format follows the extension, so this also converts between RON
+    /// and JSON. The file is stored relative to the project, and paths
+    /// outside the project are refused like `ProjectConfig::validate` does.
+    pub fn save_as(&mut self, path: &Path) -> Result<(), ProjectError> {
+        tracing::info!(target: "iced_builder::io", path = %path.display(), "Saving layout as");
+
+        let relative = path_inside(&self.path, path).ok_or_else(|| ProjectError::OutsideProject(path.to_path_buf()))?;
+        layout_file::save_layout_with_backup(path, &self.layout, 0)
+            .map_err(|e| ProjectError::LayoutParse(e.to_string()))?;
+
+        match self.config.layout_files.first_mut() {
+            Some(main) => *main = relative,
+            None => self.config.layout_files.push(relative),
+        }
+        self.save_config_file()?;
+        self.finish_save(path);
+
+        tracing::info!(target: "iced_builder::io", "Layout saved as new file");
+        Ok(())
+    }
+
+    /// Write the project configuration, keeping backups.
+    fn save_config_file(&self) -> Result<(), ProjectError> {
         let config_path = self.path.join("iced_builder.toml");
-        config::save_config(&config_path, &self.config)
+        config::save_config_with_backup(&config_path, &self.config, self.config.backup_count)
             .map_err(|e| match e {
                 config::ConfigError::ReadError(io) => ProjectError::ConfigRead(io),
                 config::ConfigError::SerializeError(s) => ProjectError::LayoutParse(s.to_string()),
                 _ => ProjectError::LayoutParse("Config save error".to_string()),
-            })?;
+            })
+    }
 
-        // Determine layout file path
-        let layout_path = if !self.config.layout_files.is_empty() {
-            self.path.join(&self.config.layout_files[0])
-        } else {
-            self.path.join("layout.ron")
+    /// Mark the project clean after the layout was written to `layout_path`.
+    fn finish_save(&mut self, layout_path: &Path) {
+        self.layout_hash = self.disk_layout_hash();
+        self.load_warnings.clear();
+        self.mark_saved();
+        self.after_write(layout_path);
+    }
+
+    /// Save the history if it persists and drop the autosave, once the
+    /// layout was written to `layout_path`.
+    fn after_write(&self, layout_path: &Path) {
+        if self.config.persist_history {
+            self.save_history(layout_path);
+        }
+        autosave::discard_autosave(&autosave::autosave_path(&self.path));
+    }
+
+    /// Save the undo stack next to the project, tagged with the layout just
+    /// written. Failures are logged and never fail the save.
+    fn save_history(&self, layout_path: &Path) {
+        let result = std::fs::read(layout_path)
+            .map_err(history_file::HistoryFileError::from)
+            .and_then(|contents| {
+                history_file::save_history(&history_file::history_path(&self.path), &self.history, &contents)
+            });
+        if let Err(e) = result {
+            tracing::warn!(target: "iced_builder::io", error = %e, "Failed to save history");
+        }
+    }
+
+    /// Restore undo history saved against the current main layout file, if any.
+    fn restore_history(&mut self) {
+        let Ok(contents) = std::fs::read(self.path.join(self.main_layout_file())) else {
+            return;
         };
+        if let Some(mut history) = history_file::load_history(&history_file::history_path(&self.path), &contents) {
+            history.mark_saved();
+            self.history = history;
+        }
+    }
 
-        // Save layout
-        layout_file::save_layout(&layout_path, &self.layout)
+    /// The layout file the project saves to.
+    pub fn layout_path(&self) -> PathBuf {
+        self.path.join(self.config.layout_file())
+    }
+
+    /// Hash the layout file as it is on disk now.
+    fn disk_layout_hash(&self) -> Option<u64> {
+        std::fs::read(self.layout_path())
+            .ok()
+            .map(|contents| history_file::content_hash(&contents))
+    }
+
+    /// Whether the layout file changed on disk since it was last loaded or
+    /// saved. A deleted file doesn't count; the next save recreates it.
+    pub fn layout_changed_on_disk(&self) -> bool {
+        match self.disk_layout_hash() {
+            Some(hash) => self.layout_hash != Some(hash),
+            None => false,
+        }
+    }
+
+    /// Replace the layout with the one on disk, as an undoable change that
+    /// leaves the layout clean.
+    pub fn reload_layout(&mut self) -> Result<(), ProjectError> {
+        let path = self.layout_path();
+        let (reloaded, warnings) =
+            layout_file::load_layout_with_warnings(&path).map_err(|e| ProjectError::LayoutParse(e.to_string()))?;
+        let previous = std::mem::replace(&mut self.layout, reloaded);
+        self.load_warnings = warnings;
+        self.history.push(previous, "Reload from disk");
+        self.history.mark_saved();
+        self.rebuild_index();
+        if self.selected_id.is_some_and(|id| !self.node_index.contains_key(&id)) {
+            self.selected_id = None;
+        }
+        self.load_components();
+        self.layout_hash = self.disk_layout_hash();
+        self.sync_dirty();
+        tracing::info!(target: "iced_builder::io", path = %path.display(), "Reloaded layout from disk");
+        Ok(())
+    }
+
+    /// Keep the layout in memory over a changed file on disk, so the change
+    /// isn't reported again; the next save overwrites it.
+    pub fn keep_local_layout(&mut self) {
+        self.layout_hash = self.disk_layout_hash();
+    }
+
+    /// Copy the project to `target` and continue working there.
+    ///
+    /// The config and main layout are written from memory, so unsaved
+    /// changes go to the copy; other layouts, components, the codegen
+    /// template and snippets are copied from disk. Relative paths leading
+    /// outside the project are made absolute so they keep resolving.
+    /// Returns the number of files written.
+    pub fn save_project_as(&mut self, target: &Path) -> Result<usize, ProjectError> {
+        let source = std::path::absolute(&self.path)?;
+        let target = std::path::absolute(target)?;
+        if target == source {
+            return Err(ProjectError::SameLocation(target));
+        }
+        tracing::info!(target: "iced_builder::io", from = %source.display(), to = %target.display(), "Saving project as");
+        std::fs::create_dir_all(&target)?;
+
+        // A main layout outside the project would be overwritten in place
+        let main = self.main_layout_file();
+        if !crate::io::copy::is_inside(&main) {
+            let name = PathBuf::from(main.file_name().unwrap_or("layout.ron".as_ref()));
+            match self.config.layout_files.first_mut() {
+                Some(first) => *first = name,
+                None => self.config.layout_files.push(name),
+            }
+        }
+        let main = self.main_layout_file();
+        let snippets = Path::new(crate::io::snippets::SNIPPETS_DIR);
+        let copied: Vec<PathBuf> = self
+            .bundle_files()
+            .into_iter()
+            .filter(|file| file != Path::new(config::CONFIG_FILENAME) && *file != main && !file.starts_with(snippets))
+            .collect();
+        let mut written = crate::io::copy::copy_files(&self.path, &target, &copied)?;
+        written += crate::io::copy::copy_dir_recursive(&self.path.join(snippets), &target.join(snippets))?;
+
+        // The edits now live in the copy, so the original has nothing to recover
+        autosave::discard_autosave(&autosave::autosave_path(&self.path));
+        self.config.rebase_outside_paths(&source);
+        self.path = target;
+
+        let layout_path = self.layout_path();
+        layout_file::save_layout_with_backup(&layout_path, &self.layout, 0)
             .map_err(|e| ProjectError::LayoutParse(e.to_string()))?;
+        self.save_config_file()?;
+        self.finish_save(&layout_path);
 
-        self.dirty = false;
-        tracing::info!(target: "iced_builder::io", "Project saved successfully");
+        tracing::info!(target: "iced_builder::io", files = written + 2, "Project saved to new location");
+        Ok(written + 2)
+    }
+
+    /// Files that make up the project, relative to its directory: the
+    /// config, layouts, components, snippets and codegen template. Files
+    /// outside the project are left out.
+    pub fn bundle_files(&self) -> Vec<PathBuf> {
+        let mut files = vec![PathBuf::from(config::CONFIG_FILENAME), self.config.layout_file().to_path_buf()];
+        files.extend(self.config.layout_files.iter().cloned());
+        files.extend(self.components.keys().cloned());
+        files.extend(self.config.codegen_template.iter().cloned());
+        files.extend(
+            crate::io::list_snippets(&self.path)
+                .into_iter()
+                .filter_map(|snippet| snippet.path.strip_prefix(&self.path).ok().map(Path::to_path_buf)),
+        );
+
+        let mut bundled = Vec::new();
+        for file in files {
+            let inside = file.components().all(|c| matches!(c, std::path::Component::Normal(_)));
+            if inside && !bundled.contains(&file) && self.path.join(&file).is_file() {
+                bundled.push(file);
+            }
+        }
+        bundled
+    }
+
+    /// Write the project's saved files to a bundle at `path`, returning how
+    /// many files it holds. Unsaved changes are not included.
+    pub fn export_bundle(&self, path: &Path) -> Result<usize, ProjectError> {
+        let files = self.bundle_files();
+        crate::io::bundle::write_bundle(path, &self.path, &self.layout.name, &files)?;
+        Ok(files.len())
+    }
+
+    /// Backups of the layout file, newest first.
+    pub fn layout_backups(&self) -> Vec<PathBuf> {
+        crate::io::backups_for(&self.layout_path())
+    }
+
+    /// Load a backup of the layout file, as an undoable change.
+    pub fn restore_backup(&mut self, backup: &Path) -> Result<(), ProjectError> {
+        // Backups end in a timestamp, so take the format from the layout file
+        let format = layout_file::LayoutFormat::from_path(&self.layout_path()).unwrap_or(layout_file::LayoutFormat::Ron);
+        let content = std::fs::read_to_string(backup)?;
+        let restored = layout_file::parse_layout(&content, format).map_err(|e| ProjectError::LayoutParse(e.to_string()))?;
+
+        let label = format!("Restore backup from {}", crate::io::backups::backup_label(backup));
+        let previous = std::mem::replace(&mut self.layout, restored);
+        self.history.push(previous, label);
+        self.selected_id = None;
+        self.rebuild_index();
+        self.load_components();
+        self.mark_dirty();
+        tracing::info!(target: "iced_builder::io", backup = %backup.display(), "Restored layout backup");
+        Ok(())
+    }
+
+    /// Write the layout to the autosave file, leaving the layout file and
+    /// the dirty flag alone.
+    pub fn autosave(&self) -> Result<(), ProjectError> {
+        autosave::save_autosave(&autosave::autosave_path(&self.path), &self.layout)
+            .map_err(|e| ProjectError::LayoutParse(e.to_string()))
+    }
+
+    /// Whether an autosave newer than the layout file is waiting to be recovered.
+    pub fn has_recoverable_autosave(&self) -> bool {
+        autosave::is_newer(&autosave::autosave_path(&self.path), &self.path.join(self.config.layout_file()))
+    }
+
+    /// Replace the layout with the autosaved one, as an undoable change.
+    ///
+    /// The autosave stays until the next manual save.
+    pub fn recover_autosave(&mut self) -> Result<(), ProjectError> {
+        let recovered = layout_file::load_layout(&autosave::autosave_path(&self.path))
+            .map_err(|e| ProjectError::LayoutParse(e.to_string()))?;
+        let previous = std::mem::replace(&mut self.layout, recovered);
+        self.history.push(previous, "Recover unsaved changes");
+        self.selected_id = None;
+        self.rebuild_index();
+        self.load_components();
+        self.mark_dirty();
+        tracing::info!(target: "iced_builder::io", "Recovered autosaved layout");
+        Ok(())
+    }
+
+    /// Delete the autosave without recovering it.
+    pub fn discard_autosave(&self) {
+        autosave::discard_autosave(&autosave::autosave_path(&self.path));
+    }
+
+    /// Whether a crash left an unsaved layout to restore.
+    pub fn has_crash_recovery(&self) -> bool {
+        crash_recovery::has_crash_recovery(&self.path)
+    }
+
+    /// Replace the layout with the one saved when the editor crashed, as an
+    /// undoable change, and delete the crash recovery file.
+    pub fn recover_crash(&mut self) -> Result<(), ProjectError> {
+        let recovered = layout_file::load_layout(&crash_recovery::crash_recovery_path(Some(&self.path)))
+            .map_err(|e| ProjectError::LayoutParse(e.to_string()))?;
+        let previous = std::mem::replace(&mut self.layout, recovered);
+        self.history.push(previous, "Recover crashed session");
+        self.selected_id = None;
+        self.rebuild_index();
+        self.load_components();
+        self.mark_dirty();
+        crash_recovery::discard_crash_recovery(&self.path);
+        tracing::info!(target: "iced_builder::io", "Recovered layout saved by a crash");
         Ok(())
     }
 
+    /// Delete the crash recovery file without restoring it.
+    pub fn discard_crash_recovery(&self) {
+        crash_recovery::discard_crash_recovery(&self.path);
+    }
+
     /// Export generated Rust code to the configured output file.
-    pub fn export(&self) -> Result<String, ProjectError> {
+    pub fn export(&self) -> Result<Formatted, ProjectError> {
         tracing::info!(target: "iced_builder::codegen", "Exporting code");
+        tracing::info!(target: "iced_builder::codegen", stats = %self.layout.stats(), "Layout statistics");
+
+        let blockers = self.export_blockers();
+        if !blockers.is_empty() {
+            return Err(ProjectError::ValidationFailed(blockers));
+        }
+        let layout = self.inlined_layout();
+
+        // Components carry their own message enum, so it must be consistent
+        let component = self.config.export_mode == ExportMode::Component;
+        if component {
+            let conflicts = layout.message_conflicts();
+            if !conflicts.is_empty() {
+                return Err(invalid_layout(&conflicts));
+            }
+        }
 
-        let code = crate::codegen::generate_code(&self.layout, &self.config);
-        let formatted = if self.config.format_output {
-            crate::util::try_format_rust_code(&code)
+        // Generate everything up front so nothing is written if a part fails
+        let writes_stubs = self.config.generate_message_stub || self.config.generate_update_skeleton;
+        let message_stub = if writes_stubs && !component {
+            let stub = crate::codegen::generate_stubs(&layout, &self.config)
+                .map_err(|conflicts| invalid_layout(&conflicts))?;
+            let stub_path = self.resolve_output(&self.config.message_stub_file);
+            if !is_generated_file(&stub_path, crate::codegen::GENERATED_HEADER) {
+                return Err(ProjectError::ForeignFile(stub_path));
+            }
+            Some((stub_path, crate::codegen::stamp(&stub)))
         } else {
-            code
+            None
         };
 
-        // Determine output path
-        let output_path = if self.config.output_file.is_absolute() {
-            self.config.output_file.clone()
+        let strings = if self.config.i18n_mode == I18nMode::Keys {
+            let strings_path = self.resolve_output(&self.config.strings_file);
+            if !is_generated_file(&strings_path, STRINGS_HEADER) {
+                return Err(ProjectError::ForeignFile(strings_path));
+            }
+            Some((strings_path, StringTable::collect(&layout).to_toml(&layout)))
         } else {
-            self.path.join(&self.config.output_file)
+            None
         };
 
+        let formatted = self.generate_output()?;
+        let output_path = self.output_path();
+
         // Create parent directories if needed
         if let Some(parent) = output_path.parent() {
             std::fs::create_dir_all(parent)?;
@@ -290,45 +1094,232 @@ impl Project {
         }
 
         // Write the generated code
-        std::fs::write(&output_path, &formatted)?;
+        std::fs::write(&output_path, &formatted.code)?;
+
+        if let Some((stub_path, stub)) = message_stub {
+            if let Some(parent) = stub_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&stub_path, stub)?;
+            tracing::info!(target: "iced_builder::codegen", path = %stub_path.display(), "Stub file written");
+        }
+
+        if let Some((strings_path, strings)) = strings {
+            if let Some(parent) = strings_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&strings_path, strings)?;
+            tracing::info!(target: "iced_builder::codegen", path = %strings_path.display(), "Strings file written");
+        }
 
         tracing::info!(
             target: "iced_builder::codegen", 
             path = %output_path.display(), 
-            size = formatted.len(),
+            size = formatted.code.len(),
+            formatter = %formatted.backend,
             "Code exported successfully"
         );
 
         Ok(formatted)
     }
 
-    /// Create a new project in the given directory.
+    /// Write a runnable example of the layout to the example directory.
     ///
-    /// Creates the config file and an initial layout file.
-    pub fn create(project_dir: &Path, template: Option<Template>) -> Result<Self, ProjectError> {
-        tracing::info!(target: "iced_builder::io", path = %project_dir.display(), "Creating new project");
-
-        // Ensure directory exists
-        std::fs::create_dir_all(project_dir)?;
+    /// Returns the path written, e.g. `examples/login_form.rs`.
+    pub fn export_example(&self) -> Result<PathBuf, ProjectError> {
+        let blockers = self.export_blockers();
+        if !blockers.is_empty() {
+            return Err(ProjectError::ValidationFailed(blockers));
+        }
+        let layout = self.inlined_layout();
+        let conflicts = layout.message_conflicts();
+        if !conflicts.is_empty() {
+            return Err(invalid_layout(&conflicts));
+        }
 
-        // Create config file
-        let config = ProjectConfig::default();
-        let config_path = project_dir.join("iced_builder.toml");
-        config::save_config(&config_path, &config)
-            .map_err(|e| match e {
-                config::ConfigError::ReadError(io) => ProjectError::ConfigRead(io),
-                config::ConfigError::SerializeError(s) => ProjectError::LayoutParse(s.to_string()),
-                _ => ProjectError::LayoutParse("Config create error".to_string()),
-            })?;
+        let file_name = format!("{}.rs", crate::util::to_snake_case(&self.layout.name, "layout"));
+        let path = self.resolve_output(&self.config.example_dir).join(file_name);
+        if !is_generated_file(&path, crate::codegen::GENERATED_HEADER) {
+            return Err(ProjectError::ForeignFile(path));
+        }
 
-        // Create layout file from template or default
-        let layout = match template {
-            Some(Template::Form) => Self::create_form_template(),
-            Some(Template::Dashboard) => Self::create_dashboard_template(),
-            None | Some(Template::Blank) => LayoutDocument::default(),
-        };
+        let code = crate::codegen::generate_example(&layout, &self.config);
+        let formatted = crate::util::try_format_rust_code(&code, self.format_backend(), &self.rustfmt_options());
 
-        let layout_path = project_dir.join("layout.ron");
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, crate::codegen::stamp(&formatted.code))?;
+        tracing::info!(target: "iced_builder::codegen", path = %path.display(), "Example exported");
+        Ok(path)
+    }
+
+    /// Validate the config, then the layout with the project's validation
+    /// settings. Config issues are reported against the root node.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut issues: Vec<ValidationError> = self
+            .config
+            .validate(&self.path)
+            .into_iter()
+            .map(|issue| {
+                ValidationError::error(format!("{}: {}", config::CONFIG_FILENAME, issue.field), issue.message, self.layout.root.id)
+            })
+            .collect();
+        issues.extend(self.layout.validate_with(&self.config.validation));
+        issues.extend(self.unresolved_components());
+        issues
+    }
+
+    /// Report component references whose layout is not loaded, which
+    /// export cannot inline.
+    fn unresolved_components(&self) -> Vec<ValidationError> {
+        self.layout
+            .exported_paths()
+            .into_iter()
+            .filter_map(|(path, node)| match &node.widget {
+                crate::model::layout::WidgetType::ComponentRef { file } if !self.components.contains_key(file) => {
+                    Some(ValidationError::error(
+                        path,
+                        format!("Component layout '{}' could not be loaded", file.display()),
+                        node.id,
+                    ))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get the layout as export generates it, with every component
+    /// reference replaced by the component's own tree.
+    ///
+    /// Component messages and bindings then become part of the project's
+    /// message enum and state like any other widget's. References to
+    /// components that are not loaded are kept as they are.
+    pub fn inlined_layout(&self) -> LayoutDocument {
+        LayoutDocument {
+            root: self.inlined_node(&self.layout.root),
+            ..self.layout.clone()
+        }
+    }
+
+    /// Get a copy of a subtree with its component references inlined.
+    pub fn inlined_node(&self, node: &LayoutNode) -> LayoutNode {
+        let mut node = node.clone();
+        self.inline_components(&mut node, &mut Vec::new());
+        node
+    }
+
+    /// Replace the component references in a subtree, skipping any that
+    /// would refer back to a component being inlined.
+    fn inline_components(&self, node: &mut LayoutNode, inlining: &mut Vec<PathBuf>) {
+        if let crate::model::layout::WidgetType::ComponentRef { file } = &node.widget {
+            let Some(doc) = self.components.get(file).filter(|_| !inlining.contains(file)) else {
+                return;
+            };
+            let mut root = doc.root.clone();
+            inlining.push(file.clone());
+            self.inline_components(&mut root, inlining);
+            inlining.pop();
+            root.exclude_from_export |= node.exclude_from_export;
+            *node = root;
+            return;
+        }
+        for child in node.child_nodes_mut() {
+            self.inline_components(child, inlining);
+        }
+    }
+
+    /// Validation errors that stop export, unless `allow_export_with_errors`
+    /// is set.
+    pub fn export_blockers(&self) -> Vec<ValidationError> {
+        if self.config.allow_export_with_errors {
+            return Vec::new();
+        }
+        self.validate()
+            .into_iter()
+            .filter(|e| e.severity == ValidationSeverity::Error)
+            .collect()
+    }
+
+    /// Generate the view code export would write, formatted if configured.
+    pub fn generate_output(&self) -> Result<Formatted, ProjectError> {
+        let template = self.template_path();
+        let code = generate_code_with_template(&self.inlined_layout(), &self.config, template.as_deref())?;
+        let formatted = crate::util::try_format_rust_code(&code, self.format_backend(), &self.rustfmt_options());
+        Ok(Formatted {
+            code: crate::codegen::stamp(&formatted.code),
+            ..formatted
+        })
+    }
+
+    /// Resolved path of the configured codegen template, if any.
+    pub fn template_path(&self) -> Option<PathBuf> {
+        self.config.codegen_template.as_deref().map(|path| self.resolve_output(path))
+    }
+
+    /// Formatter for generated code, honouring `format_output`.
+    pub fn format_backend(&self) -> FormatBackend {
+        if self.config.format_output {
+            self.config.format_backend
+        } else {
+            FormatBackend::None
+        }
+    }
+
+    /// rustfmt settings from the target project, when `project_root` is set.
+    pub fn rustfmt_options(&self) -> RustfmtOptions {
+        match &self.config.project_root {
+            Some(root) => RustfmtOptions::detect(&self.resolve_output(root)),
+            None => RustfmtOptions::default(),
+        }
+    }
+
+    /// Path export writes the view code to.
+    pub fn output_path(&self) -> PathBuf {
+        self.resolve_output(&self.config.output_file)
+    }
+
+    /// Root of the target project: `project_root`, or else the project
+    /// directory.
+    pub fn target_root(&self) -> PathBuf {
+        match &self.config.project_root {
+            Some(root) => self.resolve_output(root),
+            None => self.path.clone(),
+        }
+    }
+
+    /// Resolve a configured output path against the project directory.
+    fn resolve_output(&self, path: &Path) -> PathBuf {
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.path.join(path)
+        }
+    }
+
+    /// Create a new project in the given directory.
+    ///
+    /// Creates the config file and an initial layout file.
+    pub fn create(project_dir: &Path, template: Option<Template>) -> Result<Self, ProjectError> {
+        tracing::info!(target: "iced_builder::io", path = %project_dir.display(), "Creating new project");
+
+        // Ensure directory exists
+        std::fs::create_dir_all(project_dir)?;
+
+        // Create config file
+        let config = ProjectConfig::default();
+        let config_path = project_dir.join("iced_builder.toml");
+        config::save_config(&config_path, &config).map_err(config_create_error)?;
+
+        // Create layout file from template or default
+        let layout = match template {
+            Some(Template::Form) => Self::create_form_template(),
+            Some(Template::Dashboard) => Self::create_dashboard_template(),
+            Some(Template::MasterDetail) => Self::create_master_detail_template(),
+            None | Some(Template::Blank) => LayoutDocument::default(),
+        };
+
+        let layout_path = project_dir.join("layout.ron");
         layout_file::save_layout(&layout_path, &layout)
             .map_err(|e| ProjectError::LayoutParse(e.to_string()))?;
 
@@ -336,15 +1327,38 @@ impl Project {
 
         tracing::info!(target: "iced_builder::io", "New project created successfully");
 
-        Ok(Self {
+        let mut project = Self {
             path: project_dir.to_path_buf(),
             config,
             layout,
             node_index,
             selected_id: None,
-            history: History::new(),
+            history: History::default(),
             dirty: false,
-        })
+            unsaved_config: false,
+            revision: 0,
+            config_revision: 0,
+            subtree_revisions: SubtreeRevisions::default(),
+            components: BTreeMap::new(),
+            layout_hash: None,
+            load_warnings: Vec::new(),
+        };
+        project.layout_hash = project.disk_layout_hash();
+        Ok(project)
+    }
+
+    /// Make the directory holding an existing layout file into a project
+    /// that uses it as the main layout, then open it.
+    pub fn create_for_layout(layout_path: &Path) -> Result<Self, ProjectError> {
+        let project_dir = layout_path.parent().unwrap_or(Path::new("."));
+        tracing::info!(target: "iced_builder::io", path = %layout_path.display(), "Creating project for layout");
+
+        let config = ProjectConfig {
+            layout_files: layout_path.file_name().map(PathBuf::from).into_iter().collect(),
+            ..ProjectConfig::default()
+        };
+        config::save_config(&project_dir.join(config::CONFIG_FILENAME), &config).map_err(config_create_error)?;
+        Self::open(project_dir)
     }
 
     /// Create a form template layout.
@@ -352,7 +1366,7 @@ impl Project {
         use crate::model::layout::*;
         
         LayoutDocument {
-            version: 1,
+            version: LAYOUT_VERSION,
             name: String::from("Form"),
             root: LayoutNode::new(WidgetType::Column {
                 children: vec![
@@ -395,7 +1409,7 @@ impl Project {
         use crate::model::layout::*;
         
         LayoutDocument {
-            version: 1,
+            version: LAYOUT_VERSION,
             name: String::from("Dashboard"),
             root: LayoutNode::new(WidgetType::Column {
                 children: vec![
@@ -472,90 +1486,216 @@ impl Project {
         }
     }
 
+    /// Create a master-detail template layout.
+    fn create_master_detail_template() -> LayoutDocument {
+        use crate::model::layout::*;
+
+        let item = |label: &str, message_stub: &str| {
+            LayoutNode::new(WidgetType::Button {
+                label: label.to_string(),
+                message_stub: message_stub.to_string(),
+                attrs: ButtonAttrs {
+                    width: LengthSpec::Fill,
+                    ..Default::default()
+                },
+            })
+        };
+
+        LayoutDocument {
+            version: LAYOUT_VERSION,
+            name: String::from("Master-detail"),
+            root: LayoutNode::new(WidgetType::Row {
+                children: vec![
+                    // Item list
+                    LayoutNode::new(WidgetType::Scrollable {
+                        child: Some(Box::new(LayoutNode::new(WidgetType::Column {
+                            children: vec![
+                                item("First item", "SelectFirst"),
+                                item("Second item", "SelectSecond"),
+                                item("Third item", "SelectThird"),
+                            ],
+                            attrs: ContainerAttrs {
+                                spacing: 4.0,
+                                ..Default::default()
+                            },
+                        }))),
+                        attrs: ContainerAttrs {
+                            width: LengthSpec::FillPortion(1),
+                            height: LengthSpec::Fill,
+                            ..Default::default()
+                        },
+                    }),
+                    // Details of the selected item
+                    LayoutNode::new(WidgetType::Column {
+                        children: vec![
+                            LayoutNode::new(WidgetType::Text {
+                                content: String::from("Details"),
+                                attrs: TextAttrs {
+                                    font_size: 24.0,
+                                    ..Default::default()
+                                },
+                            }),
+                            LayoutNode::new(WidgetType::Text {
+                                content: String::from("Select an item to see it here."),
+                                attrs: TextAttrs::default(),
+                            }),
+                            LayoutNode::new(WidgetType::TextInput {
+                                placeholder: String::from("Notes..."),
+                                value_binding: String::from("notes"),
+                                message_stub: String::from("NotesChanged"),
+                                attrs: InputAttrs::default(),
+                            }),
+                            LayoutNode::new(WidgetType::Button {
+                                label: String::from("Save"),
+                                message_stub: String::from("SaveItem"),
+                                attrs: ButtonAttrs::default(),
+                            }),
+                        ],
+                        attrs: ContainerAttrs {
+                            spacing: 10.0,
+                            width: LengthSpec::FillPortion(2),
+                            ..Default::default()
+                        },
+                    }),
+                ],
+                attrs: ContainerAttrs {
+                    spacing: 20.0,
+                    padding: PaddingSpec { top: 20.0, right: 20.0, bottom: 20.0, left: 20.0 },
+                    height: LengthSpec::Fill,
+                    width: LengthSpec::Fill,
+                    ..Default::default()
+                },
+            }),
+        }
+    }
+
     /// Rebuild the node index after structural changes.
     pub fn rebuild_index(&mut self) {
         self.node_index = crate::model::layout::build_node_index(&self.layout.root);
+        self.revision += 1;
+        self.subtree_revisions.reset(self.revision);
+    }
+
+    /// Update the node index after the children of the node at
+    /// `parent_path` from `from` on moved, e.g. after an insert or removal
+    /// at `from`. Unlike [`Self::rebuild_index`], the rest of the tree is
+    /// not walked.
+    fn reindex_children(&mut self, parent_path: &[usize], from: usize) {
+        if let Some(parent) = self.layout.root.descendant_at(parent_path) {
+            crate::model::layout::reindex_children_from(&mut self.node_index, parent, parent_path, from);
+        }
+        self.revision += 1;
+        self.subtree_revisions.touch(&self.layout.root, parent_path, self.revision);
+    }
+
+    /// Insert `new_child` into the node at `parent_path` with `insert` and
+    /// index it. Returns false if `insert` refuses it.
+    fn insert_and_index(
+        &mut self,
+        parent_path: &[usize],
+        new_child: LayoutNode,
+        insert: impl FnOnce(&mut LayoutNode, LayoutNode) -> bool,
+    ) -> bool {
+        let id = new_child.id;
+        let Some(parent) = self.layout.root.descendant_at_mut(parent_path) else {
+            return false;
+        };
+        if !insert(parent, new_child) {
+            return false;
+        }
+        let position = parent.child_nodes().iter().position(|child| child.id == id).unwrap_or(0);
+        self.reindex_children(parent_path, position);
+        true
     }
 
     /// Find a node by its ComponentId.
     pub fn find_node(&self, id: ComponentId) -> Option<&LayoutNode> {
         let path = self.node_index.get(&id)?;
-        self.find_node_by_path(&self.layout.root, path)
+        self.layout.root.descendant_at(path)
     }
 
     /// Find a mutable node by its ComponentId.
+    ///
+    /// Call [`Self::rebuild_index`] after changing it, so caches of the
+    /// layout see the change; [`Self::update_node`] keeps them up to date
+    /// by itself.
     pub fn find_node_mut(&mut self, id: ComponentId) -> Option<&mut LayoutNode> {
         let path = self.node_index.get(&id)?.clone();
-        Self::find_node_by_path_mut_static(&mut self.layout.root, &path)
+        self.layout.root.descendant_at_mut(&path)
     }
 
-    /// Find a node by path (helper).
-    fn find_node_by_path<'a>(&self, root: &'a LayoutNode, path: &[usize]) -> Option<&'a LayoutNode> {
-        if path.is_empty() {
-            return Some(root);
-        }
-
-        let idx = path[0];
-        let remaining = &path[1..];
-
-        if let Some(children) = root.children() {
-            if idx < children.len() {
-                return self.find_node_by_path(&children[idx], remaining);
-            }
-        }
-
-        // Handle single-child containers
-        match &root.widget {
-            crate::model::layout::WidgetType::Container { child: Some(c), .. }
-            | crate::model::layout::WidgetType::Scrollable { child: Some(c), .. } => {
-                if idx == 0 {
-                    return self.find_node_by_path(c, remaining);
-                }
-            }
-            _ => {}
+    /// Change the node `id` in place, recording only that node in the
+    /// history rather than a snapshot of the whole layout. Returns false if
+    /// there is no such node.
+    pub fn update_node(&mut self, id: ComponentId, label: &str, update: impl FnOnce(&mut LayoutNode)) -> bool {
+        let Some(path) = self.node_index.get(&id).cloned() else {
+            return false;
+        };
+        if !self.history.push_node(&self.layout, &path, label) {
+            return false;
         }
+        let Some(node) = self.layout.root.descendant_at_mut(&path) else {
+            return false;
+        };
+        update(node);
+        self.mark_dirty();
+        self.subtree_revisions.touch(&self.layout.root, &path, self.revision);
+        true
+    }
 
-        None
+    /// Mark the project as having unsaved changes after a layout edit
+    /// recorded in the history.
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+        self.revision += 1;
     }
 
-    /// Find a mutable node by path (static helper to avoid borrow issues).
-    fn find_node_by_path_mut_static<'a>(root: &'a mut LayoutNode, path: &[usize]) -> Option<&'a mut LayoutNode> {
-        if path.is_empty() {
-            return Some(root);
-        }
+    /// Mark the config as changed; undo cannot take this back.
+    pub fn mark_config_dirty(&mut self) {
+        self.unsaved_config = true;
+        self.dirty = true;
+        self.config_revision += 1;
+    }
 
-        let idx = path[0];
-        let remaining = &path[1..];
+    /// Mark the project as saved at the current history position.
+    pub fn mark_saved(&mut self) {
+        self.dirty = false;
+        self.unsaved_config = false;
+        self.history.mark_saved();
+    }
 
-        // Check if this is a multi-child container
-        match &mut root.widget {
-            crate::model::layout::WidgetType::Column { children, .. }
-            | crate::model::layout::WidgetType::Row { children, .. }
-            | crate::model::layout::WidgetType::Stack { children, .. } => {
-                if idx < children.len() {
-                    return Self::find_node_by_path_mut_static(&mut children[idx], remaining);
-                }
-            }
-            crate::model::layout::WidgetType::Container { child: Some(c), .. }
-            | crate::model::layout::WidgetType::Scrollable { child: Some(c), .. } => {
-                if idx == 0 {
-                    return Self::find_node_by_path_mut_static(c, remaining);
-                }
-            }
-            _ => {}
-        }
+    /// Recompute the dirty flag after moving through the history.
+    fn sync_dirty(&mut self) {
+        self.dirty = self.unsaved_config || !self.history.is_at_saved();
+    }
 
-        None
+    /// Undo the last change, returning its label.
+    pub fn undo(&mut self) -> Option<String> {
+        let (previous, label) = self.history.undo(self.layout.clone())?;
+        self.layout = previous;
+        self.rebuild_index();
+        self.sync_dirty();
+        Some(label)
     }
 
-    /// Mark the project as having unsaved changes.
-    pub fn mark_dirty(&mut self) {
-        self.dirty = true;
+    /// Redo the last undone change, returning its label.
+    pub fn redo(&mut self) -> Option<String> {
+        let (next, label) = self.history.redo(self.layout.clone())?;
+        self.layout = next;
+        self.rebuild_index();
+        self.sync_dirty();
+        Some(label)
     }
 
-    /// Mark the project as saved.
-    pub fn mark_saved(&mut self) {
-        self.dirty = false;
+    /// Jump to a state of the history timeline; see [`History::jump_to`].
+    pub fn jump_to_history(&mut self, index: usize) -> bool {
+        let Some(state) = self.history.jump_to(index, self.layout.clone()) else {
+            return false;
+        };
+        self.layout = state;
+        self.rebuild_index();
+        self.sync_dirty();
+        true
     }
 
     /// Get the project directory path.
@@ -574,76 +1714,49 @@ impl Project {
         };
 
         // Cannot remove root node (empty path)
-        if path.is_empty() {
+        let Some((&child_index, parent_path)) = path.split_last() else {
             return false;
-        }
+        };
 
         // Find the parent and remove the child
-        let parent_path = &path[..path.len() - 1];
-        let child_index = path[path.len() - 1];
-
-        let removed = if parent_path.is_empty() {
-            // Parent is root
-            Self::remove_child_at(&mut self.layout.root, child_index)
-        } else {
-            // Find parent node
-            if let Some(parent) = Self::find_node_by_path_mut_static(&mut self.layout.root, parent_path) {
-                Self::remove_child_at(parent, child_index)
-            } else {
-                false
-            }
+        let removed = self.layout.root.descendant_at_mut(parent_path)
+            .and_then(|parent| Self::take_child_at(parent, child_index));
+        let Some(removed) = removed else {
+            return false;
         };
 
-        if removed {
-            self.rebuild_index();
-        }
-
-        removed
-    }
-
-    /// Remove a child at a specific index from a node.
-    fn remove_child_at(node: &mut LayoutNode, index: usize) -> bool {
-        match &mut node.widget {
-            crate::model::layout::WidgetType::Column { children, .. }
-            | crate::model::layout::WidgetType::Row { children, .. }
-            | crate::model::layout::WidgetType::Stack { children, .. } => {
-                if index < children.len() {
-                    children.remove(index);
-                    return true;
-                }
-            }
-            crate::model::layout::WidgetType::Container { child, .. }
-            | crate::model::layout::WidgetType::Scrollable { child, .. } => {
-                if index == 0 && child.is_some() {
-                    *child = None;
-                    return true;
-                }
-            }
-            _ => {}
-        }
-        false
+        // Only the removed subtree and the siblings after it change
+        crate::model::layout::unindex_subtree(&mut self.node_index, &removed);
+        self.reindex_children(parent_path, child_index);
+        true
     }
 
     /// Add a child node to a container by ComponentId.
     /// Returns true if the child was successfully added.
     /// Returns false if the target node is not a container or doesn't exist.
     pub fn add_child_to_node(&mut self, parent_id: ComponentId, new_child: LayoutNode) -> bool {
-        if let Some(parent) = self.find_node_mut(parent_id) {
-            if Self::add_child_to(parent, new_child) {
-                self.rebuild_index();
-                return true;
-            }
-        }
-        false
+        let Some(parent_path) = self.node_index.get(&parent_id).cloned() else {
+            return false;
+        };
+        self.insert_and_index(&parent_path, new_child, Self::add_child_to)
+    }
+
+    /// Insert a child at `index` among the children of a container.
+    /// The index is clamped to the child count; empty single-child
+    /// containers take the child regardless of index.
+    /// Returns false if the target is not a container with room or doesn't exist.
+    pub fn insert_child_at(&mut self, parent_id: ComponentId, index: usize, new_child: LayoutNode) -> bool {
+        let Some(parent_path) = self.node_index.get(&parent_id).cloned() else {
+            return false;
+        };
+        self.insert_and_index(&parent_path, new_child, |parent, child| {
+            Self::insert_child_into(parent, index, child)
+        })
     }
 
     /// Add a child to the root node.
     pub fn add_child_to_root(&mut self, new_child: LayoutNode) -> bool {
-        if Self::add_child_to(&mut self.layout.root, new_child) {
-            self.rebuild_index();
-            return true;
-        }
-        false
+        self.insert_and_index(&[], new_child, Self::add_child_to)
     }
 
     /// Check if a node is a container that can accept children.
@@ -657,38 +1770,32 @@ impl Project {
 
     /// Check if a node can accept children.
     fn node_is_container(node: &LayoutNode) -> bool {
-        match &node.widget {
-            crate::model::layout::WidgetType::Column { .. }
-            | crate::model::layout::WidgetType::Row { .. }
-            | crate::model::layout::WidgetType::Stack { .. } => true,
-            crate::model::layout::WidgetType::Container { child, .. }
-            | crate::model::layout::WidgetType::Scrollable { child, .. } => {
-                // Single-child containers can only accept if empty
-                child.is_none()
-            }
-            _ => false,
-        }
+        node.can_accept_child()
+    }
+
+    /// Find the closest node, starting at `id` and walking up its ancestors,
+    /// that can accept a new child.
+    pub fn nearest_container(&self, id: ComponentId) -> Option<ComponentId> {
+        let path = self.node_index.get(&id)?;
+        (0..=path.len())
+            .rev()
+            .filter_map(|len| self.layout.root.descendant_at(&path[..len]))
+            .find(|node| node.can_accept_child())
+            .map(|node| node.id)
     }
 
     /// Add a child to a specific node.
     fn add_child_to(node: &mut LayoutNode, new_child: LayoutNode) -> bool {
-        match &mut node.widget {
-            crate::model::layout::WidgetType::Column { children, .. }
-            | crate::model::layout::WidgetType::Row { children, .. }
-            | crate::model::layout::WidgetType::Stack { children, .. } => {
-                children.push(new_child);
+        if let Some(children) = node.children_mut() {
+            children.push(new_child);
+            return true;
+        }
+        match node.child_slot_mut() {
+            Some(slot @ None) => {
+                *slot = Some(Box::new(new_child));
                 true
             }
-            crate::model::layout::WidgetType::Container { child, .. }
-            | crate::model::layout::WidgetType::Scrollable { child, .. } => {
-                if child.is_none() {
-                    *child = Some(Box::new(new_child));
-                    true
-                } else {
-                    false // Already has a child
-                }
-            }
-            _ => false, // Not a container
+            _ => false, // Not a container, or already full
         }
     }
 
@@ -713,26 +1820,14 @@ impl Project {
         }
         
         // Find parent by path
-        self.find_node_by_path(&self.layout.root, parent_path).map(|n| n.id)
+        self.layout.root.descendant_at(parent_path).map(|n| n.id)
     }
 
     /// Get the first child of the currently selected node.
     pub fn get_first_child(&self) -> Option<ComponentId> {
         let selected_id = self.selected_id?;
         let node = self.find_node(selected_id)?;
-        
-        match &node.widget {
-            crate::model::layout::WidgetType::Column { children, .. }
-            | crate::model::layout::WidgetType::Row { children, .. }
-            | crate::model::layout::WidgetType::Stack { children, .. } => {
-                children.first().map(|c| c.id)
-            }
-            crate::model::layout::WidgetType::Container { child, .. }
-            | crate::model::layout::WidgetType::Scrollable { child, .. } => {
-                child.as_ref().map(|c| c.id)
-            }
-            _ => None,
-        }
+        node.child_at(0).map(|c| c.id)
     }
 
     /// Get the next sibling of the currently selected node.
@@ -751,15 +1846,10 @@ impl Project {
         let parent = if parent_path.is_empty() {
             &self.layout.root
         } else {
-            self.find_node_by_path(&self.layout.root, parent_path)?
+            self.layout.root.descendant_at(parent_path)?
         };
         
-        let children = match &parent.widget {
-            crate::model::layout::WidgetType::Column { children, .. }
-            | crate::model::layout::WidgetType::Row { children, .. }
-            | crate::model::layout::WidgetType::Stack { children, .. } => children,
-            _ => return None,
-        };
+        let children = parent.children()?;
         
         // Get next sibling
         if current_index + 1 < children.len() {
@@ -789,15 +1879,10 @@ impl Project {
         let parent = if parent_path.is_empty() {
             &self.layout.root
         } else {
-            self.find_node_by_path(&self.layout.root, parent_path)?
+            self.layout.root.descendant_at(parent_path)?
         };
         
-        let children = match &parent.widget {
-            crate::model::layout::WidgetType::Column { children, .. }
-            | crate::model::layout::WidgetType::Row { children, .. }
-            | crate::model::layout::WidgetType::Stack { children, .. } => children,
-            _ => return None,
-        };
+        let children = parent.children()?;
         
         Some(children[current_index - 1].id)
     }
@@ -824,58 +1909,671 @@ impl Project {
         let parent = if parent_path.is_empty() {
             &mut self.layout.root
         } else {
-            Self::find_node_by_path_mut_static(&mut self.layout.root, parent_path)?
+            self.layout.root.descendant_at_mut(parent_path)?
         };
         
-        match &mut parent.widget {
-            crate::model::layout::WidgetType::Column { children, .. }
-            | crate::model::layout::WidgetType::Row { children, .. }
-            | crate::model::layout::WidgetType::Stack { children, .. } => {
-                children.insert(current_index + 1, cloned);
-            }
-            _ => return None, // Single-child containers can't have duplicates
-        }
+        // Single-child containers can't have duplicates
+        parent.children_mut()?.insert(current_index + 1, cloned);
         
-        self.rebuild_index();
+        self.reindex_children(parent_path, current_index + 1);
         Some(new_id)
     }
-}
-
-/// Project templates.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Template {
-    /// Empty layout with just a root Column.
-    Blank,
-    /// A form layout with text inputs and a submit button.
-    Form,
-    /// A dashboard layout with header and content panels.
-    Dashboard,
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::model::layout::{ButtonAttrs, ContainerAttrs, TextAttrs, WidgetType};
-    use tempfile::tempdir;
 
-    #[test]
-    fn test_project_config_default() {
-        let config = ProjectConfig::default();
-        assert_eq!(config.message_type, "crate::Message");
-        assert_eq!(config.state_type, "crate::AppState");
-        assert!(config.format_output);
+    /// Get the parent of a node and the node's index within it.
+    /// Returns None for the root or an unknown node.
+    pub fn parent_of(&self, id: ComponentId) -> Option<(ComponentId, usize)> {
+        let path = self.node_index.get(&id)?;
+        let (&index, parent_path) = path.split_last()?;
+        let parent = self.layout.root.descendant_at(parent_path)?;
+        Some((parent.id, index))
     }
 
-    #[test]
-    fn test_project_new() {
-        let config = ProjectConfig::default();
-        let project = Project::new(PathBuf::from("/test"), config);
-        assert!(project.selected_id.is_none());
-        assert!(!project.dirty);
-    }
+    /// Move a node to `index` among the children of `new_parent_id`.
+    ///
+    /// The index is clamped to the parent's child count. Moves of the root,
+    /// into the node itself or into one of its descendants are rejected.
+    /// Pushes a history entry and returns true on success; on failure the
+    /// layout is left untouched.
+    pub fn move_node(&mut self, id: ComponentId, new_parent_id: ComponentId, index: usize) -> bool {
+        let Some(path) = self.node_index.get(&id).cloned() else {
+            return false;
+        };
+        let Some(target_path) = self.node_index.get(&new_parent_id).cloned() else {
+            return false;
+        };
 
-    #[test]
-    fn test_project_create_and_open() {
+        // The root cannot move, and a node cannot move into its own subtree
+        let Some((&old_index, old_parent_path)) = path.split_last() else {
+            return false;
+        };
+        if target_path.starts_with(&path) {
+            tracing::debug!(target: "iced_builder::app::tree", %id, "Rejected move into own subtree");
+            return false;
+        }
+
+        let same_parent = old_parent_path == target_path.as_slice();
+        let Some(target) = self.find_node(new_parent_id) else {
+            return false;
+        };
+        if !same_parent && !target.can_accept_child() {
+            return false;
+        }
+        if same_parent && !target.is_multi_child() {
+            // Single-child containers have nowhere else to put it
+            return false;
+        }
+
+        // Removing the node first shifts later siblings in the same parent
+        let index = if same_parent && old_index < index {
+            index - 1
+        } else {
+            index
+        };
+
+        let snapshot = self.layout.clone();
+        let taken = self.layout.root.descendant_at_mut(old_parent_path)
+            .and_then(|old_parent| Self::take_child_at(old_parent, old_index));
+        let Some(node) = taken else {
+            return false;
+        };
+        let label = format!("Move {}", node.widget.type_name());
+
+        // Paths may have shifted, so look the target up again
+        crate::model::layout::unindex_subtree(&mut self.node_index, &node);
+        self.reindex_children(old_parent_path, old_index);
+        let inserted = self.insert_child_at(new_parent_id, index, node);
+        if inserted {
+            self.history.push(snapshot, label);
+        } else {
+            self.layout = snapshot;
+            self.rebuild_index();
+        }
+        inserted
+    }
+
+    /// Take the child at `index` out of a node.
+    fn take_child_at(node: &mut LayoutNode, index: usize) -> Option<LayoutNode> {
+        if let Some(children) = node.children_mut() {
+            return (index < children.len()).then(|| children.remove(index));
+        }
+        node.child_slot_mut()
+            .filter(|_| index == 0)
+            .and_then(Option::take)
+            .map(|c| *c)
+    }
+
+    /// Insert a child at `index` (clamped) into a node.
+    fn insert_child_into(node: &mut LayoutNode, index: usize, new_child: LayoutNode) -> bool {
+        match node.children_mut() {
+            Some(children) => {
+                children.insert(index.min(children.len()), new_child);
+                true
+            }
+            None => Self::add_child_to(node, new_child),
+        }
+    }
+
+    /// Find nodes whose friendly name contains `query`, ignoring case.
+    /// Returns matches in document order; an empty query matches nothing.
+    pub fn find_by_name(&self, query: &str) -> Vec<ComponentId> {
+        self.find_where(query, LayoutNode::matches_name)
+    }
+
+    /// Find nodes whose text, labels, placeholders or message stubs contain
+    /// `query`, ignoring case. Returns matches in document order.
+    pub fn find_by_content(&self, query: &str) -> Vec<ComponentId> {
+        self.find_where(query, LayoutNode::matches_content)
+    }
+
+    /// Find nodes matching `query` by name or content, in document order.
+    pub fn find(&self, query: &str) -> Vec<ComponentId> {
+        let mut found = self.find_by_name(query);
+        found.extend(self.find_by_content(query));
+        // Index paths sort in document order, so a node matching both ways ends up adjacent
+        found.sort_by_key(|id| self.node_index.get(id));
+        found.dedup();
+        found
+    }
+
+    /// Collect the IDs of nodes matching a predicate in document order.
+    fn find_where(&self, query: &str, matches: impl Fn(&LayoutNode, &str) -> bool) -> Vec<ComponentId> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        self.layout
+            .root
+            .subtree()
+            .into_iter()
+            .filter(|node| matches(node, query))
+            .map(|node| node.id)
+            .collect()
+    }
+
+    /// Path of the main layout file, relative to the project directory.
+    fn main_layout_file(&self) -> PathBuf {
+        self.config
+            .layout_files
+            .first()
+            .cloned()
+            .unwrap_or_else(|| PathBuf::from("layout.ron"))
+    }
+
+    /// Collect the component files referenced directly within a subtree.
+    fn component_refs(node: &LayoutNode, refs: &mut Vec<PathBuf>) {
+        for node in node.subtree() {
+            if let crate::model::layout::WidgetType::ComponentRef { file } = &node.widget {
+                refs.push(file.clone());
+            }
+        }
+    }
+
+    /// Load every component layout the layout refers to, following nested references.
+    /// Missing or unreadable files are logged and skipped.
+    pub fn load_components(&mut self) {
+        let mut pending = Vec::new();
+        Self::component_refs(&self.layout.root, &mut pending);
+
+        while let Some(file) = pending.pop() {
+            if self.components.contains_key(&file) {
+                continue;
+            }
+            match layout_file::load_layout(&self.path.join(&file)) {
+                Ok(doc) => {
+                    Self::component_refs(&doc.root, &mut pending);
+                    self.components.insert(file, doc);
+                }
+                Err(e) => {
+                    tracing::warn!(target: "iced_builder::io", path = %file.display(), error = %e, "Failed to load component layout");
+                }
+            }
+        }
+    }
+
+    /// Check whether a subtree reaches `file` through component references.
+    fn references_file(&self, node: &LayoutNode, file: &Path) -> bool {
+        let mut visited = std::collections::HashSet::new();
+        let mut pending = Vec::new();
+        Self::component_refs(node, &mut pending);
+
+        while let Some(next) = pending.pop() {
+            if next == file {
+                return true;
+            }
+            if visited.insert(next.clone()) {
+                if let Some(doc) = self.components.get(&next) {
+                    Self::component_refs(&doc.root, &mut pending);
+                }
+            }
+        }
+        false
+    }
+
+    /// Move a subtree into its own layout file and reference it in its place.
+    ///
+    /// Saves the subtree to `layouts/<name>.ron`, registers the file in the
+    /// config and replaces the subtree with a `ComponentRef`, pushing a history
+    /// entry. Returns the reference node's ID. The name becomes the component's
+    /// module name, so it must be a valid Rust identifier.
+    pub fn extract_component(&mut self, id: ComponentId, name: &str) -> Result<ComponentId, ProjectError> {
+        use crate::model::layout::WidgetType;
+        use crate::util::{is_rust_keyword, is_valid_rust_identifier};
+
+        if id == self.layout.root.id {
+            return Err(ProjectError::InvalidComponent("the root cannot be extracted".to_string()));
+        }
+        if !is_valid_rust_identifier(name) || is_rust_keyword(name) {
+            return Err(ProjectError::InvalidComponent(format!(
+                "'{}' is not a valid Rust identifier",
+                name
+            )));
+        }
+
+        let file = PathBuf::from("layouts").join(format!("{name}.ron"));
+        if self.components.contains_key(&file) || self.path.join(&file).exists() {
+            return Err(ProjectError::InvalidComponent(format!(
+                "a component named '{}' already exists",
+                name
+            )));
+        }
+
+        let node = self
+            .find_node(id)
+            .cloned()
+            .ok_or_else(|| ProjectError::InvalidComponent("component not found".to_string()))?;
+        if self.references_file(&node, &self.main_layout_file()) {
+            return Err(ProjectError::InvalidComponent(
+                "the subtree refers back to this layout".to_string(),
+            ));
+        }
+
+        let doc = LayoutDocument {
+            name: name.to_string(),
+            root: node,
+            ..LayoutDocument::default()
+        };
+        layout_file::save_layout(&self.path.join(&file), &doc)
+            .map_err(|e| ProjectError::LayoutParse(e.to_string()))?;
+
+        let snapshot = self.layout.clone();
+        let reference = LayoutNode::new(WidgetType::ComponentRef { file: file.clone() });
+        let reference_id = reference.id;
+        let Some(target) = self.find_node_mut(id) else {
+            return Err(ProjectError::InvalidComponent("component not found".to_string()));
+        };
+        *target = reference;
+        self.history.push(snapshot, format!("Extract component {}", name));
+
+        // The main layout must stay first, as it is the one loaded and saved
+        if self.config.layout_files.is_empty() {
+            let main = self.main_layout_file();
+            self.config.layout_files.push(main);
+        }
+        if !self.config.layout_files.contains(&file) {
+            self.config.layout_files.push(file.clone());
+        }
+        self.unsaved_config = true;
+
+        tracing::info!(target: "iced_builder::io", path = %file.display(), "Component extracted");
+        self.components.insert(file, doc);
+        self.rebuild_index();
+        Ok(reference_id)
+    }
+
+    /// Whether a widget can be converted to `target` by `convert_widget`.
+    ///
+    /// Column, Row and Stack convert among each other, as do Container and
+    /// Scrollable. Text and Button convert both ways, keeping their text.
+    pub fn can_convert(widget: &crate::model::layout::WidgetType, target: WidgetKind) -> bool {
+        use crate::model::layout::WidgetType as W;
+        use WidgetKind as K;
+        matches!(
+            (widget, target),
+            (W::Row { .. } | W::Stack { .. }, K::ColumnContainer)
+                | (W::Column { .. } | W::Stack { .. }, K::RowContainer)
+                | (W::Column { .. } | W::Row { .. }, K::Stack)
+                | (W::Scrollable { .. }, K::Container)
+                | (W::Container { .. }, K::Scrollable)
+                | (W::Text { .. }, K::Button)
+                | (W::Button { .. }, K::Text)
+        )
+    }
+
+    /// Widget types a node can be changed to, in palette order.
+    ///
+    /// The root may become any other container, with its children kept or
+    /// wrapped; other nodes follow `can_convert`.
+    pub fn conversion_targets(widget: &crate::model::layout::WidgetType, is_root: bool) -> Vec<WidgetKind> {
+        if is_root {
+            WidgetKind::containers()
+                .iter()
+                .copied()
+                .filter(|kind| kind.name() != widget.type_name())
+                .collect()
+        } else {
+            WidgetKind::containers()
+                .iter()
+                .chain(WidgetKind::widgets())
+                .copied()
+                .filter(|kind| Self::can_convert(widget, *kind))
+                .collect()
+        }
+    }
+
+    /// Change a node's widget type, keeping its ID, children and attributes.
+    ///
+    /// Pushes a history entry and returns true on success. Returns false
+    /// without changes if the conversion is not supported.
+    pub fn convert_widget(&mut self, id: ComponentId, target: WidgetKind) -> bool {
+        use crate::model::layout::{ButtonAttrs, LengthSpec, TextAttrs, WidgetType as W};
+        use WidgetKind as K;
+
+        let label = match self.find_node(id) {
+            Some(node) if Self::can_convert(&node.widget, target) => {
+                format!("Change {} to {}", node.widget.type_name(), target.name())
+            }
+            _ => return false,
+        };
+        let snapshot = self.layout.clone();
+        let Some(node) = self.find_node_mut(id) else {
+            return false;
+        };
+
+        let placeholder = W::Space {
+            width: LengthSpec::Shrink,
+            height: LengthSpec::Shrink,
+        };
+        node.widget = match (std::mem::replace(&mut node.widget, placeholder), target) {
+            (W::Column { children, attrs } | W::Row { children, attrs } | W::Stack { children, attrs }, K::ColumnContainer) => {
+                W::Column { children, attrs }
+            }
+            (W::Column { children, attrs } | W::Row { children, attrs } | W::Stack { children, attrs }, K::RowContainer) => {
+                W::Row { children, attrs }
+            }
+            (W::Column { children, attrs } | W::Row { children, attrs } | W::Stack { children, attrs }, K::Stack) => {
+                W::Stack { children, attrs }
+            }
+            (W::Container { child, attrs } | W::Scrollable { child, attrs }, K::Container) => {
+                W::Container { child, attrs }
+            }
+            (W::Container { child, attrs } | W::Scrollable { child, attrs }, K::Scrollable) => {
+                W::Scrollable { child, attrs }
+            }
+            (W::Text { content, .. }, K::Button) => W::Button {
+                label: content,
+                message_stub: String::from("ButtonPressed"),
+                attrs: ButtonAttrs::default(),
+            },
+            (W::Button { label, .. }, K::Text) => W::Text {
+                content: label,
+                attrs: TextAttrs::default(),
+            },
+            (widget, _) => widget,
+        };
+
+        self.history.push(snapshot, label);
+        self.rebuild_index();
+        true
+    }
+
+    /// Change the root container's type, keeping its ID and attributes.
+    ///
+    /// Children carry over when the target holds several; converting to a
+    /// single-child root wraps them in a Column. Pushes a history entry and
+    /// returns false without changes if the target is not a container or is
+    /// already the root's type.
+    pub fn change_root_type(&mut self, target: WidgetKind) -> bool {
+        use crate::model::layout::{ContainerAttrs, LengthSpec, WidgetType as W};
+        use WidgetKind as K;
+
+        if !WidgetKind::containers().contains(&target) {
+            return false;
+        }
+        let current = match &self.layout.root.widget {
+            W::Column { .. } => K::ColumnContainer,
+            W::Row { .. } => K::RowContainer,
+            W::Stack { .. } => K::Stack,
+            W::Container { .. } => K::Container,
+            W::Scrollable { .. } => K::Scrollable,
+            _ => return false,
+        };
+        if current == target {
+            return false;
+        }
+
+        let snapshot = self.layout.clone();
+        let placeholder = W::Space {
+            width: LengthSpec::Shrink,
+            height: LengthSpec::Shrink,
+        };
+        let (children, attrs) = match std::mem::replace(&mut self.layout.root.widget, placeholder) {
+            W::Column { children, attrs } | W::Row { children, attrs } | W::Stack { children, attrs } => {
+                (children, attrs)
+            }
+            W::Container { child, attrs } | W::Scrollable { child, attrs } => {
+                (child.map(|c| vec![*c]).unwrap_or_default(), attrs)
+            }
+            _ => unreachable!("root type checked above"),
+        };
+
+        let wrap = |children: Vec<LayoutNode>, attrs: &ContainerAttrs| {
+            (!children.is_empty()).then(|| {
+                Box::new(LayoutNode::new(W::Column {
+                    children,
+                    attrs: ContainerAttrs {
+                        spacing: attrs.spacing,
+                        ..ContainerAttrs::default()
+                    },
+                }))
+            })
+        };
+        self.layout.root.widget = match target {
+            K::ColumnContainer => W::Column { children, attrs },
+            K::RowContainer => W::Row { children, attrs },
+            K::Stack => W::Stack { children, attrs },
+            K::Container => W::Container { child: wrap(children, &attrs), attrs },
+            _ => W::Scrollable { child: wrap(children, &attrs), attrs },
+        };
+
+        self.history.push(snapshot, format!("Change root to {}", target.name()));
+        self.rebuild_index();
+        true
+    }
+
+    /// Insert a node next to `anchor_id` in the anchor's parent.
+    ///
+    /// Pushes a history entry and returns true on success. Fails without
+    /// changes when the anchor is the root or unknown, or its parent holds
+    /// a single child.
+    pub fn insert_sibling(&mut self, anchor_id: ComponentId, node: LayoutNode, position: Position) -> bool {
+        let Some((parent_id, anchor_index)) = self.parent_of(anchor_id) else {
+            return false;
+        };
+        let index = match position {
+            Position::Before => anchor_index,
+            Position::After => anchor_index + 1,
+        };
+
+        let multi_child = self
+            .find_node(parent_id)
+            .is_some_and(LayoutNode::is_multi_child);
+        if !multi_child {
+            return false;
+        }
+
+        let snapshot = self.layout.clone();
+        let label = format!("Add {}", node.widget.type_name());
+        if !self.insert_child_at(parent_id, index, node) {
+            return false;
+        }
+        self.history.push(snapshot, label);
+        true
+    }
+}
+
+/// Where to insert a node relative to an existing sibling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Position {
+    /// Directly before the anchor.
+    Before,
+    /// Directly after the anchor.
+    After,
+}
+
+/// Project templates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Template {
+    /// Empty layout with just a root Column.
+    Blank,
+    /// A form layout with text inputs and a submit button.
+    Form,
+    /// A dashboard layout with header and content panels.
+    Dashboard,
+    /// A scrollable list of items beside the details of the selected one.
+    MasterDetail,
+}
+
+impl Template {
+    /// All templates, in the order they are offered.
+    pub const ALL: [Template; 4] = [Template::Blank, Template::Form, Template::Dashboard, Template::MasterDetail];
+
+    /// Display name of the template.
+    pub fn name(self) -> &'static str {
+        match self {
+            Template::Blank => "Blank",
+            Template::Form => "Form",
+            Template::Dashboard => "Dashboard",
+            Template::MasterDetail => "Master-detail",
+        }
+    }
+
+    /// One-line description of what the template starts with.
+    pub fn description(self) -> &'static str {
+        match self {
+            Template::Blank => "An empty column to build from scratch",
+            Template::Form => "A title, text inputs and a submit button",
+            Template::Dashboard => "A header row above two content panels",
+            Template::MasterDetail => "A list of items beside the selected item's details",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::layout::{ButtonAttrs, ContainerAttrs, LayoutStats, TextAttrs, WidgetType};
+    use crate::model::WidgetKind;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_project_config_default() {
+        let config = ProjectConfig::default();
+        assert_eq!(config.message_type, "crate::Message");
+        assert_eq!(config.state_type, "crate::AppState");
+        assert!(config.format_output);
+    }
+
+    #[test]
+    fn test_validation_settings_apply_to_project() {
+        let config: ProjectConfig = toml::from_str("[validation]\nmax_depth = 1\n").unwrap();
+        assert_eq!(config.validation.max_depth, 1);
+        assert_eq!(ProjectConfig::default().validation.max_depth, crate::model::layout::DEFAULT_MAX_DEPTH);
+        let toml = toml::to_string_pretty(&config).unwrap();
+        assert_eq!(toml::from_str::<ProjectConfig>(&toml).unwrap().validation, config.validation);
+
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), Some(Template::Form)).unwrap();
+        assert!(!project.validate().iter().any(|e| e.message.starts_with("Nested")));
+        project.config.validation = config.validation;
+        assert!(project.validate().iter().any(|e| e.message.starts_with("Nested")));
+    }
+
+    #[test]
+    fn test_config_validate_type_paths() {
+        assert!(ProjectConfig::default().validate(Path::new("/project")).is_empty());
+
+        let config = ProjectConfig {
+            message_type: "crate::Message-Type".to_string(),
+            state_type: "super::super::ui::State".to_string(),
+            ..ProjectConfig::default()
+        };
+        let issues = config.validate(Path::new("/project"));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].field, "message_type");
+
+        for bad in ["", "crate::", "crate::ui::type", "Message<T>", "crate::::Message"] {
+            assert!(!is_valid_rust_path(bad), "{bad}");
+        }
+        for good in ["Message", "::app::Message", "self::Message", "app_state::AppState"] {
+            assert!(is_valid_rust_path(good), "{good}");
+        }
+    }
+
+    #[test]
+    fn test_config_validate_output_file() {
+        let config = ProjectConfig {
+            output_file: PathBuf::from("src/ui/view.txt"),
+            ..ProjectConfig::default()
+        };
+        assert_eq!(config.validate(Path::new("/project"))[0].field, "output_file");
+
+        let config = ProjectConfig {
+            output_file: PathBuf::from("src/ui/view"),
+            ..ProjectConfig::default()
+        };
+        assert_eq!(config.validate(Path::new("/project"))[0].field, "output_file");
+    }
+
+    #[test]
+    fn test_config_validate_layout_files() {
+        let config = ProjectConfig {
+            layout_files: vec![
+                PathBuf::from("layouts/main.ron"),
+                PathBuf::from("layouts/../other.ron"),
+                PathBuf::from("../outside.ron"),
+                PathBuf::from("/etc/layout.ron"),
+                PathBuf::from("/project/layouts/saved_as.json"),
+                PathBuf::from("/project/../elsewhere.ron"),
+            ],
+            ..ProjectConfig::default()
+        };
+        let issues = config.validate(Path::new("/project"));
+        assert_eq!(issues.len(), 3);
+        assert!(issues.iter().all(|issue| issue.field == "layout_files"));
+        assert!(issues[0].message.contains("../outside.ron"));
+        assert!(issues[1].message.contains("/etc/layout.ron"));
+        assert!(issues[2].message.contains("elsewhere.ron"));
+    }
+
+    #[test]
+    fn test_config_issues_reach_project_validation() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        project.config.state_type = "crate::App State".to_string();
+        let issues = project.validate();
+        assert_eq!(issues[0].path, "iced_builder.toml: state_type");
+        assert_eq!(issues[0].node_id, project.layout.root.id);
+        assert!(matches!(project.export(), Err(ProjectError::ValidationFailed(_))));
+    }
+
+    #[test]
+    fn test_save_reports_problems_without_blocking() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        // The blank template's empty column is only a warning
+        let problems = project.save().unwrap();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].severity, ValidationSeverity::Warning);
+
+        project.config.message_type = "not a path".to_string();
+        project.mark_dirty();
+        let problems = project.save().unwrap();
+        assert!(problems.iter().any(|p| p.severity == ValidationSeverity::Error));
+        assert!(!project.dirty);
+
+        project.config.validate_on_save = false;
+        assert!(project.save().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sample_data_round_trip() {
+        let mut config = ProjectConfig::default();
+        config.sample_data.insert("username".to_string(), SampleValue::Text("alice".to_string()));
+        config.sample_data.insert("agreed".to_string(), SampleValue::Bool(true));
+        config.sample_data.insert("volume".to_string(), SampleValue::Number(0.75));
+
+        let toml = toml::to_string_pretty(&config).unwrap();
+        let loaded: ProjectConfig = toml::from_str(&toml).unwrap();
+        assert_eq!(loaded.sample_data, config.sample_data);
+
+        // Empty sample data is omitted from the file
+        let toml = toml::to_string_pretty(&ProjectConfig::default()).unwrap();
+        assert!(!toml.contains("sample_data"));
+    }
+
+    #[test]
+    fn test_sample_value_conversions() {
+        assert_eq!(SampleValue::parse("true"), SampleValue::Bool(true));
+        assert_eq!(SampleValue::parse("42"), SampleValue::Number(42.0));
+        assert_eq!(SampleValue::parse("hello"), SampleValue::Text("hello".to_string()));
+
+        assert_eq!(SampleValue::Number(3.5).as_number(), Some(3.5));
+        assert_eq!(SampleValue::Text("2".to_string()).as_number(), Some(2.0));
+        assert_eq!(SampleValue::Bool(true).as_number(), None);
+        assert_eq!(SampleValue::Text("false".to_string()).as_bool(), Some(false));
+        assert_eq!(SampleValue::Number(1.0).as_text(), "1");
+    }
+
+    #[test]
+    fn test_project_new() {
+        let config = ProjectConfig::default();
+        let project = Project::new(PathBuf::from("/test"), config);
+        assert!(project.selected_id.is_none());
+        assert!(!project.dirty);
+    }
+
+    #[test]
+    fn test_project_create_and_open() {
         let temp = tempdir().unwrap();
         let project_dir = temp.path();
 
@@ -919,282 +2617,1963 @@ mod tests {
     }
 
     #[test]
-    fn test_project_save() {
+    fn test_project_create_master_detail_template() {
+        let temp = tempdir().unwrap();
+        let project = Project::create(temp.path(), Some(Template::MasterDetail)).unwrap();
+        assert_eq!(project.layout.name, "Master-detail");
+        assert!(!project.layout.validate().iter().any(|e| e.severity == ValidationSeverity::Error));
+
+        let reopened = Project::open(temp.path()).unwrap();
+        assert_eq!(reopened.layout, project.layout);
+    }
+
+    #[test]
+    fn test_master_detail_template_stats() {
+        let stats = Project::create_master_detail_template().stats();
+        assert_eq!(stats.node_count, 11);
+        assert_eq!(stats.max_depth, 4);
+        assert_eq!(stats.type_counts["Row"], 1);
+        assert_eq!(stats.type_counts["Scrollable"], 1);
+        assert_eq!(stats.type_counts["Column"], 2);
+        assert_eq!(stats.type_counts["Button"], 4);
+        assert_eq!(stats.type_counts["TextInput"], 1);
+        assert_eq!(stats.empty_message_stubs, 0);
+    }
+
+    #[test]
+    fn test_form_template_stats() {
+        let stats = Project::create_form_template().stats();
+        assert_eq!(stats.node_count, 5);
+        assert_eq!(stats.max_depth, 2);
+        assert_eq!(stats.type_counts["Column"], 1);
+        assert_eq!(stats.type_counts["Text"], 1);
+        assert_eq!(stats.type_counts["TextInput"], 2);
+        assert_eq!(stats.type_counts["Button"], 1);
+        assert_eq!(stats.type_counts.values().sum::<usize>(), stats.node_count);
+        assert_eq!(stats.wide_containers, 0);
+        assert_eq!(stats.empty_message_stubs, 0);
+    }
+
+    #[test]
+    fn test_template_outlines() {
+        assert_eq!(
+            Project::create_form_template().root.to_outline(0),
+            "\
+Column (spacing 10, padding 20)
+  Text \"Form Title\" size=24
+  TextInput \"Enter your name...\"
+  TextInput \"Enter your email...\"
+  Button \"Submit\"
+"
+        );
+        assert_eq!(
+            Project::create_dashboard_template().root.to_outline(0),
+            "\
+Column (spacing 20, padding 20)
+  Row (spacing 10)
+    Text \"Dashboard\" size=28
+    Space
+    Button \"Settings\"
+  Row (spacing 20)
+    Column
+      Text \"Statistics\"
+    Column
+      Text \"Activity\"
+"
+        );
+        assert_eq!(
+            Project::create_master_detail_template().root.to_outline(1),
+            "  Row (spacing 20, padding 20)
+    Scrollable
+      Column (spacing 4)
+        Button \"First item\"
+        Button \"Second item\"
+        Button \"Third item\"
+    Column (spacing 10)
+      Text \"Details\" size=24
+      Text \"Select an item to see i…\"
+      TextInput \"Notes...\"
+      Button \"Save\"
+"
+        );
+    }
+
+    #[test]
+    fn test_dashboard_template_stats() {
+        let mut layout = Project::create_dashboard_template();
+        let stats = layout.stats();
+        assert_eq!(stats.node_count, 10);
+        assert_eq!(stats.max_depth, 4);
+        assert_eq!(stats.type_counts["Column"], 3);
+        assert_eq!(stats.type_counts["Row"], 2);
+        assert_eq!(stats.type_counts["Text"], 3);
+        assert_eq!(stats.type_counts.get("Slider"), None);
+        assert_eq!(stats.wide_containers, 0);
+        assert_eq!(stats.empty_message_stubs, 0);
+
+        // Crowding the header row and blanking a stub shows up in the figures
+        let header = &mut layout.root.children_mut().unwrap()[0];
+        let children = header.children_mut().unwrap();
+        for _ in 0..LayoutStats::WIDE_CONTAINER_CHILDREN {
+            children.push(LayoutNode::new(WidgetType::Button {
+                label: String::from("More"),
+                message_stub: String::new(),
+                attrs: ButtonAttrs::default(),
+            }));
+        }
+        let stats = layout.stats();
+        assert_eq!(stats.wide_containers, 1);
+        assert_eq!(stats.empty_message_stubs, LayoutStats::WIDE_CONTAINER_CHILDREN);
+    }
+
+    #[test]
+    fn test_project_persists_history_when_enabled() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        project.config.persist_history = true;
+
+        project.history.push(project.layout.clone(), "Rename layout");
+        project.layout.name = "Renamed".to_string();
+        project.save().unwrap();
+
+        let mut reopened = Project::open(temp.path()).unwrap();
+        assert_eq!(reopened.history.undo_label(), Some("Rename layout"));
+        let (previous, _) = reopened.history.undo(reopened.layout.clone()).unwrap();
+        assert_eq!(previous.name, "Untitled");
+
+        // An edit made outside the builder invalidates the saved history
+        let layout_path = temp.path().join("layout.ron");
+        let edited = std::fs::read_to_string(&layout_path).unwrap().replace("Renamed", "Edited");
+        std::fs::write(&layout_path, edited).unwrap();
+        let reopened = Project::open(temp.path()).unwrap();
+        assert!(!reopened.history.can_undo());
+    }
+
+    #[test]
+    fn test_project_history_not_persisted_by_default() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        project.history.push(project.layout.clone(), "Edit");
+        project.save().unwrap();
+
+        assert!(!history_file::history_path(temp.path()).exists());
+        let reopened = Project::open(temp.path()).unwrap();
+        assert!(!reopened.history.can_undo());
+
+        // A corrupt history file is ignored even when enabled
+        let mut config = reopened.config.clone();
+        config.persist_history = true;
+        config::save_config(&temp.path().join("iced_builder.toml"), &config).unwrap();
+        let path = history_file::history_path(temp.path());
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "garbage").unwrap();
+        let reopened = Project::open(temp.path()).unwrap();
+        assert!(!reopened.history.can_undo());
+    }
+
+    #[test]
+    fn test_project_recovers_autosave() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        // Keep mtimes apart on filesystems with coarse timestamps
+        let past = std::time::SystemTime::now() - std::time::Duration::from_secs(60);
+        std::fs::File::options()
+            .write(true)
+            .open(temp.path().join("layout.ron"))
+            .unwrap()
+            .set_modified(past)
+            .unwrap();
+
+        project.history.push(project.layout.clone(), "Rename layout");
+        project.layout.name = "Unsaved".to_string();
+        project.mark_dirty();
+        project.autosave().unwrap();
+        assert!(project.dirty);
+
+        let mut reopened = Project::open(temp.path()).unwrap();
+        assert_eq!(reopened.layout.name, "Untitled");
+        assert!(reopened.has_recoverable_autosave());
+        reopened.recover_autosave().unwrap();
+        assert_eq!(reopened.layout.name, "Unsaved");
+        assert!(reopened.dirty);
+        assert_eq!(reopened.history.undo_label(), Some("Recover unsaved changes"));
+
+        reopened.save().unwrap();
+        assert!(!autosave::autosave_path(temp.path()).exists());
+        assert!(!Project::open(temp.path()).unwrap().has_recoverable_autosave());
+    }
+
+    #[test]
+    fn test_project_recovers_crashed_session() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        project.layout.name = "Unsaved".to_string();
+        let handle = crash_recovery::CrashHandle::default();
+        handle.set(Some(&project.path), &project.layout);
+        handle.write().unwrap().unwrap();
+
+        let mut reopened = Project::open(temp.path()).unwrap();
+        assert!(reopened.has_crash_recovery());
+        reopened.recover_crash().unwrap();
+        assert_eq!(reopened.layout.name, "Unsaved");
+        assert!(reopened.dirty);
+        assert_eq!(reopened.history.undo_label(), Some("Recover crashed session"));
+        assert!(!reopened.has_crash_recovery());
+    }
+
+    #[test]
+    fn test_project_detects_and_reloads_external_changes() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        assert!(!project.layout_changed_on_disk());
+
+        let mut edited = project.layout.clone();
+        edited.name = "Edited elsewhere".to_string();
+        layout_file::save_layout_with_backup(&project.layout_path(), &edited, 0).unwrap();
+        assert!(project.layout_changed_on_disk());
+
+        project.keep_local_layout();
+        assert!(!project.layout_changed_on_disk());
+
+        edited.name = "Edited again".to_string();
+        layout_file::save_layout_with_backup(&project.layout_path(), &edited, 0).unwrap();
+        project.history.push(project.layout.clone(), "Rename layout");
+        project.layout.name = "Mine".to_string();
+        project.mark_dirty();
+        project.reload_layout().unwrap();
+        assert_eq!(project.layout.name, "Edited again");
+        assert!(!project.dirty);
+        assert!(!project.layout_changed_on_disk());
+        assert_eq!(project.history.undo_label(), Some("Reload from disk"));
+
+        // Our own saves are not external changes
+        project.layout.name = "Saved".to_string();
+        project.save().unwrap();
+        assert!(!project.layout_changed_on_disk());
+    }
+
+    #[test]
+    fn test_project_save_rotates_backups_and_restores_them() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        project.config.backup_count = 2;
+        for name in ["First", "Second", "Third"] {
+            project.layout.name = name.to_string();
+            project.save().unwrap();
+        }
+
+        let backups = project.layout_backups();
+        assert_eq!(backups.len(), 2);
+        assert_eq!(crate::io::backups_for(&temp.path().join("iced_builder.toml")).len(), 2);
+
+        project.restore_backup(&backups[1]).unwrap();
+        assert_eq!(project.layout.name, "First");
+        assert!(project.dirty);
+        let (previous, label) = project.history.undo(project.layout.clone()).unwrap();
+        assert_eq!(previous.name, "Third");
+        assert!(label.starts_with("Restore backup from "));
+
+        project.config.backup_count = 0;
+        project.save().unwrap();
+        assert_eq!(project.layout_backups().len(), 2);
+    }
+
+    #[test]
+    fn test_project_save_as_converts_and_switches_layout_file() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        project.layout.name = "Converted".to_string();
+        project.mark_dirty();
+
+        let json = temp.path().join("ui").join("main.json");
+        project.save_as(&json).unwrap();
+        assert!(!project.dirty);
+        assert_eq!(project.config.layout_files[0], PathBuf::from("ui/main.json"));
+        assert_eq!(project.layout_path(), json);
+        assert!(crate::io::backups_for(&json).is_empty());
+
+        let reopened = Project::open(temp.path()).unwrap();
+        assert_eq!(reopened.layout, project.layout);
+        assert!(project.save_as(&temp.path().join("layout.txt")).is_err());
+    }
+
+    #[test]
+    fn test_project_save_as_outside_keeps_project_exportable() {
+        let temp = tempdir().unwrap();
+        let dir = temp.path().join("project");
+        let mut project = Project::create(&dir, None).unwrap();
+
+        let outside = temp.path().join("elsewhere").join("layout.ron");
+        assert!(matches!(project.save_as(&outside), Err(ProjectError::OutsideProject(_))));
+        assert!(!outside.exists());
+        assert_eq!(project.config.layout_file(), Path::new("layout.ron"));
+        assert!(project.export_blockers().is_empty());
+        project.export().unwrap();
+
+        // An absolute path into the project is stored relative to it
+        project.save_as(&std::path::absolute(dir.join("ui/main.json")).unwrap()).unwrap();
+        assert_eq!(project.config.layout_files[0], PathBuf::from("ui/main.json"));
+        project.export().unwrap();
+    }
+
+    #[test]
+    fn test_project_reports_and_drops_unknown_fields() {
+        let temp = tempdir().unwrap();
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/layout_unknown_fields.ron");
+        Project::create(temp.path(), None).unwrap();
+        std::fs::copy(&fixture, temp.path().join("layout.ron")).unwrap();
+
+        let mut project = Project::open(temp.path()).unwrap();
+        assert_eq!(project.load_warnings.len(), 4);
+        assert_eq!(project.layout.name, "Greeting");
+
+        project.save().unwrap();
+        assert!(project.load_warnings.is_empty());
+        let saved = std::fs::read_to_string(temp.path().join("layout.ron")).unwrap();
+        assert!(!saved.contains("tooltip"));
+        assert!(Project::open(temp.path()).unwrap().load_warnings.is_empty());
+    }
+
+    #[test]
+    fn test_project_bundle_round_trip() {
+        let temp = tempdir().unwrap();
+        let source = temp.path().join("source");
+        let mut project = Project::create(&source, None).unwrap();
+        project.layout.name = "Shared".to_string();
+        project.config.codegen_template = Some(PathBuf::from("../outside.jinja"));
+        project.save().unwrap();
+        std::fs::write(temp.path().join("outside.jinja"), "").unwrap();
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Card".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        crate::io::save_snippet(&source, "Card", &text).unwrap();
+
+        let files = project.bundle_files();
+        assert_eq!(files[..2], [PathBuf::from("iced_builder.toml"), PathBuf::from("layout.ron")]);
+        assert!(files.contains(&Path::new("snippets").join("Card.ron")));
+        assert!(!files.iter().any(|f| f.ends_with("outside.jinja")));
+
+        let bundle = temp.path().join("shared.icedproj");
+        assert_eq!(project.export_bundle(&bundle).unwrap(), files.len());
+        let target = temp.path().join("copy");
+        crate::io::bundle::extract_bundle(&bundle, &target, false).unwrap();
+
+        let imported = Project::open(&target).unwrap();
+        assert_eq!(imported.layout, project.layout);
+        assert_eq!(crate::io::list_snippets(&target).len(), 1);
+    }
+
+    #[test]
+    fn test_project_save_project_as_forks_into_new_directory() {
+        let temp = tempdir().unwrap();
+        let source = temp.path().join("source");
+        let mut project = Project::create(&source, None).unwrap();
+        project.config.output_file = PathBuf::from("../app/src/view.rs");
+        project.config.strings_file = PathBuf::from("strings.toml");
+        project.save().unwrap();
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Card".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        crate::io::save_snippet(&source, "Card", &text).unwrap();
+        let saved_layout = std::fs::read_to_string(project.layout_path()).unwrap();
+
+        // Unsaved edits go to the copy only
+        assert!(project.add_child_to_root(text));
+        project.mark_dirty();
+        let target = temp.path().join("fork");
+        assert_eq!(project.save_project_as(&target).unwrap(), 3);
+
+        assert_eq!(project.path, std::path::absolute(&target).unwrap());
+        assert!(!project.dirty);
+        assert_eq!(std::fs::read_to_string(source.join("layout.ron")).unwrap(), saved_layout);
+        assert_eq!(project.output_path(), std::path::absolute(&source).unwrap().join("../app/src/view.rs"));
+        assert_eq!(project.config.strings_file, PathBuf::from("strings.toml"));
+
+        let reopened = Project::open(&target).unwrap();
+        assert_eq!(reopened.layout, project.layout);
+        assert_eq!(crate::io::list_snippets(&target).len(), 1);
+        assert!(matches!(project.save_project_as(&target), Err(ProjectError::SameLocation(_))));
+    }
+
+    #[test]
+    fn test_project_undo_back_to_saved_state_is_clean() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Hello".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        project.history.push(project.layout.clone(), "Add Text");
+        assert!(project.add_child_to_root(text));
+        project.mark_dirty();
+        assert!(project.dirty);
+
+        assert_eq!(project.undo().as_deref(), Some("Add Text"));
+        assert!(!project.dirty);
+        assert_eq!(project.redo().as_deref(), Some("Add Text"));
+        assert!(project.dirty);
+    }
+
+    #[test]
+    fn test_project_undo_past_save_is_dirty() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+
+        project.history.push(project.layout.clone(), "Rename layout");
+        project.layout.name = "Renamed".to_string();
+        project.mark_dirty();
+        project.save().unwrap();
+        assert!(!project.dirty);
+
+        project.undo();
+        assert!(project.dirty);
+        project.redo();
+        assert!(!project.dirty);
+
+        // Config changes stay dirty whatever the history position
+        project.config.sample_data.insert("name".to_string(), SampleValue::Text("Ada".to_string()));
+        project.mark_config_dirty();
+        assert!(project.jump_to_history(0));
+        assert!(project.jump_to_history(1));
+        assert!(project.dirty);
+    }
+
+    #[test]
+    fn test_project_save() {
+        let temp = tempdir().unwrap();
+        let project_dir = temp.path();
+
+        let mut project = Project::create(project_dir, None).unwrap();
+        project.layout.name = "Test Layout".to_string();
+        project.mark_dirty();
+        assert!(project.dirty);
+
+        project.save().unwrap();
+        assert!(!project.dirty);
+
+        // Re-open and verify
+        let reopened = Project::open(project_dir).unwrap();
+        assert_eq!(reopened.layout.name, "Test Layout");
+    }
+
+    #[test]
+    fn test_project_export() {
+        let temp = tempdir().unwrap();
+        let project_dir = temp.path();
+
+        // Create output directory
+        std::fs::create_dir_all(project_dir.join("src/ui")).unwrap();
+
+        let project = Project::create(project_dir, None).unwrap();
+        let code = project.export().unwrap().code;
+
+        assert!(code.contains("pub fn view"));
+        assert!(code.contains("Element"));
+
+        // Verify output file was created
+        assert!(project_dir.join("src/ui/layout_generated.rs").exists());
+    }
+
+    #[test]
+    fn test_project_export_writes_message_stub() {
+        let temp = tempdir().unwrap();
+        let project = Project::create(temp.path(), Some(Template::Form)).unwrap();
+        project.export().unwrap();
+
+        let stub_path = temp.path().join("src/ui/message_stub.rs");
+        let stub = std::fs::read_to_string(&stub_path).unwrap();
+        assert!(stub.starts_with(crate::codegen::GENERATED_HEADER));
+        assert!(stub.contains("pub enum Message"));
+
+        // Exporting again replaces our own file
+        project.export().unwrap();
+    }
+
+    #[test]
+    fn test_project_export_inlines_components() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        let card = LayoutNode::new(WidgetType::Column {
+            children: vec![
+                LayoutNode::new(WidgetType::TextInput {
+                    placeholder: "Title".to_string(),
+                    value_binding: "card_title".to_string(),
+                    message_stub: "CardTitleChanged".to_string(),
+                    attrs: crate::model::layout::InputAttrs::default(),
+                }),
+                LayoutNode::new(WidgetType::Button {
+                    label: "Save".to_string(),
+                    message_stub: "SaveCard".to_string(),
+                    attrs: ButtonAttrs::default(),
+                }),
+            ],
+            attrs: ContainerAttrs::default(),
+        });
+        let card_id = card.id;
+        assert!(project.add_child_to_root(card));
+        project.extract_component(card_id, "card").unwrap();
+
+        // The component's widgets, messages and bindings are generated in place
+        let code = project.export().unwrap().code;
+        let file = syn::parse_file(&code).unwrap();
+        assert!(file.items.iter().any(|item| matches!(item, syn::Item::Fn(f) if f.sig.ident == "view")));
+        assert!(!code.contains("card::view"));
+        assert!(code.contains("Message::SaveCard"));
+        assert!(code.contains("&state.card_title"));
+
+        let stub = std::fs::read_to_string(temp.path().join("src/ui/message_stub.rs")).unwrap();
+        syn::parse_file(&stub).unwrap();
+        assert!(stub.contains("SaveCard"));
+        assert!(stub.contains("CardTitleChanged(String)"));
+
+        // A reference that can't be inlined blocks export
+        project.components.clear();
+        assert!(matches!(project.export(), Err(ProjectError::ValidationFailed(_))));
+    }
+
+    #[test]
+    fn test_dashboard_template_generates_identical_code() {
+        // Separate projects get fresh node ids, which must not leak into the
+        // output; stability across runs is pinned by the hashes in the
+        // snapshot tests.
+        let generate = |mode: ExportMode, style: CodegenStyle| {
+            let temp = tempdir().unwrap();
+            let mut project = Project::create(temp.path(), Some(Template::Dashboard)).unwrap();
+            project.config.export_mode = mode;
+            project.config.codegen_style = style;
+            project.config.color_constants = true;
+            project.config.generate_update_skeleton = true;
+            let stubs = crate::codegen::generate_stubs(&project.layout, &project.config).unwrap();
+            (crate::codegen::generate_code(&project.layout, &project.config), stubs)
+        };
+
+        for mode in [ExportMode::ViewOnly, ExportMode::Component] {
+            for style in [CodegenStyle::Macros, CodegenStyle::Builders] {
+                assert_eq!(generate(mode, style), generate(mode, style));
+            }
+        }
+    }
+
+    #[test]
+    fn test_form_template_code_in_both_styles() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), Some(Template::Form)).unwrap();
+        let header = |hash: &str| {
+            format!(
+                "// Auto-generated by Iced Builder – do not edit manually.\n\
+                 // Regenerate by opening this project in Iced Builder.\n\
+                 // Layout: Form (layout.ron), content hash {}\n\
+                 // Iced Builder {}\n\n",
+                hash,
+                env!("CARGO_PKG_VERSION")
+            )
+        };
+        let imports = r#"use iced::widget::{
+    button, checkbox, column, container, pick_list, row,
+    scrollable, slider, stack, text, text_input, Space,
+"#;
+
+        let code = crate::codegen::generate_code(&project.layout, &project.config);
+        let expected = r#"};
+use iced::{Alignment, Color, Element, Length};
+
+use crate::Message;
+use crate::AppState;
+
+pub fn view(state: &AppState) -> Element<Message> {
+    column![
+        text("Form Title").size(24),
+        text_input("Enter your name...", &state.name).on_input(Message::NameChanged),
+        text_input("Enter your email...", &state.email).on_input(Message::EmailChanged),
+        button(text("Submit")).on_press(Message::Submit)
+    ].padding(20).spacing(10).into()
+}
+"#;
+        assert_eq!(code, format!("{}{}{}", header("8f9d1c8cc966fc5b"), imports, expected));
+
+        project.config.codegen_style = CodegenStyle::Builders;
+        let code = crate::codegen::generate_code(&project.layout, &project.config);
+        let expected = r#"    Column, Row, Stack,
+};
+use iced::{Alignment, Color, Element, Length};
+
+use crate::Message;
+use crate::AppState;
+
+pub fn view(state: &AppState) -> Element<Message> {
+    Column::new()
+        .push(text("Form Title").size(24))
+        .push(text_input("Enter your name...", &state.name).on_input(Message::NameChanged))
+        .push(text_input("Enter your email...", &state.email).on_input(Message::EmailChanged))
+        .push(button(text("Submit")).on_press(Message::Submit)).padding(20).spacing(10).into()
+}
+"#;
+        assert_eq!(code, format!("{}{}{}", header("bd632bce491e6e15"), imports, expected));
+    }
+
+    #[test]
+    fn test_component_export_of_form_template() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), Some(Template::Form)).unwrap();
+        project.config.export_mode = ExportMode::Component;
+
+        let code = crate::codegen::generate_code(&project.layout, &project.config);
+        let expected = r#"// Auto-generated by Iced Builder – do not edit manually.
+// Regenerate by opening this project in Iced Builder.
+// Layout: Form (layout.ron), content hash 63df4c6382db2309
+// Iced Builder {version}
+
+use iced::widget::{
+    button, checkbox, column, container, pick_list, row,
+    scrollable, slider, stack, text, text_input, Space,
+};
+use iced::{Alignment, Color, Element, Length};
+
+/// State of the Form component.
+#[derive(Debug, Clone, Default)]
+pub struct FormState {
+    pub email: String,
+    pub name: String,
+}
+
+/// Messages produced by the generated view.
+#[derive(Debug, Clone)]
+pub enum FormMessage {
+    EmailChanged(String),
+    NameChanged(String),
+    Submit,
+}
+
+impl FormState {
+    /// Apply a message to the state.
+    pub fn update(&mut self, message: FormMessage) {
+        match message {
+            FormMessage::EmailChanged(value) => {
+                self.email = value;
+            }
+            FormMessage::NameChanged(value) => {
+                self.name = value;
+            }
+            FormMessage::Submit => {
+                // todo!("handle Submit")
+            }
+        }
+    }
+
+    /// Render the component.
+    pub fn view(&self) -> Element<'_, FormMessage> {
+        column![
+            text("Form Title").size(24),
+            text_input("Enter your name...", &self.name).on_input(FormMessage::NameChanged),
+            text_input("Enter your email...", &self.email).on_input(FormMessage::EmailChanged),
+            button(text("Submit")).on_press(FormMessage::Submit)
+        ].padding(20).spacing(10).into()
+    }
+}
+"#;
+        assert_eq!(code, expected.replace("{version}", env!("CARGO_PKG_VERSION")));
+
+        // The component carries its own messages, so no stub file is written
+        project.export().unwrap();
+        assert!(!temp.path().join("src/ui/message_stub.rs").exists());
+    }
+
+    #[test]
+    fn test_project_export_keeps_hand_written_stub() {
+        let temp = tempdir().unwrap();
+        let project = Project::create(temp.path(), None).unwrap();
+        let stub_path = temp.path().join("src/ui/message_stub.rs");
+        std::fs::create_dir_all(stub_path.parent().unwrap()).unwrap();
+        std::fs::write(&stub_path, "pub enum Message { Mine }\n").unwrap();
+
+        let result = project.export();
+        assert!(matches!(result, Err(ProjectError::ForeignFile(_))));
+        assert_eq!(std::fs::read_to_string(&stub_path).unwrap(), "pub enum Message { Mine }\n");
+        assert!(!temp.path().join("src/ui/layout_generated.rs").exists());
+
+        let mut project = project;
+        project.config.generate_message_stub = false;
+        assert!(project.export().is_ok());
+    }
+
+    #[test]
+    fn test_project_export_with_tests() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), Some(Template::Form)).unwrap();
+        let output = temp.path().join("src/ui/layout_generated.rs");
+        project.export().unwrap();
+        assert!(!std::fs::read_to_string(&output).unwrap().contains("#[cfg(test)]"));
+
+        project.config.generate_tests = true;
+        project.export().unwrap();
+        let code = std::fs::read_to_string(&output).unwrap();
+        syn::parse_file(&code).unwrap();
+        assert!(code.contains("#[cfg(test)]"));
+        assert!(code.contains("let state = AppState::default();"));
+        assert!(code.contains("let element = view(&state);"));
+        assert!(code.contains("\"4[0, 0, 0, 1[0]]\""));
+
+        project.config.export_mode = ExportMode::Component;
+        project.export().unwrap();
+        let code = std::fs::read_to_string(&output).unwrap();
+        assert!(code.contains("let state = FormState::default();"));
+        assert!(code.contains("let element = state.view();"));
+    }
+
+    #[test]
+    fn test_project_export_with_translation_keys() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), Some(Template::Form)).unwrap();
+        project.layout.root.children_mut().unwrap()[0].name = Some("Title".to_string());
+        project.config.i18n_mode = I18nMode::Keys;
+        project.save().unwrap();
+        project.export().unwrap();
+
+        let output = temp.path().join("src/ui/layout_generated.rs");
+        let strings_path = temp.path().join("src/ui/strings.toml");
+        let code = std::fs::read_to_string(&output).unwrap();
+        let strings = std::fs::read_to_string(&strings_path).unwrap();
+        assert!(code.contains("text(t(\"form.title\")).size(24)"));
+        assert!(code.contains("button(text(t(\"form.button_"));
+        assert!(strings.contains("\"form.title\" = \"Form Title\"\n"));
+        assert!(strings.contains("= \"Submit\"\n"));
+
+        // Reopening and exporting again keeps every key
+        let reopened = Project::open(temp.path()).unwrap();
+        reopened.export().unwrap();
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), code);
+        assert_eq!(std::fs::read_to_string(&strings_path).unwrap(), strings);
+
+        std::fs::write(&strings_path, "title = \"Mine\"\n").unwrap();
+        assert!(matches!(reopened.export(), Err(ProjectError::ForeignFile(_))));
+    }
+
+    #[test]
+    fn test_project_export_with_template() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), Some(Template::Form)).unwrap();
+        project.config.codegen_template = Some(PathBuf::from("codegen/view.rs.jinja"));
+        project.config.generate_message_stub = false;
+        let output = temp.path().join("src/ui/layout_generated.rs");
+
+        // Without the template file the built-in generator is used
+        project.export().unwrap();
+        let built_in = std::fs::read_to_string(&output).unwrap();
+        assert!(built_in.contains("pub fn view(state: &AppState)"));
+
+        std::fs::create_dir_all(temp.path().join("codegen")).unwrap();
+        let template = temp.path().join("codegen/view.rs.jinja");
+        std::fs::write(&template, "// {{ messages | length }} messages\n{% for b in bindings %}{{ b.name }}\n{% endfor %}").unwrap();
+        project.export().unwrap();
+        let code = std::fs::read_to_string(&output).unwrap();
+        assert!(code.starts_with(crate::codegen::GENERATED_HEADER));
+        assert!(code.contains("\n// 3 messages\nemail\nname\n// iced-builder:hash="));
+        assert_eq!(crate::codegen::stamp_matches(&code), Some(true));
+
+        std::fs::write(&template, "ok\n{% for b in bindings %}\n").unwrap();
+        match project.export() {
+            Err(ProjectError::Template(e)) => assert_eq!(e.line, Some(2)),
+            other => panic!("expected a template error, got {:?}", other),
+        }
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), code);
+    }
+
+    #[test]
+    fn test_project_export_refuses_invalid_layout() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        let button = LayoutNode::new(WidgetType::Button {
+            label: "Back".to_string(),
+            message_stub: "return".to_string(),
+            attrs: ButtonAttrs::default(),
+        });
+        let button_id = button.id;
+        assert!(project.add_child_to_root(button));
+
+        match project.export() {
+            Err(ProjectError::ValidationFailed(errors)) => {
+                assert_eq!(errors.len(), 1);
+                assert_eq!(errors[0].node_id, button_id);
+                assert!(errors[0].message.contains("Rust keyword"));
+            }
+            other => panic!("expected ValidationFailed, got {:?}", other),
+        }
+        assert!(matches!(project.export_example(), Err(ProjectError::ValidationFailed(_))));
+        assert!(!temp.path().join("src/ui/layout_generated.rs").exists());
+        assert!(!temp.path().join("src/ui/message_stub.rs").exists());
+    }
+
+    #[test]
+    fn test_project_export_with_errors_allowed() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        assert!(project.add_child_to_root(LayoutNode::new(WidgetType::Button {
+            label: "Back".to_string(),
+            message_stub: "return".to_string(),
+            attrs: ButtonAttrs::default(),
+        })));
+        project.config.allow_export_with_errors = true;
+
+        assert!(project.export_blockers().is_empty());
+        assert!(project.export().is_ok());
+        let code = std::fs::read_to_string(temp.path().join("src/ui/layout_generated.rs")).unwrap();
+        assert!(code.contains("Message::return"));
+    }
+
+    #[test]
+    fn test_project_find_node() {
+        let temp = tempdir().unwrap();
+        let project_dir = temp.path();
+
+        let project = Project::create(project_dir, Some(Template::Form)).unwrap();
+        
+        // Should be able to find the root node
+        let root_id = project.layout.root.id;
+        let found = project.find_node(root_id);
+        assert!(found.is_some());
+    }
+
+    #[test]
+    fn test_project_create_for_layout_uses_existing_file() {
+        let temp = tempdir().unwrap();
+        let layout = LayoutDocument {
+            name: "Loose".to_string(),
+            ..LayoutDocument::default()
+        };
+        let file = temp.path().join("screen.json");
+        crate::io::save_layout(&file, &layout).unwrap();
+
+        let project = Project::create_for_layout(&file).unwrap();
+        assert_eq!(project.layout.name, "Loose");
+        assert_eq!(project.config.layout_files, vec![PathBuf::from("screen.json")]);
+        assert!(crate::io::is_valid_project(temp.path()));
+    }
+
+    #[test]
+    fn test_project_open_missing_config() {
+        let temp = tempdir().unwrap();
+        let project_dir = temp.path();
+
+        // Try to open a project without config
+        let result = Project::open(project_dir);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_project_remove_node() {
+        let temp = tempdir().unwrap();
+        let project_dir = temp.path();
+
+        let mut project = Project::create(project_dir, Some(Template::Form)).unwrap();
+        
+        // Get the root and find a child to remove
+        let root_id = project.layout.root.id;
+        
+        // Get a child node ID
+        let child_id = project.layout.root.children()
+            .expect("Root should be a container")
+            .first()
+            .expect("Should have at least one child")
+            .id;
+        
+        // Cannot remove root
+        assert!(!project.remove_node(root_id));
+        
+        // Can remove child
+        assert!(project.remove_node(child_id));
+        
+        // Child should no longer be findable
+        assert!(project.find_node(child_id).is_none());
+    }
+
+    #[test]
+    fn test_structural_edits_update_index_incrementally() {
+        use crate::model::layout::build_node_index;
+
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        let text = |i: usize| {
+            LayoutNode::new(WidgetType::Text {
+                content: format!("Item {}", i),
+                attrs: TextAttrs::default(),
+            })
+        };
+        for _ in 0..40 {
+            let group = LayoutNode::new(WidgetType::Column {
+                children: (0..25).map(text).collect(),
+                attrs: ContainerAttrs::default(),
+            });
+            assert!(project.add_child_to_root(group));
+        }
+        assert_eq!(project.node_index.len(), 1041);
+        assert_eq!(project.node_index, build_node_index(&project.layout.root));
+
+        // A full rebuild would drop this entry; incremental updates leave
+        // everything outside the edited parent alone
+        let stray = ComponentId::new();
+        project.node_index.insert(stray, vec![99]);
+        let index_without_stray = |project: &Project| {
+            let mut index = project.node_index.clone();
+            assert_eq!(index.remove(&stray), Some(vec![99]));
+            index
+        };
+
+        let groups: Vec<ComponentId> = project.layout.root.child_nodes().iter().map(|n| n.id).collect();
+        let added = text(0);
+        let added_id = added.id;
+        assert!(project.insert_child_at(groups[20], 3, added));
+        assert_eq!(project.node_index[&added_id], vec![20, 3]);
+        assert_eq!(index_without_stray(&project), build_node_index(&project.layout.root));
+
+        let moved = project.layout.root.child_nodes()[5].child_nodes()[0].id;
+        assert!(project.move_node(moved, groups[30], 0));
+        assert_eq!(project.node_index[&moved], vec![30, 0]);
+        assert_eq!(index_without_stray(&project), build_node_index(&project.layout.root));
+
+        let copy = project.duplicate_node(groups[2]).unwrap();
+        assert_eq!(project.node_index[&copy], vec![3]);
+        assert_eq!(project.node_index[&groups[39]], vec![40]);
+        assert_eq!(index_without_stray(&project), build_node_index(&project.layout.root));
+
+        assert!(project.remove_node(groups[0]));
+        assert!(!project.node_index.contains_key(&groups[0]));
+        assert_eq!(project.node_index[&groups[39]], vec![39]);
+        assert_eq!(index_without_stray(&project), build_node_index(&project.layout.root));
+
+        // Stack children are indexed the same way, incrementally or not
+        let stack = LayoutNode::new(WidgetType::Stack {
+            children: vec![text(0)],
+            attrs: ContainerAttrs::default(),
+        });
+        let stack_id = stack.id;
+        assert!(project.add_child_to_root(stack));
+        let layered = text(1);
+        let layered_id = layered.id;
+        assert!(project.add_child_to_node(stack_id, layered));
+        assert_eq!(project.node_index[&layered_id], vec![40, 1]);
+        assert_eq!(index_without_stray(&project), build_node_index(&project.layout.root));
+        project.rebuild_index();
+        assert_eq!(project.node_index[&layered_id], vec![40, 1]);
+        assert_eq!(project.find_node(layered_id).map(|node| node.id), Some(layered_id));
+    }
+
+    #[test]
+    fn test_typing_into_a_node_records_only_that_node() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        for i in 0..500 {
+            assert!(project.add_child_to_root(LayoutNode::new(WidgetType::Text {
+                content: format!("Item {}", i),
+                attrs: TextAttrs::default(),
+            })));
+        }
+        let id = project.layout.root.child_nodes()[250].id;
+        let original = project.layout.clone();
+
+        // Snapshots of this layout would hold over 2500 nodes
+        for typed in ["H", "He", "Hel", "Hell", "Hello"] {
+            assert!(project.update_node(id, "Edit text", |node| {
+                if let WidgetType::Text { content, .. } = &mut node.widget {
+                    *content = typed.to_string();
+                }
+            }));
+        }
+        assert!(project.history.memory_estimate() < 50 * std::mem::size_of::<LayoutNode>());
+        assert!(project.dirty);
+        assert!(!project.update_node(ComponentId::new(), "Edit text", |_| unreachable!()));
+        assert_eq!(project.history.undo_count(), 5);
+
+        while project.undo().is_some() {}
+        assert_eq!(project.layout, original);
+    }
+
+    #[test]
+    fn test_project_remove_node_nested() {
+        let temp = tempdir().unwrap();
+        let project_dir = temp.path();
+
+        let mut project = Project::create(project_dir, Some(Template::Dashboard)).unwrap();
+        
+        // Dashboard has nested structure, find a deeply nested node
+        let children = project.layout.root.children().unwrap();
+        if let Some(first_child) = children.first() {
+            if let Some(nested_children) = first_child.children() {
+                if let Some(nested_child) = nested_children.first() {
+                    let nested_id = nested_child.id;
+                    
+                    // Remove the nested node
+                    assert!(project.remove_node(nested_id));
+                    
+                    // Verify it's gone
+                    assert!(project.find_node(nested_id).is_none());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_project_remove_nonexistent_node() {
+        let temp = tempdir().unwrap();
+        let project_dir = temp.path();
+
+        let mut project = Project::create(project_dir, None).unwrap();
+        
+        // Try to remove a node that doesn't exist
+        let fake_id = crate::model::layout::ComponentId::new();
+        assert!(!project.remove_node(fake_id));
+    }
+
+    #[test]
+    fn test_project_history_integration() {
+        let temp = tempdir().unwrap();
+        let project_dir = temp.path();
+
+        let mut project = Project::create(project_dir, Some(Template::Form)).unwrap();
+        
+        // Initially no undo/redo available
+        assert!(!project.history.can_undo());
+        assert!(!project.history.can_redo());
+        
+        // Push a snapshot
+        project.history.push(project.layout.clone(), "Edit");
+        
+        // Now undo should be available
+        assert!(project.history.can_undo());
+        assert!(!project.history.can_redo());
+        
+        // Get a child and modify
+        let child_id = project.layout.root.children()
+            .unwrap()
+            .first()
+            .unwrap()
+            .id;
+        
+        // Remove the child
+        project.remove_node(child_id);
+        
+        // Undo should restore the child
+        let (prev, _) = project.history.undo(project.layout.clone()).unwrap();
+        project.layout = prev;
+        project.rebuild_index();
+        
+        // The child should be findable again
+        assert!(project.find_node(child_id).is_some());
+    }
+
+    #[test]
+    fn test_project_is_container() {
+        let temp = tempdir().unwrap();
+        let project_dir = temp.path();
+
+        let mut project = Project::create(project_dir, Some(Template::Form)).unwrap();
+        
+        // Root should be a container (Column)
+        let root_id = project.layout.root.id;
+        assert!(project.is_container(root_id));
+        
+        // Add a non-container widget (Button) to root
+        let button = LayoutNode::new(WidgetType::Button {
+            label: "Test".to_string(),
+            message_stub: "TestMsg".to_string(),
+            attrs: ButtonAttrs::default(),
+        });
+        let button_id = button.id;
+        assert!(project.add_child_to_root(button));
+        
+        // Button should not be a container
+        assert!(!project.is_container(button_id));
+    }
+
+    #[test]
+    fn test_project_add_child_to_root() {
+        let temp = tempdir().unwrap();
+        let project_dir = temp.path();
+
+        let mut project = Project::create(project_dir, None).unwrap();
+        
+        let initial_count = project.layout.root.children().unwrap().len();
+        
+        // Add a text widget to root
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Hello".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        let text_id = text.id;
+        assert!(project.add_child_to_root(text));
+        
+        // Verify it was added
+        assert_eq!(
+            project.layout.root.children().unwrap().len(),
+            initial_count + 1
+        );
+        
+        // Verify we can find it
+        assert!(project.find_node(text_id).is_some());
+    }
+
+    #[test]
+    fn test_project_add_child_to_node() {
+        let temp = tempdir().unwrap();
+        let project_dir = temp.path();
+
+        let mut project = Project::create(project_dir, None).unwrap();
+        
+        // Add a Row container first
+        let row = LayoutNode::new(WidgetType::Row {
+            children: Vec::new(),
+            attrs: ContainerAttrs::default(),
+        });
+        let row_id = row.id;
+        assert!(project.add_child_to_root(row));
+        
+        // Now add a button to the Row
+        let button = LayoutNode::new(WidgetType::Button {
+            label: "Click".to_string(),
+            message_stub: "Clicked".to_string(),
+            attrs: ButtonAttrs::default(),
+        });
+        let button_id = button.id;
+        assert!(project.add_child_to_node(row_id, button));
+        
+        // Verify button was added to the row
+        let row_node = project.find_node(row_id).expect("Row should exist");
+        let row_children = row_node.children().expect("Row should have children");
+        assert!(row_children.iter().any(|c| c.id == button_id));
+    }
+
+    #[test]
+    fn test_project_add_child_to_non_container() {
+        let temp = tempdir().unwrap();
+        let project_dir = temp.path();
+
+        let mut project = Project::create(project_dir, None).unwrap();
+        
+        // Add a button first (non-container)
+        let button = LayoutNode::new(WidgetType::Button {
+            label: "Test".to_string(),
+            message_stub: "TestMsg".to_string(),
+            attrs: ButtonAttrs::default(),
+        });
+        let button_id = button.id;
+        assert!(project.add_child_to_root(button));
+        
+        // Try to add a child to the button - should fail
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Hello".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        assert!(!project.add_child_to_node(button_id, text));
+    }
+
+    #[test]
+    fn test_project_add_child_to_nonexistent_node() {
+        let temp = tempdir().unwrap();
+        let project_dir = temp.path();
+
+        let mut project = Project::create(project_dir, None).unwrap();
+        
+        // Try to add to a non-existent node
+        let fake_id = crate::model::layout::ComponentId::new();
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Hello".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        assert!(!project.add_child_to_node(fake_id, text));
+    }
+
+    #[test]
+    fn test_project_selection_navigation() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+
+        for content in ["First", "Second"] {
+            let text = LayoutNode::new(WidgetType::Text {
+                content: content.to_string(),
+                attrs: TextAttrs::default(),
+            });
+            assert!(project.add_child_to_root(text));
+        }
+
+        let root_id = project.layout.root.id;
+        let children: Vec<ComponentId> = project
+            .layout
+            .root
+            .children()
+            .unwrap()
+            .iter()
+            .map(|c| c.id)
+            .collect();
+        let (last, before_last) = (children[children.len() - 1], children[children.len() - 2]);
+
+        project.selected_id = Some(root_id);
+        assert_eq!(project.get_first_child(), Some(children[0]));
+        assert_eq!(project.get_parent(), None);
+        assert_eq!(project.get_next_sibling(), None);
+
+        project.selected_id = Some(before_last);
+        assert_eq!(project.get_next_sibling(), Some(last));
+        assert_eq!(project.get_parent(), Some(root_id));
+
+        project.selected_id = Some(last);
+        assert_eq!(project.get_previous_sibling(), Some(before_last));
+        assert_eq!(project.get_next_sibling(), None);
+        assert_eq!(project.get_first_child(), None);
+    }
+
+    #[test]
+    fn test_project_nearest_container() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Hello".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        let text_id = text.id;
+        let wrapper = LayoutNode::new(WidgetType::Container {
+            child: Some(Box::new(text)),
+            attrs: ContainerAttrs::default(),
+        });
+        assert!(project.add_child_to_root(wrapper));
+
+        // Neither the text nor its full container accept children, so the root is used
+        let root_id = project.layout.root.id;
+        assert_eq!(project.nearest_container(text_id), Some(root_id));
+        assert_eq!(project.nearest_container(root_id), Some(root_id));
+        assert_eq!(project.nearest_container(ComponentId::new()), None);
+    }
+
+    #[test]
+    fn test_project_revision_tracks_layout_changes() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+
+        let before = project.revision;
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Hello".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        assert!(project.add_child_to_root(text));
+        assert!(project.revision > before);
+
+        let before = project.revision;
+        project.mark_dirty();
+        assert!(project.revision > before);
+    }
+
+    #[test]
+    fn test_project_move_node_rejects_cycles() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+
+        let inner = LayoutNode::new(WidgetType::Column {
+            children: Vec::new(),
+            attrs: ContainerAttrs::default(),
+        });
+        let inner_id = inner.id;
+        let outer = LayoutNode::new(WidgetType::Row {
+            children: vec![inner],
+            attrs: ContainerAttrs::default(),
+        });
+        let outer_id = outer.id;
+        assert!(project.add_child_to_root(outer));
+
+        let before = project.layout.clone();
+        let undo_before = project.history.undo_count();
+        assert!(!project.move_node(outer_id, inner_id, 0));
+        assert!(!project.move_node(outer_id, outer_id, 0));
+        assert!(!project.move_node(project.layout.root.id, outer_id, 0));
+        assert_eq!(project.layout, before);
+        assert_eq!(project.history.undo_count(), undo_before);
+    }
+
+    #[test]
+    fn test_project_move_node_reorders_siblings() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        let root_id = project.layout.root.id;
+
+        let ids: Vec<ComponentId> = ["A", "B", "C"]
+            .into_iter()
+            .map(|content| {
+                let text = LayoutNode::new(WidgetType::Text {
+                    content: content.to_string(),
+                    attrs: TextAttrs::default(),
+                });
+                let id = text.id;
+                assert!(project.add_child_to_root(text));
+                id
+            })
+            .collect();
+        let offset = project.layout.root.children().unwrap().len() - ids.len();
+
+        // Dropping A after C lands it at the end
+        let undo_before = project.history.undo_count();
+        assert!(project.move_node(ids[0], root_id, offset + 3));
+        assert_eq!(project.history.undo_count(), undo_before + 1);
+        assert_eq!(project.parent_of(ids[1]), Some((root_id, offset)));
+        assert_eq!(project.parent_of(ids[0]), Some((root_id, offset + 2)));
+    }
+
+    #[test]
+    fn test_project_move_node_reorders_stack_layers() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+
+        let layers: Vec<LayoutNode> = ["Back", "Middle", "Front"]
+            .into_iter()
+            .map(|content| {
+                LayoutNode::new(WidgetType::Text {
+                    content: content.to_string(),
+                    attrs: TextAttrs::default(),
+                })
+            })
+            .collect();
+        let ids: Vec<ComponentId> = layers.iter().map(|layer| layer.id).collect();
+        let stack = LayoutNode::new(WidgetType::Stack {
+            children: layers,
+            attrs: ContainerAttrs::default(),
+        });
+        let stack_id = stack.id;
+        assert!(project.add_child_to_root(stack));
+
+        // Bringing the back layer to the front
+        assert!(project.move_node(ids[0], stack_id, 3));
+        assert_eq!(project.parent_of(ids[1]), Some((stack_id, 0)));
+        assert_eq!(project.parent_of(ids[2]), Some((stack_id, 1)));
+        assert_eq!(project.parent_of(ids[0]), Some((stack_id, 2)));
+    }
+
+    #[test]
+    fn test_project_move_node_into_container() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Moved".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        let text_id = text.id;
+        let row = LayoutNode::new(WidgetType::Row {
+            children: Vec::new(),
+            attrs: ContainerAttrs::default(),
+        });
+        let row_id = row.id;
+        assert!(project.add_child_to_root(text));
+        assert!(project.add_child_to_root(row));
+
+        assert!(project.move_node(text_id, row_id, 0));
+        assert_eq!(project.parent_of(text_id), Some((row_id, 0)));
+        assert!(project.find_node(text_id).is_some());
+    }
+
+    #[test]
+    fn test_project_move_node_respects_single_child_targets() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+
+        let text = |content: &str| {
+            LayoutNode::new(WidgetType::Text {
+                content: content.to_string(),
+                attrs: TextAttrs::default(),
+            })
+        };
+        let first = text("First");
+        let first_id = first.id;
+        let second = text("Second");
+        let second_id = second.id;
+        let wrapper = LayoutNode::new(WidgetType::Container {
+            child: None,
+            attrs: ContainerAttrs::default(),
+        });
+        let wrapper_id = wrapper.id;
+        assert!(project.add_child_to_root(first));
+        assert!(project.add_child_to_root(second));
+        assert!(project.add_child_to_root(wrapper));
+
+        // An empty single-child container takes one node
+        assert!(project.move_node(first_id, wrapper_id, 0));
+        assert_eq!(project.parent_of(first_id), Some((wrapper_id, 0)));
+
+        // Once full it rejects another without changing anything
+        let before = project.layout.clone();
+        let undo_before = project.history.undo_count();
+        assert!(!project.move_node(second_id, wrapper_id, 0));
+        assert!(!project.move_node(first_id, wrapper_id, 1));
+        assert_eq!(project.layout, before);
+        assert_eq!(project.history.undo_count(), undo_before);
+    }
+
+    #[test]
+    fn test_project_move_node_clamps_index() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Moved".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        let text_id = text.id;
+        let row = LayoutNode::new(WidgetType::Row {
+            children: vec![LayoutNode::new(WidgetType::Text {
+                content: "Existing".to_string(),
+                attrs: TextAttrs::default(),
+            })],
+            attrs: ContainerAttrs::default(),
+        });
+        let row_id = row.id;
+        assert!(project.add_child_to_root(text));
+        assert!(project.add_child_to_root(row));
+
+        assert!(project.move_node(text_id, row_id, 99));
+        assert_eq!(project.parent_of(text_id), Some((row_id, 1)));
+    }
+
+    #[test]
+    fn test_project_duplicate_node_gives_fresh_ids() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        let root_id = project.layout.root.id;
+
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Nested".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        let row = LayoutNode::new(WidgetType::Row {
+            children: vec![text],
+            attrs: ContainerAttrs::default(),
+        });
+        let column = LayoutNode::new(WidgetType::Column {
+            children: vec![row],
+            attrs: ContainerAttrs::default(),
+        });
+        let column_id = column.id;
+        assert!(project.add_child_to_root(column));
+        let (_, column_index) = project.parent_of(column_id).unwrap();
+
+        let existing: std::collections::HashSet<ComponentId> = project.node_index.keys().copied().collect();
+        let copy_id = project.duplicate_node(column_id).unwrap();
+        assert_eq!(project.parent_of(copy_id), Some((root_id, column_index + 1)));
+
+        fn collect_ids(node: &LayoutNode, ids: &mut Vec<ComponentId>) {
+            ids.push(node.id);
+            for child in node.children().into_iter().flatten() {
+                collect_ids(child, ids);
+            }
+        }
+        let mut copied = Vec::new();
+        collect_ids(project.find_node(copy_id).unwrap(), &mut copied);
+        assert_eq!(copied.len(), 3);
+        assert!(copied.iter().all(|id| !existing.contains(id)));
+        assert!(copied.iter().all(|id| project.find_node(*id).is_some()));
+    }
+
+    #[test]
+    fn test_project_duplicate_root_fails() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        let before = project.layout.clone();
+
+        assert!(project.duplicate_node(project.layout.root.id).is_none());
+        assert_eq!(project.layout, before);
+    }
+
+    #[test]
+    fn test_project_insert_child_at() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+
+        let text = |content: &str| {
+            LayoutNode::new(WidgetType::Text {
+                content: content.to_string(),
+                attrs: TextAttrs::default(),
+            })
+        };
+        let row = LayoutNode::new(WidgetType::Row {
+            children: vec![text("Middle")],
+            attrs: ContainerAttrs::default(),
+        });
+        let row_id = row.id;
+        assert!(project.add_child_to_root(row));
+
+        let first = text("First");
+        let first_id = first.id;
+        assert!(project.insert_child_at(row_id, 0, first));
+        assert_eq!(project.parent_of(first_id), Some((row_id, 0)));
+
+        // Index equal to the length appends
+        let last = text("Last");
+        let last_id = last.id;
+        assert!(project.insert_child_at(row_id, 2, last));
+        assert_eq!(project.parent_of(last_id), Some((row_id, 2)));
+
+        // Out-of-range indices clamp to the end
+        let clamped = text("Clamped");
+        let clamped_id = clamped.id;
+        assert!(project.insert_child_at(row_id, 42, clamped));
+        assert_eq!(project.parent_of(clamped_id), Some((row_id, 3)));
+    }
+
+    #[test]
+    fn test_project_insert_child_at_single_child_container() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+
+        let wrapper = LayoutNode::new(WidgetType::Container {
+            child: None,
+            attrs: ContainerAttrs::default(),
+        });
+        let wrapper_id = wrapper.id;
+        assert!(project.add_child_to_root(wrapper));
+
+        let text = |content: &str| {
+            LayoutNode::new(WidgetType::Text {
+                content: content.to_string(),
+                attrs: TextAttrs::default(),
+            })
+        };
+        let inner = text("Inner");
+        let inner_id = inner.id;
+        assert!(project.insert_child_at(wrapper_id, 5, inner));
+        assert_eq!(project.parent_of(inner_id), Some((wrapper_id, 0)));
+
+        // Full containers and leaves reject further children
+        assert!(!project.insert_child_at(wrapper_id, 0, text("Extra")));
+        assert!(!project.insert_child_at(inner_id, 0, text("Extra")));
+    }
+
+    #[test]
+    fn test_project_convert_multi_child_containers() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Kept".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        let text_id = text.id;
+        let attrs = ContainerAttrs {
+            spacing: 12.0,
+            ..ContainerAttrs::default()
+        };
+        let column = LayoutNode::new(WidgetType::Column {
+            children: vec![text],
+            attrs: attrs.clone(),
+        });
+        let id = column.id;
+        assert!(project.add_child_to_root(column));
+
+        for target in [
+            WidgetKind::RowContainer,
+            WidgetKind::Stack,
+            WidgetKind::ColumnContainer,
+            WidgetKind::Stack,
+            WidgetKind::RowContainer,
+            WidgetKind::ColumnContainer,
+        ] {
+            assert!(project.convert_widget(id, target), "{target:?}");
+            match &project.find_node(id).unwrap().widget {
+                WidgetType::Column { children, attrs: kept }
+                | WidgetType::Row { children, attrs: kept }
+                | WidgetType::Stack { children, attrs: kept } => {
+                    assert_eq!(children[0].id, text_id);
+                    assert_eq!(kept, &attrs);
+                }
+                other => panic!("unexpected widget {other:?}"),
+            }
+        }
+        assert!(matches!(project.find_node(id).unwrap().widget, WidgetType::Column { .. }));
+    }
+
+    #[test]
+    fn test_project_convert_single_child_containers() {
         let temp = tempdir().unwrap();
-        let project_dir = temp.path();
-
-        let mut project = Project::create(project_dir, None).unwrap();
-        project.layout.name = "Test Layout".to_string();
-        project.mark_dirty();
-        assert!(project.dirty);
-
-        project.save().unwrap();
-        assert!(!project.dirty);
+        let mut project = Project::create(temp.path(), None).unwrap();
 
-        // Re-open and verify
-        let reopened = Project::open(project_dir).unwrap();
-        assert_eq!(reopened.layout.name, "Test Layout");
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Kept".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        let text_id = text.id;
+        let container = LayoutNode::new(WidgetType::Container {
+            child: Some(Box::new(text)),
+            attrs: ContainerAttrs::default(),
+        });
+        let id = container.id;
+        assert!(project.add_child_to_root(container));
+
+        assert!(project.convert_widget(id, WidgetKind::Scrollable));
+        assert!(matches!(
+            &project.find_node(id).unwrap().widget,
+            WidgetType::Scrollable { child: Some(child), .. } if child.id == text_id
+        ));
+        assert!(project.convert_widget(id, WidgetKind::Container));
+        assert!(matches!(
+            &project.find_node(id).unwrap().widget,
+            WidgetType::Container { child: Some(child), .. } if child.id == text_id
+        ));
+        assert!(project.find_node(text_id).is_some());
     }
 
     #[test]
-    fn test_project_export() {
+    fn test_project_convert_text_and_button() {
         let temp = tempdir().unwrap();
-        let project_dir = temp.path();
+        let mut project = Project::create(temp.path(), None).unwrap();
 
-        // Create output directory
-        std::fs::create_dir_all(project_dir.join("src/ui")).unwrap();
-
-        let project = Project::create(project_dir, None).unwrap();
-        let code = project.export().unwrap();
-
-        assert!(code.contains("pub fn view"));
-        assert!(code.contains("Element"));
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Submit".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        let id = text.id;
+        assert!(project.add_child_to_root(text));
 
-        // Verify output file was created
-        assert!(project_dir.join("src/ui/layout_generated.rs").exists());
+        assert!(project.convert_widget(id, WidgetKind::Button));
+        assert!(matches!(
+            &project.find_node(id).unwrap().widget,
+            WidgetType::Button { label, .. } if label == "Submit"
+        ));
+        assert!(project.convert_widget(id, WidgetKind::Text));
+        assert!(matches!(
+            &project.find_node(id).unwrap().widget,
+            WidgetType::Text { content, .. } if content == "Submit"
+        ));
     }
 
     #[test]
-    fn test_project_find_node() {
+    fn test_project_convert_rejects_incompatible_types() {
         let temp = tempdir().unwrap();
-        let project_dir = temp.path();
+        let mut project = Project::create(temp.path(), None).unwrap();
 
-        let project = Project::create(project_dir, Some(Template::Form)).unwrap();
-        
-        // Should be able to find the root node
-        let root_id = project.layout.root.id;
-        let found = project.find_node(root_id);
-        assert!(found.is_some());
+        let text = LayoutNode::new(WidgetType::Text {
+            content: "Text".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        let id = text.id;
+        assert!(project.add_child_to_root(text));
+        assert!(project.convert_widget(id, WidgetKind::Button));
+        project.undo().unwrap();
+        let before = project.layout.clone();
+        let (undo_count, redo_count) = (project.history.undo_count(), project.history.redo_count());
+
+        assert!(!project.convert_widget(id, WidgetKind::Text));
+        assert!(!project.convert_widget(id, WidgetKind::ColumnContainer));
+        assert!(!project.convert_widget(id, WidgetKind::Slider));
+        assert!(!project.convert_widget(ComponentId::new(), WidgetKind::Button));
+        assert_eq!(project.layout, before);
+        // Rejected conversions leave the redo history alone
+        assert_eq!(project.history.undo_count(), undo_count);
+        assert_eq!(project.history.redo_count(), redo_count);
+        assert_eq!(project.history.redo_label(), Some("Change Text to Button"));
     }
 
     #[test]
-    fn test_project_open_missing_config() {
+    fn test_project_change_root_type_between_multi_child() {
         let temp = tempdir().unwrap();
-        let project_dir = temp.path();
+        let mut project = Project::create(temp.path(), Some(Template::Form)).unwrap();
+        let root_id = project.layout.root.id;
+        let before = project.layout.root.children().unwrap().clone();
 
-        // Try to open a project without config
-        let result = Project::open(project_dir);
-        assert!(result.is_err());
-    }
+        assert!(project.change_root_type(WidgetKind::RowContainer));
+        assert_eq!(project.layout.root.id, root_id);
+        assert!(matches!(project.layout.root.widget, WidgetType::Row { .. }));
+        assert_eq!(project.layout.root.children().unwrap(), &before);
+        assert_eq!(project.parent_of(before[1].id), Some((root_id, 1)));
 
-    #[test]
-    fn test_project_remove_node() {
-        let temp = tempdir().unwrap();
-        let project_dir = temp.path();
+        // Same type and non-container targets are rejected
+        assert!(!project.change_root_type(WidgetKind::RowContainer));
+        assert!(!project.change_root_type(WidgetKind::Button));
 
-        let mut project = Project::create(project_dir, Some(Template::Form)).unwrap();
-        
-        // Get the root and find a child to remove
-        let root_id = project.layout.root.id;
-        
-        // Get a child node ID
-        let child_id = project.layout.root.children()
-            .expect("Root should be a container")
-            .first()
-            .expect("Should have at least one child")
-            .id;
-        
-        // Cannot remove root
-        assert!(!project.remove_node(root_id));
-        
-        // Can remove child
-        assert!(project.remove_node(child_id));
-        
-        // Child should no longer be findable
-        assert!(project.find_node(child_id).is_none());
+        let (previous, label) = project.history.undo(project.layout.clone()).unwrap();
+        assert_eq!(label, "Change root to Row");
+        assert!(matches!(previous.root.widget, WidgetType::Column { .. }));
     }
 
     #[test]
-    fn test_project_remove_node_nested() {
+    fn test_project_change_root_type_wraps_children_for_single_child() {
         let temp = tempdir().unwrap();
-        let project_dir = temp.path();
+        let mut project = Project::create(temp.path(), Some(Template::Form)).unwrap();
+        let root_id = project.layout.root.id;
+        let original = project.layout.root.clone();
+        let before = original.children().unwrap().clone();
 
-        let mut project = Project::create(project_dir, Some(Template::Dashboard)).unwrap();
-        
-        // Dashboard has nested structure, find a deeply nested node
-        let children = project.layout.root.children().unwrap();
-        if let Some(first_child) = children.first() {
-            if let Some(nested_children) = first_child.children() {
-                if let Some(nested_child) = nested_children.first() {
-                    let nested_id = nested_child.id;
-                    
-                    // Remove the nested node
-                    assert!(project.remove_node(nested_id));
-                    
-                    // Verify it's gone
-                    assert!(project.find_node(nested_id).is_none());
-                }
-            }
-        }
+        assert!(project.change_root_type(WidgetKind::Scrollable));
+        assert_eq!(project.layout.root.id, root_id);
+        let WidgetType::Scrollable { child: Some(wrapper), attrs } = &project.layout.root.widget else {
+            panic!("root should be a Scrollable with a child");
+        };
+        let WidgetType::Column { attrs: original_attrs, .. } = &original.widget else {
+            unreachable!()
+        };
+        assert_eq!(attrs, original_attrs);
+        assert_eq!(wrapper.children().unwrap(), &before);
+
+        // Children stay reachable through the index
+        let wrapper_id = wrapper.id;
+        assert_eq!(project.parent_of(wrapper_id), Some((root_id, 0)));
+        assert!(project.find_node(before[3].id).is_some());
+
+        // Back to a multi-child root keeps the wrapper as the only child
+        assert!(project.change_root_type(WidgetKind::RowContainer));
+        assert_eq!(project.layout.root.children().unwrap()[0].id, wrapper_id);
+
+        let (scrollable, _) = project.history.undo(project.layout.clone()).unwrap();
+        let (previous, _) = project.history.undo(scrollable).unwrap();
+        assert_eq!(previous.root, original);
     }
 
     #[test]
-    fn test_project_remove_nonexistent_node() {
+    fn test_project_change_empty_root_to_container() {
         let temp = tempdir().unwrap();
-        let project_dir = temp.path();
+        let mut project = Project::create(temp.path(), None).unwrap();
 
-        let mut project = Project::create(project_dir, None).unwrap();
-        
-        // Try to remove a node that doesn't exist
-        let fake_id = crate::model::layout::ComponentId::new();
-        assert!(!project.remove_node(fake_id));
+        assert!(project.change_root_type(WidgetKind::Container));
+        assert!(matches!(project.layout.root.widget, WidgetType::Container { child: None, .. }));
+        assert!(project.change_root_type(WidgetKind::RowContainer));
+        assert_eq!(project.layout.root.children().map(Vec::len), Some(0));
     }
 
     #[test]
-    fn test_project_history_integration() {
+    fn test_project_convert_root() {
         let temp = tempdir().unwrap();
-        let project_dir = temp.path();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        let root_id = project.layout.root.id;
+        let child_count = project.layout.root.children().unwrap().len();
 
-        let mut project = Project::create(project_dir, Some(Template::Form)).unwrap();
-        
-        // Initially no undo/redo available
-        assert!(!project.history.can_undo());
-        assert!(!project.history.can_redo());
-        
-        // Push a snapshot
-        project.history.push(project.layout.clone());
-        
-        // Now undo should be available
-        assert!(project.history.can_undo());
-        assert!(!project.history.can_redo());
-        
-        // Get a child and modify
-        let child_id = project.layout.root.children()
-            .unwrap()
-            .first()
-            .unwrap()
-            .id;
-        
-        // Remove the child
-        project.remove_node(child_id);
-        
-        // Undo should restore the child
-        let prev = project.history.undo(project.layout.clone()).unwrap();
-        project.layout = prev;
-        project.rebuild_index();
-        
-        // The child should be findable again
-        assert!(project.find_node(child_id).is_some());
+        assert!(project.convert_widget(root_id, WidgetKind::RowContainer));
+        assert_eq!(project.layout.root.id, root_id);
+        assert_eq!(project.layout.root.children().unwrap().len(), child_count);
+        assert!(!project.convert_widget(root_id, WidgetKind::Container));
     }
 
     #[test]
-    fn test_project_is_container() {
+    fn test_project_find_by_name_and_content() {
         let temp = tempdir().unwrap();
-        let project_dir = temp.path();
-
-        let mut project = Project::create(project_dir, Some(Template::Form)).unwrap();
-        
-        // Root should be a container (Column)
+        let mut project = Project::create(temp.path(), None).unwrap();
         let root_id = project.layout.root.id;
-        assert!(project.is_container(root_id));
-        
-        // Add a non-container widget (Button) to root
+
+        let mut header = LayoutNode::new(WidgetType::Text {
+            content: "Welcome back".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        header.name = Some("Page Header".to_string());
+        let header_id = header.id;
         let button = LayoutNode::new(WidgetType::Button {
-            label: "Test".to_string(),
-            message_stub: "TestMsg".to_string(),
+            label: "Save".to_string(),
+            message_stub: "HeaderSaved".to_string(),
             attrs: ButtonAttrs::default(),
         });
         let button_id = button.id;
-        assert!(project.add_child_to_root(button));
-        
-        // Button should not be a container
-        assert!(!project.is_container(button_id));
+        let mut wrapper = LayoutNode::new(WidgetType::Container {
+            child: Some(Box::new(button)),
+            attrs: ContainerAttrs::default(),
+        });
+        wrapper.name = Some("Toolbar".to_string());
+        let wrapper_id = wrapper.id;
+        assert!(project.add_child_to_root(header));
+        assert!(project.add_child_to_root(wrapper));
+
+        assert_eq!(project.find_by_name("header"), vec![header_id]);
+        assert_eq!(project.find_by_name("TOOL"), vec![wrapper_id]);
+        assert_eq!(project.find_by_content("WELCOME"), vec![header_id]);
+        assert_eq!(project.find_by_content("headersaved"), vec![button_id]);
+        assert_eq!(project.find("header"), vec![header_id, button_id]);
+        assert!(project.find_by_name("").is_empty());
+        assert!(project.find_by_content("   ").is_empty());
+        assert!(project.find("nothing like this").is_empty());
+        assert!(!project.find_by_name("toolbar").contains(&root_id));
     }
 
     #[test]
-    fn test_project_add_child_to_root() {
+    fn test_project_extract_component() {
         let temp = tempdir().unwrap();
-        let project_dir = temp.path();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        let root_id = project.layout.root.id;
 
-        let mut project = Project::create(project_dir, None).unwrap();
-        
-        let initial_count = project.layout.root.children().unwrap().len();
-        
-        // Add a text widget to root
-        let text = LayoutNode::new(WidgetType::Text {
-            content: "Hello".to_string(),
-            attrs: TextAttrs::default(),
+        let card = LayoutNode::new(WidgetType::Column {
+            children: vec![LayoutNode::new(WidgetType::Text {
+                content: "Card".to_string(),
+                attrs: TextAttrs::default(),
+            })],
+            attrs: ContainerAttrs::default(),
         });
-        let text_id = text.id;
-        assert!(project.add_child_to_root(text));
-        
-        // Verify it was added
-        assert_eq!(
-            project.layout.root.children().unwrap().len(),
-            initial_count + 1
-        );
-        
-        // Verify we can find it
-        assert!(project.find_node(text_id).is_some());
+        let card_id = card.id;
+        assert!(project.add_child_to_root(card.clone()));
+        let (_, index) = project.parent_of(card_id).unwrap();
+
+        let reference_id = project.extract_component(card_id, "card").unwrap();
+        let file = PathBuf::from("layouts").join("card.ron");
+        assert!(temp.path().join(&file).exists());
+        assert!(project.find_node(card_id).is_none());
+        assert_eq!(project.parent_of(reference_id), Some((root_id, index)));
+        assert!(matches!(
+            &project.find_node(reference_id).unwrap().widget,
+            WidgetType::ComponentRef { file: f } if *f == file
+        ));
+        assert_eq!(project.config.layout_files, vec![PathBuf::from("layout.ron"), file.clone()]);
+        assert_eq!(project.components[&file].root, card);
+
+        // Saving and reopening loads the component again
+        project.save().unwrap();
+        let reopened = Project::open(temp.path()).unwrap();
+        assert_eq!(reopened.components[&file].root, card);
+        assert!(reopened.find_node(reference_id).is_some());
+
+        // Deleting the reference leaves the file in place
+        assert!(project.remove_node(reference_id));
+        assert!(temp.path().join(&file).exists());
     }
 
     #[test]
-    fn test_project_add_child_to_node() {
+    fn test_project_extract_component_rejections() {
         let temp = tempdir().unwrap();
-        let project_dir = temp.path();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        let root_id = project.layout.root.id;
 
-        let mut project = Project::create(project_dir, None).unwrap();
-        
-        // Add a Row container first
+        let text = |content: &str| {
+            LayoutNode::new(WidgetType::Text {
+                content: content.to_string(),
+                attrs: TextAttrs::default(),
+            })
+        };
+        let first = text("First");
+        let first_id = first.id;
+        let second = text("Second");
+        let second_id = second.id;
+        assert!(project.add_child_to_root(first));
+        assert!(project.add_child_to_root(second));
+
+        assert!(matches!(
+            project.extract_component(root_id, "everything"),
+            Err(ProjectError::InvalidComponent(_))
+        ));
+        assert!(matches!(
+            project.extract_component(first_id, "not valid"),
+            Err(ProjectError::InvalidComponent(_))
+        ));
+        assert!(matches!(
+            project.extract_component(first_id, "struct"),
+            Err(ProjectError::InvalidComponent(_))
+        ));
+        project.extract_component(first_id, "label").unwrap();
+        assert!(matches!(
+            project.extract_component(second_id, "label"),
+            Err(ProjectError::InvalidComponent(_))
+        ));
+    }
+
+    #[test]
+    fn test_project_extract_component_rejects_cycles() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+
+        // A component that embeds the main layout again
+        let loop_file = PathBuf::from("layouts").join("wrapper.ron");
+        project.components.insert(
+            loop_file.clone(),
+            LayoutDocument {
+                root: LayoutNode::new(WidgetType::ComponentRef {
+                    file: PathBuf::from("layout.ron"),
+                }),
+                ..LayoutDocument::default()
+            },
+        );
+        let reference = LayoutNode::new(WidgetType::ComponentRef { file: loop_file });
         let row = LayoutNode::new(WidgetType::Row {
-            children: Vec::new(),
+            children: vec![reference],
             attrs: ContainerAttrs::default(),
         });
         let row_id = row.id;
         assert!(project.add_child_to_root(row));
-        
-        // Now add a button to the Row
-        let button = LayoutNode::new(WidgetType::Button {
-            label: "Click".to_string(),
-            message_stub: "Clicked".to_string(),
-            attrs: ButtonAttrs::default(),
-        });
-        let button_id = button.id;
-        assert!(project.add_child_to_node(row_id, button));
-        
-        // Verify button was added to the row
-        let row_node = project.find_node(row_id).expect("Row should exist");
-        let row_children = row_node.children().expect("Row should have children");
-        assert!(row_children.iter().any(|c| c.id == button_id));
+
+        let before = project.layout.clone();
+        assert!(matches!(
+            project.extract_component(row_id, "looped"),
+            Err(ProjectError::InvalidComponent(_))
+        ));
+        assert_eq!(project.layout, before);
+        assert!(!temp.path().join("layouts").join("looped.ron").exists());
     }
 
     #[test]
-    fn test_project_add_child_to_non_container() {
+    fn test_project_insert_sibling() {
         let temp = tempdir().unwrap();
-        let project_dir = temp.path();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        let root_id = project.layout.root.id;
 
-        let mut project = Project::create(project_dir, None).unwrap();
-        
-        // Add a button first (non-container)
-        let button = LayoutNode::new(WidgetType::Button {
-            label: "Test".to_string(),
-            message_stub: "TestMsg".to_string(),
-            attrs: ButtonAttrs::default(),
-        });
-        let button_id = button.id;
-        assert!(project.add_child_to_root(button));
-        
-        // Try to add a child to the button - should fail
-        let text = LayoutNode::new(WidgetType::Text {
-            content: "Hello".to_string(),
+        let anchor = LayoutNode::new(WidgetType::Text {
+            content: "Anchor".to_string(),
             attrs: TextAttrs::default(),
         });
-        assert!(!project.add_child_to_node(button_id, text));
+        let anchor_id = anchor.id;
+        assert!(project.add_child_to_root(anchor));
+        let (_, anchor_index) = project.parent_of(anchor_id).unwrap();
+
+        let text = |content: &str| {
+            LayoutNode::new(WidgetType::Text {
+                content: content.to_string(),
+                attrs: TextAttrs::default(),
+            })
+        };
+        let before = text("Before");
+        let before_id = before.id;
+        let after = text("After");
+        let after_id = after.id;
+
+        let undo_before = project.history.undo_count();
+        assert!(project.insert_sibling(anchor_id, before, Position::Before));
+        assert!(project.insert_sibling(anchor_id, after, Position::After));
+        assert_eq!(project.history.undo_count(), undo_before + 2);
+
+        assert_eq!(project.parent_of(before_id), Some((root_id, anchor_index)));
+        assert_eq!(project.parent_of(anchor_id), Some((root_id, anchor_index + 1)));
+        assert_eq!(project.parent_of(after_id), Some((root_id, anchor_index + 2)));
     }
 
     #[test]
-    fn test_project_add_child_to_nonexistent_node() {
+    fn test_project_insert_sibling_in_stack() {
         let temp = tempdir().unwrap();
-        let project_dir = temp.path();
+        let mut project = Project::create(temp.path(), None).unwrap();
 
-        let mut project = Project::create(project_dir, None).unwrap();
-        
-        // Try to add to a non-existent node
-        let fake_id = crate::model::layout::ComponentId::new();
-        let text = LayoutNode::new(WidgetType::Text {
-            content: "Hello".to_string(),
+        let anchor = LayoutNode::new(WidgetType::Text {
+            content: "Anchor".to_string(),
             attrs: TextAttrs::default(),
         });
-        assert!(!project.add_child_to_node(fake_id, text));
+        let anchor_id = anchor.id;
+        let stack = LayoutNode::new(WidgetType::Stack {
+            children: vec![anchor],
+            attrs: ContainerAttrs::default(),
+        });
+        let stack_id = stack.id;
+        assert!(project.add_child_to_root(stack));
+
+        let overlay = LayoutNode::new(WidgetType::Text {
+            content: "Overlay".to_string(),
+            attrs: TextAttrs::default(),
+        });
+        let overlay_id = overlay.id;
+        assert!(project.insert_sibling(anchor_id, overlay, Position::Before));
+        assert_eq!(project.parent_of(overlay_id), Some((stack_id, 0)));
+        assert_eq!(project.parent_of(anchor_id), Some((stack_id, 1)));
+    }
+
+    #[test]
+    fn test_project_insert_sibling_of_root_fails() {
+        let temp = tempdir().unwrap();
+        let mut project = Project::create(temp.path(), None).unwrap();
+        let root_id = project.layout.root.id;
+        let before = project.layout.clone();
+
+        for position in [Position::Before, Position::After] {
+            let text = LayoutNode::new(WidgetType::Text {
+                content: "Orphan".to_string(),
+                attrs: TextAttrs::default(),
+            });
+            assert!(!project.insert_sibling(root_id, text, position));
+        }
+        assert_eq!(project.layout, before);
+        assert!(!project.history.can_undo());
     }
 }
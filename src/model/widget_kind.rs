@@ -0,0 +1,136 @@
+//! Kinds of widgets that can be added to a layout.
+//!
+//! The palette lists these, conversions between widget types target them,
+//! and the editor settings remember the recently used ones.
+
+use serde::{Deserialize, Serialize};
+
+/// Widget categories in the palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WidgetCategory {
+    /// Widgets that hold children.
+    Containers,
+    /// Widgets without children.
+    Widgets,
+}
+
+/// Types of widgets that can be added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WidgetKind {
+    // Containers
+    /// A [`WidgetType::Column`](super::layout::WidgetType::Column).
+    ColumnContainer,
+    /// A [`WidgetType::Row`](super::layout::WidgetType::Row).
+    RowContainer,
+    /// A [`WidgetType::Container`](super::layout::WidgetType::Container).
+    Container,
+    /// A [`WidgetType::Scrollable`](super::layout::WidgetType::Scrollable).
+    Scrollable,
+    /// A [`WidgetType::Stack`](super::layout::WidgetType::Stack).
+    Stack,
+    // Widgets
+    /// A [`WidgetType::Text`](super::layout::WidgetType::Text).
+    Text,
+    /// A [`WidgetType::Button`](super::layout::WidgetType::Button).
+    Button,
+    /// A [`WidgetType::TextInput`](super::layout::WidgetType::TextInput).
+    TextInput,
+    /// A [`WidgetType::Checkbox`](super::layout::WidgetType::Checkbox).
+    Checkbox,
+    /// A [`WidgetType::Slider`](super::layout::WidgetType::Slider).
+    Slider,
+    /// A [`WidgetType::PickList`](super::layout::WidgetType::PickList).
+    PickList,
+    /// A [`WidgetType::Space`](super::layout::WidgetType::Space).
+    Space,
+}
+
+impl WidgetKind {
+    /// Get the display name for this widget type.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ColumnContainer => "Column",
+            Self::RowContainer => "Row",
+            Self::Container => "Container",
+            Self::Scrollable => "Scrollable",
+            Self::Stack => "Stack",
+            Self::Text => "Text",
+            Self::Button => "Button",
+            Self::TextInput => "TextInput",
+            Self::Checkbox => "Checkbox",
+            Self::Slider => "Slider",
+            Self::PickList => "PickList",
+            Self::Space => "Space",
+        }
+    }
+
+    /// Describe what the widget does and whether it holds children.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::ColumnContainer => {
+                "Stacks its children vertically. Container: holds any number of children."
+            }
+            Self::RowContainer => {
+                "Lines its children up horizontally. Container: holds any number of children."
+            }
+            Self::Container => {
+                "Adds padding, alignment and sizing around one widget. Container: holds a single child."
+            }
+            Self::Scrollable => {
+                "Scrolls content that does not fit its bounds. Container: holds a single child."
+            }
+            Self::Stack => {
+                "Layers its children on top of each other, first at the bottom. Container: holds any number of children."
+            }
+            Self::Text => "Displays a line or paragraph of static text.",
+            Self::Button => "A clickable button that sends a message when pressed.",
+            Self::TextInput => "A single-line field for typing text, bound to a state value.",
+            Self::Checkbox => "A labelled on/off toggle bound to a boolean state value.",
+            Self::Slider => "Picks a number from a range by dragging a handle.",
+            Self::PickList => "A dropdown for choosing one option from a list.",
+            Self::Space => "Empty space used to push widgets apart or fill leftover room.",
+        }
+    }
+
+    /// Get the category for this widget type.
+    pub fn category(&self) -> WidgetCategory {
+        match self {
+            Self::ColumnContainer
+            | Self::RowContainer
+            | Self::Container
+            | Self::Scrollable
+            | Self::Stack => WidgetCategory::Containers,
+            _ => WidgetCategory::Widgets,
+        }
+    }
+
+    /// Get all container widget kinds.
+    pub fn containers() -> &'static [WidgetKind] {
+        &[
+            Self::ColumnContainer,
+            Self::RowContainer,
+            Self::Container,
+            Self::Scrollable,
+            Self::Stack,
+        ]
+    }
+
+    /// Get all basic widget kinds.
+    pub fn widgets() -> &'static [WidgetKind] {
+        &[
+            Self::Text,
+            Self::Button,
+            Self::TextInput,
+            Self::Checkbox,
+            Self::Slider,
+            Self::PickList,
+            Self::Space,
+        ]
+    }
+}
+
+impl std::fmt::Display for WidgetKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
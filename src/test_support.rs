@@ -0,0 +1,35 @@
+//! Layout fixtures shared by the unit tests.
+
+use crate::model::layout::{ContainerAttrs, TextAttrs, WidgetType};
+use crate::model::{LayoutDocument, LayoutNode};
+
+/// A row holding one "Hello" text, for copying, pasting and snippets.
+pub fn sample_subtree() -> LayoutNode {
+    let text = LayoutNode::new(WidgetType::Text {
+        content: "Hello".to_string(),
+        attrs: TextAttrs::default(),
+    });
+    LayoutNode::new(WidgetType::Row {
+        children: vec![text],
+        attrs: ContainerAttrs::default(),
+    })
+}
+
+/// A default layout called `name`, to tell history states apart.
+pub fn doc_named(name: &str) -> LayoutDocument {
+    LayoutDocument {
+        name: name.to_string(),
+        ..LayoutDocument::default()
+    }
+}
+
+/// A layout whose root column holds `children`.
+pub fn doc_with(children: Vec<LayoutNode>) -> LayoutDocument {
+    LayoutDocument {
+        root: LayoutNode::new(WidgetType::Column {
+            children,
+            attrs: ContainerAttrs::default(),
+        }),
+        ..LayoutDocument::default()
+    }
+}
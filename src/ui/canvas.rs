@@ -2,38 +2,245 @@
 //!
 //! Renders the layout tree using actual Iced widgets wrapped in MouseArea
 //! for click interception and selection.
+//!
+//! In design mode, nodes rendered in earlier frames are reused from a
+//! [`CanvasCache`] until their subtree revision or the selection, inline
+//! edit or display options they depend on change, so hovering the palette
+//! or selecting a node rebuilds only the paths that changed.
+
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
 use iced::widget::{
-    button, center, checkbox, column, container, mouse_area, row, scrollable, slider, stack, text,
-    text_input, Space,
+    button, center, checkbox, column, container, mouse_area, pick_list, row, scrollable, slider,
+    stack, text, text_input, Space,
 };
-use iced::{Border, Color, Element, Length};
+use iced::{Border, Color, Element, Length, Task};
 
-use crate::app::{EditorMode, Message};
+use crate::app::{inline_edit_input_id, EditorMode, InlineEdit, Message};
+use crate::io::{AppSettings, CanvasSize};
+use crate::logging::targets;
 use crate::model::{
-    layout::{AlignmentSpec, LengthSpec, WidgetType},
-    ComponentId, LayoutNode,
+    layout::{AlignmentSpec, LengthSpec, NodeIndex, SubtreeRevisions, WidgetType},
+    ComponentId, LayoutDocument, LayoutNode, Project, SampleData, SampleValue,
 };
+use crate::ui::measure::{Axis, MeasureOverlay};
+use crate::ui::memo::MemoCache;
+use crate::ui::metrics;
+use crate::ui::outline::{dashed_outline, DashedOutline};
+use crate::ui::scroll::scroll_into_view;
+
+/// Color used to highlight containers that can accept a pending widget.
+const DROP_TARGET_COLOR: Color = Color::from_rgb(0.3, 0.8, 0.45);
+
+/// Color of the dashed placeholder drawn for empty containers.
+const DROP_ZONE_COLOR: Color = Color::from_rgba(0.55, 0.6, 0.7, 0.8);
+
+/// Minimum size of an empty container's drop zone.
+const DROP_ZONE_MIN_SIZE: iced::Size = iced::Size::new(80.0, 40.0);
+
+/// Component references nested deeper than this render as placeholders.
+const MAX_COMPONENT_DEPTH: usize = 8;
+
+/// Color used for outlines of otherwise invisible elements.
+const INVISIBLE_OUTLINE_COLOR: Color = Color::from_rgba(0.6, 0.6, 0.6, 0.6);
+
+/// A named canvas size choice.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CanvasPreset {
+    pub name: &'static str,
+    /// Fixed size, or `None` to fill the canvas panel.
+    pub size: Option<CanvasSize>,
+}
+
+impl std::fmt::Display for CanvasPreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.size {
+            Some(size) => write!(f, "{} ({}×{})", self.name, size.width, size.height),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// Built-in canvas size presets.
+pub const CANVAS_PRESETS: &[CanvasPreset] = &[
+    CanvasPreset { name: "Fit Panel", size: None },
+    CanvasPreset { name: "Phone", size: Some(CanvasSize::new(375.0, 667.0)) },
+    CanvasPreset { name: "Tablet", size: Some(CanvasSize::new(768.0, 1024.0)) },
+    CanvasPreset { name: "Small", size: Some(CanvasSize::new(800.0, 600.0)) },
+    CanvasPreset { name: "Laptop", size: Some(CanvasSize::new(1280.0, 800.0)) },
+    CanvasPreset { name: "HD", size: Some(CanvasSize::new(1366.0, 768.0)) },
+    CanvasPreset { name: "Full HD", size: Some(CanvasSize::new(1920.0, 1080.0)) },
+];
+
+impl CanvasPreset {
+    /// Find the preset matching a canvas size, if any.
+    pub fn matching(size: Option<CanvasSize>) -> Option<CanvasPreset> {
+        CANVAS_PRESETS.iter().copied().find(|preset| preset.size == size)
+    }
+}
 
 /// The canvas component for rendering and editing the layout.
 pub struct Canvas;
 
+/// Everything besides its own subtree that a node's canvas element
+/// depends on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeKey {
+    revision: u64,
+    /// Selection and inline edit draft, for nodes on the path to either.
+    focus: Option<(Option<ComponentId>, Option<String>)>,
+    show_invisible: bool,
+    show_measurements: bool,
+    inserting: bool,
+    dropping: bool,
+    scale: f32,
+}
+
+/// Canvas nodes rendered in earlier frames.
+#[derive(Debug, Default)]
+pub struct CanvasCache {
+    nodes: MemoCache<NodeKey, Message>,
+    /// Nodes the last canvas view built rather than reused.
+    last_built: Cell<usize>,
+}
+
+impl CanvasCache {
+    /// Forget nodes that are no longer in the layout. Until something is
+    /// removed the cache can't hold more nodes than the layout, so this
+    /// only walks it after removals.
+    fn prune(&self, node_index: &NodeIndex) {
+        if self.nodes.len() > node_index.len() {
+            self.nodes.retain(|id| node_index.contains_key(&id));
+        }
+    }
+}
+
+/// What the canvas needs to reuse nodes rendered in earlier frames.
+#[derive(Debug, Clone, Copy)]
+pub struct Reuse<'a> {
+    cache: &'a CanvasCache,
+    revisions: &'a SubtreeRevisions,
+    node_index: &'a NodeIndex,
+    /// Nodes on the paths to the selection and the inline edit.
+    focus_path: &'a [ComponentId],
+}
+
+impl<'a> Reuse<'a> {
+    /// Reuse nodes of `project` from `cache`.
+    pub fn new(cache: &'a CanvasCache, project: &'a Project) -> Self {
+        Self {
+            cache,
+            revisions: &project.subtree_revisions,
+            node_index: &project.node_index,
+            focus_path: &[],
+        }
+    }
+}
+
+/// Per-frame state shared by every node rendered on the canvas.
+#[derive(Debug, Clone, Copy)]
+pub struct RenderContext<'a> {
+    pub selected_id: Option<ComponentId>,
+    pub mode: EditorMode,
+    /// Whether to outline otherwise invisible elements.
+    pub show_invisible: bool,
+    /// Whether to overlay padding and spacing on the selected container.
+    pub show_measurements: bool,
+    pub inline_edit: Option<&'a InlineEdit>,
+    pub sample_data: Option<&'a SampleData>,
+    /// Whether a palette item is waiting to be dropped on a container.
+    pub inserting: bool,
+    /// Whether a palette item is being dragged, so releases drop it.
+    pub dropping: bool,
+    /// Transient PickList selections made in preview mode.
+    pub preview_selections: Option<&'a HashMap<ComponentId, String>>,
+    /// Component layouts rendered inline for `ComponentRef` nodes.
+    pub components: Option<&'a BTreeMap<PathBuf, LayoutDocument>>,
+    /// How many component references the current node is nested in.
+    pub component_depth: usize,
+    /// Where to reuse nodes from; only design mode reuses them.
+    pub reuse: Option<Reuse<'a>>,
+}
+
+impl<'a> RenderContext<'a> {
+    /// Get the draft text if `id` is being edited inline.
+    fn editing_draft(&self, id: ComponentId) -> Option<&'a str> {
+        self.inline_edit
+            .filter(|edit| edit.id == id && self.mode == EditorMode::Design)
+            .map(|edit| edit.draft.as_str())
+    }
+
+    /// Get the sample value for a binding (preview mode only).
+    fn sample(&self, binding: &str) -> Option<&'a SampleValue> {
+        if self.mode != EditorMode::Preview || binding.is_empty() {
+            return None;
+        }
+        self.sample_data.and_then(|data| data.get(binding))
+    }
+}
+
 impl Canvas {
+    /// Identifier of the canvas scrollable.
+    pub fn scrollable_id() -> scrollable::Id {
+        scrollable::Id::new("canvas")
+    }
+
+    /// Identifier of the container wrapping the selected node.
+    fn selection_id() -> container::Id {
+        container::Id::new("canvas-selection")
+    }
+
+    /// Scroll the canvas so the selected node is visible.
+    pub fn scroll_to_selection() -> Task<Message> {
+        scroll_into_view(Self::scrollable_id(), Self::selection_id())
+    }
+
     /// Render the canvas with the given layout.
     pub fn view<'a>(
         root: &'a LayoutNode,
-        selected_id: Option<ComponentId>,
-        mode: EditorMode,
-    ) -> Element<'a, Message> {
-        // Render the root node, but override height to Shrink for scrollable compatibility
-        let content = Self::render_node_for_canvas(root, selected_id, true, mode);
-
-        let background_color = match mode {
+        ctx: RenderContext<'a>,
+        canvas_size: Option<CanvasSize>,
+    ) -> Element<'static, Message> {
+        let background_color = match ctx.mode {
             EditorMode::Design => Color::from_rgb(0.15, 0.15, 0.15),
             EditorMode::Preview => Color::from_rgb(0.1, 0.1, 0.12), // Slightly different for preview
         };
 
-        container(scrollable(container(content).padding(20).width(Length::Fill)))
+        let focus_path;
+        let ctx = match ctx.reuse.filter(|_| ctx.mode == EditorMode::Design) {
+            Some(reuse) => {
+                reuse.cache.prune(reuse.node_index);
+                focus_path = Self::focus_path(root, ctx, reuse.node_index);
+                RenderContext {
+                    reuse: Some(Reuse { focus_path: &focus_path, ..reuse }),
+                    ..ctx
+                }
+            }
+            None => RenderContext { reuse: None, ..ctx },
+        };
+        let builds_before = ctx.reuse.map(|reuse| reuse.cache.nodes.builds());
+
+        let body: Element<'static, Message> = match canvas_size {
+            Some(size) => Self::render_framed(Self::render_node(root, ctx), size),
+            None => {
+                // Render the root node, but override height to Shrink for scrollable compatibility
+                let content = Self::render_node_for_canvas(root, ctx, true);
+                scrollable(container(content).padding(metrics::scaled(20.0)).width(Length::Fill))
+                    .id(Self::scrollable_id())
+                    .on_scroll(Message::CanvasScrolled)
+                    .into()
+            }
+        };
+
+        if let (Some(reuse), Some(before)) = (ctx.reuse, builds_before) {
+            let built = reuse.cache.nodes.builds() - before;
+            reuse.cache.last_built.set(built);
+            tracing::trace!(target: targets::CANVAS, built, total = reuse.node_index.len(), "Canvas view built");
+        }
+
+        container(body)
             .width(Length::Fill)
             .height(Length::Fill)
             .style(move |_theme| container::Style {
@@ -43,19 +250,75 @@ impl Canvas {
             .into()
     }
 
-    /// Render an empty canvas placeholder.
-    pub fn view_empty<'a>() -> Element<'a, Message> {
-        container(center(
-            text("No project open.\nUse File → New or File → Open to get started.")
-                .size(16)
-                .color(Color::from_rgb(0.5, 0.5, 0.5)),
-        ))
-        .width(Length::Fill)
-        .height(Length::Fill)
-        .style(|_theme| container::Style {
-            background: Some(iced::Background::Color(Color::from_rgb(0.15, 0.15, 0.15))),
-            ..Default::default()
-        })
+    /// Render content inside a fixed-size frame, centered in the panel.
+    ///
+    /// The frame has exactly the given size, so Fill and FillPortion lengths
+    /// resolve against it. Frames larger than the panel scroll.
+    fn render_framed(content: Element<'_, Message>, size: CanvasSize) -> Element<'_, Message> {
+        let frame = container(content)
+            .width(Length::Fixed(size.width))
+            .height(Length::Fixed(size.height))
+            .clip(true)
+            .style(|_theme| container::Style {
+                border: Border {
+                    color: Color::from_rgb(0.45, 0.45, 0.5),
+                    width: 1.0,
+                    radius: 0.0.into(),
+                },
+                ..Default::default()
+            });
+
+        center(
+            scrollable(container(frame).padding(metrics::scaled(20.0)))
+                .id(Self::scrollable_id())
+                .on_scroll(Message::CanvasScrolled)
+                .direction(scrollable::Direction::Both {
+                    vertical: scrollable::Scrollbar::default(),
+                    horizontal: scrollable::Scrollbar::default(),
+                }),
+        )
+        .into()
+    }
+
+    /// Render the canvas size picker with custom width and height inputs.
+    pub fn size_bar<'a>(
+        settings: &AppSettings,
+        width_input: &'a str,
+        height_input: &'a str,
+    ) -> Element<'static, Message> {
+        let label = |s: &'static str| text(s).size(metrics::text_md()).color(Color::from_rgb(0.6, 0.6, 0.6));
+
+        container(
+            row![
+                label("Canvas"),
+                pick_list(
+                    CANVAS_PRESETS,
+                    CanvasPreset::matching(settings.canvas_size),
+                    Message::SelectCanvasPreset,
+                )
+                .placeholder("Custom")
+                .text_size(metrics::text_md()),
+                text_input("Width", width_input)
+                    .on_input(Message::CanvasWidthInput)
+                    .on_submit(Message::CommitCanvasSize)
+                    .size(metrics::text_md())
+                    .width(Length::Fixed(metrics::scaled(60.0))),
+                label("×"),
+                text_input("Height", height_input)
+                    .on_input(Message::CanvasHeightInput)
+                    .on_submit(Message::CommitCanvasSize)
+                    .size(metrics::text_md())
+                    .width(Length::Fixed(metrics::scaled(60.0))),
+                Space::with_width(Length::Fill),
+                checkbox("Auto-scroll to selection", settings.auto_scroll_canvas)
+                    .on_toggle(Message::ToggleCanvasAutoScroll)
+                    .size(metrics::title())
+                    .text_size(metrics::text_md()),
+            ]
+            .spacing(6)
+            .align_y(iced::Alignment::Center),
+        )
+        .padding(metrics::pad())
         .into()
     }
 
@@ -63,25 +326,26 @@ impl Canvas {
     /// The root node's height is forced to Shrink to work inside a scrollable.
     fn render_node_for_canvas<'a>(
         node: &'a LayoutNode,
-        selected_id: Option<ComponentId>,
+        ctx: RenderContext<'a>,
         is_root: bool,
-        mode: EditorMode,
-    ) -> Element<'a, Message> {
-        let is_selected = selected_id == Some(node.id);
-        let widget = Self::render_widget_for_canvas(node, selected_id, is_root, mode);
+    ) -> Element<'static, Message> {
+        let is_selected = ctx.selected_id == Some(node.id);
+        let widget = Self::render_widget_for_canvas(node, ctx, is_root);
+        let widget = Self::measure_if_selected(node, widget, ctx);
+        let widget = Self::outline_if_invisible(node, widget, ctx);
+        let widget = Self::decorate_drop_target(node, widget, ctx);
 
         // In design mode, wrap in mouse_area for selection
         // In preview mode, don't wrap (let widgets behave normally)
-        let wrapped: Element<'a, Message> = match mode {
-            EditorMode::Design => {
-                mouse_area(widget).on_press(Message::SelectComponent(node.id)).into()
-            }
+        let wrapped: Element<'static, Message> = match ctx.mode {
+            EditorMode::Design => Self::selectable(node, widget, ctx),
             EditorMode::Preview => widget,
         };
 
         // Apply selection styling if selected (only in design mode)
-        if is_selected && mode == EditorMode::Design {
+        if is_selected && ctx.mode == EditorMode::Design {
             container(wrapped)
+                .id(Self::selection_id())
                 .style(|_theme| container::Style {
                     border: Border {
                         color: Color::from_rgb(0.2, 0.6, 1.0),
@@ -96,22 +360,95 @@ impl Canvas {
         }
     }
 
+    /// Make a design-mode node selectable by click, give it a context menu,
+    /// and make it a drop target while dragging.
+    fn selectable<'a>(
+        node: &'a LayoutNode,
+        widget: Element<'static, Message>,
+        ctx: RenderContext<'a>,
+    ) -> Element<'static, Message> {
+        let area = mouse_area(widget)
+            .on_press(Message::CanvasClicked(node.id))
+            .on_right_press(Message::OpenContextMenu(node.id));
+        if ctx.dropping {
+            area.on_release(Message::PaletteDropOn(node.id)).into()
+        } else {
+            area.into()
+        }
+    }
+
+    /// Get the nodes on the paths to the selection and the inline edit.
+    ///
+    /// The root is on every path, including when it is itself focused;
+    /// framed canvases reuse its element like any other node.
+    fn focus_path(root: &LayoutNode, ctx: RenderContext<'_>, node_index: &NodeIndex) -> Vec<ComponentId> {
+        let mut ids = Vec::new();
+        let focused = ctx.selected_id.into_iter().chain(ctx.inline_edit.map(|edit| edit.id));
+        for path in focused.filter_map(|id| node_index.get(&id)) {
+            if ids.is_empty() {
+                ids.push(root.id);
+            }
+            let mut node = root;
+            for &i in path {
+                let Some(child) = node.child_nodes().get(i).copied() else {
+                    break;
+                };
+                node = child;
+                ids.push(node.id);
+            }
+        }
+        ids
+    }
+
+    /// Get the key a node's element is reused under.
+    fn node_key(node: &LayoutNode, ctx: RenderContext<'_>, reuse: Reuse<'_>) -> NodeKey {
+        let focus = reuse
+            .focus_path
+            .contains(&node.id)
+            .then(|| (ctx.selected_id, ctx.inline_edit.map(|edit| edit.draft.clone())));
+        NodeKey {
+            revision: reuse.revisions.get(node.id),
+            focus,
+            show_invisible: ctx.show_invisible,
+            show_measurements: ctx.show_measurements,
+            inserting: ctx.inserting,
+            dropping: ctx.dropping,
+            scale: metrics::scale(),
+        }
+    }
+
+    /// Render a layout node, reusing the element from an earlier frame if
+    /// nothing it depends on changed.
+    fn render_node<'a>(node: &'a LayoutNode, ctx: RenderContext<'a>) -> Element<'static, Message> {
+        match ctx.reuse {
+            // Component layouts can be referenced more than once, so their
+            // node IDs don't identify one element
+            Some(reuse) if ctx.component_depth == 0 => {
+                let key = Self::node_key(node, ctx, reuse);
+                reuse.cache.nodes.get_or_build(node.id, key, || Self::build_node(node, ctx))
+            }
+            _ => Self::build_node(node, ctx),
+        }
+    }
+
     /// Recursively render a layout node.
-    fn render_node<'a>(node: &'a LayoutNode, selected_id: Option<ComponentId>, mode: EditorMode) -> Element<'a, Message> {
-        let is_selected = selected_id == Some(node.id);
-        let widget = Self::render_widget(node, selected_id, mode);
+    fn build_node<'a>(node: &'a LayoutNode, ctx: RenderContext<'a>) -> Element<'static, Message> {
+        let is_selected = ctx.selected_id == Some(node.id);
+        let widget = Self::render_widget(node, ctx);
+        let widget = Self::measure_if_selected(node, widget, ctx);
+        let widget = Self::outline_if_invisible(node, widget, ctx);
+        let widget = Self::decorate_drop_target(node, widget, ctx);
 
         // In design mode, wrap in mouse_area for selection
-        let wrapped: Element<'a, Message> = match mode {
-            EditorMode::Design => {
-                mouse_area(widget).on_press(Message::SelectComponent(node.id)).into()
-            }
+        let wrapped: Element<'static, Message> = match ctx.mode {
+            EditorMode::Design => Self::selectable(node, widget, ctx),
             EditorMode::Preview => widget,
         };
 
         // Apply selection styling if selected (only in design mode)
-        if is_selected && mode == EditorMode::Design {
+        if is_selected && ctx.mode == EditorMode::Design {
             container(wrapped)
+                .id(Self::selection_id())
                 .style(|_theme| container::Style {
                     border: Border {
                         color: Color::from_rgb(0.2, 0.6, 1.0),
@@ -129,15 +466,18 @@ impl Canvas {
     /// Render widget for canvas root - forces height to Shrink for scrollable compatibility.
     fn render_widget_for_canvas<'a>(
         node: &'a LayoutNode,
-        selected_id: Option<ComponentId>,
+        ctx: RenderContext<'a>,
         is_root: bool,
-        mode: EditorMode,
-    ) -> Element<'a, Message> {
+    ) -> Element<'static, Message> {
+        if let Some(zone) = Self::drop_zone_for(node, ctx, is_root) {
+            return zone;
+        }
+
         match &node.widget {
             WidgetType::Column { children, attrs } => {
                 let mut col = column![];
-                for child in children {
-                    col = col.push(Self::render_node(child, selected_id, mode));
+                for child in children.iter().filter(|c| !c.hidden) {
+                    col = col.push(Self::render_node(child, ctx));
                 }
                 // For root node, use Shrink height to work inside scrollable
                 let height = if is_root {
@@ -158,8 +498,8 @@ impl Canvas {
 
             WidgetType::Row { children, attrs } => {
                 let mut r = row![];
-                for child in children {
-                    r = r.push(Self::render_node(child, selected_id, mode));
+                for child in children.iter().filter(|c| !c.hidden) {
+                    r = r.push(Self::render_node(child, ctx));
                 }
                 let height = if is_root {
                     Length::Shrink
@@ -178,17 +518,26 @@ impl Canvas {
             }
 
             // For other widget types, delegate to render_widget
-            _ => Self::render_widget(node, selected_id, mode),
+            _ => Self::render_widget(node, ctx),
         }
     }
 
     /// Render the actual widget based on its type.
-    fn render_widget<'a>(node: &'a LayoutNode, selected_id: Option<ComponentId>, mode: EditorMode) -> Element<'a, Message> {
+    fn render_widget<'a>(node: &'a LayoutNode, ctx: RenderContext<'a>) -> Element<'static, Message> {
+        // A Text or Button being edited inline is swapped for a text input
+        if let Some(draft) = ctx.editing_draft(node.id) {
+            return Self::render_inline_edit(draft);
+        }
+
+        if let Some(zone) = Self::drop_zone_for(node, ctx, false) {
+            return zone;
+        }
+
         match &node.widget {
             WidgetType::Column { children, attrs } => {
                 let mut col = column![];
-                for child in children {
-                    col = col.push(Self::render_node(child, selected_id, mode));
+                for child in children.iter().filter(|c| !c.hidden) {
+                    col = col.push(Self::render_node(child, ctx));
                 }
                 col.spacing(attrs.spacing)
                     .padding(iced::Padding::new(attrs.padding.top)
@@ -203,8 +552,8 @@ impl Canvas {
 
             WidgetType::Row { children, attrs } => {
                 let mut r = row![];
-                for child in children {
-                    r = r.push(Self::render_node(child, selected_id, mode));
+                for child in children.iter().filter(|c| !c.hidden) {
+                    r = r.push(Self::render_node(child, ctx));
                 }
                 r.spacing(attrs.spacing)
                     .padding(iced::Padding::new(attrs.padding.top)
@@ -218,8 +567,8 @@ impl Canvas {
             }
 
             WidgetType::Container { child, attrs } => {
-                let content: Element<'a, Message> = match child {
-                    Some(c) => Self::render_node(c, selected_id, mode),
+                let content: Element<'static, Message> = match child.as_deref().filter(|c| !c.hidden) {
+                    Some(c) => Self::render_node(c, ctx),
                     None => text("(empty)").color(Color::from_rgb(0.5, 0.5, 0.5)).into(),
                 };
                 container(content)
@@ -235,8 +584,8 @@ impl Canvas {
             }
 
             WidgetType::Scrollable { child, attrs } => {
-                let content: Element<'a, Message> = match child {
-                    Some(c) => Self::render_node(c, selected_id, mode),
+                let content: Element<'static, Message> = match child.as_deref().filter(|c| !c.hidden) {
+                    Some(c) => Self::render_node(c, ctx),
                     None => text("(empty)").color(Color::from_rgb(0.5, 0.5, 0.5)).into(),
                 };
                 scrollable(content)
@@ -247,9 +596,10 @@ impl Canvas {
 
             WidgetType::Stack { children, attrs } => {
                 // Use Iced's stack widget for overlays
-                let layers: Vec<Element<'a, Message>> = children
+                let layers: Vec<Element<'static, Message>> = children
                     .iter()
-                    .map(|child| Self::render_node(child, selected_id, mode))
+                    .filter(|child| !child.hidden)
+                    .map(|child| Self::render_node(child, ctx))
                     .collect();
                 
                 stack(layers)
@@ -259,7 +609,7 @@ impl Canvas {
             }
 
             WidgetType::Text { content, attrs } => {
-                let mut t = text(content.as_str()).size(attrs.font_size);
+                let mut t = text(content.clone()).size(attrs.font_size);
                 if let Some(color) = attrs.color {
                     t = t.color(Color::from_rgba(color[0], color[1], color[2], color[3]));
                 }
@@ -267,24 +617,24 @@ impl Canvas {
             }
 
             WidgetType::Button { label, .. } => {
-                match mode {
+                match ctx.mode {
                     EditorMode::Design => {
                         // In design mode, buttons select instead of firing their action
-                        button(text(label.as_str()))
-                            .on_press(Message::SelectComponent(node.id))
+                        button(text(label.clone()))
+                            .on_press(Message::CanvasClicked(node.id))
                             .into()
                     }
                     EditorMode::Preview => {
                         // In preview mode, buttons show as clickable but don't do anything
-                        button(text(label.as_str()))
+                        button(text(label.clone()))
                             .on_press(Message::Noop)
                             .into()
                     }
                 }
             }
 
-            WidgetType::TextInput { placeholder, .. } => {
-                match mode {
+            WidgetType::TextInput { placeholder, value_binding, .. } => {
+                match ctx.mode {
                     EditorMode::Design => {
                         // In design mode, text inputs are read-only
                         text_input(placeholder.as_str(), "")
@@ -292,42 +642,67 @@ impl Canvas {
                     }
                     EditorMode::Preview => {
                         // In preview mode, text inputs can be typed into (but changes aren't saved)
-                        text_input(placeholder.as_str(), "")
+                        let value = ctx
+                            .sample(value_binding)
+                            .map(SampleValue::as_text)
+                            .unwrap_or_default();
+                        text_input(placeholder.as_str(), &value)
                             .on_input(|_| Message::Noop)
                             .into()
                     }
                 }
             }
 
-            WidgetType::Checkbox { label, .. } => {
-                match mode {
+            WidgetType::Checkbox { label, checked_binding, .. } => {
+                match ctx.mode {
                     EditorMode::Design => {
                         // In design mode, checkboxes don't toggle
-                        checkbox(label.as_str(), false).into()
+                        checkbox(label.clone(), false).into()
                     }
                     EditorMode::Preview => {
                         // In preview mode, checkboxes can be toggled (but state isn't saved)
-                        checkbox(label.as_str(), false)
+                        let checked = ctx
+                            .sample(checked_binding)
+                            .and_then(SampleValue::as_bool)
+                            .unwrap_or(false);
+                        checkbox(label.clone(), checked)
                             .on_toggle(|_| Message::Noop)
                             .into()
                     }
                 }
             }
 
-            WidgetType::Slider { min, max, .. } => {
-                // In both modes, sliders show at midpoint
-                let mid = (min + max) / 2.0;
-                slider(*min..=*max, mid, |_| Message::Noop).into()
+            WidgetType::Slider { min, max, value_binding, .. } => {
+                // Sliders show the sample value in preview, otherwise the midpoint
+                let value = ctx
+                    .sample(value_binding)
+                    .and_then(SampleValue::as_number)
+                    .map(|v| v.clamp(min.min(*max), max.max(*min)))
+                    .unwrap_or((min + max) / 2.0);
+                slider(*min..=*max, value, |_| Message::Noop).into()
+            }
+
+            WidgetType::PickList { options, selected_binding, attrs, .. }
+                if ctx.mode == EditorMode::Preview =>
+            {
+                // In preview mode, show a working dropdown (selection isn't saved)
+                let id = node.id;
+                let selected = ctx
+                    .preview_selections
+                    .and_then(|selections| selections.get(&id).cloned())
+                    .or_else(|| ctx.sample(selected_binding).map(SampleValue::as_text));
+                pick_list(options.clone(), selected, move |choice| {
+                    Message::PreviewPickListSelected(id, choice)
+                })
+                .placeholder(attrs.placeholder.as_str())
+                .width(Self::convert_length(attrs.width))
+                .into()
             }
 
             WidgetType::PickList { options, attrs, .. } => {
-                // Show as a disabled-looking text for now
-                let display = if options.is_empty() {
-                    attrs.placeholder.as_str()
-                } else {
-                    &options[0]
-                };
-                container(text(display).size(14))
+                // In design mode, show as a disabled-looking text so clicks select
+                let display = options.first().unwrap_or(&attrs.placeholder);
+                container(text(display.clone()).size(14))
                     .padding(5)
                     .style(|_theme| container::Style {
                         border: Border {
@@ -343,6 +718,260 @@ impl Canvas {
             WidgetType::Space { width, height } => {
                 Space::new(Self::convert_length(*width), Self::convert_length(*height)).into()
             }
+
+            WidgetType::ComponentRef { file } => Self::render_component_ref(node, file, ctx),
+        }
+    }
+
+    /// Render a referenced component inline and read-only, with a name badge.
+    fn render_component_ref<'a>(
+        node: &'a LayoutNode,
+        file: &Path,
+        ctx: RenderContext<'a>,
+    ) -> Element<'static, Message> {
+        let name = node.widget.component_name().unwrap_or("component");
+        let doc = ctx
+            .components
+            .and_then(|components| components.get(file))
+            .filter(|_| ctx.component_depth < MAX_COMPONENT_DEPTH);
+        let Some(doc) = doc else {
+            return container(text(format!("⧉ {name} (missing)")).size(metrics::text_md()))
+                .padding(metrics::scaled(8.0))
+                .style(container::rounded_box)
+                .into();
+        };
+
+        // The component's nodes aren't part of this layout, so they can't be
+        // selected or edited here
+        let inner = RenderContext {
+            selected_id: None,
+            mode: EditorMode::Preview,
+            show_measurements: false,
+            inline_edit: None,
+            inserting: false,
+            dropping: false,
+            component_depth: ctx.component_depth + 1,
+            reuse: None,
+            ..ctx
+        };
+        let mut layers = stack![Self::render_node(&doc.root, inner)];
+        if ctx.mode == EditorMode::Design {
+            // Presses select the reference instead of reaching the component's widgets
+            layers = layers.push(
+                mouse_area(Space::new(Length::Fill, Length::Fill))
+                    .on_press(Message::CanvasClicked(node.id)),
+            );
+        }
+        let badge = container(text(format!("⧉ {name}")).size(metrics::text_xs()))
+            .padding(metrics::pad_xy(1.0, 4.0))
+            .style(container::rounded_box);
+        layers.push(badge).into()
+    }
+
+    /// Render an empty container as a drop zone placeholder (design mode only).
+    ///
+    /// The root keeps a fixed height so it stays valid inside the canvas scrollable.
+    fn drop_zone_for<'a>(
+        node: &'a LayoutNode,
+        ctx: RenderContext<'a>,
+        is_root: bool,
+    ) -> Option<Element<'static, Message>> {
+        if ctx.mode != EditorMode::Design {
+            return None;
+        }
+        let kind = Self::empty_container_kind(node)?;
+        let (width, height) = match &node.widget {
+            WidgetType::Column { attrs, .. }
+            | WidgetType::Row { attrs, .. }
+            | WidgetType::Container { attrs, .. }
+            | WidgetType::Scrollable { attrs, .. }
+            | WidgetType::Stack { attrs, .. } => (attrs.width, attrs.height),
+            _ => return None,
+        };
+        let height = match height {
+            LengthSpec::Fill | LengthSpec::FillPortion(_) if is_root => LengthSpec::Shrink,
+            other => other,
+        };
+
+        let label = text(Self::drop_zone_label(kind))
+            .size(metrics::text_sm())
+            .color(DROP_ZONE_COLOR)
+            .align_x(iced::alignment::Horizontal::Center);
+        let zone = container(center(label))
+            .width(Self::drop_zone_length(width, DROP_ZONE_MIN_SIZE.width))
+            .height(Self::drop_zone_length(height, DROP_ZONE_MIN_SIZE.height));
+
+        Some(stack![zone, DashedOutline::new(DROP_ZONE_COLOR)].into())
+    }
+
+    /// Get the type name of a container node that has no children.
+    fn empty_container_kind(node: &LayoutNode) -> Option<&'static str> {
+        let empty = node.can_accept_child() && node.child_nodes().is_empty();
+        empty.then(|| node.widget.type_name())
+    }
+
+    /// Get the label shown inside a drop zone.
+    fn drop_zone_label(kind: &str) -> String {
+        format!("Drop here\nempty {}", kind)
+    }
+
+    /// Resolve a drop zone length, never smaller than `min`.
+    fn drop_zone_length(spec: LengthSpec, min: f32) -> Length {
+        match spec {
+            LengthSpec::Fixed(v) => Length::Fixed(v.max(min)),
+            LengthSpec::Shrink => Length::Fixed(min),
+            other => Self::convert_length(other),
+        }
+    }
+
+    /// Render the inline edit input that replaces a Text or Button being edited.
+    fn render_inline_edit(draft: &str) -> Element<'static, Message> {
+        text_input("", draft)
+            .id(inline_edit_input_id())
+            .on_input(Message::InlineEditChanged)
+            .on_submit(Message::CommitInlineEdit)
+            .width(Length::Fixed(200.0))
+            .into()
+    }
+
+    /// Overlay padding and spacing bands on the selected container (design mode only).
+    fn measure_if_selected<'a>(
+        node: &'a LayoutNode,
+        widget: Element<'static, Message>,
+        ctx: RenderContext<'a>,
+    ) -> Element<'static, Message> {
+        if !ctx.show_measurements
+            || ctx.mode != EditorMode::Design
+            || ctx.selected_id != Some(node.id)
+        {
+            return widget;
+        }
+
+        let (attrs, axis) = match &node.widget {
+            WidgetType::Column { attrs, .. } => (attrs, Some(Axis::Vertical)),
+            WidgetType::Row { attrs, .. } => (attrs, Some(Axis::Horizontal)),
+            WidgetType::Container { attrs, .. } => (attrs, None),
+            // Other containers don't render padding on the canvas
+            _ => return widget,
+        };
+
+        let padding = iced::Padding::new(attrs.padding.top)
+            .right(attrs.padding.right)
+            .bottom(attrs.padding.bottom)
+            .left(attrs.padding.left);
+        let overlay = MeasureOverlay::new(widget, padding);
+        match axis {
+            Some(axis) => overlay.spacing_axis(axis).into(),
+            None => overlay.into(),
+        }
+    }
+
+    /// Highlight eligible drop targets and dim ineligible ones while an insert is pending.
+    ///
+    /// Leaf widgets are covered by a dimming overlay. Single-child containers
+    /// that already hold a child are dimmed underneath their content instead,
+    /// so an eligible target inside them stays bright. Uses container
+    /// borders, backgrounds and stacked overlays so layout is unchanged.
+    fn decorate_drop_target<'a>(
+        node: &'a LayoutNode,
+        widget: Element<'static, Message>,
+        ctx: RenderContext<'a>,
+    ) -> Element<'static, Message> {
+        if !ctx.inserting || ctx.mode != EditorMode::Design {
+            return widget;
+        }
+
+        if node.can_accept_child() {
+            container(widget)
+                .style(|_theme| container::Style {
+                    background: Some(iced::Background::Color(Color {
+                        a: 0.08,
+                        ..DROP_TARGET_COLOR
+                    })),
+                    border: Border {
+                        color: DROP_TARGET_COLOR,
+                        width: 2.0,
+                        radius: 2.0.into(),
+                    },
+                    ..Default::default()
+                })
+                .into()
+        } else if Self::is_leaf(node) {
+            let dim = container(Space::new(Length::Fill, Length::Fill)).style(|_theme| {
+                container::Style {
+                    background: Some(iced::Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.35))),
+                    ..Default::default()
+                }
+            });
+            stack![widget, dim].into()
+        } else {
+            container(widget)
+                .style(|_theme| container::Style {
+                    background: Some(iced::Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.35))),
+                    border: Border {
+                        color: Color::from_rgba(0.5, 0.5, 0.5, 0.6),
+                        width: 2.0,
+                        radius: 2.0.into(),
+                    },
+                    ..Default::default()
+                })
+                .into()
+        }
+    }
+
+    /// Check if a node is a leaf widget that can never hold children.
+    fn is_leaf(node: &LayoutNode) -> bool {
+        !matches!(
+            node.widget,
+            WidgetType::Column { .. }
+                | WidgetType::Row { .. }
+                | WidgetType::Stack { .. }
+                | WidgetType::Container { .. }
+                | WidgetType::Scrollable { .. }
+        )
+    }
+
+    /// Overlay a dashed outline on nodes that would otherwise be invisible.
+    ///
+    /// The outline is stacked on top of the widget, so it never changes the
+    /// widget's measured size. Only applies in design mode.
+    fn outline_if_invisible<'a>(
+        node: &'a LayoutNode,
+        widget: Element<'static, Message>,
+        ctx: RenderContext<'a>,
+    ) -> Element<'static, Message> {
+        if !ctx.show_invisible || ctx.mode != EditorMode::Design {
+            return widget;
+        }
+
+        match Self::invisible_label(node) {
+            Some(label) => stack![widget, dashed_outline(label, INVISIBLE_OUTLINE_COLOR)].into(),
+            None => widget,
+        }
+    }
+
+    /// Get the outline label for a node that renders without visible content.
+    fn invisible_label(node: &LayoutNode) -> Option<&'static str> {
+        let is_zero = |length: LengthSpec| matches!(length, LengthSpec::Fixed(v) if v <= 0.0);
+
+        match &node.widget {
+            WidgetType::Space { .. } => Some("Space"),
+            // Empty containers already render as visible drop zones
+            _ if Self::empty_container_kind(node).is_some() => None,
+            WidgetType::Column { attrs, .. }
+            | WidgetType::Row { attrs, .. }
+            | WidgetType::Container { attrs, .. }
+            | WidgetType::Scrollable { attrs, .. }
+            | WidgetType::Stack { attrs, .. }
+                if is_zero(attrs.width) || is_zero(attrs.height) =>
+            {
+                Some("zero-size")
+            }
+            WidgetType::Button { attrs, .. } if is_zero(attrs.width) || is_zero(attrs.height) => {
+                Some("zero-size")
+            }
+            WidgetType::Text { content, .. } if content.is_empty() => Some("empty Text"),
+            _ => None,
         }
     }
 
@@ -384,3 +1013,147 @@ impl Canvas {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::layout::{ContainerAttrs, TextAttrs};
+    use tempfile::tempdir;
+
+    fn text_node() -> LayoutNode {
+        LayoutNode::new(WidgetType::Text {
+            content: "Hi".to_string(),
+            attrs: TextAttrs::default(),
+        })
+    }
+
+    /// Render the project in design mode, returning how many nodes were built.
+    fn render(project: &Project, cache: &CanvasCache, show_invisible: bool, size: Option<CanvasSize>) -> usize {
+        let ctx = RenderContext {
+            selected_id: project.selected_id,
+            mode: EditorMode::Design,
+            show_invisible,
+            show_measurements: true,
+            inline_edit: None,
+            sample_data: None,
+            inserting: false,
+            dropping: false,
+            preview_selections: None,
+            components: None,
+            component_depth: 0,
+            reuse: Some(Reuse::new(cache, project)),
+        };
+        drop(Canvas::view(&project.layout.root, ctx, size));
+        cache.last_built.get()
+    }
+
+    #[test]
+    fn test_view_builds_only_changed_nodes() {
+        // Scrolling canvases render the root directly; framed ones reuse it too
+        for size in [None, Some(CanvasSize::new(800.0, 600.0))] {
+            let temp = tempdir().unwrap();
+            let mut project = Project::create(temp.path(), None).unwrap();
+            // A stress layout of 40 rows of 40 texts
+            for _ in 0..40 {
+                assert!(project.add_child_to_root(LayoutNode::new(WidgetType::Row {
+                    children: (0..40).map(|_| text_node()).collect(),
+                    attrs: ContainerAttrs::default(),
+                })));
+            }
+            let total = project.node_index.len() - 1;
+            assert!(total > 1600);
+            let rows: Vec<ComponentId> = project.layout.root.child_nodes().iter().rev().take(3).map(|row| row.id).collect();
+            let leaf = |project: &Project, row: usize, i: usize| project.find_node(rows[row]).unwrap().child_nodes()[i].id;
+            let root = usize::from(size.is_some());
+            let render = |project: &Project, cache: &CanvasCache, show_invisible: bool| {
+                render(project, cache, show_invisible, size)
+            };
+
+            let cache = CanvasCache::default();
+            assert_eq!(render(&project, &cache, true), total + root);
+            assert_eq!(render(&project, &cache, true), 0);
+
+            // Selecting rebuilds the paths to the old and new selection
+            project.selected_id = Some(leaf(&project, 0, 5));
+            assert_eq!(render(&project, &cache, true), 2 + root);
+            project.selected_id = Some(leaf(&project, 1, 7));
+            assert_eq!(render(&project, &cache, true), 4 + root);
+            assert_eq!(render(&project, &cache, true), 0);
+            project.selected_id = Some(project.layout.root.id);
+            assert_eq!(render(&project, &cache, true), 2 + root);
+            project.selected_id = None;
+            assert_eq!(render(&project, &cache, true), root);
+
+            // Edits rebuild the edited node and its ancestors
+            let edited = leaf(&project, 2, 3);
+            assert!(project.update_node(edited, "Edit text", |node| {
+                if let WidgetType::Text { content, .. } = &mut node.widget {
+                    *content = "Changed".to_string();
+                }
+            }));
+            assert_eq!(render(&project, &cache, true), 2 + root);
+
+            let added = text_node();
+            let added_id = added.id;
+            assert!(project.add_child_to_node(rows[2], added));
+            assert_eq!(render(&project, &cache, true), 2 + root);
+            assert!(project.remove_node(added_id));
+            assert_eq!(render(&project, &cache, true), 1 + root);
+
+            // Display options and wholesale changes rebuild everything
+            assert_eq!(render(&project, &cache, false), total + root);
+            project.rebuild_index();
+            assert_eq!(render(&project, &cache, false), total + root);
+        }
+    }
+
+    #[test]
+    fn test_empty_container_kind() {
+        let empty_column = LayoutNode::new(WidgetType::Column {
+            children: vec![],
+            attrs: ContainerAttrs::default(),
+        });
+        assert_eq!(Canvas::empty_container_kind(&empty_column), Some("Column"));
+
+        let empty_scrollable = LayoutNode::new(WidgetType::Scrollable {
+            child: None,
+            attrs: ContainerAttrs::default(),
+        });
+        assert_eq!(Canvas::empty_container_kind(&empty_scrollable), Some("Scrollable"));
+
+        let filled_row = LayoutNode::new(WidgetType::Row {
+            children: vec![text_node()],
+            attrs: ContainerAttrs::default(),
+        });
+        assert_eq!(Canvas::empty_container_kind(&filled_row), None);
+        assert_eq!(Canvas::empty_container_kind(&text_node()), None);
+    }
+
+    #[test]
+    fn test_drop_zone_label() {
+        assert_eq!(Canvas::drop_zone_label("Row"), "Drop here\nempty Row");
+    }
+
+    #[test]
+    fn test_drop_zone_length_enforces_minimum() {
+        assert_eq!(Canvas::drop_zone_length(LengthSpec::Shrink, 80.0), Length::Fixed(80.0));
+        assert_eq!(Canvas::drop_zone_length(LengthSpec::Fixed(10.0), 80.0), Length::Fixed(80.0));
+        assert_eq!(Canvas::drop_zone_length(LengthSpec::Fixed(200.0), 80.0), Length::Fixed(200.0));
+        assert_eq!(Canvas::drop_zone_length(LengthSpec::Fill, 80.0), Length::Fill);
+    }
+
+    #[test]
+    fn test_empty_containers_are_not_outlined_as_invisible() {
+        let empty_container = LayoutNode::new(WidgetType::Container {
+            child: None,
+            attrs: ContainerAttrs::default(),
+        });
+        assert_eq!(Canvas::invisible_label(&empty_container), None);
+
+        let space = LayoutNode::new(WidgetType::Space {
+            width: LengthSpec::Fixed(10.0),
+            height: LengthSpec::Fixed(10.0),
+        });
+        assert_eq!(Canvas::invisible_label(&space), Some("Space"));
+    }
+}
@@ -0,0 +1,246 @@
+//! Generated code preview.
+//!
+//! A modal over the editor showing the Rust code export would write. The
+//! code is regenerated as the layout changes, but only once edits settle
+//! for `REGENERATE_DELAY`, so typing in the inspector doesn't regenerate
+//! on every keystroke.
+//!
+//! Before export overwrites an existing output file, the panel shows the
+//! changes as a diff and waits for confirmation.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use iced::widget::{button, checkbox, column, container, horizontal_space, opaque, row, scrollable, text, Column};
+use iced::{Color, Element, Font, Length};
+
+use crate::app::Message;
+use crate::ui::metrics;
+use crate::util::diff::{diff_summary, DiffLine};
+
+/// How long the layout must stay unchanged before the preview regenerates.
+pub const REGENERATE_DELAY: Duration = Duration::from_millis(300);
+
+/// State of the open code preview.
+#[derive(Debug, Default)]
+pub struct CodePreview {
+    /// Generated code, empty until the first generation finishes.
+    pub code: String,
+    /// Project revision the code was, or is being, generated from.
+    pub revision: Option<u64>,
+    /// Revision a delayed regeneration is waiting on.
+    pub scheduled: Option<u64>,
+    /// Whether the preview is run through rustfmt.
+    pub format: bool,
+    /// Export waiting for confirmation, if any.
+    pub review: Option<ExportReview>,
+}
+
+impl CodePreview {
+    /// Create an empty preview.
+    pub fn new(format: bool) -> Self {
+        Self {
+            format,
+            ..Self::default()
+        }
+    }
+}
+
+/// Changes an export would make to an existing output file.
+#[derive(Debug)]
+pub struct ExportReview {
+    /// The file that would be overwritten.
+    pub path: PathBuf,
+    /// Diff from the existing file to the new code.
+    pub diff: Vec<DiffLine>,
+    /// Number of added lines.
+    pub added: usize,
+    /// Number of removed lines.
+    pub removed: usize,
+    /// Whether the existing file lacks the generated-file header.
+    pub foreign: bool,
+    /// Whether the existing file was edited by hand since it was exported.
+    pub edited: bool,
+    /// Whether overwriting a foreign or edited file was confirmed.
+    pub acknowledged: bool,
+    /// Whether the panel was opened for this review and closes after it.
+    pub close_after: bool,
+}
+
+impl ExportReview {
+    /// Review replacing `existing` at `path` with `code`.
+    pub fn new(path: PathBuf, existing: &str, code: &str, close_after: bool) -> Self {
+        let diff = crate::util::diff::diff_lines(existing, code);
+        let (added, removed) = diff_summary(&diff);
+        let foreign = !existing.starts_with(crate::codegen::GENERATED_HEADER);
+        Self {
+            path,
+            diff,
+            added,
+            removed,
+            foreign,
+            edited: !foreign && crate::codegen::stamp_matches(existing) == Some(false),
+            acknowledged: false,
+            close_after,
+        }
+    }
+
+    /// Whether the export may go ahead.
+    pub fn can_confirm(&self) -> bool {
+        !(self.foreign || self.edited) || self.acknowledged
+    }
+}
+
+/// The code preview modal.
+pub struct CodePanel;
+
+/// Unchanged lines kept around each change when runs are collapsed.
+const DIFF_CONTEXT: usize = 3;
+
+impl CodePanel {
+    /// Render the preview centered over a dimmed backdrop that blocks input
+    /// to the editor below.
+    pub fn view(preview: &CodePreview) -> Element<'_, Message> {
+        let content = match &preview.review {
+            Some(review) => Self::review_view(review),
+            None => Self::code_view(preview),
+        };
+
+        let card = container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(metrics::scaled(12.0))
+            .style(|theme: &iced::Theme| container::Style {
+                background: Some(theme.extended_palette().background.base.color.into()),
+                border: iced::Border {
+                    color: theme.extended_palette().background.strong.color,
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                ..Default::default()
+            });
+
+        opaque(
+            container(card)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .padding(metrics::scaled(40.0))
+                .style(|_theme| container::Style {
+                    background: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.5).into()),
+                    ..Default::default()
+                }),
+        )
+    }
+
+    fn code_view(preview: &CodePreview) -> Element<'_, Message> {
+        let header = row![
+            text("Generated Code").size(metrics::title()),
+            horizontal_space(),
+            checkbox("Format", preview.format)
+                .on_toggle(Message::ToggleCodeFormat)
+                .size(metrics::title())
+                .text_size(metrics::text_md()),
+            button(text("Copy").size(metrics::text_md()))
+                .on_press_maybe((!preview.code.is_empty()).then_some(Message::CopyGeneratedCode))
+                .padding(metrics::pad()),
+            button(text("Export").size(metrics::text_md()))
+                .on_press(Message::ExportCode)
+                .padding(metrics::pad()),
+            button(text("Close").size(metrics::text_md()))
+                .on_press(Message::ToggleCodePanel)
+                .padding(metrics::pad()),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center);
+
+        let body: Element<Message> = if preview.code.is_empty() {
+            text("Generating…").size(metrics::text_md()).into()
+        } else {
+            scrollable(text(&preview.code).font(Font::MONOSPACE).size(metrics::text_md()))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into()
+        };
+
+        column![header, body].spacing(8).into()
+    }
+
+    fn review_view(review: &ExportReview) -> Element<'_, Message> {
+        let header = row![
+            text(format!("Export changes to {}", review.path.display())).size(metrics::title()),
+            horizontal_space(),
+            text(format!("+{}  −{}", review.added, review.removed)).size(metrics::text_md()),
+            button(text("Confirm Export").size(metrics::text_md()))
+                .on_press_maybe(review.can_confirm().then_some(Message::ConfirmExport))
+                .style(button::primary)
+                .padding(metrics::pad()),
+            button(text("Cancel").size(metrics::text_md()))
+                .on_press(Message::CancelExport)
+                .padding(metrics::pad()),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center);
+
+        let mut content = column![header].spacing(8);
+        let warning = if review.foreign {
+            Some("This file was not generated by Iced Builder and may contain hand-written code.")
+        } else if review.edited {
+            Some("This file was edited by hand since it was exported; those edits will be lost.")
+        } else {
+            None
+        };
+        if let Some(warning) = warning {
+            content = content.push(
+                row![
+                    text(warning)
+                        .size(metrics::text_md())
+                        .color(Color::from_rgb(0.9, 0.4, 0.3)),
+                    checkbox("Overwrite anyway", review.acknowledged)
+                        .on_toggle(Message::AcknowledgeOverwrite)
+                        .size(metrics::title())
+                        .text_size(metrics::text_md()),
+                ]
+                .spacing(12)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+
+        content
+            .push(scrollable(Self::diff_lines(&review.diff)).width(Length::Fill).height(Length::Fill))
+            .into()
+    }
+
+    /// Render diff lines, collapsing long unchanged runs to their context.
+    fn diff_lines(diff: &[DiffLine]) -> Column<'_, Message> {
+        let line = |prefix: &str, content: &str, color: Color| {
+            text(format!("{} {}", prefix, content)).font(Font::MONOSPACE).size(metrics::text_md()).color(color)
+        };
+        let near_change = |index: usize| {
+            let start = index.saturating_sub(DIFF_CONTEXT);
+            let end = (index + DIFF_CONTEXT + 1).min(diff.len());
+            diff[start..end].iter().any(|l| !matches!(l, DiffLine::Unchanged(_)))
+        };
+
+        let mut lines = Column::new();
+        let mut hidden = 0;
+        for (index, diff_line) in diff.iter().enumerate() {
+            if matches!(diff_line, DiffLine::Unchanged(_)) && !near_change(index) {
+                hidden += 1;
+                continue;
+            }
+            if hidden > 0 {
+                lines = lines.push(line("⋯", &format!("{} unchanged lines", hidden), Color::from_rgb(0.5, 0.5, 0.5)));
+                hidden = 0;
+            }
+            lines = lines.push(match diff_line {
+                DiffLine::Unchanged(l) => line(" ", l, Color::from_rgb(0.6, 0.6, 0.6)),
+                DiffLine::Added(l) => line("+", l, Color::from_rgb(0.4, 0.8, 0.4)),
+                DiffLine::Removed(l) => line("-", l, Color::from_rgb(0.9, 0.4, 0.4)),
+            });
+        }
+        if hidden > 0 {
+            lines = lines.push(line("⋯", &format!("{} unchanged lines", hidden), Color::from_rgb(0.5, 0.5, 0.5)));
+        }
+        lines
+    }
+}
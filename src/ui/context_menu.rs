@@ -0,0 +1,194 @@
+//! Right-click menu for layout nodes.
+//!
+//! Tree rows and canvas widgets open the menu with a right press, but a
+//! press inside a scrollable only knows where it landed in the scrolled
+//! content. The whole window is therefore wrapped in a [`RightPressArea`],
+//! which reports each right press in window coordinates before the widget
+//! under the cursor handles it, and the menu opens at the reported point.
+
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::renderer;
+use iced::advanced::widget::{tree, Operation, Tree, Widget};
+use iced::advanced::{overlay, Clipboard, Shell};
+use iced::event::{self, Event};
+use iced::widget::{container, mouse_area, vertical_space};
+use iced::{alignment, mouse};
+use iced::{Element, Length, Padding, Point, Rectangle, Size, Vector};
+
+use crate::app::Message;
+use crate::ui::menu_bar::{MenuBar, MenuEntry};
+
+/// A wrapper that reports where right presses land on its content.
+///
+/// The press is reported first and then passed on, so a message published
+/// by the content for the same press arrives after the position.
+pub struct RightPressArea<'a, Message, Theme, Renderer> {
+    content: Element<'a, Message, Theme, Renderer>,
+    on_press: Box<dyn Fn(Point) -> Message + 'a>,
+}
+
+impl<'a, Message, Theme, Renderer> RightPressArea<'a, Message, Theme, Renderer> {
+    /// Wrap `content`, turning the position of each right press into a message.
+    pub fn new(
+        content: impl Into<Element<'a, Message, Theme, Renderer>>,
+        on_press: impl Fn(Point) -> Message + 'a,
+    ) -> Self {
+        Self {
+            content: content.into(),
+            on_press: Box::new(on_press),
+        }
+    }
+}
+
+impl<Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+    for RightPressArea<'_, Message, Theme, Renderer>
+where
+    Renderer: renderer::Renderer,
+{
+    fn tag(&self) -> tree::Tag {
+        self.content.as_widget().tag()
+    }
+
+    fn state(&self) -> tree::State {
+        self.content.as_widget().state()
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        self.content.as_widget().children()
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        self.content.as_widget().diff(tree);
+    }
+
+    fn size(&self) -> Size<Length> {
+        self.content.as_widget().size()
+    }
+
+    fn size_hint(&self) -> Size<Length> {
+        self.content.as_widget().size_hint()
+    }
+
+    fn layout(&self, tree: &mut Tree, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        self.content.as_widget().layout(tree, renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content
+            .as_widget()
+            .draw(tree, renderer, theme, style, layout, cursor, viewport);
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        self.content
+            .as_widget()
+            .operate(tree, layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        if let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right)) = event {
+            if let Some(position) = cursor.position_over(layout.bounds()) {
+                shell.publish((self.on_press)(position));
+            }
+        }
+
+        self.content.as_widget_mut().on_event(
+            tree, event, layout, cursor, renderer, clipboard, shell, viewport,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content
+            .as_widget()
+            .mouse_interaction(tree, layout, cursor, viewport, renderer)
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        self.content
+            .as_widget_mut()
+            .overlay(tree, layout, renderer, translation)
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<RightPressArea<'a, Message, Theme, Renderer>>
+    for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a,
+    Theme: 'a,
+    Renderer: renderer::Renderer + 'a,
+{
+    fn from(area: RightPressArea<'a, Message, Theme, Renderer>) -> Self {
+        Element::new(area)
+    }
+}
+
+/// The context menu overlay.
+pub struct ContextMenu;
+
+impl ContextMenu {
+    /// Render the menu at `position` as an overlay for a `stack`, with a
+    /// click-away area covering the window.
+    ///
+    /// The menu opens toward whichever side of the window has more room, so
+    /// it stays on screen near the edges.
+    pub fn view(position: Point, window: Size, entries: Vec<MenuEntry>) -> Element<'static, Message> {
+        let close_area = mouse_area(container(vertical_space()).width(Length::Fill).height(Length::Fill))
+            .on_press(Message::CloseContextMenu)
+            .on_right_press(Message::CloseContextMenu);
+
+        let leftward = position.x > window.width / 2.0;
+        let upward = position.y > window.height / 2.0;
+        let padding = Padding {
+            top: if upward { 0.0 } else { position.y },
+            right: if leftward { (window.width - position.x).max(0.0) } else { 0.0 },
+            bottom: if upward { (window.height - position.y).max(0.0) } else { 0.0 },
+            left: if leftward { 0.0 } else { position.x },
+        };
+        let positioned = container(MenuBar::card(entries))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(padding)
+            .align_x(if leftward { alignment::Horizontal::Right } else { alignment::Horizontal::Left })
+            .align_y(if upward { alignment::Vertical::Bottom } else { alignment::Vertical::Top });
+
+        iced::widget::stack![close_area, positioned].into()
+    }
+}
@@ -0,0 +1,90 @@
+//! Confirmation before deleting a node with children.
+//!
+//! Deleting a container removes everything inside it, so when the selected
+//! node has descendants the editor asks first. Leaf widgets are deleted
+//! without asking, and the question can be turned off altogether.
+
+use iced::widget::{button, checkbox, column, container, horizontal_space, opaque, row, text};
+use iced::{Color, Element, Length};
+
+use crate::app::Message;
+use crate::model::ComponentId;
+use crate::ui::metrics;
+
+/// A delete waiting for confirmation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeleteConfirm {
+    /// The node to delete.
+    pub id: ComponentId,
+    /// Widget type of the node, e.g. "Column".
+    pub type_name: &'static str,
+    /// Number of nodes below it.
+    pub descendants: usize,
+    /// Whether "Don't ask again" is ticked.
+    pub dont_ask: bool,
+}
+
+impl DeleteConfirm {
+    /// The question, e.g. "Delete Column and its 12 children?".
+    pub fn question(&self) -> String {
+        match self.descendants {
+            1 => format!("Delete {} and its child?", self.type_name),
+            count => format!("Delete {} and its {} children?", self.type_name, count),
+        }
+    }
+}
+
+/// The delete confirmation dialog.
+pub struct DeleteConfirmDialog;
+
+impl DeleteConfirmDialog {
+    /// Render the dialog centered over a dimmed backdrop.
+    pub fn view(confirm: &DeleteConfirm) -> Element<'_, Message> {
+        let content = column![
+            text(confirm.question()).size(metrics::heading()),
+            text("Everything inside it is removed too. Undo brings it back.")
+                .size(metrics::text_md())
+                .color(Color::from_rgb(0.5, 0.5, 0.5)),
+            row![
+                checkbox("Don't ask again", confirm.dont_ask)
+                    .on_toggle(Message::ToggleDontAskDelete)
+                    .size(metrics::scaled(14.0))
+                    .text_size(metrics::text_md()),
+                horizontal_space(),
+                button(text("Cancel").size(metrics::text_md()))
+                    .on_press(Message::CancelDelete)
+                    .style(button::secondary)
+                    .padding(metrics::pad_xy(4.0, 10.0)),
+                button(text("Delete").size(metrics::text_md()))
+                    .on_press(Message::ConfirmDelete)
+                    .style(button::danger)
+                    .padding(metrics::pad_xy(4.0, 10.0)),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center),
+        ]
+        .spacing(12);
+
+        let card = container(content)
+            .width(Length::Fixed(metrics::scaled(420.0)))
+            .padding(metrics::scaled(16.0))
+            .style(|theme: &iced::Theme| container::Style {
+                background: Some(theme.extended_palette().background.base.color.into()),
+                border: iced::Border {
+                    color: theme.extended_palette().background.strong.color,
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                ..Default::default()
+            });
+
+        opaque(
+            container(card)
+                .center(Length::Fill)
+                .style(|_theme| container::Style {
+                    background: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.5).into()),
+                    ..Default::default()
+                }),
+        )
+    }
+}
@@ -0,0 +1,154 @@
+//! Modal error dialog.
+//!
+//! Failures that lose or block work (opening, saving, exporting) are shown
+//! here in full rather than cut off in the status bar: what failed, the
+//! path involved and every error in the chain, with a button to copy it all.
+
+use std::path::{Path, PathBuf};
+
+use iced::widget::{button, column, container, horizontal_space, opaque, row, scrollable, text};
+use iced::{Color, Element, Length};
+
+use crate::app::Message;
+use crate::ui::metrics;
+
+/// Contents of the error dialog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DialogState {
+    /// What failed, e.g. "Failed to save project".
+    pub title: String,
+    /// The file or directory involved, if any.
+    pub path: Option<PathBuf>,
+    /// The error, then each error that caused it.
+    pub chain: Vec<String>,
+}
+
+impl DialogState {
+    /// Describe `error` and the errors behind it.
+    pub fn error(title: impl Into<String>, path: Option<&Path>, error: &dyn std::error::Error) -> Self {
+        let mut chain = vec![error.to_string()];
+        let mut source = error.source();
+        while let Some(cause) = source {
+            let message = cause.to_string();
+            // Wrapping errors often repeat their cause; don't list it twice
+            if !chain.last().is_some_and(|last| last.ends_with(&message)) {
+                chain.push(message);
+            }
+            source = cause.source();
+        }
+        Self {
+            title: title.into(),
+            path: path.map(Path::to_path_buf),
+            chain,
+        }
+    }
+
+    /// The dialog contents as plain text, for the clipboard.
+    pub fn details(&self) -> String {
+        let mut details = self.title.clone();
+        if let Some(path) = &self.path {
+            details.push_str(&format!("\nPath: {}", path.display()));
+        }
+        for (index, message) in self.chain.iter().enumerate() {
+            match index {
+                0 => details.push_str(&format!("\n\n{}", message)),
+                _ => details.push_str(&format!("\nCaused by: {}", message)),
+            }
+        }
+        details
+    }
+}
+
+/// The error dialog component.
+pub struct ErrorDialog;
+
+impl ErrorDialog {
+    /// Render the dialog centered over a dimmed backdrop that blocks input
+    /// to the editor below.
+    pub fn view(dialog: &DialogState) -> Element<'_, Message> {
+        let mut body = column![].spacing(6);
+        if let Some(path) = &dialog.path {
+            body = body.push(
+                text(format!("Path: {}", path.display()))
+                    .size(metrics::text_md())
+                    .color(Color::from_rgb(0.5, 0.5, 0.5)),
+            );
+        }
+        for (index, message) in dialog.chain.iter().enumerate() {
+            body = body.push(match index {
+                0 => text(message).size(metrics::text_lg()),
+                _ => text(format!("Caused by: {}", message)).size(metrics::text_md()),
+            });
+        }
+
+        let content = column![
+            text(&dialog.title).size(metrics::heading()).color(Color::from_rgb(0.9, 0.3, 0.3)),
+            scrollable(body).height(Length::Shrink),
+            row![
+                horizontal_space(),
+                button(text("Copy details").size(metrics::text_md()))
+                    .on_press(Message::CopyDialogDetails)
+                    .style(button::secondary)
+                    .padding(metrics::pad_xy(4.0, 10.0)),
+                button(text("Close").size(metrics::text_md()))
+                    .on_press(Message::DismissDialog)
+                    .padding(metrics::pad_xy(4.0, 10.0)),
+            ]
+            .spacing(8),
+        ]
+        .spacing(12);
+
+        let card = container(content)
+            .width(Length::Fixed(metrics::scaled(520.0)))
+            .max_height(420.0)
+            .padding(metrics::scaled(16.0))
+            .style(|theme: &iced::Theme| container::Style {
+                background: Some(theme.extended_palette().background.base.color.into()),
+                border: iced::Border {
+                    color: theme.extended_palette().background.strong.color,
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                ..Default::default()
+            });
+
+        opaque(
+            container(card)
+                .center(Length::Fill)
+                .style(|_theme| container::Style {
+                    background: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.5).into()),
+                    ..Default::default()
+                }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dialog_lists_error_chain_without_repeats() {
+        let io = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "access denied");
+        let error = crate::model::ProjectError::ConfigRead(io);
+        let dialog = DialogState::error("Failed to save project", Some(Path::new("/p")), &error);
+
+        assert_eq!(dialog.chain, ["Failed to read config file: access denied"]);
+        assert_eq!(
+            dialog.details(),
+            "Failed to save project\nPath: /p\n\nFailed to read config file: access denied"
+        );
+
+        let wrapped = crate::cli::CliError::Project(crate::model::ProjectError::ForeignFile(PathBuf::from("view.rs")));
+        let dialog = DialogState::error("Export failed", None, &wrapped);
+        assert_eq!(dialog.chain.len(), 1);
+
+        #[derive(Debug, thiserror::Error)]
+        #[error("Could not open layout")]
+        struct Outer(#[source] std::io::Error);
+        let outer = Outer(std::io::Error::other("disk on fire"));
+        let dialog = DialogState::error("Failed to open project", None, &outer);
+        assert_eq!(dialog.chain, ["Could not open layout", "disk on fire"]);
+        assert!(dialog.details().ends_with("Caused by: disk on fire"));
+    }
+}
@@ -0,0 +1,108 @@
+//! Undo history panel.
+//!
+//! Lists recorded actions newest-first with a marker on the current state.
+//! Undone actions stay listed, dimmed, until a new edit replaces them.
+//! Clicking an entry jumps straight to the state after that action.
+//! The header holds the history limit and an estimate of the memory used.
+
+use iced::widget::{button, column, container, horizontal_space, row, scrollable, text, text_input, Column};
+use iced::{Element, Length};
+
+use crate::app::Message;
+use crate::model::History;
+use crate::ui::metrics;
+
+/// Height of the panel below the inspector.
+pub const PANEL_HEIGHT: f32 = 200.0;
+
+/// The undo history panel.
+pub struct HistoryPanel;
+
+impl HistoryPanel {
+    /// Render the history timeline, newest entry first.
+    ///
+    /// `limit_input` is the text of the history limit field, applied when
+    /// it is submitted.
+    pub fn view<'a>(history: &'a History, limit_input: &'a str) -> Element<'a, Message> {
+        let position = history.position();
+        let labels: Vec<&str> = history.labels().collect();
+
+        let rows = (0..=labels.len()).rev().map(|index| {
+            let label = match index {
+                0 => "Initial state",
+                _ => labels[index - 1],
+            };
+            let current = index == position;
+            let undone = index > position;
+
+            button(
+                row![
+                    text(if current { "▶" } else { "" }).size(metrics::text_sm()).width(Length::Fixed(metrics::scaled(12.0))),
+                    text(label).size(metrics::text_md()),
+                ]
+                .spacing(4),
+            )
+            .on_press_maybe((!current).then_some(Message::JumpToHistory(index)))
+            .width(Length::Fill)
+            .padding(metrics::pad_sm())
+            .style(move |theme, status| {
+                let mut style = if current {
+                    button::primary(theme, button::Status::Active)
+                } else {
+                    button::text(theme, status)
+                };
+                if undone {
+                    style.text_color = style.text_color.scale_alpha(0.5);
+                }
+                style
+            })
+            .into()
+        });
+
+        let header = row![
+            text(format!("History ({})", labels.len())).size(metrics::text_lg()),
+            horizontal_space(),
+            text(format_bytes(history.memory_estimate()))
+                .size(metrics::text_sm())
+                .color(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+        ]
+        .align_y(iced::Alignment::Center);
+        let limit = row![
+            text("Limit").size(metrics::text_md()),
+            text_input("50", limit_input)
+                .on_input(Message::HistoryLimitInput)
+                .on_submit(Message::CommitHistoryLimit)
+                .size(metrics::text_md())
+                .width(Length::Fixed(metrics::scaled(60.0))),
+            text("steps").size(metrics::text_md()),
+        ]
+        .spacing(6)
+        .align_y(iced::Alignment::Center);
+
+        container(
+            column![
+                header,
+                limit,
+                scrollable(Column::with_children(rows).spacing(1)).height(Length::Fill),
+            ]
+            .spacing(6),
+        )
+        .width(Length::Fixed(metrics::scaled(250.0)))
+        .height(Length::Fixed(PANEL_HEIGHT))
+        .padding(metrics::pad_panel())
+        .into()
+    }
+}
+
+/// Format a byte count as an approximate size, e.g. "~1.2 MB".
+fn format_bytes(bytes: usize) -> String {
+    const KB: f64 = 1024.0;
+    let bytes = bytes as f64;
+    if bytes < KB {
+        format!("~{} B", bytes)
+    } else if bytes < KB * KB {
+        format!("~{:.1} KB", bytes / KB)
+    } else {
+        format!("~{:.1} MB", bytes / (KB * KB))
+    }
+}
@@ -2,109 +2,21 @@
 //!
 //! Displays and allows editing of properties for the selected component.
 
-use iced::widget::{button, column, container, row, scrollable, text, text_input, Column};
+use iced::widget::{
+    button, checkbox, column, container, pick_list, row, scrollable, text, text_input, Column,
+};
 use iced::{Element, Length};
+use std::collections::HashMap;
 
 use crate::app::Message;
 use crate::model::{
     layout::{AlignmentSpec, LengthSpec, WidgetType},
-    ComponentId, LayoutNode,
+    ComponentId, LayoutNode, Project, SampleData, SampleValue,
 };
+use crate::ui::metrics;
+use crate::ui::palette::WidgetKind;
 
-/// Predefined color palette for text styling.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ColorChoice {
-    Default,
-    White,
-    Black,
-    Red,
-    Green,
-    Blue,
-    Yellow,
-    Orange,
-    Purple,
-    Gray,
-}
-
-impl ColorChoice {
-    /// All available color choices.
-    pub const ALL: [ColorChoice; 10] = [
-        ColorChoice::Default,
-        ColorChoice::White,
-        ColorChoice::Black,
-        ColorChoice::Red,
-        ColorChoice::Green,
-        ColorChoice::Blue,
-        ColorChoice::Yellow,
-        ColorChoice::Orange,
-        ColorChoice::Purple,
-        ColorChoice::Gray,
-    ];
-
-    /// Convert to RGBA array (None for default).
-    pub fn to_rgba(self) -> Option<[f32; 4]> {
-        match self {
-            ColorChoice::Default => None,
-            ColorChoice::White => Some([1.0, 1.0, 1.0, 1.0]),
-            ColorChoice::Black => Some([0.0, 0.0, 0.0, 1.0]),
-            ColorChoice::Red => Some([1.0, 0.2, 0.2, 1.0]),
-            ColorChoice::Green => Some([0.2, 0.8, 0.2, 1.0]),
-            ColorChoice::Blue => Some([0.2, 0.5, 1.0, 1.0]),
-            ColorChoice::Yellow => Some([1.0, 0.9, 0.2, 1.0]),
-            ColorChoice::Orange => Some([1.0, 0.6, 0.2, 1.0]),
-            ColorChoice::Purple => Some([0.7, 0.3, 0.9, 1.0]),
-            ColorChoice::Gray => Some([0.5, 0.5, 0.5, 1.0]),
-        }
-    }
-
-    /// Create from RGBA array.
-    pub fn from_rgba(color: Option<[f32; 4]>) -> Self {
-        match color {
-            None => ColorChoice::Default,
-            Some([r, g, b, _]) => {
-                // Find closest match
-                if (r - 1.0).abs() < 0.1 && (g - 1.0).abs() < 0.1 && (b - 1.0).abs() < 0.1 {
-                    ColorChoice::White
-                } else if r < 0.1 && g < 0.1 && b < 0.1 {
-                    ColorChoice::Black
-                } else if r > 0.8 && g < 0.4 && b < 0.4 {
-                    ColorChoice::Red
-                } else if r < 0.4 && g > 0.6 && b < 0.4 {
-                    ColorChoice::Green
-                } else if r < 0.4 && g < 0.6 && b > 0.8 {
-                    ColorChoice::Blue
-                } else if r > 0.8 && g > 0.8 && b < 0.4 {
-                    ColorChoice::Yellow
-                } else if r > 0.8 && g > 0.4 && g < 0.8 && b < 0.4 {
-                    ColorChoice::Orange
-                } else if r > 0.5 && g < 0.5 && b > 0.8 {
-                    ColorChoice::Purple
-                } else if (r - 0.5).abs() < 0.1 && (g - 0.5).abs() < 0.1 && (b - 0.5).abs() < 0.1 {
-                    ColorChoice::Gray
-                } else {
-                    ColorChoice::Default
-                }
-            }
-        }
-    }
-}
-
-impl std::fmt::Display for ColorChoice {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ColorChoice::Default => write!(f, "Default"),
-            ColorChoice::White => write!(f, "White"),
-            ColorChoice::Black => write!(f, "Black"),
-            ColorChoice::Red => write!(f, "Red"),
-            ColorChoice::Green => write!(f, "Green"),
-            ColorChoice::Blue => write!(f, "Blue"),
-            ColorChoice::Yellow => write!(f, "Yellow"),
-            ColorChoice::Orange => write!(f, "Orange"),
-            ColorChoice::Purple => write!(f, "Purple"),
-            ColorChoice::Gray => write!(f, "Gray"),
-        }
-    }
-}
+pub use crate::codegen::colors::ColorChoice;
 
 /// Length variant for the picker (simplified for UI).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -116,13 +28,6 @@ pub enum LengthVariant {
 }
 
 impl LengthVariant {
-    pub const ALL: [LengthVariant; 4] = [
-        LengthVariant::Fill,
-        LengthVariant::Shrink,
-        LengthVariant::Fixed,
-        LengthVariant::FillPortion,
-    ];
-
     pub fn from_spec(spec: LengthSpec) -> Self {
         match spec {
             LengthSpec::Fill => LengthVariant::Fill,
@@ -144,6 +49,50 @@ impl std::fmt::Display for LengthVariant {
     }
 }
 
+/// Numeric inspector field whose typed text can differ from the applied value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DraftField {
+    /// A slider's minimum.
+    Min,
+    /// A slider's maximum.
+    Max,
+    /// A slider's preview sample value.
+    Sample,
+}
+
+/// Text typed into numeric inspector fields, kept so that partial entries
+/// like "1." or "-" aren't replaced by the parsed value on every keystroke.
+///
+/// Drafts belong to the layout revision they were typed at; any other edit,
+/// undo included, drops them so the fields show applied values again.
+#[derive(Debug, Default)]
+pub struct Drafts {
+    revision: Option<u64>,
+    texts: HashMap<(ComponentId, DraftField), String>,
+}
+
+impl Drafts {
+    /// Record `text` for a field. `before` and `after` are the layout
+    /// revisions around applying it.
+    pub fn set(&mut self, before: u64, after: u64, id: ComponentId, field: DraftField, text: String) {
+        if self.revision != Some(before) {
+            self.texts.clear();
+        }
+        self.revision = Some(after);
+        self.texts.insert((id, field), text);
+    }
+
+    /// The drafts typed at `revision`, if they are still current.
+    pub fn current(&self, revision: u64) -> Option<&Self> {
+        (self.revision == Some(revision)).then_some(self)
+    }
+
+    /// Typed text for a field, if any.
+    pub fn get(&self, id: ComponentId, field: DraftField) -> Option<&str> {
+        self.texts.get(&(id, field)).map(String::as_str)
+    }
+}
+
 /// The property inspector component.
 pub struct Inspector;
 
@@ -152,43 +101,207 @@ impl Inspector {
     pub fn view<'a>(
         selected_node: Option<&'a LayoutNode>,
         _selected_id: Option<ComponentId>,
+        sample_data: Option<&'a SampleData>,
+        palette_hint: Option<WidgetKind>,
+        root_selected: bool,
+        editable: bool,
+        drafts: Option<&Drafts>,
     ) -> Element<'a, Message> {
+        let draft = |node: &LayoutNode, field| drafts.and_then(|d| d.get(node.id, field)).map(str::to_owned);
         let content: Element<'a, Message> = match selected_node {
-            Some(node) => Self::render_properties(node),
-            None => Self::render_empty(),
+            Some(node) if editable => Self::render_properties(node, sample_data, root_selected, &draft),
+            Some(node) => Self::render_read_only(node, sample_data, draft(node, DraftField::Sample)),
+            None => Self::render_empty(palette_hint),
         };
 
         container(scrollable(content).height(Length::Fill))
-            .width(Length::Fixed(250.0))
+            .width(Length::Fixed(metrics::scaled(250.0)))
             .height(Length::Fill)
-            .padding(10)
+            .padding(metrics::pad_panel())
             .into()
     }
 
     /// Render the empty state when nothing is selected.
-    fn render_empty<'a>() -> Element<'a, Message> {
-        text("Select a component to edit its properties.")
-            .size(13)
-            .color(iced::Color::from_rgb(0.5, 0.5, 0.5))
-            .into()
+    ///
+    /// Describes the hovered or armed palette item, if any.
+    fn render_empty<'a>(palette_hint: Option<WidgetKind>) -> Element<'a, Message> {
+        let placeholder = text("Select a component to edit its properties.")
+            .size(metrics::text_lg())
+            .color(iced::Color::from_rgb(0.5, 0.5, 0.5));
+
+        match palette_hint {
+            Some(kind) => column![
+                text(kind.name()).size(metrics::heading()),
+                text(kind.description()).size(metrics::text_lg()),
+                placeholder,
+            ]
+            .spacing(8)
+            .into(),
+            None => placeholder.into(),
+        }
     }
 
     /// Render properties for the selected node.
-    fn render_properties<'a>(node: &'a LayoutNode) -> Element<'a, Message> {
-        let header = text(Self::widget_type_name(&node.widget))
-            .size(16);
+    fn render_properties<'a>(
+        node: &'a LayoutNode,
+        sample_data: Option<&'a SampleData>,
+        is_root: bool,
+        draft: &dyn Fn(&LayoutNode, DraftField) -> Option<String>,
+    ) -> Element<'a, Message> {
+        let title = text(Self::widget_type_name(&node.widget))
+            .size(metrics::heading())
+            .width(Length::Fill);
+        let duplicate = button(text("Duplicate").size(metrics::text_sm()))
+            .on_press(Message::DuplicateSelected)
+            .padding(metrics::pad())
+            .style(button::secondary);
+        let header = row![title, duplicate].align_y(iced::Alignment::Center);
+
+        let id = node.id;
+        let targets = Project::conversion_targets(&node.widget, is_root);
+        let change_type = if is_root {
+            // The root may become any container; children are kept or wrapped
+            Some(
+                pick_list(targets, None::<WidgetKind>, Message::ChangeRootType)
+                    .placeholder("Change root type…")
+                    .text_size(metrics::text_md()),
+            )
+        } else {
+            (!targets.is_empty()).then(|| {
+                pick_list(targets, None::<WidgetKind>, move |kind| Message::ConvertWidget(id, kind))
+                    .placeholder("Change type…")
+                    .text_size(metrics::text_md())
+            })
+        };
 
         let id_text = text(format!("ID: {}...", &node.id.to_string()[..8]))
-            .size(11)
+            .size(metrics::text_sm())
             .color(iced::Color::from_rgb(0.5, 0.5, 0.5));
 
-        let properties = Self::render_widget_properties(node);
+        let properties = Self::render_widget_properties(node, draft);
+
+        let name = Self::labeled_input_owned(
+            "Name",
+            node.name.clone().unwrap_or_default(),
+            move |s| Message::UpdateNodeName(id, s),
+        );
+
+        let visibility = row![
+            checkbox("Hidden", node.hidden)
+                .on_toggle(move |_| Message::ToggleNodeHidden(id))
+                .text_size(metrics::text_md()),
+            checkbox("Exclude from export", node.exclude_from_export)
+                .on_toggle(move |exclude| Message::SetExcludeFromExport(id, exclude))
+                .text_size(metrics::text_md()),
+        ]
+        .spacing(12);
 
-        column![header, id_text, properties]
+        column![header, id_text, name, visibility]
+            .push_maybe(change_type)
+            .push(properties)
+            .push_maybe(Self::render_sample_props(node, sample_data, draft(node, DraftField::Sample)))
             .spacing(15)
             .into()
     }
 
+    /// Render a selected node in preview mode, where only sample values can
+    /// be changed.
+    fn render_read_only<'a>(
+        node: &'a LayoutNode,
+        sample_data: Option<&'a SampleData>,
+        sample_draft: Option<String>,
+    ) -> Element<'a, Message> {
+        column![
+            text(Self::widget_type_name(&node.widget)).size(metrics::heading()),
+            text("Properties are read-only in preview mode. Switch to Design (Ctrl+P) to edit them.")
+                .size(metrics::text_md())
+                .color(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+        ]
+        .push_maybe(Self::render_sample_props(node, sample_data, sample_draft))
+        .spacing(15)
+        .into()
+    }
+
+    /// Render the preview sample value editor for a bound widget.
+    ///
+    /// `draft` is the text typed into a slider's value, shown while it still
+    /// parses to the stored sample.
+    fn render_sample_props<'a>(
+        node: &'a LayoutNode,
+        sample_data: Option<&'a SampleData>,
+        draft: Option<String>,
+    ) -> Option<Element<'a, Message>> {
+        let binding = match &node.widget {
+            WidgetType::TextInput { value_binding, .. } => value_binding,
+            WidgetType::Checkbox { checked_binding, .. } => checked_binding,
+            WidgetType::Slider { value_binding, .. } => value_binding,
+            WidgetType::PickList { selected_binding, .. } => selected_binding,
+            _ => return None,
+        };
+        if binding.is_empty() {
+            return None;
+        }
+
+        let sample = sample_data.and_then(|data| data.get(binding));
+        let key = binding.clone();
+
+        let editor: Element<'a, Message> = match &node.widget {
+            WidgetType::Checkbox { .. } => {
+                let checked = sample.and_then(SampleValue::as_bool).unwrap_or(false);
+                checkbox("Checked", checked)
+                    .on_toggle(move |b| {
+                        Message::UpdateSampleValue(key.clone(), Some(SampleValue::Bool(b)))
+                    })
+                    .size(metrics::title())
+                    .text_size(metrics::text_lg())
+                    .into()
+            }
+            WidgetType::PickList { options, .. } => pick_list(
+                options.as_slice(),
+                sample.map(SampleValue::as_text),
+                move |selected| {
+                    Message::UpdateSampleValue(key.clone(), Some(SampleValue::Text(selected)))
+                },
+            )
+            .placeholder("No sample")
+            .text_size(metrics::text_lg())
+            .into(),
+            WidgetType::Slider { .. } => {
+                // Clearing or undoing elsewhere makes the draft stale
+                let parsed = |s: &str| (!s.trim().is_empty()).then(|| SampleValue::parse(s));
+                let shown = draft
+                    .filter(|d| parsed(d).as_ref() == sample)
+                    .unwrap_or_else(|| sample.map(SampleValue::as_text).unwrap_or_default());
+                let id = node.id;
+                Self::labeled_input_owned("Value", shown, move |s| {
+                    Message::DraftInput(id, DraftField::Sample, s)
+                })
+                .into()
+            }
+            _ => Self::labeled_input_owned(
+                "Value",
+                sample.map(SampleValue::as_text).unwrap_or_default(),
+                move |s| {
+                    let value = (!s.is_empty()).then_some(SampleValue::Text(s));
+                    Message::UpdateSampleValue(key.clone(), value)
+                },
+            )
+            .into(),
+        };
+
+        let clear = button(text("Clear").size(metrics::text_sm()))
+            .on_press_maybe(
+                sample.map(|_| Message::UpdateSampleValue(binding.clone(), None)),
+            )
+            .padding(metrics::pad_sm());
+
+        Some(
+            column![Self::section_header("Preview Sample"), editor, clear]
+                .spacing(8)
+                .into(),
+        )
+    }
+
     /// Get the display name for a widget type.
     fn widget_type_name(widget: &WidgetType) -> &'static str {
         match widget {
@@ -204,11 +317,15 @@ impl Inspector {
             WidgetType::Slider { .. } => "Slider",
             WidgetType::PickList { .. } => "PickList",
             WidgetType::Space { .. } => "Space",
+            WidgetType::ComponentRef { .. } => "Component",
         }
     }
 
     /// Render properties specific to the widget type.
-    fn render_widget_properties<'a>(node: &'a LayoutNode) -> Element<'a, Message> {
+    fn render_widget_properties<'a>(
+        node: &'a LayoutNode,
+        draft: &dyn Fn(&LayoutNode, DraftField) -> Option<String>,
+    ) -> Element<'a, Message> {
         match &node.widget {
             WidgetType::Column { attrs, children } | WidgetType::Row { attrs, children } => {
                 Self::render_container_props(node.id, attrs, Some(children.len()))
@@ -235,7 +352,8 @@ impl Inspector {
                 Self::render_checkbox_props(node.id, label, checked_binding, message_stub)
             }
             WidgetType::Slider { min, max, value_binding, message_stub, .. } => {
-                Self::render_slider_props(node.id, *min, *max, value_binding, message_stub)
+                let typed = (draft(node, DraftField::Min), draft(node, DraftField::Max));
+                Self::render_slider_props(node.id, (*min, *max), typed, value_binding, message_stub)
             }
             WidgetType::PickList { options, selected_binding, message_stub, .. } => {
                 Self::render_picklist_props(node.id, options, selected_binding, message_stub)
@@ -243,6 +361,9 @@ impl Inspector {
             WidgetType::Space { width, height } => {
                 Self::render_space_props(*width, *height)
             }
+            WidgetType::ComponentRef { file } => {
+                Self::render_component_props(file)
+            }
         }
     }
 
@@ -315,13 +436,15 @@ impl Inspector {
         ]
         .spacing(2);
 
-        // Show value input for Fixed and FillPortion
+        // Show value input for Fixed and FillPortion. Sizes must be positive,
+        // so zero and negative entries are ignored rather than applied.
         let value_input: Element<'static, Message> = match current_variant {
             LengthVariant::Fixed => {
                 let val_str = current_value.map(|v| format!("{}", v)).unwrap_or_default();
                 text_input("100", &val_str)
                     .on_input(move |s| {
                         s.parse::<f32>().ok()
+                            .filter(|v| v.is_finite() && *v > 0.0)
                             .map(|v| {
                                 if is_width {
                                     Message::UpdateWidth(id, LengthSpec::Fixed(v))
@@ -331,8 +454,8 @@ impl Inspector {
                             })
                             .unwrap_or(Message::Noop)
                     })
-                    .size(12)
-                    .width(Length::Fixed(60.0))
+                    .size(metrics::text_md())
+                    .width(Length::Fixed(metrics::scaled(60.0)))
                     .into()
             }
             LengthVariant::FillPortion => {
@@ -340,6 +463,7 @@ impl Inspector {
                 text_input("1", &val_str)
                     .on_input(move |s| {
                         s.parse::<u16>().ok()
+                            .filter(|v| *v > 0)
                             .map(|v| {
                                 if is_width {
                                     Message::UpdateWidth(id, LengthSpec::FillPortion(v))
@@ -349,15 +473,15 @@ impl Inspector {
                             })
                             .unwrap_or(Message::Noop)
                     })
-                    .size(12)
-                    .width(Length::Fixed(40.0))
+                    .size(metrics::text_md())
+                    .width(Length::Fixed(metrics::scaled(40.0)))
                     .into()
             }
             _ => text("").into(),
         };
 
         column![
-            text(label).size(11).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+            text(label).size(metrics::text_sm()).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
             row![variant_buttons, value_input].spacing(4),
         ]
         .spacing(2)
@@ -392,9 +516,9 @@ impl Inspector {
             Message::UpdateHeight(id, spec)
         };
         
-        button(text(label).size(10))
+        button(text(label).size(metrics::text_xs()))
             .on_press(msg)
-            .padding(3)
+            .padding(metrics::scaled(3.0))
             .style(move |_theme, _status| button::Style {
                 background: Some(iced::Background::Color(bg_color)),
                 text_color: iced::Color::WHITE,
@@ -422,7 +546,7 @@ impl Inspector {
         .spacing(2);
 
         column![
-            text(label).size(11).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+            text(label).size(metrics::text_sm()).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
             buttons,
         ]
         .spacing(2)
@@ -449,9 +573,9 @@ impl Inspector {
             Message::UpdateAlignY(id, alignment)
         };
         
-        button(text(label).size(10))
+        button(text(label).size(metrics::text_xs()))
             .on_press(msg)
-            .padding(3)
+            .padding(metrics::scaled(3.0))
             .style(move |_theme, _status| button::Style {
                 background: Some(iced::Background::Color(bg_color)),
                 text_color: iced::Color::WHITE,
@@ -481,7 +605,7 @@ impl Inspector {
             Self::numeric_input_owned("Font Size", font_size_str, move |s| {
                 s.parse::<f32>().ok().map(|v| Message::UpdateFontSize(id, v)).unwrap_or(Message::Noop)
             }),
-            Self::property_row_static("Alignment", Self::alignment_display(attrs.horizontal_alignment)),
+            Self::property_row_static("Alignment", Self::alignment_display(attrs.align_x)),
             Self::color_picker("Color", id, current_color),
         ]
         .spacing(8)
@@ -513,7 +637,7 @@ impl Inspector {
         .spacing(2);
 
         column![
-            text(label).size(11).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+            text(label).size(metrics::text_sm()).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
             buttons,
             buttons2,
         ]
@@ -549,11 +673,11 @@ impl Inspector {
             ""
         };
         
-        button(text(label_text).size(8))
+        button(text(label_text).size(metrics::scaled(8.0)))
             .on_press(Message::UpdateTextColor(id, color.to_rgba()))
-            .padding(2)
-            .width(Length::Fixed(22.0))
-            .height(Length::Fixed(22.0))
+            .padding(metrics::scaled(2.0))
+            .width(Length::Fixed(metrics::scaled(22.0)))
+            .height(Length::Fixed(metrics::scaled(22.0)))
             .style(move |_theme, _status| button::Style {
                 background: Some(iced::Background::Color(display_color)),
                 text_color: iced::Color::WHITE,
@@ -573,10 +697,10 @@ impl Inspector {
         F: Fn(String) -> Message + 'static,
     {
         column![
-            text(label).size(11).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+            text(label).size(metrics::text_sm()).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
             text_input("", &value)
                 .on_input(on_change)
-                .size(13),
+                .size(metrics::text_lg()),
         ]
         .spacing(2)
     }
@@ -634,28 +758,61 @@ impl Inspector {
     }
 
     /// Render slider properties.
+    ///
+    /// `range` holds the min and max text to show, typed or applied. An
+    /// inverted or empty range is never applied; the typed text stays until
+    /// the entry makes sense again.
+    ///
+    /// `typed` holds the text typed into the Min and Max fields, shown in
+    /// place of the applied bounds along with why it isn't applied yet.
     fn render_slider_props<'a>(
         id: ComponentId,
-        min: f32,
-        max: f32,
+        (min, max): (f32, f32),
+        typed: (Option<String>, Option<String>),
         value_binding: &'a str,
         message_stub: &'a str,
     ) -> Element<'a, Message> {
-        let min_str = format!("{}", min);
-        let max_str = format!("{}", max);
-        
+        let problem = Self::slider_range_problem((min, max), (typed.0.as_deref(), typed.1.as_deref()));
+        let problem = problem.map(|problem| {
+            text(problem)
+                .size(metrics::text_sm())
+                .color(iced::Color::from_rgb(0.9, 0.3, 0.3))
+        });
+        let (min_text, max_text) = (
+            typed.0.unwrap_or_else(|| min.to_string()),
+            typed.1.unwrap_or_else(|| max.to_string()),
+        );
         column![
             Self::section_header("Range"),
-            Self::property_row_static("Min", &min_str),
-            Self::property_row_static("Max", &max_str),
-            Self::section_header("Bindings"),
-            Self::labeled_input("Value Binding", value_binding, move |s| Message::UpdateBinding(id, s.clone())),
-            Self::labeled_input("Message", message_stub, move |s| Message::UpdateMessageStub(id, s)),
+            Self::labeled_input_owned("Min", min_text, move |s| Message::DraftInput(id, DraftField::Min, s)),
+            Self::labeled_input_owned("Max", max_text, move |s| Message::DraftInput(id, DraftField::Max, s)),
         ]
+        .push_maybe(problem)
+        .push(Self::section_header("Bindings"))
+        .push(Self::labeled_input("Value Binding", value_binding, move |s| Message::UpdateBinding(id, s.clone())))
+        .push(Self::labeled_input("Message", message_stub, move |s| Message::UpdateMessageStub(id, s)))
         .spacing(8)
         .into()
     }
 
+    /// Why the text typed into a slider's bounds isn't applied, if it isn't.
+    ///
+    /// The applied `min` and `max` stay in effect until the typed bounds are
+    /// numbers with Min below Max.
+    fn slider_range_problem((min, max): (f32, f32), (min_text, max_text): (Option<&str>, Option<&str>)) -> Option<String> {
+        let parse = |text: &str| text.trim().parse::<f32>().ok().filter(|v| v.is_finite());
+        let typed_min = min_text.map(parse);
+        let typed_max = max_text.map(parse);
+        if typed_min == Some(None) {
+            return Some(format!("Min isn't a number yet; still using {}", min));
+        }
+        if typed_max == Some(None) {
+            return Some(format!("Max isn't a number yet; still using {}", max));
+        }
+        let (low, high) = (typed_min.flatten().unwrap_or(min), typed_max.flatten().unwrap_or(max));
+        (low >= high).then(|| format!("Min must be below Max; still using {} to {}", min, max))
+    }
+
     /// Render picklist properties.
     fn render_picklist_props<'a>(
         id: ComponentId,
@@ -687,11 +844,24 @@ impl Inspector {
         .into()
     }
 
+    /// Render component reference properties.
+    fn render_component_props<'a>(file: &std::path::Path) -> Element<'a, Message> {
+        column![
+            Self::section_header("Component"),
+            Self::property_row_static("Layout File", &file.display().to_string()),
+            text("Shown read-only; edit its layout file to change it.")
+                .size(metrics::text_sm())
+                .color(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+        ]
+        .spacing(8)
+        .into()
+    }
+
     /// Render a section header.
     fn section_header<'a>(title: &'static str) -> Column<'a, Message> {
         column![
             text(title)
-                .size(12)
+                .size(metrics::text_md())
                 .color(iced::Color::from_rgb(0.4, 0.6, 0.9)),
         ]
     }
@@ -699,8 +869,8 @@ impl Inspector {
     /// Render a property row with owned value.
     fn property_row_owned(label: &'static str, value: String) -> Column<'static, Message> {
         column![
-            text(label).size(11).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
-            text(value).size(13),
+            text(label).size(metrics::text_sm()).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+            text(value).size(metrics::text_lg()),
         ]
         .spacing(2)
     }
@@ -708,8 +878,8 @@ impl Inspector {
     /// Render a property row with static value.
     fn property_row_static<'a>(label: &'static str, value: &str) -> Column<'a, Message> {
         column![
-            text(label).size(11).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
-            text(value.to_string()).size(13),
+            text(label).size(metrics::text_sm()).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+            text(value.to_string()).size(metrics::text_lg()),
         ]
         .spacing(2)
     }
@@ -720,10 +890,10 @@ impl Inspector {
         F: Fn(String) -> Message + 'static,
     {
         column![
-            text(label).size(11).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+            text(label).size(metrics::text_sm()).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
             text_input("", &value)
                 .on_input(on_change)
-                .size(13),
+                .size(metrics::text_lg()),
         ]
         .spacing(2)
     }
@@ -735,10 +905,10 @@ impl Inspector {
         F: Fn(String) -> Message + 'a,
     {
         column![
-            text(label).size(11).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+            text(label).size(metrics::text_sm()).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
             text_input("", value)
                 .on_input(on_change)
-                .size(13),
+                .size(metrics::text_lg()),
         ]
         .spacing(2)
     }
@@ -749,10 +919,10 @@ impl Inspector {
         F: Fn(String) -> Message + 'a,
     {
         column![
-            text(label).size(11).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+            text(label).size(metrics::text_sm()).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
             text_input("", value)
                 .on_input(on_change)
-                .size(13),
+                .size(metrics::text_lg()),
         ]
         .spacing(2)
     }
@@ -776,3 +946,20 @@ impl Inspector {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slider_range_problem_explains_unapplied_bounds() {
+        let problem = |min_text, max_text| Inspector::slider_range_problem((0.0, 10.0), (min_text, max_text));
+
+        assert_eq!(problem(None, None), None);
+        assert_eq!(problem(Some("-100"), None), None);
+        assert_eq!(problem(Some("-"), None).as_deref(), Some("Min isn't a number yet; still using 0"));
+        assert_eq!(problem(None, Some("1e")).as_deref(), Some("Max isn't a number yet; still using 10"));
+        assert_eq!(problem(Some("20"), None).as_deref(), Some("Min must be below Max; still using 0 to 10"));
+        assert_eq!(problem(Some("-5"), Some("-10")).as_deref(), Some("Min must be below Max; still using 0 to 10"));
+    }
+}
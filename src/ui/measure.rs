@@ -0,0 +1,233 @@
+//! Padding and spacing measurement overlay.
+//!
+//! Wraps a container widget and draws translucent bands over its padding
+//! and the gaps between its children, like browser devtools. The wrapper
+//! reuses the content's layout unchanged, so measuring never moves anything.
+
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::renderer::{self, Quad};
+use iced::advanced::widget::{tree, Operation, Tree, Widget};
+use iced::advanced::{overlay, Clipboard, Shell};
+use iced::event::{self, Event};
+use iced::mouse;
+use iced::{Color, Element, Length, Padding, Rectangle, Size, Vector};
+
+/// Color of the padding bands.
+const PADDING_COLOR: Color = Color::from_rgba(0.3, 0.8, 0.4, 0.3);
+
+/// Color of the spacing gaps between children.
+const SPACING_COLOR: Color = Color::from_rgba(1.0, 0.6, 0.2, 0.35);
+
+/// Direction in which a container lays out its children.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    Vertical,
+    Horizontal,
+}
+
+/// A wrapper that overlays padding and spacing bands on its content.
+pub struct MeasureOverlay<'a, Message, Theme, Renderer> {
+    content: Element<'a, Message, Theme, Renderer>,
+    padding: Padding,
+    axis: Option<Axis>,
+}
+
+impl<'a, Message, Theme, Renderer> MeasureOverlay<'a, Message, Theme, Renderer> {
+    /// Wrap `content`, measuring the given padding.
+    pub fn new(content: impl Into<Element<'a, Message, Theme, Renderer>>, padding: Padding) -> Self {
+        Self {
+            content: content.into(),
+            padding,
+            axis: None,
+        }
+    }
+
+    /// Also mark the gaps between children laid out along `axis`.
+    pub fn spacing_axis(mut self, axis: Axis) -> Self {
+        self.axis = Some(axis);
+        self
+    }
+}
+
+/// Compute the four padding bands inside `bounds`.
+fn padding_bands(bounds: Rectangle, padding: Padding) -> [Rectangle; 4] {
+    let inner_height = (bounds.height - padding.top - padding.bottom).max(0.0);
+    [
+        Rectangle { height: padding.top, ..bounds },
+        Rectangle {
+            y: bounds.y + bounds.height - padding.bottom,
+            height: padding.bottom,
+            ..bounds
+        },
+        Rectangle {
+            y: bounds.y + padding.top,
+            width: padding.left,
+            height: inner_height,
+            ..bounds
+        },
+        Rectangle {
+            x: bounds.x + bounds.width - padding.right,
+            y: bounds.y + padding.top,
+            width: padding.right,
+            height: inner_height,
+        },
+    ]
+}
+
+/// Compute the gap between two consecutive children along `axis`.
+fn gap_between(previous: Rectangle, next: Rectangle, bounds: Rectangle, padding: Padding, axis: Axis) -> Rectangle {
+    match axis {
+        Axis::Vertical => Rectangle {
+            x: bounds.x + padding.left,
+            y: previous.y + previous.height,
+            width: (bounds.width - padding.left - padding.right).max(0.0),
+            height: (next.y - previous.y - previous.height).max(0.0),
+        },
+        Axis::Horizontal => Rectangle {
+            x: previous.x + previous.width,
+            y: bounds.y + padding.top,
+            width: (next.x - previous.x - previous.width).max(0.0),
+            height: (bounds.height - padding.top - padding.bottom).max(0.0),
+        },
+    }
+}
+
+impl<Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+    for MeasureOverlay<'_, Message, Theme, Renderer>
+where
+    Renderer: renderer::Renderer,
+{
+    fn tag(&self) -> tree::Tag {
+        self.content.as_widget().tag()
+    }
+
+    fn state(&self) -> tree::State {
+        self.content.as_widget().state()
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        self.content.as_widget().children()
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        self.content.as_widget().diff(tree);
+    }
+
+    fn size(&self) -> Size<Length> {
+        self.content.as_widget().size()
+    }
+
+    fn size_hint(&self) -> Size<Length> {
+        self.content.as_widget().size_hint()
+    }
+
+    fn layout(&self, tree: &mut Tree, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        self.content.as_widget().layout(tree, renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content
+            .as_widget()
+            .draw(tree, renderer, theme, style, layout, cursor, viewport);
+
+        let bounds = layout.bounds();
+        renderer.with_layer(bounds, |renderer| {
+            let mut band = |rect: Rectangle, color: Color| {
+                if rect.width > 0.0 && rect.height > 0.0 {
+                    renderer.fill_quad(
+                        Quad {
+                            bounds: rect,
+                            ..Quad::default()
+                        },
+                        color,
+                    );
+                }
+            };
+
+            for rect in padding_bands(bounds, self.padding) {
+                band(rect, PADDING_COLOR);
+            }
+
+            if let Some(axis) = self.axis {
+                let children: Vec<Rectangle> = layout.children().map(|c| c.bounds()).collect();
+                for pair in children.windows(2) {
+                    band(gap_between(pair[0], pair[1], bounds, self.padding, axis), SPACING_COLOR);
+                }
+            }
+        });
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        self.content
+            .as_widget()
+            .operate(tree, layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        self.content.as_widget_mut().on_event(
+            tree, event, layout, cursor, renderer, clipboard, shell, viewport,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content
+            .as_widget()
+            .mouse_interaction(tree, layout, cursor, viewport, renderer)
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        self.content
+            .as_widget_mut()
+            .overlay(tree, layout, renderer, translation)
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<MeasureOverlay<'a, Message, Theme, Renderer>>
+    for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a,
+    Theme: 'a,
+    Renderer: renderer::Renderer + 'a,
+{
+    fn from(overlay: MeasureOverlay<'a, Message, Theme, Renderer>) -> Self {
+        Element::new(overlay)
+    }
+}
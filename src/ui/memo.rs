@@ -0,0 +1,308 @@
+//! Reusing rendered elements between frames.
+//!
+//! iced rebuilds the whole element tree on every `view`, which gets slow
+//! for large layouts. A [`MemoCache`] keeps the elements it built, keyed by
+//! node ID, and hands the same element back while the node's key stays the
+//! same. The element comes back wrapped in a [`Memo`], which returns it to
+//! the cache when the frame's element tree is dropped and skips diffing its
+//! widget state when the element is the one the state was built for.
+//!
+//! Cached elements are `'static`, so they must not borrow the layout.
+//! A node rebuilt inside a reused parent is never asked for: the parent's
+//! element still holds the old one, which is why keys must change along
+//! with everything below them.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::renderer;
+use iced::advanced::widget::{tree, Operation, Tree, Widget};
+use iced::advanced::{overlay, Clipboard, Shell};
+use iced::event::{self, Event};
+use iced::mouse;
+use iced::{Element, Length, Rectangle, Size, Vector};
+
+use crate::model::ComponentId;
+
+/// An element built for a node, while it isn't on screen.
+struct Entry<Key, Message, Theme, Renderer> {
+    key: Key,
+    /// Identifies this build, so widget state made for it can be reused.
+    token: u64,
+    /// The element, unless a [`Memo`] currently holds it.
+    element: Option<Element<'static, Message, Theme, Renderer>>,
+}
+
+struct Slots<Key, Message, Theme, Renderer> {
+    entries: HashMap<ComponentId, Entry<Key, Message, Theme, Renderer>>,
+    next_token: u64,
+    /// Elements built since the cache was created.
+    builds: usize,
+}
+
+/// Elements built for nodes in earlier frames.
+pub struct MemoCache<Key, Message, Theme = iced::Theme, Renderer = iced::Renderer>(
+    Rc<RefCell<Slots<Key, Message, Theme, Renderer>>>,
+);
+
+impl<Key, Message, Theme, Renderer> Clone for MemoCache<Key, Message, Theme, Renderer> {
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0))
+    }
+}
+
+impl<Key, Message, Theme, Renderer> Default for MemoCache<Key, Message, Theme, Renderer> {
+    fn default() -> Self {
+        Self(Rc::new(RefCell::new(Slots {
+            entries: HashMap::new(),
+            next_token: 0,
+            builds: 0,
+        })))
+    }
+}
+
+impl<Key, Message, Theme, Renderer> std::fmt::Debug for MemoCache<Key, Message, Theme, Renderer> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let slots = self.0.borrow();
+        f.debug_struct("MemoCache")
+            .field("entries", &slots.entries.len())
+            .field("builds", &slots.builds)
+            .finish()
+    }
+}
+
+impl<Key, Message, Theme, Renderer> MemoCache<Key, Message, Theme, Renderer>
+where
+    Key: PartialEq + 'static,
+    Message: 'static,
+    Theme: 'static,
+    Renderer: renderer::Renderer + 'static,
+{
+    /// Get the element for `id`, reusing the last one built if its key
+    /// was `key`, and building it with `build` otherwise.
+    pub fn get_or_build(
+        &self,
+        id: ComponentId,
+        key: Key,
+        build: impl FnOnce() -> Element<'static, Message, Theme, Renderer>,
+    ) -> Element<'static, Message, Theme, Renderer> {
+        let mut slots = self.0.borrow_mut();
+        if let Some(entry) = slots.entries.get_mut(&id).filter(|entry| entry.key == key) {
+            if let Some(element) = entry.element.take() {
+                let token = entry.token;
+                drop(slots);
+                return self.memo(id, token, element);
+            }
+        }
+
+        // Drop the old element first, so the memos inside it hand their
+        // elements back for `build` to reuse
+        let stale = slots.entries.remove(&id);
+        drop(slots);
+        drop(stale);
+
+        let element = build();
+        let mut slots = self.0.borrow_mut();
+        slots.next_token += 1;
+        slots.builds += 1;
+        let token = slots.next_token;
+        slots.entries.insert(id, Entry { key, token, element: None });
+        drop(slots);
+        self.memo(id, token, element)
+    }
+
+    fn memo(
+        &self,
+        id: ComponentId,
+        token: u64,
+        element: Element<'static, Message, Theme, Renderer>,
+    ) -> Element<'static, Message, Theme, Renderer> {
+        Element::new(Memo {
+            id,
+            token,
+            element: Some(element),
+            cache: self.clone(),
+        })
+    }
+
+    /// Get the number of nodes with a cached element.
+    pub fn len(&self) -> usize {
+        self.0.borrow().entries.len()
+    }
+
+    /// Get the number of elements built so far.
+    pub fn builds(&self) -> usize {
+        self.0.borrow().builds
+    }
+
+    /// Forget the elements of nodes for which `keep` is false.
+    pub fn retain(&self, keep: impl Fn(ComponentId) -> bool) {
+        let mut slots = self.0.borrow_mut();
+        let gone: Vec<ComponentId> = slots.entries.keys().copied().filter(|&id| !keep(id)).collect();
+        let stale: Vec<_> = gone.iter().filter_map(|id| slots.entries.remove(id)).collect();
+        drop(slots);
+        drop(stale);
+    }
+}
+
+/// Widget state of a [`Memo`]: which build the child state was made for.
+struct State {
+    token: u64,
+}
+
+/// A cached element, returned to its [`MemoCache`] when dropped.
+pub struct Memo<Key, Message, Theme, Renderer> {
+    id: ComponentId,
+    token: u64,
+    element: Option<Element<'static, Message, Theme, Renderer>>,
+    cache: MemoCache<Key, Message, Theme, Renderer>,
+}
+
+impl<Key, Message, Theme, Renderer> Memo<Key, Message, Theme, Renderer> {
+    fn content(&self) -> &Element<'static, Message, Theme, Renderer> {
+        self.element.as_ref().expect("memo element is only taken on drop")
+    }
+
+    fn content_mut(&mut self) -> &mut Element<'static, Message, Theme, Renderer> {
+        self.element.as_mut().expect("memo element is only taken on drop")
+    }
+}
+
+impl<Key, Message, Theme, Renderer> Drop for Memo<Key, Message, Theme, Renderer> {
+    fn drop(&mut self) {
+        let Some(element) = self.element.take() else {
+            return;
+        };
+        // Busy only if dropped from inside the cache, which never happens
+        // while it holds the borrow; the element is then simply dropped
+        let Ok(mut slots) = self.cache.0.try_borrow_mut() else {
+            return;
+        };
+        match slots.entries.get_mut(&self.id).filter(|entry| entry.token == self.token) {
+            Some(entry) => entry.element = Some(element),
+            None => {
+                // Rebuilt or forgotten since; drop it outside the borrow
+                drop(slots);
+                drop(element);
+            }
+        }
+    }
+}
+
+impl<Key, Message, Theme, Renderer> Widget<Message, Theme, Renderer> for Memo<Key, Message, Theme, Renderer>
+where
+    Renderer: renderer::Renderer,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State { token: self.token })
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(self.content())]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        let state = tree.state.downcast_mut::<State>();
+        if state.token == self.token && tree.children.len() == 1 {
+            // The child state was made for this very element
+            return;
+        }
+        state.token = self.token;
+        tree.diff_children(std::slice::from_ref(self.content()));
+    }
+
+    fn size(&self) -> Size<Length> {
+        self.content().as_widget().size()
+    }
+
+    fn size_hint(&self) -> Size<Length> {
+        self.content().as_widget().size_hint()
+    }
+
+    fn layout(&self, tree: &mut Tree, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        self.content()
+            .as_widget()
+            .layout(&mut tree.children[0], renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content()
+            .as_widget()
+            .draw(&tree.children[0], renderer, theme, style, layout, cursor, viewport);
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        self.content()
+            .as_widget()
+            .operate(&mut tree.children[0], layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        self.content_mut().as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content()
+            .as_widget()
+            .mouse_interaction(&tree.children[0], layout, cursor, viewport, renderer)
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        self.content_mut()
+            .as_widget_mut()
+            .overlay(&mut tree.children[0], layout, renderer, translation)
+    }
+}
@@ -0,0 +1,172 @@
+//! Menu bar with File, Edit and View drop-downs.
+//!
+//! Clicking a title opens its menu as an overlay under the bar. Clicking an
+//! item runs its command and closes the menu; clicking anywhere else below
+//! the bar just closes it. Items show their keyboard accelerator
+//! right-aligned and are greyed out when the command isn't available.
+
+use iced::widget::{button, column, container, horizontal_rule, mouse_area, row, text, vertical_space};
+use iced::{Element, Length};
+
+use crate::app::Message;
+use crate::ui::metrics;
+
+/// Height of the menu bar above the toolbar, at 1× scale.
+pub const MENU_BAR_HEIGHT: f32 = 26.0;
+
+/// Width of each menu title in the bar.
+const TITLE_WIDTH: f32 = 56.0;
+
+/// Width of an open menu.
+const MENU_WIDTH: f32 = 260.0;
+
+/// A menu in the menu bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Menu {
+    File,
+    Edit,
+    View,
+}
+
+impl Menu {
+    /// All menus, in bar order.
+    pub const ALL: [Menu; 3] = [Menu::File, Menu::Edit, Menu::View];
+
+    /// The title shown in the bar.
+    pub fn title(self) -> &'static str {
+        match self {
+            Menu::File => "File",
+            Menu::Edit => "Edit",
+            Menu::View => "View",
+        }
+    }
+
+    /// Position of the menu in the bar.
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|menu| *menu == self).unwrap_or(0)
+    }
+}
+
+/// An entry in an open menu.
+#[derive(Debug, Clone)]
+pub enum MenuEntry {
+    /// A command; `message` is `None` while it is unavailable.
+    Item {
+        label: String,
+        accelerator: Option<&'static str>,
+        message: Option<Message>,
+    },
+    /// A line between groups of items.
+    Separator,
+}
+
+impl MenuEntry {
+    /// A command item.
+    pub fn item(label: impl Into<String>, accelerator: Option<&'static str>, message: Option<Message>) -> Self {
+        Self::Item {
+            label: label.into(),
+            accelerator,
+            message,
+        }
+    }
+
+    /// A command item with a check mark showing whether it is on.
+    pub fn toggle(label: &str, checked: bool, accelerator: Option<&'static str>, message: Option<Message>) -> Self {
+        Self::item(format!("{} {}", if checked { "✓" } else { "  " }, label), accelerator, message)
+    }
+}
+
+/// The menu bar component.
+pub struct MenuBar;
+
+impl MenuBar {
+    /// Render the bar of menu titles, highlighting the open menu.
+    pub fn view(open: Option<Menu>) -> Element<'static, Message> {
+        let titles = Menu::ALL.into_iter().map(|menu| {
+            button(text(menu.title()).size(metrics::text_md()).center())
+                .on_press(Message::ToggleMenu(menu))
+                .width(Length::Fixed(metrics::scaled(TITLE_WIDTH)))
+                .padding(metrics::pad_xy(4.0, 0.0))
+                .style(if open == Some(menu) { button::primary } else { button::text })
+                .into()
+        });
+
+        container(row(titles).spacing(0))
+            .width(Length::Fill)
+            .height(Length::Fixed(metrics::scaled(MENU_BAR_HEIGHT)))
+            .padding(metrics::pad_xy(0.0, 4.0))
+            .into()
+    }
+
+    /// Render a list of entries as a menu card.
+    pub fn card(entries: Vec<MenuEntry>) -> Element<'static, Message> {
+        let mut items = column![].spacing(2).width(Length::Fixed(metrics::scaled(MENU_WIDTH)));
+        for entry in entries {
+            items = match entry {
+                MenuEntry::Item {
+                    label,
+                    accelerator,
+                    message,
+                } => {
+                    let enabled = message.is_some();
+                    items.push(
+                        button(
+                            row![
+                                text(label).size(metrics::text_md()).width(Length::Fill),
+                                text(accelerator.unwrap_or_default())
+                                    .size(metrics::text_sm())
+                                    .color(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+                            ]
+                            .spacing(12),
+                        )
+                        .on_press_maybe(message.map(|message| Message::MenuCommand(Box::new(message))))
+                        .width(Length::Fill)
+                        .padding(metrics::pad_xy(3.0, 8.0))
+                        .style(move |theme, status| {
+                            let mut style = button::text(theme, status);
+                            if !enabled {
+                                style.text_color = style.text_color.scale_alpha(0.4);
+                            }
+                            style
+                        }),
+                    )
+                }
+                MenuEntry::Separator => items.push(horizontal_rule(1)),
+            };
+        }
+
+        container(items)
+            .padding(metrics::scaled(4.0))
+            .style(|theme: &iced::Theme| container::Style {
+                background: Some(theme.extended_palette().background.weak.color.into()),
+                border: iced::Border {
+                    color: theme.extended_palette().background.strong.color,
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                ..Default::default()
+            })
+            .into()
+    }
+
+    /// Render an open menu as an overlay for a `stack`, with a click-away
+    /// area covering the window below the bar.
+    pub fn dropdown(menu: Menu, entries: Vec<MenuEntry>) -> Element<'static, Message> {
+        let card = Self::card(entries);
+
+        let close_area = column![
+            // The bar stays clickable to switch menus
+            vertical_space().height(Length::Fixed(metrics::scaled(MENU_BAR_HEIGHT))),
+            mouse_area(container(vertical_space()).width(Length::Fill).height(Length::Fill))
+                .on_press(Message::CloseMenu),
+        ];
+        let positioned = container(card).width(Length::Fill).height(Length::Fill).padding(iced::Padding {
+            top: metrics::scaled(MENU_BAR_HEIGHT),
+            right: 0.0,
+            bottom: 0.0,
+            left: metrics::scaled(4.0 + TITLE_WIDTH * menu.index() as f32),
+        });
+
+        iced::widget::stack![close_area, positioned].into()
+    }
+}
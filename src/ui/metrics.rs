@@ -0,0 +1,110 @@
+//! Text sizes and paddings for the builder UI.
+//!
+//! Components take their sizes from here instead of using literal numbers,
+//! so the UI scale setting can enlarge or shrink the whole builder. The
+//! scale is process-wide and read on every `view`, so changing it applies
+//! on the next redraw. The canvas renders the user's layout at its real
+//! sizes; only the builder chrome around it is scaled.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use iced::Padding;
+
+/// Smallest UI scale.
+pub const MIN_SCALE: f32 = 0.75;
+
+/// Largest UI scale.
+pub const MAX_SCALE: f32 = 2.0;
+
+/// Amount the zoom shortcuts change the scale by.
+pub const SCALE_STEP: f32 = 0.125;
+
+/// The current scale, as `f32` bits.
+static SCALE: AtomicU32 = AtomicU32::new(1.0f32.to_bits());
+
+/// Limit a scale to the supported range, treating nonsense as 1×.
+pub fn clamp_scale(scale: f32) -> f32 {
+    if scale.is_finite() {
+        scale.clamp(MIN_SCALE, MAX_SCALE)
+    } else {
+        1.0
+    }
+}
+
+/// Set the UI scale, clamped to the supported range.
+pub fn set_scale(scale: f32) {
+    SCALE.store(clamp_scale(scale).to_bits(), Ordering::Relaxed);
+}
+
+/// The current UI scale.
+pub fn scale() -> f32 {
+    f32::from_bits(SCALE.load(Ordering::Relaxed))
+}
+
+/// A size in logical pixels at the current scale.
+pub fn scaled(value: f32) -> f32 {
+    value * scale()
+}
+
+/// Tiny text: paths and hints under a label.
+pub fn text_xs() -> f32 {
+    scaled(10.0)
+}
+
+/// Small text: secondary buttons, badges and the status bar.
+pub fn text_sm() -> f32 {
+    scaled(11.0)
+}
+
+/// Body text: labels, inputs and toolbar buttons.
+pub fn text_md() -> f32 {
+    scaled(12.0)
+}
+
+/// Large text: list items and panel headers.
+pub fn text_lg() -> f32 {
+    scaled(13.0)
+}
+
+/// Section titles.
+pub fn title() -> f32 {
+    scaled(14.0)
+}
+
+/// Headings at the top of a panel or dialog.
+pub fn heading() -> f32 {
+    scaled(16.0)
+}
+
+/// Padding of toolbar and dialog buttons.
+pub fn pad() -> Padding {
+    pad_xy(4.0, 8.0)
+}
+
+/// Padding of rows in lists.
+pub fn pad_sm() -> Padding {
+    pad_xy(2.0, 6.0)
+}
+
+/// Padding around the contents of a panel or popover.
+pub fn pad_panel() -> Padding {
+    Padding::new(scaled(10.0))
+}
+
+/// Padding with the given vertical and horizontal amounts, scaled.
+pub fn pad_xy(vertical: f32, horizontal: f32) -> Padding {
+    Padding::from([scaled(vertical), scaled(horizontal)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clamp_scale() {
+        assert_eq!(clamp_scale(0.5), MIN_SCALE);
+        assert_eq!(clamp_scale(3.0), MAX_SCALE);
+        assert_eq!(clamp_scale(1.25), 1.25);
+        assert_eq!(clamp_scale(f32::NAN), 1.0);
+    }
+}
@@ -0,0 +1,300 @@
+//! Canvas minimap.
+//!
+//! Draws a scaled-down outline of the whole layout (boxes only, no text)
+//! with the visible part of the canvas marked. The boxes are cached and
+//! only redrawn when the layout revision changes.
+
+use std::cell::Cell;
+
+use iced::mouse;
+use iced::widget::canvas::{self, event, Event, Frame, Geometry, Path, Stroke};
+use iced::widget::scrollable;
+use iced::{Color, Element, Length, Point, Rectangle, Renderer, Size, Theme, Vector};
+
+use crate::app::Message;
+use crate::model::layout::{LengthSpec, WidgetType};
+use crate::model::LayoutNode;
+
+/// Width of the minimap in logical pixels.
+const MINIMAP_WIDTH: f32 = 120.0;
+
+/// Height of the minimap in logical pixels.
+const MINIMAP_HEIGHT: f32 = 160.0;
+
+/// Size used for empty containers so they still show up.
+const EMPTY_CONTAINER: Size = Size::new(40.0, 20.0);
+
+/// Cached minimap geometry, kept in `App` between frames.
+#[derive(Debug, Default)]
+pub struct MinimapCache {
+    cache: canvas::Cache,
+    revision: Cell<Option<u64>>,
+    /// Scale and scaled content size for the cached revision.
+    metrics: Cell<Option<(f32, Size)>>,
+}
+
+impl MinimapCache {
+    /// Clear the cached boxes if the layout revision changed.
+    fn sync(&self, revision: u64) {
+        if self.revision.get() != Some(revision) {
+            self.cache.clear();
+            self.metrics.set(None);
+            self.revision.set(Some(revision));
+        }
+    }
+}
+
+/// Visible part of the canvas as fractions of its content height.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MinimapViewport {
+    /// Offset of the viewport top.
+    pub start: f32,
+    /// Height of the viewport.
+    pub extent: f32,
+}
+
+impl MinimapViewport {
+    /// Compute the visible fractions from a scrollable viewport.
+    pub fn from_scroll(viewport: &scrollable::Viewport) -> Self {
+        let content_height = viewport.content_bounds().height.max(1.0);
+        Self {
+            start: viewport.absolute_offset().y / content_height,
+            extent: (viewport.bounds().height / content_height).min(1.0),
+        }
+    }
+
+    /// Relative scroll offset that centers the viewport on `fraction` of the content.
+    pub fn centered_on(&self, fraction: f32) -> f32 {
+        if self.extent >= 1.0 {
+            0.0
+        } else {
+            ((fraction - self.extent / 2.0) / (1.0 - self.extent)).clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// The minimap component.
+pub struct Minimap<'a> {
+    root: &'a LayoutNode,
+    cache: &'a MinimapCache,
+    viewport: Option<MinimapViewport>,
+}
+
+impl<'a> Minimap<'a> {
+    /// Render the minimap for a layout.
+    pub fn view(
+        root: &'a LayoutNode,
+        revision: u64,
+        cache: &'a MinimapCache,
+        viewport: Option<MinimapViewport>,
+    ) -> Element<'a, Message> {
+        cache.sync(revision);
+
+        canvas::Canvas::new(Minimap {
+            root,
+            cache,
+            viewport,
+        })
+        .width(Length::Fixed(MINIMAP_WIDTH))
+        .height(Length::Fixed(MINIMAP_HEIGHT))
+        .into()
+    }
+
+    /// Scale that fits the whole layout into the minimap.
+    fn scale(&self) -> (f32, Size) {
+        if let Some(metrics) = self.cache.metrics.get() {
+            return metrics;
+        }
+
+        let size = measure(self.root);
+        let scale = (MINIMAP_WIDTH / size.width.max(1.0))
+            .min(MINIMAP_HEIGHT / size.height.max(1.0))
+            .min(1.0);
+        let metrics = (scale, Size::new(size.width * scale, size.height * scale));
+        self.cache.metrics.set(Some(metrics));
+        metrics
+    }
+}
+
+/// Approximate size of a fixed or shrinking length, falling back to `natural`.
+fn resolve(length: LengthSpec, natural: f32) -> f32 {
+    match length {
+        LengthSpec::Fixed(v) => v,
+        _ => natural,
+    }
+}
+
+/// Estimate the size a node takes up, without real text measurement.
+fn measure(node: &LayoutNode) -> Size {
+    match &node.widget {
+        WidgetType::Column { children, attrs } | WidgetType::Row { children, attrs } => {
+            let vertical = matches!(node.widget, WidgetType::Column { .. });
+            let sizes: Vec<Size> = children.iter().map(measure).collect();
+            let gaps = attrs.spacing * sizes.len().saturating_sub(1) as f32;
+            let (main, cross) = sizes.iter().fold((gaps, 0.0f32), |(main, cross), s| {
+                if vertical {
+                    (main + s.height, cross.max(s.width))
+                } else {
+                    (main + s.width, cross.max(s.height))
+                }
+            });
+            let inner = if sizes.is_empty() {
+                EMPTY_CONTAINER
+            } else if vertical {
+                Size::new(cross, main)
+            } else {
+                Size::new(main, cross)
+            };
+            let p = &attrs.padding;
+            Size::new(
+                resolve(attrs.width, inner.width + p.left + p.right),
+                resolve(attrs.height, inner.height + p.top + p.bottom),
+            )
+        }
+        WidgetType::Container { child, attrs } | WidgetType::Scrollable { child, attrs } => {
+            let inner = child.as_deref().map(measure).unwrap_or(EMPTY_CONTAINER);
+            let p = &attrs.padding;
+            Size::new(
+                resolve(attrs.width, inner.width + p.left + p.right),
+                resolve(attrs.height, inner.height + p.top + p.bottom),
+            )
+        }
+        WidgetType::Stack { children, attrs } => {
+            let inner = children
+                .iter()
+                .map(measure)
+                .fold(None, |acc: Option<Size>, s| {
+                    Some(acc.map_or(s, |a| Size::new(a.width.max(s.width), a.height.max(s.height))))
+                })
+                .unwrap_or(EMPTY_CONTAINER);
+            Size::new(resolve(attrs.width, inner.width), resolve(attrs.height, inner.height))
+        }
+        WidgetType::Text { attrs, .. } => Size::new(80.0, attrs.font_size * 1.3),
+        WidgetType::Button { attrs, .. } => {
+            Size::new(resolve(attrs.width, 80.0), resolve(attrs.height, 32.0))
+        }
+        WidgetType::TextInput { attrs, .. } => Size::new(resolve(attrs.width, 160.0), 32.0),
+        WidgetType::Checkbox { .. } => Size::new(100.0, 20.0),
+        WidgetType::Slider { attrs, .. } => Size::new(resolve(attrs.width, 160.0), 20.0),
+        WidgetType::PickList { attrs, .. } => Size::new(resolve(attrs.width, 120.0), 32.0),
+        WidgetType::Space { width, height } => Size::new(resolve(*width, 10.0), resolve(*height, 10.0)),
+        WidgetType::ComponentRef { .. } => Size::new(120.0, 40.0),
+    }
+}
+
+/// Draw a node and its children as nested boxes at `origin`.
+fn draw_boxes(frame: &mut Frame, node: &LayoutNode, origin: Point, scale: f32, depth: usize) {
+    let size = measure(node);
+    let rect = Path::rectangle(origin, Size::new(size.width * scale, size.height * scale));
+
+    let shade = (0.35 + depth as f32 * 0.08).min(0.8);
+    let color = if matches!(node.widget, WidgetType::Space { .. }) {
+        Color::TRANSPARENT
+    } else {
+        Color::from_rgba(shade, shade, shade + 0.1, 0.9)
+    };
+    frame.stroke(&rect, Stroke::default().with_color(color).with_width(1.0));
+
+    match &node.widget {
+        WidgetType::Column { children, attrs } | WidgetType::Row { children, attrs } => {
+            let vertical = matches!(node.widget, WidgetType::Column { .. });
+            let mut cursor = origin + Vector::new(attrs.padding.left, attrs.padding.top) * scale;
+            for child in children {
+                draw_boxes(frame, child, cursor, scale, depth + 1);
+                let child_size = measure(child);
+                cursor = if vertical {
+                    cursor + Vector::new(0.0, (child_size.height + attrs.spacing) * scale)
+                } else {
+                    cursor + Vector::new((child_size.width + attrs.spacing) * scale, 0.0)
+                };
+            }
+        }
+        WidgetType::Container { child: Some(child), attrs }
+        | WidgetType::Scrollable { child: Some(child), attrs } => {
+            let offset = Vector::new(attrs.padding.left, attrs.padding.top) * scale;
+            draw_boxes(frame, child, origin + offset, scale, depth + 1);
+        }
+        WidgetType::Stack { children, .. } => {
+            for child in children {
+                draw_boxes(frame, child, origin, scale, depth + 1);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl canvas::Program<Message> for Minimap<'_> {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (event::Status, Option<Message>) {
+        let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event else {
+            return (event::Status::Ignored, None);
+        };
+        let Some(position) = cursor.position_in(bounds) else {
+            return (event::Status::Ignored, None);
+        };
+
+        let (_, content) = self.scale();
+        let fraction = (position.y / content.height.max(1.0)).clamp(0.0, 1.0);
+        (event::Status::Captured, Some(Message::MinimapClicked(fraction)))
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let (scale, content) = self.scale();
+
+        let boxes = self.cache.cache.draw(renderer, bounds.size(), |frame| {
+            frame.fill_rectangle(
+                Point::ORIGIN,
+                frame.size(),
+                Color::from_rgba(0.08, 0.08, 0.1, 0.85),
+            );
+            draw_boxes(frame, self.root, Point::ORIGIN, scale, 0);
+        });
+
+        let mut geometry = vec![boxes];
+
+        if let Some(viewport) = self.viewport {
+            let mut frame = Frame::new(renderer, bounds.size());
+            let rect = Path::rectangle(
+                Point::new(0.0, viewport.start * content.height),
+                Size::new(content.width.max(1.0), viewport.extent * content.height),
+            );
+            frame.fill(&rect, Color::from_rgba(0.3, 0.6, 1.0, 0.15));
+            frame.stroke(
+                &rect,
+                Stroke::default()
+                    .with_color(Color::from_rgb(0.3, 0.6, 1.0))
+                    .with_width(1.0),
+            );
+            geometry.push(frame.into_geometry());
+        }
+
+        geometry
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &Self::State,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if cursor.is_over(bounds) {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
@@ -1,17 +1,49 @@
 //! UI components for the Iced Builder application.
 //!
 //! This module contains the visual components of the builder:
+//! - Menu bar (top), and a right-click menu for layout nodes
 //! - Widget palette (left sidebar)
-//! - Canvas/viewport (center)
+//! - Canvas/viewport (center), or the welcome screen when no project is open
 //! - Property inspector (right sidebar)
 //! - Tree view (optional bottom/left panel)
+//! - Undo history (optional panel under the inspector)
+//! - Validation problems (optional panel under the inspector)
+//! - Generated code preview (modal)
+//! - Error dialog (modal)
+//! - Delete confirmation for nodes with children (modal)
+//! - New project template picker (modal)
 
 pub mod canvas;
+pub mod code_panel;
+pub mod context_menu;
+pub mod delete_confirm;
+pub mod error_dialog;
+pub mod history_panel;
 pub mod inspector;
+pub mod measure;
+pub mod memo;
+pub mod menu_bar;
+pub mod metrics;
+pub mod minimap;
+pub mod outline;
 pub mod palette;
+pub mod problems_panel;
+pub mod scroll;
+pub mod splitter;
+pub mod template_picker;
 pub mod tree_view;
+pub mod welcome;
 
 pub use canvas::Canvas;
+pub use code_panel::CodePanel;
+pub use context_menu::ContextMenu;
+pub use delete_confirm::DeleteConfirmDialog;
+pub use error_dialog::ErrorDialog;
+pub use history_panel::HistoryPanel;
 pub use inspector::Inspector;
+pub use menu_bar::MenuBar;
 pub use palette::Palette;
+pub use problems_panel::ProblemsPanel;
+pub use template_picker::TemplatePicker;
 pub use tree_view::TreeView;
+pub use welcome::Welcome;
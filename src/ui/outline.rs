@@ -0,0 +1,158 @@
+//! Dashed outline overlay for design-mode affordances.
+//!
+//! Draws a dashed rectangle and an optional faint label on top of a widget
+//! without taking part in layout: the outline fills whatever bounds the
+//! enclosing `stack` gives it, so wrapping a widget never changes its size.
+
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::renderer::{self, Quad};
+use iced::advanced::text::{self, Text};
+use iced::advanced::widget::{self, Widget};
+use iced::alignment;
+use iced::mouse;
+use iced::{Color, Element, Length, Pixels, Point, Rectangle, Size};
+
+/// Length of each dash and gap in logical pixels.
+const DASH_LENGTH: f32 = 4.0;
+
+/// Thickness of the outline stroke.
+const STROKE_WIDTH: f32 = 1.0;
+
+/// Minimum visual extent for zero-size elements, so they still show a marker.
+const MIN_EXTENT: f32 = 12.0;
+
+/// Font size of the faint label drawn in the top-left corner.
+const LABEL_SIZE: f32 = 10.0;
+
+/// A non-interactive dashed outline with an optional label.
+pub struct DashedOutline {
+    label: Option<String>,
+    color: Color,
+}
+
+impl DashedOutline {
+    /// Create a new outline with the given stroke color.
+    pub fn new(color: Color) -> Self {
+        Self { label: None, color }
+    }
+
+    /// Set the label drawn in the top-left corner of the outline.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+}
+
+/// Create a dashed outline overlay with a label.
+pub fn dashed_outline<'a, Message: 'a>(label: impl Into<String>, color: Color) -> Element<'a, Message> {
+    DashedOutline::new(color).label(label).into()
+}
+
+/// Expand a rectangle around its center so both sides are at least `MIN_EXTENT`.
+fn visible_bounds(bounds: Rectangle) -> Rectangle {
+    let width = bounds.width.max(MIN_EXTENT);
+    let height = bounds.height.max(MIN_EXTENT);
+
+    Rectangle {
+        x: bounds.x - (width - bounds.width) / 2.0,
+        y: bounds.y - (height - bounds.height) / 2.0,
+        width,
+        height,
+    }
+}
+
+/// Compute the dash segments along one edge of `length` starting at `start`.
+fn dash_segments(start: f32, length: f32) -> Vec<(f32, f32)> {
+    let mut segments = Vec::new();
+    let mut offset = 0.0;
+
+    while offset < length {
+        let dash = DASH_LENGTH.min(length - offset);
+        segments.push((start + offset, dash));
+        offset += DASH_LENGTH * 2.0;
+    }
+
+    segments
+}
+
+impl<Message, Theme, Renderer> Widget<Message, Theme, Renderer> for DashedOutline
+where
+    Renderer: text::Renderer<Font = iced::Font>,
+{
+    fn size(&self) -> Size<Length> {
+        Size {
+            width: Length::Fill,
+            height: Length::Fill,
+        }
+    }
+
+    fn layout(
+        &self,
+        _tree: &mut widget::Tree,
+        _renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        layout::Node::new(limits.max())
+    }
+
+    fn draw(
+        &self,
+        _tree: &widget::Tree,
+        renderer: &mut Renderer,
+        _theme: &Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        let bounds = visible_bounds(layout.bounds());
+
+        let mut dash = |x: f32, y: f32, width: f32, height: f32| {
+            renderer.fill_quad(
+                Quad {
+                    bounds: Rectangle { x, y, width, height },
+                    ..Quad::default()
+                },
+                self.color,
+            );
+        };
+
+        for (x, len) in dash_segments(bounds.x, bounds.width) {
+            dash(x, bounds.y, len, STROKE_WIDTH);
+            dash(x, bounds.y + bounds.height - STROKE_WIDTH, len, STROKE_WIDTH);
+        }
+        for (y, len) in dash_segments(bounds.y, bounds.height) {
+            dash(bounds.x, y, STROKE_WIDTH, len);
+            dash(bounds.x + bounds.width - STROKE_WIDTH, y, STROKE_WIDTH, len);
+        }
+
+        if let Some(label) = &self.label {
+            renderer.fill_text(
+                Text {
+                    content: label.clone(),
+                    bounds: Size::new(f32::INFINITY, LABEL_SIZE * 1.5),
+                    size: Pixels(LABEL_SIZE),
+                    line_height: text::LineHeight::default(),
+                    font: renderer.default_font(),
+                    horizontal_alignment: alignment::Horizontal::Left,
+                    vertical_alignment: alignment::Vertical::Top,
+                    shaping: text::Shaping::Basic,
+                    wrapping: text::Wrapping::None,
+                },
+                Point::new(bounds.x + 2.0, bounds.y + 1.0),
+                Color { a: self.color.a * 0.9, ..self.color },
+                *viewport,
+            );
+        }
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<DashedOutline> for Element<'a, Message, Theme, Renderer>
+where
+    Renderer: text::Renderer<Font = iced::Font> + 'a,
+    Theme: 'a,
+{
+    fn from(outline: DashedOutline) -> Self {
+        Element::new(outline)
+    }
+}
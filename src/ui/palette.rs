@@ -1,91 +1,157 @@
 //! Widget palette sidebar.
 //!
 //! Displays available widgets and containers that can be added to the layout.
+//! Items can be clicked to arm an insertion or dragged onto the canvas or
+//! a tree row. Sections collapse to their header, and the search box
+//! filters items across all sections. The insertion mode decides whether
+//! items go inside the selection or next to it.
 
-use iced::widget::{button, column, container, scrollable, text, Column};
-use iced::{Element, Length};
+use std::collections::BTreeSet;
 
+use iced::widget::{
+    button, column, container, mouse_area, row, scrollable, text, text_input, tooltip, Column,
+};
+use iced::{mouse, Element, Length, Padding, Point};
 use crate::app::Message;
+use crate::io::app_settings::MAX_RECENT_WIDGETS;
+use crate::io::Snippet;
+use crate::model::Position;
+use crate::ui::metrics;
 
-/// Widget categories in the palette.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum WidgetCategory {
-    Containers,
-    Widgets,
-}
+pub use crate::model::WidgetKind;
 
-/// Types of widgets that can be added.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum WidgetKind {
-    // Containers
-    ColumnContainer,
-    RowContainer,
-    Container,
-    Scrollable,
-    Stack,
-    // Widgets
-    Text,
-    Button,
-    TextInput,
-    Checkbox,
-    Slider,
-    PickList,
-    Space,
+/// Where palette items are inserted relative to the selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InsertMode {
+    /// Append inside the selected container.
+    #[default]
+    Inside,
+    /// Insert before the selected node in its parent.
+    Before,
+    /// Insert after the selected node in its parent.
+    After,
 }
 
-impl WidgetKind {
-    /// Get the display name for this widget type.
+impl InsertMode {
+    /// All modes in display order.
+    pub const ALL: [InsertMode; 3] = [Self::Before, Self::Inside, Self::After];
+
+    /// Get the display name for this mode.
     pub fn name(&self) -> &'static str {
         match self {
-            Self::ColumnContainer => "Column",
-            Self::RowContainer => "Row",
-            Self::Container => "Container",
-            Self::Scrollable => "Scrollable",
-            Self::Stack => "Stack",
-            Self::Text => "Text",
-            Self::Button => "Button",
-            Self::TextInput => "TextInput",
-            Self::Checkbox => "Checkbox",
-            Self::Slider => "Slider",
-            Self::PickList => "PickList",
-            Self::Space => "Space",
+            Self::Inside => "Inside",
+            Self::Before => "Before",
+            Self::After => "After",
         }
     }
 
-    /// Get the category for this widget type.
-    pub fn category(&self) -> WidgetCategory {
+    /// The sibling position for this mode, or `None` when inserting inside.
+    pub fn position(&self) -> Option<Position> {
         match self {
-            Self::ColumnContainer
-            | Self::RowContainer
-            | Self::Container
-            | Self::Scrollable
-            | Self::Stack => WidgetCategory::Containers,
-            _ => WidgetCategory::Widgets,
+            Self::Inside => None,
+            Self::Before => Some(Position::Before),
+            Self::After => Some(Position::After),
         }
     }
+}
+
+
+/// Maximum width of an item's description tooltip.
+const TOOLTIP_WIDTH: f32 = 220.0;
+
+/// Distance the cursor must travel before a press becomes a drag.
+const DRAG_THRESHOLD: f32 = 4.0;
+
+/// Offset of the drag ghost from the cursor.
+const GHOST_OFFSET: f32 = 12.0;
 
-    /// Get all container widget kinds.
-    pub fn containers() -> &'static [WidgetKind] {
-        &[
-            Self::ColumnContainer,
-            Self::RowContainer,
-            Self::Container,
-            Self::Scrollable,
-            Self::Stack,
-        ]
-    }
-
-    /// Get all basic widget kinds.
-    pub fn widgets() -> &'static [WidgetKind] {
-        &[
-            Self::Text,
-            Self::Button,
-            Self::TextInput,
-            Self::Checkbox,
-            Self::Slider,
-            Self::PickList,
-            Self::Space,
-        ]
+/// A press on a palette item that may turn into a drag.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaletteDrag {
+    /// The widget kind being dragged.
+    pub kind: WidgetKind,
+    /// Cursor position when the first move arrived.
+    pub origin: Option<Point>,
+    /// Latest cursor position.
+    pub cursor: Option<Point>,
+}
+
+impl PaletteDrag {
+    /// Start tracking a press on `kind`.
+    pub fn new(kind: WidgetKind) -> Self {
+        Self {
+            kind,
+            origin: None,
+            cursor: None,
+        }
+    }
+
+    /// Record a cursor move.
+    pub fn move_to(&mut self, position: Point) {
+        self.origin.get_or_insert(position);
+        self.cursor = Some(position);
+    }
+
+    /// Whether the cursor moved far enough to count as a drag rather than a click.
+    pub fn is_dragging(&self) -> bool {
+        match (self.origin, self.cursor) {
+            (Some(origin), Some(cursor)) => origin.distance(cursor) > DRAG_THRESHOLD,
+            _ => false,
+        }
+    }
+}
+
+/// Everything the palette needs to render, borrowed from `App`.
+#[derive(Debug, Clone, Copy)]
+pub struct PaletteContext<'a> {
+    /// Item armed for click-to-insert.
+    pub pending: Option<WidgetKind>,
+    /// Recently inserted kinds, newest first.
+    pub recent: &'a [WidgetKind],
+    /// Snippets saved in the open project.
+    pub snippets: &'a [Snippet],
+    /// Snippet being renamed and the draft name.
+    pub renaming: Option<&'a (usize, String)>,
+    /// Name typed for the selection being saved as a snippet.
+    pub naming: Option<&'a str>,
+    /// Titles of collapsed sections.
+    pub collapsed: &'a BTreeSet<String>,
+    /// Search text; non-empty searches expand every section.
+    pub query: &'a str,
+    /// Where new items go relative to the selection.
+    pub insert_mode: InsertMode,
+    /// Why items cannot go where the insertion mode asks, if they cannot.
+    pub ineligible: Option<&'static str>,
+    /// Whether ineligible items are disabled rather than dimmed.
+    pub disable_ineligible: bool,
+}
+
+impl PaletteContext<'_> {
+    /// Whether a section shows its items.
+    fn is_expanded(&self, title: &str) -> bool {
+        !self.query.is_empty() || !self.collapsed.contains(title)
+    }
+
+    /// Whether an item name matches the search text.
+    fn matches(&self, name: &str) -> bool {
+        name.to_lowercase().contains(&self.query.trim().to_lowercase())
+    }
+
+    /// Widget kinds in `kinds` matching the search text.
+    fn filter(&self, kinds: &[WidgetKind]) -> Vec<WidgetKind> {
+        kinds.iter().copied().filter(|kind| self.matches(kind.name())).collect()
+    }
+
+    /// First widget kind matching the search text, if searching.
+    pub fn first_match(&self) -> Option<WidgetKind> {
+        if self.query.trim().is_empty() {
+            return None;
+        }
+        WidgetKind::containers()
+            .iter()
+            .chain(WidgetKind::widgets())
+            .copied()
+            .find(|kind| self.matches(kind.name()))
     }
 }
 
@@ -93,32 +159,217 @@ impl WidgetKind {
 pub struct Palette;
 
 impl Palette {
-    /// Render the palette sidebar.
-    pub fn view<'a>() -> Element<'a, Message> {
-        let container_section = Self::section("Containers", WidgetKind::containers());
-        let widget_section = Self::section("Widgets", WidgetKind::widgets());
+    /// Identifier of the name input shown while saving a snippet.
+    pub fn snippet_name_id() -> text_input::Id {
+        text_input::Id::new("snippet-name")
+    }
+
+    /// Render the palette sidebar, highlighting the item pending insertion.
+    ///
+    /// Recently inserted kinds are listed first, most recent at the top,
+    /// and the project's snippets last. Section headers collapse their items.
+    pub fn view<'a>(ctx: PaletteContext<'a>) -> Element<'a, Message> {
+        let search = text_input("Search widgets…", ctx.query)
+            .on_input(Message::PaletteSearchChanged)
+            .on_submit(Message::PaletteSearchSubmitted)
+            .size(metrics::text_lg());
 
-        let content = column![container_section, widget_section]
+        let mut content = column![search, Self::insert_modes(ctx.insert_mode)]
             .spacing(20)
-            .padding(10)
+            .padding(metrics::pad_panel())
             .width(Length::Fill);
+        let recent = &ctx.recent[..ctx.recent.len().min(MAX_RECENT_WIDGETS)];
+        for (title, kinds) in [
+            ("Recent", recent),
+            ("Containers", WidgetKind::containers()),
+            ("Widgets", WidgetKind::widgets()),
+        ] {
+            let kinds = ctx.filter(kinds);
+            if !kinds.is_empty() {
+                content = content.push(Self::section(title, &kinds, ctx));
+            }
+        }
+
+        let snippets: Vec<(usize, &Snippet)> = ctx
+            .snippets
+            .iter()
+            .enumerate()
+            .filter(|(_, snippet)| ctx.matches(&snippet.name))
+            .collect();
+        if !snippets.is_empty() || ctx.naming.is_some() {
+            content = content.push(Self::snippet_section(&snippets, ctx));
+        }
 
         container(scrollable(content).height(Length::Fill))
-            .width(Length::Fixed(180.0))
+            .width(Length::Fixed(metrics::scaled(180.0)))
             .height(Length::Fill)
             .into()
     }
 
+    /// Render the label that follows the cursor while dragging an item.
+    pub fn ghost<'a>(drag: &PaletteDrag) -> Option<Element<'a, Message>> {
+        let cursor = drag.cursor.filter(|_| drag.is_dragging())?;
+        let label = container(text(drag.kind.name()).size(metrics::text_lg()))
+            .padding(metrics::pad())
+            .style(container::rounded_box);
+
+        Some(
+            container(label)
+                .padding(Padding {
+                    top: cursor.y + GHOST_OFFSET,
+                    left: cursor.x + GHOST_OFFSET,
+                    ..Padding::ZERO
+                })
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into(),
+        )
+    }
+
+    /// Render the segmented control choosing the insertion mode.
+    fn insert_modes<'a>(current: InsertMode) -> Element<'a, Message> {
+        let buttons = InsertMode::ALL.map(|mode| {
+            let style = if mode == current {
+                button::primary
+            } else {
+                button::secondary
+            };
+            button(text(mode.name()).size(metrics::text_sm()))
+                .on_press(Message::SetInsertMode(mode))
+                .padding(metrics::pad_xy(3.0, 6.0))
+                .width(Length::Fill)
+                .style(style)
+                .into()
+        });
+        let control = row(buttons).spacing(1);
+        tooltip(
+            control,
+            container(text("Alt-click inserts after the selection, Shift+Alt-click before it").size(metrics::text_md()))
+                .padding(metrics::scaled(6.0))
+                .max_width(TOOLTIP_WIDTH)
+                .style(container::rounded_box),
+            tooltip::Position::Bottom,
+        )
+        .gap(4)
+        .into()
+    }
+
+    /// Render a clickable section header that collapses the section.
+    fn header<'a>(title: &'static str, count: usize, expanded: bool) -> Element<'a, Message> {
+        let label = if expanded {
+            format!("▾ {title}")
+        } else {
+            format!("▸ {title} ({count})")
+        };
+        button(text(label).size(metrics::title()))
+            .on_press(Message::TogglePaletteSection(title.to_string()))
+            .padding(0)
+            .style(button::text)
+            .into()
+    }
+
+    /// Render the "My Components" section listing saved snippets.
+    ///
+    /// While a snippet is being saved, its name input sits under the header
+    /// even if the section is collapsed.
+    fn snippet_section<'a>(snippets: &[(usize, &'a Snippet)], ctx: PaletteContext<'a>) -> Column<'a, Message> {
+        const TITLE: &str = "My Components";
+        let expanded = ctx.is_expanded(TITLE);
+        let small = |label| button(text(label).size(metrics::text_sm())).padding(metrics::pad_xy(4.0, 6.0)).style(button::secondary);
+        let naming = ctx.naming.map(|draft| {
+            row![
+                text_input("Snippet name", draft)
+                    .id(Self::snippet_name_id())
+                    .on_input(Message::SnippetNameInput)
+                    .on_submit(Message::CommitSnippetName)
+                    .size(metrics::text_lg()),
+                small("×").on_press(Message::CancelSnippetName),
+            ]
+            .spacing(2)
+        });
+        let col = column![Self::header(TITLE, snippets.len(), expanded)]
+            .push_maybe(naming)
+            .spacing(5);
+        if !expanded {
+            return col;
+        }
+
+        snippets.iter().fold(col, |col, &(index, snippet)| {
+            let item: Element<'a, Message> = match ctx.renaming {
+                Some((renamed, draft)) if *renamed == index => text_input("Snippet name", draft)
+                    .on_input(Message::SnippetRenameInput)
+                    .on_submit(Message::CommitSnippetRename)
+                    .size(metrics::text_lg())
+                    .into(),
+                _ => button(text(snippet.name.as_str()).size(metrics::text_lg()))
+                    .on_press(Message::InsertSnippet(index))
+                    .width(Length::Fill)
+                    .style(button::primary)
+                    .into(),
+            };
+            col.push(
+                row![
+                    item,
+                    small("✎").on_press(Message::StartRenameSnippet(index)),
+                    small("×").on_press(Message::DeleteSnippet(index)),
+                ]
+                .spacing(2),
+            )
+        })
+    }
+
     /// Render a section of the palette.
-    fn section<'a>(title: &'a str, kinds: &[WidgetKind]) -> Column<'a, Message> {
-        let header = text(title).size(14);
+    fn section<'a>(title: &'static str, kinds: &[WidgetKind], ctx: PaletteContext<'a>) -> Column<'a, Message> {
+        let expanded = ctx.is_expanded(title);
+        let header = Self::header(title, kinds.len(), expanded);
+        if !expanded {
+            return column![header];
+        }
 
+        let dimmed = ctx.ineligible.is_some();
+        let disabled = dimmed && ctx.disable_ineligible;
         let buttons: Vec<Element<'a, Message>> = kinds
             .iter()
             .map(|kind| {
-                button(text(kind.name()).size(13))
-                    .on_press(Message::PaletteItemClicked(*kind))
+                let style = if ctx.pending == Some(*kind) {
+                    button::success
+                } else {
+                    button::primary
+                };
+                // The press is handled by the mouse area so it can become a
+                // drag; a release without moving counts as a click
+                let item = button(text(kind.name()).size(metrics::text_lg()))
                     .width(Length::Fill)
+                    .style(move |theme, status| {
+                        let status = match status {
+                            button::Status::Disabled if !disabled => button::Status::Active,
+                            other => other,
+                        };
+                        let mut style = style(theme, status);
+                        if dimmed {
+                            style.background = style.background.map(|b| b.scale_alpha(0.4));
+                            style.text_color = style.text_color.scale_alpha(0.5);
+                        }
+                        style
+                    });
+                let mut item = mouse_area(item)
+                    .on_enter(Message::PaletteHovered(*kind))
+                    .on_exit(Message::PaletteUnhovered(*kind));
+                if !disabled {
+                    item = item
+                        .on_press(Message::PaletteDragStart(*kind))
+                        .interaction(mouse::Interaction::Grab);
+                }
+                let mut tip = column![text(kind.description()).size(metrics::text_md())].spacing(4);
+                if let Some(reason) = ctx.ineligible {
+                    tip = tip.push(text(reason).size(metrics::text_md()));
+                }
+                let description = container(tip)
+                    .padding(metrics::scaled(6.0))
+                    .max_width(TOOLTIP_WIDTH)
+                    .style(container::rounded_box);
+                tooltip(item, description, tooltip::Position::Right)
+                    .gap(4)
                     .into()
             })
             .collect();
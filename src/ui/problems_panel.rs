@@ -0,0 +1,196 @@
+//! Problems panel.
+//!
+//! Lists the errors and warnings found by validating the layout, in
+//! document order. The list is refreshed once edits settle for
+//! `REVALIDATE_DELAY` rather than on every keystroke. Clicking a problem
+//! selects the node it is about so the inspector shows the field to fix.
+//! The same counts show at the right end of the status bar.
+//!
+//! "Check against project" looks up the state struct in the target project
+//! and from then on also lists bindings it lacks, until the check is cleared
+//! or another project becomes active.
+
+use std::time::Duration;
+
+use iced::widget::{button, column, container, horizontal_space, row, scrollable, text, tooltip, Column};
+use iced::{Color, Element, Length};
+
+use crate::app::Message;
+use crate::codegen::state_check::StateStruct;
+use crate::model::layout::{ValidationError, ValidationSeverity};
+use crate::model::ComponentId;
+use crate::ui::metrics;
+
+/// Height of the panel below the inspector.
+pub const PANEL_HEIGHT: f32 = 180.0;
+
+/// How long the layout must stay unchanged before problems are refreshed.
+pub const REVALIDATE_DELAY: Duration = Duration::from_millis(300);
+
+/// Validation results for the active project.
+#[derive(Debug, Clone, Default)]
+pub struct Problems {
+    /// Problems found, in document order.
+    pub list: Vec<ValidationError>,
+    /// Project revision the list was found in.
+    pub revision: Option<u64>,
+    /// Revision a delayed refresh is waiting on.
+    pub scheduled: Option<u64>,
+    /// State struct bindings are checked against, once asked for.
+    pub state: Option<StateStruct>,
+}
+
+impl Problems {
+    /// Number of errors in the list.
+    pub fn errors(&self) -> usize {
+        self.list.iter().filter(|p| p.severity == ValidationSeverity::Error).count()
+    }
+
+    /// Number of warnings in the list.
+    pub fn warnings(&self) -> usize {
+        self.list.len() - self.errors()
+    }
+
+    /// Short summary for the status bar, e.g. "2 errors, 1 warning".
+    pub fn summary(&self) -> String {
+        let plural = |count: usize, noun: &str| format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" });
+        match (self.errors(), self.warnings()) {
+            (0, 0) => "No problems".to_string(),
+            (errors, 0) => plural(errors, "error"),
+            (0, warnings) => plural(warnings, "warning"),
+            (errors, warnings) => format!("{}, {}", plural(errors, "error"), plural(warnings, "warning")),
+        }
+    }
+}
+
+/// The problems panel.
+pub struct ProblemsPanel;
+
+impl ProblemsPanel {
+    /// Render the status bar counter: "⚠ 2  ✖ 1", or a green check when
+    /// there are no problems.
+    pub fn indicator(problems: &Problems, panel_open: bool) -> Element<'_, Message> {
+        let content: Element<Message> = match (problems.warnings(), problems.errors()) {
+            (0, 0) => text("✔").size(metrics::text_sm()).color(Color::from_rgb(0.4, 0.75, 0.4)).into(),
+            (warnings, errors) => row![
+                text(format!("⚠ {}", warnings)).size(metrics::text_sm()).color(Color::from_rgb(0.9, 0.7, 0.2)),
+                text(format!("✖ {}", errors)).size(metrics::text_sm()).color(Color::from_rgb(0.9, 0.3, 0.3)),
+            ]
+            .spacing(8)
+            .into(),
+        };
+        tooltip(
+            button(content)
+                .on_press(Message::ShowProblems)
+                .style(if panel_open { button::secondary } else { button::text })
+                .padding(metrics::pad_xy(0.0, 6.0)),
+            container(text(problems.summary()).size(metrics::text_sm()))
+                .padding(metrics::scaled(6.0))
+                .style(container::rounded_box),
+            tooltip::Position::Top,
+        )
+        .gap(4)
+        .into()
+    }
+
+    /// Render the problem list, highlighting those about the selected node.
+    pub fn view(problems: &Problems, selected: Option<ComponentId>) -> Element<'_, Message> {
+        let header = row![
+            text("Problems").size(metrics::text_lg()),
+            horizontal_space(),
+            text(problems.summary()).size(metrics::text_sm()).color(Color::from_rgb(0.5, 0.5, 0.5)),
+        ]
+        .align_y(iced::Alignment::Center);
+
+        let check = row![
+            button(text("Check against project").size(metrics::text_sm()))
+                .on_press(Message::CheckStateBindings)
+                .style(button::secondary)
+                .padding(metrics::pad_xy(2.0, 6.0)),
+            horizontal_space(),
+        ]
+        .push_maybe(problems.state.as_ref().map(|state| {
+            text(format!("vs {}", state.name)).size(metrics::text_sm()).color(Color::from_rgb(0.5, 0.5, 0.5))
+        }))
+        .push_maybe(problems.state.as_ref().map(|_| {
+            button(text("✕").size(metrics::text_sm()))
+                .on_press(Message::ClearStateCheck)
+                .style(button::text)
+                .padding(metrics::pad_xy(0.0, 4.0))
+        }))
+        .spacing(4)
+        .align_y(iced::Alignment::Center);
+
+        let list: Element<Message> = if problems.list.is_empty() {
+            text("No problems").size(metrics::text_md()).color(Color::from_rgb(0.5, 0.5, 0.5)).into()
+        } else {
+            let rows = problems.list.iter().map(|problem| {
+                let (icon, color) = match problem.severity {
+                    ValidationSeverity::Error => ("✖", Color::from_rgb(0.9, 0.3, 0.3)),
+                    ValidationSeverity::Warning => ("⚠", Color::from_rgb(0.9, 0.7, 0.2)),
+                };
+                let current = selected == Some(problem.node_id);
+                let entry = button(
+                    row![
+                        text(icon).size(metrics::text_sm()).color(color).width(Length::Fixed(metrics::scaled(14.0))),
+                        column![
+                            text(&problem.message).size(metrics::text_md()),
+                            text(&problem.path).size(metrics::text_xs()).color(Color::from_rgb(0.5, 0.5, 0.5)),
+                        ]
+                        .spacing(1),
+                    ]
+                    .spacing(4),
+                )
+                .on_press(Message::SelectProblem(problem.node_id))
+                .width(Length::Fill)
+                .padding(metrics::pad_sm())
+                .style(if current { button::secondary } else { button::text });
+
+                // Problems involving two nodes can jump to the other one too
+                match &problem.related {
+                    Some((path, id)) => row![
+                        entry,
+                        tooltip(
+                            button(text("↪").size(metrics::text_sm()))
+                                .on_press(Message::SelectProblem(*id))
+                                .padding(metrics::pad_sm())
+                                .style(if selected == Some(*id) { button::secondary } else { button::text }),
+                            container(text(format!("Go to {}", path)).size(metrics::text_sm()))
+                                .padding(metrics::scaled(6.0))
+                                .style(container::rounded_box),
+                            tooltip::Position::Left,
+                        )
+                        .gap(4),
+                    ]
+                    .into(),
+                    None => entry.into(),
+                }
+            });
+            scrollable(Column::with_children(rows).spacing(1)).height(Length::Fill).into()
+        };
+
+        container(column![header, check, list].spacing(6))
+            .width(Length::Fixed(metrics::scaled(250.0)))
+            .height(Length::Fixed(PANEL_HEIGHT))
+            .padding(metrics::pad_panel())
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_counts_errors_and_warnings() {
+        let mut problems = Problems::default();
+        assert_eq!(problems.summary(), "No problems");
+
+        problems.list.push(ValidationError::warning("root", "Empty column", ComponentId::new()));
+        assert_eq!(problems.summary(), "1 warning");
+        problems.list.push(ValidationError::error("root.children[0]", "Bad name", ComponentId::new()));
+        problems.list.push(ValidationError::error("root.children[1]", "Bad name", ComponentId::new()));
+        assert_eq!(problems.summary(), "2 errors, 1 warning");
+        assert_eq!(problems.errors(), 2);
+    }
+}
@@ -0,0 +1,108 @@
+//! Scrolling helpers for keeping widgets in view.
+//!
+//! Provides a widget operation that scrolls a scrollable just far enough
+//! to reveal a target container inside it.
+
+use iced::advanced::widget::operation::{self, Operation, Outcome, Scrollable};
+use iced::advanced::widget::{self, Id};
+use iced::widget::scrollable::AbsoluteOffset;
+use iced::{Rectangle, Task, Vector};
+
+/// Space left between the revealed widget and the viewport edge.
+const REVEAL_MARGIN: f32 = 16.0;
+
+/// Scroll `scrollable` so the container `target` is visible.
+///
+/// Does nothing if either widget is missing or the target is already in view.
+pub fn scroll_into_view<T: Send + 'static>(
+    scrollable: impl Into<Id>,
+    target: impl Into<Id>,
+) -> Task<T> {
+    widget::operate(ScrollIntoView {
+        scrollable: scrollable.into(),
+        target: target.into(),
+        viewport: None,
+        target_bounds: None,
+    })
+}
+
+/// Viewport of the scrollable: bounds, content bounds, and current translation.
+type Viewport = (Rectangle, Rectangle, Vector);
+
+struct ScrollIntoView {
+    scrollable: Id,
+    target: Id,
+    viewport: Option<Viewport>,
+    target_bounds: Option<Rectangle>,
+}
+
+/// Compute the offset along one axis that brings `[start, start + len]` into
+/// the visible range `[offset, offset + visible]`, if it is not already there.
+fn reveal_offset(start: f32, len: f32, offset: f32, visible: f32) -> f32 {
+    if start < offset {
+        (start - REVEAL_MARGIN).max(0.0)
+    } else if start + len > offset + visible {
+        // Larger-than-viewport targets align to their start
+        if len + REVEAL_MARGIN * 2.0 > visible {
+            (start - REVEAL_MARGIN).max(0.0)
+        } else {
+            start + len - visible + REVEAL_MARGIN
+        }
+    } else {
+        offset
+    }
+}
+
+impl<T: 'static> Operation<T> for ScrollIntoView {
+    fn scrollable(
+        &mut self,
+        _state: &mut dyn Scrollable,
+        id: Option<&Id>,
+        bounds: Rectangle,
+        content_bounds: Rectangle,
+        translation: Vector,
+    ) {
+        if id == Some(&self.scrollable) {
+            self.viewport = Some((bounds, content_bounds, translation));
+        }
+    }
+
+    fn container(
+        &mut self,
+        id: Option<&Id>,
+        bounds: Rectangle,
+        operate_on_children: &mut dyn FnMut(&mut dyn Operation<T>),
+    ) {
+        if id == Some(&self.target) {
+            self.target_bounds = Some(bounds);
+            return;
+        }
+        operate_on_children(self);
+    }
+
+    fn finish(&self) -> Outcome<T> {
+        let (Some((bounds, content, translation)), Some(target)) =
+            (self.viewport, self.target_bounds)
+        else {
+            return Outcome::None;
+        };
+
+        // Target position relative to the unscrolled content
+        let x = target.x - content.x;
+        let y = target.y - content.y;
+
+        let offset = AbsoluteOffset {
+            x: reveal_offset(x, target.width, translation.x, bounds.width),
+            y: reveal_offset(y, target.height, translation.y, bounds.height),
+        };
+
+        if offset.x == translation.x && offset.y == translation.y {
+            return Outcome::None;
+        }
+
+        Outcome::Chain(Box::new(operation::scrollable::scroll_to(
+            self.scrollable.clone(),
+            offset,
+        )))
+    }
+}
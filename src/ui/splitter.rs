@@ -0,0 +1,84 @@
+//! Draggable splitters between panels.
+//!
+//! A splitter is a thin handle that starts a resize drag when pressed.
+//! The drag itself is tracked in `App` from global cursor events, so it
+//! keeps following the cursor after it leaves the handle.
+
+use iced::widget::{container, mouse_area, Space};
+use iced::{mouse, Color, Element, Length, Point};
+
+use crate::app::Message;
+
+/// Thickness of the splitter handle in logical pixels.
+const HANDLE_SIZE: f32 = 6.0;
+
+/// A panel size that can be resized with a splitter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Split {
+    /// Height of the tree view below the canvas.
+    TreeHeight,
+}
+
+impl Split {
+    /// Smallest and largest allowed size.
+    pub fn range(self) -> (f32, f32) {
+        match self {
+            Split::TreeHeight => (80.0, 600.0),
+        }
+    }
+
+    /// Clamp a size to the allowed range.
+    pub fn clamp(self, size: f32) -> f32 {
+        let (min, max) = self.range();
+        size.clamp(min, max)
+    }
+
+    /// Whether the handle is dragged vertically rather than horizontally.
+    fn is_vertical(self) -> bool {
+        matches!(self, Split::TreeHeight)
+    }
+
+    /// Size after moving the cursor from `anchor` to `position`.
+    ///
+    /// The resized panel sits after the handle, so dragging towards it shrinks it.
+    pub fn resize(self, start_size: f32, anchor: Point, position: Point) -> f32 {
+        let delta = if self.is_vertical() {
+            position.y - anchor.y
+        } else {
+            position.x - anchor.x
+        };
+        self.clamp(start_size - delta)
+    }
+}
+
+/// A resize drag in progress.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SplitterDrag {
+    /// The size being resized.
+    pub split: Split,
+    /// Cursor position and size when the drag started, set on the first move.
+    pub anchor: Option<(Point, f32)>,
+}
+
+/// Render a splitter handle for `split`.
+pub fn splitter<'a>(split: Split, active: bool) -> Element<'a, Message> {
+    let (width, height, interaction) = if split.is_vertical() {
+        (Length::Fill, Length::Fixed(HANDLE_SIZE), mouse::Interaction::ResizingVertically)
+    } else {
+        (Length::Fixed(HANDLE_SIZE), Length::Fill, mouse::Interaction::ResizingHorizontally)
+    };
+
+    let color = if active {
+        Color::from_rgb(0.3, 0.6, 1.0)
+    } else {
+        Color::from_rgb(0.25, 0.25, 0.25)
+    };
+
+    mouse_area(
+        container(Space::new(width, height))
+            .style(move |_theme| container::background(color)),
+    )
+    .on_press(Message::SplitterPressed(split))
+    .interaction(interaction)
+    .into()
+}
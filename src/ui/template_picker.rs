@@ -0,0 +1,80 @@
+//! Template picker for new projects.
+//!
+//! After a folder is chosen for a new project, a modal asks which template
+//! to start from. The same template cards appear on the welcome screen.
+
+use std::path::Path;
+
+use iced::widget::{button, column, container, horizontal_space, opaque, row, text, Row};
+use iced::{Color, Element, Length};
+
+use crate::app::Message;
+use crate::model::Template;
+use crate::ui::metrics;
+
+/// The template picker.
+pub struct TemplatePicker;
+
+impl TemplatePicker {
+    /// Render a card for each template, sending `on_pick` with the chosen one.
+    pub fn cards(on_pick: impl Fn(Template) -> Message) -> Row<'static, Message> {
+        let muted = Color::from_rgb(0.6, 0.6, 0.6);
+        let cards = Template::ALL.into_iter().map(|template| {
+            button(
+                column![
+                    text(template.name()).size(metrics::text_lg()),
+                    text(template.description()).size(metrics::text_sm()).color(muted),
+                ]
+                .spacing(4),
+            )
+            .on_press(on_pick(template))
+            .style(button::secondary)
+            .width(Length::Fill)
+            .padding(metrics::pad_panel())
+            .into()
+        });
+        row(cards).spacing(8)
+    }
+
+    /// Render the picker for a new project in `folder`, centered over a
+    /// dimmed backdrop.
+    pub fn view(folder: &Path) -> Element<'_, Message> {
+        let content = column![
+            text("Choose a template").size(metrics::heading()),
+            text(format!("New project in {}", folder.display()))
+                .size(metrics::text_sm())
+                .color(Color::from_rgb(0.5, 0.5, 0.5)),
+            Self::cards(|template| Message::CreateProjectAt(folder.to_path_buf(), template)),
+            row![
+                horizontal_space(),
+                button(text("Cancel").size(metrics::text_md()))
+                    .on_press(Message::CancelTemplatePicker)
+                    .style(button::secondary)
+                    .padding(metrics::pad()),
+            ],
+        ]
+        .spacing(12);
+
+        let card = container(content)
+            .width(Length::Fixed(metrics::scaled(640.0)))
+            .padding(metrics::scaled(16.0))
+            .style(|theme: &iced::Theme| container::Style {
+                background: Some(theme.extended_palette().background.base.color.into()),
+                border: iced::Border {
+                    color: theme.extended_palette().background.strong.color,
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                ..Default::default()
+            });
+
+        opaque(
+            container(card)
+                .center(Length::Fill)
+                .style(|_theme| container::Style {
+                    background: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.5).into()),
+                    ..Default::default()
+                }),
+        )
+    }
+}
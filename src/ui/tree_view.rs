@@ -1,75 +1,467 @@
 //! Tree view for hierarchical layout navigation.
 //!
 //! Displays the component tree in a collapsible, hierarchical format
-//! similar to a DOM inspector.
+//! similar to a DOM inspector. Rows can be dragged onto containers or
+//! between siblings to move nodes, and right-clicked for a context menu.
+//!
+//! Rendering is virtualized: the visible rows are flattened first, and
+//! widgets are only built for rows near the scroll viewport.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
 
-use iced::widget::{button, column, container, row, scrollable, text, Space};
-use iced::{Color, Element, Length};
+use iced::widget::{
+    button, column, container, horizontal_rule, horizontal_space, mouse_area, row, scrollable, stack,
+    text, text_input, Space,
+};
+use iced::{mouse, Color, Element, Length};
 
 use crate::app::Message;
 use crate::model::{layout::WidgetType, ComponentId, LayoutNode};
+use crate::ui::metrics;
+
+/// Width of the expand/collapse toggle.
+const DISCLOSURE_WIDTH: f32 = 12.0;
+
+/// Height of a single tree row in logical pixels.
+pub const ROW_HEIGHT: f32 = 24.0;
+
+/// Padding around the rows inside the scrollable.
+const CONTENT_PADDING: f32 = 10.0;
+
+/// Rows built above and below the viewport so fast scrolling stays filled.
+const OVERSCAN_ROWS: usize = 10;
+
+/// Viewport height assumed before the scrollable reports its size.
+const FALLBACK_VIEWPORT_HEIGHT: f32 = 800.0;
+
+/// Color of the drop indicator line and the drop-inside highlight.
+const DROP_INDICATOR_COLOR: Color = Color::from_rgb(0.3, 0.6, 1.0);
+
+/// Where a dragged row lands relative to the row under the cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropPosition {
+    /// Insert as the previous sibling.
+    Before,
+    /// Append as the last child.
+    Inside,
+    /// Insert as the next sibling.
+    After,
+}
+
+impl DropPosition {
+    /// Pick a drop position from the cursor's height within a row.
+    ///
+    /// Containers reserve the middle half of the row for dropping inside;
+    /// the root only accepts drops inside.
+    fn from_row_offset(y: f32, accepts_children: bool, is_root: bool) -> Self {
+        if is_root {
+            Self::Inside
+        } else if accepts_children {
+            if y < ROW_HEIGHT * 0.25 {
+                Self::Before
+            } else if y > ROW_HEIGHT * 0.75 {
+                Self::After
+            } else {
+                Self::Inside
+            }
+        } else if y < ROW_HEIGHT * 0.5 {
+            Self::Before
+        } else {
+            Self::After
+        }
+    }
+}
+
+/// A tree row drag in progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeDrag {
+    /// The node being dragged.
+    pub source: ComponentId,
+    /// The row under the cursor and where the node would land.
+    pub target: Option<(ComponentId, DropPosition)>,
+}
+
+/// Tree view state kept in `App` between frames.
+#[derive(Debug, Default)]
+pub struct TreeState {
+    /// Nodes whose children are hidden.
+    pub collapsed: HashSet<ComponentId>,
+    /// Row drag in progress, if any.
+    pub drag: Option<TreeDrag>,
+    /// Whether the tree has keyboard focus for row navigation.
+    pub focused: bool,
+    /// Scroll offset and height of the tree viewport, once known.
+    pub viewport: Option<(f32, f32)>,
+    /// Find text; rows matching it by name or content are highlighted.
+    pub search: String,
+    /// Descendant counts for the cached layout revision.
+    counts: RefCell<Option<(u64, HashMap<ComponentId, usize>)>>,
+}
+
+impl TreeState {
+    /// Whether a node's children are hidden.
+    pub fn is_collapsed(&self, id: ComponentId) -> bool {
+        self.collapsed.contains(&id)
+    }
+
+    /// Toggle whether a node's children are hidden.
+    pub fn toggle(&mut self, id: ComponentId) {
+        if !self.collapsed.remove(&id) {
+            self.collapsed.insert(id);
+        }
+    }
+
+    /// Show the children of every node.
+    pub fn expand_all(&mut self) {
+        self.collapsed.clear();
+    }
+
+    /// Number of nodes below `id`, counted once per layout revision.
+    fn descendant_count(&self, root: &LayoutNode, revision: u64, id: ComponentId) -> usize {
+        fn visit(node: &LayoutNode, counts: &mut HashMap<ComponentId, usize>) -> usize {
+            let below = node
+                .child_nodes()
+                .into_iter()
+                .map(|child| 1 + visit(child, counts))
+                .sum();
+            counts.insert(node.id, below);
+            below
+        }
+
+        let mut cache = self.counts.borrow_mut();
+        if cache.as_ref().is_none_or(|(cached, _)| *cached != revision) {
+            let mut counts = HashMap::new();
+            visit(root, &mut counts);
+            *cache = Some((revision, counts));
+        }
+        cache
+            .as_ref()
+            .and_then(|(_, counts)| counts.get(&id).copied())
+            .unwrap_or(0)
+    }
+
+    /// Hide the children of every node that has any.
+    pub fn collapse_all(&mut self, root: &LayoutNode) {
+        fn visit(node: &LayoutNode, collapsed: &mut HashSet<ComponentId>) {
+            let children = node.child_nodes();
+            if !children.is_empty() {
+                collapsed.insert(node.id);
+            }
+            for child in children {
+                visit(child, collapsed);
+            }
+        }
+        self.collapsed.clear();
+        visit(root, &mut self.collapsed);
+    }
+}
+
+/// A visible row: a node and its nesting depth.
+#[derive(Debug, Clone, Copy)]
+pub struct TreeRow<'a> {
+    /// The node shown on this row.
+    pub node: &'a LayoutNode,
+    /// Nesting depth, zero for the root.
+    pub depth: usize,
+}
+
+/// Range of rows to build for a viewport, including overscan.
+pub fn row_window(row_count: usize, viewport: Option<(f32, f32)>) -> Range<usize> {
+    let (offset, height) = viewport.unwrap_or((0.0, FALLBACK_VIEWPORT_HEIGHT));
+    let first = ((offset - CONTENT_PADDING).max(0.0) / ROW_HEIGHT) as usize;
+    let last = ((offset + height - CONTENT_PADDING).max(0.0) / ROW_HEIGHT).ceil() as usize;
+
+    let start = first.saturating_sub(OVERSCAN_ROWS).min(row_count);
+    let end = (last + OVERSCAN_ROWS).min(row_count);
+    start..end
+}
 
 /// The tree view component.
 pub struct TreeView;
 
 impl TreeView {
+    /// Identifier of the tree view scrollable.
+    pub fn scrollable_id() -> scrollable::Id {
+        scrollable::Id::new("tree-view")
+    }
+
+    /// Identifier of the find input in the tree header.
+    pub fn search_id() -> text_input::Id {
+        text_input::Id::new("tree-search")
+    }
+
+    /// Get the relative scroll offset that brings a node's row into view.
+    ///
+    /// Rows are listed in pre-order, so the offset is the row's position
+    /// among all visible rows.
+    pub fn scroll_offset_for(
+        root: &LayoutNode,
+        id: ComponentId,
+        state: &TreeState,
+    ) -> Option<scrollable::RelativeOffset> {
+        let rows = Self::visible_rows(root, state);
+        let row = rows.iter().position(|&row| row == id)?;
+        let y = if rows.len() > 1 {
+            row as f32 / (rows.len() - 1) as f32
+        } else {
+            0.0
+        };
+        Some(scrollable::RelativeOffset { x: 0.0, y })
+    }
+
+    /// List the visible rows in display order, skipping collapsed subtrees.
+    pub fn visible_rows(root: &LayoutNode, state: &TreeState) -> Vec<ComponentId> {
+        Self::flatten(root, state).into_iter().map(|row| row.node.id).collect()
+    }
+
+    /// Flatten the visible rows in display order.
+    ///
+    /// Collapsed subtrees are never entered, so this is linear in the
+    /// number of visible rows rather than the size of the layout.
+    pub fn flatten<'a>(root: &'a LayoutNode, state: &TreeState) -> Vec<TreeRow<'a>> {
+        fn visit<'a>(node: &'a LayoutNode, depth: usize, state: &TreeState, rows: &mut Vec<TreeRow<'a>>) {
+            rows.push(TreeRow { node, depth });
+            if state.is_collapsed(node.id) {
+                return;
+            }
+            for child in node.child_nodes() {
+                visit(child, depth + 1, state, rows);
+            }
+        }
+
+        let mut rows = Vec::new();
+        visit(root, 0, state, &mut rows);
+        rows
+    }
+
     /// Render the tree view.
     pub fn view<'a>(
         root: &'a LayoutNode,
+        revision: u64,
         selected_id: Option<ComponentId>,
+        state: &TreeState,
+        dropping: bool,
     ) -> Element<'a, Message> {
-        let content = Self::render_node(root, selected_id, 0);
+        let rows = Self::flatten(root, state);
+        let window = row_window(rows.len(), state.viewport);
+
+        // Spacers stand in for the off-screen rows so the scroll range is unchanged
+        let spacer = |count: usize| Space::new(Length::Fill, Length::Fixed(count as f32 * ROW_HEIGHT));
+        let content = column![spacer(window.start)]
+            .extend(rows[window.clone()].iter().map(|row| {
+                let descendants = state.descendant_count(root, revision, row.node.id);
+                Self::render_row(*row, descendants, selected_id, state, dropping)
+            }))
+            .push(spacer(rows.len() - window.end));
+
+        let header_button = |label, message| {
+            button(text(label).size(metrics::text_sm()))
+                .on_press(message)
+                .padding(metrics::pad_sm())
+                .style(button::text)
+        };
+        let search = text_input("Find (Ctrl+F)", &state.search)
+            .id(Self::search_id())
+            .on_input(Message::TreeSearchChanged)
+            .on_submit(Message::FindNext)
+            .size(metrics::text_sm())
+            .padding(metrics::pad_xy(2.0, 4.0))
+            .width(Length::Fixed(metrics::scaled(120.0)));
+        let header = row![
+            text("Layers").size(metrics::text_md()),
+            horizontal_space(),
+            search,
+            header_button("Expand All", Message::ExpandAllTree),
+            header_button("Collapse All", Message::CollapseAllTree),
+        ]
+        .spacing(4)
+        .padding(metrics::pad_xy(2.0, 8.0))
+        .align_y(iced::Alignment::Center);
 
-        container(scrollable(
-            container(content).padding(10).width(Length::Fill),
-        ).height(Length::Fill))
+        let focused = state.focused;
+        let panel = container(column![
+            header,
+            horizontal_rule(1),
+            scrollable(container(content).padding(CONTENT_PADDING).width(Length::Fill))
+                .id(Self::scrollable_id())
+                .on_scroll(Message::TreeScrolled)
+                .height(Length::Fill),
+        ])
         .width(Length::Fill)
-        .height(Length::Fixed(200.0))
-        .into()
+        .height(Length::Fill)
+        .style(move |_theme| container::Style {
+            border: iced::Border {
+                color: if focused {
+                    DROP_INDICATOR_COLOR.scale_alpha(0.6)
+                } else {
+                    Color::TRANSPARENT
+                },
+                width: 1.0,
+                radius: 0.0.into(),
+            },
+            ..Default::default()
+        });
+
+        // Presses that no row handles still give the tree focus
+        mouse_area(panel).on_press(Message::FocusTree).into()
     }
 
-    /// Recursively render a node and its children.
-    fn render_node<'a>(
-        node: &'a LayoutNode,
+    /// Render a single row.
+    fn render_row<'a>(
+        row: TreeRow<'a>,
+        descendants: usize,
         selected_id: Option<ComponentId>,
-        depth: usize,
+        state: &TreeState,
+        dropping: bool,
     ) -> Element<'a, Message> {
+        let TreeRow { node, depth } = row;
+        let drag = state.drag;
+        let has_children = !node.child_nodes().is_empty();
+        let collapsed = state.is_collapsed(node.id);
+
         let is_selected = selected_id == Some(node.id);
+        let query = state.search.trim();
+        let is_match = !query.is_empty() && (node.matches_name(query) || node.matches_content(query));
+        let is_dragged = drag.is_some_and(|d| d.source == node.id);
         let indent = Space::new(Length::Fixed((depth * 16) as f32), Length::Shrink);
 
         let icon = Self::get_icon(&node.widget);
-        let name = Self::get_name(&node.widget);
+        let name = node.label();
 
-        let label_color = if is_selected {
+        let label_color = if is_dragged || node.hidden {
+            Color::from_rgb(0.5, 0.5, 0.5)
+        } else if is_selected {
             Color::from_rgb(0.3, 0.7, 1.0)
+        } else if is_match {
+            Color::from_rgb(1.0, 0.8, 0.3)
         } else {
             Color::from_rgb(0.8, 0.8, 0.8)
         };
 
+        let disclosure: Element<'a, Message> = if !has_children {
+            Space::new(Length::Fixed(DISCLOSURE_WIDTH), Length::Shrink).into()
+        } else {
+            button(text(if collapsed { "▸" } else { "▾" }).size(metrics::text_xs()))
+                .on_press(Message::ToggleTreeNode(node.id))
+                .width(Length::Fixed(DISCLOSURE_WIDTH))
+                .padding(0)
+                .style(button::text)
+                .into()
+        };
+
+        let count: Element<'a, Message> = if Self::is_container(&node.widget) {
+            text(format!(" ({descendants})"))
+                .size(metrics::text_sm())
+                .color(Color::from_rgb(0.5, 0.5, 0.5))
+                .into()
+        } else {
+            Space::new(Length::Shrink, Length::Shrink).into()
+        };
+
+        let visibility: Element<'a, Message> = if depth == 0 {
+            Space::new(Length::Shrink, Length::Shrink).into()
+        } else {
+            button(text(if node.hidden { "○" } else { "●" }).size(metrics::text_xs()).color(label_color))
+                .on_press(Message::ToggleNodeHidden(node.id))
+                .padding(metrics::pad_xy(0.0, 4.0))
+                .style(button::text)
+                .into()
+        };
+
         let node_row = row![
             indent,
-            text(icon).size(12),
+            disclosure,
+            text(icon).size(metrics::text_md()),
             Space::new(Length::Fixed(4.0), Length::Shrink),
-            button(text(name).size(12).color(label_color))
-                .on_press(Message::SelectComponent(node.id))
-                .padding(2)
-                .style(|_theme, _status| button::Style {
-                    background: None,
-                    ..Default::default()
-                }),
+            text(name).size(metrics::text_md()).color(label_color),
+            count,
+            horizontal_space(),
+            visibility,
         ]
+        .width(Length::Fill)
+        .padding(metrics::pad_xy(0.0, 2.0))
         .align_y(iced::Alignment::Center);
 
-        // Render children
-        let children = Self::get_children(node);
-        if children.is_empty() {
-            node_row.into()
-        } else {
-            let mut col = column![node_row].spacing(2);
-            for child in children {
-                col = col.push(Self::render_node(child, selected_id, depth + 1));
-            }
-            col.into()
+        let drop = drag
+            .and_then(|d| d.target)
+            .filter(|(target, _)| *target == node.id)
+            .map(|(_, position)| position);
+        let node_row = Self::render_drop_indicator(node_row.into(), drop);
+
+        let id = node.id;
+        let mut area = mouse_area(node_row)
+            .on_press(Message::TreeDragStart(id))
+            .on_right_press(Message::OpenContextMenu(id))
+            .interaction(if drag.is_some() {
+                mouse::Interaction::Grabbing
+            } else {
+                mouse::Interaction::Pointer
+            });
+        if dropping {
+            area = area.on_release(Message::PaletteDropOn(id));
+        }
+        if drag.is_some() {
+            let accepts = node.can_accept_child();
+            let is_root = depth == 0;
+            area = area.on_move(move |point| {
+                Message::TreeDragOver(id, DropPosition::from_row_offset(point.y, accepts, is_root))
+            });
+        }
+
+        area.into()
+    }
+
+    /// Whether the widget type holds children.
+    fn is_container(widget: &WidgetType) -> bool {
+        matches!(
+            widget,
+            WidgetType::Column { .. }
+                | WidgetType::Row { .. }
+                | WidgetType::Stack { .. }
+                | WidgetType::Container { .. }
+                | WidgetType::Scrollable { .. }
+        )
+    }
+
+    /// Wrap a row with the drop indicator for the given position.
+    ///
+    /// Sibling drops draw a line on the row edge; inside drops highlight the row.
+    fn render_drop_indicator<'a>(
+        node_row: Element<'a, Message>,
+        drop: Option<DropPosition>,
+    ) -> Element<'a, Message> {
+        let row_box = container(node_row)
+            .width(Length::Fill)
+            .height(Length::Fixed(ROW_HEIGHT))
+            .align_y(iced::alignment::Vertical::Center);
+
+        let line = |align: iced::alignment::Vertical| {
+            container(
+                container(Space::new(Length::Fill, Length::Fixed(2.0)))
+                    .style(|_theme| container::background(DROP_INDICATOR_COLOR)),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_y(align)
+        };
+
+        match drop {
+            None => row_box.into(),
+            Some(DropPosition::Inside) => row_box
+                .style(|_theme| container::Style {
+                    background: Some(DROP_INDICATOR_COLOR.scale_alpha(0.25).into()),
+                    border: iced::Border {
+                        color: DROP_INDICATOR_COLOR,
+                        width: 1.0,
+                        radius: 2.0.into(),
+                    },
+                    ..Default::default()
+                })
+                .into(),
+            Some(DropPosition::Before) => stack![row_box, line(iced::alignment::Vertical::Top)].into(),
+            Some(DropPosition::After) => stack![row_box, line(iced::alignment::Vertical::Bottom)].into(),
         }
     }
 
@@ -88,37 +480,80 @@ impl TreeView {
             WidgetType::Slider { .. } => "─●",
             WidgetType::PickList { .. } => "▼",
             WidgetType::Space { .. } => "·",
+            WidgetType::ComponentRef { .. } => "⧉",
         }
     }
+}
 
-    /// Get a display name for the widget.
-    fn get_name(widget: &WidgetType) -> &'static str {
-        match widget {
-            WidgetType::Column { .. } => "Column",
-            WidgetType::Row { .. } => "Row",
-            WidgetType::Container { .. } => "Container",
-            WidgetType::Scrollable { .. } => "Scrollable",
-            WidgetType::Stack { .. } => "Stack",
-            WidgetType::Text { .. } => "Text",
-            WidgetType::Button { .. } => "Button",
-            WidgetType::TextInput { .. } => "TextInput",
-            WidgetType::Checkbox { .. } => "Checkbox",
-            WidgetType::Slider { .. } => "Slider",
-            WidgetType::PickList { .. } => "PickList",
-            WidgetType::Space { .. } => "Space",
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::layout::{ContainerAttrs, TextAttrs};
+
+    fn column_of(children: Vec<LayoutNode>) -> LayoutNode {
+        LayoutNode::new(WidgetType::Column {
+            children,
+            attrs: ContainerAttrs::default(),
+        })
     }
 
-    /// Get children of a node.
-    fn get_children(node: &LayoutNode) -> Vec<&LayoutNode> {
-        match &node.widget {
-            WidgetType::Column { children, .. }
-            | WidgetType::Row { children, .. }
-            | WidgetType::Stack { children, .. } => children.iter().collect(),
-            WidgetType::Container { child, .. } | WidgetType::Scrollable { child, .. } => {
-                child.as_ref().map(|c| vec![c.as_ref()]).unwrap_or_default()
-            }
-            _ => Vec::new(),
+    /// A root column with 100 groups of 9 texts: 1,001 nodes.
+    fn large_layout() -> LayoutNode {
+        column_of(
+            (0..100)
+                .map(|_| {
+                    column_of(
+                        (0..9)
+                            .map(|i| {
+                                LayoutNode::new(WidgetType::Text {
+                                    content: format!("Item {i}"),
+                                    attrs: TextAttrs::default(),
+                                })
+                            })
+                            .collect(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_flatten_skips_collapsed_subtrees() {
+        let root = large_layout();
+        let mut state = TreeState::default();
+        assert_eq!(TreeView::flatten(&root, &state).len(), 1001);
+
+        state.collapse_all(&root);
+        assert_eq!(TreeView::flatten(&root, &state).len(), 1);
+
+        // Expanding only the root shows the groups but none of their texts
+        state.toggle(root.id);
+        let rows = TreeView::flatten(&root, &state);
+        assert_eq!(rows.len(), 101);
+        assert!(rows.iter().skip(1).all(|row| row.depth == 1));
+    }
+
+    #[test]
+    fn test_row_window_is_bounded_by_viewport() {
+        let window = row_window(1001, Some((ROW_HEIGHT * 500.0, ROW_HEIGHT * 20.0)));
+        assert!(window.contains(&500));
+        assert!(window.contains(&519));
+        assert!(window.len() <= 20 + 2 * OVERSCAN_ROWS + 1);
+
+        // Short lists are built whole, and the window never runs past the end
+        assert_eq!(row_window(5, None), 0..5);
+        assert_eq!(row_window(1001, Some((ROW_HEIGHT * 1000.0, 400.0))).end, 1001);
+    }
+
+    #[test]
+    fn test_descendant_counts_follow_revision() {
+        let mut root = large_layout();
+        let state = TreeState::default();
+        assert_eq!(state.descendant_count(&root, 0, root.id), 1000);
+
+        if let WidgetType::Column { children, .. } = &mut root.widget {
+            children.truncate(10);
         }
+        assert_eq!(state.descendant_count(&root, 1, root.id), 100);
     }
 }
@@ -0,0 +1,111 @@
+//! Welcome screen shown in place of the canvas when no project is open.
+//!
+//! Offers the ways to get started: a new project, an existing one, a
+//! project seeded from one of the built-in templates, or a recently opened
+//! project. Template cards ask for a folder before creating the project.
+//! While projects are being read in the background, a loading notice takes
+//! the canvas's place instead.
+
+use std::path::PathBuf;
+
+use iced::widget::{button, column, container, row, scrollable, text, Space};
+use iced::{Color, Element, Length};
+
+use crate::app::Message;
+use crate::io::RecentProject;
+use crate::ui::metrics;
+use crate::ui::template_picker::TemplatePicker;
+
+/// Width of the welcome content.
+const CONTENT_WIDTH: f32 = 560.0;
+
+/// The welcome screen.
+pub struct Welcome;
+
+impl Welcome {
+    /// Render the notice shown while the projects at `paths` are opening.
+    pub fn loading(paths: &[PathBuf]) -> Element<'_, Message> {
+        let names: Vec<String> = paths
+            .iter()
+            .map(|path| path.file_name().unwrap_or(path.as_os_str()).to_string_lossy().into_owned())
+            .collect();
+        container(
+            column![
+                text(format!("Loading {}…", names.join(", "))).size(metrics::title()),
+                text("Large layouts can take a moment to read.")
+                    .size(metrics::text_md())
+                    .color(Color::from_rgb(0.6, 0.6, 0.6)),
+            ]
+            .spacing(6)
+            .align_x(iced::Alignment::Center),
+        )
+        .center(Length::Fill)
+        .into()
+    }
+
+    /// Render the welcome screen with the recently opened projects.
+    pub fn view(recent: &[RecentProject]) -> Element<'_, Message> {
+        let muted = Color::from_rgb(0.6, 0.6, 0.6);
+
+        let big_button = |label: &'static str, message: Message| {
+            button(text(label).size(metrics::title()).center())
+                .on_press(message)
+                .width(Length::Fill)
+                .padding(metrics::pad_xy(12.0, 16.0))
+        };
+        let actions = row![
+            big_button("New Project", Message::NewProject),
+            big_button("Open Project", Message::OpenProject),
+        ]
+        .spacing(12);
+
+        let mut content = column![
+            text("Iced Builder").size(metrics::scaled(24.0)),
+            text("Design iced layouts visually and export them as Rust code. Drop a project folder here to open it.")
+                .size(metrics::text_md())
+                .color(muted),
+            Space::with_height(8),
+            actions,
+            Space::with_height(8),
+            text("Start from a template").size(metrics::title()),
+            TemplatePicker::cards(Message::NewProjectFromTemplate),
+        ]
+        .spacing(6)
+        .width(Length::Fixed(metrics::scaled(CONTENT_WIDTH)));
+
+        if !recent.is_empty() {
+            content = content
+                .push(Space::with_height(8))
+                .push(text("Recent projects").size(metrics::title()));
+        }
+        for project in recent {
+            content = content.push(
+                button(
+                    row![
+                        column![
+                            text(&project.name).size(metrics::text_lg()),
+                            text(project.path.display().to_string()).size(metrics::text_sm()).color(muted),
+                        ]
+                        .spacing(2)
+                        .width(Length::Fill),
+                        text(project.opened_ago()).size(metrics::text_sm()).color(muted),
+                    ]
+                    .align_y(iced::Alignment::Center),
+                )
+                .on_press(Message::OpenProjectAt(project.path.clone()))
+                .style(button::text)
+                .width(Length::Fill)
+                .padding(metrics::pad()),
+            );
+        }
+
+        container(scrollable(container(content).center_x(Length::Fill).padding(metrics::scaled(20.0))))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(|_theme| container::Style {
+                background: Some(iced::Background::Color(Color::from_rgb(0.15, 0.15, 0.15))),
+                ..Default::default()
+            })
+            .into()
+    }
+}
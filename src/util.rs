@@ -2,26 +2,150 @@
 //!
 //! Shared helpers for ID generation, formatting invocation, etc.
 
+pub mod diff;
+
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use thiserror::Error;
 
 /// Errors that can occur during formatting.
 #[derive(Debug, Error)]
 pub enum FormatError {
+    /// rustfmt is not installed.
     #[error("rustfmt not found in PATH")]
     RustfmtNotFound,
 
+    /// rustfmt exited with an error.
     #[error("rustfmt failed: {0}")]
     RustfmtFailed(String),
 
+    /// Running rustfmt failed.
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
+
+    /// The code is not valid Rust.
+    #[error("Failed to parse generated code: {0}")]
+    Parse(#[from] syn::Error),
 }
 
-/// Format Rust code using rustfmt.
+/// Formatter used for generated code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FormatBackend {
+    /// rustfmt from PATH, falling back to prettyplease when it can't be run.
+    #[default]
+    Rustfmt,
+    /// The built-in prettyplease printer.
+    PrettyPlease,
+    /// Leave code as generated.
+    None,
+}
+
+impl std::fmt::Display for FormatBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatBackend::Rustfmt => write!(f, "rustfmt"),
+            FormatBackend::PrettyPlease => write!(f, "prettyplease"),
+            FormatBackend::None => write!(f, "none"),
+        }
+    }
+}
+
+/// Code after formatting, with the backend that produced it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Formatted {
+    /// The code, formatted unless `backend` is `None`.
+    pub code: String,
+    /// `FormatBackend::None` when the code was left unformatted.
+    pub backend: FormatBackend,
+}
+
+/// rustfmt settings taken from the target project, so its own rustfmt
+/// leaves generated code alone.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RustfmtOptions {
+    /// The project's `rustfmt.toml` or `.rustfmt.toml`.
+    pub config_path: Option<PathBuf>,
+    /// The package edition from the project's `Cargo.toml`.
+    pub edition: Option<String>,
+}
+
+impl RustfmtOptions {
+    /// Look for a rustfmt config and the package edition in `root`. Anything
+    /// missing or unreadable is left out, so rustfmt uses its defaults.
+    pub fn detect(root: &Path) -> Self {
+        let config_path = ["rustfmt.toml", ".rustfmt.toml"]
+            .into_iter()
+            .map(|name| root.join(name))
+            .find(|path| path.is_file());
+        let edition = std::fs::read_to_string(root.join("Cargo.toml"))
+            .ok()
+            .and_then(|manifest| manifest.parse::<toml::Table>().ok())
+            .and_then(|manifest| {
+                // `edition.workspace = true` is a table, not an edition
+                manifest.get("package")?.get("edition")?.as_str().map(str::to_string)
+            });
+        Self { config_path, edition }
+    }
+
+    /// Arguments passing these settings to rustfmt.
+    fn args(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+        if let Some(path) = &self.config_path {
+            args.push(OsString::from("--config-path"));
+            args.push(path.clone().into_os_string());
+        }
+        if let Some(edition) = &self.edition {
+            args.push(OsString::from("--edition"));
+            args.push(OsString::from(edition));
+        }
+        args
+    }
+}
+
+/// Format Rust code with the preferred backend.
+///
+/// `Rustfmt` falls back to prettyplease when rustfmt is not installed.
+pub fn format_rust_code(code: &str, backend: FormatBackend, rustfmt: &RustfmtOptions) -> Result<Formatted, FormatError> {
+    let formatted = match backend {
+        FormatBackend::Rustfmt => match run_rustfmt(code, rustfmt) {
+            Err(FormatError::RustfmtNotFound) => {
+                tracing::debug!(target: "iced_builder::codegen", "rustfmt not found, using prettyplease");
+                return format_rust_code(code, FormatBackend::PrettyPlease, rustfmt);
+            }
+            result => result?,
+        },
+        FormatBackend::PrettyPlease => format_with_prettyplease(code)?,
+        FormatBackend::None => code.to_string(),
+    };
+    Ok(Formatted { code: formatted, backend })
+}
+
+/// Format Rust code with prettyplease.
 ///
-/// Returns the formatted code, or the original code with a warning if rustfmt fails.
-pub fn format_rust_code(code: &str) -> Result<String, FormatError> {
+/// Plain comments are not part of the syntax tree, so the leading comment
+/// block (the generated-file header) is carried over by hand.
+fn format_with_prettyplease(code: &str) -> Result<String, FormatError> {
+    let header_len: usize = code
+        .lines()
+        .take_while(|line| line.starts_with("//") && !line.starts_with("///"))
+        .map(|line| line.len() + 1)
+        .sum();
+    let (header, body) = code.split_at(header_len.min(code.len()));
+    let file = syn::parse_file(body)?;
+    let printed = prettyplease::unparse(&file);
+    if header.is_empty() {
+        Ok(printed)
+    } else {
+        Ok(format!("{}\n{}", header.trim_end(), printed))
+    }
+}
+
+/// Format Rust code using rustfmt.
+fn run_rustfmt(code: &str, options: &RustfmtOptions) -> Result<String, FormatError> {
     // Check if rustfmt is available
     let rustfmt_check = Command::new("rustfmt").arg("--version").output();
 
@@ -32,6 +156,7 @@ pub fn format_rust_code(code: &str) -> Result<String, FormatError> {
     // Run rustfmt
     let mut child = Command::new("rustfmt")
         .arg("--emit=stdout")
+        .args(options.args())
         .stdin(std::process::Stdio::piped())
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped())
@@ -46,6 +171,9 @@ pub fn format_rust_code(code: &str) -> Result<String, FormatError> {
     let output = child.wait_with_output()?;
 
     if output.status.success() {
+        if let Some(path) = &options.config_path {
+            tracing::debug!(target: "iced_builder::codegen", path = %path.display(), "Formatted with project rustfmt.toml");
+        }
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -53,17 +181,41 @@ pub fn format_rust_code(code: &str) -> Result<String, FormatError> {
     }
 }
 
-/// Try to format code, returning original on failure.
-pub fn try_format_rust_code(code: &str) -> String {
-    match format_rust_code(code) {
+/// Try to format code, returning the original unformatted on failure.
+pub fn try_format_rust_code(code: &str, backend: FormatBackend, rustfmt: &RustfmtOptions) -> Formatted {
+    match format_rust_code(code, backend, rustfmt) {
         Ok(formatted) => formatted,
         Err(e) => {
-            eprintln!("Warning: Could not format code: {}", e);
-            code.to_string()
+            tracing::warn!(target: "iced_builder::codegen", error = %e, "Could not format code");
+            Formatted {
+                code: code.to_string(),
+                backend: FormatBackend::None,
+            }
         }
     }
 }
 
+/// Try to format a single expression, returning it unchanged on failure.
+///
+/// Formatters only accept whole files, so the expression is formatted as
+/// the body of a function that is stripped again afterwards.
+pub fn try_format_rust_expr(expr: &str, backend: FormatBackend, rustfmt: &RustfmtOptions) -> String {
+    let wrapped = format!("fn snippet() {{\n{}\n}}\n", expr);
+    let formatted = try_format_rust_code(&wrapped, backend, rustfmt);
+    if formatted.backend == FormatBackend::None {
+        return expr.to_string();
+    }
+    let lines: Vec<&str> = formatted.code.trim_end().lines().collect();
+    match lines.as_slice() {
+        [first, body @ .., "}"] if first.starts_with("fn snippet()") => body
+            .iter()
+            .map(|line| line.strip_prefix("    ").unwrap_or(line))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => expr.to_string(),
+    }
+}
+
 /// Validate that a string is a valid Rust identifier.
 pub fn is_valid_rust_identifier(s: &str) -> bool {
     if s.is_empty() {
@@ -82,23 +234,145 @@ pub fn is_valid_rust_identifier(s: &str) -> bool {
     chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
 }
 
-/// List of Rust keywords that cannot be used as identifiers.
-pub const RUST_KEYWORDS: &[&str] = &[
+/// Strict Rust keywords, which cannot be used as plain identifiers.
+pub const STRICT_KEYWORDS: &[&str] = &[
     "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
     "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
     "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true",
     "type", "unsafe", "use", "where", "while",
 ];
 
-/// Check if a string is a Rust keyword.
+/// Keywords reserved for future use, which are rejected just like strict ones.
+pub const RESERVED_KEYWORDS: &[&str] = &[
+    "abstract", "become", "box", "do", "final", "gen", "macro", "override", "priv", "try", "typeof",
+    "unsized", "virtual", "yield",
+];
+
+/// Keywords only in some positions, which are fine as identifiers.
+pub const CONTEXTUAL_KEYWORDS: &[&str] = &["macro_rules", "raw", "safe", "union"];
+
+/// Check if a string is a strict or reserved Rust keyword.
 pub fn is_rust_keyword(s: &str) -> bool {
-    RUST_KEYWORDS.contains(&s)
+    STRICT_KEYWORDS.contains(&s) || RESERVED_KEYWORDS.contains(&s)
+}
+
+/// Check if a string is a contextual Rust keyword.
+pub fn is_contextual_keyword(s: &str) -> bool {
+    CONTEXTUAL_KEYWORDS.contains(&s)
+}
+
+/// Check if a keyword can be written as a raw identifier such as `r#type`.
+/// Path keywords like `self` and `crate` can't.
+pub fn is_raw_identifier_keyword(s: &str) -> bool {
+    is_rust_keyword(s) && !matches!(s, "crate" | "self" | "Self" | "super")
+}
+
+/// Write an identifier for generated code, as a raw identifier when it is
+/// a keyword, e.g. `r#type`.
+pub fn to_ident(s: &str) -> Cow<'_, str> {
+    if is_raw_identifier_keyword(s) {
+        Cow::Owned(format!("r#{}", s))
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+/// Convert free text such as a layout name into a PascalCase identifier.
+///
+/// Returns `fallback` when no identifier characters remain, and prefixes it
+/// when the result would start with a digit.
+pub fn to_pascal_case(s: &str, fallback: &str) -> String {
+    let name: String = s
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().unwrap_or_default().to_ascii_uppercase();
+            std::iter::once(first).chain(chars).collect::<String>()
+        })
+        .collect();
+    if name.is_empty() {
+        fallback.to_string()
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("{}{}", fallback, name)
+    } else {
+        name
+    }
+}
+
+/// Convert free text such as a layout name into a snake_case identifier.
+///
+/// Uses the same word splitting and fallback rules as `to_pascal_case`.
+pub fn to_snake_case(s: &str, fallback: &str) -> String {
+    let mut name = String::new();
+    for word in s.split(|c: char| !c.is_ascii_alphanumeric()).filter(|w| !w.is_empty()) {
+        if !name.is_empty() {
+            name.push('_');
+        }
+        name.push_str(&word.to_ascii_lowercase());
+    }
+    if name.is_empty() {
+        fallback.to_string()
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("{}_{}", fallback, name)
+    } else {
+        name
+    }
+}
+
+/// Shorten text to at most `max_chars` characters, ending with an ellipsis if cut.
+///
+/// Only the first line is kept, so multi-line content stays on one row.
+pub fn truncate_with_ellipsis(s: &str, max_chars: usize) -> Cow<'_, str> {
+    let line = s.lines().next().unwrap_or("");
+    let multi_line = line.len() < s.trim_end().len();
+
+    if !multi_line && line.chars().count() <= max_chars {
+        return Cow::Borrowed(line);
+    }
+
+    let kept: String = line.chars().take(max_chars.saturating_sub(1)).collect();
+    Cow::Owned(format!("{}…", kept.trim_end()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_prettyplease_formats_stably() {
+        let code = "// Header line\n// Second line\n\nuse iced::Element;\npub fn view(state:&AppState)->Element<Message>{column![text(\"Hi\"),button(\"Go\").on_press(Message::Go)].spacing(10).into()}\n";
+        let formatted = format_rust_code(code, FormatBackend::PrettyPlease, &RustfmtOptions::default()).unwrap();
+        assert_eq!(formatted.backend, FormatBackend::PrettyPlease);
+        assert_eq!(
+            formatted.code,
+            "// Header line\n// Second line\nuse iced::Element;\npub fn view(state: &AppState) -> Element<Message> {\n    column![text(\"Hi\"), button(\"Go\").on_press(Message::Go)].spacing(10).into()\n}\n"
+        );
+
+        // Formatting again changes nothing
+        let again = format_rust_code(&formatted.code, FormatBackend::PrettyPlease, &RustfmtOptions::default()).unwrap();
+        assert_eq!(again.code, formatted.code);
+    }
+
+    #[test]
+    fn test_format_backend_none_and_invalid_code() {
+        let formatted = format_rust_code("fn  x ( ) { }", FormatBackend::None, &RustfmtOptions::default()).unwrap();
+        assert_eq!(formatted.code, "fn  x ( ) { }");
+
+        let fallback = try_format_rust_code("fn broken(", FormatBackend::PrettyPlease, &RustfmtOptions::default());
+        assert_eq!(fallback.code, "fn broken(");
+        assert_eq!(fallback.backend, FormatBackend::None);
+    }
+
+    #[test]
+    fn test_truncate_with_ellipsis() {
+        assert_eq!(truncate_with_ellipsis("Save", 24), "Save");
+        assert_eq!(truncate_with_ellipsis("abcdefghij", 5), "abcd…");
+        assert_eq!(truncate_with_ellipsis("héllo wörld", 6), "héllo…");
+        assert_eq!(truncate_with_ellipsis("first\nsecond", 24), "first…");
+        assert_eq!(truncate_with_ellipsis("", 24), "");
+    }
+
     #[test]
     fn test_is_valid_rust_identifier() {
         assert!(is_valid_rust_identifier("foo"));
@@ -122,6 +396,27 @@ mod tests {
         assert!(!is_rust_keyword("myStruct"));
     }
 
+    #[test]
+    fn test_reserved_and_contextual_keywords() {
+        for reserved in ["abstract", "become", "box", "do", "final", "macro", "override", "priv", "try", "typeof", "unsized", "virtual", "yield"] {
+            assert!(is_rust_keyword(reserved), "{}", reserved);
+        }
+        assert!(!is_rust_keyword("union"));
+        assert!(is_contextual_keyword("union"));
+        assert!(!is_contextual_keyword("type"));
+    }
+
+    #[test]
+    fn test_to_ident_uses_raw_identifiers() {
+        assert_eq!(to_ident("name"), "name");
+        assert_eq!(to_ident("type"), "r#type");
+        assert_eq!(to_ident("try"), "r#try");
+        assert_eq!(to_ident("union"), "union");
+        // Path keywords have no raw form
+        assert_eq!(to_ident("self"), "self");
+        assert!(!is_raw_identifier_keyword("Self"));
+    }
+
     #[test]
     fn test_is_valid_rust_identifier_unicode() {
         // ASCII only for identifiers
@@ -138,12 +433,68 @@ mod tests {
         assert!(!is_valid_rust_identifier("0_"));
     }
 
+    #[test]
+    fn test_to_pascal_case() {
+        assert_eq!(to_pascal_case("Login Form", "Layout"), "LoginForm");
+        assert_eq!(to_pascal_case("user-profile_card", "Layout"), "UserProfileCard");
+        assert_eq!(to_pascal_case("myWidget", "Layout"), "MyWidget");
+        assert_eq!(to_pascal_case("2 columns", "Layout"), "Layout2Columns");
+        assert_eq!(to_pascal_case("  !! ", "Layout"), "Layout");
+    }
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("Login Form", "layout"), "login_form");
+        assert_eq!(to_snake_case("user-Profile", "layout"), "user_profile");
+        assert_eq!(to_snake_case("2 columns", "layout"), "layout_2_columns");
+        assert_eq!(to_snake_case("", "layout"), "layout");
+    }
+
     #[test]
     fn test_try_format_rust_code() {
         let code = "fn main() { println!(\"hello\"); }";
-        let result = try_format_rust_code(code);
+        let result = try_format_rust_code(code, FormatBackend::Rustfmt, &RustfmtOptions::default());
         // Should either be formatted or return original
-        assert!(result.contains("fn main"));
+        assert!(result.code.contains("fn main"));
+    }
+
+    #[test]
+    fn test_rustfmt_options_from_target_project() {
+        let temp = tempfile::tempdir().unwrap();
+        assert_eq!(RustfmtOptions::detect(temp.path()), RustfmtOptions::default());
+        assert!(RustfmtOptions::default().args().is_empty());
+
+        std::fs::write(temp.path().join(".rustfmt.toml"), "max_width = 40\n").unwrap();
+        std::fs::write(
+            temp.path().join("Cargo.toml"),
+            "[package]\nname = \"target\"\nedition = \"2018\"\n",
+        )
+        .unwrap();
+        let options = RustfmtOptions::detect(temp.path());
+        assert_eq!(options.config_path, Some(temp.path().join(".rustfmt.toml")));
+        assert_eq!(options.edition.as_deref(), Some("2018"));
+        assert_eq!(options.args().len(), 4);
+
+        // Only runs where rustfmt is installed
+        let code = "fn main() { let total = first_value + second_value + third_value; }";
+        let formatted = try_format_rust_code(code, FormatBackend::Rustfmt, &options);
+        if run_rustfmt("", &RustfmtOptions::default()).is_ok() {
+            assert!(formatted.code.lines().all(|line| line.len() <= 40));
+        }
+
+        // A workspace-inherited edition isn't a value to pass on
+        std::fs::write(temp.path().join("Cargo.toml"), "[package]\nedition.workspace = true\n").unwrap();
+        assert_eq!(RustfmtOptions::detect(temp.path()).edition, None);
+    }
+
+    #[test]
+    fn test_try_format_rust_expr() {
+        let formatted = try_format_rust_expr("text( \"a\" ).size(12)", FormatBackend::PrettyPlease, &RustfmtOptions::default());
+        assert_eq!(formatted, "text(\"a\").size(12)");
+
+        let invalid = "text(";
+        assert_eq!(try_format_rust_expr(invalid, FormatBackend::PrettyPlease, &RustfmtOptions::default()), invalid);
+        assert_eq!(try_format_rust_expr(invalid, FormatBackend::None, &RustfmtOptions::default()), invalid);
     }
 
     #[test]
@@ -0,0 +1,115 @@
+//! Line diffs between two versions of a text file.
+//!
+//! Used to show what an export would change in an existing output file.
+//! Lines shared at the start and end are matched directly, and the middle
+//! is diffed by longest common subsequence.
+
+/// One line of a diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// Present in both versions.
+    Unchanged(String),
+    /// Only in the new version.
+    Added(String),
+    /// Only in the old version.
+    Removed(String),
+}
+
+/// Diff `old` against `new` line by line.
+///
+/// Removals come before additions where lines were replaced.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    // lcs[i][j] is the common subsequence length of old_mid[i..] and new_mid[j..]
+    let mut lcs = vec![vec![0u32; new_mid.len() + 1]; old_mid.len() + 1];
+    for i in (0..old_mid.len()).rev() {
+        for j in (0..new_mid.len()).rev() {
+            lcs[i][j] = if old_mid[i] == new_mid[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff: Vec<DiffLine> = old[..prefix].iter().map(|l| DiffLine::Unchanged(l.to_string())).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < old_mid.len() || j < new_mid.len() {
+        if i < old_mid.len() && j < new_mid.len() && old_mid[i] == new_mid[j] {
+            diff.push(DiffLine::Unchanged(old_mid[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if j == new_mid.len() || (i < old_mid.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push(DiffLine::Removed(old_mid[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new_mid[j].to_string()));
+            j += 1;
+        }
+    }
+    diff.extend(old[old.len() - suffix..].iter().map(|l| DiffLine::Unchanged(l.to_string())));
+    diff
+}
+
+/// Count the added and removed lines in a diff.
+pub fn diff_summary(diff: &[DiffLine]) -> (usize, usize) {
+    diff.iter().fold((0, 0), |(added, removed), line| match line {
+        DiffLine::Added(_) => (added + 1, removed),
+        DiffLine::Removed(_) => (added, removed + 1),
+        DiffLine::Unchanged(_) => (added, removed),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(diff: &[DiffLine]) -> Vec<String> {
+        diff.iter()
+            .map(|line| match line {
+                DiffLine::Unchanged(l) => format!(" {}", l),
+                DiffLine::Added(l) => format!("+{}", l),
+                DiffLine::Removed(l) => format!("-{}", l),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_identical() {
+        let diff = diff_lines("a\nb\n", "a\nb\n");
+        assert_eq!(render(&diff), vec![" a", " b"]);
+        assert_eq!(diff_summary(&diff), (0, 0));
+    }
+
+    #[test]
+    fn test_diff_replacement_and_insertion() {
+        let diff = diff_lines("a\nb\nc\nd", "a\nx\nc\nd\ne");
+        assert_eq!(render(&diff), vec![" a", "-b", "+x", " c", " d", "+e"]);
+        assert_eq!(diff_summary(&diff), (2, 1));
+    }
+
+    #[test]
+    fn test_diff_keeps_common_lines_in_middle() {
+        let diff = diff_lines("1\nkeep\n2\nkeep2", "keep\n3\nkeep2\n4");
+        assert_eq!(render(&diff), vec!["-1", " keep", "-2", "+3", " keep2", "+4"]);
+    }
+
+    #[test]
+    fn test_diff_against_empty() {
+        assert_eq!(render(&diff_lines("", "a\nb")), vec!["+a", "+b"]);
+        assert_eq!(render(&diff_lines("a\nb", "")), vec!["-a", "-b"]);
+        assert!(diff_lines("", "").is_empty());
+    }
+}
@@ -0,0 +1,21 @@
+//! Exporting a template project's runnable example through the public API.
+
+use iced_builder::model::{Project, ProjectError, Template};
+use tempfile::tempdir;
+
+#[test]
+fn test_export_example_parses() {
+    let temp = tempdir().unwrap();
+    let project = Project::create(temp.path(), Some(Template::Form)).unwrap();
+
+    let path = project.export_example().unwrap();
+    assert_eq!(path, temp.path().join("examples/form.rs"));
+    let code = std::fs::read_to_string(&path).unwrap();
+    let file = syn::parse_file(&code).unwrap();
+    assert!(file.items.iter().any(|item| matches!(item, syn::Item::Fn(f) if f.sig.ident == "main")));
+    assert!(code.contains("FormState::update"));
+
+    // A hand-written example with the same name is left alone
+    std::fs::write(&path, "fn main() {}\n").unwrap();
+    assert!(matches!(project.export_example(), Err(ProjectError::ForeignFile(_))));
+}